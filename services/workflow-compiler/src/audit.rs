@@ -0,0 +1,63 @@
+//! Audit trail of changes to the service's stored state and configuration, listed by
+//! `GET /api/v1/admin/audit`
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tracing::error;
+
+use crate::error::CompilerError;
+use crate::registry::now;
+use crate::store::WorkflowStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    TemplateUploaded,
+    WorkflowStored,
+    WorkflowsRevalidated,
+    ConfigReloaded,
+}
+
+/// One recorded change
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    /// Time of the change in seconds since the Unix epoch
+    pub at: u64,
+    pub action: AuditAction,
+    /// Tenant whose data changed; `None` for service-wide changes
+    pub tenant: Option<String>,
+    /// Template or workflow name
+    pub subject: Option<String>,
+    pub version: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct AuditLog {
+    store: Arc<dyn WorkflowStore>,
+}
+
+impl AuditLog {
+    pub fn new(store: Arc<dyn WorkflowStore>) -> Self {
+        Self { store }
+    }
+
+    /// Record a change; failing to record it is logged rather than failing the change
+    pub fn record(&self, action: AuditAction, tenant: Option<&str>, subject: Option<&str>, version: Option<u32>) {
+        let event = AuditEvent {
+            at: now(),
+            action,
+            tenant: tenant.map(str::to_string),
+            subject: subject.map(str::to_string),
+            version,
+        };
+        if let Err(e) = self.store.append_audit(event) {
+            error!("Failed to record audit event {:?}: {}", action, e);
+        }
+    }
+
+    /// Recorded changes, oldest first
+    pub fn events(&self) -> Result<Vec<AuditEvent>, CompilerError> {
+        self.store.audit_events()
+    }
+}
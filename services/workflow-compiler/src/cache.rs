@@ -0,0 +1,100 @@
+//! Server-side response cache and HTTP caching headers for deterministic endpoints
+//!
+//! Responses are keyed by everything they depend on, typically an endpoint name plus the
+//! definition fingerprint, so entries never go stale and only need dropping on reload.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::error::CompilerError;
+use crate::store::WorkflowStore;
+
+/// Cache-Control for cached responses
+const CACHE_CONTROL: &str = "public, max-age=300";
+
+/// Rendered response body and when it was first produced
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub body: String,
+    pub etag: String,
+    pub last_modified: SystemTime,
+}
+
+/// Response cache over the service's store; a failing store only costs cache hits
+pub struct ResponseCache {
+    store: Arc<dyn WorkflowStore>,
+}
+
+impl ResponseCache {
+    pub fn new(store: Arc<dyn WorkflowStore>) -> Self {
+        Self { store }
+    }
+
+    /// Drop all entries, e.g. after a configuration reload
+    pub fn clear(&self) {
+        if let Err(e) = self.store.clear_responses() {
+            error!("Failed to clear the response cache: {}", e);
+        }
+    }
+
+    /// Serve the response for `key`, rendering and caching it on a miss
+    ///
+    /// Honours `If-None-Match`; failed renders are returned uncached with `no-store`.
+    pub fn respond(
+        &self,
+        request_headers: &HeaderMap,
+        key: &str,
+        render: impl FnOnce() -> Result<Value, CompilerError>,
+    ) -> Response {
+        let cached = self.store.cached_response(key).unwrap_or_else(|e| {
+            error!("Response cache lookup for '{}' failed: {}", key, e);
+            None
+        });
+        let entry = match cached {
+            Some(entry) => entry,
+            None => match render() {
+                Ok(value) => {
+                    let body = value.to_string();
+                    let digest = Sha256::digest(body.as_bytes());
+                    let hex: String = digest.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+                    let entry = Arc::new(CachedResponse {
+                        body,
+                        etag: format!("\"{}\"", hex),
+                        last_modified: SystemTime::now(),
+                    });
+                    if let Err(e) = self.store.cache_response(key, entry.clone()) {
+                        error!("Failed to cache the response for '{}': {}", key, e);
+                    }
+                    entry
+                }
+                Err(e) => {
+                    let body = json!({ "success": false, "error": e.to_string() });
+                    return ([(header::CACHE_CONTROL, "no-store")], axum::Json(body)).into_response();
+                }
+            },
+        };
+
+        let etag = HeaderValue::from_str(&entry.etag).expect("ETag is hex");
+        let last_modified = HeaderValue::from_str(&httpdate::fmt_http_date(entry.last_modified)).expect("HTTP date is ASCII");
+        let headers = [
+            (header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL)),
+            (header::ETAG, etag),
+            (header::LAST_MODIFIED, last_modified),
+        ];
+
+        let not_modified = request_headers.get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|tags| tags.split(',').any(|t| t.trim() == entry.etag || t.trim() == "*"));
+        if not_modified {
+            return (StatusCode::NOT_MODIFIED, headers).into_response();
+        }
+
+        (headers, [(header::CONTENT_TYPE, "application/json")], entry.body.clone()).into_response()
+    }
+}
@@ -0,0 +1,685 @@
+//! Apache Airflow generation: a DAG file with one task per node and the definition's edges as
+//! `>>` dependencies
+//!
+//! Activity and PublishEvent nodes become `PythonOperator` tasks calling a function of the
+//! generated activities module, HttpCall nodes `HttpOperator` tasks on a connection named after
+//! the URL's host, and DatabaseQuery nodes with a `query` `SQLExecuteQueryOperator` tasks on the
+//! node's database connection. Decisions are `BranchPythonOperator` tasks evaluating the edge
+//! conditions over the run's params, so tasks where branches merge wait for any non-skipped
+//! branch. Workflow variables are the DAG's params and the first schedule trigger its schedule.
+//!
+//! Features without an Airflow equivalent do not fail the compilation: their nodes become
+//! `EmptyOperator` placeholders, workflow-level ones are dropped, and each is listed in the
+//! target metadata and the header of the DAG file.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{self, activity_name, go_string_literal as string_literal, is_activity_node};
+use crate::compiler::{duration, limits};
+use crate::compiler::python::KEYWORDS;
+use crate::compiler::steps::{self, TargetMetadata, TargetSources, UnmappedFeature};
+use crate::compiler::types::base_type;
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    to_pascal_case, CodegenTarget, DatabaseQueryConfig, EdgeKind, GeneratedFile, HttpCallConfig, JoinPolicy, NodeType, OverlapPolicy, ParallelGatewayConfig,
+    SubWorkflowConfig, TriggerType, WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+/// Airflow release the generated DAG and requirements target
+pub const AIRFLOW_VERSION: &str = "2.9.3";
+
+const HTTP_PROVIDER: &str = "apache-airflow-providers-http>=4.10.0";
+const SQL_PROVIDER: &str = "apache-airflow-providers-common-sql>=1.14.0";
+
+pub struct Airflow;
+
+/// Snake-case Python identifier for a label or CamelCase activity name
+pub fn identifier(name: &str) -> String {
+    let mut words: Vec<String> = vec![];
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            previous = None;
+            continue;
+        }
+        let boundary = c.is_ascii_uppercase() && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit());
+        match words.last_mut() {
+            Some(word) if previous.is_some() && !boundary => word.push(c.to_ascii_lowercase()),
+            _ => words.push(c.to_ascii_lowercase().to_string()),
+        }
+        previous = Some(c);
+    }
+    let mut name = words.join("_");
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "task_");
+    }
+    if KEYWORDS.contains(&name.as_str()) || matches!(name.as_str(), "dag" | "activities") {
+        name.push('_');
+    }
+    name
+}
+
+/// Task ID, and the variable holding the task: the node's label, with the node ID when the
+/// label is not unique
+pub fn task_id(definition: &WorkflowDefinition, node: &WorkflowNode) -> String {
+    if definition.nodes.iter().filter(|n| identifier(&n.label) == identifier(&node.label)).count() > 1 {
+        identifier(&format!("{} {}", node.label, node.id))
+    } else {
+        identifier(&node.label)
+    }
+}
+
+/// Python literal for a JSON value
+pub fn python_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "None".to_string(),
+        Value::Bool(true) => "True".to_string(),
+        Value::Bool(false) => "False".to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => string_literal(s),
+        Value::Array(items) => format!("[{}]", items.iter().map(python_literal).collect::<Vec<_>>().join(", ")),
+        Value::Object(map) => format!(
+            "{{{}}}",
+            map.iter().map(|(k, v)| format!("{}: {}", string_literal(k), python_literal(v))).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn timedelta(duration: &Duration) -> String {
+    format!("timedelta(seconds={})", duration.as_secs_f64())
+}
+
+/// Python expression for an edge condition over the run's values in the dict named `params`:
+/// `&&`, `||` and `!` become `and`, `or` and `not`
+pub fn python_expression(definition: &WorkflowDefinition, condition: &str, params: &str) -> Result<String, CompilerError> {
+    let invalid = |reason: String| CompilerError::CodeGenError(format!("Condition '{}' cannot be expressed in Python: {}", condition, reason));
+    let mut out = String::new();
+    let mut chars = condition.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err(invalid("unterminated string literal".to_string())),
+                    }
+                }
+                out.push_str(&string_literal(&text));
+            }
+            '&' | '|' => {
+                if chars.next() != Some(c) {
+                    return Err(invalid(format!("unsupported operator '{}'", c)));
+                }
+                out.push_str(if c == '&' { " and " } else { " or " });
+            }
+            '!' if chars.peek() != Some(&'=') => out.push_str(" not "),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_alphanumeric() || **ch == '_' || **ch == '.') {
+                    word.push(ch);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "true" => out.push_str("True"),
+                    "false" => out.push_str("False"),
+                    "null" => out.push_str("None"),
+                    _ => {
+                        let name = word.strip_prefix("input.").unwrap_or(&word);
+                        let mut path = name.split('.');
+                        let head = path.next().unwrap_or_default();
+                        let variable = definition.variables.iter()
+                            .find(|v| v.name == head || to_pascal_case(&v.name) == head)
+                            .ok_or_else(|| invalid(format!("'{}' is not a workflow variable", head)))?;
+                        out.push_str(&format!("{}[{}]", params, string_literal(&variable.name)));
+                        for field in path {
+                            out.push_str(&format!("[{}]", string_literal(field)));
+                        }
+                    }
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Flow edges that close a loop, found depth-first from the start node in definition order
+pub fn back_edges(definition: &WorkflowDefinition) -> HashSet<&str> {
+    fn visit<'a>(definition: &'a WorkflowDefinition, node: &'a str, on_path: &mut Vec<&'a str>, done: &mut HashSet<&'a str>, back: &mut HashSet<&'a str>) {
+        on_path.push(node);
+        for edge in graph::outgoing_edges(definition, node).filter(|e| e.kind == EdgeKind::Flow) {
+            if on_path.contains(&edge.target.as_str()) {
+                back.insert(&edge.id);
+            } else if !done.contains(edge.target.as_str()) {
+                visit(definition, &edge.target, on_path, done, back);
+            }
+        }
+        on_path.pop();
+        done.insert(node);
+    }
+
+    let mut done = HashSet::new();
+    let mut back = HashSet::new();
+    for start in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::Start)) {
+        visit(definition, &start.id, &mut vec![], &mut done, &mut back);
+    }
+    back
+}
+
+/// Reachable nodes of a definition with every node after all of its predecessors over `edges`,
+/// which must not loop
+pub fn dependency_order<'a>(definition: &'a WorkflowDefinition, edges: &[&WorkflowEdge]) -> Vec<&'a WorkflowNode> {
+    let reachable = graph::reachable_nodes(definition);
+    let mut incoming: HashMap<&str, usize> = HashMap::new();
+    for edge in edges {
+        *incoming.entry(edge.target.as_str()).or_default() += 1;
+    }
+    let mut ready: VecDeque<&WorkflowNode> = definition.nodes.iter()
+        .filter(|n| reachable.contains(n.id.as_str()) && !incoming.contains_key(n.id.as_str()))
+        .collect();
+    let mut order = vec![];
+    while let Some(node) = ready.pop_front() {
+        order.push(node);
+        for edge in edges.iter().filter(|e| e.source == node.id) {
+            let count = incoming.get_mut(edge.target.as_str()).expect("targets of kept edges are counted");
+            *count -= 1;
+            if *count == 0 {
+                ready.extend(graph::find_node(definition, &edge.target));
+            }
+        }
+    }
+    order
+}
+
+/// Placeholder fallback for a node type without an operator, as the feature and what the
+/// placeholder leaves out
+pub fn placeholder(node_type: &NodeType) -> Option<(&'static str, &'static str)> {
+    Some(match node_type {
+        NodeType::WaitTimer => ("a durable timer", "the run does not wait"),
+        NodeType::WaitSignal => ("a signal wait", "the run does not wait for the signal"),
+        NodeType::WaitSignals => ("a multi-signal wait", "the run does not wait for the signals"),
+        NodeType::Transform => ("a data transform", "the transform is not applied"),
+        NodeType::Notification => ("a notification", "no notification is sent"),
+        NodeType::DynamicActivity => ("a dynamic activity", "no activity runs"),
+        NodeType::DecisionTable => ("a decision table", "every outgoing branch runs"),
+        NodeType::CancellationScope => ("a cancellation scope", "nothing is cancelled"),
+        NodeType::NexusOperation => ("a Nexus operation", "no operation runs"),
+        NodeType::FeatureFlag => ("a feature flag", "every outgoing branch runs"),
+        NodeType::WeightedSplit => ("a weighted split", "every outgoing branch runs"),
+        _ => return None,
+    })
+}
+
+/// JSON Schema type for a DSL variable type
+pub fn param_type(var_type: &str) -> Option<&'static str> {
+    Some(match base_type(var_type) {
+        "string" => "string",
+        "int" | "integer" => "integer",
+        "float" | "number" => "number",
+        "bool" | "boolean" => "boolean",
+        "object" => "object",
+        "array" => "array",
+        _ => return None,
+    })
+}
+
+/// DAG file being assembled: imports, branch callables and tasks in dependency order
+struct Dag<'a> {
+    definition: &'a WorkflowDefinition,
+    package_name: &'a str,
+    /// Flow edges kept as dependencies, without loops and edges out of unreachable nodes
+    edges: Vec<&'a WorkflowEdge>,
+    /// Names imported from each Airflow module
+    imports: BTreeMap<&'static str, BTreeSet<&'static str>>,
+    uses_json: bool,
+    requirements: BTreeSet<&'static str>,
+    callables: Vec<String>,
+    activities: BTreeSet<String>,
+    unmapped: Vec<UnmappedFeature>,
+}
+
+impl<'a> Dag<'a> {
+    fn new(definition: &'a WorkflowDefinition, package_name: &'a str) -> Self {
+        let reachable = graph::reachable_nodes(definition);
+        let loops = back_edges(definition);
+        let mut dag = Dag {
+            definition,
+            package_name,
+            edges: vec![],
+            imports: BTreeMap::from([("airflow", BTreeSet::from(["DAG"])), ("airflow.models.param", BTreeSet::from(["Param"]))]),
+            uses_json: false,
+            requirements: BTreeSet::new(),
+            callables: vec![],
+            activities: BTreeSet::new(),
+            unmapped: vec![],
+        };
+        for edge in definition.edges.iter().filter(|e| reachable.contains(e.source.as_str())) {
+            match edge.kind {
+                EdgeKind::Cancel => dag.unmap(Some(&edge.source), format!("a cancel edge to '{}'", edge.target), "dropped"),
+                EdgeKind::Flow if loops.contains(edge.id.as_str()) => {
+                    dag.unmap(Some(&edge.source), format!("a loop back to '{}'", edge.target), "dropped; Airflow DAGs cannot loop")
+                }
+                EdgeKind::Flow => dag.edges.push(edge),
+            }
+        }
+        dag
+    }
+
+    fn import(&mut self, module: &'static str, name: &'static str) {
+        self.imports.entry(module).or_default().insert(name);
+    }
+
+    fn unmap(&mut self, node_id: Option<&str>, feature: impl Into<String>, fallback: &str) {
+        self.unmapped.push(UnmappedFeature { node_id: node_id.map(str::to_string), feature: feature.into(), fallback: fallback.to_string() });
+    }
+
+    /// Reachable nodes with every node after all of its predecessors
+    fn order(&self) -> Vec<&'a WorkflowNode> {
+        dependency_order(self.definition, &self.edges)
+    }
+
+    /// Nodes a decision may skip, which merge points after them must tolerate
+    fn behind_decision(&self, order: &[&'a WorkflowNode]) -> HashSet<&'a str> {
+        let mut behind = HashSet::new();
+        for node in order {
+            let skippable = matches!(node.node_type, NodeType::Decision) || behind.contains(node.id.as_str());
+            if skippable {
+                behind.extend(self.edges.iter().filter(|e| e.source == node.id).map(|e| e.target.as_str()));
+            }
+        }
+        behind
+    }
+
+    /// `retries` arguments for a node retry policy; Airflow counts retries, not attempts, and
+    /// only doubles the delay between them
+    fn retry_arguments(&mut self, node: &WorkflowNode) -> Result<Vec<String>, CompilerError> {
+        let Some(retry) = steps::retry(node)? else { return Ok(vec![]) };
+        let mut arguments = vec![
+            format!("retries={}", retry.max_attempts.saturating_sub(1)),
+            format!("retry_delay={}", timedelta(&retry.initial_interval)),
+        ];
+        if retry.backoff_coefficient > 1.0 {
+            arguments.push("retry_exponential_backoff=True".to_string());
+            arguments.push(format!("max_retry_delay={}", timedelta(&retry.max_interval)));
+            if retry.backoff_coefficient != 2.0 {
+                self.unmap(Some(&node.id), format!("a retry backoff coefficient of {}", retry.backoff_coefficient), "the delay doubles between retries");
+            }
+        }
+        Ok(arguments)
+    }
+
+    /// Branch callable for a decision: the task of the first edge whose condition holds, else
+    /// the unconditional edge's, else none
+    fn branch_callable(&mut self, node: &WorkflowNode) -> Result<String, CompilerError> {
+        let mut body = String::new();
+        let mut default = None;
+        for edge in graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow) {
+            let kept = self.edges.iter().any(|e| e.id == edge.id);
+            let choice = graph::find_node(self.definition, &edge.target)
+                .filter(|_| kept)
+                .map_or_else(|| "[]".to_string(), |target| string_literal(&task_id(self.definition, target)));
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => {
+                    body.push_str(&format!("    if {}:\n        return {}\n", python_expression(self.definition, condition, "params")?, choice));
+                }
+                None => {
+                    default.get_or_insert(choice);
+                }
+            }
+        }
+        let name = format!("choose_{}", task_id(self.definition, node));
+        self.callables.push(format!(
+            "\n\ndef {name}(**context: Any) -> Union[str, list[str]]:\n    \"\"\"Branch taken by {label}\"\"\"\n    params = context[\"params\"]\n{body}    return {default}\n",
+            label = node.label.replace('"', "'"),
+            default = default.unwrap_or_else(|| "[]".to_string()),
+        ));
+        Ok(name)
+    }
+
+    /// Activity function the task calls, registered for the activities module
+    fn activity_callable(&mut self, node: &WorkflowNode) -> String {
+        let function = identifier(&activity_name(node));
+        self.activities.insert(activity_name(node));
+        format!("activities.{}", function)
+    }
+
+    /// Operator and its arguments beyond `task_id` for a node
+    fn operator(&mut self, node: &WorkflowNode) -> Result<(&'static str, Vec<String>), CompilerError> {
+        let config_str = |key: &str| node.config.get(key).and_then(Value::as_str).filter(|v| !v.is_empty());
+        Ok(match node.node_type {
+            NodeType::Decision => {
+                self.import("airflow.operators.python", "BranchPythonOperator");
+                let callable = self.branch_callable(node)?;
+                ("BranchPythonOperator", vec![format!("python_callable={}", callable)])
+            }
+            NodeType::HttpCall => {
+                let config: HttpCallConfig = node.typed_config()?;
+                let url = Some(config.url.as_str()).filter(|u| !u.is_empty())
+                    .ok_or_else(|| CompilerError::CodeGenError(format!("HttpCall node '{}' has no url", node.id)))?;
+                let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+                let (host, endpoint) = rest.split_once('/').map_or((rest, "/".to_string()), |(h, p)| (h, format!("/{}", p)));
+                self.import("airflow.providers.http.operators.http", "HttpOperator");
+                self.requirements.insert(HTTP_PROVIDER);
+                let mut arguments = vec![
+                    format!("http_conn_id={}", string_literal(&format!("http_{}", identifier(host)))),
+                    format!("endpoint={}", string_literal(&endpoint)),
+                    format!("method={}", string_literal(&config.method.to_ascii_uppercase())),
+                ];
+                if !config.headers.is_empty() {
+                    arguments.push(format!("headers={}", python_literal(&json!(config.headers))));
+                }
+                match &config.body {
+                    Value::String(body) => arguments.push(format!("data={}", string_literal(body))),
+                    Value::Null => {}
+                    body => {
+                        self.uses_json = true;
+                        arguments.push(format!("data=json.dumps({})", python_literal(body)));
+                    }
+                }
+                ("HttpOperator", arguments)
+            }
+            NodeType::DatabaseQuery if config_str("query").is_some() => {
+                self.import("airflow.providers.common.sql.operators.sql", "SQLExecuteQueryOperator");
+                self.requirements.insert(SQL_PROVIDER);
+                let config: DatabaseQueryConfig = node.typed_config()?;
+                let connection = config.connection.into_iter().chain(config.database).find(|c| !c.is_empty()).map_or_else(|| format!("{}_db", self.package_name), |c| identifier(&c));
+                (
+                    "SQLExecuteQueryOperator",
+                    vec![format!("conn_id={}", string_literal(&connection)), format!("sql={}", string_literal(&config.query.unwrap_or_default()))],
+                )
+            }
+            _ if is_activity_node(node) => {
+                self.import("airflow.operators.python", "PythonOperator");
+                ("PythonOperator", vec![format!("python_callable={}", self.activity_callable(node))])
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                if config.namespace.is_some() || config.task_queue.is_some() {
+                    self.unmap(Some(&node.id), "a child workflow on another namespace or task queue", "the child DAG runs on this Airflow deployment");
+                }
+                self.unmap(Some(&node.id), "child workflow input", "the child DAG runs with its own param defaults");
+                self.import("airflow.operators.trigger_dagrun", "TriggerDagRunOperator");
+                (
+                    "TriggerDagRunOperator",
+                    vec![
+                        format!("trigger_dag_id={}", string_literal(&identifier(&config.workflow))),
+                        format!("wait_for_completion={}", if config.wait_for_completion { "True" } else { "False" }),
+                    ],
+                )
+            }
+            NodeType::ParallelGateway => {
+                let config: ParallelGatewayConfig = node.typed_config()?;
+                match config.join {
+                    JoinPolicy::All => {}
+                    JoinPolicy::Any => self.unmap(Some(&node.id), "an any join", "the join waits for every branch"),
+                    JoinPolicy::NOfM(n) => self.unmap(Some(&node.id), format!("an n_of_m join of {}", n), "the join waits for every branch"),
+                }
+                self.import("airflow.operators.empty", "EmptyOperator");
+                ("EmptyOperator", vec![])
+            }
+            _ => {
+                if let Some((feature, consequence)) = placeholder(&node.node_type) {
+                    self.unmap(Some(&node.id), feature, &format!("EmptyOperator placeholder; {}", consequence));
+                }
+                self.import("airflow.operators.empty", "EmptyOperator");
+                ("EmptyOperator", vec![])
+            }
+        })
+    }
+
+    /// Task assignment for a node
+    fn task(&mut self, node: &WorkflowNode, merges_after_branch: bool) -> Result<String, CompilerError> {
+        if node.session.is_some() {
+            self.unmap(Some(&node.id), "a worker session", "ignored; the task runs on any worker");
+        }
+        let id = task_id(self.definition, node);
+        let (operator, mut arguments) = self.operator(node)?;
+        arguments.insert(0, format!("task_id={}", string_literal(&id)));
+        arguments.extend(self.retry_arguments(node)?);
+        if merges_after_branch {
+            arguments.push("trigger_rule=\"none_failed_min_one_success\"".to_string());
+        }
+        let arguments: String = arguments.iter().map(|a| format!("        {},\n", a)).collect();
+        Ok(format!("    # {}\n    {} = {}(\n{}    )\n", node.label, id, operator, arguments))
+    }
+
+    /// `DAG(...)` arguments for the schedule, timeouts and params
+    fn dag_arguments(&mut self) -> Result<Vec<String>, CompilerError> {
+        let definition = self.definition;
+        let mut arguments = vec![format!("dag_id={}", string_literal(self.package_name))];
+        if let Some(description) = definition.description.as_deref().filter(|d| !d.is_empty()) {
+            arguments.push(format!("description={}", string_literal(description)));
+        }
+
+        let schedules = codegen::schedule_triggers(definition)?;
+        match schedules.split_first() {
+            Some((schedule, rest)) => {
+                arguments.push(format!("schedule={}", string_literal(&schedule.cron)));
+                arguments.push("max_active_runs=1".to_string());
+                if schedule.overlap != OverlapPolicy::BufferAll {
+                    let policy = serde_json::to_value(schedule.overlap).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+                    self.unmap(None, format!("the {} schedule overlap policy", policy), "overlapping runs queue behind max_active_runs=1");
+                }
+                if schedule.catchup_window.is_some() {
+                    self.unmap(None, "a schedule catch-up window", "catchup=False; missed runs are not caught up");
+                }
+                if schedule.paused {
+                    arguments.push("is_paused_upon_creation=True".to_string());
+                }
+                for other in rest {
+                    self.unmap(None, format!("an additional schedule '{}'", other.cron), "not scheduled; only the first schedule trigger is the DAG's schedule");
+                }
+            }
+            None => arguments.push("schedule=None".to_string()),
+        }
+        arguments.push("start_date=pendulum.datetime(2024, 1, 1, tz=\"UTC\")".to_string());
+        arguments.push("catchup=False".to_string());
+        for trigger in &definition.triggers {
+            match trigger.trigger_type {
+                TriggerType::Webhook => self.unmap(None, "a webhook trigger", "not generated; start runs through the Airflow REST API"),
+                TriggerType::Event => self.unmap(None, "an event trigger", "not generated; start runs through the Airflow REST API"),
+                TriggerType::Manual | TriggerType::Schedule => {}
+            }
+        }
+
+        if let Some(timeouts) = &definition.timeouts {
+            let timeout = match (&timeouts.execution, &timeouts.run) {
+                (Some(raw), _) => Some(duration::parse_field(raw, "timeouts.execution", None)?),
+                (None, Some(raw)) => Some(duration::parse_field(raw, "timeouts.run", None)?),
+                (None, None) => None,
+            };
+            if let Some(timeout) = timeout {
+                arguments.push(format!("dagrun_timeout={}", timedelta(&timeout)));
+            }
+            if timeouts.task.is_some() {
+                self.unmap(None, "a workflow task timeout", "ignored; Airflow runs have no workflow tasks");
+            }
+        }
+
+        if !definition.variables.is_empty() {
+            let params: String = definition.variables.iter()
+                .map(|variable| {
+                    let mut param = vec![python_literal(variable.default_value.as_ref().unwrap_or(&Value::Null))];
+                    let var_type = param_type(&variable.var_type);
+                    match (var_type, &variable.default_value) {
+                        (Some(t), Some(default)) if !default.is_null() => param.push(format!("type={}", string_literal(t))),
+                        (Some(t), _) => param.push(format!("type=[{}, \"null\"]", string_literal(t))),
+                        (None, _) => {}
+                    }
+                    if let Some(schema) = &variable.schema {
+                        let min_length = schema.min_length.or(if schema.required && var_type == Some("string") { Some(1) } else { None });
+                        param.extend(schema.minimum.map(|m| format!("minimum={}", m)));
+                        param.extend(schema.maximum.map(|m| format!("maximum={}", m)));
+                        let (min_key, max_key) = if var_type == Some("array") { ("minItems", "maxItems") } else { ("minLength", "maxLength") };
+                        param.extend(min_length.map(|m| format!("{}={}", min_key, m)));
+                        param.extend(schema.max_length.map(|m| format!("{}={}", max_key, m)));
+                        param.extend(schema.pattern.as_deref().map(|p| format!("pattern={}", string_literal(p))));
+                    }
+                    format!("        {}: Param({}),\n", string_literal(&variable.name), param.join(", "))
+                })
+                .collect();
+            arguments.push(format!("params={{\n{}    }}", params));
+        }
+        arguments.push("render_template_as_native_obj=True".to_string());
+        arguments.push("tags=[\"omniroute\"]".to_string());
+        Ok(arguments)
+    }
+}
+
+/// DAG file, activities module and requirements for a definition
+fn generate_dag(dag: &mut Dag, fingerprint: &str) -> Result<(String, String, Vec<GeneratedFile>, usize), CompilerError> {
+    let definition = dag.definition;
+    let order = dag.order();
+    let behind = dag.behind_decision(&order);
+    let mut tasks = String::new();
+    for node in &order {
+        limits::checkpoint("code generation")?;
+        let incoming = dag.edges.iter().filter(|e| e.target == node.id).count();
+        tasks.push_str(&dag.task(node, incoming > 1 && behind.contains(node.id.as_str()))?);
+        tasks.push('\n');
+    }
+
+    let mut dependencies = vec![];
+    for edge in &dag.edges {
+        let (Some(source), Some(target)) = (graph::find_node(definition, &edge.source), graph::find_node(definition, &edge.target)) else { continue };
+        let dependency = format!("    {} >> {}\n", task_id(definition, source), task_id(definition, target));
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+
+    let arguments: String = dag.dag_arguments()?.iter().map(|a| format!("    {},\n", a)).collect();
+    let mut imports: String = dag.imports.iter()
+        .map(|(module, names)| format!("from {} import {}\n", module, names.iter().copied().collect::<Vec<_>>().join(", ")))
+        .collect();
+    if !dag.activities.is_empty() {
+        imports.push_str(&format!("\nimport {}_activities as activities\n", dag.package_name));
+    }
+    let json = if dag.uses_json { "import json\n" } else { "" };
+    let typing = if dag.callables.is_empty() { "" } else { "from typing import Any, Union\n" };
+    let unmapped: String = if dag.unmapped.is_empty() {
+        String::new()
+    } else {
+        let lines: String = dag.unmapped.iter()
+            .map(|u| match &u.node_id {
+                Some(node) => format!("#   node '{}': {} ({})\n", node, u.feature, u.fallback),
+                None => format!("#   {} ({})\n", u.feature, u.fallback),
+            })
+            .collect();
+        format!("#\n# Not mapped onto Airflow:\n{}", lines)
+    };
+
+    let workflow_code = format!(r#"# Generated by OmniRoute Workflow Compiler
+# DO NOT EDIT - This file is auto-generated
+{unmapped}
+{json}from datetime import timedelta
+{typing}
+import pendulum
+{imports}
+# Version of the workflow definition this DAG was compiled from
+DEFINITION_VERSION = {version}
+# Content hash of that definition
+DEFINITION_FINGERPRINT = {fingerprint}
+{callables}
+
+with DAG(
+{arguments}) as dag:
+{tasks}{dependencies}"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        callables = dag.callables.concat(),
+        dependencies = dependencies.concat(),
+    );
+
+    let functions: String = dag.activities.iter()
+        .map(|name| format!(r#"
+
+def {function}(**context: Any) -> None:
+    """{name} implements the {name} activity"""
+    params = context["params"]
+    # TODO: implement {name} using params
+"#, function = identifier(name)))
+        .collect();
+    let activity_code = format!("# Generated by OmniRoute Workflow Compiler\n\nfrom typing import Any\n{}", functions);
+
+    let mut requirements = format!("apache-airflow=={}\n", AIRFLOW_VERSION);
+    for requirement in &dag.requirements {
+        requirements.push_str(requirement);
+        requirements.push('\n');
+    }
+    requirements.push_str("pytest>=8.0\n");
+    let files = vec![GeneratedFile { path: "requirements.txt".to_string(), content: requirements }];
+    Ok((workflow_code, activity_code, files, order.len()))
+}
+
+fn generate_test(package_name: &str, task_count: usize) -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+
+import os
+
+from airflow.models import DagBag
+
+DAG_FOLDER = os.path.join(os.path.dirname(__file__), "..", "dags")
+
+
+def test_{package_name}_dag_loads() -> None:
+    dag_bag = DagBag(dag_folder=DAG_FOLDER, include_examples=False)
+    assert dag_bag.import_errors == {{}}
+
+    dag = dag_bag.get_dag({dag_id})
+    assert dag is not None
+    assert len(dag.tasks) == {task_count}
+"#, package_name = identifier(package_name), dag_id = string_literal(package_name))
+}
+
+impl Backend for Airflow {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Airflow
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        AIRFLOW_VERSION
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        format!(r#"# Generated by OmniRoute Workflow Compiler
+services:
+  airflow:
+    image: apache/airflow:{AIRFLOW_VERSION}
+    command: standalone
+    environment:
+      _PIP_ADDITIONAL_REQUIREMENTS: "{HTTP_PROVIDER} {SQL_PROVIDER}"
+    ports:
+      - "8080:8080"
+    volumes:
+      - ./dags:/opt/airflow/dags
+"#)
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let mut dag = Dag::new(definition, package_name);
+        let (workflow_code, activity_code, files, task_count) = generate_dag(&mut dag, fingerprint)?;
+
+        Ok(TargetSources {
+            workflow_code,
+            activity_code,
+            worker_code: String::new(),
+            test_code: generate_test(package_name, task_count),
+            files,
+            signals: vec![],
+            metadata: Some(TargetMetadata { unmapped: dag.unmapped }),
+        })
+    }
+}
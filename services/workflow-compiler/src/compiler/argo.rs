@@ -0,0 +1,503 @@
+//! Argo Workflows generation: a `WorkflowTemplate` whose DAG mirrors the definition's graph,
+//! and a `Workflow` submitting it
+//!
+//! The template is the workflow code and the submitting `Workflow` the worker code. Activity
+//! nodes become container templates running the node's `image` config, by default
+//! `<package>-activities:<version>`, with the activity name as their argument and the workflow
+//! parameters as JSON in `WORKFLOW_INPUT`; HttpCall nodes use Argo's `http` template.
+//! `WaitSignal` and `WaitTimer` become suspend templates, resumed by `argo resume` or once the
+//! timer's duration passes.
+//!
+//! Decisions, forks and joins produce no task. Each task depends on the nearest tasks before
+//! it, and tasks behind a decision carry a `when` guard built from the edge conditions, so a
+//! skipped branch skips everything up to where the branches merge. Workflow variables are the
+//! template's parameters. Argo runs on Kubernetes, so there is no local compose file, and a
+//! DAG cannot loop.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use serde_json::{json, Value};
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{activity_name, is_activity_node};
+use crate::compiler::{duration, limits};
+use crate::compiler::steps::{self, TargetSources};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::export::yaml;
+use crate::{
+    to_pascal_case, CodegenTarget, EdgeKind, JoinPolicy, NodeType, ParallelGatewayConfig, SubWorkflowConfig, WaitSignalConfig, WaitTimerConfig,
+    WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+/// Argo Workflows release whose CRDs the manifests are written against
+pub const ARGO_VERSION: &str = "3.5.8";
+
+const API_VERSION: &str = "argoproj.io/v1alpha1";
+
+pub struct Argo;
+
+/// Lowercase DNS-label name Argo accepts for templates, tasks and resources
+fn resource_name(name: &str) -> String {
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    match name.as_str() {
+        "" => "task".to_string(),
+        _ if name.starts_with(|c: char| c.is_ascii_digit()) => format!("task-{}", name),
+        _ => name,
+    }
+}
+
+/// Task name for a node: its label, with the node ID when the label is not unique or is
+/// `main`, the entrypoint template
+fn task_name(definition: &WorkflowDefinition, node: &WorkflowNode) -> String {
+    let duplicate = definition.nodes.iter().filter(|n| resource_name(&n.label) == resource_name(&node.label)).count() > 1;
+    if duplicate || resource_name(&node.label) == "main" {
+        resource_name(&format!("{} {}", node.label, node.id))
+    } else {
+        resource_name(&node.label)
+    }
+}
+
+/// Whether a node runs as a DAG task rather than only shaping the dependencies between tasks
+fn is_task(node: &WorkflowNode) -> bool {
+    !matches!(
+        node.node_type,
+        NodeType::Start | NodeType::End | NodeType::Decision | NodeType::ParallelGateway | NodeType::Transform | NodeType::Notification
+    )
+}
+
+/// Nodes reachable from the start node with every node after all of its predecessors
+fn topological_order(definition: &WorkflowDefinition) -> Result<Vec<&WorkflowNode>, CompilerError> {
+    let reachable = graph::reachable_nodes(definition);
+    let edges: Vec<&WorkflowEdge> = definition.edges.iter()
+        .filter(|e| e.kind == EdgeKind::Flow && reachable.contains(e.source.as_str()) && reachable.contains(e.target.as_str()))
+        .collect();
+    let mut incoming: HashMap<&str, usize> = HashMap::new();
+    for edge in &edges {
+        *incoming.entry(edge.target.as_str()).or_default() += 1;
+    }
+    let mut ready: VecDeque<&WorkflowNode> = definition.nodes.iter()
+        .filter(|n| reachable.contains(n.id.as_str()) && !incoming.contains_key(n.id.as_str()))
+        .collect();
+    let mut order = Vec::new();
+    while let Some(node) = ready.pop_front() {
+        order.push(node);
+        for edge in edges.iter().filter(|e| e.source == node.id) {
+            let count = incoming.get_mut(edge.target.as_str()).expect("targets of counted edges are counted");
+            *count -= 1;
+            if *count == 0 {
+                ready.extend(graph::find_node(definition, &edge.target));
+            }
+        }
+    }
+    if let Some(node) = definition.nodes.iter().find(|n| reachable.contains(n.id.as_str()) && !order.iter().any(|o| o.id == n.id)) {
+        return Err(CompilerError::CodeGenError(format!(
+            "Workflow '{}' loops through node '{}'; Argo DAGs cannot loop",
+            definition.name, node.id
+        )));
+    }
+    Ok(order)
+}
+
+/// `when` expression for an edge condition: workflow variables become workflow parameters,
+/// quoted when they hold strings, and string literals are single-quoted
+fn when_expression(definition: &WorkflowDefinition, condition: &str) -> Result<String, CompilerError> {
+    let invalid = |reason: String| CompilerError::CodeGenError(format!("Condition '{}' cannot be expressed as an Argo when: {}", condition, reason));
+    let mut out = String::new();
+    let mut chars = condition.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err(invalid("unterminated string literal".to_string())),
+                    }
+                }
+                if text.contains('\'') {
+                    return Err(invalid("string literals cannot contain single quotes".to_string()));
+                }
+                out.push_str(&format!("'{}'", text));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_alphanumeric() || **ch == '_' || **ch == '.') {
+                    word.push(ch);
+                    chars.next();
+                }
+                if matches!(word.as_str(), "true" | "false") {
+                    out.push_str(&word);
+                    continue;
+                }
+                let name = word.strip_prefix("input.").unwrap_or(&word);
+                let variable = definition.variables.iter()
+                    .find(|v| v.name == name || to_pascal_case(&v.name) == name)
+                    .ok_or_else(|| invalid(format!("'{}' is not a workflow variable", name)))?;
+                let parameter = format!("{{{{workflow.parameters.{}}}}}", variable.name);
+                let is_string = variable.var_type == "string" || variable.default_value.as_ref().is_some_and(Value::is_string);
+                out.push_str(&if is_string { format!("'{}'", parameter) } else { parameter });
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Decision edge a branch takes, as the decision node ID and edge ID
+type Choice<'a> = (&'a str, &'a str);
+
+/// When a node runs, in disjunctive normal form: if every choice along any one of the paths
+/// was taken. A path without choices means the node always runs.
+type Guard<'a> = BTreeSet<BTreeSet<Choice<'a>>>;
+
+fn decision_edges<'a>(definition: &'a WorkflowDefinition, decision: &'a str) -> Vec<&'a WorkflowEdge> {
+    graph::outgoing_edges(definition, decision).filter(|e| e.kind == EdgeKind::Flow).collect()
+}
+
+fn edge_condition(edge: &WorkflowEdge) -> Option<&str> {
+    edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty())
+}
+
+/// Collapse paths that cover every edge of a decision with an unconditional edge, since one of
+/// those edges is always taken, and drop paths implied by shorter ones
+fn simplify<'a>(definition: &'a WorkflowDefinition, mut guard: Guard<'a>) -> Result<Guard<'a>, CompilerError> {
+    loop {
+        limits::checkpoint("code generation")?;
+        let mut collapsed = None;
+        'search: for path in &guard {
+            for &(decision, _) in path {
+                let edges = decision_edges(definition, decision);
+                if !edges.iter().any(|e| edge_condition(e).is_none()) {
+                    continue;
+                }
+                let base: BTreeSet<Choice> = path.iter().filter(|(d, _)| *d != decision).copied().collect();
+                let covered = edges.iter().all(|e| {
+                    let mut alternative = base.clone();
+                    alternative.insert((decision, e.id.as_str()));
+                    guard.contains(&alternative)
+                });
+                if covered {
+                    collapsed = Some((decision, base));
+                    break 'search;
+                }
+            }
+        }
+        let Some((decision, base)) = collapsed else { break };
+        guard.retain(|path| !(path.len() == base.len() + 1 && path.is_superset(&base) && path.iter().any(|(d, _)| *d == decision)));
+        guard.insert(base);
+    }
+    let paths: Vec<BTreeSet<Choice>> = guard.iter().cloned().collect();
+    guard.retain(|path| !paths.iter().any(|other| other.len() < path.len() && other.is_subset(path)));
+    Ok(guard)
+}
+
+/// Expression for a decision taking `edge`: the first edge whose condition holds is taken, and
+/// the unconditional edge when none does
+fn choice_expression(definition: &WorkflowDefinition, (decision, edge_id): Choice) -> Result<String, CompilerError> {
+    let mut terms = Vec::new();
+    for edge in decision_edges(definition, decision) {
+        let condition = edge_condition(edge).map(|c| when_expression(definition, c)).transpose()?;
+        if edge.id == edge_id {
+            terms.extend(condition);
+            break;
+        }
+        terms.extend(condition.map(|c| format!("!({})", c)));
+    }
+    Ok(match terms.as_slice() {
+        [only] => only.clone(),
+        _ => terms.iter().map(|t| format!("({})", t)).collect::<Vec<_>>().join(" && "),
+    })
+}
+
+/// `when` expression for a guard, or `None` when the node always runs
+fn guard_expression(definition: &WorkflowDefinition, guard: &Guard) -> Result<Option<String>, CompilerError> {
+    if guard.iter().any(BTreeSet::is_empty) {
+        return Ok(None);
+    }
+    let paths = guard.iter()
+        .map(|path| {
+            let choices = path.iter().map(|&choice| choice_expression(definition, choice)).collect::<Result<Vec<_>, _>>()?;
+            Ok(match choices.as_slice() {
+                [only] => only.clone(),
+                _ => choices.iter().map(|c| format!("({})", c)).collect::<Vec<_>>().join(" && "),
+            })
+        })
+        .collect::<Result<Vec<String>, CompilerError>>()?;
+    Ok(Some(match paths.as_slice() {
+        [only] => only.clone(),
+        _ => paths.iter().map(|p| format!("({})", p)).collect::<Vec<_>>().join(" || "),
+    }))
+}
+
+/// Node running as a DAG task
+struct DagTask<'a> {
+    node: &'a WorkflowNode,
+    dependencies: BTreeSet<String>,
+    when: Option<String>,
+}
+
+/// DAG tasks in dependency order
+fn dag_tasks(definition: &WorkflowDefinition) -> Result<Vec<DagTask<'_>>, CompilerError> {
+    let order = topological_order(definition)?;
+    // Tasks a node's successors depend on, and when the node runs
+    let mut frontier: HashMap<&str, BTreeSet<String>> = HashMap::new();
+    let mut guards: HashMap<&str, Guard> = HashMap::new();
+    let mut tasks = Vec::new();
+    for node in order {
+        let mut dependencies = BTreeSet::new();
+        let mut guard = Guard::new();
+        for edge in definition.edges.iter().filter(|e| e.target == node.id && e.kind == EdgeKind::Flow) {
+            let Some(source) = guards.get(edge.source.as_str()) else { continue };
+            dependencies.extend(frontier[edge.source.as_str()].iter().cloned());
+            let is_decision = graph::find_node(definition, &edge.source).is_some_and(|s| matches!(s.node_type, NodeType::Decision));
+            for path in source {
+                let mut path = path.clone();
+                if is_decision {
+                    path.insert((edge.source.as_str(), edge.id.as_str()));
+                }
+                guard.insert(path);
+            }
+        }
+        if guard.is_empty() {
+            // The start node
+            guard.insert(BTreeSet::new());
+        }
+        let guard = simplify(definition, guard)?;
+        if is_task(node) {
+            frontier.insert(&node.id, BTreeSet::from([task_name(definition, node)]));
+            tasks.push(DagTask { node, dependencies, when: guard_expression(definition, &guard)? });
+        } else {
+            frontier.insert(&node.id, dependencies);
+        }
+        guards.insert(&node.id, guard);
+    }
+    Ok(tasks)
+}
+
+/// Retry strategy for a node retry policy; Argo counts retries, not attempts
+fn retry_strategy(node: &WorkflowNode) -> Result<Option<Value>, CompilerError> {
+    Ok(steps::retry(node)?.map(|retry| {
+        json!({
+            "limit": retry.max_attempts.saturating_sub(1),
+            "retryPolicy": "Always",
+            "backoff": {
+                "duration": format!("{}s", retry.initial_interval.as_secs().max(1)),
+                "factor": retry.backoff_coefficient.to_string(),
+                "maxDuration": format!("{}s", retry.max_interval.as_secs().max(1)),
+            },
+        })
+    }))
+}
+
+/// Template running a task node, or `None` for nodes calling another workflow's template
+fn template(node: &WorkflowNode, name: &str, default_image: &str, target: &str) -> Result<Option<Value>, CompilerError> {
+    let mut template = match node.node_type {
+        NodeType::HttpCall => {
+            let config = |key: &str| node.config.get(key).filter(|v| !v.is_null());
+            let url = config("url").and_then(Value::as_str).ok_or_else(|| CompilerError::CodeGenError(format!("HttpCall node '{}' has no url", node.id)))?;
+            let mut http = json!({
+                "url": url,
+                "method": config("method").and_then(Value::as_str).unwrap_or("GET").to_ascii_uppercase(),
+            });
+            if let Some(headers) = config("headers").and_then(Value::as_object) {
+                let headers: Vec<Value> = headers.iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value.as_str().map_or_else(|| value.to_string(), str::to_string) }))
+                    .collect();
+                http["headers"] = json!(headers);
+            }
+            if let Some(body) = config("body") {
+                http["body"] = json!(body.as_str().map_or_else(|| body.to_string(), str::to_string));
+            }
+            json!({ "name": name, "http": http })
+        }
+        _ if is_activity_node(node) => {
+            let image = node.config.get("image").and_then(Value::as_str).unwrap_or(default_image);
+            json!({
+                "name": name,
+                "container": {
+                    "image": image,
+                    "args": [activity_name(node)],
+                    "env": [{ "name": "WORKFLOW_INPUT", "value": "{{workflow.parameters.json}}" }],
+                },
+            })
+        }
+        NodeType::WaitTimer => {
+            let config: WaitTimerConfig = node.typed_config()?;
+            let duration = duration::parse_field(&config.duration, "duration", Some(&node.id))?;
+            json!({ "name": name, "suspend": { "duration": format!("{}s", duration.as_secs().max(1)) } })
+        }
+        NodeType::WaitSignal => json!({ "name": name, "suspend": {} }),
+        NodeType::SubWorkflow => return Ok(None),
+        NodeType::WaitSignals => return Err(steps::unsupported(node, "a multi-signal wait", target)),
+        NodeType::DynamicActivity => return Err(steps::unsupported(node, "a dynamic activity", target)),
+        NodeType::DecisionTable => return Err(steps::unsupported(node, "a decision table", target)),
+        NodeType::CancellationScope => return Err(steps::unsupported(node, "a cancellation scope", target)),
+        NodeType::NexusOperation => return Err(steps::unsupported(node, "a Nexus operation", target)),
+        NodeType::FeatureFlag => return Err(steps::unsupported(node, "a feature flag", target)),
+        NodeType::WeightedSplit => return Err(steps::unsupported(node, "a weighted split", target)),
+        _ => unreachable!("is_task excludes {:?} nodes", node.node_type),
+    };
+    if let Some(strategy) = retry_strategy(node)? {
+        template["retryStrategy"] = strategy;
+    }
+    Ok(Some(template))
+}
+
+/// Reject constructs the DAG cannot express before building it
+fn check_definition(definition: &WorkflowDefinition, target: &str) -> Result<(), CompilerError> {
+    if let Some(variable) = definition.variables.iter().find(|v| v.schema.is_some()) {
+        return Err(CompilerError::CodeGenError(format!(
+            "Variable '{}': input schemas are not supported by the {} target",
+            variable.name, target
+        )));
+    }
+    if let Some(edge) = definition.edges.iter().find(|e| e.kind == EdgeKind::Cancel) {
+        return Err(CompilerError::CodeGenError(format!("Edge '{}': cancel edges are not supported by the {} target", edge.id, target)));
+    }
+    for node in &definition.nodes {
+        if node.session.is_some() {
+            return Err(steps::unsupported(node, "a worker session", target));
+        }
+        match node.node_type {
+            NodeType::ParallelGateway => {
+                let config: ParallelGatewayConfig = node.typed_config()?;
+                if !matches!(config.join, JoinPolicy::All) {
+                    return Err(steps::unsupported(node, "an any or n_of_m join", target));
+                }
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                if config.namespace.is_some() || config.task_queue.is_some() {
+                    return Err(steps::unsupported(node, "a child workflow on another namespace or task queue", target));
+                }
+                if !config.wait_for_completion {
+                    return Err(steps::unsupported(node, "a child workflow that does not wait for completion", target));
+                }
+                if !config.input.is_empty() {
+                    return Err(steps::unsupported(node, "a child workflow input mapping", target));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn workflow_template(definition: &WorkflowDefinition, name: &str, package_name: &str, fingerprint: &str, target: &str) -> Result<Value, CompilerError> {
+    let default_image = format!("{}-activities:{}", resource_name(package_name), definition.version);
+    let mut tasks = Vec::new();
+    let mut templates = Vec::new();
+    for DagTask { node, dependencies, when } in dag_tasks(definition)? {
+        let task_name = task_name(definition, node);
+        let mut task = json!({ "name": task_name });
+        match template(node, &task_name, &default_image, target)? {
+            Some(template) => {
+                task["template"] = json!(task_name);
+                templates.push(template);
+            }
+            None => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                task["templateRef"] = json!({ "name": resource_name(&config.workflow), "template": "main" });
+            }
+        }
+        if !dependencies.is_empty() {
+            task["dependencies"] = json!(dependencies);
+        }
+        if let Some(when) = when {
+            task["when"] = json!(when);
+        }
+        tasks.push(task);
+    }
+    if tasks.is_empty() {
+        return Err(CompilerError::CodeGenError(format!("Workflow '{}' has no node that runs as an Argo task", definition.name)));
+    }
+    templates.insert(0, json!({ "name": "main", "dag": { "tasks": tasks } }));
+
+    let parameters: Vec<Value> = definition.variables.iter()
+        .map(|variable| match &variable.default_value {
+            Some(Value::String(value)) => json!({ "name": variable.name, "value": value }),
+            Some(value) if !value.is_null() => json!({ "name": variable.name, "value": value.to_string() }),
+            _ => json!({ "name": variable.name }),
+        })
+        .collect();
+    let mut spec = json!({ "entrypoint": "main", "templates": templates });
+    if !parameters.is_empty() {
+        spec["arguments"] = json!({ "parameters": parameters });
+    }
+    if let Some(raw) = definition.timeouts.as_ref().and_then(|t| t.execution.as_deref()) {
+        spec["activeDeadlineSeconds"] = json!(duration::parse_field(raw, "timeouts.execution", None)?.as_secs().max(1));
+    }
+    Ok(json!({
+        "apiVersion": API_VERSION,
+        "kind": "WorkflowTemplate",
+        "metadata": {
+            "name": name,
+            "annotations": {
+                "workflows.omniroute.io/definition-version": definition.version,
+                "workflows.omniroute.io/fingerprint": fingerprint,
+            },
+        },
+        "spec": spec,
+    }))
+}
+
+fn manifest(resource: &Value) -> String {
+    format!("# Generated by OmniRoute Workflow Compiler\n{}", yaml::render(resource))
+}
+
+impl Backend for Argo {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Argo
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        ARGO_VERSION
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        String::new()
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let target = self.target().name();
+        check_definition(definition, target)?;
+        let name = resource_name(package_name);
+        let template = workflow_template(definition, &name, package_name, fingerprint, target)?;
+        let workflow = json!({
+            "apiVersion": API_VERSION,
+            "kind": "Workflow",
+            "metadata": { "generateName": format!("{}-", name) },
+            "spec": { "workflowTemplateRef": { "name": name } },
+        });
+
+        let reachable = graph::reachable_nodes(definition);
+        let mut signals: Vec<String> = definition.nodes.iter()
+            .filter(|n| matches!(n.node_type, NodeType::WaitSignal) && reachable.contains(n.id.as_str()))
+            .map(|n| n.typed_config::<WaitSignalConfig>().map(|c| c.signal))
+            .collect::<Result<_, _>>()?;
+        signals.sort();
+        signals.dedup();
+
+        Ok(TargetSources {
+            workflow_code: manifest(&template),
+            activity_code: String::new(),
+            worker_code: manifest(&workflow),
+            test_code: String::new(),
+            files: vec![],
+            signals,
+            metadata: None,
+        })
+    }
+}
@@ -0,0 +1,50 @@
+//! Code generation backends for the non-Go targets
+//!
+//! Go is generated by `WorkflowCompiler` itself, which also owns SDK resolution, dependency
+//! pinning and tenant templates. Every other target implements `Backend` over the shared
+//! step traversal in `steps`, so adding a language means adding one module here.
+
+use crate::compiler::steps::TargetSources;
+use crate::compiler::codegen::VERSION_QUERY;
+use crate::compiler::{airflow, argo, conductor, dagster, dotnet, durable, java, prefect, python, scaffold, serverless_workflow, typescript};
+use crate::error::CompilerError;
+use crate::{CodegenTarget, WorkflowDefinition};
+
+pub trait Backend: Sync {
+    fn target(&self) -> CodegenTarget;
+
+    /// SDK release the generated project depends on
+    fn sdk_version(&self) -> &'static str;
+
+    /// Queries the generated workflow answers
+    fn queries(&self) -> Vec<String> {
+        vec![VERSION_QUERY.to_string()]
+    }
+
+    /// Compose file for the local services the generated project runs against
+    fn docker_compose(&self) -> String {
+        scaffold::generate_docker_compose()
+    }
+
+    /// Generate the project sources for a validated, optimized definition
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError>;
+}
+
+/// Backend generating `target`, or `None` for the Go pipeline shared by `go` and `cadence`
+pub fn for_target(target: CodegenTarget) -> Option<&'static dyn Backend> {
+    match target {
+        CodegenTarget::Go | CodegenTarget::Cadence => None,
+        CodegenTarget::Typescript => Some(&typescript::TypeScript),
+        CodegenTarget::Python => Some(&python::Python),
+        CodegenTarget::Java => Some(&java::Java),
+        CodegenTarget::Dotnet => Some(&dotnet::Dotnet),
+        CodegenTarget::DurableCsharp => Some(&durable::CSHARP),
+        CodegenTarget::DurableJavascript => Some(&durable::JAVASCRIPT),
+        CodegenTarget::Argo => Some(&argo::Argo),
+        CodegenTarget::Airflow => Some(&airflow::Airflow),
+        CodegenTarget::ServerlessWorkflow => Some(&serverless_workflow::ServerlessWorkflow),
+        CodegenTarget::Conductor => Some(&conductor::Conductor),
+        CodegenTarget::Dagster => Some(&dagster::Dagster),
+        CodegenTarget::Prefect => Some(&prefect::Prefect),
+    }
+}
@@ -0,0 +1,199 @@
+//! Definition size and complexity budgets from the service configuration
+//!
+//! Node and edge counts, parallel fan-out and fork nesting are checked first, before
+//! plugins are lowered or any pass whose cost grows faster than the graph runs, so an
+//! oversized payload is rejected without being walked. A fork's nesting depth is the number
+//! of forks open where it starts, itself included, on any path from a Start node.
+//! History events are estimated per reachable node from the events Temporal records for it,
+//! counting every branch of a decision and multiplying nodes on a cycle by the configured
+//! loop iterations, so the estimate is an upper bound for runs that do not loop longer.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::compiler::limits;
+use crate::compiler::diagnostic::Diagnostic;
+use crate::config::Budgets;
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{EdgeKind, NodeType, WaitSignalsConfig, WorkflowDefinition, WorkflowNode};
+
+/// Events every run records: started, its first workflow task and completed
+const RUN_EVENTS: u64 = 5;
+
+/// Scheduled, started and completed events of a workflow task
+const WORKFLOW_TASK_EVENTS: u64 = 3;
+
+/// Temporal warns about a run's history past this many events
+pub const HISTORY_WARN_EVENTS: u64 = 10_240;
+
+/// History events a single execution of a node records
+fn node_events(node: &WorkflowNode) -> u64 {
+    match node.node_type {
+        // Scheduled, started and completed, then the workflow task handling the result
+        NodeType::Activity | NodeType::HttpCall | NodeType::DatabaseQuery | NodeType::Notification
+        | NodeType::PublishEvent | NodeType::DynamicActivity | NodeType::FeatureFlag
+        | NodeType::SubWorkflow | NodeType::NexusOperation => 3 + WORKFLOW_TASK_EVENTS,
+        // Started and fired
+        NodeType::WaitTimer => 2 + WORKFLOW_TASK_EVENTS,
+        NodeType::WaitSignal => 1 + WORKFLOW_TASK_EVENTS,
+        NodeType::WaitSignals => {
+            let config = node.typed_config::<WaitSignalsConfig>().unwrap_or_default();
+            let timer = if config.timeout.is_some() { 2 } else { 0 };
+            config.signals.len() as u64 * (1 + WORKFLOW_TASK_EVENTS) + timer
+        }
+        // Side effect marker
+        NodeType::WeightedSplit => 1,
+        NodeType::Start | NodeType::End | NodeType::Decision | NodeType::ParallelGateway | NodeType::Transform
+        | NodeType::DecisionTable | NodeType::CancellationScope => 0,
+        // Plugin nodes are lowered before budgets are checked; count one as an activity otherwise
+        NodeType::Plugin(_) => 3 + WORKFLOW_TASK_EVENTS,
+    }
+}
+
+/// IDs of nodes on a cycle of flow edges
+fn cyclic_nodes(definition: &WorkflowDefinition) -> Result<HashSet<&str>, CompilerError> {
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Flow) {
+        successors.entry(edge.source.as_str()).or_default().push(edge.target.as_str());
+    }
+    // A node is on a cycle when it can reach itself
+    let mut cyclic = HashSet::new();
+    for id in definition.nodes.iter().map(|n| n.id.as_str()) {
+        limits::checkpoint("history estimate")?;
+        let mut seen = HashSet::new();
+        let mut pending: Vec<&str> = successors.get(id).cloned().unwrap_or_default();
+        while let Some(next) = pending.pop() {
+            if next == id {
+                cyclic.insert(id);
+                break;
+            }
+            if seen.insert(next) {
+                pending.extend(successors.get(next).into_iter().flatten());
+            }
+        }
+    }
+    Ok(cyclic)
+}
+
+/// Upper-bound estimate of the history events a run records
+pub fn estimate_history_events(definition: &WorkflowDefinition, loop_iterations: u64) -> Result<u64, CompilerError> {
+    let reachable = graph::reachable_nodes(definition);
+    let cyclic = cyclic_nodes(definition)?;
+    let node_total: u64 = definition.nodes.iter()
+        .filter(|n| reachable.contains(n.id.as_str()))
+        .map(|n| {
+            let events = node_events(n);
+            if cyclic.contains(n.id.as_str()) { events * loop_iterations.max(1) } else { events }
+        })
+        .sum();
+    Ok(RUN_EVENTS + node_total)
+}
+
+/// First fork gateway nested more than `max` forks deep on a path from a Start node
+fn fork_nested_past(definition: &WorkflowDefinition, max: usize) -> Result<Option<(&WorkflowNode, usize)>, CompilerError> {
+    let nodes: HashMap<&str, &WorkflowNode> = definition.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut successors: HashMap<&str, Vec<&WorkflowNode>> = HashMap::new();
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Flow) {
+        if let Some(target) = nodes.get(edge.target.as_str()) {
+            successors.entry(edge.source.as_str()).or_default().push(target);
+        }
+    }
+    // Forks open a level and joins close one; depths stop at `max + 1`, bounding the walk
+    let mut seen = HashSet::new();
+    let mut pending: Vec<(&WorkflowNode, usize)> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::Start))
+        .map(|n| (n, 0))
+        .collect();
+    while let Some((node, depth)) = pending.pop() {
+        limits::checkpoint("nesting depth")?;
+        if !seen.insert((node.id.as_str(), depth)) {
+            continue;
+        }
+        let next = successors.get(node.id.as_str()).map_or(&[][..], Vec::as_slice);
+        let depth = match node.node_type {
+            NodeType::ParallelGateway if next.len() <= 1 => depth.saturating_sub(1),
+            NodeType::ParallelGateway if depth == max => return Ok(Some((node, depth + 1))),
+            NodeType::ParallelGateway => depth + 1,
+            _ => depth,
+        };
+        pending.extend(next.iter().map(|n| (*n, depth)));
+    }
+    Ok(None)
+}
+
+/// Check the graph's size against the node, edge, fan-out and nesting budgets; cheap enough
+/// to run on an unchecked payload before any other pass
+pub fn check_size(definition: &WorkflowDefinition, budgets: &Budgets) -> Result<(), CompilerError> {
+    if let Some(max) = budgets.max_nodes {
+        if definition.nodes.len() > max {
+            return Err(CompilerError::ValidationError(format!(
+                "Workflow has {} nodes, over the budget of {}",
+                definition.nodes.len(),
+                max
+            ).into()));
+        }
+    }
+
+    if let Some(max) = budgets.max_edges {
+        if definition.edges.len() > max {
+            return Err(CompilerError::ValidationError(format!(
+                "Workflow has {} edges, over the budget of {}",
+                definition.edges.len(),
+                max
+            ).into()));
+        }
+    }
+
+    if let Some(max) = budgets.max_fan_out {
+        let mut branches: HashMap<&str, usize> = HashMap::new();
+        for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Flow) {
+            *branches.entry(edge.source.as_str()).or_default() += 1;
+        }
+        for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::ParallelGateway)) {
+            let branches = branches.get(node.id.as_str()).copied().unwrap_or_default();
+            if branches > max {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "ParallelGateway node '{}' forks {} branches, over the fan-out budget of {}",
+                    node.id, branches, max
+                )).node(&node.id)));
+            }
+        }
+    }
+
+    if let Some(max) = budgets.max_nesting_depth {
+        if let Some((fork, depth)) = fork_nested_past(definition, max)? {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "ParallelGateway node '{}' opens a fork nested {} deep, over the nesting budget of {}",
+                fork.id, depth, max
+            )).node(&fork.id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the definition against the configured budgets, warning when the history estimate
+/// passes Temporal's own warning threshold
+pub fn check(definition: &WorkflowDefinition, budgets: &Budgets) -> Result<Vec<String>, CompilerError> {
+    check_size(definition, budgets)?;
+
+    let events = estimate_history_events(definition, budgets.loop_iterations)?;
+    if let Some(max) = budgets.max_history_events {
+        if events > max {
+            return Err(CompilerError::ValidationError(format!(
+                "Workflow is estimated to record up to {} history events per run, over the budget of {}; \
+                 split it into child workflows or continue-as-new",
+                events, max
+            ).into()));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if events > HISTORY_WARN_EVENTS {
+        warnings.push(format!(
+            "Workflow is estimated to record up to {} history events per run, past Temporal's warning threshold of {}",
+            events, HISTORY_WARN_EVENTS
+        ));
+    }
+    Ok(warnings)
+}
@@ -0,0 +1,399 @@
+//! Cadence Go generation: the go target's sources ported to the `go.uber.org/cadence` client
+//!
+//! Cadence and Temporal share their workflow programming model, so the workflow, activity,
+//! outbox and test sources come from the go target's templates and are rewritten here: SDK
+//! imports, `cadence.NewCustomError` in place of application errors (the error type becomes
+//! the reason), the client and thrift enums, zap's sugared logger, and the options Cadence
+//! requires, a schedule-to-start timeout on activities and an execution timeout on child
+//! workflows. Only the client and worker wiring is Cadence's own: the worker reaches the
+//! frontend over TChannel through a YARPC dispatcher and polls a domain's task list, starts
+//! set the execution timeout Cadence requires, and schedule triggers start cron workflows.
+//!
+//! Cadence retries custom errors like any other failure, so errors the go target raises as
+//! non-retryable end an activity only once its retry policy is exhausted.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+
+use crate::compiler::codegen::{self, go_string_literal};
+use crate::compiler::duration;
+use crate::compiler::scaffold::WORKER_PACKAGE;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, NodeType, OverlapPolicy, WorkflowDefinition};
+
+/// Cadence Go client release the generated module requires
+pub const CADENCE_VERSION: &str = "1.2.9";
+
+/// Host port of the local Cadence frontend's TChannel endpoint
+const CADENCE_PORT: u16 = 7933;
+
+/// Execution timeout of starts when the definition sets no run or execution timeout
+const DEFAULT_EXECUTION_TIMEOUT: &str = "24 * time.Hour";
+
+/// Temporal SDK imports and the Cadence packages replacing them
+const IMPORTS: &[(&str, &str)] = &[
+    ("\"go.temporal.io/sdk/activity\"", "\"go.uber.org/cadence/activity\""),
+    ("\"go.temporal.io/sdk/client\"", "\"go.uber.org/cadence/client\""),
+    ("\"go.temporal.io/sdk/temporal\"", "\"go.uber.org/cadence\""),
+    ("\"go.temporal.io/sdk/testsuite\"", "\"go.uber.org/cadence/testsuite\""),
+    ("\"go.temporal.io/sdk/worker\"", "\"go.uber.org/cadence/worker\""),
+    ("\"go.temporal.io/sdk/workflow\"", "\"go.uber.org/cadence/workflow\""),
+];
+
+/// Temporal's enum package, whose constants Cadence splits between `client` and `shared`
+const ENUMS_IMPORT: &str = "enumspb \"go.temporal.io/api/enums/v1\"";
+
+/// Packages the rewritten enum constants live in, by the identifier qualifying them
+const ENUM_PACKAGES: &[(&str, &str)] = &[
+    ("client", "\"go.uber.org/cadence/client\""),
+    ("shared", "\"go.uber.org/cadence/.gen/go/shared\""),
+];
+
+/// Literal rewrites of go target code whose Cadence form differs
+const REWRITES: &[(&str, &str)] = &[
+    ("enumspb.PARENT_CLOSE_POLICY_TERMINATE", "client.ParentClosePolicyTerminate"),
+    ("enumspb.PARENT_CLOSE_POLICY_REQUEST_CANCEL", "client.ParentClosePolicyRequestCancel"),
+    ("enumspb.PARENT_CLOSE_POLICY_ABANDON", "client.ParentClosePolicyAbandon"),
+    ("enumspb.TIMEOUT_TYPE_START_TO_CLOSE", "shared.TimeoutTypeStartToClose"),
+    ("enumspb.TIMEOUT_TYPE_HEARTBEAT", "shared.TimeoutTypeHeartbeat"),
+    ("*temporal.ApplicationError", "*cadence.CustomError"),
+    ("appErr.Type()", "appErr.Reason()"),
+    // Cadence rejects retry policies without an initial interval and backoff
+    ("temporal.RetryPolicy{MaximumAttempts: 3}", "cadence.RetryPolicy{InitialInterval: time.Second, BackoffCoefficient: 2, MaximumAttempts: 3}"),
+    // Cadence loggers are zap loggers; the sugared logger takes key-value pairs
+    ("logger := workflow.GetLogger(ctx)", "logger := workflow.GetLogger(ctx).Sugar()"),
+    ("workflow.GetLogger(ctx).Warn(", "workflow.GetLogger(ctx).Sugar().Warnw("),
+    ("logger.Info(", "logger.Infow("),
+    ("logger.Warn(", "logger.Warnw("),
+    ("logger.Error(", "logger.Errorw("),
+    ("Namespace:           ", "Domain:              "),
+    ("TaskQueue:           ", "TaskList:            "),
+    // Children run within the parent's execution timeout, which Cadence requires them to set
+    (
+        "workflow.ChildWorkflowOptions{\n",
+        "workflow.ChildWorkflowOptions{\n            ExecutionStartToCloseTimeout: time.Duration(workflow.GetInfo(ctx).ExecutionStartToCloseTimeoutSeconds) * time.Second,\n",
+    ),
+];
+
+struct Patterns {
+    application_error: Regex,
+    timeout_error: Regex,
+    activity_options: Regex,
+    sdk_root: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        application_error: Regex::new(r#"temporal\.New(?:NonRetryable)?ApplicationError\((.+?), ("\w+")((?:, [\w.]+)*)\)"#)
+            .expect("valid application error pattern"),
+        timeout_error: Regex::new(r"temporal\.NewTimeoutError\(([\w.]+), nil\)").expect("valid timeout error pattern"),
+        activity_options: Regex::new(r"workflow\.ActivityOptions\{\n(\s*)StartToCloseTimeout:(\s*)([^,\n]+),")
+            .expect("valid activity options pattern"),
+        sdk_root: Regex::new(r"\btemporal\.([A-Z])").expect("valid SDK root pattern"),
+    })
+}
+
+/// Reject definitions using Temporal features the Cadence client lacks
+pub fn check(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    if let Some(node) = definition.nodes.iter().find(|n| matches!(n.node_type, NodeType::NexusOperation)) {
+        return Err(CompilerError::CodeGenError(format!(
+            "Node '{}': a Nexus operation is not supported by the cadence target",
+            node.id
+        )));
+    }
+    // Cron workflows skip runs due while the previous one runs and cannot be paused or caught up
+    for schedule in codegen::schedule_triggers(definition)? {
+        let unsupported = if schedule.overlap != OverlapPolicy::Skip {
+            Some("an overlap policy other than skip")
+        } else if schedule.catchup_window.is_some() {
+            Some("a catchup window")
+        } else if schedule.paused {
+            Some("a paused schedule")
+        } else {
+            None
+        };
+        if let Some(what) = unsupported {
+            return Err(CompilerError::CodeGenError(format!(
+                "Schedule trigger '{}': {} is not supported by the cadence target",
+                schedule.cron, what
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite go target code, outside its import block, for the Cadence client
+fn rewrite(code: &str) -> String {
+    let patterns = patterns();
+    let mut code = REWRITES.iter().fold(code.to_string(), |code, (from, to)| code.replace(from, to));
+    code = patterns.application_error.replace_all(&code, |caps: &Captures| {
+        let details: String = caps[3].split(", ").filter(|d| !d.is_empty() && *d != "nil").map(|d| format!(", {d}")).collect();
+        format!("cadence.NewCustomError({}, {}{})", &caps[2], &caps[1], details)
+    }).into_owned();
+    code = patterns.timeout_error.replace_all(&code, "cadence.NewTimeoutError($1)").into_owned();
+    code = patterns.activity_options.replace_all(&code, |caps: &Captures| {
+        format!("workflow.ActivityOptions{{\n{indent}ScheduleToStartTimeout: {timeout},\n{indent}StartToCloseTimeout:{pad}{timeout},",
+            indent = &caps[1], pad = &caps[2], timeout = &caps[3])
+    }).into_owned();
+    patterns.sdk_root.replace_all(&code, "cadence.$1").into_owned()
+}
+
+/// Swap the Temporal imports of a file's import block for their Cadence packages
+fn rewrite_imports(code: &str) -> String {
+    let Some(start) = code.find("import (\n").map(|i| i + "import (\n".len()) else {
+        return code.to_string();
+    };
+    let end = code[start..].find("\n)").map_or(code.len(), |i| start + i + 1);
+    let block = &code[start..end];
+
+    let mut imports = String::new();
+    for line in block.lines() {
+        let import = line.trim();
+        if import == ENUMS_IMPORT {
+            for (package, path) in ENUM_PACKAGES {
+                let used = Regex::new(&format!(r"\b{package}\.[A-Z]")).is_ok_and(|re| re.is_match(&code[end..]));
+                if used && !block.contains(path) {
+                    imports.push_str(&format!("    {path}\n"));
+                }
+            }
+            continue;
+        }
+        let ported = IMPORTS.iter().find(|(temporal, _)| *temporal == import).map_or(import, |(_, cadence)| cadence);
+        imports.push_str(&line.replace(import, ported));
+        imports.push('\n');
+    }
+
+    format!("{}{}{}", &code[..start], imports, &code[end..])
+}
+
+/// Port a go target source file to the Cadence client
+pub fn port(code: &str) -> String {
+    if code.is_empty() {
+        return String::new();
+    }
+    rewrite_imports(&rewrite(code))
+}
+
+/// Port the workflow file, moving each node's offset to where its code lands
+pub fn port_workflow(code: &str, node_offsets: &[(String, usize)]) -> (String, Vec<(String, usize)>) {
+    // Node code starts at statement boundaries, so each node's code is rewritten on its own
+    let mut cuts: Vec<usize> = node_offsets.iter().map(|(_, offset)| *offset).collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+    let mut body = String::new();
+    let mut moved = BTreeMap::new();
+    let mut start = 0;
+    for cut in cuts {
+        body.push_str(&rewrite(&code[start..cut]));
+        moved.insert(cut, body.len());
+        start = cut;
+    }
+    body.push_str(&rewrite(&code[start..]));
+
+    // The import block precedes every node, shifting them all alike
+    let ported = rewrite_imports(&body);
+    let shift = ported.len() as isize - body.len() as isize;
+    let offsets = node_offsets.iter()
+        .map(|(id, offset)| (id.clone(), (moved[offset] as isize + shift) as usize))
+        .collect();
+    (ported, offsets)
+}
+
+/// Task list the generated worker polls and starts are sent to
+fn task_list(package_name: &str) -> String {
+    format!("{package_name}-task-list")
+}
+
+/// Generate the worker entrypoint, connecting to the frontend named by `CADENCE_ADDRESS`
+/// and polling the domain named by `CADENCE_DOMAIN`
+pub fn generate_worker_code(definition: &WorkflowDefinition, package_name: &str) -> String {
+    let workflow_name = to_pascal_case(&definition.name);
+    let worker_options = codegen::generate_worker_options(definition, None);
+    let task_list = task_list(package_name);
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+package main
+
+import (
+    "log"
+    "os"
+    "go.uber.org/cadence/.gen/go/cadence/workflowserviceclient"
+    "go.uber.org/cadence/worker"
+    "go.uber.org/yarpc"
+    "go.uber.org/yarpc/transport/tchannel"
+    "{package_name}"
+)
+
+func main() {{
+    transport, err := tchannel.NewChannelTransport(tchannel.ServiceName("{package_name}-worker"))
+    if err != nil {{
+        log.Fatalln("Unable to create transport", err)
+    }}
+    dispatcher := yarpc.NewDispatcher(yarpc.Config{{
+        Name: "{package_name}-worker",
+        Outbounds: yarpc.Outbounds{{
+            "cadence-frontend": {{Unary: transport.NewSingleOutbound(os.Getenv("CADENCE_ADDRESS"))}},
+        }},
+    }})
+    if err := dispatcher.Start(); err != nil {{
+        log.Fatalln("Unable to start dispatcher", err)
+    }}
+    defer dispatcher.Stop()
+    service := workflowserviceclient.New(dispatcher.ClientConfig("cadence-frontend"))
+
+    w := worker.New(service, os.Getenv("CADENCE_DOMAIN"), "{task_list}", {worker_options})
+
+    w.RegisterWorkflow({package_name}.{workflow_name})
+
+    activities := {package_name}.NewActivities()
+    w.RegisterActivity(activities)
+
+    err = w.Run()
+    if err != nil {{
+        log.Fatalln("Unable to start worker", err)
+    }}
+}}
+"#)
+}
+
+/// Generate the start options and starter, plus a function starting one cron workflow per
+/// schedule trigger
+pub fn generate_starter_code(definition: &WorkflowDefinition, package_name: &str) -> Result<String, CompilerError> {
+    let workflow_name = to_pascal_case(&definition.name);
+    let task_list = task_list(package_name);
+    let timeouts = definition.timeouts.as_ref();
+    // Cadence execution timeouts bound a single run
+    let execution = match timeouts.and_then(|t| t.run.as_ref().or(t.execution.as_ref())) {
+        Some(raw) => duration::go_expr_for_field(raw, "ExecutionStartToCloseTimeout", None)?,
+        None => DEFAULT_EXECUTION_TIMEOUT.to_string(),
+    };
+    let decision = match timeouts.and_then(|t| t.task.as_ref()) {
+        Some(raw) => format!(
+            "        DecisionTaskStartToCloseTimeout: {},\n",
+            duration::go_expr_for_field(raw, "DecisionTaskStartToCloseTimeout", None)?
+        ),
+        None => String::new(),
+    };
+
+    let schedules = codegen::schedule_triggers(definition)?;
+    let schedules = if schedules.is_empty() {
+        String::new()
+    } else {
+        let starts: String = schedules.iter().enumerate()
+            .map(|(i, schedule)| format!(r#"    options {assign} {workflow_name}StartOptions("{package_name}-schedule-{i}")
+    options.CronSchedule = {cron}
+    if _, err := c.StartWorkflow(ctx, options, {workflow_name}, input); err != nil {{
+        return err
+    }}
+"#, assign = if i == 0 { ":=" } else { "=" }, cron = go_string_literal(&schedule.cron)))
+            .collect();
+        format!(r#"
+// Create{workflow_name}Schedules starts one cron workflow per schedule trigger
+func Create{workflow_name}Schedules(ctx context.Context, c client.Client, input {workflow_name}Input) error {{
+{starts}    return nil
+}}
+"#)
+    };
+
+    Ok(format!(r#"// Generated by OmniRoute Workflow Compiler
+package {package_name}
+
+import (
+    "context"
+    "go.uber.org/cadence/client"
+    "time"
+)
+
+// {workflow_name}StartOptions returns the start options for {workflow_name} executions
+func {workflow_name}StartOptions(workflowID string) client.StartWorkflowOptions {{
+    return client.StartWorkflowOptions{{
+        ID:                           workflowID,
+        TaskList:                     "{task_list}",
+        ExecutionStartToCloseTimeout: {execution},
+{decision}    }}
+}}
+
+// Start{workflow_name} starts a new {workflow_name} execution
+func Start{workflow_name}(ctx context.Context, c client.Client, workflowID string, input {workflow_name}Input) (client.WorkflowRun, error) {{
+    return c.ExecuteWorkflow(ctx, {workflow_name}StartOptions(workflowID), {workflow_name}, input)
+}}
+{schedules}"#))
+}
+
+/// Generate a Makefile with build, test and run targets plus the local Cadence harness
+pub fn generate_makefile(package_name: &str, workflow_name: &str) -> String {
+    let task_list = task_list(package_name);
+    format!(
+        "# Generated by OmniRoute Workflow Compiler\n\
+         .PHONY: build test bench vet run start dev-up dev-down\n\
+         \n\
+         BIN := bin/{package_name}-worker\n\
+         CADENCE_ADDRESS ?= localhost:{CADENCE_PORT}\n\
+         CADENCE_DOMAIN ?= default\n\
+         \n\
+         build:\n\
+         \tgo build -o $(BIN) {WORKER_PACKAGE}\n\
+         \n\
+         test:\n\
+         \tgo test ./...\n\
+         \n\
+         bench:\n\
+         \tgo test -run '^$' -bench . -benchmem ./...\n\
+         \n\
+         vet:\n\
+         \tgo vet ./...\n\
+         \n\
+         run: build\n\
+         \tCADENCE_ADDRESS=$(CADENCE_ADDRESS) CADENCE_DOMAIN=$(CADENCE_DOMAIN) ./$(BIN)\n\
+         \n\
+         start:\n\
+         \tdocker compose exec cadence cadence --domain $(CADENCE_DOMAIN) workflow start \\\n\
+         \t\t--tasklist {task_list} --workflow_type {package_name}.{workflow_name} --execution_timeout 86400 --input '{{}}'\n\
+         \n\
+         dev-up:\n\
+         \tdocker compose up -d --wait\n\
+         \n\
+         dev-down:\n\
+         \tdocker compose down\n"
+    )
+}
+
+/// Generate a docker-compose file running a Cadence server on Cassandra with the web UI
+pub fn generate_docker_compose() -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+services:
+  cassandra:
+    image: cassandra:4.1
+    healthcheck:
+      test: ["CMD", "cqlsh", "-e", "describe keyspaces"]
+      interval: 10s
+      timeout: 10s
+      retries: 18
+  cadence:
+    image: ubercadence/server:master-auto-setup
+    environment:
+      - CASSANDRA_SEEDS=cassandra
+    ports:
+      - "{CADENCE_PORT}:7933"
+    depends_on:
+      cassandra:
+        condition: service_healthy
+    healthcheck:
+      # Healthy once the frontend answers, registering the default domain on first run
+      test: ["CMD-SHELL", "cadence --domain default domain describe || cadence --domain default domain register"]
+      interval: 5s
+      timeout: 10s
+      retries: 24
+  cadence-web:
+    image: ubercadence/web:latest
+    environment:
+      - CADENCE_TCHANNEL_PEERS=cadence:7933
+    ports:
+      - "8088:8088"
+    depends_on:
+      - cadence
+"#)
+}
@@ -0,0 +1,1518 @@
+//! Go code generation for workflow graph nodes
+
+use std::collections::{HashMap, HashSet};
+
+use crate::compiler::types::{self, base_type, VarType};
+use crate::compiler::validator::is_loop_head;
+use crate::compiler::{decision_table, duration, feature_flag, input_validation, limits, report, source_map};
+use crate::dsl::condition::{self, Expr, Literal};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    to_pascal_case, ActivityConfig, ActivityOrigin, CancellationScopeConfig, ChildCancellationType, DynamicActivityConfig, EdgeKind, FeatureFlagConfig, FlagProvider, JoinPolicy,
+    NexusOperationConfig, NodeType, OverlapPolicy, ParallelGatewayConfig, ParentClosePolicy, ScheduleTriggerConfig, SignalWaitMode,
+    SubWorkflowConfig, TriggerType, Variable, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig, WeightedSplitConfig, WorkflowDefinition,
+    WorkflowEdge, WorkflowNode,
+};
+
+/// Query every generated workflow answers with its compiled definition version
+pub const VERSION_QUERY: &str = "__omniroute_version";
+
+/// Whether the node compiles to a Temporal activity
+pub fn is_activity_node(node: &WorkflowNode) -> bool {
+    matches!(node.node_type, NodeType::Activity | NodeType::HttpCall | NodeType::DatabaseQuery | NodeType::PublishEvent)
+}
+
+/// Registered activity name for a node: the activity type a plugin lowered it to, or its label
+pub fn activity_name(node: &WorkflowNode) -> String {
+    let activity_type = match node.node_type {
+        NodeType::Activity => node.typed_config::<ActivityConfig>().ok().and_then(|c| c.activity_type),
+        _ => None,
+    };
+    activity_type.unwrap_or_else(|| format!("{}Activity", to_pascal_case(&node.label)))
+}
+
+/// Go identifier generated from a node's label: the activity name of an activity node without
+/// an explicit `activity_type`, or the evaluation function of a decision table
+pub fn label_identifier(node: &WorkflowNode) -> Option<String> {
+    let explicit = matches!(node.node_type, NodeType::Activity)
+        && node.typed_config::<ActivityConfig>().is_ok_and(|c| c.activity_type.is_some());
+    match node.node_type {
+        NodeType::DecisionTable => Some(decision_table::function_name(node)),
+        _ if is_activity_node(node) && !explicit => Some(activity_name(node)),
+        _ => None,
+    }
+}
+
+/// Activity names a node may execute, including dynamic dispatch targets
+fn node_activities(node: &WorkflowNode) -> Vec<String> {
+    if is_activity_node(node) {
+        vec![activity_name(node)]
+    } else if matches!(node.node_type, NodeType::DynamicActivity) {
+        node.typed_config::<DynamicActivityConfig>().map(|c| c.allowed).unwrap_or_default()
+    } else {
+        vec![]
+    }
+}
+
+/// All activity names the worker must register, including dynamic dispatch targets
+pub fn activity_names(definition: &WorkflowDefinition) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in definition.nodes.iter().flat_map(node_activities) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Activities that can execute, each with the reachable node IDs it originates from
+pub fn reachable_activities(definition: &WorkflowDefinition) -> Vec<ActivityOrigin> {
+    let reachable = graph::reachable_nodes(definition);
+    let mut origins: Vec<ActivityOrigin> = Vec::new();
+    for node in definition.nodes.iter().filter(|n| reachable.contains(n.id.as_str())) {
+        for name in node_activities(node) {
+            match origins.iter_mut().find(|o| o.activity == name) {
+                Some(origin) => origin.node_ids.push(node.id.clone()),
+                None => origins.push(ActivityOrigin { activity: name, node_ids: vec![node.id.clone()] }),
+            }
+        }
+    }
+    origins
+}
+
+/// Generate a stub method on `Activities` for every registered activity name not in `skip`
+pub fn generate_activity_methods(definition: &WorkflowDefinition, workflow_name: &str, skip: &[String]) -> Result<String, CompilerError> {
+    let mut methods = String::new();
+    for name in activity_names(definition).iter().filter(|name| !skip.contains(name)) {
+        limits::checkpoint("code generation")?;
+        methods.push_str(&activity_stub(name, workflow_name));
+    }
+    Ok(methods)
+}
+
+/// Stub method on `Activities` for one activity, left for the user to implement
+pub fn activity_stub(name: &str, workflow_name: &str) -> String {
+    format!(r#"// {name} implements the {name} activity
+func (a *Activities) {name}(ctx context.Context, input {workflow_name}Input) error {{
+    // TODO: implement {name}
+    return nil
+}}
+
+"#)
+}
+
+/// Whether a string is a legal Go identifier
+pub fn is_go_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Render a Rust string as a Go string literal
+pub fn go_string_literal(s: &str) -> String {
+    // JSON string escapes are a subset of Go's interpreted string literal escapes
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Parent variable a SubWorkflow input value names on its own, as `{{name}}` or `{{input.name}}`
+pub fn child_input_variable<'a>(definition: &'a WorkflowDefinition, value: &serde_json::Value) -> Option<&'a Variable> {
+    let inner = value.as_str()?.trim().strip_prefix("{{")?.strip_suffix("}}")?.trim();
+    let name = inner.strip_prefix("input.").unwrap_or(inner);
+    definition.variables.iter().find(|v| v.name == name || to_pascal_case(&v.name) == name)
+}
+
+/// Go expression for a SubWorkflow input value: the parent variable it names, or the JSON
+/// scalar it holds; None for arrays, objects and strings with other templates
+pub fn child_input_expr(definition: &WorkflowDefinition, value: &serde_json::Value) -> Option<String> {
+    use serde_json::Value;
+    if let Some(variable) = child_input_variable(definition, value) {
+        return Some(format!("input.{}", to_pascal_case(&variable.name)));
+    }
+    match value {
+        Value::String(text) if text.contains("{{") => None,
+        Value::String(text) => Some(go_string_literal(text)),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Null => Some("nil".to_string()),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// Map a DSL variable type onto a Go type
+pub fn go_type(var_type: &str) -> &'static str {
+    match base_type(var_type) {
+        "string" => "string",
+        "int" | "integer" => "int64",
+        "float" | "number" => "float64",
+        "bool" | "boolean" => "bool",
+        "object" => "map[string]any",
+        "array" => "[]any",
+        _ => "any",
+    }
+}
+
+/// Generate `client.StartWorkflowOptions` timeout fields from the workflow-level timeouts
+pub fn generate_timeout_options(definition: &WorkflowDefinition) -> Result<String, CompilerError> {
+    let Some(timeouts) = &definition.timeouts else {
+        return Ok(String::new());
+    };
+
+    let mut fields = String::new();
+    for (field, value) in [
+        ("WorkflowExecutionTimeout", &timeouts.execution),
+        ("WorkflowRunTimeout", &timeouts.run),
+        ("WorkflowTaskTimeout", &timeouts.task),
+    ] {
+        if let Some(raw) = value {
+            let duration = duration::go_expr_for_field(raw, field, None)?;
+            fields.push_str(&format!("        {field}: {duration},\n"));
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Typed configs of all schedule triggers, in definition order
+pub fn schedule_triggers(definition: &WorkflowDefinition) -> Result<Vec<ScheduleTriggerConfig>, CompilerError> {
+    definition.triggers.iter()
+        .filter(|t| matches!(t.trigger_type, TriggerType::Schedule))
+        .map(|t| serde_json::from_value(t.config.clone()).map_err(|e| {
+            CompilerError::ValidationError(format!("Invalid schedule trigger config: {}", e).into())
+        }))
+        .collect()
+}
+
+/// Generate the starter file import block
+pub fn generate_starter_imports(definition: &WorkflowDefinition) -> String {
+    let has_schedules = definition.triggers.iter().any(|t| matches!(t.trigger_type, TriggerType::Schedule));
+    let has_timeouts = definition.timeouts.as_ref()
+        .is_some_and(|t| t.execution.is_some() || t.run.is_some() || t.task.is_some());
+
+    let mut imports = vec!["\"context\"", "\"go.temporal.io/sdk/client\""];
+    if has_schedules {
+        imports.push("enumspb \"go.temporal.io/api/enums/v1\"");
+    }
+    let has_catchup = schedule_triggers(definition)
+        .is_ok_and(|schedules| schedules.iter().any(|s| s.catchup_window.is_some()));
+    if has_timeouts || has_catchup {
+        imports.push("\"time\"");
+    }
+    imports.sort_unstable_by_key(|i| i.trim_start_matches(|c| c != '"'));
+
+    imports.iter().map(|i| format!("    {i}\n")).collect()
+}
+
+/// Generate a function registering one Temporal schedule per Schedule trigger,
+/// carrying the overlap, catchup and pause policy plus the workflow-level timeouts
+pub fn generate_schedules(definition: &WorkflowDefinition, workflow_name: &str, package_name: &str) -> Result<String, CompilerError> {
+    let schedules = schedule_triggers(definition)?;
+    if schedules.is_empty() {
+        return Ok(String::new());
+    }
+
+    let timeout_options = indent(&generate_timeout_options(definition)?, 4);
+    let mut creates = String::new();
+    for (i, schedule) in schedules.iter().enumerate() {
+        let overlap = match schedule.overlap {
+            OverlapPolicy::Skip => "SKIP",
+            OverlapPolicy::BufferOne => "BUFFER_ONE",
+            OverlapPolicy::BufferAll => "BUFFER_ALL",
+            OverlapPolicy::CancelOther => "CANCEL_OTHER",
+            OverlapPolicy::TerminateOther => "TERMINATE_OTHER",
+            OverlapPolicy::AllowAll => "ALLOW_ALL",
+        };
+        let catchup = match &schedule.catchup_window {
+            Some(raw) => {
+                let duration = duration::go_expr_for_field(raw, "schedule catchup_window", None)?;
+                format!("\n        CatchupWindow: {duration},")
+            }
+            None => String::new(),
+        };
+        let note = schedule.note.as_deref()
+            .map(|n| format!("\n        Note:          {},", go_string_literal(n)))
+            .unwrap_or_default();
+        let cron = go_string_literal(&schedule.cron);
+        let paused = schedule.paused;
+
+        creates.push_str(&format!(r#"    if _, err := c.ScheduleClient().Create(ctx, client.ScheduleOptions{{
+        ID: "{package_name}-schedule-{i}",
+        Spec: client.ScheduleSpec{{
+            CronExpressions: []string{{{cron}}},
+        }},
+        Action: &client.ScheduleWorkflowAction{{
+            ID:        "{package_name}-scheduled",
+            Workflow:  {workflow_name},
+            Args:      []interface{{}}{{input}},
+            TaskQueue: "{package_name}-task-queue",
+{timeout_options}        }},
+        Overlap:       enumspb.SCHEDULE_OVERLAP_POLICY_{overlap},{catchup}
+        Paused:        {paused},{note}
+    }}); err != nil {{
+        return err
+    }}
+"#));
+    }
+
+    Ok(format!(r#"
+// Create{workflow_name}Schedules registers the workflow's schedule triggers
+func Create{workflow_name}Schedules(ctx context.Context, c client.Client, input {workflow_name}Input) error {{
+{creates}    return nil
+}}
+"#))
+}
+
+/// Default worker Build ID: the definition version plus a fingerprint prefix
+pub fn default_build_id(definition: &WorkflowDefinition, fingerprint: &str) -> String {
+    let hash = fingerprint.trim_start_matches("sha256:");
+    format!("{}-{}", definition.version, &hash[..hash.len().min(12)])
+}
+
+/// Generate constants identifying the definition this code was compiled from
+pub fn generate_version_info(definition: &WorkflowDefinition, workflow_name: &str, fingerprint: &str) -> String {
+    let version = go_string_literal(&definition.version);
+    let fingerprint = go_string_literal(fingerprint);
+
+    format!(r#"const (
+    // {workflow_name}DefinitionVersion is the version of the workflow definition this code was compiled from
+    {workflow_name}DefinitionVersion = {version}
+    // {workflow_name}DefinitionFingerprint is the content hash of that definition
+    {workflow_name}DefinitionFingerprint = {fingerprint}
+)
+
+// {workflow_name}VersionInfo is returned by the {VERSION_QUERY} query
+type {workflow_name}VersionInfo struct {{
+    Version     string `json:"version"`
+    Fingerprint string `json:"fingerprint"`
+}}
+"#)
+}
+
+/// Generate the query handler registration reporting the compiled version of a running execution
+pub fn generate_version_query(workflow_name: &str) -> String {
+    format!(r#"    if err := workflow.SetQueryHandler(ctx, "{VERSION_QUERY}", func() ({workflow_name}VersionInfo, error) {{
+        return {workflow_name}VersionInfo{{
+            Version:     {workflow_name}DefinitionVersion,
+            Fingerprint: {workflow_name}DefinitionFingerprint,
+        }}, nil
+    }}); err != nil {{
+        return nil, err
+    }}
+"#)
+}
+
+/// Generate the workflow file import block for the packages the node logic uses, plus the
+/// trace's when `traced`
+pub fn generate_workflow_imports(definition: &WorkflowDefinition, traced: bool) -> String {
+    let mut imports = vec!["\"go.temporal.io/sdk/workflow\"", "\"time\""];
+    let uses = |f: fn(&NodeType) -> bool| definition.nodes.iter().any(|n| f(&n.node_type));
+    let has_retries = definition.nodes.iter()
+        .any(|n| n.retries.is_some() && (is_activity_node(n) || matches!(n.node_type, NodeType::DynamicActivity)));
+    if traced || has_retries || uses(|t| matches!(t, NodeType::WaitSignals | NodeType::DynamicActivity | NodeType::CancellationScope)) {
+        imports.push("\"go.temporal.io/sdk/temporal\"");
+    }
+    if uses(|t| matches!(t, NodeType::SubWorkflow)) {
+        imports.push("enumspb \"go.temporal.io/api/enums/v1\"");
+    }
+    if uses(|t| matches!(t, NodeType::WeightedSplit)) {
+        imports.push("\"math/rand\"");
+    }
+    imports.extend(input_validation::imports(definition));
+    imports.sort_unstable_by_key(|i| i.trim_start_matches(|c| c != '"'));
+    imports.dedup();
+
+    imports.iter().map(|i| format!("    {i}\n")).collect()
+}
+
+/// Names of all signals the workflow listens for
+pub fn signal_names(definition: &WorkflowDefinition) -> Vec<String> {
+    let mut signals: Vec<String> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::WaitSignals))
+        .filter_map(|n| n.typed_config::<WaitSignalsConfig>().ok())
+        .flat_map(|c| c.signals)
+        .collect();
+    signals.sort();
+    signals.dedup();
+    signals
+}
+
+/// Generate the fields of the workflow input struct from the declared variables
+pub fn generate_input_fields(definition: &WorkflowDefinition) -> String {
+    definition.variables.iter()
+        .map(|v| format!("    {} {} `json:\"{}\"`\n", to_pascal_case(&v.name), go_type(&v.var_type), v.name))
+        .collect()
+}
+
+/// Generate package-level helper functions required by node logic
+pub fn generate_workflow_helpers(definition: &WorkflowDefinition, workflow_name: &str) -> Result<String, CompilerError> {
+    let mut helpers = input_validation::generate_validator(definition, workflow_name);
+    if definition.nodes.iter().any(|n| matches!(n.node_type, NodeType::WaitSignals)) {
+        helpers.push_str(r#"// SignalPayload is the envelope expected on every correlated signal channel
+type SignalPayload struct {
+    CorrelationKey string `json:"correlation_key"`
+    Data           any    `json:"data,omitempty"`
+}
+
+"#);
+    }
+    for node in &definition.nodes {
+        if matches!(node.node_type, NodeType::DecisionTable) {
+            helpers.push_str(&decision_table::generate_function(node, workflow_name)?);
+        }
+    }
+
+    Ok(helpers)
+}
+
+/// Statements of the main workflow function
+pub struct WorkflowBody {
+    pub code: String,
+    /// Byte offset in `code` at which each node's statements begin, for nodes outside a fork,
+    /// scope, split or flag block
+    pub node_offsets: Vec<(String, usize)>,
+}
+
+impl WorkflowBody {
+    fn new(code: String) -> Self {
+        Self { code, node_offsets: vec![] }
+    }
+
+    /// Append another block, keeping its node offsets
+    fn push(&mut self, block: WorkflowBody) {
+        let base = self.code.len();
+        self.node_offsets.extend(block.node_offsets.into_iter().map(|(id, offset)| (id, base + offset)));
+        self.code.push_str(&block.code);
+    }
+
+    /// The block without trailing blank lines, indented by `spaces`; node offsets move with
+    /// their lines
+    fn indented(self, spaces: usize) -> WorkflowBody {
+        let code = indent(self.code.trim_end(), spaces);
+        let line_starts: Vec<usize> = std::iter::once(0).chain(code.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        let node_offsets = self.node_offsets.into_iter()
+            .map(|(id, offset)| {
+                let line = self.code.get(..offset).map_or(0, |before| before.matches('\n').count());
+                (id, line_starts.get(line).copied().unwrap_or(code.len()))
+            })
+            .collect();
+        WorkflowBody { code, node_offsets }
+    }
+}
+
+/// Comment lines for a node's annotations and incoming edge labels, at `pad` spaces
+fn note_comments(definition: &WorkflowDefinition, node: &WorkflowNode, pad: usize) -> String {
+    let pad = " ".repeat(pad);
+    source_map::notes(definition, node).iter().map(|note| format!("{pad}// {note}\n")).collect()
+}
+
+/// Activity call for a node emitted inside another node's block, with its notes
+fn branch_activity_call(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<String, CompilerError> {
+    let code = note_comments(definition, node, 4) + &generate_activity_call(node, "ctx", "return nil, err")?;
+    Ok(indent(code.trim_end(), 4))
+}
+
+/// Generate the statements of the main workflow function from the graph nodes in
+/// topological order; `traced` opens an execution report step before each node
+pub fn generate_workflow_body(definition: &WorkflowDefinition, traced: bool) -> Result<WorkflowBody, CompilerError> {
+    // Nodes started by a parallel gateway fork are emitted inside the gateway block
+    let mut nested: HashSet<&str> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::ParallelGateway))
+        .filter(|n| graph::outgoing_edges(definition, &n.id).count() > 1)
+        .flat_map(|n| graph::outgoing_edges(definition, &n.id).map(|e| e.target.as_str()))
+        .collect();
+    // Scope members and cancellation triggers are emitted inside the scope block
+    nested.extend(scoped_nodes(definition));
+    // Weighted split targets are emitted inside the split's switch
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::WeightedSplit)) {
+        let config: WeightedSplitConfig = node.typed_config()?;
+        nested.extend(config.branches.iter().filter_map(|b| graph::find_node(definition, &b.target)).map(|n| n.id.as_str()));
+    }
+    // Feature flag branches are emitted inside the flag's if/else
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::FeatureFlag)) {
+        let (on, off) = feature_flag::branches(definition, node)?;
+        nested.extend([on, off].into_iter().flatten().map(|n| n.id.as_str()));
+    }
+
+    let order = graph::topological_order(definition);
+    let mut builder = BodyBuilder {
+        definition,
+        traced,
+        position: order.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect(),
+        nested,
+        placed: HashSet::new(),
+    };
+    builder.sequence(&order, &[])
+}
+
+/// Emits workflow statements, nesting the nodes a Decision's branches run inside its if/else
+struct BodyBuilder<'a> {
+    definition: &'a WorkflowDefinition,
+    traced: bool,
+    /// Index of each node in topological order
+    position: HashMap<&'a str, usize>,
+    /// Nodes emitted inside a fork, scope, split or flag block
+    nested: HashSet<&'a str>,
+    placed: HashSet<&'a str>,
+}
+
+/// Nodes one branch of a Decision runs, in topological order, and the stop nodes it reaches
+struct Branch<'a> {
+    edge: &'a WorkflowEdge,
+    nodes: Vec<&'a WorkflowNode>,
+    reached: Vec<&'a str>,
+}
+
+impl<'a> BodyBuilder<'a> {
+    /// Statements for `nodes` in order, skipping those already placed or nested in another
+    /// block; `stops` are the nodes where the enclosing branches end
+    fn sequence(&mut self, nodes: &[&'a WorkflowNode], stops: &[&'a str]) -> Result<WorkflowBody, CompilerError> {
+        let definition = self.definition;
+        let mut body = WorkflowBody::new(String::new());
+        let mut open_session: Option<&str> = None;
+        for &node in nodes {
+            limits::checkpoint("code generation")?;
+            if self.nested.contains(node.id.as_str()) || !self.placed.insert(node.id.as_str()) {
+                continue;
+            }
+
+            // Consecutive nodes of one session group share a single worker session
+            let session = node.session.as_deref();
+            if session != open_session {
+                if open_session.is_some() {
+                    body.code.push_str(SESSION_CLOSE);
+                }
+                if let Some(name) = session {
+                    body.code.push_str(&generate_session_open(name));
+                }
+                open_session = session;
+            }
+
+            let block = match node.node_type {
+                NodeType::Decision => self.decision(node, stops)?,
+                NodeType::DecisionTable => self.decision_table(node, stops)?,
+                _ => WorkflowBody::new(match node.node_type {
+                    NodeType::ParallelGateway => generate_parallel_gateway(definition, node)?,
+                    NodeType::WaitSignals => generate_signal_wait(node)?,
+                    NodeType::DynamicActivity => generate_dynamic_activity(node)?,
+                    NodeType::SubWorkflow => generate_child_workflow(definition, node)?,
+                    NodeType::NexusOperation => generate_nexus_operation(node)?,
+                    NodeType::WaitTimer => generate_timer(node)?,
+                    NodeType::CancellationScope => generate_cancellation_scope(definition, node)?,
+                    NodeType::FeatureFlag => generate_feature_flag(definition, node)?,
+                    NodeType::WeightedSplit => generate_weighted_split(definition, node)?,
+                    _ if is_activity_node(node) && session.is_some() => {
+                        indent(&generate_activity_call(node, "sessionCtx", "return nil, err")?, 4)
+                    }
+                    _ if is_activity_node(node) => generate_activity_call(node, "ctx", "return nil, err")?,
+                    _ => continue,
+                }),
+            };
+            if !block.code.is_empty() {
+                body.node_offsets.push((node.id.clone(), body.code.len()));
+            }
+            if self.traced {
+                body.code.push_str(&report::generate_step(&node.id, &node.label));
+            }
+            if !block.code.is_empty() {
+                body.code.push_str(&note_comments(definition, node, if session.is_some() { 8 } else { 4 }));
+            }
+            body.push(block);
+        }
+        if open_session.is_some() {
+            body.code.push_str(SESSION_CLOSE);
+        }
+
+        Ok(body)
+    }
+
+    /// Nodes a branch runs from its first node until it reaches one of `stops` or an End
+    /// node. A node the branch runs must not also be entered from outside it, or running it
+    /// inside the branch would skip it on the other paths there.
+    fn branch(&self, decision: &WorkflowNode, edge: &'a WorkflowEdge, stops: &[&'a str]) -> Result<Branch<'a>, CompilerError> {
+        let definition = self.definition;
+        let (nodes, reached) = branch_nodes(definition, edge, stops);
+        let mut branch = Branch { edge, nodes, reached };
+        let inside: HashSet<&str> = branch.nodes.iter().map(|n| n.id.as_str()).collect();
+        for node in &branch.nodes {
+            let outside = definition.edges.iter()
+                .filter(|e| e.target == node.id && e.kind == EdgeKind::Flow)
+                .find(|e| e.source != decision.id && !inside.contains(e.source.as_str()));
+            if let Some(outside) = outside {
+                return Err(CompilerError::CodeGenError(format!(
+                    "Node '{}' runs in the branch of Decision '{}' along edge '{}' but is also entered from '{}'; route the paths through the node where they merge",
+                    node.id, decision.id, edge.id, outside.source
+                )));
+            }
+        }
+        branch.nodes.sort_by_key(|n| self.position.get(n.id.as_str()).copied().unwrap_or(usize::MAX));
+        Ok(branch)
+    }
+
+    /// Statements a branch runs, indented into the decision's block, ending with `exit`
+    fn branch_block(&mut self, branch: &Branch<'a>, stops: &[&'a str], exit: Option<&str>) -> Result<WorkflowBody, CompilerError> {
+        let mut block = self.sequence(&branch.nodes, stops)?.indented(4);
+        if block.code.is_empty() && exit.is_none() {
+            block.code = format!("        // continues at '{}'\n", branch.edge.target);
+        }
+        if let Some(exit) = exit {
+            block.code.push_str(&format!("        {exit}\n"));
+        }
+        Ok(block)
+    }
+
+    /// Block for the branch along `edge`, which must end at `rejoin`, where the other branches
+    /// merge or, `looping`, loop back. Reaching none of `stops` it ends at an End node, so it
+    /// returns from the workflow when code follows the decision.
+    fn branch_to(
+        &mut self,
+        node: &'a WorkflowNode,
+        edge: &'a WorkflowEdge,
+        inner: &[&'a str],
+        rejoin: Option<&'a str>,
+        looping: bool,
+        stops: &[&'a str],
+    ) -> Result<WorkflowBody, CompilerError> {
+        let branch = self.branch(node, edge, inner)?;
+        if let Some((stop, rejoin)) = rejoin.and_then(|r| branch.reached.iter().find(|s| **s != r).map(|s| (s, r))) {
+            return Err(CompilerError::CodeGenError(format!(
+                "Node '{}' branch along edge '{}' continues at '{}' without passing '{}', where its other branches {}",
+                node.id,
+                edge.id,
+                stop,
+                rejoin,
+                if looping { "loop back" } else { "merge" }
+            )));
+        }
+        let workflow_return = format!(
+            "return &{}Output{{Success: true, Message: \"Workflow completed successfully\"}}, nil",
+            to_pascal_case(&self.definition.name)
+        );
+        let exit = if looping {
+            Some("continue")
+        } else if branch.reached.is_empty() && (rejoin.is_some() || !stops.is_empty()) {
+            Some(workflow_return.as_str())
+        } else {
+            None
+        };
+        self.branch_block(&branch, inner, exit)
+    }
+
+    /// Evaluate the table, then switch on its outcome to run the branch of the edge naming
+    /// it, or of the else edge, up to the node where the branches merge
+    fn decision_table(&mut self, node: &'a WorkflowNode, stops: &[&'a str]) -> Result<WorkflowBody, CompilerError> {
+        let definition = self.definition;
+        let mut code = WorkflowBody::new(generate_decision_table_call(node));
+        let edges: Vec<&'a WorkflowEdge> = graph::outgoing_edges(definition, &node.id).filter(|e| e.kind == EdgeKind::Flow).collect();
+        if edges.len() < 2 {
+            return Ok(code);
+        }
+        let merge = graph::merge_point(definition, node).map(|m| m.id.as_str());
+        let mut inner = stops.to_vec();
+        inner.extend(merge);
+
+        code.code.truncate(code.code.trim_end().len());
+        code.code.push_str(&format!("\n    switch {} {{\n", decision_table_outcome(node)));
+        let (named, default): (Vec<_>, Vec<_>) = edges.into_iter().partition(|e| decision_table::edge_output(e).is_some());
+        for edge in named.into_iter().chain(default) {
+            let case = match decision_table::edge_output(edge) {
+                Some(output) => format!("case {}:", go_string_literal(output)),
+                None => "default:".to_string(),
+            };
+            code.code.push_str(&format!("    {case}\n"));
+            code.push(self.branch_to(node, edge, &inner, merge, false, stops)?);
+        }
+        code.code.push_str("    }\n\n");
+        Ok(code)
+    }
+
+    /// Test the conditional edges in order and run the first branch whose condition holds,
+    /// or the default edge's branch. Each branch runs up to the node where the branches
+    /// merge, or returns from the workflow at an End node when code follows the decision.
+    /// A loop-marked Decision repeats inside a `for` until its one exit edge is taken.
+    fn decision(&mut self, node: &'a WorkflowNode, stops: &[&'a str]) -> Result<WorkflowBody, CompilerError> {
+        let definition = self.definition;
+        let edges: Vec<&'a WorkflowEdge> = graph::outgoing_edges(definition, &node.id).filter(|e| e.kind == EdgeKind::Flow).collect();
+        let condition = |e: &WorkflowEdge| e.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()).map(str::to_string);
+        if !edges.iter().any(|e| condition(e).is_some()) {
+            return Ok(WorkflowBody::new(String::new()));
+        }
+        let looping = is_loop_head(node);
+        let loops_back = |e: &WorkflowEdge| loops_back(definition, node, e);
+        if looping {
+            let exits: Vec<&str> = edges.iter().filter(|e| !loops_back(e)).map(|e| e.id.as_str()).collect();
+            if exits.len() > 1 {
+                return Err(CompilerError::CodeGenError(format!(
+                    "Loop Decision '{}' leaves the loop along more than one edge: {}; merge the exits first",
+                    node.id,
+                    exits.join(", ")
+                )));
+            }
+        }
+        let merge = if looping { None } else { graph::merge_point(definition, node).map(|m| m.id.as_str()) };
+        let mut inner = stops.to_vec();
+        inner.extend(branch_stops(definition, node));
+
+        let mut branches = vec![];
+        for &edge in &edges {
+            if looping && !loops_back(edge) {
+                branches.push((edge, WorkflowBody::new("        break\n".to_string())));
+                continue;
+            }
+            let loop_head = if looping { Some(node.id.as_str()) } else { None };
+            branches.push((edge, self.branch_to(node, edge, &inner, merge.or(loop_head), looping, stops)?));
+        }
+
+        let (conditional, default): (Vec<_>, Vec<_>) = branches.into_iter().partition(|(e, _)| condition(e).is_some());
+        let mut chain = WorkflowBody::new(String::new());
+        for (i, (edge, block)) in conditional.into_iter().enumerate() {
+            let condition = condition(edge).unwrap_or_default();
+            let expr = condition::parse(&condition)
+                .map_err(|e| CompilerError::CodeGenError(format!("Condition '{}' on edge '{}' is invalid: {}", condition, edge.id, e)))?;
+            let go = GoCondition::new(definition).render(&expr)
+                .map_err(|reason| CompilerError::CodeGenError(format!("Condition '{}' on edge '{}' cannot be expressed in Go: {}", condition, edge.id, reason)))?;
+            chain.code.push_str(&format!("    {} {} {{\n", if i == 0 { "if" } else { "} else if" }, go));
+            chain.push(block);
+        }
+        let has_default = !default.is_empty();
+        if let Some((_, block)) = default.into_iter().next() {
+            chain.code.push_str("    } else {\n");
+            chain.push(block);
+        }
+        chain.code.push_str("    }\n");
+
+        let mut code = WorkflowBody::new(format!("    // Decision: {}\n", node.label));
+        if looping {
+            // With no default edge a pass matching no condition leaves the loop
+            if !has_default {
+                chain.code.push_str("    break\n");
+            }
+            code.code.push_str("    for {\n");
+            code.push(chain.indented(4));
+            code.code.push_str("    }\n\n");
+        } else {
+            code.push(chain);
+            code.code.push('\n');
+        }
+        Ok(code)
+    }
+}
+
+/// Whether any node runs inside a worker session
+pub fn uses_sessions(definition: &WorkflowDefinition) -> bool {
+    definition.nodes.iter().any(|n| n.session.is_some())
+}
+
+/// Generate the `worker.Options` literal for the generated worker
+pub fn generate_worker_options(definition: &WorkflowDefinition, build_id: Option<&str>) -> String {
+    let mut fields = Vec::new();
+    if uses_sessions(definition) {
+        fields.push("EnableSessionWorker: true".to_string());
+    }
+    if let Some(id) = build_id {
+        fields.push(format!("BuildID: {}", go_string_literal(id)));
+        fields.push("UseBuildIDForVersioning: true".to_string());
+    }
+
+    if fields.is_empty() {
+        "worker.Options{}".to_string()
+    } else {
+        let fields: String = fields.iter().map(|f| format!("        {f},\n")).collect();
+        format!("worker.Options{{\n{fields}    }}")
+    }
+}
+
+const SESSION_CLOSE: &str = "        workflow.CompleteSession(sessionCtx)\n    }\n\n";
+
+fn generate_session_open(name: &str) -> String {
+    format!(r#"    // Worker session: {name}
+    {{
+        sessionCtx, err := workflow.CreateSession(ctx, &workflow.SessionOptions{{
+            CreationTimeout:  time.Minute,
+            ExecutionTimeout: 30 * time.Minute,
+        }})
+        if err != nil {{
+            logger.Error("Failed to create worker session", "session", "{name}", "error", err)
+            return nil, err
+        }}
+
+"#)
+}
+
+/// Indent every non-empty line of a generated block
+pub fn indent(code: &str, spaces: usize) -> String {
+    let pad = " ".repeat(spaces);
+    code.lines()
+        .map(|l| if l.is_empty() { "\n".to_string() } else { format!("{pad}{l}\n") })
+        .collect()
+}
+
+/// Execute an activity node; `fail` is the statement run on error
+/// Activity context for a node, applying its modeled timeouts and retry policy if it has them
+fn activity_context(node: &WorkflowNode, ctx: &str) -> Result<String, CompilerError> {
+    let mut ctx = ctx.to_string();
+    if let Some(timeouts) = &node.timeouts {
+        let options = [
+            ("WithStartToCloseTimeout", "timeouts.start_to_close", &timeouts.start_to_close),
+            ("WithScheduleToCloseTimeout", "timeouts.schedule_to_close", &timeouts.schedule_to_close),
+            ("WithHeartbeatTimeout", "timeouts.heartbeat", &timeouts.heartbeat),
+        ];
+        for (option, field, raw) in options {
+            if let Some(raw) = raw {
+                ctx = format!("workflow.{}({}, {})", option, ctx, duration::go_expr_for_field(raw, field, Some(&node.id))?);
+            }
+        }
+    }
+    let Some(retries) = &node.retries else {
+        return Ok(ctx);
+    };
+    let initial = duration::go_expr_for_field(&retries.initial_interval, "retries.initial_interval", Some(&node.id))?;
+    let max = duration::go_expr_for_field(&retries.max_interval, "retries.max_interval", Some(&node.id))?;
+
+    Ok(format!(
+        "workflow.WithRetryPolicy({ctx}, temporal.RetryPolicy{{InitialInterval: {initial}, BackoffCoefficient: {}, MaximumInterval: {max}, MaximumAttempts: {}}})",
+        retries.backoff_coefficient, retries.max_attempts
+    ))
+}
+
+fn generate_activity_call(node: &WorkflowNode, ctx: &str, fail: &str) -> Result<String, CompilerError> {
+    let activity = activity_name(node);
+    let label = &node.label;
+    let activity_ctx = activity_context(node, ctx)?;
+
+    Ok(format!(r#"    // {label}
+    if err := workflow.ExecuteActivity({activity_ctx}, "{activity}", input).Get({ctx}, nil); err != nil {{
+        logger.Error("{label} failed", "error", err)
+        {fail}
+    }}
+
+"#))
+}
+
+/// Go variable holding a decision table's outcome
+fn decision_table_outcome(node: &WorkflowNode) -> String {
+    format!("{}Outcome", to_camel_case(&node.label))
+}
+
+fn generate_decision_table_call(node: &WorkflowNode) -> String {
+    let function = decision_table::function_name(node);
+    let label = &node.label;
+    let outcome = decision_table_outcome(node);
+
+    format!(r#"    // Decision table: {label}
+    {outcome} := {function}(input)
+    logger.Info("{label} evaluated", "outcome", {outcome})
+
+"#)
+}
+
+/// Emit a selector over one channel per signal, optionally raced against a timeout timer.
+/// Payloads whose correlation key does not match the workflow variable are ignored.
+fn generate_signal_wait(node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: WaitSignalsConfig = node.typed_config()?;
+    let label = &node.label;
+    let required = match config.mode {
+        SignalWaitMode::All => config.signals.len(),
+        SignalWaitMode::Any => 1,
+    };
+    let mode = match config.mode {
+        SignalWaitMode::All => "all",
+        SignalWaitMode::Any => "any",
+    };
+
+    let correlation = match &config.correlation_key {
+        Some(key) => format!(r#"
+            if payload.CorrelationKey != input.{} {{
+                return
+            }}"#, to_pascal_case(key)),
+        None => String::new(),
+    };
+
+    let mut receivers = String::new();
+    for signal in &config.signals {
+        let name = go_string_literal(signal);
+        receivers.push_str(&format!(r#"        selector.AddReceive(workflow.GetSignalChannel(ctx, {name}), func(c workflow.ReceiveChannel, more bool) {{
+            var payload SignalPayload
+            c.Receive(ctx, &payload){correlation}
+            received[{name}] = true
+        }})
+"#));
+    }
+
+    let (timer, timer_cancel, timed_out) = match &config.timeout {
+        Some(timeout) => {
+            let duration = duration::go_expr_for_field(timeout, "timeout", Some(&node.id))?;
+            (
+                format!(r#"        timedOut := false
+        timerCtx, cancelTimer := workflow.WithCancel(ctx)
+        selector.AddFuture(workflow.NewTimer(timerCtx, {duration}), func(f workflow.Future) {{
+            timedOut = true
+        }})
+"#),
+                "        cancelTimer()
+",
+                " && !timedOut",
+            )
+        }
+        None => (String::new(), "", ""),
+    };
+
+    Ok(format!(r#"    // Wait for signals: {label} ({mode} of {count})
+    {{
+        received := map[string]bool{{}}
+        selector := workflow.NewSelector(ctx)
+{receivers}{timer}        for len(received) < {required}{timed_out} {{
+            selector.Select(ctx)
+        }}
+{timer_cancel}        if len(received) < {required} {{
+            return nil, temporal.NewApplicationError("{label} timed out waiting for signals", "SignalTimeout")
+        }}
+    }}
+
+"#, count = config.signals.len()))
+}
+
+/// Emit string-based activity dispatch guarded by the compile-time allowlist,
+/// so a tampered input can never reach an activity outside the modeled set.
+fn generate_dynamic_activity(node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: DynamicActivityConfig = node.typed_config()?;
+    let label = &node.label;
+    let selector = to_pascal_case(&config.selector);
+    let activity_ctx = activity_context(node, "ctx")?;
+    let allowed = config.allowed.iter()
+        .map(|a| format!("{}: true", go_string_literal(a)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!(r#"    // Dynamic activity: {label}
+    {{
+        allowed := map[string]bool{{{allowed}}}
+        activityName := input.{selector}
+        if !allowed[activityName] {{
+            return nil, temporal.NewApplicationError("{label}: activity not allowed: "+activityName, "ActivityNotAllowed")
+        }}
+        if err := workflow.ExecuteActivity({activity_ctx}, activityName, input).Get(ctx, nil); err != nil {{
+            logger.Error("{label} failed", "activity", activityName, "error", err)
+            return nil, err
+        }}
+    }}
+
+"#))
+}
+
+/// Evaluate the flag through the generated activity, falling back to the modeled default
+/// if the provider is unreachable, then run the matching branch.
+fn generate_feature_flag(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: FeatureFlagConfig = node.typed_config()?;
+    let label = &node.label;
+    let enabled = format!("{}On", to_camel_case(&node.label));
+    let provider = match config.provider {
+        FlagProvider::Static => "static",
+        FlagProvider::Launchdarkly => "launchdarkly",
+        FlagProvider::Unleash => "unleash",
+    };
+    let flag = go_string_literal(&config.flag);
+    let context = match &config.context {
+        Some(key) => format!("input.{}", to_pascal_case(key)),
+        None => "\"\"".to_string(),
+    };
+    let default = config.default;
+    let activity = feature_flag::ACTIVITY;
+
+    let (on, off) = feature_flag::branches(definition, node)?;
+    let branch = |target: Option<&WorkflowNode>| -> Result<String, CompilerError> {
+        match target {
+            Some(target) => branch_activity_call(definition, target),
+            None => Ok(String::new()),
+        }
+    };
+    let (on, off) = (branch(on)?, branch(off)?);
+
+    Ok(format!(r#"    // Feature flag: {label} ({raw})
+    {enabled} := {default}
+    if err := workflow.ExecuteActivity(ctx, "{activity}", FeatureFlagRequest{{
+        Provider: "{provider}",
+        Flag:     {flag},
+        Context:  {context},
+        Default:  {default},
+    }}).Get(ctx, &{enabled}); err != nil {{
+        logger.Warn("{label} evaluation failed, using default", "flag", {flag}, "error", err)
+        {enabled} = {default}
+    }}
+    if {enabled} {{
+{on}    }} else {{
+{off}    }}
+
+"#, raw = config.flag))
+}
+
+/// Whether following `edge` out of `node` leads back to it
+fn loops_back(definition: &WorkflowDefinition, node: &WorkflowNode, edge: &WorkflowEdge) -> bool {
+    graph::find_node(definition, &edge.target).is_some_and(|t| graph::reachable_from(definition, t).iter().any(|n| n.id == node.id))
+}
+
+/// Nodes where the branches of a Decision end: the node where they merge, or for a
+/// loop-marked Decision the Decision itself and the targets of its exit edges
+fn branch_stops<'a>(definition: &'a WorkflowDefinition, node: &'a WorkflowNode) -> Vec<&'a str> {
+    if !is_loop_head(node) {
+        return graph::merge_point(definition, node).map(|m| m.id.as_str()).into_iter().collect();
+    }
+    let exits = graph::outgoing_edges(definition, &node.id)
+        .filter(|e| e.kind == EdgeKind::Flow && !loops_back(definition, node, e))
+        .map(|e| e.target.as_str());
+    std::iter::once(node.id.as_str()).chain(exits).collect()
+}
+
+/// Nodes a branch runs from the target of `edge` until it reaches one of `stops` or an End
+/// node, and the stops it reaches
+fn branch_nodes<'a>(definition: &'a WorkflowDefinition, edge: &WorkflowEdge, stops: &[&'a str]) -> (Vec<&'a WorkflowNode>, Vec<&'a str>) {
+    let (mut nodes, mut reached) = (vec![], vec![]);
+    let mut pending: Vec<&'a WorkflowNode> = graph::find_node(definition, &edge.target).into_iter().collect();
+    let mut seen = HashSet::new();
+    while let Some(node) = pending.pop() {
+        if let Some(stop) = stops.iter().find(|s| **s == node.id) {
+            reached.push(*stop);
+            continue;
+        }
+        if matches!(node.node_type, NodeType::End) || !seen.insert(node.id.as_str()) {
+            continue;
+        }
+        nodes.push(node);
+        pending.extend(graph::flow_targets(definition, node));
+    }
+    (nodes, reached)
+}
+
+/// Whether a node only runs when a Decision or decision table selects its branch, or as often
+/// as a loop-marked Decision repeats
+pub fn is_decision_branch(definition: &WorkflowDefinition, node: &WorkflowNode) -> bool {
+    definition.nodes.iter()
+        .filter(|d| matches!(d.node_type, NodeType::Decision | NodeType::DecisionTable))
+        .any(|decision| {
+            let stops = branch_stops(definition, decision);
+            graph::outgoing_edges(definition, &decision.id)
+                .filter(|e| e.kind == EdgeKind::Flow && (!is_loop_head(decision) || loops_back(definition, decision, e)))
+                .any(|e| branch_nodes(definition, e, &stops).0.iter().any(|n| n.id == node.id))
+        })
+}
+
+/// Go operand rendered from a condition, with its declared type; `dynamic` when its Go type
+/// is `any` and comparing it with a concrete value needs a type assertion
+struct GoOperand {
+    code: String,
+    var_type: VarType,
+    dynamic: bool,
+}
+
+/// Renders parsed conditions as Go boolean expressions over the workflow input
+struct GoCondition<'a> {
+    variables: Vec<(&'a Variable, VarType)>,
+}
+
+impl<'a> GoCondition<'a> {
+    fn new(definition: &'a WorkflowDefinition) -> Self {
+        let variables = definition.variables.iter().map(|v| (v, VarType::parse(&v.var_type).unwrap_or(VarType::Any))).collect();
+        Self { variables }
+    }
+
+    /// Go type a dynamic value of this type is asserted to; JSON numbers decode as `float64`
+    fn assertion(var_type: &VarType) -> Option<&'static str> {
+        match var_type {
+            VarType::String => Some("string"),
+            VarType::Int | VarType::Float => Some("float64"),
+            VarType::Bool => Some("bool"),
+            VarType::Object(_) => Some("map[string]any"),
+            VarType::Array(_) => Some("[]any"),
+            VarType::Any => None,
+        }
+    }
+
+    fn render(&self, expr: &Expr) -> Result<String, String> {
+        match expr {
+            Expr::Or(lhs, rhs) => Ok(format!("{} || {}", self.render(lhs)?, self.render(rhs)?)),
+            Expr::And(lhs, rhs) => Ok(format!("{} && {}", self.grouped(lhs, true)?, self.grouped(rhs, true)?)),
+            Expr::Not(inner) => Ok(format!("!{}", self.grouped(inner, false)?)),
+            Expr::Compare(lhs, op, rhs) => {
+                let (mut a, mut b) = (self.operand(lhs)?, self.operand(rhs)?);
+                let null = matches!(**lhs, Expr::Literal(Literal::Null)) || matches!(**rhs, Expr::Literal(Literal::Null));
+                if !null {
+                    let types = (a.var_type.clone(), b.var_type.clone());
+                    for (side, other) in [(&mut a, &types.1), (&mut b, &types.0)] {
+                        if !side.dynamic {
+                            continue;
+                        }
+                        match Self::assertion(&side.var_type).or_else(|| Self::assertion(other)) {
+                            Some(go_type) => {
+                                side.code = format!("{}.({})", side.code, go_type);
+                                side.dynamic = false;
+                                if go_type == "float64" {
+                                    side.var_type = VarType::Float;
+                                }
+                            }
+                            None if op.is_ordering() => return Err(format!("'{}' needs a declared type to be ordered", side.code)),
+                            None => {}
+                        }
+                    }
+                }
+                // Go compares int64 with float64 only after a conversion, and with integral constants only
+                let fractional = |e: &Expr| matches!(e, Expr::Literal(Literal::Number(n)) if n.parse::<f64>().is_ok_and(|n| n.fract() != 0.0));
+                let types = (a.var_type.clone(), b.var_type.clone());
+                for (side, own, other, other_expr) in [(&mut a, &**lhs, &types.1, &**rhs), (&mut b, &**rhs, &types.0, &**lhs)] {
+                    let literal = |e: &Expr| matches!(e, Expr::Literal(_));
+                    let widen = (*other == VarType::Float && !literal(other_expr)) || fractional(other_expr);
+                    if !side.dynamic && !literal(own) && side.var_type == VarType::Int && widen {
+                        side.code = format!("float64({})", side.code);
+                    }
+                }
+                Ok(format!("{} {} {}", a.code, op.as_str(), b.code))
+            }
+            Expr::Variable { .. } | Expr::Literal(_) => {
+                let operand = self.operand(expr)?;
+                Ok(if operand.dynamic { format!("{}.(bool)", operand.code) } else { operand.code })
+            }
+        }
+    }
+
+    /// Render an operand of `&&` or `!`, parenthesizing `||`, and under `!` any comparison
+    fn grouped(&self, expr: &Expr, and: bool) -> Result<String, String> {
+        let rendered = self.render(expr)?;
+        Ok(match expr {
+            Expr::Or(..) => format!("({})", rendered),
+            Expr::And(..) | Expr::Compare(..) if !and => format!("({})", rendered),
+            _ => rendered,
+        })
+    }
+
+    fn operand(&self, expr: &Expr) -> Result<GoOperand, String> {
+        Ok(match expr {
+            Expr::Variable { name, fields } => {
+                let (variable, _) = self.variables.iter()
+                    .find(|(v, _)| v.name == *name || to_pascal_case(&v.name) == *name)
+                    .ok_or_else(|| format!("'{}' is not a workflow variable", name))?;
+                let mut code = format!("input.{}", to_pascal_case(&variable.name));
+                let mut dynamic = go_type(&variable.var_type) == "any";
+                for field in fields {
+                    if dynamic {
+                        code.push_str(".(map[string]any)");
+                    }
+                    code.push_str(&format!("[{}]", go_string_literal(field)));
+                    dynamic = true;
+                }
+                let var_type = types::path_type(&self.variables, name, fields).and_then(Result::ok).unwrap_or(VarType::Any);
+                GoOperand { code, var_type, dynamic }
+            }
+            Expr::Literal(literal) => GoOperand {
+                code: match literal {
+                    Literal::String(s) => go_string_literal(s),
+                    Literal::Number(n) => n.clone(),
+                    Literal::Bool(b) => b.to_string(),
+                    Literal::Null => "nil".to_string(),
+                },
+                var_type: types::literal_type(literal),
+                dynamic: false,
+            },
+            other => GoOperand { code: format!("({})", self.render(other)?), var_type: VarType::Bool, dynamic: false },
+        })
+    }
+}
+
+/// Draw a bucket in [0, 100) inside a side effect, so replays reuse the recorded value,
+/// and run the branch whose cumulative weight range contains it.
+fn generate_weighted_split(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: WeightedSplitConfig = node.typed_config()?;
+    let label = &node.label;
+    let bucket = format!("{}Bucket", to_camel_case(&node.label));
+    let weights = config.branches.iter().map(|b| b.weight.to_string()).collect::<Vec<_>>().join("/");
+
+    let mut cases = String::new();
+    let mut upper = 0;
+    for (i, branch) in config.branches.iter().enumerate() {
+        let target = graph::find_node(definition, &branch.target).ok_or_else(|| {
+            CompilerError::CodeGenError(format!("WeightedSplit node '{}' target '{}' not found", node.id, branch.target))
+        })?;
+        upper += branch.weight;
+        let case = if i + 1 == config.branches.len() {
+            "default:".to_string()
+        } else {
+            format!("case {bucket} < {upper}:")
+        };
+        let call = branch_activity_call(definition, target)?;
+        cases.push_str(&format!("    {case}\n{call}"));
+    }
+
+    Ok(format!(r#"    // Weighted split: {label} ({weights})
+    var {bucket} int
+    if err := workflow.SideEffect(ctx, func(ctx workflow.Context) any {{
+        return rand.Intn(100)
+    }}).Get(&{bucket}); err != nil {{
+        return nil, err
+    }}
+    logger.Info("{label} routed", "bucket", {bucket})
+    switch {{
+{cases}    }}
+
+"#))
+}
+
+fn generate_timer(node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: WaitTimerConfig = node.typed_config()?;
+    let duration = duration::go_expr_for_field(&config.duration, "duration", Some(&node.id))?;
+    let label = &node.label;
+
+    Ok(format!(r#"    // {label}: wait {raw}
+    if err := workflow.Sleep(ctx, {duration}); err != nil {{
+        return nil, err
+    }}
+
+"#, raw = config.duration))
+}
+
+/// Emit child workflow execution with close policy and cancellation behaviour applied, passing
+/// the mapped input variables
+fn generate_child_workflow(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: SubWorkflowConfig = node.typed_config()?;
+    let label = &node.label;
+    let child = go_string_literal(&config.workflow);
+    let node_id = &node.id;
+
+    let close_policy = match config.parent_close_policy {
+        ParentClosePolicy::Terminate => "enumspb.PARENT_CLOSE_POLICY_TERMINATE",
+        ParentClosePolicy::RequestCancel => "enumspb.PARENT_CLOSE_POLICY_REQUEST_CANCEL",
+        ParentClosePolicy::Abandon => "enumspb.PARENT_CLOSE_POLICY_ABANDON",
+    };
+    let wait_for_cancellation = config.cancellation_type == ChildCancellationType::WaitCancellationCompleted;
+    // Abandoned children run in a disconnected context so parent cancellation never reaches them
+    let parent_ctx = if config.cancellation_type == ChildCancellationType::Abandon {
+        "workflow.NewDisconnectedContext(ctx)"
+    } else {
+        "ctx"
+    };
+    let namespace = config.namespace.as_deref()
+        .map(|ns| format!("\n            Namespace:           {},", go_string_literal(ns)))
+        .unwrap_or_default();
+    let task_queue = config.task_queue.as_deref()
+        .map(|q| format!("\n            TaskQueue:           {},", go_string_literal(q)))
+        .unwrap_or_default();
+    let wait = if config.wait_for_completion {
+        "childFuture.Get(childCtx, nil)"
+    } else {
+        "childFuture.GetChildWorkflowExecution().Get(childCtx, nil)"
+    };
+    let mut args = String::new();
+    if !config.input.is_empty() {
+        args.push_str(", map[string]interface{}{\n");
+        for (name, value) in &config.input {
+            let expr = child_input_expr(definition, value).ok_or_else(|| CompilerError::CodeGenError(format!(
+                "SubWorkflow node '{}' input '{}' is neither a literal nor a single variable reference",
+                node.id, name
+            )))?;
+            args.push_str(&format!("            {}: {},\n", go_string_literal(name), expr));
+        }
+        args.push_str("        }");
+    }
+
+    Ok(format!(r#"    // Child workflow: {label}
+    {{
+        childCtx := workflow.WithChildOptions({parent_ctx}, workflow.ChildWorkflowOptions{{
+            WorkflowID:          workflow.GetInfo(ctx).WorkflowExecution.ID + "-{node_id}",{namespace}{task_queue}
+            ParentClosePolicy:   {close_policy},
+            WaitForCancellation: {wait_for_cancellation},
+        }})
+        childFuture := workflow.ExecuteChildWorkflow(childCtx, {child}{args})
+        if err := {wait}; err != nil {{
+            logger.Error("{label} failed", "error", err)
+            return nil, err
+        }}
+    }}
+
+"#))
+}
+
+/// Emit a Nexus operation call through a client bound to the node's endpoint and service
+fn generate_nexus_operation(node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: NexusOperationConfig = node.typed_config()?;
+    let label = &node.label;
+    let endpoint = go_string_literal(&config.endpoint);
+    let service = go_string_literal(&config.service);
+    let operation = go_string_literal(&config.operation);
+    let result = format!("{}Result", to_camel_case(&node.label));
+    let timeout = match &config.schedule_to_close_timeout {
+        Some(raw) => {
+            let duration = duration::go_expr_for_field(raw, "schedule_to_close_timeout", Some(&node.id))?;
+            format!("\n            ScheduleToCloseTimeout: {duration},\n        ")
+        }
+        None => String::new(),
+    };
+
+    Ok(format!(r#"    // Nexus operation: {label}
+    var {result} any
+    {{
+        nexusClient := workflow.NewNexusClient({endpoint}, {service})
+        nexusFuture := nexusClient.ExecuteOperation(ctx, {operation}, input, workflow.NexusOperationOptions{{{timeout}}})
+        if err := nexusFuture.Get(ctx, &{result}); err != nil {{
+            logger.Error("{label} failed", "error", err)
+            return nil, err
+        }}
+    }}
+    logger.Info("{label} completed", "result", {result})
+
+"#))
+}
+
+/// Node IDs emitted inside a cancellation scope: members and cancel-edge triggers
+fn scoped_nodes(definition: &WorkflowDefinition) -> Vec<&str> {
+    let mut nodes: Vec<&str> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::CancellationScope))
+        .filter_map(|n| n.typed_config::<CancellationScopeConfig>().ok())
+        .flat_map(|c| c.members)
+        .filter_map(|id| graph::find_node(definition, &id).map(|n| n.id.as_str()))
+        .collect();
+    nodes.extend(definition.edges.iter()
+        .filter(|e| e.kind == EdgeKind::Cancel)
+        .map(|e| e.source.as_str()));
+    nodes
+}
+
+/// Emit the scope's members in a cancellable context. Each cancel edge source runs in a
+/// watcher coroutine; when it completes the scope is cancelled and the workflow continues.
+fn generate_cancellation_scope(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<String, CompilerError> {
+    let config: CancellationScopeConfig = node.typed_config()?;
+    let label = &node.label;
+
+    let mut watchers = String::new();
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Cancel && e.target == node.id) {
+        let Some(trigger) = graph::find_node(definition, &edge.source) else { continue };
+        let wait = if matches!(trigger.node_type, NodeType::WaitSignal) {
+            let signal = go_string_literal(&trigger.typed_config::<WaitSignalConfig>()?.signal);
+            format!("workflow.GetSignalChannel(gCtx, {signal}).Receive(gCtx, nil)\n")
+        } else if is_activity_node(trigger) {
+            format!(
+                "if err := workflow.ExecuteActivity({}, \"{}\", input).Get(gCtx, nil); err != nil {{\n                return\n            }}\n",
+                activity_context(trigger, "gCtx")?,
+                activity_name(trigger)
+            )
+        } else {
+            return Err(CompilerError::CodeGenError(format!(
+                "Cancel edge '{}' must start at a WaitSignal or activity node",
+                edge.id
+            )));
+        };
+        watchers.push_str(&format!(r#"        workflow.Go(watchCtx, func(gCtx workflow.Context) {{
+            {wait}            logger.Info("{label} cancelled", "trigger", "{trigger_id}")
+            cancelScope()
+        }})
+"#, trigger_id = trigger.id));
+    }
+
+    let mut members = String::new();
+    for id in &config.members {
+        if let Some(member) = graph::find_node(definition, id) {
+            members.push_str(&indent(&(note_comments(definition, member, 4) + &generate_activity_call(member, "ctx", "return err")?), 8));
+        }
+    }
+
+    Ok(format!(r#"    // Cancellation scope: {label}
+    {{
+        scopeCtx, cancelScope := workflow.WithCancel(ctx)
+        watchCtx, stopWatching := workflow.WithCancel(ctx)
+{watchers}        err := func(ctx workflow.Context) error {{
+{members}            return nil
+        }}(scopeCtx)
+        stopWatching()
+        cancelScope()
+        if err != nil && !temporal.IsCanceledError(err) {{
+            return nil, err
+        }}
+    }}
+
+"#))
+}
+
+fn to_camel_case(s: &str) -> String {
+    let pascal = to_pascal_case(s);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => c.to_lowercase().chain(chars).collect(),
+    }
+}
+
+/// Emit a selector over the branch futures that completes according to the join policy.
+/// Branches still running once the policy is satisfied (or can no longer be) are cancelled.
+/// Number of branches that must succeed for a fork to join
+fn required_branches(join: &JoinPolicy, total: u32) -> u32 {
+    match join {
+        JoinPolicy::All => total,
+        JoinPolicy::Any => 1,
+        JoinPolicy::NOfM(n) => *n,
+    }
+}
+
+/// Whether a single failure of this activity node leaves the workflow running: it is a
+/// fork branch whose join tolerates failures, or it only triggers a cancellation scope
+pub fn failure_is_tolerated(definition: &WorkflowDefinition, node: &WorkflowNode) -> bool {
+    let tolerated_branch = definition.nodes.iter()
+        .filter(|g| matches!(g.node_type, NodeType::ParallelGateway))
+        .any(|gateway| {
+            let targets: Vec<&str> = graph::outgoing_edges(definition, &gateway.id).map(|e| e.target.as_str()).collect();
+            let total = targets.len() as u32;
+            total > 1
+                && targets.contains(&node.id.as_str())
+                && gateway.typed_config::<ParallelGatewayConfig>()
+                    .is_ok_and(|c| required_branches(&c.join, total) < total)
+        });
+    let cancel_trigger = definition.edges.iter().any(|e| e.kind == EdgeKind::Cancel && e.source == node.id);
+
+    tolerated_branch || cancel_trigger
+}
+
+fn generate_parallel_gateway(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<String, CompilerError> {
+    let branches: Vec<&WorkflowNode> = graph::outgoing_edges(definition, &node.id)
+        .filter_map(|e| graph::find_node(definition, &e.target))
+        .collect();
+
+    // A gateway with a single outgoing edge is a join; its semantics are emitted at the fork
+    if branches.len() <= 1 {
+        return Ok(String::new());
+    }
+
+    let config: ParallelGatewayConfig = node.typed_config()?;
+    let total = branches.len() as u32;
+    let required = required_branches(&config.join, total);
+    let tolerated_failures = total.saturating_sub(required);
+
+    let mut futures = String::new();
+    for branch in &branches {
+        if !is_activity_node(branch) {
+            return Err(CompilerError::CodeGenError(format!(
+                "Parallel gateway '{}' branch '{}' must start with an activity node",
+                node.id, branch.id
+            )));
+        }
+        let activity = activity_name(branch);
+        let activity_ctx = activity_context(branch, "branchCtx")?;
+        futures.push_str(&note_comments(definition, branch, 8));
+        futures.push_str(&format!(r#"        selector.AddFuture(workflow.ExecuteActivity({activity_ctx}, "{activity}", input), func(f workflow.Future) {{
+            if err := f.Get(branchCtx, nil); err != nil {{
+                failed++
+                branchErr = err
+            }} else {{
+                succeeded++
+            }}
+        }})
+"#));
+    }
+
+    let label = &node.label;
+    let policy = match config.join {
+        JoinPolicy::All => "all".to_string(),
+        JoinPolicy::Any => "any".to_string(),
+        JoinPolicy::NOfM(n) => format!("{n} of {total}"),
+    };
+
+    Ok(format!(r#"    // Parallel gateway: {label} (join: {policy})
+    {{
+        branchCtx, cancelBranches := workflow.WithCancel(ctx)
+        selector := workflow.NewSelector(ctx)
+        succeeded, failed := 0, 0
+        var branchErr error
+{futures}        for succeeded < {required} && failed <= {tolerated_failures} {{
+            selector.Select(ctx)
+        }}
+        cancelBranches()
+        if succeeded < {required} {{
+            logger.Error("{label} failed", "error", branchErr)
+            return nil, branchErr
+        }}
+    }}
+
+"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{CompileOptions, WorkflowCompiler, WorkflowDefinition};
+
+    fn node(id: &str, node_type: &str, label: &str) -> serde_json::Value {
+        json!({ "id": id, "node_type": node_type, "label": label, "config": {}, "position": { "x": 0, "y": 0 } })
+    }
+
+    /// A Decision whose first branch runs two activities before rejoining at Ship, with the
+    /// nodes listed out of graph order
+    fn with_two_step_branch() -> WorkflowDefinition {
+        serde_json::from_value(json!({
+            "id": "3f8a9c1e-1111-4222-8333-444455556668",
+            "name": "order flow",
+            "version": "1.0.0",
+            "variables": [{ "name": "amount", "var_type": "float" }],
+            "triggers": [],
+            "nodes": [
+                node("ship", "activity", "Ship"),
+                node("big2", "activity", "Escalate"),
+                node("end", "end", "End"),
+                node("small", "activity", "Approve"),
+                node("dec", "decision", "Large order"),
+                node("big", "activity", "Review"),
+                node("charge", "activity", "Charge Card"),
+                node("start", "start", "Start"),
+            ],
+            "edges": [
+                { "id": "e1", "source": "start", "target": "charge" },
+                { "id": "e2", "source": "charge", "target": "dec" },
+                { "id": "e3", "source": "dec", "target": "big", "condition": "amount > 100" },
+                { "id": "e4", "source": "big", "target": "big2" },
+                { "id": "e5", "source": "big2", "target": "ship" },
+                { "id": "e6", "source": "dec", "target": "small" },
+                { "id": "e7", "source": "small", "target": "ship" },
+                { "id": "e8", "source": "ship", "target": "end" },
+            ],
+        }))
+        .expect("definition deserializes")
+    }
+
+    #[test]
+    fn decision_branches_run_every_node_up_to_the_merge() {
+        let compiled = WorkflowCompiler::new(vec![])
+            .compile(&with_two_step_branch(), &CompileOptions::default())
+            .expect("workflow compiles");
+        let code = &compiled.workflow_code;
+        let at = |needle: &str| code.find(needle).unwrap_or_else(|| panic!("{needle} missing from:\n{code}"));
+
+        let charge = at("\"ChargeCardActivity\"");
+        let (if_open, else_open) = (at("if input.Amount > 100 {"), at("} else {"));
+        let (review, escalate, approve) = (at("\"ReviewActivity\""), at("\"EscalateActivity\""), at("\"ApproveActivity\""));
+        let ship = at("\"ShipActivity\"");
+        assert!(charge < if_open, "Charge Card runs before the decision");
+        assert!(if_open < review && review < escalate && escalate < else_open, "both steps of the branch run inside the if");
+        assert!(else_open < approve && approve < ship, "the default branch runs inside the else");
+        assert_eq!(code.matches("\"ShipActivity\"").count(), 1);
+        assert!(code[approve..ship].contains("    }\n"), "Ship runs after the if/else closes");
+    }
+    #[test]
+    fn decision_table_runs_the_branch_of_its_outcome() {
+        let mut definition = with_two_step_branch();
+        let table = &mut definition.nodes[4];
+        table.node_type = crate::NodeType::DecisionTable;
+        table.config = json!({
+            "inputs": ["amount"],
+            "rules": [{ "when": [">100"], "then": "large" }],
+            "default_output": "small",
+        });
+        definition.edges[2].condition = None;
+        definition.edges[2].label = Some("large".to_string());
+
+        let compiled = WorkflowCompiler::new(vec![])
+            .compile(&definition, &CompileOptions::default())
+            .expect("workflow compiles");
+        let code = &compiled.workflow_code;
+        let at = |needle: &str| code.find(needle).unwrap_or_else(|| panic!("{needle} missing from:\n{code}"));
+
+        let (switch, large, default) = (at("switch largeOrderOutcome {"), at("case \"large\":"), at("default:"));
+        assert!(switch < large && large < at("\"EscalateActivity\"") && at("\"EscalateActivity\"") < default);
+        assert!(default < at("\"ApproveActivity\"") && at("\"ApproveActivity\"") < at("\"ShipActivity\""));
+
+        definition.edges[2].label = Some("huge".to_string());
+        assert!(WorkflowCompiler::new(vec![]).compile(&definition, &CompileOptions::default()).is_err(), "no rule produces 'huge'");
+    }
+}
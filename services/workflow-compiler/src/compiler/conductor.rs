@@ -0,0 +1,499 @@
+//! Netflix Conductor generation: a workflow definition and the task definitions it uses, as
+//! the JSON documents Conductor's metadata API registers
+//!
+//! The workflow definition is the workflow code and the task definitions the activity code.
+//! Activity, DatabaseQuery and PublishEvent nodes become `SIMPLE` tasks polled by workers
+//! under the activity name, HttpCall nodes `HTTP` system tasks, decisions `SWITCH` tasks
+//! evaluating the edge conditions in JavaScript, forks `FORK_JOIN` tasks with their `JOIN`,
+//! subworkflows `SUB_WORKFLOW` or, when they do not wait, `START_WORKFLOW` tasks, and timers
+//! and signal waits `WAIT` tasks. Retry policies and activity timeouts live on the task
+//! definitions.
+//!
+//! Conductor workflows are trees of task lists, so a decision's cases run up to the node
+//! where its branches merge, which then follows the `SWITCH`; cases reaching an End node
+//! before that end the workflow with a `TERMINATE` task. Graphs that loop, or whose branches
+//! do not merge, have no such tree and are rejected.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::{json, Map, Value};
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{activity_name, is_activity_node};
+use crate::compiler::steps::{self, TargetSources};
+use crate::compiler::{duration, limits};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    to_pascal_case, CodegenTarget, DynamicActivityConfig, EdgeKind, JoinPolicy, NodeType, ParallelGatewayConfig,
+    SubWorkflowConfig, WaitSignalConfig, WaitTimerConfig, WorkflowDefinition, WorkflowNode,
+};
+
+/// Conductor OSS release the definitions and the compose file target
+pub const CONDUCTOR_VERSION: &str = "3.15.0";
+
+/// Response timeout of tasks without activity timeouts, matching the other targets' default
+/// ten-minute StartToCloseTimeout
+const DEFAULT_RESPONSE_TIMEOUT_SECONDS: u64 = 600;
+
+pub struct Conductor;
+
+/// Snake-case task reference name for a label
+fn reference_name(name: &str) -> String {
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("_");
+    match name.as_str() {
+        "" => "task".to_string(),
+        _ if name.starts_with(|c: char| c.is_ascii_digit()) => format!("task_{}", name),
+        _ => name,
+    }
+}
+
+/// JavaScript expression for an edge condition over the `SWITCH` task's input, which holds
+/// every workflow variable
+fn javascript_condition(definition: &WorkflowDefinition, condition: &str) -> Result<String, CompilerError> {
+    let invalid = |reason: String| CompilerError::CodeGenError(format!("Condition '{}' cannot be expressed in Conductor JavaScript: {}", condition, reason));
+    let mut out = String::new();
+    let mut chars = condition.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err(invalid("unterminated string literal".to_string())),
+                    }
+                }
+                out.push_str(&Value::String(text).to_string());
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_alphanumeric() || **ch == '_' || **ch == '.') {
+                    word.push(ch);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "true" | "false" | "null" => out.push_str(&word),
+                    _ => {
+                        let name = word.strip_prefix("input.").unwrap_or(&word);
+                        let (head, rest) = name.split_once('.').map_or((name, ""), |(h, r)| (h, r));
+                        let variable = definition.variables.iter()
+                            .find(|v| v.name == head || to_pascal_case(&v.name) == head)
+                            .ok_or_else(|| invalid(format!("'{}' is not a workflow variable", head)))?;
+                        out.push_str(&format!("$.{}", variable.name));
+                        if !rest.is_empty() {
+                            out.push('.');
+                            out.push_str(rest);
+                        }
+                    }
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Conductor `WAIT` duration such as `1 hours 30 minutes 5 seconds`
+fn wait_duration(seconds: u64) -> String {
+    let units = [("days", 86_400), ("hours", 3600), ("minutes", 60), ("seconds", 1)];
+    let mut remaining = seconds.max(1);
+    let mut parts = vec![];
+    for (unit, size) in units {
+        if remaining >= size {
+            parts.push(format!("{} {}", remaining / size, unit));
+            remaining %= size;
+        }
+    }
+    parts.join(" ")
+}
+
+/// Error for the first reachable feature Conductor and Prefect, which both place nodes as nested
+/// structured blocks, cannot express
+pub fn check_definition(definition: &WorkflowDefinition, target: &str) -> Result<(), CompilerError> {
+    if let Some(edge) = definition.edges.iter().find(|e| e.kind == EdgeKind::Cancel) {
+        return Err(CompilerError::CodeGenError(format!("Edge '{}': cancel edges are not supported by the {} target", edge.id, target)));
+    }
+    let reachable = graph::reachable_nodes(definition);
+    for node in definition.nodes.iter().filter(|n| reachable.contains(n.id.as_str())) {
+        if node.session.is_some() {
+            return Err(steps::unsupported(node, "a worker session", target));
+        }
+        let what = match node.node_type {
+            NodeType::DecisionTable => "a decision table",
+            NodeType::WaitSignals => "a wait for several signals",
+            NodeType::CancellationScope => "a cancellation scope",
+            NodeType::NexusOperation => "a Nexus operation",
+            NodeType::FeatureFlag => "a feature flag",
+            NodeType::WeightedSplit => "a weighted split",
+            NodeType::Notification => "a notification",
+            NodeType::ParallelGateway => {
+                let config: ParallelGatewayConfig = node.typed_config()?;
+                if matches!(config.join, JoinPolicy::All) {
+                    continue;
+                }
+                "an any or n_of_m join"
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                if config.namespace.is_none() && config.task_queue.is_none() {
+                    continue;
+                }
+                "a child workflow on another namespace or task queue"
+            }
+            _ => continue,
+        };
+        return Err(steps::unsupported(node, what, target));
+    }
+    Ok(())
+}
+
+/// Workflow definition being assembled from the graph
+struct Builder<'a> {
+    definition: &'a WorkflowDefinition,
+    target: &'a str,
+    /// Nodes already placed in the task tree
+    placed: HashSet<&'a str>,
+    references: HashSet<String>,
+    task_defs: BTreeMap<String, Value>,
+    signals: Vec<String>,
+}
+
+impl<'a> Builder<'a> {
+    /// Unused task reference name: `name`, or `name` with a numeric suffix
+    fn reference(&mut self, name: &str) -> String {
+        let mut reference = name.to_string();
+        let mut suffix = 2;
+        while !self.references.insert(reference.clone()) {
+            reference = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        reference
+    }
+
+    /// Task reference name for a node: its label, with the node ID when the label is not unique
+    fn node_reference(&mut self, node: &WorkflowNode) -> String {
+        let duplicate = self.definition.nodes.iter().filter(|n| reference_name(&n.label) == reference_name(&node.label)).count() > 1;
+        let name = if duplicate { reference_name(&format!("{} {}", node.label, node.id)) } else { reference_name(&node.label) };
+        self.reference(&name)
+    }
+
+    /// Single successor of a node with sequential semantics
+    fn successor(&self, node: &WorkflowNode) -> Result<Option<&'a WorkflowNode>, CompilerError> {
+        let targets = graph::flow_targets(self.definition, node);
+        if targets.len() > 1 {
+            return Err(steps::unsupported(node, "more than one outgoing edge on a node that is not a decision or fork", self.target));
+        }
+        Ok(targets.first().copied())
+    }
+
+    /// Tasks from `start` up to, not including, `stop`; End nodes reached in a nested list
+    /// terminate the workflow
+    fn sequence(&mut self, start: Option<&'a WorkflowNode>, stop: Option<&str>, nested: bool) -> Result<Vec<Value>, CompilerError> {
+        let mut tasks = vec![];
+        let mut next = start;
+        while let Some(node) = next {
+            limits::checkpoint("code generation")?;
+            if Some(node.id.as_str()) == stop {
+                break;
+            }
+            if !matches!(node.node_type, NodeType::End) && !self.placed.insert(node.id.as_str()) {
+                return Err(steps::unsupported(node, "a node reached again through a loop or through branches that do not merge", self.target));
+            }
+            next = match node.node_type {
+                NodeType::Start | NodeType::Transform => self.successor(node)?,
+                NodeType::End => {
+                    if nested {
+                        let reference = self.reference(&format!("{}_terminate", reference_name(&node.label)));
+                        tasks.push(json!({
+                            "name": "terminate",
+                            "taskReferenceName": reference,
+                            "type": "TERMINATE",
+                            "inputParameters": { "terminationStatus": "COMPLETED" },
+                        }));
+                    }
+                    None
+                }
+                NodeType::Decision => {
+                    let merge = graph::merge_point(self.definition, node);
+                    tasks.push(self.switch(node, merge)?);
+                    merge
+                }
+                NodeType::ParallelGateway if graph::is_join(self.definition, node) => self.successor(node)?,
+                NodeType::ParallelGateway => {
+                    let (fork, join, after) = self.fork(node)?;
+                    tasks.push(fork);
+                    tasks.push(join);
+                    after
+                }
+                _ => {
+                    tasks.push(self.task(node)?);
+                    self.successor(node)?
+                }
+            };
+        }
+        Ok(tasks)
+    }
+
+    fn switch(&mut self, node: &'a WorkflowNode, merge: Option<&'a WorkflowNode>) -> Result<Value, CompilerError> {
+        let reference = self.node_reference(node);
+        let stop = merge.map(|m| m.id.as_str());
+        let mut cases = Map::new();
+        let mut conditions = vec![];
+        let mut default = None;
+        for edge in graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow) {
+            let Some(target) = graph::find_node(self.definition, &edge.target) else { continue };
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => {
+                    conditions.push((javascript_condition(self.definition, condition)?, edge.id.clone()));
+                    let tasks = self.sequence(Some(target), stop, true)?;
+                    cases.insert(edge.id.clone(), json!(tasks));
+                }
+                None if default.is_none() => default = Some(self.sequence(Some(target), stop, true)?),
+                None => return Err(steps::unsupported(node, "more than one edge without a condition", self.target)),
+            }
+        }
+        let expression = conditions.iter().rev().fold("\"default\"".to_string(), |otherwise, (condition, case)| {
+            format!("({}) ? {} : {}", condition, Value::String(case.clone()), otherwise)
+        });
+        let inputs: Map<String, Value> = self.definition.variables.iter()
+            .map(|v| (v.name.clone(), json!(format!("${{workflow.input.{}}}", v.name))))
+            .collect();
+        Ok(json!({
+            "name": reference,
+            "taskReferenceName": reference,
+            "type": "SWITCH",
+            "evaluatorType": "javascript",
+            "expression": expression,
+            "inputParameters": inputs,
+            "decisionCases": cases,
+            "defaultCase": default.unwrap_or_default(),
+        }))
+    }
+
+    /// `FORK_JOIN` task with one list per branch, its `JOIN` and the node after the join
+    fn fork(&mut self, node: &'a WorkflowNode) -> Result<(Value, Value, Option<&'a WorkflowNode>), CompilerError> {
+        let reference = self.node_reference(node);
+        let targets = graph::flow_targets(self.definition, node);
+        let join = targets.first().and_then(|first| graph::find_join(self.definition, first));
+        let stop = join.map(|j| j.id.as_str());
+        let mut branches = vec![];
+        let mut join_on = vec![];
+        for target in targets {
+            let tasks = self.sequence(Some(target), stop, true)?;
+            join_on.extend(tasks.last().and_then(|t| t["taskReferenceName"].as_str()).map(str::to_string));
+            branches.push(tasks);
+        }
+        let fork = json!({ "name": reference, "taskReferenceName": reference, "type": "FORK_JOIN", "forkTasks": branches });
+        let join_reference = self.reference(&format!("{}_join", reference));
+        let join_task = json!({ "name": join_reference, "taskReferenceName": join_reference, "type": "JOIN", "joinOn": join_on });
+        let after = match join {
+            Some(join) => {
+                self.placed.insert(join.id.as_str());
+                self.successor(join)?
+            }
+            None => None,
+        };
+        Ok((fork, join_task, after))
+    }
+
+    /// Task for a node without branching semantics
+    fn task(&mut self, node: &WorkflowNode) -> Result<Value, CompilerError> {
+        let reference = self.node_reference(node);
+        let task = match node.node_type {
+            NodeType::HttpCall => {
+                let config = |key: &str| node.config.get(key).filter(|v| !v.is_null());
+                let mut request = Map::new();
+                request.insert("uri".into(), config("url").cloned().unwrap_or_default());
+                let method = config("method").and_then(Value::as_str).unwrap_or("GET").to_ascii_uppercase();
+                request.insert("method".into(), json!(method));
+                for key in ["headers", "body"] {
+                    if let Some(value) = config(key) {
+                        request.insert(key.into(), value.clone());
+                    }
+                }
+                let name = activity_name(node);
+                self.task_def(node, &name)?;
+                json!({ "name": name, "taskReferenceName": reference, "type": "HTTP", "inputParameters": { "http_request": request } })
+            }
+            _ if is_activity_node(node) => {
+                let name = activity_name(node);
+                self.task_def(node, &name)?;
+                json!({ "name": name, "taskReferenceName": reference, "type": "SIMPLE", "inputParameters": { "input": "${workflow.input}" } })
+            }
+            NodeType::DynamicActivity => {
+                let config: DynamicActivityConfig = node.typed_config()?;
+                for activity in &config.allowed {
+                    self.task_defs.entry(activity.clone()).or_insert_with(|| default_task_def(activity));
+                }
+                json!({
+                    "name": reference,
+                    "taskReferenceName": reference,
+                    "type": "DYNAMIC",
+                    "dynamicTaskNameParam": "taskToExecute",
+                    "inputParameters": { "taskToExecute": format!("${{workflow.input.{}}}", config.selector), "input": "${workflow.input}" },
+                })
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                if config.wait_for_completion {
+                    json!({
+                        "name": reference,
+                        "taskReferenceName": reference,
+                        "type": "SUB_WORKFLOW",
+                        "subWorkflowParam": { "name": config.workflow },
+                        "inputParameters": { "input": "${workflow.input}" },
+                    })
+                } else {
+                    json!({
+                        "name": reference,
+                        "taskReferenceName": reference,
+                        "type": "START_WORKFLOW",
+                        "inputParameters": { "startWorkflow": { "name": config.workflow, "input": "${workflow.input}" } },
+                    })
+                }
+            }
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config()?;
+                let timer = duration::parse_field(&config.duration, "duration", Some(&node.id))?;
+                json!({ "name": reference, "taskReferenceName": reference, "type": "WAIT", "inputParameters": { "duration": wait_duration(timer.as_secs()) } })
+            }
+            NodeType::WaitSignal => {
+                // Completed through the tasks API by whoever sends the signal
+                let config: WaitSignalConfig = node.typed_config()?;
+                self.signals.push(config.signal.clone());
+                json!({ "name": reference, "taskReferenceName": reference, "type": "WAIT", "inputParameters": { "signal": config.signal } })
+            }
+            _ => return Err(steps::unsupported(node, &format!("a {:?} node", node.node_type), self.target)),
+        };
+        Ok(task)
+    }
+
+    /// Task definition for an activity, carrying the node's retry policy and timeouts
+    fn task_def(&mut self, node: &WorkflowNode, name: &str) -> Result<(), CompilerError> {
+        if self.task_defs.contains_key(name) {
+            return Ok(());
+        }
+        let mut def = default_task_def(name);
+        if let Some(retry) = steps::retry(node)? {
+            def["retryCount"] = json!(retry.max_attempts.saturating_sub(1));
+            def["retryDelaySeconds"] = json!(retry.initial_interval.as_secs().max(1));
+            if retry.backoff_coefficient > 1.0 {
+                def["retryLogic"] = json!("EXPONENTIAL_BACKOFF");
+                def["backoffScaleFactor"] = json!(retry.backoff_coefficient.round().max(1.0) as u64);
+            } else {
+                def["retryLogic"] = json!("FIXED");
+            }
+        }
+        let timeouts = steps::timeouts(node)?;
+        let response = timeouts.heartbeat.or(timeouts.start_to_close).map_or(DEFAULT_RESPONSE_TIMEOUT_SECONDS, |d| d.as_secs().max(1));
+        def["responseTimeoutSeconds"] = json!(response);
+        if let Some(total) = timeouts.schedule_to_close {
+            def["timeoutSeconds"] = json!(total.as_secs().max(response));
+        }
+        self.task_defs.insert(name.to_string(), def);
+        Ok(())
+    }
+}
+
+/// Task definition with Conductor's defaults: no retries and no overall timeout
+fn default_task_def(name: &str) -> Value {
+    json!({
+        "name": name,
+        "retryCount": 0,
+        "timeoutSeconds": 0,
+        "responseTimeoutSeconds": DEFAULT_RESPONSE_TIMEOUT_SECONDS,
+        "timeoutPolicy": "TIME_OUT_WF",
+    })
+}
+
+/// Workflow definition and task definitions for a definition
+fn definitions(definition: &WorkflowDefinition, package_name: &str, fingerprint: &str, target: &str) -> Result<(Value, Value, Vec<String>), CompilerError> {
+    check_definition(definition, target)?;
+    let start = definition.nodes.iter()
+        .find(|n| matches!(n.node_type, NodeType::Start))
+        .ok_or_else(|| CompilerError::CodeGenError("The workflow needs a Start node".to_string()))?;
+    let mut builder = Builder {
+        definition,
+        target,
+        placed: HashSet::new(),
+        references: HashSet::new(),
+        task_defs: BTreeMap::new(),
+        signals: vec![],
+    };
+    let tasks = builder.sequence(Some(start), None, false)?;
+
+    let timeout = match definition.timeouts.as_ref().and_then(|t| t.execution.as_ref().map(|raw| (raw, "timeouts.execution")).or(t.run.as_ref().map(|raw| (raw, "timeouts.run")))) {
+        Some((raw, field)) => duration::parse_field(raw, field, None)?.as_secs(),
+        None => 0,
+    };
+    let version = definition.version.split('.').next().and_then(|major| major.parse::<u32>().ok()).unwrap_or(1).max(1);
+    let mut workflow = json!({
+        "name": package_name,
+        "version": version,
+        "schemaVersion": 2,
+        "inputParameters": definition.variables.iter().map(|v| v.name.clone()).collect::<Vec<_>>(),
+        "tasks": tasks,
+        "outputParameters": { "definitionVersion": definition.version, "definitionFingerprint": fingerprint },
+        "restartable": true,
+        "timeoutPolicy": if timeout > 0 { "TIME_OUT_WF" } else { "ALERT_ONLY" },
+        "timeoutSeconds": timeout,
+    });
+    if let Some(description) = definition.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        workflow["description"] = json!(description);
+    }
+
+    let mut signals = builder.signals;
+    signals.sort();
+    signals.dedup();
+    Ok((workflow, json!(builder.task_defs.into_values().collect::<Vec<_>>()), signals))
+}
+
+fn document(value: &Value) -> Result<String, CompilerError> {
+    serde_json::to_string_pretty(value).map(|json| json + "\n").map_err(|e| CompilerError::CodeGenError(e.to_string()))
+}
+
+impl Backend for Conductor {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Conductor
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        CONDUCTOR_VERSION
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        format!(r#"# Generated by OmniRoute Workflow Compiler
+services:
+  conductor:
+    image: conductoross/conductor-standalone:{CONDUCTOR_VERSION}
+    ports:
+      - "8080:8080"
+      - "5000:5000"
+"#)
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let (workflow, task_defs, signals) = definitions(definition, package_name, fingerprint, self.target().name())?;
+        Ok(TargetSources {
+            workflow_code: document(&workflow)?,
+            activity_code: document(&task_defs)?,
+            worker_code: String::new(),
+            test_code: String::new(),
+            files: vec![],
+            signals,
+            metadata: None,
+        })
+    }
+}
@@ -0,0 +1,640 @@
+//! Dagster generation: one op per node and a job wiring the definition's edges as op inputs
+//!
+//! Every op takes the dict its upstream ops return, merged when several feed it, and returns it
+//! with its own result added, so the start op's run config reaches the whole pipeline. Transform
+//! nodes call a function of the generated activities module with the node config, DatabaseQuery
+//! nodes with a `query` run it on a SQLAlchemy resource named after the node's database
+//! connection, HttpCall nodes call the URL with `requests`, and the other activity nodes call
+//! their activity function. Decisions yield one optional output per branch, so ops behind the
+//! branch not taken are skipped. Workflow variables are the start op's config and every schedule
+//! trigger a schedule of the job.
+//!
+//! As for Airflow, features without a Dagster equivalent do not fail the compilation: their
+//! nodes become pass-through ops, workflow-level ones are dropped, and each is listed in the
+//! target metadata and the header of the jobs module.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::compiler::airflow::{back_edges, dependency_order, identifier, param_type, placeholder, python_expression, python_literal, task_id};
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{self, activity_name, go_string_literal as string_literal, is_activity_node};
+use crate::compiler::{duration, limits};
+use crate::compiler::steps::{self, TargetMetadata, TargetSources, UnmappedFeature};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    to_pascal_case, CodegenTarget, DatabaseQueryConfig, EdgeKind, GeneratedFile, HttpCallConfig, JoinPolicy, NodeType, OverlapPolicy, ParallelGatewayConfig,
+    SubWorkflowConfig, TriggerType, WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+/// Dagster release the generated project targets
+pub const DAGSTER_VERSION: &str = "1.7.16";
+
+const REQUESTS: &str = "requests>=2.31";
+const SQLALCHEMY: &str = "sqlalchemy>=2.0";
+
+/// Names the jobs module imports or defines, which ops must not shadow
+const RESERVED: &[&str] = &["activities", "defs", "job", "op", "requests", "sqlalchemy"];
+
+pub struct Dagster;
+
+/// Op function name for a node: its task ID, unless that shadows a module-level name
+fn op_name(definition: &WorkflowDefinition, node: &WorkflowNode) -> String {
+    let name = task_id(definition, node);
+    if RESERVED.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// Python annotation for a DSL variable type
+fn python_type(var_type: &str) -> &'static str {
+    match param_type(var_type) {
+        Some("string") => "str",
+        Some("integer") => "int",
+        Some("number") => "float",
+        Some("boolean") => "bool",
+        Some("object") => "dict",
+        Some("array") => "list",
+        _ => "Any",
+    }
+}
+
+/// Function generated in the activities module
+enum Stub {
+    /// Activity function, by activity name
+    Activity(String),
+    /// Transform function, by node label
+    Transform(String),
+}
+
+/// Jobs module being assembled: resources, ops in dependency order and the job wiring them
+struct Job<'a> {
+    definition: &'a WorkflowDefinition,
+    package_name: &'a str,
+    /// Flow edges kept as op inputs, without loops and edges out of unreachable nodes
+    edges: Vec<&'a WorkflowEdge>,
+    /// Names imported from `dagster`
+    imports: BTreeSet<&'static str>,
+    uses_any: bool,
+    uses_optional: bool,
+    uses_field: bool,
+    requirements: BTreeSet<&'static str>,
+    /// Resource keys of the database connections, with the environment variable of each URL
+    databases: BTreeMap<String, String>,
+    /// Activities module functions by function name
+    stubs: BTreeMap<String, Stub>,
+    unmapped: Vec<UnmappedFeature>,
+}
+
+impl<'a> Job<'a> {
+    fn new(definition: &'a WorkflowDefinition, package_name: &'a str) -> Self {
+        let reachable = graph::reachable_nodes(definition);
+        let loops = back_edges(definition);
+        let mut job = Job {
+            definition,
+            package_name,
+            edges: vec![],
+            imports: BTreeSet::from(["Definitions", "job", "op"]),
+            uses_any: false,
+            uses_optional: false,
+            uses_field: false,
+            requirements: BTreeSet::new(),
+            databases: BTreeMap::new(),
+            stubs: BTreeMap::new(),
+            unmapped: vec![],
+        };
+        for edge in definition.edges.iter().filter(|e| reachable.contains(e.source.as_str())) {
+            match edge.kind {
+                EdgeKind::Cancel => job.unmap(Some(&edge.source), format!("a cancel edge to '{}'", edge.target), "dropped"),
+                EdgeKind::Flow if loops.contains(edge.id.as_str()) => {
+                    job.unmap(Some(&edge.source), format!("a loop back to '{}'", edge.target), "dropped; Dagster jobs cannot loop")
+                }
+                EdgeKind::Flow if job.edges.iter().any(|e| e.source == edge.source && e.target == edge.target) => {}
+                EdgeKind::Flow => job.edges.push(edge),
+            }
+        }
+        job
+    }
+
+    fn unmap(&mut self, node_id: Option<&str>, feature: impl Into<String>, fallback: &str) {
+        self.unmapped.push(UnmappedFeature { node_id: node_id.map(str::to_string), feature: feature.into(), fallback: fallback.to_string() });
+    }
+
+    /// Decision branches as output names, which are the op names of the branch targets
+    fn branches(&self, node: &WorkflowNode) -> Vec<String> {
+        let mut branches = vec![];
+        for edge in self.edges.iter().filter(|e| e.source == node.id) {
+            if let Some(target) = graph::find_node(self.definition, &edge.target) {
+                branches.push(op_name(self.definition, target));
+            }
+        }
+        branches
+    }
+
+    /// `retry_policy` argument for a node retry policy; Dagster counts retries, not attempts,
+    /// and only doubles the delay between them, without a limit
+    fn retry_argument(&mut self, node: &WorkflowNode) -> Result<Option<String>, CompilerError> {
+        let Some(retry) = steps::retry(node)? else { return Ok(None) };
+        self.imports.insert("RetryPolicy");
+        let mut arguments = vec![
+            format!("max_retries={}", retry.max_attempts.saturating_sub(1)),
+            format!("delay={}", retry.initial_interval.as_secs_f64()),
+        ];
+        if retry.backoff_coefficient > 1.0 {
+            self.imports.insert("Backoff");
+            arguments.push("backoff=Backoff.EXPONENTIAL".to_string());
+            if retry.backoff_coefficient != 2.0 {
+                self.unmap(Some(&node.id), format!("a retry backoff coefficient of {}", retry.backoff_coefficient), "the delay doubles between retries");
+            }
+            self.unmap(Some(&node.id), format!("a maximum retry interval of {}s", retry.max_interval.as_secs_f64()), "the delay keeps doubling");
+        }
+        Ok(Some(format!("retry_policy=RetryPolicy({})", arguments.join(", "))))
+    }
+
+    /// Config class of the start op holding the workflow variables, with its name and the
+    /// dict the start op returns
+    fn config(&mut self) -> (String, String, String) {
+        self.imports.insert("Config");
+        let mut fields = String::new();
+        let mut values = vec![];
+        for variable in &self.definition.variables {
+            let field = identifier(&variable.name);
+            let annotation = python_type(&variable.var_type);
+            self.uses_any |= annotation == "Any";
+            let var_type = param_type(&variable.var_type);
+            let mut constraints = vec![];
+            if let Some(schema) = &variable.schema {
+                let min_length = schema.min_length.or(if schema.required && var_type == Some("string") { Some(1) } else { None });
+                constraints.extend(schema.minimum.map(|m| format!("ge={}", m)));
+                constraints.extend(schema.maximum.map(|m| format!("le={}", m)));
+                constraints.extend(min_length.map(|m| format!("min_length={}", m)));
+                constraints.extend(schema.max_length.map(|m| format!("max_length={}", m)));
+                constraints.extend(schema.pattern.as_deref().map(|p| format!("pattern={}", string_literal(p))));
+            }
+            let (annotation, default) = match &variable.default_value {
+                Some(default) if !default.is_null() => (annotation.to_string(), python_literal(default)),
+                _ if annotation == "Any" => (annotation.to_string(), "None".to_string()),
+                _ => {
+                    self.uses_optional = true;
+                    (format!("Optional[{}]", annotation), "None".to_string())
+                }
+            };
+            let value = if constraints.is_empty() {
+                default
+            } else {
+                self.uses_field = true;
+                constraints.insert(0, format!("default={}", default));
+                format!("Field({})", constraints.join(", "))
+            };
+            fields.push_str(&format!("    {}: {} = {}\n", field, annotation, value));
+            values.push(format!("{}: config.{}", string_literal(&variable.name), field));
+        }
+        let class = format!("{}Config", to_pascal_case(&self.definition.name));
+        let code = format!("\n\nclass {class}(Config):\n    \"\"\"Workflow variables the run starts with\"\"\"\n\n{fields}");
+        (code, class, format!("{{{}}}", values.join(", ")))
+    }
+
+    /// Parameters of an op and the statement binding `data` when its inputs must be merged
+    fn inputs(&self, node: &WorkflowNode) -> (String, String) {
+        match self.edges.iter().filter(|e| e.target == node.id).count() {
+            0 => (String::new(), "    data: dict = {}\n".to_string()),
+            1 => ("data: dict".to_string(), String::new()),
+            _ => (
+                "upstream: list[dict]".to_string(),
+                "    data = {key: value for branch in upstream for key, value in branch.items()}\n".to_string(),
+            ),
+        }
+    }
+
+    /// Body of an op returning the dict its downstream ops receive, with the resources it
+    /// takes beyond its inputs
+    fn body(&mut self, node: &WorkflowNode, name: &str) -> Result<(Vec<String>, String), CompilerError> {
+        let config_str = |key: &str| node.config.get(key).and_then(Value::as_str).filter(|v| !v.is_empty());
+        Ok(match node.node_type {
+            NodeType::Transform => {
+                self.stubs.entry(name.to_string()).or_insert_with(|| Stub::Transform(node.label.clone()));
+                (vec![], format!("    return activities.{}(data, {})\n", name, python_literal(&node.config)))
+            }
+            NodeType::HttpCall => {
+                let config: HttpCallConfig = node.typed_config()?;
+                if config.url.is_empty() {
+                    return Err(CompilerError::CodeGenError(format!("HttpCall node '{}' has no url", node.id)));
+                }
+                self.requirements.insert(REQUESTS);
+                let mut arguments = vec![string_literal(&config.method.to_ascii_uppercase()), string_literal(&config.url)];
+                if !config.headers.is_empty() {
+                    arguments.push(format!("headers={}", python_literal(&json!(config.headers))));
+                }
+                match &config.body {
+                    Value::String(body) => arguments.push(format!("data={}", string_literal(body))),
+                    Value::Null => {}
+                    body => arguments.push(format!("json={}", python_literal(body))),
+                }
+                arguments.push("timeout=30".to_string());
+                (vec![], format!(
+                    "    response = requests.request({})\n    response.raise_for_status()\n    return {{**data, {}: response.json() if response.headers.get(\"content-type\", \"\").startswith(\"application/json\") else response.text}}\n",
+                    arguments.join(", "),
+                    string_literal(name),
+                ))
+            }
+            NodeType::DatabaseQuery if config_str("query").is_some() => {
+                self.imports.insert("ConfigurableResource");
+                self.imports.insert("EnvVar");
+                self.requirements.insert(SQLALCHEMY);
+                let config: DatabaseQueryConfig = node.typed_config()?;
+                let connection = config.connection.into_iter().chain(config.database).find(|c| !c.is_empty()).map_or_else(|| format!("{}_db", self.package_name), |c| identifier(&c));
+                self.databases.insert(connection.clone(), format!("{}_URL", connection.to_ascii_uppercase()));
+                (
+                    vec![format!("{}: DatabaseResource", connection)],
+                    format!(
+                        "    return {{**data, {}: {}.query({}, data)}}\n",
+                        string_literal(name),
+                        connection,
+                        string_literal(&config.query.unwrap_or_default()),
+                    ),
+                )
+            }
+            _ if is_activity_node(node) => {
+                let function = identifier(&activity_name(node));
+                self.stubs.entry(function.clone()).or_insert_with(|| Stub::Activity(activity_name(node)));
+                (vec![], format!("    return activities.{}(data)\n", function))
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                self.unmap(Some(&node.id), format!("a child workflow '{}'", config.workflow), "pass-through op; the child job is not launched");
+                (vec![], "    return data\n".to_string())
+            }
+            NodeType::ParallelGateway => {
+                let config: ParallelGatewayConfig = node.typed_config()?;
+                match config.join {
+                    JoinPolicy::All => {}
+                    JoinPolicy::Any => self.unmap(Some(&node.id), "an any join", "the join waits for every branch"),
+                    JoinPolicy::NOfM(n) => self.unmap(Some(&node.id), format!("an n_of_m join of {}", n), "the join waits for every branch"),
+                }
+                (vec![], "    return data\n".to_string())
+            }
+            NodeType::Start | NodeType::End => (vec![], "    return data\n".to_string()),
+            _ => {
+                if let Some((feature, consequence)) = placeholder(&node.node_type) {
+                    self.unmap(Some(&node.id), feature, &format!("pass-through op; {}", consequence));
+                }
+                (vec![], "    return data\n".to_string())
+            }
+        })
+    }
+
+    /// Decision op yielding the branch of the first edge whose condition holds, else the
+    /// unconditional edge's, else none
+    fn decision(&mut self, node: &WorkflowNode, name: &str, arguments: &mut Vec<String>) -> Result<String, CompilerError> {
+        self.imports.insert("Out");
+        self.imports.insert("Output");
+        let (parameters, merge) = self.inputs(node);
+        let branches = self.branches(node);
+        let outs: Vec<String> = branches.iter().map(|b| format!("{}: Out(dict, is_required=False)", string_literal(b))).collect();
+        let mut body = merge;
+        let mut default = None;
+        for edge in graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow) {
+            let branch = self.edges.iter().find(|e| e.id == edge.id)
+                .and_then(|e| graph::find_node(self.definition, &e.target))
+                .map(|target| op_name(self.definition, target));
+            let choice = branch.map_or_else(|| "return".to_string(), |b| format!("yield Output(data, {})", string_literal(&b)));
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => {
+                    let expression = python_expression(self.definition, condition, "data")?;
+                    body.push_str(&format!("    if {}:\n        {}\n        return\n", expression, choice));
+                }
+                None => {
+                    default.get_or_insert(choice);
+                }
+            }
+        }
+        body.push_str(&format!("    {}\n", default.unwrap_or_else(|| "return".to_string())));
+        arguments.insert(0, format!("out={{{}}}", outs.join(", ")));
+        Ok(format!(
+            "def {name}({parameters}):\n    \"\"\"Branch taken by {label}\"\"\"\n{body}",
+            label = node.label.replace('"', "'"),
+        ))
+    }
+
+    /// Op definition for a node; `start_config` names the config class of the start op and
+    /// the dict it returns
+    fn op(&mut self, node: &WorkflowNode, start_config: Option<(&str, &str)>) -> Result<String, CompilerError> {
+        if node.session.is_some() {
+            self.unmap(Some(&node.id), "a worker session", "ignored; the op runs in any run worker");
+        }
+        let name = op_name(self.definition, node);
+        let label = node.label.replace('"', "'");
+        let mut arguments: Vec<String> = self.retry_argument(node)?.into_iter().collect();
+        let function = match (&node.node_type, start_config) {
+            (NodeType::Decision, _) => self.decision(node, &name, &mut arguments)?,
+            (NodeType::Start, Some((class, values))) => {
+                format!("def {name}(config: {class}) -> dict:\n    \"\"\"{label}\"\"\"\n    return {values}\n")
+            }
+            _ => {
+                let (mut parameters, merge) = self.inputs(node);
+                let (resources, body) = self.body(node, &name)?;
+                for resource in resources {
+                    if !parameters.is_empty() {
+                        parameters.push_str(", ");
+                    }
+                    parameters.push_str(&resource);
+                }
+                format!("def {name}({parameters}) -> dict:\n    \"\"\"{label}\"\"\"\n{merge}{body}")
+            }
+        };
+        let decorator = if arguments.is_empty() { "@op".to_string() } else { format!("@op({})", arguments.join(", ")) };
+        Ok(format!("\n\n{}\n{}", decorator, function))
+    }
+
+    /// Python expression for the output of a node an edge carries to its target
+    fn output(&self, source: &WorkflowNode, target: &WorkflowNode) -> String {
+        let result = format!("{}_result", op_name(self.definition, source));
+        if matches!(source.node_type, NodeType::Decision) && self.branches(source).len() > 1 {
+            format!("{}.{}", result, op_name(self.definition, target))
+        } else {
+            result
+        }
+    }
+
+    /// Job body invoking every op on the outputs of its upstream ops
+    fn wiring(&self, order: &[&'a WorkflowNode]) -> String {
+        let mut lines = String::new();
+        for node in order {
+            let upstream: Vec<String> = self.edges.iter()
+                .filter(|e| e.target == node.id)
+                .filter_map(|e| graph::find_node(self.definition, &e.source))
+                .map(|source| self.output(source, node))
+                .collect();
+            let arguments = match upstream.len() {
+                0 | 1 => upstream.concat(),
+                _ => format!("[{}]", upstream.join(", ")),
+            };
+            let name = op_name(self.definition, node);
+            let feeds = self.edges.iter().any(|e| e.source == node.id);
+            let assignment = if feeds { format!("{}_result = ", name) } else { String::new() };
+            lines.push_str(&format!("    {}{}({})\n", assignment, name, arguments));
+        }
+        lines
+    }
+
+    /// `@job(...)` arguments and the schedules of the job
+    fn job_arguments(&mut self, job_function: &str) -> Result<(Vec<String>, Vec<String>), CompilerError> {
+        let definition = self.definition;
+        let mut arguments = vec![format!("name={}", string_literal(self.package_name))];
+        if let Some(description) = definition.description.as_deref().filter(|d| !d.is_empty()) {
+            arguments.push(format!("description={}", string_literal(description)));
+        }
+
+        let mut tags = vec!["\"omniroute/definition_version\": DEFINITION_VERSION".to_string()];
+        if let Some(timeouts) = &definition.timeouts {
+            let timeout: Option<Duration> = match (&timeouts.execution, &timeouts.run) {
+                (Some(raw), _) => Some(duration::parse_field(raw, "timeouts.execution", None)?),
+                (None, Some(raw)) => Some(duration::parse_field(raw, "timeouts.run", None)?),
+                (None, None) => None,
+            };
+            if let Some(timeout) = timeout {
+                tags.push(format!("\"dagster/max_runtime\": \"{}\"", timeout.as_secs()));
+            }
+            if timeouts.task.is_some() {
+                self.unmap(None, "a workflow task timeout", "ignored; Dagster runs have no workflow tasks");
+            }
+        }
+        arguments.push(format!("tags={{{}}}", tags.join(", ")));
+
+        let mut schedules = vec![];
+        for (index, schedule) in codegen::schedule_triggers(definition)?.iter().enumerate() {
+            self.imports.insert("ScheduleDefinition");
+            self.imports.insert("DefaultScheduleStatus");
+            if schedule.overlap != OverlapPolicy::AllowAll {
+                let policy = serde_json::to_value(schedule.overlap).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+                self.unmap(None, format!("the {} schedule overlap policy", policy), "runs of the schedule may overlap");
+            }
+            if schedule.catchup_window.is_some() {
+                self.unmap(None, "a schedule catch-up window", "missed ticks are not caught up");
+            }
+            let name = match index {
+                0 => format!("{}_schedule", identifier(self.package_name)),
+                _ => format!("{}_schedule_{}", identifier(self.package_name), index + 1),
+            };
+            schedules.push(format!(
+                "        ScheduleDefinition(\n            name={},\n            job={},\n            cron_schedule={},\n            default_status=DefaultScheduleStatus.{},\n        ),\n",
+                string_literal(&name),
+                job_function,
+                string_literal(&schedule.cron),
+                if schedule.paused { "STOPPED" } else { "RUNNING" },
+            ));
+        }
+        for trigger in &definition.triggers {
+            match trigger.trigger_type {
+                TriggerType::Webhook => self.unmap(None, "a webhook trigger", "not generated; launch runs through the Dagster GraphQL API"),
+                TriggerType::Event => self.unmap(None, "an event trigger", "not generated; add a sensor that launches runs"),
+                TriggerType::Manual | TriggerType::Schedule => {}
+            }
+        }
+        Ok((arguments, schedules))
+    }
+}
+
+const DATABASE_RESOURCE: &str = r#"
+
+class DatabaseResource(ConfigurableResource):
+    """Database reached through SQLAlchemy at a URL"""
+
+    url: str
+
+    def query(self, sql: str, params: dict) -> list[dict]:
+        engine = sqlalchemy.create_engine(self.url)
+        with engine.begin() as connection:
+            result = connection.execute(sqlalchemy.text(sql), params)
+            return [dict(row._mapping) for row in result] if result.returns_rows else []
+"#;
+
+/// Jobs module, activities module and project file for a definition, with the op count
+fn generate_job(job: &mut Job, fingerprint: &str) -> Result<(String, String, Vec<GeneratedFile>, usize), CompilerError> {
+    let definition = job.definition;
+    let order = dependency_order(definition, &job.edges);
+    let (config_class, start_config) = match definition.variables.is_empty() {
+        true => (String::new(), None),
+        false => {
+            let (code, class, values) = job.config();
+            (code, Some((class, values)))
+        }
+    };
+    let mut ops = String::new();
+    for node in &order {
+        limits::checkpoint("code generation")?;
+        ops.push_str(&job.op(node, start_config.as_ref().map(|(c, v)| (c.as_str(), v.as_str())))?);
+    }
+
+    let job_function = format!("{}_job", identifier(job.package_name));
+    let (arguments, schedules) = job.job_arguments(&job_function)?;
+    let arguments: String = arguments.iter().map(|a| format!("    {},\n", a)).collect();
+    let resources: String = job.databases.iter()
+        .map(|(key, variable)| format!("        {}: DatabaseResource(url=EnvVar({})),\n", string_literal(key), string_literal(variable)))
+        .collect();
+    let mut definitions = vec![format!("    jobs=[{}],\n", job_function)];
+    if !schedules.is_empty() {
+        definitions.push(format!("    schedules=[\n{}    ],\n", schedules.concat()));
+    }
+    if !resources.is_empty() {
+        definitions.push(format!("    resources={{\n{}    }},\n", resources));
+    }
+
+    let mut modules = vec![];
+    if job.requirements.contains(REQUESTS) {
+        modules.push("import requests\n");
+    }
+    if job.requirements.contains(SQLALCHEMY) {
+        modules.push("import sqlalchemy\n");
+    }
+    let typing: Vec<&str> = [("Any", job.uses_any), ("Optional", job.uses_optional)].iter().filter(|(_, used)| *used).map(|(name, _)| *name).collect();
+    let mut header = String::new();
+    if !typing.is_empty() {
+        header.push_str(&format!("from typing import {}\n\n", typing.join(", ")));
+    }
+    header.push_str(&modules.concat());
+    header.push_str(&format!("from dagster import {}\n", job.imports.iter().copied().collect::<Vec<_>>().join(", ")));
+    if job.uses_field {
+        header.push_str("from pydantic import Field\n");
+    }
+    if !job.stubs.is_empty() {
+        header.push_str("\nimport activities\n");
+    }
+    let unmapped: String = if job.unmapped.is_empty() {
+        String::new()
+    } else {
+        let lines: String = job.unmapped.iter()
+            .map(|u| match &u.node_id {
+                Some(node) => format!("#   node '{}': {} ({})\n", node, u.feature, u.fallback),
+                None => format!("#   {} ({})\n", u.feature, u.fallback),
+            })
+            .collect();
+        format!("#\n# Not mapped onto Dagster:\n{}", lines)
+    };
+    let database = if job.databases.is_empty() { "" } else { DATABASE_RESOURCE };
+
+    let workflow_code = format!(r#"# Generated by OmniRoute Workflow Compiler
+# DO NOT EDIT - This file is auto-generated
+{unmapped}
+{header}
+# Version of the workflow definition this job was compiled from
+DEFINITION_VERSION = {version}
+# Content hash of that definition
+DEFINITION_FINGERPRINT = {fingerprint}
+{database}{config_class}{ops}
+
+@job(
+{arguments})
+def {job_function}():
+{wiring}
+
+defs = Definitions(
+{definitions})
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        wiring = job.wiring(&order),
+        definitions = definitions.concat(),
+    );
+
+    let functions: String = job.stubs.iter()
+        .map(|(function, stub)| match stub {
+            Stub::Activity(name) => format!(r#"
+
+def {function}(data: dict) -> dict:
+    """{name} implements the {name} activity"""
+    # TODO: implement {name} using data
+    return data
+"#),
+            Stub::Transform(label) => format!(r#"
+
+def {function}(data: dict, config: dict) -> dict:
+    """{function} applies the {label} transform"""
+    # TODO: apply the transform described by config to data
+    return data
+"#, label = label.replace('"', "'")),
+        })
+        .collect();
+    let activity_code = format!("# Generated by OmniRoute Workflow Compiler\n{}", functions);
+
+    let mut dependencies = vec![format!("dagster=={}", DAGSTER_VERSION), format!("dagster-webserver=={}", DAGSTER_VERSION)];
+    dependencies.extend(job.requirements.iter().map(|r| r.to_string()));
+    let pyproject = format!(r#"[project]
+name = {name}
+version = {version}
+requires-python = ">=3.9"
+dependencies = [{dependencies}]
+
+[project.optional-dependencies]
+test = ["pytest>=8.0"]
+
+[tool.dagster]
+module_name = "jobs"
+"#,
+        name = string_literal(&job.package_name.replace('_', "-")),
+        version = string_literal(&definition.version),
+        dependencies = dependencies.iter().map(|d| string_literal(d)).collect::<Vec<_>>().join(", "),
+    );
+    let files = vec![GeneratedFile { path: "pyproject.toml".to_string(), content: pyproject }];
+    Ok((workflow_code, activity_code, files, order.len()))
+}
+
+fn generate_test(package_name: &str, op_count: usize) -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+
+from jobs import defs
+
+
+def test_{package_name}_job_loads() -> None:
+    job = defs.get_job_def({job_name})
+    assert len(job.graph.nodes) == {op_count}
+"#, package_name = identifier(package_name), job_name = string_literal(package_name))
+}
+
+impl Backend for Dagster {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Dagster
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        DAGSTER_VERSION
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        r#"# Generated by OmniRoute Workflow Compiler
+services:
+  dagster:
+    image: python:3.11-slim
+    working_dir: /opt/dagster/app
+    command: sh -c "pip install -e . && dagster dev -h 0.0.0.0 -p 3000"
+    ports:
+      - "3000:3000"
+    volumes:
+      - .:/opt/dagster/app
+"#.to_string()
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let mut job = Job::new(definition, package_name);
+        let (workflow_code, activity_code, files, op_count) = generate_job(&mut job, fingerprint)?;
+
+        Ok(TargetSources {
+            workflow_code,
+            activity_code,
+            worker_code: String::new(),
+            test_code: generate_test(package_name, op_count),
+            files,
+            signals: vec![],
+            metadata: Some(TargetMetadata { unmapped: job.unmapped }),
+        })
+    }
+}
@@ -0,0 +1,593 @@
+//! DMN-style decision tables: rule parsing, compile-time checks and Go evaluation
+
+use crate::compiler::codegen::go_string_literal;
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::limits;
+use crate::compiler::types::VarType;
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, DecisionTableConfig, EdgeKind, HitPolicy, WorkflowDefinition, WorkflowEdge, WorkflowNode};
+
+/// Parsed DMN unary test for a single input cell
+#[derive(Debug, Clone, PartialEq)]
+enum UnaryTest {
+    /// `-` matches every value
+    Any,
+    Eq(Literal),
+    Ne(Literal),
+    /// Numeric interval; `None` bounds are open-ended
+    Range { low: Option<Bound>, high: Option<Bound> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bound {
+    value: f64,
+    inclusive: bool,
+}
+
+fn parse_literal(raw: &str) -> Literal {
+    let raw = raw.trim();
+    if let Ok(n) = raw.parse::<f64>() {
+        return Literal::Number(n);
+    }
+    match raw {
+        "true" => Literal::Bool(true),
+        "false" => Literal::Bool(false),
+        _ => Literal::Str(raw.trim_matches('"').to_string()),
+    }
+}
+
+fn parse_number(raw: &str, cell: &str) -> Result<f64, String> {
+    raw.trim().parse::<f64>().map_err(|_| format!("expected a number in '{}'", cell))
+}
+
+fn parse_unary_test(cell: &str) -> Result<UnaryTest, String> {
+    let cell = cell.trim();
+    if cell.is_empty() || cell == "-" {
+        return Ok(UnaryTest::Any);
+    }
+
+    // Intervals: [1..10], (1..10), ]1..10[ and mixed brackets
+    if let Some((low, high)) = cell.get(1..cell.len().saturating_sub(1)).and_then(|inner| inner.split_once("..")) {
+        let low_inclusive = cell.starts_with('[');
+        let high_inclusive = cell.ends_with(']');
+        let opens = cell.starts_with(['[', '(', ']']);
+        let closes = cell.ends_with([']', ')', '[']);
+        if opens && closes {
+            return Ok(UnaryTest::Range {
+                low: Some(Bound { value: parse_number(low, cell)?, inclusive: low_inclusive }),
+                high: Some(Bound { value: parse_number(high, cell)?, inclusive: high_inclusive }),
+            });
+        }
+    }
+
+    for (op, inclusive) in [("<=", true), (">=", true), ("<", false), (">", false)] {
+        if let Some(rest) = cell.strip_prefix(op) {
+            let bound = Some(Bound { value: parse_number(rest, cell)?, inclusive });
+            return Ok(if op.starts_with('<') {
+                UnaryTest::Range { low: None, high: bound }
+            } else {
+                UnaryTest::Range { low: bound, high: None }
+            });
+        }
+    }
+
+    if let Some(rest) = cell.strip_prefix("!=") {
+        return Ok(UnaryTest::Ne(parse_literal(rest)));
+    }
+
+    Ok(UnaryTest::Eq(parse_literal(cell.strip_prefix('=').unwrap_or(cell))))
+}
+
+impl UnaryTest {
+    /// Conservative overlap check: only returns false when the tests provably cannot both match
+    fn overlaps(&self, other: &UnaryTest) -> bool {
+        match (self, other) {
+            (UnaryTest::Any, _) | (_, UnaryTest::Any) => true,
+            (UnaryTest::Eq(a), UnaryTest::Eq(b)) => a == b,
+            (UnaryTest::Eq(a), UnaryTest::Ne(b)) | (UnaryTest::Ne(b), UnaryTest::Eq(a)) => a != b,
+            (UnaryTest::Eq(Literal::Number(n)), range @ UnaryTest::Range { .. })
+            | (range @ UnaryTest::Range { .. }, UnaryTest::Eq(Literal::Number(n))) => range.contains(*n),
+            (UnaryTest::Eq(_), UnaryTest::Range { .. }) | (UnaryTest::Range { .. }, UnaryTest::Eq(_)) => false,
+            (
+                UnaryTest::Range { low: l1, high: h1 },
+                UnaryTest::Range { low: l2, high: h2 },
+            ) => below(*l1, *h2) && below(*l2, *h1),
+            _ => true,
+        }
+    }
+
+    fn contains(&self, n: f64) -> bool {
+        match self {
+            UnaryTest::Range { low, high } => {
+                let above_low = low.is_none_or(|b| if b.inclusive { n >= b.value } else { n > b.value });
+                let below_high = high.is_none_or(|b| if b.inclusive { n <= b.value } else { n < b.value });
+                above_low && below_high
+            }
+            _ => false,
+        }
+    }
+
+    fn to_go(&self, field: &str) -> String {
+        match self {
+            UnaryTest::Any => "true".to_string(),
+            UnaryTest::Eq(lit) => format!("{} == {}", field, literal_to_go(lit)),
+            UnaryTest::Ne(lit) => format!("{} != {}", field, literal_to_go(lit)),
+            UnaryTest::Range { low, high } => {
+                let mut parts = Vec::new();
+                if let Some(b) = low {
+                    parts.push(format!("{} {} {}", field, if b.inclusive { ">=" } else { ">" }, b.value));
+                }
+                if let Some(b) = high {
+                    parts.push(format!("{} {} {}", field, if b.inclusive { "<=" } else { "<" }, b.value));
+                }
+                parts.join(" && ")
+            }
+        }
+    }
+}
+
+/// Whether a lower bound sits below an upper bound, i.e. the interval between them is non-empty
+fn below(low: Option<Bound>, high: Option<Bound>) -> bool {
+    match (low, high) {
+        (Some(l), Some(h)) if l.inclusive && h.inclusive => l.value <= h.value,
+        (Some(l), Some(h)) => l.value < h.value,
+        _ => true,
+    }
+}
+
+/// Input value standing for every value the tests of its column cannot tell apart
+#[derive(Debug, Clone, PartialEq)]
+enum Sample {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    /// A value equal to none of the literals its column is tested against
+    Other,
+}
+
+impl Literal {
+    fn equals(&self, sample: &Sample) -> bool {
+        match (self, sample) {
+            (Literal::Number(a), Sample::Number(b)) => a == b,
+            (Literal::Str(a), Sample::Str(b)) => a == b,
+            (Literal::Bool(a), Sample::Bool(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl UnaryTest {
+    fn matches(&self, sample: &Sample) -> bool {
+        match (self, sample) {
+            (UnaryTest::Any, _) => true,
+            (UnaryTest::Eq(lit), sample) => lit.equals(sample),
+            (UnaryTest::Ne(lit), sample) => !lit.equals(sample),
+            (UnaryTest::Range { .. }, Sample::Number(n)) => self.contains(*n),
+            (UnaryTest::Range { .. }, _) => false,
+        }
+    }
+}
+
+/// Samples of one input column between which every test of the column gives every answer it
+/// can: each tested number with one between each pair and one beyond each end (integers for
+/// an int input), each tested string plus one matching none, and both booleans
+fn samples(tests: &[&UnaryTest], integral: bool) -> Vec<Sample> {
+    let mut points = vec![];
+    let (mut strings, mut booleans) = (vec![], false);
+    for test in tests {
+        match test {
+            UnaryTest::Any => {}
+            UnaryTest::Eq(lit) | UnaryTest::Ne(lit) => match lit {
+                Literal::Number(n) => points.push(*n),
+                Literal::Str(s) if !strings.contains(s) => strings.push(s.clone()),
+                Literal::Str(_) => {}
+                Literal::Bool(_) => booleans = true,
+            },
+            UnaryTest::Range { low, high } => points.extend([low, high].into_iter().flatten().map(|b| b.value)),
+        }
+    }
+    points.sort_by(f64::total_cmp);
+    points.dedup();
+
+    let mut numbers = vec![];
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        numbers.push(if integral { first.ceil() - 1.0 } else { first - 1.0 });
+        for (i, point) in points.iter().enumerate() {
+            if !integral || point.fract() == 0.0 {
+                numbers.push(*point);
+            }
+            if let Some(next) = points.get(i + 1) {
+                let between = if integral { point.floor() + 1.0 } else { (point + next) / 2.0 };
+                if between > *point && between < *next {
+                    numbers.push(between);
+                }
+            }
+        }
+        numbers.push(if integral { last.floor() + 1.0 } else { last + 1.0 });
+    }
+
+    let mut samples: Vec<Sample> = numbers.into_iter().map(Sample::Number).collect();
+    if booleans {
+        samples.extend([Sample::Bool(true), Sample::Bool(false)]);
+    }
+    let tests_strings = !strings.is_empty();
+    samples.extend(strings.into_iter().map(Sample::Str));
+    // A column tested only with `-` is covered by any one value
+    if tests_strings || samples.is_empty() {
+        samples.push(Sample::Other);
+    }
+    samples
+}
+
+/// Upper bound on the input combinations searched for a gap
+const MAX_GAP_SEARCH: usize = 100_000;
+
+/// First combination of sample values, one per leading column, that none of the `candidates`
+/// rules matches; the columns after it may take any value
+fn find_gap(columns: &[Vec<Sample>], rules: &[Vec<UnaryTest>], candidates: &[usize], prefix: &mut Vec<Sample>, budget: &mut usize) -> Result<Option<Vec<Sample>>, String> {
+    let column = prefix.len();
+    if candidates.is_empty() {
+        return Ok(Some(prefix.clone()));
+    }
+    // A rule testing none of the remaining columns matches whatever they hold
+    if candidates.iter().any(|&r| rules[r][column..].iter().all(|t| *t == UnaryTest::Any)) {
+        return Ok(None);
+    }
+    for sample in &columns[column] {
+        *budget = budget.checked_sub(1).ok_or("has too many input combinations to check that its rules cover them all")?;
+        let matching: Vec<usize> = candidates.iter().copied().filter(|&r| rules[r][column].matches(sample)).collect();
+        prefix.push(sample.clone());
+        if let Some(gap) = find_gap(columns, rules, &matching, prefix, budget)? {
+            return Ok(Some(gap));
+        }
+        prefix.pop();
+    }
+    Ok(None)
+}
+
+/// Input combination no rule matches, described as `input = value` per leading input, if any
+fn coverage_gap(definition: &WorkflowDefinition, node: &WorkflowNode, config: &DecisionTableConfig, rules: &[Vec<UnaryTest>]) -> Result<Option<String>, CompilerError> {
+    let columns: Vec<Vec<Sample>> = config.inputs.iter().enumerate()
+        .map(|(i, input)| {
+            let integral = definition.variables.iter()
+                .find(|v| &v.name == input)
+                .is_some_and(|v| VarType::parse(&v.var_type) == Ok(VarType::Int));
+            samples(&rules.iter().map(|r| &r[i]).collect::<Vec<_>>(), integral)
+        })
+        .collect();
+    let candidates: Vec<usize> = (0..rules.len()).collect();
+    let mut budget = MAX_GAP_SEARCH;
+    limits::checkpoint("decision table checks")?;
+    let gap = find_gap(&columns, rules, &candidates, &mut vec![], &mut budget).map_err(|e| {
+        CompilerError::ValidationError(Diagnostic::new(format!(
+            "Decision table '{}' {}; add a catch-all rule or default_output",
+            node.id, e
+        )).node(&node.id)).with_code("decision-table-gap")
+    })?;
+
+    Ok(gap.map(|gap| {
+        gap.iter().zip(&config.inputs).enumerate()
+            .map(|(i, (sample, input))| match sample {
+                Sample::Number(n) => format!("{} = {}", input, n),
+                Sample::Str(s) => format!("{} = {}", input, go_string_literal(s)),
+                Sample::Bool(b) => format!("{} = {}", input, b),
+                Sample::Other => {
+                    let tested: Vec<String> = columns[i].iter()
+                        .filter_map(|s| match s { Sample::Str(s) => Some(go_string_literal(s)), _ => None })
+                        .collect();
+                    if tested.is_empty() { format!("any {}", input) } else { format!("{} other than {}", input, tested.join(", ")) }
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }))
+}
+
+fn literal_to_go(lit: &Literal) -> String {
+    match lit {
+        Literal::Number(n) => n.to_string(),
+        Literal::Str(s) => go_string_literal(s),
+        Literal::Bool(b) => b.to_string(),
+    }
+}
+
+fn parse_rules(node: &WorkflowNode, config: &DecisionTableConfig) -> Result<Vec<Vec<UnaryTest>>, CompilerError> {
+    config.rules.iter().enumerate().map(|(i, rule)| {
+        if rule.when.len() != config.inputs.len() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Decision table '{}' rule {} has {} entries but {} inputs",
+                node.id, i + 1, rule.when.len(), config.inputs.len()
+            )).node(&node.id)));
+        }
+        rule.when.iter()
+            .map(|cell| parse_unary_test(cell).map_err(|e| CompilerError::ValidationError(Diagnostic::new(format!(
+                "Decision table '{}' rule {}: {}", node.id, i + 1, e
+            )).node(&node.id))))
+            .collect()
+    }).collect()
+}
+
+/// Types implied by the literals each input column is tested against, as
+/// `(input, var_type)` pairs; rules that fail to parse are skipped
+pub fn input_types(node: &WorkflowNode) -> Vec<(String, &'static str)> {
+    let Ok(config) = node.typed_config::<DecisionTableConfig>() else {
+        return vec![];
+    };
+
+    let mut types = Vec::new();
+    for rule in &config.rules {
+        for (input, cell) in config.inputs.iter().zip(&rule.when) {
+            let var_type = match parse_unary_test(cell) {
+                Ok(UnaryTest::Range { .. }) => "float",
+                Ok(UnaryTest::Eq(Literal::Number(n)) | UnaryTest::Ne(Literal::Number(n))) => {
+                    if n.fract() == 0.0 { "int" } else { "float" }
+                }
+                Ok(UnaryTest::Eq(Literal::Str(_)) | UnaryTest::Ne(Literal::Str(_))) => "string",
+                Ok(UnaryTest::Eq(Literal::Bool(_)) | UnaryTest::Ne(Literal::Bool(_))) => "bool",
+                Ok(UnaryTest::Any) | Err(_) => continue,
+            };
+            types.push((input.clone(), var_type));
+        }
+    }
+    types
+}
+
+/// Outcomes a table produces: each rule's, then the default output, without repeats
+pub fn outputs(config: &DecisionTableConfig) -> Vec<&str> {
+    let mut outputs: Vec<&str> = vec![];
+    for output in config.rules.iter().map(|r| r.then.as_str()).chain(config.default_output.as_deref()) {
+        if !outputs.contains(&output) {
+            outputs.push(output);
+        }
+    }
+    outputs
+}
+
+/// Outcome a flow edge out of a decision table selects, named by its condition or else its
+/// label; `None` for the else edge, which takes every outcome no other edge names
+pub fn edge_output(edge: &WorkflowEdge) -> Option<&str> {
+    [&edge.condition, &edge.label].into_iter().flatten().map(|s| s.trim()).find(|s| !s.is_empty())
+}
+
+/// Check that each outgoing flow edge names an outcome the table produces, once, and that
+/// every outcome has an edge to take: its own or the else edge
+fn validate_routing(definition: &WorkflowDefinition, node: &WorkflowNode, config: &DecisionTableConfig) -> Result<(), CompilerError> {
+    let outputs = outputs(config);
+    let error = |message: String| CompilerError::ValidationError(Diagnostic::new(message).node(&node.id)).with_code("decision-table-routing");
+    let mut named: Vec<&str> = vec![];
+    let mut else_edges = vec![];
+    for edge in graph::outgoing_edges(definition, &node.id).filter(|e| e.kind == EdgeKind::Flow) {
+        match edge_output(edge) {
+            Some(output) if !outputs.contains(&output) => return Err(error(format!(
+                "Decision table '{}' edge '{}' selects outcome '{}', which no rule or default_output produces",
+                node.id, edge.id, output
+            ))),
+            Some(output) if named.contains(&output) => return Err(error(format!(
+                "Decision table '{}' has more than one edge for outcome '{}'",
+                node.id, output
+            ))),
+            Some(output) => named.push(output),
+            None => else_edges.push(edge.id.as_str()),
+        }
+    }
+    if else_edges.len() > 1 {
+        return Err(error(format!(
+            "Decision table '{}' has more than one edge without a condition or label: {}",
+            node.id,
+            else_edges.join(", ")
+        )));
+    }
+    if else_edges.is_empty() && !named.is_empty() {
+        if let Some(missing) = outputs.iter().find(|o| !named.contains(o)) {
+            return Err(error(format!(
+                "Decision table '{}' outcome '{}' has no edge; label an edge with it or add one without a condition or label",
+                node.id, missing
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate table shape, input references, coverage of every input combination and rule
+/// overlap for the hit policy, and that its outgoing edges route every outcome
+pub fn validate(definition: &WorkflowDefinition, node: &WorkflowNode) -> Result<(), CompilerError> {
+    let config: DecisionTableConfig = node.typed_config()?;
+
+    if config.inputs.is_empty() || config.rules.is_empty() {
+        return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+            "Decision table '{}' must declare at least one input and one rule",
+            node.id
+        )).node(&node.id)));
+    }
+    for input in &config.inputs {
+        if !definition.variables.iter().any(|v| &v.name == input) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Decision table '{}' input '{}' is not a workflow variable",
+                node.id, input
+            )).node(&node.id)));
+        }
+    }
+
+    let rules = parse_rules(node, &config)?;
+
+    // Completeness: without a default output some rule must match every input combination
+    if config.default_output.is_none() {
+        if let Some(gap) = coverage_gap(definition, node, &config, &rules)? {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Decision table '{}' has no rule for {}; add one, a catch-all rule or default_output",
+                node.id, gap
+            )).node(&node.id)).with_code("decision-table-gap"));
+        }
+    }
+
+    // Overlap: unique tables forbid it, any tables require overlapping rules to agree
+    if config.hit_policy != HitPolicy::First {
+        for (i, a) in rules.iter().enumerate() {
+            limits::checkpoint("decision table checks")?;
+            for (j, b) in rules.iter().enumerate().skip(i + 1) {
+                let overlapping = a.iter().zip(b).all(|(x, y)| x.overlaps(y));
+                let conflicting = config.hit_policy == HitPolicy::Unique
+                    || config.rules[i].then != config.rules[j].then;
+                if overlapping && conflicting {
+                    return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                        "Decision table '{}' rules {} and {} overlap",
+                        node.id, i + 1, j + 1
+                    )).node(&node.id)).with_code("decision-table-overlap"));
+                }
+            }
+        }
+    }
+
+    validate_routing(definition, node, &config)
+}
+
+/// Name of the generated Go evaluation function for a decision table node
+pub fn function_name(node: &WorkflowNode) -> String {
+    format!("evaluate{}", to_pascal_case(&node.label))
+}
+
+/// Generate a package-level Go function evaluating the table against the workflow input
+pub fn generate_function(node: &WorkflowNode, workflow_name: &str) -> Result<String, CompilerError> {
+    let config: DecisionTableConfig = node.typed_config()?;
+    let rules = parse_rules(node, &config)?;
+    let function = function_name(node);
+    let policy = match config.hit_policy {
+        HitPolicy::Unique => "unique",
+        HitPolicy::First => "first",
+        HitPolicy::Any => "any",
+    };
+
+    let mut code = format!(
+        "// {function} evaluates the {} decision table (hit policy: {policy})\nfunc {function}(input {workflow_name}Input) string {{\n",
+        node.label
+    );
+    for (rule, tests) in config.rules.iter().zip(&rules) {
+        let condition = config.inputs.iter().zip(tests)
+            .filter(|(_, t)| **t != UnaryTest::Any)
+            .map(|(name, t)| format!("({})", t.to_go(&format!("input.{}", to_pascal_case(name)))))
+            .collect::<Vec<_>>();
+        let output = go_string_literal(&rule.then);
+        if condition.is_empty() {
+            code.push_str(&format!("    return {output}\n"));
+        } else {
+            code.push_str(&format!("    if {} {{\n        return {output}\n    }}\n", condition.join(" && ")));
+        }
+    }
+    code.push_str(&format!("    return {}\n}}\n\n", go_string_literal(config.default_output.as_deref().unwrap_or(""))));
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Validate a table over `inputs` (name and type) with a routed edge per outcome
+    fn check(inputs: &[(&str, &str)], rules: &[(&[&str], &str)], hit_policy: &str, default_output: Option<&str>) -> Result<(), (&'static str, String)> {
+        let mut outcomes: Vec<&str> = rules.iter().map(|(_, then)| *then).chain(default_output).collect();
+        outcomes.sort_unstable();
+        outcomes.dedup();
+        let definition: WorkflowDefinition = serde_json::from_value(json!({
+            "id": "3f8a9c1e-1111-4222-8333-444455556669",
+            "name": "table",
+            "version": "1.0.0",
+            "variables": inputs.iter().map(|(name, t)| json!({ "name": name, "var_type": t })).collect::<Vec<_>>(),
+            "triggers": [],
+            "nodes": [{
+                "id": "table",
+                "node_type": "decision_table",
+                "label": "Route",
+                "config": {
+                    "inputs": inputs.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+                    "rules": rules.iter().map(|(when, then)| json!({ "when": when, "then": then })).collect::<Vec<_>>(),
+                    "hit_policy": hit_policy,
+                    "default_output": default_output,
+                },
+                "position": { "x": 0, "y": 0 },
+            }],
+            "edges": outcomes.iter().enumerate()
+                .map(|(i, outcome)| json!({ "id": format!("e{i}"), "source": "table", "target": format!("n{i}"), "condition": outcome }))
+                .collect::<Vec<_>>(),
+        }))
+        .expect("definition deserializes");
+        validate(&definition, &definition.nodes[0]).map_err(|e| match e {
+            CompilerError::Rule { code, error } => (code, error.to_string()),
+            other => ("", other.to_string()),
+        })
+    }
+
+    #[test]
+    fn parses_unary_tests() {
+        let bound = |value, inclusive| Some(Bound { value, inclusive });
+        assert_eq!(parse_unary_test(" - ").unwrap(), UnaryTest::Any);
+        assert_eq!(parse_unary_test("").unwrap(), UnaryTest::Any);
+        assert_eq!(parse_unary_test("[1..10)").unwrap(), UnaryTest::Range { low: bound(1.0, true), high: bound(10.0, false) });
+        assert_eq!(parse_unary_test("]0..5]").unwrap(), UnaryTest::Range { low: bound(0.0, false), high: bound(5.0, true) });
+        assert_eq!(parse_unary_test(">= 2.5").unwrap(), UnaryTest::Range { low: bound(2.5, true), high: None });
+        assert_eq!(parse_unary_test("<3").unwrap(), UnaryTest::Range { low: None, high: bound(3.0, false) });
+        assert_eq!(parse_unary_test("!= \"gold\"").unwrap(), UnaryTest::Ne(Literal::Str("gold".to_string())));
+        assert_eq!(parse_unary_test("=7").unwrap(), UnaryTest::Eq(Literal::Number(7.0)));
+        assert_eq!(parse_unary_test("true").unwrap(), UnaryTest::Eq(Literal::Bool(true)));
+        assert_eq!(parse_unary_test("[a..3]").unwrap_err(), "expected a number in '[a..3]'");
+        assert_eq!(parse_unary_test("> x").unwrap_err(), "expected a number in '> x'");
+    }
+
+    #[test]
+    fn unique_tables_reject_overlapping_rules() {
+        let overlapping: &[(&[&str], &str)] = &[(&["[0..100]"], "low"), (&[">= 100"], "high")];
+        let (code, message) = check(&[("amount", "float")], overlapping, "unique", Some("low")).unwrap_err();
+        assert_eq!(code, "decision-table-overlap");
+        assert_eq!(message, "Validation error: Decision table 'table' rules 1 and 2 overlap");
+
+        let disjoint: &[(&[&str], &str)] = &[(&["[0..100)"], "low"), (&[">= 100"], "high"), (&["< 0"], "low")];
+        assert_eq!(check(&[("amount", "float")], disjoint, "unique", None), Ok(()));
+        assert!(check(&[("amount", "float")], overlapping, "first", Some("low")).is_ok());
+    }
+
+    #[test]
+    fn any_tables_allow_overlap_only_on_the_same_outcome() {
+        let inputs = [("tier", "string"), ("amount", "float")];
+        let agreeing: &[(&[&str], &str)] = &[(&["\"gold\"", "-"], "fast"), (&["-", "> 1000"], "fast")];
+        assert!(check(&inputs, agreeing, "any", Some("slow")).is_ok());
+
+        let conflicting: &[(&[&str], &str)] = &[(&["\"gold\"", "-"], "fast"), (&["-", "> 1000"], "review")];
+        assert_eq!(check(&inputs, conflicting, "any", Some("slow")).unwrap_err().0, "decision-table-overlap");
+
+        let other_tier: &[(&[&str], &str)] = &[(&["\"gold\"", "-"], "fast"), (&["!= \"gold\"", "> 1000"], "review")];
+        assert!(check(&inputs, other_tier, "any", Some("slow")).is_ok());
+    }
+
+    #[test]
+    fn gaps_name_an_uncovered_combination() {
+        let gap = |inputs: &[(&str, &str)], rules: &[(&[&str], &str)]| check(inputs, rules, "first", None).map_err(|(code, message)| {
+            assert_eq!(code, "decision-table-gap");
+            message
+        });
+        let closed: &[(&[&str], &str)] = &[(&["< 100"], "low"), (&[">= 100"], "high")];
+        assert!(gap(&[("amount", "float")], closed).is_ok());
+
+        let open: &[(&[&str], &str)] = &[(&["< 100"], "low"), (&["> 100"], "high")];
+        assert_eq!(
+            gap(&[("amount", "float")], open).unwrap_err(),
+            "Validation error: Decision table 'table' has no rule for amount = 100; add one, a catch-all rule or default_output"
+        );
+
+        let counts: &[(&[&str], &str)] = &[(&["< 1"], "none"), (&["1"], "one"), (&["2"], "two"), (&["> 2"], "many")];
+        assert!(gap(&[("count", "int")], counts).is_ok());
+        assert!(gap(&[("count", "float")], counts).unwrap_err().contains("count = 1.5"));
+
+        let flags: &[(&[&str], &str)] = &[(&["true", "\"gold\""], "fast"), (&["false", "-"], "slow")];
+        assert!(gap(&[("rush", "bool"), ("tier", "string")], flags).unwrap_err().contains("rush = true, tier other than \"gold\""));
+    }
+}
@@ -0,0 +1,185 @@
+//! Pinned Go dependency manifest for generated projects
+//!
+//! Versions and go.sum checksums are configured centrally through a JSON manifest
+//! named by `DEPENDENCY_MANIFEST`, so compiled projects build reproducibly offline.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compiler::cadence;
+use crate::compiler::sdk::SdkRelease;
+use crate::error::CompilerError;
+
+pub const TEMPORAL_SDK_MODULE: &str = "go.temporal.io/sdk";
+pub const TEMPORAL_API_MODULE: &str = "go.temporal.io/api";
+pub const TESTIFY_MODULE: &str = "github.com/stretchr/testify";
+pub const CADENCE_MODULE: &str = "go.uber.org/cadence";
+pub const YARPC_MODULE: &str = "go.uber.org/yarpc";
+pub const TESTIFY_VERSION: &str = "v1.11.1";
+
+/// go.sum lines shipped with the compiler for its default pins
+const BUILTIN_CHECKSUMS: &[&str] = &[
+    "github.com/stretchr/testify v1.11.1 h1:7s2iGBzp5EwR7/aIZr8ao5+dra3wiQyKjjFuvgVKu7U=",
+    "github.com/stretchr/testify v1.11.1/go.mod h1:wZwfW3scLgRK+23gO65QZefKpKQRnfz6sD981Nm4B6U=",
+];
+
+/// Centrally configured module pins and checksums
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DependencyManifest {
+    /// Overrides the `go` directive chosen from the SDK matrix
+    pub go: Option<String>,
+    /// Module path to version pins; the Temporal SDK itself follows the `temporal_sdk` compile option
+    pub modules: BTreeMap<String, String>,
+    /// go.sum lines (`<module> <version>[/go.mod] h1:<hash>`) for direct and transitive modules
+    pub checksums: Vec<String>,
+}
+
+/// A direct requirement of the generated module
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub path: String,
+    pub version: String,
+}
+
+impl DependencyManifest {
+    /// Load the manifest named by `DEPENDENCY_MANIFEST`, or the built-in pins when unset
+    pub fn load() -> Result<Self, CompilerError> {
+        match std::env::var("DEPENDENCY_MANIFEST") {
+            Ok(path) => Self::from_file(&path),
+            Err(_) => Ok(Self::builtin()),
+        }
+    }
+
+    /// Built-in pins with the checksums shipped for them
+    pub fn builtin() -> Self {
+        Self {
+            checksums: BUILTIN_CHECKSUMS.iter().map(|l| l.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, CompilerError> {
+        let raw = std::fs::read_to_string(path)?;
+        let manifest: Self = serde_json::from_str(&raw)
+            .map_err(|e| CompilerError::ParseError(format!("Invalid dependency manifest '{}': {}", path, e)))?;
+
+        if let Some(line) = manifest.checksums.iter().find(|l| parse_checksum(l).is_none()) {
+            return Err(CompilerError::ParseError(format!(
+                "Invalid checksum line in dependency manifest '{}': '{}'",
+                path, line
+            )));
+        }
+
+        Ok(manifest)
+    }
+
+    /// Direct requirements for the targeted SDK release, sorted by module path
+    pub fn requirements(&self, sdk: &SdkRelease) -> Vec<Requirement> {
+        let pinned = |path: &str, default: String| self.modules.get(path).cloned().unwrap_or(default);
+
+        let mut requirements = vec![
+            Requirement { path: TEMPORAL_SDK_MODULE.to_string(), version: format!("v{}", sdk.sdk) },
+            Requirement { path: TEMPORAL_API_MODULE.to_string(), version: pinned(TEMPORAL_API_MODULE, format!("v{}", sdk.api)) },
+            Requirement { path: TESTIFY_MODULE.to_string(), version: pinned(TESTIFY_MODULE, TESTIFY_VERSION.to_string()) },
+        ];
+        requirements.sort_by(|a, b| a.path.cmp(&b.path));
+        requirements
+    }
+
+    /// Direct requirements of the cadence target's module, sorted by module path
+    pub fn cadence_requirements(&self) -> Vec<Requirement> {
+        let pinned = |path: &str, default: String| self.modules.get(path).cloned().unwrap_or(default);
+
+        let mut requirements = vec![
+            Requirement { path: CADENCE_MODULE.to_string(), version: pinned(CADENCE_MODULE, format!("v{}", cadence::CADENCE_VERSION)) },
+            Requirement { path: YARPC_MODULE.to_string(), version: pinned(YARPC_MODULE, "v1.70.0".to_string()) },
+            Requirement { path: TESTIFY_MODULE.to_string(), version: pinned(TESTIFY_MODULE, TESTIFY_VERSION.to_string()) },
+        ];
+        requirements.sort_by(|a, b| a.path.cmp(&b.path));
+        requirements
+    }
+
+    /// `go` directive for the generated module
+    pub fn go_version<'a>(&'a self, sdk: &'a SdkRelease) -> &'a str {
+        self.go.as_deref().unwrap_or(sdk.go)
+    }
+}
+
+/// Split a go.sum line into module path and version (without the `/go.mod` suffix)
+fn parse_checksum(line: &str) -> Option<(&str, &str)> {
+    let mut parts = line.split_whitespace();
+    let (path, version, hash) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || !hash.starts_with("h1:") {
+        return None;
+    }
+    Some((path, version.trim_end_matches("/go.mod")))
+}
+
+/// Generate a go.mod pinning every direct requirement
+pub fn generate_go_mod(package_name: &str, go_version: &str, requirements: &[Requirement]) -> String {
+    let require: String = requirements.iter()
+        .map(|r| format!("    {} {}\n", r.path, r.version))
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+module {package_name}
+
+go {go_version}
+
+require (
+{require})
+"#)
+}
+
+/// Generate go.sum from the manifest checksums, returning warnings for direct
+/// requirements without a pinned checksum
+pub fn generate_go_sum(manifest: &DependencyManifest, requirements: &[Requirement]) -> (String, Vec<String>) {
+    // Checksums for other versions of a direct requirement are dropped
+    let mut lines: Vec<&str> = manifest.checksums.iter()
+        .map(String::as_str)
+        .filter(|line| match parse_checksum(line) {
+            Some((path, version)) => requirements.iter().all(|r| r.path != path || r.version == version),
+            None => false,
+        })
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+
+    let warnings = requirements.iter()
+        .filter(|r| !lines.iter().any(|l| parse_checksum(l) == Some((r.path.as_str(), r.version.as_str()))))
+        .map(|r| format!(
+            "No pinned checksum for {} {}; go.sum is incomplete and builds will need module proxy access",
+            r.path, r.version
+        ))
+        .collect();
+
+    let go_sum = lines.iter().map(|l| format!("{l}\n")).collect();
+    (go_sum, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::sdk;
+
+    #[test]
+    fn builtin_checksums_parse() {
+        assert!(BUILTIN_CHECKSUMS.iter().all(|l| parse_checksum(l).is_some()));
+    }
+
+    #[test]
+    fn builtin_go_sum_covers_testify_on_both_targets() {
+        let manifest = DependencyManifest::builtin();
+        let release = sdk::resolve(None).unwrap();
+        for requirements in [manifest.requirements(release), manifest.cadence_requirements()] {
+            let go_mod = generate_go_mod("example", manifest.go_version(release), &requirements);
+            let (go_sum, warnings) = generate_go_sum(&manifest, &requirements);
+            assert!(go_mod.contains(&format!("{TESTIFY_MODULE} {TESTIFY_VERSION}")));
+            assert!(go_sum.contains(&format!("{TESTIFY_MODULE} {TESTIFY_VERSION} h1:")));
+            assert!(go_sum.contains(&format!("{TESTIFY_MODULE} {TESTIFY_VERSION}/go.mod h1:")));
+            assert!(warnings.iter().all(|w| !w.contains(TESTIFY_MODULE)), "{warnings:?}");
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Registry of deprecated node types and config fields
+//!
+//! Deprecated constructs keep compiling; each use is reported with replacement guidance
+//! and the version it will be removed in. Entries come from the `deprecations` list in
+//! the compiler config, so they can be added with a config reload rather than a release.
+//! Once the configured `dsl_version` reaches a rule's removal version, uses of the construct
+//! fail validation instead.
+
+use serde::{Deserialize, Serialize};
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::config::CompilerConfig;
+use crate::error::CompilerError;
+use crate::WorkflowDefinition;
+
+/// A deprecated node type, or a config field of one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecationRule {
+    /// Node type in its serialized form, e.g. `wait_signal`
+    pub node_type: String,
+    /// Dotted config field path, e.g. `retry.max`; unset deprecates the whole node type
+    pub field: Option<String>,
+    pub replacement: String,
+    /// DSL version in which the construct stops compiling
+    pub removal_version: String,
+}
+
+/// One use of a deprecated construct
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprecationWarning {
+    pub node_id: String,
+    pub node_type: String,
+    pub field: Option<String>,
+    pub replacement: String,
+    pub removal_version: String,
+    pub message: String,
+}
+
+/// Report every use of a deprecated node type or config field in the definition
+pub fn check(definition: &WorkflowDefinition, rules: &[DeprecationRule]) -> Vec<DeprecationWarning> {
+    let mut warnings = Vec::new();
+    for node in &definition.nodes {
+        let node_type = serde_json::to_value(&node.node_type)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+
+        for rule in rules.iter().filter(|r| r.node_type == node_type) {
+            let subject = match &rule.field {
+                Some(field) => {
+                    let pointer = format!("/{}", field.replace('.', "/"));
+                    if node.config.pointer(&pointer).is_none() {
+                        continue;
+                    }
+                    format!("{} field '{}'", node_type, field)
+                }
+                None => format!("{} node", node_type),
+            };
+            warnings.push(DeprecationWarning {
+                node_id: node.id.clone(),
+                node_type: node_type.clone(),
+                field: rule.field.clone(),
+                replacement: rule.replacement.clone(),
+                removal_version: rule.removal_version.clone(),
+                message: format!(
+                    "Node '{}' uses deprecated {}; use {} instead before it is removed in {}",
+                    node.id, subject, rule.replacement, rule.removal_version
+                ),
+            });
+        }
+    }
+    warnings
+}
+
+/// Dotted numeric version such as `2` or `v2.1.0`; missing parts are zero
+pub fn parse_version(raw: &str) -> Option<[u32; 3]> {
+    let parts: Vec<&str> = raw.trim().trim_start_matches('v').split('.').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut numbers = [0u32; 3];
+    for (i, part) in parts.iter().enumerate() {
+        numbers[i] = part.parse().ok()?;
+    }
+    Some(numbers)
+}
+
+/// Reject uses of deprecated constructs whose removal version is the configured `dsl_version`
+/// or earlier
+pub fn check_removed(definition: &WorkflowDefinition, config: &CompilerConfig) -> Result<(), CompilerError> {
+    let Some(current) = config.dsl_version.as_deref().and_then(parse_version) else {
+        return Ok(());
+    };
+    let removed = check(definition, &config.deprecations).into_iter()
+        .find(|w| parse_version(&w.removal_version).is_some_and(|removal| removal <= current));
+    match removed {
+        Some(w) => Err(CompilerError::ValidationError(Diagnostic::new(format!(
+            "Node '{}' uses {}{}, removed in DSL version {}; use {} instead",
+            w.node_id,
+            w.node_type,
+            w.field.as_ref().map(|f| format!(" field '{}'", f)).unwrap_or_else(|| " node".to_string()),
+            w.removal_version,
+            w.replacement
+        )).node(&w.node_id))),
+        None => Ok(()),
+    }
+}
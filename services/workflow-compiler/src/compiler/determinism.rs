@@ -0,0 +1,112 @@
+//! Replay safety: constructs that would make the generated workflow code non-deterministic
+//!
+//! Temporal replays workflow code against its recorded history, so the code must compute the
+//! same values on every run. Transform expressions are evaluated in workflow code, so calls
+//! reading the wall-clock time, generating random values or making network requests fail
+//! validation, as do HttpCall nodes asking to run inline rather than as activities. The
+//! `{{...}}` templates of activity nodes build their inputs in workflow code too; the same
+//! calls there are reported as warnings, since the activity should compute the value itself.
+
+use serde_json::Value;
+
+use crate::compiler::codegen::is_activity_node;
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::validator::config_templates;
+use crate::error::CompilerError;
+use crate::{HttpCallConfig, NodeType, WorkflowDefinition};
+
+/// What a call does that differs between runs, if anything
+fn nondeterminism(call: &str) -> Option<&'static str> {
+    let lower = call.to_ascii_lowercase();
+    let head = lower.split('.').next().unwrap_or_default();
+    let last = lower.rsplit('.').next().unwrap_or_default();
+    if matches!(last, "now" | "utcnow" | "today" | "currenttimemillis" | "nanotime") || call == "Date" {
+        Some("reads the wall-clock time")
+    } else if matches!(head, "rand" | "random" | "crypto" | "uuid") || matches!(last, "random" | "randint" | "uuid" | "uuid4" | "randomuuid") {
+        Some("generates a random value")
+    } else if matches!(head, "http" | "https" | "axios" | "requests" | "urllib") || last == "fetch" {
+        Some("makes a network request")
+    } else {
+        None
+    }
+}
+
+/// Names of the functions an expression calls, outside string literals
+fn called_functions(expression: &str) -> Vec<&str> {
+    let mut calls = vec![];
+    let mut chars = expression.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                while let Some((_, ch)) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            chars.next();
+                        }
+                        ch if ch == c => break,
+                        _ => {}
+                    }
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, ch)) = chars.peek().filter(|(_, ch)| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.')) {
+                    end = i + ch.len_utf8();
+                    chars.next();
+                }
+                if expression[end..].trim_start().starts_with('(') {
+                    calls.push(&expression[start..end]);
+                }
+            }
+            _ => {}
+        }
+    }
+    calls
+}
+
+/// Non-deterministic calls in a node's config templates, naming the field and what each does
+fn nondeterministic_calls(config: &Value) -> Vec<String> {
+    let mut templates = vec![];
+    config_templates(config, "config".to_string(), &mut templates);
+    templates.into_iter()
+        .flat_map(|(field, template)| {
+            called_functions(template).into_iter()
+                .filter_map(move |call| nondeterminism(call).map(|what| format!("{}() in {}, which {}", call, field, what)))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Check that Transforms make no non-deterministic calls and that HttpCall nodes run as
+/// activities, returning a warning for each non-deterministic call in another node's templates
+pub fn check(definition: &WorkflowDefinition) -> Result<Vec<String>, CompilerError> {
+    let mut problems = vec![];
+    let mut first = None;
+    let mut warnings = vec![];
+    for node in &definition.nodes {
+        match node.node_type {
+            NodeType::Transform => {
+                for call in nondeterministic_calls(&node.config) {
+                    problems.push(format!("Transform node '{}' calls {}", node.id, call));
+                    first.get_or_insert(node.id.as_str());
+                }
+            }
+            NodeType::HttpCall if node.typed_config::<HttpCallConfig>()?.inline => {
+                problems.push(format!("HttpCall node '{}' is set to run inline, but network requests must run as activities", node.id));
+                first.get_or_insert(node.id.as_str());
+            }
+            _ if is_activity_node(node) => warnings.extend(nondeterministic_calls(&node.config).into_iter().map(|call| format!(
+                "Node '{}' calls {} while building its input in workflow code; compute the value in the activity so replays match the first run",
+                node.id, call
+            ))),
+            _ => {}
+        }
+    }
+    match first {
+        None => Ok(warnings),
+        Some(node) => Err(CompilerError::ValidationError(Diagnostic::new(format!(
+            "Workflow code would not replay deterministically: {}",
+            problems.join("; ")
+        )).node(node))),
+    }
+}
@@ -0,0 +1,74 @@
+//! Structured diagnostics locating validation failures in the graph
+//!
+//! Checks raise a `CompilerError::ValidationError` carrying a diagnostic with its message
+//! and, when a single node or edge is at fault, that element's ID. The rule code and
+//! severity are filled in from the rule that claimed the error, and the editor position
+//! from the definition, so API responses can highlight the offending element.
+
+use serde::Serialize;
+
+use crate::compiler::rules::Severity;
+use crate::{Position, WorkflowDefinition};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// Rule code, as listed in `compiler::rules::RULES`; absent for errors no rule claimed
+    pub code: Option<&'static str>,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge: Option<String>,
+    /// Editor position of the node, or of the edge's source node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { code: None, severity: Severity::Error, message: message.into(), node: None, edge: None, position: None }
+    }
+
+    /// Attribute the diagnostic to the node with `id`
+    pub fn node(mut self, id: &str) -> Self {
+        self.node = Some(id.to_string());
+        self
+    }
+
+    /// Attribute the diagnostic to the edge with `id`
+    pub fn edge(mut self, id: &str) -> Self {
+        self.edge = Some(id.to_string());
+        self
+    }
+
+    /// Fill in the position of the node at fault, or of the source of the edge at fault
+    pub fn locate(mut self, definition: &WorkflowDefinition) -> Self {
+        let node = self.node.clone().or_else(|| {
+            let edge = self.edge.as_deref()?;
+            definition.edges.iter().find(|e| e.id == edge).map(|e| e.source.clone())
+        });
+        self.position = node
+            .and_then(|id| definition.nodes.iter().find(|n| n.id == id))
+            .map(|n| n.position.clone());
+        self
+    }
+}
+
+impl From<String> for Diagnostic {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for Diagnostic {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
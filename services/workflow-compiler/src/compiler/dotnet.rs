@@ -0,0 +1,480 @@
+//! C# code generation on the `Temporalio` .NET SDK
+//!
+//! The workflow class, activity class, worker `Program.cs` and an xUnit test map onto the
+//! `CompiledWorkflow` code fields; the worker and test `.csproj` files are project files, with
+//! the test project under `tests/`. Node logic comes from the shared step traversal.
+
+use std::time::Duration;
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{go_string_literal as string_literal, VERSION_QUERY};
+use crate::compiler::steps::{self, ActivityCall, Retry, Step, TargetSources, Timeouts};
+use crate::compiler::types::base_type;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, ChildCancellationType, CodegenTarget, GeneratedFile, ParentClosePolicy, WorkflowDefinition};
+
+/// Temporalio .NET SDK release the generated project depends on
+pub const SDK_VERSION: &str = "1.3.0";
+
+/// Map a DSL variable type onto a nullable C# type
+fn cs_type(var_type: &str) -> &'static str {
+    match base_type(var_type) {
+        "string" => "string?",
+        "int" | "integer" => "long?",
+        "float" | "number" => "double?",
+        "bool" | "boolean" => "bool?",
+        "object" => "Dictionary<string, object?>?",
+        "array" => "List<object?>?",
+        _ => "object?",
+    }
+}
+
+/// PascalCase C# identifier for a name such as a variable or activity
+fn identifier(name: &str) -> String {
+    let mut ident: String = to_pascal_case(name).chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+fn time_span(duration: &Duration) -> String {
+    format!("TimeSpan.FromMilliseconds({})", duration.as_millis())
+}
+
+fn retry_policy(retry: &Retry, pad: &str) -> String {
+    format!(
+        "new RetryPolicy\n{pad}    {{\n{pad}        InitialInterval = {},\n{pad}        MaximumInterval = {},\n{pad}        BackoffCoefficient = {:?}F,\n{pad}        MaximumAttempts = {},\n{pad}    }}",
+        time_span(&retry.initial_interval), time_span(&retry.max_interval), retry.backoff_coefficient, retry.max_attempts
+    )
+}
+
+/// Activity options expression, with the node's timeouts and retry policy when it has them
+fn options(retry: &Option<Retry>, timeouts: &Timeouts, pad: &str) -> String {
+    if retry.is_none() && timeouts.is_empty() {
+        return "new ActivityOptions { StartToCloseTimeout = ActivityTimeout }".to_string();
+    }
+    let start_to_close = timeouts.start_to_close.as_ref().map_or("ActivityTimeout".to_string(), time_span);
+    let mut properties = format!("{pad}    StartToCloseTimeout = {start_to_close},\n");
+    if let Some(timeout) = &timeouts.schedule_to_close {
+        properties.push_str(&format!("{pad}    ScheduleToCloseTimeout = {},\n", time_span(timeout)));
+    }
+    if let Some(timeout) = &timeouts.heartbeat {
+        properties.push_str(&format!("{pad}    HeartbeatTimeout = {},\n", time_span(timeout)));
+    }
+    if let Some(retry) = retry {
+        properties.push_str(&format!("{pad}    RetryPolicy = {},\n", retry_policy(retry, pad)));
+    }
+    format!("new ActivityOptions\n{pad}{{\n{properties}{pad}}}")
+}
+
+/// `ExecuteActivityAsync` call for a registered name or a name expression
+fn execute_activity(name: &str, retry: &Option<Retry>, timeouts: &Timeouts, pad: &str) -> String {
+    format!("{pad}await Workflow.ExecuteActivityAsync({name}, new object?[] {{ input }}, {});\n", options(retry, timeouts, pad))
+}
+
+fn activity_call(call: &ActivityCall, pad: &str) -> String {
+    format!("{pad}// {}\n{}", call.label, execute_activity(&string_literal(&call.activity), &call.retry, &call.timeouts, pad))
+}
+
+fn render_step(step: &Step) -> String {
+    match step {
+        Step::Activity(call) => format!("{}\n", activity_call(call, "        ")),
+        Step::Timer { label, raw, duration } => {
+            format!("        // {label}: wait {raw}\n        await Workflow.DelayAsync({});\n\n", time_span(duration))
+        }
+        Step::Signals { label, signals, required, correlation_key, timeout } => {
+            let names = signals.iter().map(|s| string_literal(s)).collect::<Vec<_>>().join(", ");
+            let key = correlation_key.as_deref().map(|k| format!("input.{}?.ToString()", identifier(k))).unwrap_or_else(|| "null".to_string());
+            let count = signals.len();
+            let wait = match timeout {
+                Some(timeout) => format!(
+                    "            if (!await Workflow.WaitConditionAsync(Arrived, {}))\n            {{\n                throw new ApplicationFailureException({}, errorType: \"SignalTimeout\", nonRetryable: true);\n            }}\n",
+                    time_span(timeout),
+                    string_literal(&format!("{label} timed out waiting for signals"))
+                ),
+                None => "            await Workflow.WaitConditionAsync(Arrived);\n".to_string(),
+            };
+            format!(r#"        // Wait for signals: {label} ({required} of {count})
+        {{
+            var pending = new HashSet<string> {{ {names} }};
+            string? key = {key};
+            bool Arrived()
+            {{
+                pending.RemoveWhere(name => TakeSignal(name, key));
+                return {count} - pending.Count >= {required};
+            }}
+{wait}        }}
+
+"#)
+        }
+        Step::DynamicActivity { label, selector, allowed, retry, timeouts } => {
+            let allowed = allowed.iter().map(|a| string_literal(a)).collect::<Vec<_>>().join(", ");
+            let not_allowed = string_literal(&format!("{label}: activity not allowed: "));
+            format!(r#"        // Dynamic activity: {label}
+        {{
+            var activityName = input.{selector}?.ToString() ?? "";
+            if (!new HashSet<string> {{ {allowed} }}.Contains(activityName))
+            {{
+                throw new ApplicationFailureException({not_allowed} + activityName, errorType: "ActivityNotAllowed", nonRetryable: true);
+            }}
+{call}        }}
+
+"#, selector = identifier(selector), call = execute_activity("activityName", retry, timeouts, "            "))
+        }
+        Step::Child { label, workflow, task_queue, parent_close_policy, cancellation_type, wait_for_completion } => {
+            let close = match parent_close_policy {
+                ParentClosePolicy::Terminate => "Terminate",
+                ParentClosePolicy::RequestCancel => "RequestCancel",
+                ParentClosePolicy::Abandon => "Abandon",
+            };
+            let cancellation = match cancellation_type {
+                ChildCancellationType::TryCancel => "TryCancel",
+                ChildCancellationType::WaitCancellationCompleted => "WaitCancellationCompleted",
+                ChildCancellationType::Abandon => "Abandon",
+            };
+            let task_queue = task_queue.as_deref()
+                .map(|q| format!("            TaskQueue = {},\n", string_literal(q)))
+                .unwrap_or_default();
+            // StartChildWorkflowAsync returns once the child has started
+            let start = if *wait_for_completion { "ExecuteChildWorkflowAsync" } else { "StartChildWorkflowAsync" };
+            format!(r#"        // Child workflow: {label}
+        await Workflow.{start}({workflow}, Array.Empty<object?>(), new ChildWorkflowOptions
+        {{
+{task_queue}            ParentClosePolicy = ParentClosePolicy.{close},
+            CancellationType = ChildWorkflowCancellationType.{cancellation},
+        }});
+
+"#, workflow = string_literal(workflow))
+        }
+        Step::WeightedSplit { label, branches } => {
+            let weights = branches.iter()
+                .scan(0, |lower, (upper, _)| {
+                    let weight = upper - *lower;
+                    *lower = *upper;
+                    Some(weight.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let mut cases = String::new();
+            for (i, (upper, call)) in branches.iter().enumerate() {
+                let condition = if i + 1 == branches.len() {
+                    "            else\n".to_string()
+                } else if i == 0 {
+                    format!("            if (bucket < {upper})\n")
+                } else {
+                    format!("            else if (bucket < {upper})\n")
+                };
+                cases.push_str(&condition);
+                cases.push_str("            {\n");
+                cases.push_str(&activity_call(call, "                "));
+                cases.push_str("            }\n");
+            }
+            format!(r#"        // Weighted split: {label} ({weights})
+        {{
+            // Workflow.Random is seeded per execution, so replays pick the same bucket
+            var bucket = Workflow.Random.Next(100);
+{cases}        }}
+
+"#)
+        }
+    }
+}
+
+fn generate_workflow(definition: &WorkflowDefinition, steps: &[Step], workflow_name: &str, namespace: &str, fingerprint: &str) -> String {
+    let properties: String = definition.variables.iter()
+        .map(|v| format!(
+            "    [JsonPropertyName({})]\n    public {} {} {{ get; init; }}\n",
+            string_literal(&v.name), cs_type(&v.var_type), identifier(&v.name)
+        ))
+        .collect();
+
+    let signals = steps::signal_names(steps);
+    let (signal_types, signal_handlers) = if signals.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let handlers: String = signals.iter().enumerate()
+            .map(|(i, name)| format!(r#"
+    [WorkflowSignal({name})]
+    public Task Signal{i}Async(SignalPayload? payload)
+    {{
+        Buffer({name}, payload);
+        return Task.CompletedTask;
+    }}
+"#, name = string_literal(name)))
+            .collect();
+        (
+            r#"
+/// <summary>Envelope expected on every correlated signal</summary>
+public record SignalPayload
+{
+    [JsonPropertyName("correlation_key")]
+    public string? CorrelationKey { get; init; }
+
+    [JsonPropertyName("data")]
+    public object? Data { get; init; }
+}
+"#.to_string(),
+            format!(r#"
+    // Buffer signals from the start of the execution so none are missed before their wait
+    private readonly Dictionary<string, List<SignalPayload>> received = new();
+{handlers}
+    private void Buffer(string name, SignalPayload? payload)
+    {{
+        if (!received.TryGetValue(name, out var queue))
+        {{
+            queue = new List<SignalPayload>();
+            received[name] = queue;
+        }}
+        queue.Add(payload ?? new SignalPayload());
+    }}
+
+    private bool TakeSignal(string name, string? key)
+    {{
+        if (!received.TryGetValue(name, out var queue))
+        {{
+            return false;
+        }}
+        var index = queue.FindIndex(payload => key == null || payload.CorrelationKey == key);
+        if (index < 0)
+        {{
+            return false;
+        }}
+        queue.RemoveAt(index);
+        return true;
+    }}
+"#),
+        )
+    };
+
+    let body: String = steps.iter().map(render_step).collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+// DO NOT EDIT - This file is auto-generated
+
+using System.Text.Json.Serialization;
+using Temporalio.Common;
+using Temporalio.Exceptions;
+using Temporalio.Workflows;
+
+namespace {namespace};
+
+/// <summary>Workflow input</summary>
+public record {workflow_name}Input
+{{
+{properties}}}
+
+/// <summary>Workflow output</summary>
+public record {workflow_name}Output(bool Success, string Message);
+{signal_types}
+[Workflow("{workflow_name}")]
+public class {workflow_name}Workflow
+{{
+    /// <summary>Version of the workflow definition this code was compiled from</summary>
+    public const string DefinitionVersion = {version};
+
+    /// <summary>Content hash of that definition</summary>
+    public const string DefinitionFingerprint = {fingerprint};
+
+    private static readonly TimeSpan ActivityTimeout = TimeSpan.FromMinutes(10);
+{signal_handlers}
+    /// <summary>Reports the definition version a running execution was compiled from</summary>
+    [WorkflowQuery({query})]
+    public IReadOnlyDictionary<string, string> Version() => new Dictionary<string, string>
+    {{
+        ["version"] = DefinitionVersion,
+        ["fingerprint"] = DefinitionFingerprint,
+    }};
+
+    [WorkflowRun]
+    public async Task<{workflow_name}Output> RunAsync({workflow_name}Input input)
+    {{
+{body}        return new {workflow_name}Output(true, "Workflow completed successfully");
+    }}
+}}
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        query = string_literal(VERSION_QUERY),
+    )
+}
+
+fn generate_activities(steps: &[Step], workflow_name: &str, namespace: &str) -> String {
+    let methods: String = steps::activity_names(steps).iter()
+        .map(|name| format!(r#"
+    /// <summary>{name} implements the {name} activity</summary>
+    [Activity("{name}")]
+    public Task {method}Async({workflow_name}Input input)
+    {{
+        // TODO: implement {name}
+        return Task.CompletedTask;
+    }}
+"#, method = identifier(name)))
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+using Temporalio.Activities;
+
+namespace {namespace};
+
+public class {workflow_name}Activities
+{{{methods}}}
+"#)
+}
+
+fn generate_worker(workflow_name: &str, package_name: &str, namespace: &str) -> String {
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+using Temporalio.Client;
+using Temporalio.Worker;
+using {namespace};
+
+const string TaskQueue = "{package_name}-task-queue";
+
+var client = await TemporalClient.ConnectAsync(new(Environment.GetEnvironmentVariable("TEMPORAL_ADDRESS") ?? "localhost:7233")
+{{
+    Namespace = Environment.GetEnvironmentVariable("TEMPORAL_NAMESPACE") ?? "default",
+}});
+
+using var cancellation = new CancellationTokenSource();
+Console.CancelKeyPress += (_, eventArgs) =>
+{{
+    eventArgs.Cancel = true;
+    cancellation.Cancel();
+}};
+
+using var worker = new TemporalWorker(
+    client,
+    new TemporalWorkerOptions(TaskQueue)
+        .AddAllActivities(new {workflow_name}Activities())
+        .AddWorkflow<{workflow_name}Workflow>());
+
+try
+{{
+    await worker.ExecuteAsync(cancellation.Token);
+}}
+catch (OperationCanceledException)
+{{
+    Console.WriteLine("Worker stopped");
+}}
+"#)
+}
+
+fn generate_test(workflow_name: &str, package_name: &str, namespace: &str) -> String {
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+using Temporalio.Testing;
+using Temporalio.Worker;
+using Xunit;
+
+namespace {namespace}.Tests;
+
+public class {workflow_name}WorkflowTests
+{{
+    [Fact]
+    public async Task CompletesSuccessfully()
+    {{
+        const string taskQueue = "test-{package_name}";
+        await using var env = await WorkflowEnvironment.StartTimeSkippingAsync();
+        using var worker = new TemporalWorker(
+            env.Client,
+            new TemporalWorkerOptions(taskQueue)
+                .AddAllActivities(new {workflow_name}Activities())
+                .AddWorkflow<{workflow_name}Workflow>());
+
+        await worker.ExecuteAsync(async () =>
+        {{
+            var result = await env.Client.ExecuteWorkflowAsync(
+                ({workflow_name}Workflow wf) => wf.RunAsync(new {workflow_name}Input()),
+                new(id: "test-{package_name}", taskQueue: taskQueue));
+
+            Assert.True(result.Success);
+        }});
+    }}
+}}
+"#)
+}
+
+fn generate_project_files(workflow_name: &str, version: &str, namespace: &str) -> Vec<GeneratedFile> {
+    let version = version.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let project = format!(r#"<!-- Generated by OmniRoute Workflow Compiler -->
+<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <OutputType>Exe</OutputType>
+    <TargetFramework>net8.0</TargetFramework>
+    <ImplicitUsings>enable</ImplicitUsings>
+    <Nullable>enable</Nullable>
+    <RootNamespace>{namespace}</RootNamespace>
+    <Version>{version}</Version>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <!-- The test project compiles its own sources -->
+    <Compile Remove="tests/**" />
+  </ItemGroup>
+
+  <ItemGroup>
+    <PackageReference Include="Temporalio" Version="{SDK_VERSION}" />
+  </ItemGroup>
+
+</Project>
+"#);
+    let test_project = format!(r#"<!-- Generated by OmniRoute Workflow Compiler -->
+<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <ImplicitUsings>enable</ImplicitUsings>
+    <Nullable>enable</Nullable>
+    <IsPackable>false</IsPackable>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <PackageReference Include="Microsoft.NET.Test.Sdk" Version="17.10.0" />
+    <PackageReference Include="xunit" Version="2.8.1" />
+    <PackageReference Include="xunit.runner.visualstudio" Version="2.8.1" />
+  </ItemGroup>
+
+  <ItemGroup>
+    <ProjectReference Include="../../{workflow_name}.csproj" />
+  </ItemGroup>
+
+</Project>
+"#);
+
+    vec![
+        GeneratedFile { path: format!("{workflow_name}.csproj"), content: project },
+        GeneratedFile { path: format!("tests/{workflow_name}.Tests/{workflow_name}.Tests.csproj"), content: test_project },
+    ]
+}
+
+/// .NET backend
+pub struct Dotnet;
+
+impl Backend for Dotnet {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Dotnet
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let steps = steps::workflow_steps(definition, self.target().name())?;
+        let workflow_name = to_pascal_case(&definition.name);
+        let namespace = format!("OmniRoute.Workflows.{}", identifier(package_name));
+
+        Ok(TargetSources {
+            workflow_code: generate_workflow(definition, &steps, &workflow_name, &namespace, fingerprint),
+            activity_code: generate_activities(&steps, &workflow_name, &namespace),
+            worker_code: generate_worker(&workflow_name, package_name, &namespace),
+            test_code: generate_test(&workflow_name, package_name, &namespace),
+            files: generate_project_files(&workflow_name, &definition.version, &namespace),
+            signals: steps::signal_names(&steps),
+            metadata: None,
+        })
+    }
+}
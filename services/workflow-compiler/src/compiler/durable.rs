@@ -0,0 +1,806 @@
+//! Azure Durable Functions code generation, in C# on the isolated worker or in JavaScript on
+//! the v4 Node.js programming model
+//!
+//! The orchestrator (with its HTTP starter), activity functions, host entry point and a unit
+//! test map onto the `CompiledWorkflow` code fields; `host.json`, `local.settings.json` and the
+//! project manifest are project files. The JavaScript code fields belong at
+//! `src/functions/orchestrator.js`, `src/functions/activities.js`, `src/functions/httpStart.js`
+//! and `test/orchestrator.test.js`.
+//!
+//! `WaitTimer` becomes a durable timer and signal waits become external events, which the
+//! Durable Task framework buffers per instance, so an event raised before its wait is not lost.
+//! Durable Functions has no queries: the orchestrator publishes the definition version and
+//! fingerprint as its custom status instead. Child workflows on another task queue or that do
+//! not wait for completion have no Durable equivalent and are rejected.
+
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{go_string_literal as string_literal, is_go_identifier};
+use crate::compiler::steps::{self, ActivityCall, Retry, Step, TargetSources};
+use crate::compiler::types::base_type;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, CodegenTarget, GeneratedFile, WorkflowDefinition};
+
+/// `Microsoft.Azure.Functions.Worker.Extensions.DurableTask` release the C# project depends on
+pub const CSHARP_SDK_VERSION: &str = "1.1.4";
+
+/// `durable-functions` npm release the JavaScript project depends on
+pub const JAVASCRIPT_SDK_VERSION: &str = "3.1.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    CSharp,
+    JavaScript,
+}
+
+/// Azure Durable Functions backend for one language
+pub struct Durable {
+    language: Language,
+}
+
+pub static CSHARP: Durable = Durable { language: Language::CSharp };
+pub static JAVASCRIPT: Durable = Durable { language: Language::JavaScript };
+
+/// Reject child workflow options Durable sub-orchestrations cannot express
+fn check_steps(steps: &[Step], target: &str) -> Result<(), CompilerError> {
+    for step in steps {
+        if let Step::Child { label, task_queue, wait_for_completion, .. } = step {
+            if task_queue.is_some() {
+                return Err(CompilerError::CodeGenError(format!(
+                    "Child workflow '{}': task queues are not supported by the {} target",
+                    label, target
+                )));
+            }
+            if !wait_for_completion {
+                return Err(CompilerError::CodeGenError(format!(
+                    "Child workflow '{}': children that do not wait for completion are not supported by the {} target",
+                    label, target
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn weights(branches: &[(u32, ActivityCall)]) -> String {
+    branches.iter()
+        .scan(0, |lower, (upper, _)| {
+            let weight = upper - *lower;
+            *lower = *upper;
+            Some(weight.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// =============================================================================
+// C# on the isolated worker
+// =============================================================================
+
+fn cs_type(var_type: &str) -> &'static str {
+    match base_type(var_type) {
+        "string" => "string?",
+        "int" | "integer" => "long?",
+        "float" | "number" => "double?",
+        "bool" | "boolean" => "bool?",
+        "object" => "Dictionary<string, object?>?",
+        "array" => "List<object?>?",
+        _ => "object?",
+    }
+}
+
+/// PascalCase C# identifier for a name such as a variable or activity
+fn cs_identifier(name: &str) -> String {
+    let mut ident: String = to_pascal_case(name).chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+fn time_span(duration: &Duration) -> String {
+    format!("TimeSpan.FromMilliseconds({})", duration.as_millis())
+}
+
+/// `TaskOptions` argument for a node's retry policy; Temporal's unlimited zero becomes `int.MaxValue`
+fn cs_task_options(retry: &Option<Retry>) -> String {
+    let Some(retry) = retry else { return String::new() };
+    let attempts = if retry.max_attempts == 0 { "int.MaxValue".to_string() } else { retry.max_attempts.to_string() };
+    format!(
+        ", TaskOptions.FromRetryPolicy(new RetryPolicy(maxNumberOfAttempts: {}, firstRetryInterval: {}, backoffCoefficient: {:?}, maxRetryInterval: {}))",
+        attempts, time_span(&retry.initial_interval), retry.backoff_coefficient, time_span(&retry.max_interval)
+    )
+}
+
+fn cs_call_activity(name: &str, retry: &Option<Retry>, pad: &str) -> String {
+    format!("{pad}await context.CallActivityAsync({name}, input{});\n", cs_task_options(retry))
+}
+
+fn cs_activity_call(call: &ActivityCall, pad: &str) -> String {
+    format!("{pad}// {}\n{}", call.label, cs_call_activity(&string_literal(&call.activity), &call.retry, pad))
+}
+
+fn cs_render_step(step: &Step) -> String {
+    match step {
+        Step::Activity(call) => format!("{}\n", cs_activity_call(call, "        ")),
+        Step::Timer { label, raw, duration } => format!(
+            "        // {label}: durable timer for {raw}\n        await context.CreateTimer(context.CurrentUtcDateTime.Add({}), CancellationToken.None);\n\n",
+            time_span(duration)
+        ),
+        Step::Signals { label, signals, required, correlation_key, timeout } => {
+            let waits = signals.iter()
+                .map(|s| format!("[{name}] = context.WaitForExternalEvent<SignalPayload?>({name})", name = string_literal(s)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let key = correlation_key.as_deref().map(|k| format!("input.{}?.ToString()", cs_identifier(k))).unwrap_or_else(|| "null".to_string());
+            let count = signals.len();
+            let (deadline, tasks, expired, cancel) = match timeout {
+                Some(timeout) => (
+                    format!(
+                        "            using var timeout = new CancellationTokenSource();\n            var deadline = context.CreateTimer(context.CurrentUtcDateTime.Add({}), timeout.Token);\n",
+                        time_span(timeout)
+                    ),
+                    "pending.Values.Append<Task>(deadline)",
+                    format!(
+                        "                if (winner == deadline)\n                {{\n                    throw new TimeoutException({});\n                }}\n",
+                        string_literal(&format!("{label} timed out waiting for signals"))
+                    ),
+                    "            timeout.Cancel();\n",
+                ),
+                None => (String::new(), "pending.Values", String::new(), ""),
+            };
+            format!(r#"        // Wait for external events: {label} ({required} of {count})
+        {{
+            var pending = new Dictionary<string, Task<SignalPayload?>> {{ {waits} }};
+            string? key = {key};
+{deadline}            var arrived = 0;
+            while (arrived < {required})
+            {{
+                Task winner = await Task.WhenAny({tasks});
+{expired}                var name = pending.First(entry => entry.Value == winner).Key;
+                var payload = await pending[name];
+                if (key == null || payload?.CorrelationKey == key)
+                {{
+                    pending.Remove(name);
+                    arrived++;
+                }}
+                else
+                {{
+                    // Wrong correlation key: wait for the next event of the same name
+                    pending[name] = context.WaitForExternalEvent<SignalPayload?>(name);
+                }}
+            }}
+{cancel}        }}
+
+"#)
+        }
+        Step::DynamicActivity { label, selector, allowed, retry, .. } => {
+            let allowed = allowed.iter().map(|a| string_literal(a)).collect::<Vec<_>>().join(", ");
+            let not_allowed = string_literal(&format!("{label}: activity not allowed: "));
+            format!(r#"        // Dynamic activity: {label}
+        {{
+            var activityName = input.{selector}?.ToString() ?? "";
+            if (!new HashSet<string> {{ {allowed} }}.Contains(activityName))
+            {{
+                throw new InvalidOperationException({not_allowed} + activityName);
+            }}
+{call}        }}
+
+"#, selector = cs_identifier(selector), call = cs_call_activity("activityName", retry, "            "))
+        }
+        Step::Child { label, workflow, .. } => format!(
+            "        // Sub-orchestration: {label}\n        await context.CallSubOrchestratorAsync({});\n\n",
+            string_literal(workflow)
+        ),
+        Step::WeightedSplit { label, branches } => {
+            let mut cases = String::new();
+            for (i, (upper, call)) in branches.iter().enumerate() {
+                let condition = if i + 1 == branches.len() {
+                    "            else\n".to_string()
+                } else if i == 0 {
+                    format!("            if (bucket < {upper})\n")
+                } else {
+                    format!("            else if (bucket < {upper})\n")
+                };
+                cases.push_str(&condition);
+                cases.push_str("            {\n");
+                cases.push_str(&cs_activity_call(call, "                "));
+                cases.push_str("            }\n");
+            }
+            format!(r#"        // Weighted split: {label} ({weights})
+        {{
+            // NewGuid is deterministic per instance, so replays pick the same bucket
+            var bucket = (int)(BitConverter.ToUInt32(context.NewGuid().ToByteArray(), 0) % 100);
+{cases}        }}
+
+"#, weights = weights(branches))
+        }
+    }
+}
+
+fn cs_orchestrator(definition: &WorkflowDefinition, steps: &[Step], workflow_name: &str, namespace: &str, fingerprint: &str) -> String {
+    let properties: String = definition.variables.iter()
+        .map(|v| format!(
+            "    [JsonPropertyName({})]\n    public {} {} {{ get; init; }}\n",
+            string_literal(&v.name), cs_type(&v.var_type), cs_identifier(&v.name)
+        ))
+        .collect();
+
+    let signal_types = if steps::signal_names(steps).is_empty() {
+        ""
+    } else {
+        r#"
+/// <summary>Envelope expected on every correlated external event</summary>
+public record SignalPayload
+{
+    [JsonPropertyName("correlation_key")]
+    public string? CorrelationKey { get; init; }
+
+    [JsonPropertyName("data")]
+    public object? Data { get; init; }
+}
+"#
+    };
+
+    let body: String = steps.iter().map(cs_render_step).collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+// DO NOT EDIT - This file is auto-generated
+
+using System.Text.Json.Serialization;
+using Microsoft.Azure.Functions.Worker;
+using Microsoft.Azure.Functions.Worker.Http;
+using Microsoft.DurableTask;
+using Microsoft.DurableTask.Client;
+
+namespace {namespace};
+
+/// <summary>Orchestration input</summary>
+public record {workflow_name}Input
+{{
+{properties}}}
+
+/// <summary>Orchestration output</summary>
+public record {workflow_name}Output(bool Success, string Message);
+{signal_types}
+public static class {workflow_name}Orchestrator
+{{
+    /// <summary>Version of the workflow definition this code was compiled from</summary>
+    public const string DefinitionVersion = {version};
+
+    /// <summary>Content hash of that definition</summary>
+    public const string DefinitionFingerprint = {fingerprint};
+
+    [Function("{workflow_name}")]
+    public static async Task<{workflow_name}Output> RunOrchestrator([OrchestrationTrigger] TaskOrchestrationContext context)
+    {{
+        var input = context.GetInput<{workflow_name}Input>() ?? new {workflow_name}Input();
+        // Reports the definition version a running instance was compiled from
+        context.SetCustomStatus(new Dictionary<string, string>
+        {{
+            ["version"] = DefinitionVersion,
+            ["fingerprint"] = DefinitionFingerprint,
+        }});
+
+{body}        return new {workflow_name}Output(true, "Workflow completed successfully");
+    }}
+
+    [Function("{workflow_name}_HttpStart")]
+    public static async Task<HttpResponseData> HttpStart(
+        [HttpTrigger(AuthorizationLevel.Function, "post")] HttpRequestData req,
+        [DurableClient] DurableTaskClient client)
+    {{
+        var input = await req.ReadFromJsonAsync<{workflow_name}Input>() ?? new {workflow_name}Input();
+        var instanceId = await client.ScheduleNewOrchestrationInstanceAsync("{workflow_name}", input);
+        return await client.CreateCheckStatusResponseAsync(req, instanceId);
+    }}
+}}
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+    )
+}
+
+fn cs_activities(steps: &[Step], workflow_name: &str, namespace: &str) -> String {
+    let functions: String = steps::activity_names(steps).iter()
+        .map(|name| format!(r#"
+    /// <summary>{name} implements the {name} activity</summary>
+    [Function("{name}")]
+    public static Task {method}Async([ActivityTrigger] {workflow_name}Input input, FunctionContext executionContext)
+    {{
+        // TODO: implement {name}
+        return Task.CompletedTask;
+    }}
+"#, method = cs_identifier(name)))
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+using Microsoft.Azure.Functions.Worker;
+
+namespace {namespace};
+
+public static class {workflow_name}Activities
+{{{functions}}}
+"#)
+}
+
+fn cs_program() -> String {
+    r#"// Generated by OmniRoute Workflow Compiler
+
+using Microsoft.Azure.Functions.Worker;
+using Microsoft.Extensions.Hosting;
+
+var host = new HostBuilder()
+    .ConfigureFunctionsWorkerDefaults()
+    .Build();
+
+host.Run();
+"#.to_string()
+}
+
+fn cs_test(workflow_name: &str, namespace: &str) -> String {
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+using Microsoft.DurableTask;
+using Moq;
+using Xunit;
+
+namespace {namespace}.Tests;
+
+public class {workflow_name}OrchestratorTests
+{{
+    [Fact]
+    public async Task CompletesSuccessfully()
+    {{
+        // A loose mock completes every activity, timer and external event immediately
+        var context = new Mock<TaskOrchestrationContext>();
+        context.Setup(c => c.GetInput<{workflow_name}Input>()).Returns(new {workflow_name}Input());
+
+        var result = await {workflow_name}Orchestrator.RunOrchestrator(context.Object);
+
+        Assert.True(result.Success);
+    }}
+}}
+"#)
+}
+
+fn cs_project_files(workflow_name: &str, version: &str, namespace: &str) -> Vec<GeneratedFile> {
+    let version = version.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let project = format!(r#"<!-- Generated by OmniRoute Workflow Compiler -->
+<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <AzureFunctionsVersion>v4</AzureFunctionsVersion>
+    <OutputType>Exe</OutputType>
+    <ImplicitUsings>enable</ImplicitUsings>
+    <Nullable>enable</Nullable>
+    <RootNamespace>{namespace}</RootNamespace>
+    <Version>{version}</Version>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <!-- The test project compiles its own sources -->
+    <Compile Remove="tests/**" />
+  </ItemGroup>
+
+  <ItemGroup>
+    <PackageReference Include="Microsoft.Azure.Functions.Worker" Version="1.22.0" />
+    <PackageReference Include="Microsoft.Azure.Functions.Worker.Extensions.DurableTask" Version="{CSHARP_SDK_VERSION}" />
+    <PackageReference Include="Microsoft.Azure.Functions.Worker.Extensions.Http" Version="3.2.0" />
+    <PackageReference Include="Microsoft.Azure.Functions.Worker.Sdk" Version="1.17.2" />
+  </ItemGroup>
+
+  <ItemGroup>
+    <None Update="host.json">
+      <CopyToOutputDirectory>PreserveNewest</CopyToOutputDirectory>
+    </None>
+    <None Update="local.settings.json">
+      <CopyToOutputDirectory>PreserveNewest</CopyToOutputDirectory>
+      <CopyToPublishDirectory>Never</CopyToPublishDirectory>
+    </None>
+  </ItemGroup>
+
+</Project>
+"#);
+    let test_project = format!(r#"<!-- Generated by OmniRoute Workflow Compiler -->
+<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <ImplicitUsings>enable</ImplicitUsings>
+    <Nullable>enable</Nullable>
+    <IsPackable>false</IsPackable>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <PackageReference Include="Microsoft.NET.Test.Sdk" Version="17.10.0" />
+    <PackageReference Include="Moq" Version="4.20.70" />
+    <PackageReference Include="xunit" Version="2.8.1" />
+    <PackageReference Include="xunit.runner.visualstudio" Version="2.8.1" />
+  </ItemGroup>
+
+  <ItemGroup>
+    <ProjectReference Include="../../{workflow_name}.csproj" />
+  </ItemGroup>
+
+</Project>
+"#);
+
+    vec![
+        GeneratedFile { path: format!("{workflow_name}.csproj"), content: project },
+        GeneratedFile { path: format!("tests/{workflow_name}.Tests/{workflow_name}.Tests.csproj"), content: test_project },
+    ]
+}
+
+// =============================================================================
+// JavaScript on the v4 Node.js programming model
+// =============================================================================
+
+fn js_input_field(name: &str) -> String {
+    if is_go_identifier(name) {
+        format!("input.{name}")
+    } else {
+        format!("input[{}]", string_literal(name))
+    }
+}
+
+/// `RetryOptions` expression; Temporal's unlimited zero becomes `Number.MAX_SAFE_INTEGER`
+fn js_retry_options(retry: &Retry) -> String {
+    let attempts = if retry.max_attempts == 0 { "Number.MAX_SAFE_INTEGER".to_string() } else { retry.max_attempts.to_string() };
+    format!(
+        "retryOptions({}, {}, {}, {})",
+        retry.initial_interval.as_millis(), attempts, retry.backoff_coefficient, retry.max_interval.as_millis()
+    )
+}
+
+fn js_call_activity(name: &str, retry: &Option<Retry>, pad: &str) -> String {
+    match retry {
+        Some(retry) => format!("{pad}yield context.df.callActivityWithRetry({name}, {}, input);\n", js_retry_options(retry)),
+        None => format!("{pad}yield context.df.callActivity({name}, input);\n"),
+    }
+}
+
+fn js_activity_call(call: &ActivityCall, pad: &str) -> String {
+    format!("{pad}// {}\n{}", call.label, js_call_activity(&string_literal(&call.activity), &call.retry, pad))
+}
+
+fn js_render_step(step: &Step) -> String {
+    match step {
+        Step::Activity(call) => format!("{}\n", js_activity_call(call, "  ")),
+        Step::Timer { label, raw, duration } => format!(
+            "  // {label}: durable timer for {raw}\n  yield context.df.createTimer(new Date(context.df.currentUtcDateTime.getTime() + {}));\n\n",
+            duration.as_millis()
+        ),
+        Step::Signals { label, signals, required, correlation_key, timeout } => {
+            let names = signals.iter().map(|s| string_literal(s)).collect::<Vec<_>>().join(", ");
+            let key = correlation_key.as_deref()
+                .map(|k| format!("{field} == null ? null : String({field})", field = js_input_field(k)))
+                .unwrap_or_else(|| "null".to_string());
+            let count = signals.len();
+            let (deadline, tasks, expired, cancel) = match timeout {
+                Some(timeout) => (
+                    format!(
+                        "    const deadline = context.df.createTimer(new Date(context.df.currentUtcDateTime.getTime() + {}));\n",
+                        timeout.as_millis()
+                    ),
+                    "[...pending.values(), deadline]",
+                    format!(
+                        "      if (winner === deadline) {{\n        throw new Error({});\n      }}\n",
+                        string_literal(&format!("{label} timed out waiting for signals"))
+                    ),
+                    "    deadline.cancel();\n",
+                ),
+                None => (String::new(), "[...pending.values()]", String::new(), ""),
+            };
+            format!(r#"  // Wait for external events: {label} ({required} of {count})
+  {{
+    const pending = new Map([{names}].map((name) => [name, context.df.waitForExternalEvent(name)]));
+    const key = {key};
+{deadline}    let arrived = 0;
+    while (arrived < {required}) {{
+      const winner = yield context.df.Task.any({tasks});
+{expired}      const [name] = [...pending].find(([, task]) => task === winner);
+      if (key === null || (winner.result && winner.result.correlation_key === key)) {{
+        pending.delete(name);
+        arrived += 1;
+      }} else {{
+        // Wrong correlation key: wait for the next event of the same name
+        pending.set(name, context.df.waitForExternalEvent(name));
+      }}
+    }}
+{cancel}  }}
+
+"#)
+        }
+        Step::DynamicActivity { label, selector, allowed, retry, .. } => {
+            let allowed = allowed.iter().map(|a| string_literal(a)).collect::<Vec<_>>().join(", ");
+            let not_allowed = string_literal(&format!("{label}: activity not allowed: "));
+            format!(r#"  // Dynamic activity: {label}
+  {{
+    const activityName = String({selector} ?? '');
+    if (![{allowed}].includes(activityName)) {{
+      throw new Error({not_allowed} + activityName);
+    }}
+{call}  }}
+
+"#, selector = js_input_field(selector), call = js_call_activity("activityName", retry, "    "))
+        }
+        Step::Child { label, workflow, .. } => format!(
+            "  // Sub-orchestration: {label}\n  yield context.df.callSubOrchestrator({});\n\n",
+            string_literal(workflow)
+        ),
+        Step::WeightedSplit { label, branches } => {
+            let mut cases = String::new();
+            for (i, (upper, call)) in branches.iter().enumerate() {
+                let condition = if i + 1 == branches.len() {
+                    " else {\n".to_string()
+                } else if i == 0 {
+                    format!("    if (bucket < {upper}) {{\n")
+                } else {
+                    format!(" else if (bucket < {upper}) {{\n")
+                };
+                cases.push_str(&condition);
+                cases.push_str(&js_activity_call(call, "      "));
+                cases.push_str("    }");
+            }
+            format!(r#"  // Weighted split: {label} ({weights})
+  {{
+    // newGuid is deterministic per instance, so replays pick the same bucket
+    const bucket = parseInt(context.df.newGuid(context.df.instanceId).slice(0, 8), 16) % 100;
+{cases}
+  }}
+
+"#, weights = weights(branches))
+        }
+    }
+}
+
+fn js_orchestrator(definition: &WorkflowDefinition, steps: &[Step], workflow_name: &str, fingerprint: &str) -> String {
+    let retry_helper = if steps.iter().any(has_retry) {
+        r#"
+function retryOptions(firstRetryIntervalMs, maxAttempts, backoffCoefficient, maxRetryIntervalMs) {
+  const options = new df.RetryOptions(firstRetryIntervalMs, maxAttempts);
+  options.backoffCoefficient = backoffCoefficient;
+  options.maxRetryIntervalInMilliseconds = maxRetryIntervalMs;
+  return options;
+}
+"#
+    } else {
+        ""
+    };
+    let body: String = steps.iter().map(js_render_step).collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+// DO NOT EDIT - This file is auto-generated
+
+const df = require('durable-functions');
+
+/** Version of the workflow definition this code was compiled from */
+const DEFINITION_VERSION = {version};
+
+/** Content hash of that definition */
+const DEFINITION_FINGERPRINT = {fingerprint};
+{retry_helper}
+function* orchestrator(context) {{
+  const input = context.df.getInput() || {{}};
+  // Reports the definition version a running instance was compiled from
+  context.df.setCustomStatus({{ version: DEFINITION_VERSION, fingerprint: DEFINITION_FINGERPRINT }});
+
+{body}  return {{ success: true, message: 'Workflow completed successfully' }};
+}}
+
+df.app.orchestration({name}, orchestrator);
+
+module.exports = {{ orchestrator, DEFINITION_VERSION, DEFINITION_FINGERPRINT }};
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        name = string_literal(workflow_name),
+    )
+}
+
+fn has_retry(step: &Step) -> bool {
+    match step {
+        Step::Activity(call) => call.retry.is_some(),
+        Step::DynamicActivity { retry, .. } => retry.is_some(),
+        Step::WeightedSplit { branches, .. } => branches.iter().any(|(_, call)| call.retry.is_some()),
+        _ => false,
+    }
+}
+
+fn js_activities(steps: &[Step]) -> String {
+    let functions: String = steps::activity_names(steps).iter()
+        .map(|name| format!(r#"
+/** {name} implements the {name} activity */
+df.app.activity({literal}, {{
+  handler: async (input, context) => {{
+    // TODO: implement {name}
+  }},
+}});
+"#, literal = string_literal(name)))
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+const df = require('durable-functions');
+{functions}"#)
+}
+
+fn js_http_start(workflow_name: &str) -> String {
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+const {{ app }} = require('@azure/functions');
+const df = require('durable-functions');
+
+app.http('{workflow_name}HttpStart', {{
+  route: 'orchestrators/{workflow_name}',
+  methods: ['POST'],
+  extraInputs: [df.input.durableClient()],
+  handler: async (request, context) => {{
+    const client = df.getClient(context);
+    const input = await request.json().catch(() => ({{}}));
+    const instanceId = await client.startNew('{workflow_name}', {{ input }});
+    context.log(`Started orchestration with ID = '${{instanceId}}'`);
+    return client.createCheckStatusResponse(request, instanceId);
+  }},
+}});
+"#)
+}
+
+fn js_test() -> String {
+    r#"// Generated by OmniRoute Workflow Compiler
+
+const test = require('node:test');
+const assert = require('node:assert');
+
+const { orchestrator } = require('../src/functions/orchestrator');
+
+// Completes every activity, timer and external event immediately
+function fakeContext(input) {
+  const task = (result) => ({ result, cancel() {} });
+  return {
+    df: {
+      instanceId: 'test-instance',
+      currentUtcDateTime: new Date(0),
+      getInput: () => input,
+      setCustomStatus() {},
+      newGuid: () => '00000000-0000-0000-0000-000000000000',
+      callActivity: () => task(undefined),
+      callActivityWithRetry: () => task(undefined),
+      callSubOrchestrator: () => task(undefined),
+      createTimer: () => task(undefined),
+      waitForExternalEvent: () => task({}),
+      Task: { any: (tasks) => tasks[0] },
+    },
+  };
+}
+
+test('completes successfully', () => {
+  const run = orchestrator(fakeContext({}));
+  let step = run.next();
+  while (!step.done) {
+    step = run.next(step.value);
+  }
+  assert.strictEqual(step.value.success, true);
+});
+"#.to_string()
+}
+
+fn js_project_files(package_name: &str, version: &str) -> Vec<GeneratedFile> {
+    let package = json!({
+        "name": package_name.replace('_', "-"),
+        "version": version,
+        "private": true,
+        "main": "src/functions/*.js",
+        "scripts": {
+            "start": "func start",
+            "test": "node --test test/"
+        },
+        "dependencies": {
+            "@azure/functions": "^4.5.0",
+            "durable-functions": format!("^{JAVASCRIPT_SDK_VERSION}")
+        }
+    });
+    vec![GeneratedFile { path: "package.json".to_string(), content: format!("{:#}\n", package) }]
+}
+
+// =============================================================================
+// Shared host configuration
+// =============================================================================
+
+fn host_files(workflow_name: &str, worker_runtime: &str) -> Vec<GeneratedFile> {
+    // Task hub names are restricted to letters and digits
+    let hub: String = workflow_name.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let host = json!({
+        "version": "2.0",
+        "extensions": {
+            "durableTask": { "hubName": format!("{hub}Hub") }
+        }
+    });
+    let settings = json!({
+        "IsEncrypted": false,
+        "Values": {
+            "AzureWebJobsStorage": "UseDevelopmentStorage=true",
+            "FUNCTIONS_WORKER_RUNTIME": worker_runtime
+        }
+    });
+    vec![
+        GeneratedFile { path: "host.json".to_string(), content: format!("{:#}\n", host) },
+        GeneratedFile { path: "local.settings.json".to_string(), content: format!("{:#}\n", settings) },
+    ]
+}
+
+impl Backend for Durable {
+    fn target(&self) -> CodegenTarget {
+        match self.language {
+            Language::CSharp => CodegenTarget::DurableCsharp,
+            Language::JavaScript => CodegenTarget::DurableJavascript,
+        }
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        match self.language {
+            Language::CSharp => CSHARP_SDK_VERSION,
+            Language::JavaScript => JAVASCRIPT_SDK_VERSION,
+        }
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        // Azurite emulates the storage account `UseDevelopmentStorage=true` points at
+        r#"# Generated by OmniRoute Workflow Compiler
+services:
+  azurite:
+    image: mcr.microsoft.com/azure-storage/azurite:latest
+    ports:
+      - "10000:10000"
+      - "10001:10001"
+      - "10002:10002"
+"#.to_string()
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let steps = steps::workflow_steps(definition, self.target().name())?;
+        check_steps(&steps, self.target().name())?;
+        let workflow_name = to_pascal_case(&definition.name);
+
+        let sources = match self.language {
+            Language::CSharp => {
+                let namespace = format!("OmniRoute.Workflows.{}", cs_identifier(package_name));
+                let mut files = cs_project_files(&workflow_name, &definition.version, &namespace);
+                files.extend(host_files(&workflow_name, "dotnet-isolated"));
+                TargetSources {
+                    workflow_code: cs_orchestrator(definition, &steps, &workflow_name, &namespace, fingerprint),
+                    activity_code: cs_activities(&steps, &workflow_name, &namespace),
+                    worker_code: cs_program(),
+                    test_code: cs_test(&workflow_name, &namespace),
+                    files,
+                    signals: steps::signal_names(&steps),
+                    metadata: None,
+                }
+            }
+            Language::JavaScript => {
+                let mut files = js_project_files(package_name, &definition.version);
+                files.extend(host_files(&workflow_name, "node"));
+                TargetSources {
+                    workflow_code: js_orchestrator(definition, &steps, &workflow_name, fingerprint),
+                    activity_code: js_activities(&steps),
+                    worker_code: js_http_start(&workflow_name),
+                    test_code: js_test(),
+                    files,
+                    signals: steps::signal_names(&steps),
+                    metadata: None,
+                }
+            }
+        };
+        Ok(sources)
+    }
+}
@@ -0,0 +1,265 @@
+//! Duration parsing shared by validation and code generation
+//!
+//! Accepts Go-style (`1h30m`, `1.5s`), humantime (`2 hours 5 mins`, `3d`) and
+//! ISO-8601 (`PT1H30M`, `P1DT12H`) spellings and renders Go `time.Duration` expressions and
+//! ISO-8601 strings.
+
+use std::time::Duration;
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::error::CompilerError;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Nanoseconds per unit for Go-style and humantime unit spellings
+fn unit_nanos(unit: &str) -> Option<u128> {
+    let nanos = match unit {
+        "ns" | "nsec" | "nanos" | "nanosecond" | "nanoseconds" => 1,
+        "us" | "µs" | "usec" | "micros" | "microsecond" | "microseconds" => 1_000,
+        "ms" | "msec" | "millis" | "millisecond" | "milliseconds" => 1_000_000,
+        "s" | "sec" | "secs" | "second" | "seconds" => NANOS_PER_SECOND,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60 * NANOS_PER_SECOND,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600 * NANOS_PER_SECOND,
+        "d" | "day" | "days" => 86_400 * NANOS_PER_SECOND,
+        "w" | "week" | "weeks" => 604_800 * NANOS_PER_SECOND,
+        _ => return None,
+    };
+    Some(nanos)
+}
+
+/// Parse a duration in any supported spelling
+pub fn parse_duration(raw: &str) -> Result<Duration, String> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return Err("duration is empty".into());
+    }
+    if s.starts_with('-') {
+        return Err("duration cannot be negative".into());
+    }
+
+    let nanos = if s.starts_with(['P', 'p']) {
+        parse_iso8601(s)?
+    } else {
+        parse_units(s)?
+    };
+
+    u64::try_from(nanos)
+        .map(Duration::from_nanos)
+        .map_err(|_| "duration is too large".to_string())
+}
+
+/// Scale a decimal number by a unit without going through floating point for the integer part
+fn scale(number: &str, unit: u128) -> Result<u128, String> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(format!("invalid number '{}'", number));
+    }
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| format!("invalid number '{}'", number))? };
+
+    let mut nanos = whole.checked_mul(unit).ok_or("duration is too large")?;
+    let mut divisor = 1u128;
+    for digit in fraction.chars() {
+        let digit = digit.to_digit(10).ok_or_else(|| format!("invalid number '{}'", number))? as u128;
+        divisor = divisor.saturating_mul(10);
+        nanos += unit.saturating_mul(digit) / divisor;
+    }
+    Ok(nanos)
+}
+
+/// Go-style and humantime: a sequence of `<number><unit>` terms, optionally separated by spaces
+fn parse_units(s: &str) -> Result<u128, String> {
+    if s == "0" {
+        return Ok(0);
+    }
+
+    let mut total = 0u128;
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        rest = rest.strip_prefix("and ").unwrap_or(rest);
+        if rest.is_empty() {
+            break;
+        }
+
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        if number_len == 0 {
+            return Err(format!("expected a number at '{}'", rest));
+        }
+        let (number, tail) = rest.split_at(number_len);
+        let tail = tail.trim_start();
+        let unit_len = tail.find(|c: char| !c.is_alphabetic()).unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        if unit.is_empty() {
+            return Err(format!("missing unit after '{}'", number));
+        }
+        let unit = unit_nanos(&unit.to_lowercase()).ok_or_else(|| format!("unknown unit '{}'", unit))?;
+
+        total = total.checked_add(scale(number, unit)?).ok_or("duration is too large")?;
+        rest = tail;
+    }
+
+    Ok(total)
+}
+
+/// ISO-8601 durations limited to fixed-length components: `PnW`, `PnDTnHnMnS`
+fn parse_iso8601(s: &str) -> Result<u128, String> {
+    let body = &s[1..];
+
+    let mut total = 0u128;
+    let mut components = 0;
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in body.chars() {
+        match c.to_ascii_uppercase() {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            d if d.is_ascii_digit() || d == '.' || d == ',' => number.push(if d == ',' { '.' } else { d }),
+            unit => {
+                if number.is_empty() {
+                    return Err(format!("missing number before '{}'", c));
+                }
+                let seconds: u128 = match (unit, in_time) {
+                    ('W', false) => 604_800,
+                    ('D', false) => 86_400,
+                    ('H', true) => 3_600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    ('Y', false) | ('M', false) => {
+                        return Err("calendar years and months are not fixed-length durations".into())
+                    }
+                    _ => return Err(format!("unexpected '{}' in ISO-8601 duration", c)),
+                };
+                total = total
+                    .checked_add(scale(&number, seconds * NANOS_PER_SECOND)?)
+                    .ok_or("duration is too large")?;
+                number.clear();
+                components += 1;
+            }
+        }
+    }
+    if !number.is_empty() {
+        return Err(format!("missing unit after '{}'", number));
+    }
+    if components == 0 {
+        return Err("ISO-8601 duration has no components".into());
+    }
+
+    Ok(total)
+}
+
+/// Render a duration as a Go `time.Duration` expression using the largest exact unit
+pub fn to_go_expr(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0".to_string();
+    }
+
+    let units: [(u128, &str); 6] = [
+        (3_600 * NANOS_PER_SECOND, "time.Hour"),
+        (60 * NANOS_PER_SECOND, "time.Minute"),
+        (NANOS_PER_SECOND, "time.Second"),
+        (1_000_000, "time.Millisecond"),
+        (1_000, "time.Microsecond"),
+        (1, "time.Nanosecond"),
+    ];
+    let (size, unit) = units.iter().find(|(size, _)| nanos.is_multiple_of(*size)).copied().unwrap_or((1, "time.Nanosecond"));
+    format!("{} * {}", nanos / size, unit)
+}
+
+/// Render a duration in ISO-8601 form such as `PT1H30M`, to the millisecond
+pub fn to_iso8601(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let millis = duration.subsec_millis();
+    let (days, hours, minutes, seconds) = (seconds / 86_400, seconds % 86_400 / 3600, seconds % 3600 / 60, seconds % 60);
+    let mut out = "P".to_string();
+    if days > 0 {
+        out.push_str(&format!("{}D", days));
+    }
+    if days == 0 || hours > 0 || minutes > 0 || seconds > 0 || millis > 0 {
+        out.push('T');
+        if hours > 0 {
+            out.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{}M", minutes));
+        }
+        match millis {
+            0 if seconds > 0 || (hours == 0 && minutes == 0) => out.push_str(&format!("{}S", seconds)),
+            0 => {}
+            _ => out.push_str(&format!("{}.{:03}S", seconds, millis)),
+        }
+    }
+    out
+}
+
+/// Parse a duration-valued field, naming the field and node in the error
+pub fn parse_field(raw: &str, field: &str, node_id: Option<&str>) -> Result<Duration, CompilerError> {
+    parse_duration(raw).map_err(|reason| {
+        let location = node_id.map(|id| format!(" on node '{}'", id)).unwrap_or_default();
+        let diagnostic = Diagnostic::new(format!("Invalid duration '{}' for {}{}: {}", raw, field, location, reason));
+        CompilerError::ValidationError(match node_id {
+            Some(id) => diagnostic.node(id),
+            None => diagnostic,
+        })
+    })
+}
+
+/// Parse a duration-valued field and render it as a Go expression
+pub fn go_expr_for_field(raw: &str, field: &str, node_id: Option<&str>) -> Result<String, CompilerError> {
+    parse_field(raw, field, node_id).map(to_go_expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_go_humantime_and_iso8601_spellings() {
+        let cases = [
+            ("1h30m", 5_400_000),
+            ("1.5s", 1_500),
+            ("250ms", 250),
+            ("2 hours 5 mins", 7_500_000),
+            ("3d", 259_200_000),
+            ("1 week, 2 days and 3 hours", 788_400_000),
+            ("0", 0),
+            ("PT1H30M", 5_400_000),
+            ("P1DT12H", 129_600_000),
+            ("pt0,5s", 500),
+            ("P2W", 1_209_600_000),
+        ];
+        for (raw, millis) in cases {
+            assert_eq!(parse_duration(raw), Ok(Duration::from_millis(millis)), "{raw}");
+        }
+        assert_eq!(parse_duration("0.000000001s"), Ok(Duration::from_nanos(1)));
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        let cases = [
+            ("", "duration is empty"),
+            ("-5s", "duration cannot be negative"),
+            ("10", "missing unit after '10'"),
+            ("5 parsecs", "unknown unit 'parsecs'"),
+            ("1..2s", "invalid number '1..2'"),
+            ("P1M", "calendar years and months are not fixed-length durations"),
+            ("PT5", "missing unit after '5'"),
+            ("P", "ISO-8601 duration has no components"),
+            ("PT1D", "unexpected 'D' in ISO-8601 duration"),
+            ("600000w", "duration is too large"),
+        ];
+        for (raw, error) in cases {
+            assert_eq!(parse_duration(raw).unwrap_err(), error, "{raw}");
+        }
+    }
+
+    #[test]
+    fn renders_go_and_iso8601_forms() {
+        assert_eq!(to_go_expr(Duration::from_secs(7_200)), "2 * time.Hour");
+        assert_eq!(to_go_expr(Duration::from_millis(1_500)), "1500 * time.Millisecond");
+        assert_eq!(to_go_expr(Duration::ZERO), "0");
+        assert_eq!(to_iso8601(Duration::from_secs(129_600)), "P1DT12H");
+        assert_eq!(to_iso8601(Duration::from_millis(90_250)), "PT1M30.250S");
+        assert_eq!(to_iso8601(Duration::from_secs(86_400)), "P1D");
+        assert_eq!(to_iso8601(Duration::ZERO), "PT0S");
+    }
+}
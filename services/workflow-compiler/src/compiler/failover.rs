@@ -0,0 +1,160 @@
+//! Multi-region Temporal connection config for DR-ready workers
+
+use std::collections::HashSet;
+
+use crate::compiler::codegen::go_string_literal;
+use crate::compiler::sdk::SdkRelease;
+use crate::config::NamespacePolicy;
+use crate::error::CompilerError;
+use crate::FailoverRegion;
+
+/// Check region names, endpoints, namespaces and mTLS material
+pub fn validate_regions(regions: &[FailoverRegion], namespace_policy: &NamespacePolicy) -> Result<(), CompilerError> {
+    let mut names = HashSet::new();
+    for region in regions {
+        if region.name.trim().is_empty() {
+            return Err(CompilerError::ValidationError("Failover regions must be named".into()));
+        }
+        if !names.insert(region.name.as_str()) {
+            return Err(CompilerError::ValidationError(format!(
+                "Failover region '{}' is declared more than once",
+                region.name
+            ).into()));
+        }
+        if !region.address.rsplit_once(':').is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok()) {
+            return Err(CompilerError::ValidationError(format!(
+                "Failover region '{}' address '{}' must be host:port",
+                region.name, region.address
+            ).into()));
+        }
+        if let Some(namespace) = &region.namespace {
+            namespace_policy.check(namespace).map_err(|reason| CompilerError::ValidationError(format!(
+                "Failover region '{}' namespace '{}' {}",
+                region.name, namespace, reason
+            ).into()))?;
+        }
+        if let Some(tls) = &region.tls {
+            if tls.client_cert.is_some() != tls.client_key.is_some() {
+                return Err(CompilerError::ValidationError(format!(
+                    "Failover region '{}' mTLS needs both client_cert and client_key",
+                    region.name
+                ).into()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate the region table and a `DialTemporal` helper that connects to the region named
+/// by `TEMPORAL_REGION` (default: the first, primary region) and fails over in declared order
+pub fn generate_connection_code(regions: &[FailoverRegion], package_name: &str, sdk: &SdkRelease) -> String {
+    let new_client = sdk.client_constructor();
+    let literal = |value: &Option<String>| go_string_literal(value.as_deref().unwrap_or(""));
+    let entries: String = regions.iter()
+        .map(|r| {
+            let tls = r.tls.clone().unwrap_or_default();
+            format!(
+                "    {{\n        Name:       {},\n        HostPort:   {},\n        Namespace:  {},\n        TLS:        {},\n        ClientCert: {},\n        ClientKey:  {},\n        RootCA:     {},\n        ServerName: {},\n    }},\n",
+                go_string_literal(&r.name),
+                go_string_literal(&r.address),
+                go_string_literal(r.namespace.as_deref().unwrap_or("default")),
+                r.tls.is_some(),
+                literal(&tls.client_cert),
+                literal(&tls.client_key),
+                literal(&tls.root_ca),
+                literal(&tls.server_name),
+            )
+        })
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+package {package_name}
+
+import (
+    "crypto/tls"
+    "crypto/x509"
+    "fmt"
+    "os"
+    "go.temporal.io/sdk/client"
+)
+
+// TemporalRegion is one Temporal cluster endpoint the worker can connect to
+type TemporalRegion struct {{
+    Name       string
+    HostPort   string
+    Namespace  string
+    TLS        bool
+    ClientCert string
+    ClientKey  string
+    RootCA     string
+    ServerName string
+}}
+
+// TemporalRegions lists the failover regions in priority order; the first is primary
+var TemporalRegions = []TemporalRegion{{
+{entries}}}
+
+func (r TemporalRegion) clientOptions() (client.Options, error) {{
+    options := client.Options{{HostPort: r.HostPort, Namespace: r.Namespace}}
+    if !r.TLS {{
+        return options, nil
+    }}
+
+    tlsConfig := &tls.Config{{ServerName: r.ServerName}}
+    if r.ClientCert != "" {{
+        cert, err := tls.LoadX509KeyPair(r.ClientCert, r.ClientKey)
+        if err != nil {{
+            return options, fmt.Errorf("load client certificate: %w", err)
+        }}
+        tlsConfig.Certificates = []tls.Certificate{{cert}}
+    }}
+    if r.RootCA != "" {{
+        pem, err := os.ReadFile(r.RootCA)
+        if err != nil {{
+            return options, fmt.Errorf("read root CA: %w", err)
+        }}
+        pool := x509.NewCertPool()
+        if !pool.AppendCertsFromPEM(pem) {{
+            return options, fmt.Errorf("no certificates in %s", r.RootCA)
+        }}
+        tlsConfig.RootCAs = pool
+    }}
+    options.ConnectionOptions = client.ConnectionOptions{{TLS: tlsConfig}}
+    return options, nil
+}}
+
+// DialTemporal connects to the region named by TEMPORAL_REGION, defaulting to the primary,
+// and fails over to the remaining regions in priority order
+func DialTemporal() (client.Client, TemporalRegion, error) {{
+    preferred := os.Getenv("TEMPORAL_REGION")
+    if preferred == "" {{
+        preferred = TemporalRegions[0].Name
+    }}
+    ordered := make([]TemporalRegion, 0, len(TemporalRegions))
+    for _, r := range TemporalRegions {{
+        if r.Name == preferred {{
+            ordered = append(ordered, r)
+        }}
+    }}
+    for _, r := range TemporalRegions {{
+        if r.Name != preferred {{
+            ordered = append(ordered, r)
+        }}
+    }}
+
+    var failures []string
+    for _, r := range ordered {{
+        options, err := r.clientOptions()
+        if err == nil {{
+            var c client.Client
+            if c, err = {new_client}(options); err == nil {{
+                return c, r, nil
+            }}
+        }}
+        failures = append(failures, fmt.Sprintf("%s: %v", r.Name, err))
+    }}
+    return nil, TemporalRegion{{}}, fmt.Errorf("no Temporal region reachable: %v", failures)
+}}
+"#)
+}
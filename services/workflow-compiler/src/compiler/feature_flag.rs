@@ -0,0 +1,219 @@
+//! Feature-flag branching: compile-time flag checks, branch selection and the generated
+//! evaluation activity for LaunchDarkly, Unleash and static flag files
+
+use crate::compiler::codegen::is_activity_node;
+use crate::compiler::diagnostic::Diagnostic;
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{FeatureFlagConfig, NodeType, WorkflowDefinition, WorkflowNode};
+
+/// Activity evaluating a flag; generated once per workflow that uses FeatureFlag nodes
+pub const ACTIVITY: &str = "EvaluateFeatureFlag";
+
+/// Edge labels or conditions selecting the enabled and disabled branches
+const ON: &[&str] = &["on", "true", "enabled"];
+const OFF: &[&str] = &["off", "false", "disabled"];
+
+/// Flag keys accepted by every provider: alphanumeric start, then letters, digits, `.`, `_`, `-`
+fn is_flag_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    key.len() <= 256
+        && chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// First node of the enabled and disabled branches; either may be absent
+pub fn branches<'a>(definition: &'a WorkflowDefinition, node: &WorkflowNode) -> Result<(Option<&'a WorkflowNode>, Option<&'a WorkflowNode>), CompilerError> {
+    let (mut on, mut off) = (None, None);
+    for edge in graph::outgoing_edges(definition, &node.id) {
+        let selector = edge.label.as_deref().or(edge.condition.as_deref()).unwrap_or_default().trim().to_ascii_lowercase();
+        let slot = if ON.contains(&selector.as_str()) {
+            &mut on
+        } else if OFF.contains(&selector.as_str()) {
+            &mut off
+        } else {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "FeatureFlag node '{}' edge '{}' must be labelled on or off",
+                node.id, edge.id
+            )).node(&node.id).edge(&edge.id)));
+        };
+        if slot.is_some() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "FeatureFlag node '{}' has more than one '{}' branch",
+                node.id, selector
+            )).node(&node.id)));
+        }
+        *slot = graph::find_node(definition, &edge.target);
+    }
+
+    Ok((on, off))
+}
+
+/// Check the flag key, targeting variable and branch edges of every FeatureFlag node
+pub fn validate(definition: &WorkflowDefinition, known_flags: &[String]) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::FeatureFlag)) {
+        let config: FeatureFlagConfig = node.typed_config()?;
+
+        if !is_flag_key(&config.flag) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "FeatureFlag node '{}' flag key '{}' must start with a letter or digit and contain only letters, digits, '.', '_' and '-'",
+                node.id, config.flag
+            )).node(&node.id)));
+        }
+        if !known_flags.is_empty() && !known_flags.contains(&config.flag) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "FeatureFlag node '{}' flag '{}' is not a known feature flag",
+                node.id, config.flag
+            )).node(&node.id)));
+        }
+
+        if let Some(key) = &config.context {
+            match definition.variables.iter().find(|v| &v.name == key) {
+                Some(v) if v.var_type == "string" => {}
+                Some(_) => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "FeatureFlag node '{}' context '{}' must be a string variable",
+                    node.id, key
+                )).node(&node.id))),
+                None => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "FeatureFlag node '{}' context '{}' is not a workflow variable",
+                    node.id, key
+                )).node(&node.id))),
+            }
+        }
+
+        let (on, off) = branches(definition, node)?;
+        if on.is_none() && off.is_none() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "FeatureFlag node '{}' needs an on or off branch",
+                node.id
+            )).node(&node.id)));
+        }
+        if let Some(branch) = [on, off].into_iter().flatten().find(|b| !is_activity_node(b)) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "FeatureFlag node '{}' branch '{}' must start with an activity node",
+                node.id, branch.id
+            )).node(&node.id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the workflow needs the generated evaluation activity
+pub fn uses_feature_flags(definition: &WorkflowDefinition) -> bool {
+    definition.nodes.iter().any(|n| matches!(n.node_type, NodeType::FeatureFlag))
+}
+
+/// Imports the evaluation activity adds to the activities file
+pub const ACTIVITY_IMPORTS: &[&str] = &["\"encoding/base64\"", "\"encoding/json\"", "\"fmt\"", "\"net/http\"", "\"net/url\"", "\"os\""];
+
+/// Generate the request type and `EvaluateFeatureFlag` activity with its provider clients
+pub fn generate_activity() -> String {
+    format!(r#"// FeatureFlagRequest identifies a boolean flag and the context it is evaluated for
+type FeatureFlagRequest struct {{
+    Provider string `json:"provider"`
+    Flag     string `json:"flag"`
+    Context  string `json:"context"`
+    Default  bool   `json:"default"`
+}}
+
+// {ACTIVITY} evaluates a boolean feature flag with the requested provider
+func (a *Activities) {ACTIVITY}(ctx context.Context, req FeatureFlagRequest) (bool, error) {{
+    switch req.Provider {{
+    case "launchdarkly":
+        return evaluateLaunchDarkly(ctx, req)
+    case "unleash":
+        return evaluateUnleash(ctx, req)
+    default:
+        return evaluateStaticFlag(req)
+    }}
+}}
+
+// evaluateStaticFlag reads flags from the JSON object of booleans named by FEATURE_FLAGS_FILE
+func evaluateStaticFlag(req FeatureFlagRequest) (bool, error) {{
+    path := os.Getenv("FEATURE_FLAGS_FILE")
+    if path == "" {{
+        return req.Default, nil
+    }}
+    raw, err := os.ReadFile(path)
+    if err != nil {{
+        return false, fmt.Errorf("read feature flags: %w", err)
+    }}
+    var flags map[string]bool
+    if err := json.Unmarshal(raw, &flags); err != nil {{
+        return false, fmt.Errorf("parse feature flags: %w", err)
+    }}
+    if value, ok := flags[req.Flag]; ok {{
+        return value, nil
+    }}
+    return req.Default, nil
+}}
+
+func getFlagJSON(ctx context.Context, endpoint, authorization string, out any) error {{
+    httpReq, err := http.NewRequestWithContext(ctx, http.MethodGet, endpoint, nil)
+    if err != nil {{
+        return err
+    }}
+    httpReq.Header.Set("Authorization", authorization)
+    resp, err := http.DefaultClient.Do(httpReq)
+    if err != nil {{
+        return err
+    }}
+    defer resp.Body.Close()
+    if resp.StatusCode != http.StatusOK {{
+        return fmt.Errorf("flag provider returned %s", resp.Status)
+    }}
+    return json.NewDecoder(resp.Body).Decode(out)
+}}
+
+// evaluateLaunchDarkly evaluates a user context with the server-side SDK key in LAUNCHDARKLY_SDK_KEY
+func evaluateLaunchDarkly(ctx context.Context, req FeatureFlagRequest) (bool, error) {{
+    key := req.Context
+    if key == "" {{
+        key = "anonymous"
+    }}
+    ldContext, err := json.Marshal(map[string]string{{"kind": "user", "key": key}})
+    if err != nil {{
+        return false, err
+    }}
+    base := os.Getenv("LAUNCHDARKLY_BASE_URI")
+    if base == "" {{
+        base = "https://sdk.launchdarkly.com"
+    }}
+    endpoint := fmt.Sprintf("%s/sdk/evalx/contexts/%s", base, base64.RawURLEncoding.EncodeToString(ldContext))
+
+    var flags map[string]struct {{
+        Value any `json:"value"`
+    }}
+    if err := getFlagJSON(ctx, endpoint, os.Getenv("LAUNCHDARKLY_SDK_KEY"), &flags); err != nil {{
+        return false, err
+    }}
+    if value, ok := flags[req.Flag].Value.(bool); ok {{
+        return value, nil
+    }}
+    return req.Default, nil
+}}
+
+// evaluateUnleash uses the frontend API at UNLEASH_URL, which lists only the toggles enabled for the context
+func evaluateUnleash(ctx context.Context, req FeatureFlagRequest) (bool, error) {{
+    endpoint := fmt.Sprintf("%s/api/frontend?userId=%s", os.Getenv("UNLEASH_URL"), url.QueryEscape(req.Context))
+
+    var body struct {{
+        Toggles []struct {{
+            Name    string `json:"name"`
+            Enabled bool   `json:"enabled"`
+        }} `json:"toggles"`
+    }}
+    if err := getFlagJSON(ctx, endpoint, os.Getenv("UNLEASH_API_TOKEN"), &body); err != nil {{
+        return false, err
+    }}
+    for _, toggle := range body.Toggles {{
+        if toggle.Name == req.Flag {{
+            return toggle.Enabled, nil
+        }}
+    }}
+    return false, nil
+}}
+
+"#)
+}
@@ -0,0 +1,217 @@
+//! Constant folding of Decision conditions
+//!
+//! Variables marked `constant` keep their default value for the whole run: no Transform may
+//! assign them, and the Go target pins them at the top of the workflow whatever the caller
+//! passes. Conditions that read only constants and literals are evaluated at compile time.
+//! A Decision loses the branches whose conditions can never be the first to hold, and a
+//! Decision whose outcome is fixed is replaced by the edge it always takes; branch nodes that
+//! nothing else leads to are then removed as unreachable. Validation warns with the branches
+//! each Decision loses. Decisions marked as loops, in cancellation scopes or with edges other
+//! than flow edges are left as they are.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::limits;
+use crate::compiler::testgen::go_any_literal;
+use crate::dsl::condition::{self, CompareOp, Expr, Literal};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, CancellationScopeConfig, DecisionConfig, EdgeKind, NodeType, TransformConfig, WorkflowDefinition, WorkflowEdge};
+
+/// Default values of the constant variables, by name and by generated field name
+fn constants(definition: &WorkflowDefinition) -> BTreeMap<String, &Value> {
+    let mut constants = BTreeMap::new();
+    for variable in definition.variables.iter().filter(|v| v.constant) {
+        let Some(value) = variable.default_value.as_ref().filter(|v| !v.is_null()) else { continue };
+        constants.insert(variable.name.clone(), value);
+        constants.insert(to_pascal_case(&variable.name), value);
+    }
+    constants
+}
+
+/// Check constant variables have a default value and that no Transform assigns them
+pub fn validate_constants(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for variable in definition.variables.iter().filter(|v| v.constant) {
+        if variable.default_value.as_ref().is_none_or(Value::is_null) {
+            return Err(CompilerError::ValidationError(format!(
+                "Constant variable '{}' has no default_value to keep",
+                variable.name
+            ).into()));
+        }
+        for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::Transform)) {
+            let Ok(config) = node.typed_config::<TransformConfig>() else { continue };
+            let outputs = config.output_schema.as_ref().and_then(|s| s.get("properties")).and_then(Value::as_object);
+            if config.assign.contains_key(&variable.name) || outputs.is_some_and(|o| o.contains_key(&variable.name)) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Transform node '{}' assigns constant variable '{}'",
+                    node.id, variable.name
+                )).node(&node.id)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn literal_value(literal: &Literal) -> Option<Value> {
+    Some(match literal {
+        Literal::String(s) => Value::String(s.clone()),
+        Literal::Number(n) => Value::from(n.parse::<f64>().ok()?),
+        Literal::Bool(b) => Value::Bool(*b),
+        Literal::Null => Value::Null,
+    })
+}
+
+fn compare(lhs: &Value, op: CompareOp, rhs: &Value) -> Option<bool> {
+    let ordering = match (lhs, rhs) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?)?,
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ if !op.is_ordering() => return Some((lhs == rhs) == (op == CompareOp::Eq)),
+        _ => return None,
+    };
+    Some(match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::Ne => ordering.is_ne(),
+        CompareOp::Lt => ordering.is_lt(),
+        CompareOp::Le => ordering.is_le(),
+        CompareOp::Gt => ordering.is_gt(),
+        CompareOp::Ge => ordering.is_ge(),
+    })
+}
+
+/// Value of an expression at compile time, if it reads nothing but constants and literals
+fn evaluate(expr: &Expr, constants: &BTreeMap<String, &Value>) -> Option<Value> {
+    let truth = |e: &Expr| evaluate(e, constants).and_then(|v| v.as_bool());
+    match expr {
+        Expr::Variable { name, fields } => {
+            let mut value = *constants.get(name)?;
+            for field in fields {
+                value = value.get(field)?;
+            }
+            Some(value.clone())
+        }
+        Expr::Literal(literal) => literal_value(literal),
+        Expr::Not(inner) => truth(inner).map(|b| Value::Bool(!b)),
+        Expr::And(lhs, rhs) => match (truth(lhs), truth(rhs)) {
+            (Some(false), _) | (_, Some(false)) => Some(Value::Bool(false)),
+            (Some(true), Some(true)) => Some(Value::Bool(true)),
+            _ => None,
+        },
+        Expr::Or(lhs, rhs) => match (truth(lhs), truth(rhs)) {
+            (Some(true), _) | (_, Some(true)) => Some(Value::Bool(true)),
+            (Some(false), Some(false)) => Some(Value::Bool(false)),
+            _ => None,
+        },
+        Expr::Compare(lhs, op, rhs) => compare(&evaluate(lhs, constants)?, *op, &evaluate(rhs, constants)?).map(Value::Bool),
+    }
+}
+
+/// Outcome of folding one Decision's conditions
+struct Folded<'a> {
+    /// Edges no run can take, in definition order
+    pruned: Vec<&'a WorkflowEdge>,
+    /// Edge that holds whenever the edges before it do not, which becomes the default
+    always: Option<&'a WorkflowEdge>,
+    /// Edge every run takes, when the outcome is fixed
+    taken: Option<&'a WorkflowEdge>,
+}
+
+fn fold_decision<'a>(definition: &'a WorkflowDefinition, node_id: &'a str, constants: &BTreeMap<String, &Value>) -> Option<Folded<'a>> {
+    let edges: Vec<&WorkflowEdge> = graph::outgoing_edges(definition, node_id).collect();
+    if edges.iter().any(|e| e.kind != EdgeKind::Flow) {
+        return None;
+    }
+    let condition = |e: &WorkflowEdge| e.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()).map(str::to_string);
+    let default = edges.iter().copied().find(|e| condition(e).is_none());
+
+    let mut folded = Folded { pruned: vec![], always: None, taken: None };
+    let mut undecided = false;
+    for edge in edges.iter().copied() {
+        let Some(condition) = condition(edge) else { continue };
+        if folded.always.is_some() {
+            folded.pruned.push(edge);
+            continue;
+        }
+        let expr = condition::parse(&condition).ok()?;
+        match evaluate(&expr, constants).and_then(|v| v.as_bool()) {
+            Some(false) => folded.pruned.push(edge),
+            Some(true) => folded.always = Some(edge),
+            None => undecided = true,
+        }
+    }
+    if folded.always.is_some() {
+        folded.pruned.extend(default);
+    }
+    if !undecided {
+        folded.taken = folded.always.or(default);
+        // Nothing is decided when every condition fails and there is no default to take
+        folded.taken?;
+    }
+    (!folded.pruned.is_empty() || folded.taken.is_some()).then_some(folded)
+}
+
+/// Definition with the branches constant conditions rule out removed, and a warning per
+/// Decision naming the branches it lost
+pub fn fold(definition: &WorkflowDefinition) -> Result<(WorkflowDefinition, Vec<String>), CompilerError> {
+    let constants = constants(definition);
+    let mut folded = definition.clone();
+    let mut warnings = vec![];
+    if constants.is_empty() {
+        return Ok((folded, warnings));
+    }
+    let scoped: Vec<String> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::CancellationScope))
+        .filter_map(|n| n.typed_config::<CancellationScopeConfig>().ok())
+        .flat_map(|c| c.members)
+        .collect();
+
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::Decision)) {
+        limits::checkpoint("constant folding")?;
+        if scoped.contains(&node.id) || node.typed_config::<DecisionConfig>().is_ok_and(|c| c.is_loop) {
+            continue;
+        }
+        let Some(outcome) = fold_decision(definition, &node.id, &constants) else { continue };
+        if outcome.taken.is_some_and(|e| e.target == node.id) {
+            continue;
+        }
+
+        let mut warning = format!("Decision node '{}' has conditions decided by constant variables", node.id);
+        if !outcome.pruned.is_empty() {
+            let removed: Vec<String> = outcome.pruned.iter().map(|e| format!("'{}' to '{}'", e.id, e.target)).collect();
+            warning.push_str(&format!("; removed branches: {}", removed.join(", ")));
+        }
+        folded.edges.retain(|e| !outcome.pruned.iter().any(|p| p.id == e.id));
+        match outcome.taken {
+            Some(taken) => {
+                warning.push_str(&format!("; it always continues to '{}' and is removed", taken.target));
+                folded.nodes.retain(|n| n.id != node.id);
+                folded.edges.retain(|e| e.id != taken.id);
+                for edge in folded.edges.iter_mut().filter(|e| e.target == node.id) {
+                    edge.target = taken.target.clone();
+                }
+            }
+            None => {
+                if let Some(always) = outcome.always {
+                    let edge = folded.edges.iter_mut().find(|e| e.id == always.id).expect("edge is in the definition");
+                    edge.condition = None;
+                }
+            }
+        }
+        warnings.push(warning);
+    }
+    Ok((folded, warnings))
+}
+
+/// Statements pinning constant variables to their defaults at the top of the Go workflow
+pub fn generate_pins(definition: &WorkflowDefinition) -> String {
+    let pins: String = definition.variables.iter()
+        .filter(|v| v.constant)
+        .filter_map(|v| v.default_value.as_ref().map(|d| format!("    input.{} = {}\n", to_pascal_case(&v.name), go_any_literal(d))))
+        .collect();
+    if pins.is_empty() {
+        return pins;
+    }
+    format!("    // Constant variables keep their defaults whatever the caller passes\n{pins}\n")
+}
@@ -0,0 +1,58 @@
+//! Go identifiers generated from user-supplied names
+//!
+//! The workflow name becomes the package name and workflow type, node labels become activity
+//! and decision table function names, and variable names become input fields. Names that are
+//! not valid Go identifiers as written are sanitized: `to_pascal_case` always yields an
+//! exported identifier, and `go_package_name` a package name that is not a keyword. Every
+//! rename beyond the usual casing is reported as a warning, so authors know what to look for
+//! in the generated code.
+
+use crate::compiler::codegen::label_identifier;
+use crate::{to_pascal_case, WorkflowDefinition};
+
+/// Go keywords and `main`, whose package cannot be imported by the generated worker
+const RESERVED: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough", "for", "func", "go", "goto",
+    "if", "import", "interface", "map", "package", "range", "return", "select", "struct", "switch", "type", "var", "main",
+];
+
+/// Package name for a workflow name: lower-cased with every character other than a letter or
+/// digit replaced by `_`, prefixed with `_` when it would start with a digit and suffixed with
+/// `_` when it is reserved
+pub fn go_package_name(name: &str) -> String {
+    let mut package: String = name.to_lowercase().chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect();
+    if !package.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        package.insert(0, '_');
+    }
+    if RESERVED.contains(&package.as_str()) {
+        package.push('_');
+    }
+    package
+}
+
+/// Whether a name maps onto Go identifiers by casing alone: a letter followed by letters,
+/// digits and the word separators spaces, `_` and `-`
+fn is_plain(name: &str) -> bool {
+    name.starts_with(char::is_alphabetic) && name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-'))
+}
+
+/// Warnings for the workflow name, node labels and variable names that had to be sanitized
+/// into Go identifiers, naming the identifier generated for each
+pub fn check(definition: &WorkflowDefinition) -> Vec<String> {
+    let mut warnings = vec![];
+    let package = go_package_name(&definition.name);
+    if !is_plain(&definition.name) || package != definition.name.to_lowercase().replace(' ', "_") {
+        warnings.push(format!(
+            "Workflow name '{}' generates the Go package {} and workflow type {}",
+            definition.name, package, to_pascal_case(&definition.name)
+        ));
+    }
+    for node in definition.nodes.iter().filter(|n| !is_plain(&n.label)) {
+        let Some(identifier) = label_identifier(node) else { continue };
+        warnings.push(format!("Node '{}' label '{}' generates the Go identifier {}", node.id, node.label, identifier));
+    }
+    for variable in definition.variables.iter().filter(|v| !is_plain(&v.name)) {
+        warnings.push(format!("Variable '{}' generates the Go input field {}", variable.name, to_pascal_case(&variable.name)));
+    }
+    warnings
+}
@@ -0,0 +1,172 @@
+//! Type inference for variables declared without a type or as `auto`
+//!
+//! Evidence comes from default values, activity `output_schema` properties and how
+//! variables are used: decision table tests, edge condition comparisons, and string-only
+//! positions such as correlation keys, dynamic activity selectors and flag contexts.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::compiler::codegen::is_activity_node;
+use crate::compiler::decision_table;
+use crate::{DynamicActivityConfig, FeatureFlagConfig, NodeType, WaitSignalsConfig, WorkflowDefinition};
+
+/// Whether a variable's type is left to inference
+pub fn is_untyped(var_type: &str) -> bool {
+    var_type.is_empty() || var_type == "auto"
+}
+
+/// Declared type for a JSON value
+fn value_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some("bool"),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some("int"),
+        Value::Number(_) => Some("float"),
+        Value::String(_) => Some("string"),
+        Value::Array(_) => Some("array"),
+        Value::Object(_) => Some("object"),
+    }
+}
+
+/// Declared type for a JSON Schema `type` keyword
+fn schema_type(schema: &Value) -> Option<&'static str> {
+    match schema.get("type")?.as_str()? {
+        "string" => Some("string"),
+        "integer" => Some("int"),
+        "number" => Some("float"),
+        "boolean" => Some("bool"),
+        "object" => Some("object"),
+        "array" => Some("array"),
+        _ => None,
+    }
+}
+
+/// Declared type for a literal written in an edge condition
+fn literal_type(literal: &str) -> &'static str {
+    match literal {
+        "true" | "false" => "bool",
+        _ if literal.starts_with(['"', '\'']) => "string",
+        _ if literal.contains('.') => "float",
+        _ => "int",
+    }
+}
+
+/// Comparisons of a variable against a literal, in either order
+fn comparisons() -> &'static (Regex, Regex) {
+    static PATTERNS: OnceLock<(Regex, Regex)> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let literal = r#"("[^"]*"|'[^']*'|-?\d+(?:\.\d+)?|true|false)"#;
+        let variable = r"(?:input\.)?([A-Za-z_]\w*)";
+        let op = r"\s*(?:==|!=|<=|>=|<|>)\s*";
+        (
+            Regex::new(&format!("{variable}{op}{literal}")).expect("valid comparison pattern"),
+            Regex::new(&format!("{literal}{op}{variable}")).expect("valid comparison pattern"),
+        )
+    })
+}
+
+/// Combine evidence into one type, widening `int` to `float`; `None` when types conflict
+fn unify(evidence: &[(&'static str, String)]) -> Option<&'static str> {
+    let mut unified = evidence.first()?.0;
+    for (var_type, _) in &evidence[1..] {
+        unified = match (unified, *var_type) {
+            (a, b) if a == b => a,
+            ("int", "float") | ("float", "int") => "float",
+            _ => return None,
+        };
+    }
+    Some(unified)
+}
+
+/// Return a copy of the definition with untyped variables resolved, plus a diagnostic for
+/// each variable whose evidence conflicts or is missing; those fall back to `any`
+pub fn infer_variable_types(definition: &WorkflowDefinition) -> (WorkflowDefinition, Vec<String>) {
+    let mut typed = definition.clone();
+    let mut diagnostics = Vec::new();
+    if !definition.variables.iter().any(|v| is_untyped(&v.var_type)) {
+        return (typed, diagnostics);
+    }
+
+    // Usage evidence as (variable, type, source)
+    let mut usage: Vec<(String, &'static str, String)> = Vec::new();
+    for node in &definition.nodes {
+        if is_activity_node(node) {
+            if let Some(properties) = node.config.pointer("/output_schema/properties").and_then(Value::as_object) {
+                for (name, schema) in properties {
+                    if let Some(var_type) = schema_type(schema) {
+                        usage.push((name.clone(), var_type, format!("output of node '{}'", node.id)));
+                    }
+                }
+            }
+        }
+        match node.node_type {
+            NodeType::DecisionTable => {
+                for (input, var_type) in decision_table::input_types(node) {
+                    usage.push((input, var_type, format!("decision table '{}'", node.id)));
+                }
+            }
+            NodeType::WaitSignals => {
+                if let Some(key) = node.typed_config::<WaitSignalsConfig>().ok().and_then(|c| c.correlation_key) {
+                    usage.push((key, "string", format!("correlation key of node '{}'", node.id)));
+                }
+            }
+            NodeType::DynamicActivity => {
+                if let Ok(config) = node.typed_config::<DynamicActivityConfig>() {
+                    usage.push((config.selector, "string", format!("selector of node '{}'", node.id)));
+                }
+            }
+            NodeType::FeatureFlag => {
+                if let Some(key) = node.typed_config::<FeatureFlagConfig>().ok().and_then(|c| c.context) {
+                    usage.push((key, "string", format!("flag context of node '{}'", node.id)));
+                }
+            }
+            _ => {}
+        }
+    }
+    let (forward, reverse) = comparisons();
+    for edge in &definition.edges {
+        let Some(condition) = &edge.condition else { continue };
+        let source = format!("condition on edge '{}'", edge.id);
+        for captures in forward.captures_iter(condition) {
+            usage.push((captures[1].to_string(), literal_type(&captures[2]), source.clone()));
+        }
+        for captures in reverse.captures_iter(condition) {
+            usage.push((captures[2].to_string(), literal_type(&captures[1]), source.clone()));
+        }
+    }
+
+    for variable in typed.variables.iter_mut().filter(|v| is_untyped(&v.var_type)) {
+        let mut evidence: Vec<(&'static str, String)> = Vec::new();
+        if let Some(var_type) = variable.default_value.as_ref().and_then(value_type) {
+            evidence.push((var_type, "default value".to_string()));
+        }
+        evidence.extend(usage.iter()
+            .filter(|(name, _, _)| *name == variable.name)
+            .map(|(_, var_type, source)| (*var_type, source.clone())));
+
+        variable.var_type = match unify(&evidence) {
+            Some(var_type) => var_type.to_string(),
+            None if evidence.is_empty() => {
+                diagnostics.push(format!(
+                    "Variable '{}' has no type and none could be inferred; it is generated as any",
+                    variable.name
+                ));
+                "any".to_string()
+            }
+            None => {
+                let sources: Vec<String> = evidence.iter().map(|(t, source)| format!("{} from {}", t, source)).collect();
+                diagnostics.push(format!(
+                    "Variable '{}' has conflicting inferred types ({}); it is generated as any",
+                    variable.name,
+                    sources.join(", ")
+                ));
+                "any".to_string()
+            }
+        };
+    }
+
+    (typed, diagnostics)
+}
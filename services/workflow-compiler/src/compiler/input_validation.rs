@@ -0,0 +1,300 @@
+//! Workflow input validation generated from variable schemas
+//!
+//! The workflow checks its input before running any node and fails with a non-retryable
+//! `InputValidationError` whose details list every violation, so a malformed start fails
+//! immediately instead of deep inside an activity.
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::compiler::codegen::{go_string_literal, go_type, indent};
+use crate::compiler::testgen;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, Variable, VariableSchema, WorkflowDefinition};
+
+/// Application error type of a rejected workflow input
+pub const ERROR_TYPE: &str = "InputValidationError";
+
+/// Schema of a variable that constrains anything
+fn constraints(variable: &Variable) -> Option<&VariableSchema> {
+    variable.schema.as_ref().filter(|s| {
+        s.required || s.minimum.is_some() || s.maximum.is_some() || s.min_length.is_some() || s.max_length.is_some() || s.pattern.is_some()
+    })
+}
+
+/// Whether the workflow validates its input
+pub fn uses_input_validation(definition: &WorkflowDefinition) -> bool {
+    definition.variables.iter().any(|v| constraints(v).is_some())
+}
+
+/// Unit counted by length bounds
+fn length_unit(go_type: &str) -> &'static str {
+    if go_type == "string" { "characters" } else { "items" }
+}
+
+/// Violation of a present value against the bounds and pattern of its schema
+fn violation(schema: &VariableSchema, go_type: &str, value: &Value) -> Option<String> {
+    let length = match value {
+        Value::String(s) => Some(s.chars().count()),
+        Value::Array(items) => Some(items.len()),
+        _ => None,
+    };
+    if let (Some(n), Some(min)) = (value.as_f64(), schema.minimum) {
+        if n < min {
+            return Some(format!("must be at least {min}"));
+        }
+    }
+    if let (Some(n), Some(max)) = (value.as_f64(), schema.maximum) {
+        if n > max {
+            return Some(format!("must be at most {max}"));
+        }
+    }
+    if let (Some(n), Some(min)) = (length, schema.min_length) {
+        if n < min {
+            return Some(format!("must be at least {min} {}", length_unit(go_type)));
+        }
+    }
+    if let (Some(n), Some(max)) = (length, schema.max_length) {
+        if n > max {
+            return Some(format!("must be at most {max} {}", length_unit(go_type)));
+        }
+    }
+    if let (Some(s), Some(pattern)) = (value.as_str(), &schema.pattern) {
+        if !s.is_empty() && !Regex::new(pattern).is_ok_and(|re| re.is_match(s)) {
+            return Some(format!("must match pattern {pattern}"));
+        }
+    }
+    None
+}
+
+/// Check every variable schema fits its type and default; warns when the generated tests
+/// cannot satisfy a pattern
+pub fn validate(definition: &WorkflowDefinition) -> Result<Vec<String>, CompilerError> {
+    let mut warnings = Vec::new();
+    for variable in &definition.variables {
+        let Some(schema) = &variable.schema else { continue };
+        let go_type = go_type(&variable.var_type);
+        let invalid = |message: String| CompilerError::ValidationError(format!("Variable '{}' {}", variable.name, message).into());
+        let numeric = matches!(go_type, "int64" | "float64");
+
+        if schema.required && (numeric || go_type == "bool") {
+            return Err(invalid(format!(
+                "cannot be required: the zero value of a {} is a valid input, use minimum or maximum instead",
+                variable.var_type
+            )));
+        }
+        if schema.minimum.is_some() || schema.maximum.is_some() {
+            if !numeric {
+                return Err(invalid("minimum and maximum apply only to number variables".into()));
+            }
+            if go_type == "int64" && [schema.minimum, schema.maximum].iter().flatten().any(|b| b.fract() != 0.0) {
+                return Err(invalid("bounds must be whole numbers for an integer variable".into()));
+            }
+        }
+        if let (Some(min), Some(max)) = (schema.minimum, schema.maximum) {
+            if min > max {
+                return Err(invalid(format!("minimum {min} exceeds maximum {max}")));
+            }
+        }
+        if (schema.min_length.is_some() || schema.max_length.is_some()) && !matches!(go_type, "string" | "[]any") {
+            return Err(invalid("min_length and max_length apply only to string and array variables".into()));
+        }
+        if let (Some(min), Some(max)) = (schema.min_length, schema.max_length) {
+            if min > max {
+                return Err(invalid(format!("min_length {min} exceeds max_length {max}")));
+            }
+        }
+        if let Some(pattern) = &schema.pattern {
+            if go_type != "string" {
+                return Err(invalid("pattern applies only to string variables".into()));
+            }
+            let re = Regex::new(pattern).map_err(|e| invalid(format!("pattern is invalid: {e}")))?;
+            if !variable.default_value.as_ref().is_some_and(Value::is_string) && !re.is_match(&testgen::synthetic_string(variable)) {
+                warnings.push(format!(
+                    "Variable '{}' pattern does not match the generated test input; set a matching default_value",
+                    variable.name
+                ));
+            }
+        }
+        if let Some(message) = variable.default_value.as_ref().and_then(|d| violation(schema, go_type, d)) {
+            return Err(invalid(format!("default value {message}")));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Imports the generated validator needs in the workflow file
+pub fn imports(definition: &WorkflowDefinition) -> Vec<&'static str> {
+    if !uses_input_validation(definition) {
+        return vec![];
+    }
+    let mut imports = vec!["\"go.temporal.io/sdk/temporal\"", "\"strings\""];
+    let schemas = || definition.variables.iter().filter_map(|v| constraints(v).map(|s| (v, s)));
+    if schemas().any(|(_, s)| s.pattern.is_some()) {
+        imports.push("\"regexp\"");
+    }
+    if schemas().any(|(v, s)| go_type(&v.var_type) == "string" && (s.min_length.is_some() || s.max_length.is_some())) {
+        imports.push("\"unicode/utf8\"");
+    }
+    imports
+}
+
+fn pattern_var(workflow_name: &str, variable: &Variable) -> String {
+    let mut name = format!("{workflow_name}{}Pattern", to_pascal_case(&variable.name));
+    name.replace_range(..1, &name[..1].to_lowercase());
+    name
+}
+
+/// Statement recording a violation
+fn add(variable: &Variable, message: &str) -> String {
+    format!("add({}, {})\n", go_string_literal(&variable.name), go_string_literal(message))
+}
+
+/// Checks for one variable, guarded so optional strings and arrays are only checked when present
+fn variable_checks(variable: &Variable, schema: &VariableSchema, workflow_name: &str) -> String {
+    let go_type = go_type(&variable.var_type);
+    let field = format!("input.{}", to_pascal_case(&variable.name));
+    let check = |condition: String, message: String| format!("if {condition} {{\n{}}}\n", indent(&add(variable, &message), 4));
+
+    let mut checks = String::new();
+    if let Some(min) = schema.minimum {
+        checks.push_str(&check(format!("{field} < {min}"), format!("must be at least {min}")));
+    }
+    if let Some(max) = schema.maximum {
+        checks.push_str(&check(format!("{field} > {max}"), format!("must be at most {max}")));
+    }
+    let length = if go_type == "string" { format!("utf8.RuneCountInString({field})") } else { format!("len({field})") };
+    if let Some(min) = schema.min_length {
+        checks.push_str(&check(format!("{length} < {min}"), format!("must be at least {min} {}", length_unit(go_type))));
+    }
+    if let Some(max) = schema.max_length {
+        checks.push_str(&check(format!("{length} > {max}"), format!("must be at most {max} {}", length_unit(go_type))));
+    }
+    if let Some(pattern) = &schema.pattern {
+        let re = pattern_var(workflow_name, variable);
+        checks.push_str(&check(format!("!{re}.MatchString({field})"), format!("must match pattern {pattern}")));
+    }
+
+    let absent = match go_type {
+        "int64" | "float64" | "bool" => return checks,
+        "string" => format!("{field} == \"\""),
+        _ => format!("{field} == nil"),
+    };
+    match (schema.required, checks.is_empty()) {
+        (true, true) => format!("if {absent} {{\n{}}}\n", indent(&add(variable, "is required"), 4)),
+        (true, false) => format!("if {absent} {{\n{}}} else {{\n{}}}\n", indent(&add(variable, "is required"), 4), indent(&checks, 4)),
+        (false, _) => {
+            let present = absent.replace("==", "!=");
+            format!("if {present} {{\n{}}}\n", indent(&checks, 4))
+        }
+    }
+}
+
+/// Generate the violation types, compiled patterns and exported validator
+pub fn generate_validator(definition: &WorkflowDefinition, workflow_name: &str) -> String {
+    if !uses_input_validation(definition) {
+        return String::new();
+    }
+    let schemas: Vec<(&Variable, &VariableSchema)> = definition.variables.iter()
+        .filter_map(|v| constraints(v).map(|s| (v, s)))
+        .collect();
+    let patterns: String = schemas.iter()
+        .filter_map(|(v, s)| s.pattern.as_ref().map(|p| {
+            format!("var {} = regexp.MustCompile({})\n", pattern_var(workflow_name, v), go_string_literal(p))
+        }))
+        .collect();
+    let patterns = if patterns.is_empty() { patterns } else { format!("{patterns}\n") };
+    let checks: String = schemas.iter().map(|(v, s)| variable_checks(v, s, workflow_name)).collect();
+    let checks = indent(&checks, 4);
+
+    format!(r#"// {workflow_name}InputViolation is one field of the workflow input that failed validation
+type {workflow_name}InputViolation struct {{
+    Field   string `json:"field"`
+    Message string `json:"message"`
+}}
+
+// {workflow_name}InputValidationError lists every violation found in a workflow input; the
+// workflow fails with it as the details of a non-retryable "{ERROR_TYPE}"
+type {workflow_name}InputValidationError struct {{
+    Violations []{workflow_name}InputViolation `json:"violations"`
+}}
+
+func (e *{workflow_name}InputValidationError) Error() string {{
+    messages := make([]string, len(e.Violations))
+    for i, v := range e.Violations {{
+        messages[i] = v.Field + " " + v.Message
+    }}
+    return "invalid {workflow_name} input: " + strings.Join(messages, "; ")
+}}
+
+{patterns}// Validate{workflow_name}Input checks an input against the variable schemas; starters can call
+// it to reject a malformed input before starting a run
+func Validate{workflow_name}Input(input {workflow_name}Input) *{workflow_name}InputValidationError {{
+    var violations []{workflow_name}InputViolation
+    add := func(field, message string) {{
+        violations = append(violations, {workflow_name}InputViolation{{Field: field, Message: message}})
+    }}
+
+{checks}
+    if len(violations) == 0 {{
+        return nil
+    }}
+    return &{workflow_name}InputValidationError{{Violations: violations}}
+}}
+
+"#)
+}
+
+/// Go statement breaking one constraint of the synthetic test input, with the rule it breaks
+pub fn invalid_assignment(definition: &WorkflowDefinition) -> Option<(String, String)> {
+    definition.variables.iter().find_map(|variable| {
+        let schema = constraints(variable)?;
+        let go_type = go_type(&variable.var_type);
+        let field = format!("input.{}", to_pascal_case(&variable.name));
+        let assign = |value: String, rule: String| Some((format!("{field} = {value}"), format!("{} {rule}", variable.name)));
+        let text = |len: usize| go_string_literal(&"x".repeat(len));
+        let items = |len: usize| format!("[]any{{{}}}", vec!["\"item\""; len].join(", "));
+        let bound = |n: f64| if go_type == "int64" { (n as i64).to_string() } else { n.to_string() };
+
+        if schema.required {
+            let empty = if go_type == "string" { "\"\"" } else { "nil" };
+            return assign(empty.to_string(), "is required".into());
+        }
+        if let Some(min) = schema.minimum {
+            return assign(bound(min - 1.0), format!("must be at least {min}"));
+        }
+        if let Some(max) = schema.maximum {
+            return assign(bound(max + 1.0), format!("must be at most {max}"));
+        }
+        let unit = length_unit(go_type);
+        match schema.min_length {
+            // An empty optional string counts as absent
+            Some(min) if go_type == "string" && min > 1 => return assign(text(min - 1), format!("must be at least {min} {unit}")),
+            Some(min) if go_type == "[]any" && min > 0 => return assign(items(min - 1), format!("must be at least {min} {unit}")),
+            _ => {}
+        }
+        if let Some(max) = schema.max_length {
+            let value = if go_type == "string" { text(max + 1) } else { items(max + 1) };
+            return assign(value, format!("must be at most {max} {unit}"));
+        }
+        let pattern = schema.pattern.as_ref()?;
+        let re = Regex::new(pattern).ok()?;
+        let mismatch = ["!", "x", "0", " ", "\n"].into_iter().find(|c| !re.is_match(c))?;
+        assign(go_string_literal(mismatch), format!("must match pattern {pattern}"))
+    })
+}
+
+/// Generate the check run at the top of the workflow function
+pub fn generate_check(definition: &WorkflowDefinition, workflow_name: &str) -> String {
+    if !uses_input_validation(definition) {
+        return String::new();
+    }
+    format!(r#"    // Reject malformed input before any node runs
+    if verr := Validate{workflow_name}Input(input); verr != nil {{
+        logger.Error("Invalid workflow input", "error", verr)
+        return nil, temporal.NewNonRetryableApplicationError(verr.Error(), "{ERROR_TYPE}", verr, verr.Violations)
+    }}
+
+"#)
+}
@@ -0,0 +1,532 @@
+//! Java code generation on `io.temporal:temporal-sdk`, in the Maven layout
+//!
+//! The workflow and activity implementations, worker main and JUnit 5 test map onto the
+//! `CompiledWorkflow` code fields; `pom.xml`, the workflow and activity interfaces and the
+//! input and output classes are project files. Node logic comes from the shared step traversal.
+
+use std::time::Duration;
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{go_string_literal as string_literal, VERSION_QUERY};
+use crate::compiler::steps::{self, ActivityCall, Retry, Step, TargetSources, Timeouts};
+use crate::compiler::types::base_type;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, ChildCancellationType, CodegenTarget, GeneratedFile, ParentClosePolicy, WorkflowDefinition};
+
+/// Temporal Java SDK release the generated project depends on
+pub const SDK_VERSION: &str = "1.25.0";
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class", "const", "continue", "default",
+    "do", "double", "else", "enum", "extends", "final", "finally", "float", "for", "goto", "if", "implements", "import",
+    "instanceof", "int", "interface", "long", "native", "new", "package", "private", "protected", "public", "return",
+    "short", "static", "strictfp", "super", "switch", "synchronized", "this", "throw", "throws", "transient", "try",
+    "void", "volatile", "while", "true", "false", "null",
+];
+
+/// Map a DSL variable type onto a Java type
+fn java_type(var_type: &str) -> &'static str {
+    match base_type(var_type) {
+        "string" => "String",
+        "int" | "integer" => "Long",
+        "float" | "number" => "Double",
+        "bool" | "boolean" => "Boolean",
+        "object" => "Map<String, Object>",
+        "array" => "List<Object>",
+        _ => "Object",
+    }
+}
+
+/// camelCase Java identifier for a name such as a variable or activity
+fn identifier(name: &str) -> String {
+    let pascal: String = to_pascal_case(name).chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let mut chars = pascal.chars();
+    let mut ident: String = match chars.next() {
+        Some(c) => c.to_lowercase().chain(chars).collect(),
+        None => "field".to_string(),
+    };
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+fn java_duration(duration: &Duration) -> String {
+    format!("Duration.ofMillis({})", duration.as_millis())
+}
+
+fn retry_options(retry: &Retry) -> String {
+    format!(
+        "RetryOptions.newBuilder()\n                .setInitialInterval({})\n                .setMaximumInterval({})\n                .setBackoffCoefficient({:?})\n                .setMaximumAttempts({})\n                .build()",
+        java_duration(&retry.initial_interval), java_duration(&retry.max_interval), retry.backoff_coefficient, retry.max_attempts
+    )
+}
+
+/// Activity options expression, with the node's timeouts and retry policy when it has them
+fn options(retry: &Option<Retry>, timeouts: &Timeouts) -> String {
+    let mut setters = String::new();
+    let durations = [
+        ("setStartToCloseTimeout", &timeouts.start_to_close),
+        ("setScheduleToCloseTimeout", &timeouts.schedule_to_close),
+        ("setHeartbeatTimeout", &timeouts.heartbeat),
+    ];
+    for (setter, duration) in durations {
+        if let Some(duration) = duration {
+            setters.push_str(&format!("\n            .{}({})", setter, java_duration(duration)));
+        }
+    }
+    if let Some(retry) = retry {
+        setters.push_str(&format!("\n            .setRetryOptions({})", retry_options(retry)));
+    }
+    if setters.is_empty() {
+        return "activityOptions".to_string();
+    }
+    format!("ActivityOptions.newBuilder(activityOptions){}\n            .build()", setters)
+}
+
+fn activity_call(call: &ActivityCall, workflow_name: &str, pad: &str) -> String {
+    let method = identifier(&call.activity);
+    let stub = match options(&call.retry, &call.timeouts).as_str() {
+        "activityOptions" => "activities".to_string(),
+        options => format!("Workflow.newActivityStub({workflow_name}Activities.class, {})", options),
+    };
+    format!("{pad}// {}\n{pad}{stub}.{method}(input);\n", call.label)
+}
+
+fn render_step(step: &Step, workflow_name: &str) -> String {
+    match step {
+        Step::Activity(call) => format!("{}\n", activity_call(call, workflow_name, "        ")),
+        Step::Timer { label, raw, duration } => {
+            format!("        // {label}: wait {raw}\n        Workflow.sleep({});\n\n", java_duration(duration))
+        }
+        Step::Signals { label, signals, required, correlation_key, timeout } => {
+            let names = signals.iter().map(|s| string_literal(s)).collect::<Vec<_>>().join(", ");
+            let key = correlation_key.as_deref().map(|k| format!("Objects.toString(input.{}, null)", identifier(k))).unwrap_or_else(|| "null".to_string());
+            let count = signals.len();
+            let condition = format!(
+                "() -> {{\n                pending.removeIf(name -> takeSignal(name, key));\n                return {count} - pending.size() >= {required};\n            }}"
+            );
+            let wait = match timeout {
+                Some(timeout) => format!(
+                    "            if (!Workflow.await({}, {condition})) {{\n                throw ApplicationFailure.newNonRetryableFailure({}, \"SignalTimeout\");\n            }}\n",
+                    java_duration(timeout),
+                    string_literal(&format!("{label} timed out waiting for signals"))
+                ),
+                None => format!("            Workflow.await({condition});\n"),
+            };
+            format!(r#"        // Wait for signals: {label} ({required} of {count})
+        {{
+            Set<String> pending = new HashSet<>(List.of({names}));
+            String key = {key};
+{wait}        }}
+
+"#)
+        }
+        Step::DynamicActivity { label, selector, allowed, retry, timeouts } => {
+            let allowed = allowed.iter().map(|a| string_literal(a)).collect::<Vec<_>>().join(", ");
+            let not_allowed = string_literal(&format!("{label}: activity not allowed: "));
+            let selector = identifier(selector);
+            format!(r#"        // Dynamic activity: {label}
+        {{
+            String activityName = Objects.toString(input.{selector}, "");
+            if (!Set.of({allowed}).contains(activityName)) {{
+                throw ApplicationFailure.newNonRetryableFailure({not_allowed} + activityName, "ActivityNotAllowed");
+            }}
+            Workflow.newUntypedActivityStub({}).execute(activityName, Void.class, input);
+        }}
+
+"#, options(retry, timeouts))
+        }
+        Step::Child { label, workflow, task_queue, parent_close_policy, cancellation_type, wait_for_completion } => {
+            let close = match parent_close_policy {
+                ParentClosePolicy::Terminate => "PARENT_CLOSE_POLICY_TERMINATE",
+                ParentClosePolicy::RequestCancel => "PARENT_CLOSE_POLICY_REQUEST_CANCEL",
+                ParentClosePolicy::Abandon => "PARENT_CLOSE_POLICY_ABANDON",
+            };
+            let cancellation = match cancellation_type {
+                ChildCancellationType::TryCancel => "TRY_CANCEL",
+                ChildCancellationType::WaitCancellationCompleted => "WAIT_CANCELLATION_COMPLETED",
+                ChildCancellationType::Abandon => "ABANDON",
+            };
+            let task_queue = task_queue.as_deref()
+                .map(|q| format!("\n                .setTaskQueue({})", string_literal(q)))
+                .unwrap_or_default();
+            let run = if *wait_for_completion {
+                "            child.execute(Void.class);\n"
+            } else {
+                "            child.executeAsync(Void.class);\n            // Wait only until the child has started\n            child.getExecution().get();\n"
+            };
+            format!(r#"        // Child workflow: {label}
+        {{
+            ChildWorkflowStub child = Workflow.newUntypedChildWorkflowStub({workflow}, ChildWorkflowOptions.newBuilder(){task_queue}
+                .setParentClosePolicy(ParentClosePolicy.{close})
+                .setCancellationType(ChildWorkflowCancellationType.{cancellation})
+                .build());
+{run}        }}
+
+"#, workflow = string_literal(workflow))
+        }
+        Step::WeightedSplit { label, branches } => {
+            let weights = branches.iter()
+                .scan(0, |lower, (upper, _)| {
+                    let weight = upper - *lower;
+                    *lower = *upper;
+                    Some(weight.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let mut cases = String::new();
+            for (i, (upper, call)) in branches.iter().enumerate() {
+                let condition = if i + 1 == branches.len() {
+                    " else {\n".to_string()
+                } else if i == 0 {
+                    format!("            if (bucket < {upper}) {{\n")
+                } else {
+                    format!(" else if (bucket < {upper}) {{\n")
+                };
+                cases.push_str(&condition);
+                cases.push_str(&activity_call(call, workflow_name, "                "));
+                cases.push_str("            }");
+            }
+            format!(r#"        // Weighted split: {label} ({weights})
+        {{
+            // Workflow.newRandom is seeded per execution, so replays pick the same bucket
+            int bucket = Workflow.newRandom().nextInt(100);
+{cases}
+        }}
+
+"#)
+        }
+    }
+}
+
+fn java_file(package: &str, imports: &str, body: &str) -> String {
+    let imports = if imports.is_empty() { String::new() } else { format!("{imports}\n") };
+    format!("// Generated by OmniRoute Workflow Compiler\n// DO NOT EDIT - This file is auto-generated\n\npackage {package};\n\n{imports}{body}")
+}
+
+const WORKFLOW_IMPORTS: &str = r#"import io.temporal.activity.ActivityOptions;
+import io.temporal.api.enums.v1.ParentClosePolicy;
+import io.temporal.common.RetryOptions;
+import io.temporal.failure.ApplicationFailure;
+import io.temporal.workflow.ChildWorkflowCancellationType;
+import io.temporal.workflow.ChildWorkflowOptions;
+import io.temporal.workflow.ChildWorkflowStub;
+import io.temporal.workflow.Workflow;
+import java.time.Duration;
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.HashSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Objects;
+import java.util.Set;
+"#;
+
+fn generate_workflow_impl(definition: &WorkflowDefinition, steps: &[Step], workflow_name: &str, package: &str, fingerprint: &str) -> String {
+    let signals = steps::signal_names(steps);
+    let signal_handlers: String = signals.iter().enumerate()
+        .map(|(i, name)| format!(r#"
+    @Override
+    public void signal{i}({workflow_name}Workflow.SignalPayload payload) {{
+        received.computeIfAbsent({name}, k -> new ArrayList<>()).add(payload != null ? payload : new {workflow_name}Workflow.SignalPayload());
+    }}
+"#, name = string_literal(name)))
+        .collect();
+    let signal_buffer = if signals.is_empty() {
+        String::new()
+    } else {
+        format!("\n    // Buffer signals from the start of the execution so none are missed before their wait\n    private final Map<String, List<{workflow_name}Workflow.SignalPayload>> received = new HashMap<>();\n")
+    };
+    let take_signal = if signals.is_empty() {
+        String::new()
+    } else {
+        format!(r#"
+    private boolean takeSignal(String name, String key) {{
+        List<{workflow_name}Workflow.SignalPayload> queue = received.get(name);
+        if (queue == null) {{
+            return false;
+        }}
+        for (int i = 0; i < queue.size(); i++) {{
+            if (key == null || key.equals(queue.get(i).correlationKey)) {{
+                queue.remove(i);
+                return true;
+            }}
+        }}
+        return false;
+    }}
+"#)
+    };
+    let body: String = steps.iter().map(|s| render_step(s, workflow_name)).collect();
+
+    java_file(package, WORKFLOW_IMPORTS, &format!(r#"public class {workflow_name}WorkflowImpl implements {workflow_name}Workflow {{
+    /** Version of the workflow definition this code was compiled from */
+    public static final String DEFINITION_VERSION = {version};
+    /** Content hash of that definition */
+    public static final String DEFINITION_FINGERPRINT = {fingerprint};
+
+    private final ActivityOptions activityOptions = ActivityOptions.newBuilder()
+        .setStartToCloseTimeout(Duration.ofMinutes(10))
+        .build();
+    private final {workflow_name}Activities activities = Workflow.newActivityStub({workflow_name}Activities.class, activityOptions);
+{signal_buffer}
+    @Override
+    public {workflow_name}Output run({workflow_name}Input input) {{
+{body}        return new {workflow_name}Output(true, "Workflow completed successfully");
+    }}
+
+    @Override
+    public Map<String, String> version() {{
+        return Map.of("version", DEFINITION_VERSION, "fingerprint", DEFINITION_FINGERPRINT);
+    }}
+{signal_handlers}{take_signal}}}
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+    ))
+}
+
+fn generate_workflow_interface(steps: &[Step], workflow_name: &str, package: &str) -> String {
+    let signals = steps::signal_names(steps);
+    let (signal_methods, payload) = if signals.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (
+            signals.iter().enumerate()
+                .map(|(i, name)| format!("\n    @SignalMethod(name = {})\n    void signal{i}(SignalPayload payload);\n", string_literal(name)))
+                .collect(),
+            r#"
+    /** Envelope expected on every correlated signal */
+    class SignalPayload {
+        @JsonProperty("correlation_key")
+        public String correlationKey;
+        @JsonProperty("data")
+        public Object data;
+    }
+"#.to_string(),
+        )
+    };
+    let imports = "import com.fasterxml.jackson.annotation.JsonProperty;\nimport io.temporal.workflow.QueryMethod;\nimport io.temporal.workflow.SignalMethod;\nimport io.temporal.workflow.WorkflowInterface;\nimport io.temporal.workflow.WorkflowMethod;\nimport java.util.Map;\n";
+
+    java_file(package, imports, &format!(r#"@WorkflowInterface
+public interface {workflow_name}Workflow {{
+    @WorkflowMethod(name = "{workflow_name}")
+    {workflow_name}Output run({workflow_name}Input input);
+
+    /** Reports the definition version a running execution was compiled from */
+    @QueryMethod(name = {query})
+    Map<String, String> version();
+{signal_methods}{payload}}}
+"#, query = string_literal(VERSION_QUERY)))
+}
+
+fn generate_data_classes(definition: &WorkflowDefinition, workflow_name: &str, package: &str) -> (String, String) {
+    let fields: String = definition.variables.iter()
+        .map(|v| format!("    @JsonProperty({})\n    public {} {};\n", string_literal(&v.name), java_type(&v.var_type), identifier(&v.name)))
+        .collect();
+    let input = java_file(
+        package,
+        "import com.fasterxml.jackson.annotation.JsonProperty;\nimport java.util.List;\nimport java.util.Map;\n",
+        &format!("/** Workflow input */\npublic class {workflow_name}Input {{\n{fields}}}\n"),
+    );
+    let output = java_file(package, "", &format!(r#"/** Workflow output */
+public class {workflow_name}Output {{
+    public boolean success;
+    public String message;
+
+    public {workflow_name}Output() {{}}
+
+    public {workflow_name}Output(boolean success, String message) {{
+        this.success = success;
+        this.message = message;
+    }}
+}}
+"#));
+    (input, output)
+}
+
+fn generate_activities(steps: &[Step], workflow_name: &str, package: &str) -> (String, String) {
+    let names = steps::activity_names(steps);
+    let methods: String = names.iter()
+        .map(|name| format!("\n    @ActivityMethod(name = {})\n    void {}({workflow_name}Input input);\n", string_literal(name), identifier(name)))
+        .collect();
+    let interface = java_file(
+        package,
+        "import io.temporal.activity.ActivityInterface;\nimport io.temporal.activity.ActivityMethod;\n",
+        &format!("@ActivityInterface\npublic interface {workflow_name}Activities {{{methods}}}\n"),
+    );
+    let implementations: String = names.iter()
+        .map(|name| format!(r#"
+    /** {name} implements the {name} activity */
+    @Override
+    public void {method}({workflow_name}Input input) {{
+        // TODO: implement {name}
+    }}
+"#, method = identifier(name)))
+        .collect();
+    let implementation = java_file(
+        package,
+        "",
+        &format!("public class {workflow_name}ActivitiesImpl implements {workflow_name}Activities {{{implementations}}}\n"),
+    );
+    (interface, implementation)
+}
+
+fn generate_worker(workflow_name: &str, package_name: &str, package: &str) -> String {
+    java_file(package, r#"import io.temporal.client.WorkflowClient;
+import io.temporal.client.WorkflowClientOptions;
+import io.temporal.serviceclient.WorkflowServiceStubs;
+import io.temporal.serviceclient.WorkflowServiceStubsOptions;
+import io.temporal.worker.Worker;
+import io.temporal.worker.WorkerFactory;
+"#, &format!(r#"public class {workflow_name}Worker {{
+    public static final String TASK_QUEUE = "{package_name}-task-queue";
+
+    public static void main(String[] args) {{
+        String address = System.getenv().getOrDefault("TEMPORAL_ADDRESS", "localhost:7233");
+        String namespace = System.getenv().getOrDefault("TEMPORAL_NAMESPACE", "default");
+
+        WorkflowServiceStubs service = WorkflowServiceStubs.newServiceStubs(
+            WorkflowServiceStubsOptions.newBuilder().setTarget(address).build());
+        WorkflowClient client = WorkflowClient.newInstance(
+            service, WorkflowClientOptions.newBuilder().setNamespace(namespace).build());
+        WorkerFactory factory = WorkerFactory.newInstance(client);
+
+        Worker worker = factory.newWorker(TASK_QUEUE);
+        worker.registerWorkflowImplementationTypes({workflow_name}WorkflowImpl.class);
+        worker.registerActivitiesImplementations(new {workflow_name}ActivitiesImpl());
+        factory.start();
+    }}
+}}
+"#))
+}
+
+fn generate_test(workflow_name: &str, package: &str) -> String {
+    java_file(package, r#"import static org.junit.jupiter.api.Assertions.assertTrue;
+
+import io.temporal.testing.TestWorkflowExtension;
+import org.junit.jupiter.api.Test;
+import org.junit.jupiter.api.extension.RegisterExtension;
+"#, &format!(r#"public class {workflow_name}WorkflowTest {{
+    @RegisterExtension
+    public static final TestWorkflowExtension testWorkflow = TestWorkflowExtension.newBuilder()
+        .registerWorkflowImplementationTypes({workflow_name}WorkflowImpl.class)
+        .setActivityImplementations(new {workflow_name}ActivitiesImpl())
+        .build();
+
+    @Test
+    public void completesSuccessfully({workflow_name}Workflow workflow) {{
+        {workflow_name}Output result = workflow.run(new {workflow_name}Input());
+        assertTrue(result.success);
+    }}
+}}
+"#))
+}
+
+fn generate_pom(package_name: &str, version: &str, package: &str, workflow_name: &str) -> String {
+    let artifact = package_name.replace('_', "-");
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Generated by OmniRoute Workflow Compiler -->
+<project xmlns="http://maven.apache.org/POM/4.0.0"
+         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+         xsi:schemaLocation="http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd">
+  <modelVersion>4.0.0</modelVersion>
+
+  <groupId>com.omniroute.workflows</groupId>
+  <artifactId>{artifact}</artifactId>
+  <version>{version}</version>
+
+  <properties>
+    <maven.compiler.release>17</maven.compiler.release>
+    <project.build.sourceEncoding>UTF-8</project.build.sourceEncoding>
+    <temporal.version>{SDK_VERSION}</temporal.version>
+  </properties>
+
+  <dependencies>
+    <dependency>
+      <groupId>io.temporal</groupId>
+      <artifactId>temporal-sdk</artifactId>
+      <version>${{temporal.version}}</version>
+    </dependency>
+    <dependency>
+      <groupId>io.temporal</groupId>
+      <artifactId>temporal-testing</artifactId>
+      <version>${{temporal.version}}</version>
+      <scope>test</scope>
+    </dependency>
+    <dependency>
+      <groupId>org.junit.jupiter</groupId>
+      <artifactId>junit-jupiter</artifactId>
+      <version>5.10.2</version>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+
+  <build>
+    <plugins>
+      <plugin>
+        <groupId>org.apache.maven.plugins</groupId>
+        <artifactId>maven-surefire-plugin</artifactId>
+        <version>3.2.5</version>
+      </plugin>
+      <plugin>
+        <groupId>org.codehaus.mojo</groupId>
+        <artifactId>exec-maven-plugin</artifactId>
+        <version>3.2.0</version>
+        <configuration>
+          <mainClass>{package}.{workflow_name}Worker</mainClass>
+        </configuration>
+      </plugin>
+    </plugins>
+  </build>
+</project>
+"#, version = xml_escape(version))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Java backend
+pub struct Java;
+
+impl Backend for Java {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Java
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let steps = steps::workflow_steps(definition, self.target().name())?;
+        let workflow_name = to_pascal_case(&definition.name);
+        let package = format!(
+            "com.omniroute.workflows.{}",
+            package_name.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_').collect::<String>()
+        );
+        let main_dir = format!("src/main/java/{}", package.replace('.', "/"));
+        let (input, output) = generate_data_classes(definition, &workflow_name, &package);
+        let (activity_interface, activity_code) = generate_activities(&steps, &workflow_name, &package);
+
+        Ok(TargetSources {
+            workflow_code: generate_workflow_impl(definition, &steps, &workflow_name, &package, fingerprint),
+            activity_code,
+            worker_code: generate_worker(&workflow_name, package_name, &package),
+            test_code: generate_test(&workflow_name, &package),
+            files: vec![
+                GeneratedFile { path: "pom.xml".to_string(), content: generate_pom(package_name, &definition.version, &package, &workflow_name) },
+                GeneratedFile { path: format!("{main_dir}/{workflow_name}Workflow.java"), content: generate_workflow_interface(&steps, &workflow_name, &package) },
+                GeneratedFile { path: format!("{main_dir}/{workflow_name}Activities.java"), content: activity_interface },
+                GeneratedFile { path: format!("{main_dir}/{workflow_name}Input.java"), content: input },
+                GeneratedFile { path: format!("{main_dir}/{workflow_name}Output.java"), content: output },
+            ],
+            signals: steps::signal_names(&steps),
+            metadata: None,
+        })
+    }
+}
@@ -0,0 +1,143 @@
+//! Wall-clock and memory limits for a single compilation
+//!
+//! `enforce` installs the configured limits on the current thread for the duration of a
+//! compilation, and the passes whose cost grows fastest on pathological input call
+//! `checkpoint` between units of work. A compilation past either limit stops at its next
+//! checkpoint with a structured error instead of holding a runtime worker or the service's
+//! memory. Memory is the heap the thread allocated since the compilation began, as counted by
+//! `CountingAllocator`; without it installed as the global allocator only time is limited.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::config::CompilationLimits;
+use crate::error::CompilerError;
+
+thread_local! {
+    /// Net bytes allocated on this thread
+    static ALLOCATED: Cell<i64> = const { Cell::new(0) };
+    static ACTIVE: Cell<Option<Active>> = const { Cell::new(None) };
+}
+
+/// Limits of the compilation running on this thread
+#[derive(Debug, Clone, Copy)]
+struct Active {
+    started: Instant,
+    timeout: Option<Duration>,
+    max_memory_bytes: Option<u64>,
+    /// `ALLOCATED` when the compilation started
+    baseline: i64,
+}
+
+/// System allocator that counts the bytes each thread holds
+pub struct CountingAllocator;
+
+fn record(bytes: i64) {
+    // Allocations while the thread's locals are torn down go uncounted
+    let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + bytes));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as i64));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            record(new_size as i64 - layout.size() as i64);
+        }
+        new
+    }
+}
+
+/// Clears the thread's limits when the compilation ends, including by panic
+struct Reset;
+
+impl Drop for Reset {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.set(None));
+    }
+}
+
+/// Run `compile` under `limits`; compilations nested in one already limited share its limits
+pub fn enforce<T>(limits: &CompilationLimits, compile: impl FnOnce() -> Result<T, CompilerError>) -> Result<T, CompilerError> {
+    if ACTIVE.with(Cell::get).is_some() {
+        return compile();
+    }
+    ACTIVE.with(|active| {
+        active.set(Some(Active {
+            started: Instant::now(),
+            timeout: limits.timeout_ms.map(Duration::from_millis),
+            max_memory_bytes: limits.max_memory_bytes,
+            baseline: ALLOCATED.with(Cell::get),
+        }))
+    });
+    let _reset = Reset;
+    compile()
+}
+
+/// Stop the running compilation if it is past a limit; `stage` names the pass in the error
+pub fn checkpoint(stage: &'static str) -> Result<(), CompilerError> {
+    let Some(active) = ACTIVE.with(Cell::get) else { return Ok(()) };
+    let elapsed = active.started.elapsed();
+    if let Some(timeout) = active.timeout.filter(|timeout| elapsed > *timeout) {
+        return Err(CompilerError::CompilationTimedOut {
+            stage,
+            limit_ms: timeout.as_millis() as u64,
+            elapsed_ms: elapsed.as_millis() as u64,
+        });
+    }
+    let used = ALLOCATED.with(Cell::get).saturating_sub(active.baseline).max(0) as u64;
+    if let Some(limit) = active.max_memory_bytes.filter(|limit| used > *limit) {
+        return Err(CompilerError::CompilationMemoryExceeded { stage, limit_bytes: limit, used_bytes: used });
+    }
+    Ok(())
+}
+
+/// Limit a compilation was stopped by, as reported in responses
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitExceeded {
+    /// `time` or `memory`
+    pub limit: &'static str,
+    pub stage: &'static str,
+    /// Milliseconds for `time`, bytes for `memory`
+    pub maximum: u64,
+    pub used: u64,
+}
+
+impl LimitExceeded {
+    /// The limit behind `error`, or `None` for errors of the definition itself
+    pub fn from_error(error: &CompilerError) -> Option<Self> {
+        match error {
+            CompilerError::CompilationTimedOut { stage, limit_ms, elapsed_ms } => {
+                Some(LimitExceeded { limit: "time", stage, maximum: *limit_ms, used: *elapsed_ms })
+            }
+            CompilerError::CompilationMemoryExceeded { stage, limit_bytes, used_bytes } => {
+                Some(LimitExceeded { limit: "memory", stage, maximum: *limit_bytes, used: *used_bytes })
+            }
+            CompilerError::Rule { error, .. } => Self::from_error(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Lint rules: checks for definitions that compile but are likely to misbehave in production
+//!
+//! Lints are listed in `rules::RULES` with their default severity and reported by
+//! `POST /api/v1/lint`; they never fail compilation. The `lints` map of the service config
+//! sets a lint's level to `off`, `info`, `warning` or `error`, and a request's `rules` map
+//! overrides the configured levels for that request. Lints run after plugins are lowered and
+//! execution profiles applied, so settings a profile supplies count as set.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compiler::codegen::is_activity_node;
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::rules::{self, Severity};
+use crate::{NodeType, WorkflowDefinition};
+
+/// Level a lint is reported at, or `off` to skip it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LintLevel {
+    fn severity(self) -> Option<Severity> {
+        match self {
+            LintLevel::Off => None,
+            LintLevel::Info => Some(Severity::Info),
+            LintLevel::Warning => Some(Severity::Warning),
+            LintLevel::Error => Some(Severity::Error),
+        }
+    }
+}
+
+/// Lint levels by rule code
+pub type LintLevels = BTreeMap<String, LintLevel>;
+
+struct Lint {
+    code: &'static str,
+    check: fn(&WorkflowDefinition) -> Vec<Diagnostic>,
+}
+
+const LINTS: &[Lint] = &[
+    Lint { code: "activity-missing-retry", check: activity_missing_retry },
+    Lint { code: "http-call-without-timeout", check: http_call_without_timeout },
+    Lint { code: "unlabeled-decision-branch", check: unlabeled_decision_branch },
+];
+
+/// Check that every code in `levels` names a lint
+pub fn check_levels(levels: &LintLevels) -> Result<(), String> {
+    match levels.keys().find(|code| !LINTS.iter().any(|l| l.code == code.as_str())) {
+        Some(code) => Err(format!(
+            "Unknown lint rule '{}'; lint rules are {}",
+            code,
+            LINTS.iter().map(|l| l.code).collect::<Vec<_>>().join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Findings of every lint not turned off, at the level requested, else configured, else the
+/// rule's default
+pub fn lint(definition: &WorkflowDefinition, configured: &LintLevels, requested: &LintLevels) -> Vec<Diagnostic> {
+    run(definition, |code| match requested.get(code).or_else(|| configured.get(code)) {
+        Some(level) => level.severity(),
+        None => rules::find(code).map(|r| r.severity),
+    })
+}
+
+/// Findings of only the lints `levels` lists, at those levels
+pub fn lint_only(definition: &WorkflowDefinition, levels: &LintLevels) -> Vec<Diagnostic> {
+    run(definition, |code| levels.get(code).and_then(|level| level.severity()))
+}
+
+fn run(definition: &WorkflowDefinition, severity: impl Fn(&str) -> Option<Severity>) -> Vec<Diagnostic> {
+    let mut findings = vec![];
+    for lint in LINTS {
+        let Some(severity) = severity(lint.code) else { continue };
+        findings.extend((lint.check)(definition).into_iter().map(|finding| Diagnostic { code: Some(lint.code), severity, ..finding }));
+    }
+    findings
+}
+
+fn activity_missing_retry(definition: &WorkflowDefinition) -> Vec<Diagnostic> {
+    definition.nodes.iter()
+        .filter(|n| (is_activity_node(n) || matches!(n.node_type, NodeType::DynamicActivity)) && n.retries.is_none())
+        .map(|n| Diagnostic::new(format!(
+            "Activity node '{}' has no retry policy, so it retries with Temporal's default of unlimited attempts",
+            n.id
+        )).node(&n.id))
+        .collect()
+}
+
+fn http_call_without_timeout(definition: &WorkflowDefinition) -> Vec<Diagnostic> {
+    definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::HttpCall))
+        .filter(|n| n.timeouts.as_ref().is_none_or(|t| t.start_to_close.is_none() && t.schedule_to_close.is_none()))
+        .map(|n| Diagnostic::new(format!(
+            "HttpCall node '{}' sets no start_to_close or schedule_to_close timeout, so each attempt may run for the workflow default of 10 minutes",
+            n.id
+        )).node(&n.id))
+        .collect()
+}
+
+fn unlabeled_decision_branch(definition: &WorkflowDefinition) -> Vec<Diagnostic> {
+    definition.edges.iter()
+        .filter(|e| e.label.as_deref().is_none_or(|l| l.trim().is_empty()))
+        .filter(|e| definition.nodes.iter().any(|n| n.id == e.source && matches!(n.node_type, NodeType::Decision)))
+        .map(|e| Diagnostic::new(format!("Edge '{}' leaving Decision node '{}' has no label", e.id, e.source)).edge(&e.id))
+        .collect()
+}
@@ -0,0 +1,406 @@
+//! Message catalog translating diagnostics into the client's language, keyed by rule code
+//!
+//! A localized diagnostic carries its rule's summary in the selected locale next to the
+//! specific English message and the node or edge at fault, so editors can show both and
+//! highlight the element while codes stay the stable key they match on. The locale comes
+//! from `Accept-Language`; English serves unknown locales and codes without a translation.
+
+use serde::Serialize;
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::rules::{self, Severity};
+use crate::Position;
+
+/// Locale used when the client accepts none of the translated ones
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales with translations, besides English
+const TRANSLATED: &[&str] = &["fr", "es", "pt"];
+
+/// Rule summaries by code, in French, Spanish and Portuguese
+const CATALOG: &[(&str, [&str; 3])] = &[
+    ("plugin-node", [
+        "Les nœuds de types fournis par des plugins nomment un plugin enregistré et passent sa validation",
+        "Los nodos de tipos de plugin nombran un plugin registrado y superan su validación",
+        "Os nós de tipos de plugin nomeiam um plugin registrado e passam na sua validação",
+    ]),
+    ("missing-start-node", [
+        "Le workflow doit avoir un nœud Start",
+        "El flujo de trabajo necesita un nodo Start",
+        "O fluxo de trabalho precisa de um nó Start",
+    ]),
+    ("missing-end-node", [
+        "Le workflow doit avoir un nœud End",
+        "El flujo de trabajo necesita un nodo End",
+        "O fluxo de trabalho precisa de um nó End",
+    ]),
+    ("duplicate-node-id", [
+        "Chaque nœud a un ID qu'aucun autre nœud n'utilise",
+        "Cada nodo tiene un ID que ningún otro nodo usa",
+        "Cada nó tem um ID que nenhum outro nó usa",
+    ]),
+    ("edge-reference", [
+        "Les arêtes relient des nœuds existants, n'entrent jamais dans Start ni ne sortent de End et ont des ID uniques",
+        "Las aristas conectan nodos existentes, nunca entran en Start ni salen de End y tienen ID únicos",
+        "As arestas ligam nós existentes, nunca entram em Start nem saem de End e têm IDs únicos",
+    ]),
+    ("complexity-budget", [
+        "Le nombre de nœuds et d'arêtes, la parallélisation et son imbrication et le nombre estimé d'événements d'historique restent dans les budgets configurés",
+        "El número de nodos y aristas, la ramificación paralela y su anidamiento y los eventos de historial estimados se mantienen dentro de los presupuestos configurados",
+        "O número de nós e arestas, a ramificação paralela e seu aninhamento e os eventos de histórico estimados ficam dentro dos orçamentos configurados",
+    ]),
+    ("history-size", [
+        "Une exécution devrait enregistrer plus d'événements d'historique que le seuil d'avertissement de Temporal",
+        "Se estima que una ejecución registre más eventos de historial que el umbral de advertencia de Temporal",
+        "Estima-se que uma execução registre mais eventos de histórico do que o limite de aviso do Temporal",
+    ]),
+    ("invalid-node-config", [
+        "La configuration d'un nœud ne correspond pas au schéma de son type",
+        "La configuración de un nodo no coincide con el esquema de su tipo",
+        "A configuração de um nó não corresponde ao esquema do seu tipo",
+    ]),
+    ("legacy-payload", [
+        "Des noms de champs obsolètes, des identifiants ou positions manquants et des nombres en texte ont été corrigés à l'import",
+        "Se actualizaron nombres de campo heredados, identificadores o posiciones ausentes y números como texto al importar",
+        "Nomes de campos legados, identificadores ou posições ausentes e números como texto foram atualizados na importação",
+    ]),
+    ("untyped-variable", [
+        "Le type d'une variable sans type a été déduit des valeurs par défaut, des schémas de sortie et de l'usage, ou générée sans type",
+        "El tipo de una variable sin tipo se dedujo de los valores por defecto, los esquemas de salida y el uso, o se generó sin tipo",
+        "O tipo de uma variável sem tipo foi inferido dos valores padrão, dos esquemas de saída e do uso, ou gerado sem tipo",
+    ]),
+    ("condition-syntax", [
+        "Les conditions des arêtes suivent la grammaire des conditions : comparaisons, opérateurs booléens, variables et littéraux",
+        "Las condiciones de las aristas siguen la gramática de condiciones: comparaciones, operadores booleanos, variables y literales",
+        "As condições das arestas seguem a gramática de condições: comparações, operadores booleanos, variáveis e literais",
+    ]),
+    ("variable-reference", [
+        "Les noms de variables sont uniques, et les conditions et modèles de configuration ne référencent que des variables déclarées",
+        "Los nombres de variables son únicos, y las condiciones y plantillas de configuración solo hacen referencia a variables declaradas",
+        "Os nomes de variáveis são únicos, e as condições e modelos de configuração só referenciam variáveis declaradas",
+    ]),
+    ("variable-type", [
+        "Les valeurs par défaut, les affectations et sorties des Transform et les conditions des Decision correspondent aux types déclarés des variables",
+        "Los valores por defecto, las asignaciones y salidas de los Transform y las condiciones de los Decision coinciden con los tipos declarados de las variables",
+        "Os valores padrão, as atribuições e saídas dos Transform e as condições dos Decision correspondem aos tipos declarados das variáveis",
+    ]),
+    ("constant-variable", [
+        "Les variables constantes ont une valeur par défaut et aucun Transform ne les affecte",
+        "Las variables constantes tienen un valor por defecto y ningún Transform las asigna",
+        "As variáveis constantes têm um valor padrão e nenhum Transform as atribui",
+    ]),
+    ("variable-schema", [
+        "Les schémas de variables utilisent des contraintes adaptées au type, des motifs RE2 valides et une valeur par défaut qui les respecte",
+        "Los esquemas de variables usan restricciones acordes al tipo, patrones RE2 válidos y un valor por defecto que los cumple",
+        "Os esquemas de variáveis usam restrições adequadas ao tipo, padrões RE2 válidos e um valor padrão que os satisfaz",
+    ]),
+    ("unused-variable", [
+        "Une variable qu'aucun nœud ni aucune arête ne lit est omise de l'entrée générée",
+        "Una variable que ningún nodo ni arista lee se omite de la entrada generada",
+        "Uma variável que nenhum nó ou aresta lê é omitida da entrada gerada",
+    ]),
+    ("synthetic-input-pattern", [
+        "Un motif rejette l'entrée de test générée : la variable a besoin d'une valeur par défaut conforme",
+        "Un patrón rechaza la entrada de prueba generada, por lo que la variable necesita un valor por defecto que coincida",
+        "Um padrão rejeita a entrada de teste gerada, então a variável precisa de um valor padrão compatível",
+    ]),
+    ("workflow-cycle", [
+        "Chaque cycle d'arêtes de flux passe par un nœud Decision marqué comme boucle",
+        "Cada ciclo de aristas de flujo pasa por un nodo Decision marcado como bucle",
+        "Todo ciclo de arestas de fluxo passa por um nó Decision marcado como laço",
+    ]),
+    ("unconnected-node", [
+        "Chaque nœud est accessible depuis Start et mène à End",
+        "Cada nodo es alcanzable desde Start y tiene un camino hasta End",
+        "Todo nó é alcançável a partir de Start e tem um caminho até End",
+    ]),
+    ("unreachable-node-removed", [
+        "Les nœuds inaccessibles depuis Start sont supprimés par l'élimination des nœuds morts au lieu d'être refusés",
+        "Los nodos no alcanzables desde Start se eliminan con la eliminación de nodos muertos en lugar de rechazarse",
+        "Os nós inalcançáveis a partir de Start são removidos pela eliminação de nós mortos em vez de rejeitados",
+    ]),
+    ("decision-branches", [
+        "Les décisions ont au moins une arête conditionnelle et au plus une arête par défaut",
+        "Las decisiones tienen al menos una arista condicional y como máximo una arista por defecto",
+        "As decisões têm pelo menos uma aresta condicional e no máximo uma aresta padrão",
+    ]),
+    ("decision-not-exhaustive", [
+        "Une décision sans arête par défaut peut ne satisfaire aucune de ses conditions",
+        "Una decisión sin arista por defecto puede no cumplir ninguna de sus condiciones",
+        "Uma decisão sem aresta padrão pode não satisfazer nenhuma das suas condições",
+    ]),
+    ("constant-branch-pruned", [
+        "Les conditions qui ne lisent que des variables constantes et des littéraux sont décidées à la compilation et les branches qu'elles excluent sont supprimées",
+        "Las condiciones que solo leen variables constantes y literales se deciden al compilar y se eliminan las ramas que descartan",
+        "As condições que só leem variáveis constantes e literais são decididas na compilação e os ramos que excluem são removidos",
+    ]),
+    ("parallel-gateway-pairing", [
+        "Chaque passerelle de division se referme sur une jonction atteinte par toutes ses branches, sans qu'aucune branche ne quitte la région entre les deux",
+        "Cada compuerta de bifurcación se cierra en una unión que alcanzan todas sus ramas, sin que ninguna rama salga de la región entre ambas",
+        "Cada gateway de bifurcação fecha numa junção alcançada por todos os seus ramos, sem que nenhum ramo saia da região entre os dois",
+    ]),
+    ("parallel-join-policy", [
+        "Une jointure n_of_m doit attendre entre 1 et le nombre de branches parallèles",
+        "Una unión n_of_m debe esperar entre 1 y el número de ramas paralelas",
+        "Uma junção n_of_m deve esperar entre 1 e o número de ramos paralelos",
+    ]),
+    ("decision-table-shape", [
+        "Les tables de décision déclarent des entrées qui sont des variables du workflow, au moins une règle et un test valide par entrée",
+        "Las tablas de decisión declaran entradas que son variables del flujo, al menos una regla y una prueba válida por entrada",
+        "As tabelas de decisão declaram entradas que são variáveis do fluxo, pelo menos uma regra e um teste válido por entrada",
+    ]),
+    ("decision-table-overlap", [
+        "Les règles qui se chevauchent sont refusées avec la politique unique, et avec any lorsque leurs résultats diffèrent",
+        "Las reglas que se superponen se rechazan con la política unique, y con any cuando sus resultados difieren",
+        "Regras sobrepostas são rejeitadas com a política unique, e com any quando seus resultados diferem",
+    ]),
+    ("decision-table-gap", [
+        "Une table de décision sans default_output a une règle pour chaque combinaison de valeurs d'entrée",
+        "Una tabla de decisión sin default_output tiene una regla para cada combinación de valores de entrada",
+        "Uma tabela de decisão sem default_output tem uma regra para cada combinação de valores de entrada",
+    ]),
+    ("decision-table-routing", [
+        "Chaque arête sortant d'une table de décision nomme l'un de ses résultats, et chaque résultat a une arête ou une arête par défaut sans libellé",
+        "Cada arista que sale de una tabla de decisión nombra uno de sus resultados, y cada resultado tiene una arista o una arista por defecto sin etiqueta",
+        "Cada aresta que sai de uma tabela de decisão nomeia um de seus resultados, e cada resultado tem uma aresta ou uma aresta padrão sem rótulo",
+    ]),
+    ("signal-wait", [
+        "Les attentes multi-signaux exigent des noms de signaux distincts, une variable de corrélation texte et un délai valide",
+        "Las esperas de varias señales requieren nombres de señal distintos, una variable de correlación de texto y un tiempo límite válido",
+        "As esperas de vários sinais exigem nomes de sinal distintos, uma variável de correlação de texto e um tempo limite válido",
+    ]),
+    ("dynamic-activity-allowlist", [
+        "Les activités dynamiques choisissent via une variable texte parmi une liste autorisée non vide d'identifiants Go",
+        "Las actividades dinámicas eligen mediante una variable de texto desde una lista permitida no vacía de identificadores Go",
+        "As atividades dinâmicas escolhem por meio de uma variável de texto a partir de uma lista permitida não vazia de identificadores Go",
+    ]),
+    ("publish-event-topic", [
+        "Les nœuds PublishEvent indiquent le sujet sur lequel ils publient",
+        "Los nodos PublishEvent indican el tema en el que publican",
+        "Os nós PublishEvent indicam o tópico em que publicam",
+    ]),
+    ("weighted-split", [
+        "Les poids d'une répartition pondérée totalisent 100 et couvrent exactement une fois chaque arête sortante vers une activité",
+        "Los pesos de una división ponderada suman 100 y cubren exactamente una vez cada arista saliente hacia una actividad",
+        "Os pesos de uma divisão ponderada somam 100 e cobrem exatamente uma vez cada aresta de saída para uma atividade",
+    ]),
+    ("feature-flag", [
+        "Les clés de feature flag sont bien formées et connues, les contextes sont des variables texte et les arêtes sont étiquetées on ou off",
+        "Las claves de feature flag están bien formadas y son conocidas, los contextos son variables de texto y las aristas se etiquetan on u off",
+        "As chaves de feature flag são bem formadas e conhecidas, os contextos são variáveis de texto e as arestas são rotuladas on ou off",
+    ]),
+    ("session-group", [
+        "Les groupes de session de worker ne contiennent que des activités et forment une seule suite continue",
+        "Los grupos de sesión de worker contienen solo actividades y forman una única secuencia continua",
+        "Os grupos de sessão de worker contêm apenas atividades e formam uma única sequência contínua",
+    ]),
+    ("child-workflow-options", [
+        "Les workflows enfants nomment une cible et évitent des politiques de fermeture et d'annulation contradictoires",
+        "Los flujos hijos nombran un destino y evitan políticas de cierre y cancelación contradictorias",
+        "Os fluxos filhos nomeiam um destino e evitam políticas de encerramento e cancelamento contraditórias",
+    ]),
+    ("child-workflow-reference", [
+        "Les workflows enfants nomment un workflow connu et fournissent les entrées dont il a besoin",
+        "Los flujos hijos nombran un flujo conocido y asignan las entradas que necesita",
+        "Os fluxos filhos nomeiam um fluxo conhecido e mapeiam as entradas de que ele precisa",
+    ]),
+    ("namespace-policy", [
+        "Les cibles dans un autre namespace respectent les règles de nommage de Temporal et la politique de namespaces configurée",
+        "Los destinos en otro namespace siguen las reglas de nombres de Temporal y la política de namespaces configurada",
+        "Os destinos em outro namespace seguem as regras de nomes do Temporal e a política de namespaces configurada",
+    ]),
+    ("cancellation-scope", [
+        "Les membres d'une portée d'annulation sont des activités et les arêtes d'annulation relient un déclencheur valide à une portée",
+        "Los miembros de un ámbito de cancelación son actividades y las aristas de cancelación conectan un disparador válido con un ámbito",
+        "Os membros de um escopo de cancelamento são atividades e as arestas de cancelamento ligam um gatilho válido a um escopo",
+    ]),
+    ("nexus-target", [
+        "Les opérations Nexus nomment un endpoint, un service et une opération valides",
+        "Las operaciones Nexus nombran un endpoint, un servicio y una operación válidos",
+        "As operações Nexus nomeiam um endpoint, um serviço e uma operação válidos",
+    ]),
+    ("workflow-timeouts", [
+        "Les délais du workflow sont positifs, run tient dans execution, et task dure au plus 2 min et tient dans run",
+        "Los tiempos límite del flujo son positivos, run cabe en execution, y task dura como máximo 2 min y cabe en run",
+        "Os tempos limite do fluxo são positivos, run cabe em execution, e task dura no máximo 2 min e cabe em run",
+    ]),
+    ("schedule-trigger", [
+        "Les déclencheurs planifiés portent une expression cron valide et une fenêtre de rattrapage utilisable",
+        "Los disparadores programados llevan una expresión cron válida y una ventana de recuperación utilizable",
+        "Os gatilhos agendados trazem uma expressão cron válida e uma janela de recuperação utilizável",
+    ]),
+    ("trigger-config", [
+        "Les déclencheurs webhook définissent une méthode et un chemin valides, et les déclencheurs d'événement nomment un sujet",
+        "Los disparadores webhook definen un método y una ruta válidos, y los disparadores de evento nombran un tema o asunto",
+        "Os gatilhos webhook definem um método e um caminho válidos, e os gatilhos de evento nomeiam um tópico ou assunto",
+    ]),
+    ("execution-profile", [
+        "Les nœuds nomment un profil d'exécution configuré dans le service et exécutent une activité",
+        "Los nodos nombran un perfil de ejecución configurado en el servicio y ejecutan una actividad",
+        "Os nós nomeiam um perfil de execução configurado no serviço e executam uma atividade",
+    ]),
+    ("duration-format", [
+        "Les durées des minuteurs, les intervalles de retry et les délais des activités sont au format Go, humantime ou ISO-8601",
+        "Las duraciones de temporizadores, los intervalos de reintento y los plazos de las actividades usan el formato Go, humantime o ISO-8601",
+        "As durações de temporizadores, os intervalos de nova tentativa e os prazos das atividades usam o formato Go, humantime ou ISO-8601",
+    ]),
+    ("retry-policy", [
+        "Les politiques de retry ont entre 1 et le maximum de tentatives, un backoff d'au moins 1,0 et des intervalles ordonnés",
+        "Las políticas de reintento tienen entre 1 y el máximo de intentos, un backoff de al menos 1,0 e intervalos ordenados",
+        "As políticas de nova tentativa têm entre 1 e o máximo de tentativas, um backoff de pelo menos 1,0 e intervalos ordenados",
+    ]),
+    ("non-idempotent-retry", [
+        "Relancer une opération non idempotente sans idempotency_key peut répéter ses effets de bord",
+        "Reintentar una operación no idempotente sin idempotency_key puede repetir sus efectos secundarios",
+        "Repetir uma operação não idempotente sem idempotency_key pode repetir seus efeitos colaterais",
+    ]),
+    ("failover-regions", [
+        "Les régions de bascule ont des noms uniques, des adresses hôte:port, des namespaces autorisés et des paramètres mTLS complets",
+        "Las regiones de conmutación tienen nombres únicos, direcciones host:puerto, namespaces permitidos y ajustes mTLS completos",
+        "As regiões de failover têm nomes únicos, endereços host:porta, namespaces permitidos e configurações mTLS completas",
+    ]),
+    ("outbox-unused", [
+        "L'option outbox est activée mais aucun DatabaseQuery n'est directement suivi d'un PublishEvent",
+        "La opción outbox está activada pero ningún DatabaseQuery va seguido directamente de un PublishEvent",
+        "A opção outbox está ativada, mas nenhum DatabaseQuery é seguido diretamente por um PublishEvent",
+    ]),
+    ("validation-profile", [
+        "Les options de compilation nomment un profil de validation configuré dans le service",
+        "Las opciones de compilación nombran un perfil de validación configurado en el servicio",
+        "As opções de compilação nomeiam um perfil de validação configurado no serviço",
+    ]),
+    ("execution-report", [
+        "Les rapports d'exécution sont envoyés à un canal du service de notification et à un destinataire non vide",
+        "Los informes de ejecución van a un canal del servicio de notificaciones y a un destinatario no vacío",
+        "Os relatórios de execução vão para um canal do serviço de notificações e para um destinatário não vazio",
+    ]),
+    ("target-support", [
+        "Les cibles autres que Go prennent en charge une partie des types de nœuds et des options ; les autres sont refusés",
+        "Los destinos distintos de Go admiten un subconjunto de tipos de nodo y opciones; los demás se rechazan",
+        "Os destinos diferentes de Go suportam um subconjunto de tipos de nó e opções; os demais são rejeitados",
+    ]),
+    ("optimization-passes", [
+        "L'option de compilation opt_level vaut 0, 1 ou 2 et passes ne nomme que des passes d'optimisation connues",
+        "La opción de compilación opt_level vale 0, 1 o 2 y passes solo nombra pasadas de optimización conocidas",
+        "A opção de compilação opt_level vale 0, 1 ou 2 e passes só nomeia passagens de otimização conhecidas",
+    ]),
+    ("sdk-feature", [
+        "Les fonctionnalités utilisées par le workflow sont prises en charge par la version ciblée du SDK Temporal",
+        "Las funcionalidades que usa el flujo están soportadas por la versión del SDK de Temporal elegida",
+        "Os recursos usados pelo fluxo são suportados pela versão do SDK do Temporal escolhida",
+    ]),
+    ("unpinned-dependency", [
+        "Un module Go n'a pas de somme de contrôle épinglée : la compilation nécessite l'accès au proxy de modules",
+        "Un módulo Go no tiene una suma de verificación fijada, por lo que la compilación necesita acceso al proxy de módulos",
+        "Um módulo Go não tem uma soma de verificação fixada, então a compilação precisa de acesso ao proxy de módulos",
+    ]),
+    ("deprecated-construct", [
+        "Un type de nœud ou un champ de configuration déclaré obsolète est utilisé",
+        "Se usa un tipo de nodo o un campo de configuración declarado obsoleto",
+        "Um tipo de nó ou campo de configuração declarado obsoleto está sendo usado",
+    ]),
+    ("removed-construct", [
+        "Un type de nœud ou un champ obsolète est utilisé alors que la dsl_version configurée a atteint sa version de suppression",
+        "Se usa un tipo de nodo o campo obsoleto cuya versión de eliminación ya alcanzó la dsl_version configurada",
+        "É usado um tipo de nó ou campo obsoleto cuja versão de remoção já foi atingida pela dsl_version configurada",
+    ]),
+    ("identifier-collision", [
+        "Les noms d'activités et les fonctions de tables de décision générés à partir des libellés sont distincts pour des nœuds distincts",
+        "Los nombres de actividades y las funciones de tablas de decisión generados a partir de las etiquetas son distintos para nodos distintos",
+        "Os nomes de atividades e as funções de tabelas de decisão gerados a partir dos rótulos são distintos para nós distintos",
+    ]),
+    ("identifier-sanitized", [
+        "Un nom de workflow, un libellé de nœud ou un nom de variable n'est pas un identifiant Go valide tel quel et a été renommé dans le code généré",
+        "Un nombre de flujo, una etiqueta de nodo o un nombre de variable no es un identificador Go válido tal cual y se renombró en el código generado",
+        "Um nome de fluxo, rótulo de nó ou nome de variável não é um identificador Go válido como escrito e foi renomeado no código gerado",
+    ]),
+    ("nondeterministic-construct", [
+        "Les Transform ne lisent ni l'heure système, ni des valeurs aléatoires, ni des réponses réseau, et les HttpCall s'exécutent comme activités, afin que le code du workflow rejoue de façon déterministe",
+        "Los Transform no leen la hora del sistema, valores aleatorios ni respuestas de red, y los HttpCall se ejecutan como actividades, para que el código del flujo se reproduzca de forma determinista",
+        "Os Transform não leem a hora do sistema, valores aleatórios nem respostas de rede, e os HttpCall executam como atividades, para que o código do fluxo seja reproduzido de forma determinística",
+    ]),
+    ("nondeterministic-activity-input", [
+        "Un modèle d'entrée d'activité lit l'heure système, des valeurs aléatoires ou une réponse réseau dans le code du workflow",
+        "Una plantilla de entrada de actividad lee la hora del sistema, valores aleatorios o una respuesta de red en el código del flujo",
+        "Um modelo de entrada de atividade lê a hora do sistema, valores aleatórios ou uma resposta de rede no código do fluxo",
+    ]),
+    ("activity-missing-retry", [
+        "Une activité n'a pas de politique de relance propre ni issue d'un profil d'exécution",
+        "Una actividad no tiene política de reintentos propia ni de un perfil de ejecución",
+        "Uma atividade não tem política de novas tentativas própria nem de um perfil de execução",
+    ]),
+    ("http-call-without-timeout", [
+        "Un HttpCall ne définit aucun délai start-to-close ni schedule-to-close, ni lui-même ni par un profil d'exécution",
+        "Un HttpCall no define un tiempo límite start-to-close ni schedule-to-close propio ni de un perfil de ejecución",
+        "Um HttpCall não define um tempo limite start-to-close nem schedule-to-close próprio nem de um perfil de execução",
+    ]),
+    ("unlabeled-decision-branch", [
+        "Une arête sortant d'un Decision n'a pas de libellé à afficher dans l'éditeur et la documentation générée",
+        "Una arista que sale de un Decision no tiene etiqueta para mostrar en el editor y la documentación generada",
+        "Uma aresta que sai de um Decision não tem rótulo para mostrar no editor e na documentação gerada",
+    ]),
+];
+
+/// Diagnostic with its rule summary in the client's locale
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalizedDiagnostic {
+    /// Rule code, stable across locales; absent for errors no rule claimed
+    pub code: Option<&'static str>,
+    pub severity: Severity,
+    /// Rule summary in the selected locale, or the detail when there is no rule
+    pub message: String,
+    /// Specific message in English, naming the nodes and values involved
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+}
+
+/// Best supported locale for an `Accept-Language` header, by quality then order
+pub fn negotiate(accept_language: Option<&str>) -> &'static str {
+    let mut ranges: Vec<(&str, f32)> = accept_language.unwrap_or_default()
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let tag = parts.next().filter(|t| !t.is_empty())?;
+            let quality = parts
+                .find_map(|p| p.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+            Some((tag, quality))
+        })
+        .filter(|(_, quality)| *quality > 0.0)
+        .collect();
+    // Stable, so equally weighted ranges keep the client's order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.iter()
+        .find_map(|(tag, _)| {
+            let primary = tag.split('-').next().unwrap_or_default().to_ascii_lowercase();
+            std::iter::once(DEFAULT_LOCALE).chain(TRANSLATED.iter().copied()).find(|l| *l == primary)
+        })
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Summary of the rule with `code` in `locale`, falling back to English
+pub fn summary(code: &str, locale: &str) -> Option<&'static str> {
+    let english = rules::find(code)?.description;
+    let translated = TRANSLATED.iter().position(|l| *l == locale)
+        .and_then(|i| CATALOG.iter().find(|(c, _)| *c == code).map(|(_, texts)| texts[i]));
+    Some(translated.unwrap_or(english))
+}
+
+/// Localize a diagnostic, keeping the severity it was raised at
+pub fn localize(diagnostic: Diagnostic, locale: &str) -> LocalizedDiagnostic {
+    let Diagnostic { code, severity, message: detail, node, edge, position } = diagnostic;
+    LocalizedDiagnostic {
+        code,
+        severity,
+        message: code.and_then(|c| summary(c, locale)).map_or_else(|| detail.clone(), str::to_string),
+        detail,
+        node,
+        edge,
+        position,
+    }
+}
@@ -1,5 +1,49 @@
 //! Compiler module
+pub mod airflow;
+pub mod argo;
+pub mod backend;
+pub mod budget;
+pub mod cadence;
 pub mod codegen;
+pub mod conductor;
+pub mod dagster;
+pub mod decision_table;
+pub mod dependencies;
+pub mod deprecations;
+pub mod determinism;
+pub mod diagnostic;
+pub mod dotnet;
+pub mod durable;
+pub mod duration;
+pub mod failover;
+pub mod feature_flag;
+pub mod folding;
+pub mod identifiers;
+pub mod inference;
+pub mod input_validation;
+pub mod java;
+pub mod limits;
+pub mod lint;
+pub mod messages;
 pub mod optimizer;
+pub mod outbox;
 pub mod parser;
+pub mod passes;
+pub mod plugins;
+pub mod profiles;
+pub mod prefect;
+pub mod python;
+pub mod report;
+pub mod rules;
+pub mod scaffold;
+pub mod sarif;
+pub mod sdk;
+pub mod serverless_workflow;
+pub mod source_map;
+pub mod steps;
+pub mod strictness;
+pub mod testgen;
+pub mod triggers;
+pub mod types;
+pub mod typescript;
 pub mod validator;
@@ -0,0 +1,355 @@
+//! Graph optimizations applied to a validated definition before code generation
+//!
+//! Dead-node elimination keeps only the nodes reachable from a Start node, following every
+//! edge kind and the members of reachable cancellation scopes as validation does, and drops
+//! the edges leaving or entering anything removed. Generators then never emit activities,
+//! handlers or timers that no execution can reach. What was removed is reported in the
+//! compilation metadata, so authors can clean the definition up at its source.
+//!
+//! Dead-variable elimination then drops the declared variables no remaining node or edge
+//! mentions, so the generated input types only carry fields the workflow uses. A variable is
+//! mentioned by a condition, a `{{...}}` template in a config, a config field naming it, such
+//! as a decision table input or a signal correlation key, or a Transform assigning it;
+//! variables with an input schema are kept for the checks it generates.
+//!
+//! Sequential fusion then collapses chains of nodes that run one after another into the
+//! first node of each chain. A link in a chain is an unconditional flow edge that is the only
+//! edge leaving one node and the only edge entering the next, between nodes outside sessions
+//! and cancellation scopes. Transforms that only assign variables are always merged, unless a
+//! later one reads a variable an earlier one assigns. With the `fuse_activities` option, plain
+//! Activity nodes with the same retry policy and timeouts are fused too: the workflow
+//! schedules one generated activity running the chain's activities in order, saving a
+//! round-trip and its history events per activity, at the cost of a retry repeating the
+//! whole chain.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compiler::codegen::{activity_name, activity_names, activity_stub};
+use crate::compiler::limits;
+use crate::compiler::validator::{config_templates, referenced_names};
+use crate::dsl::{condition, graph};
+use crate::error::CompilerError;
+use crate::{
+    to_pascal_case, ActivityConfig, CancellationScopeConfig, DecisionTableConfig, DynamicActivityConfig, EdgeKind, FeatureFlagConfig, NodeType,
+    TransformConfig, WaitSignalsConfig, WorkflowDefinition, WorkflowNode,
+};
+
+/// Nodes, edges and variables removed from a definition because nothing reaches or reads them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Eliminated {
+    /// IDs of nodes unreachable from Start, in definition order
+    pub nodes: Vec<String>,
+    /// IDs of edges leaving or entering a removed node, in definition order
+    pub edges: Vec<String>,
+    /// Names of variables no remaining node or edge mentions, in declaration order
+    #[serde(default)]
+    pub variables: Vec<String>,
+}
+
+/// Definition without the nodes unreachable from Start and their edges, with what was removed
+pub fn eliminate_unreachable(definition: &WorkflowDefinition) -> (WorkflowDefinition, Eliminated) {
+    let reachable = graph::reachable_nodes(definition);
+    let mut eliminated = Eliminated::default();
+    let mut optimized = definition.clone();
+    optimized.nodes.retain(|node| {
+        let keep = reachable.contains(node.id.as_str());
+        if !keep {
+            eliminated.nodes.push(node.id.clone());
+        }
+        keep
+    });
+    optimized.edges.retain(|edge| {
+        let keep = reachable.contains(edge.source.as_str()) && reachable.contains(edge.target.as_str());
+        if !keep {
+            eliminated.edges.push(edge.id.clone());
+        }
+        keep
+    });
+    (optimized, eliminated)
+}
+
+/// Names of the variables a node mentions outside its `{{...}}` templates
+fn named_variables(node: &WorkflowNode) -> Vec<String> {
+    match node.node_type {
+        NodeType::DecisionTable => node.typed_config::<DecisionTableConfig>().map(|c| c.inputs).unwrap_or_default(),
+        NodeType::DynamicActivity => node.typed_config::<DynamicActivityConfig>().map(|c| vec![c.selector]).unwrap_or_default(),
+        NodeType::WaitSignals => node.typed_config::<WaitSignalsConfig>().ok().and_then(|c| c.correlation_key).into_iter().collect(),
+        NodeType::FeatureFlag => node.typed_config::<FeatureFlagConfig>().ok().and_then(|c| c.context).into_iter().collect(),
+        NodeType::Transform => {
+            let Ok(config) = node.typed_config::<TransformConfig>() else { return vec![] };
+            let outputs = config.output_schema.as_ref().and_then(|s| s.get("properties")).and_then(Value::as_object);
+            config.assign.into_keys().chain(outputs.into_iter().flat_map(|o| o.keys().cloned())).collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Names variables are mentioned by in conditions, templates and config fields
+fn mentioned_names(definition: &WorkflowDefinition) -> Result<HashSet<String>, CompilerError> {
+    let mut names: HashSet<String> = HashSet::new();
+    for edge in &definition.edges {
+        let Some(expr) = edge.condition.as_deref().and_then(|c| condition::parse(c).ok()) else { continue };
+        names.extend(expr.variables().into_iter().map(|(name, _)| name.to_string()));
+    }
+    for node in &definition.nodes {
+        limits::checkpoint("dead-variable elimination")?;
+        let mut templates = vec![];
+        config_templates(&node.config, "config".to_string(), &mut templates);
+        names.extend(templates.into_iter().flat_map(|(_, t)| referenced_names(t)).map(str::to_string));
+        names.extend(named_variables(node));
+    }
+    Ok(names)
+}
+
+/// Definition without the variables no node or edge mentions, with their names
+pub fn eliminate_unused_variables(definition: &WorkflowDefinition) -> Result<(WorkflowDefinition, Vec<String>), CompilerError> {
+    let mentioned = mentioned_names(definition)?;
+    let mut removed = vec![];
+    let mut optimized = definition.clone();
+    optimized.variables.retain(|variable| {
+        let keep = variable.schema.is_some() || mentioned.contains(&variable.name) || mentioned.contains(&to_pascal_case(&variable.name));
+        if !keep {
+            removed.push(variable.name.clone());
+        }
+        keep
+    });
+    Ok((optimized, removed))
+}
+
+/// A chain of sequential nodes merged into its first node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FusedChain {
+    /// ID of the first node, which now runs the whole chain
+    pub node: String,
+    /// IDs of the nodes merged into it, in execution order
+    pub merged: Vec<String>,
+    /// Registered name of the generated activity; unset for Transform chains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
+    /// Activities the generated activity runs, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub activities: Vec<String>,
+}
+
+/// Whether a node can take part in a chain at all
+fn standalone(definition: &WorkflowDefinition, node: &WorkflowNode, scoped: &[String]) -> bool {
+    node.session.is_none()
+        && !scoped.contains(&node.id)
+        && !definition.edges.iter().any(|e| e.kind == EdgeKind::Cancel && e.source == node.id)
+}
+
+/// The node `node` always continues to, when `node` is its only predecessor
+fn sole_successor<'a>(definition: &'a WorkflowDefinition, node: &'a WorkflowNode) -> Option<&'a WorkflowNode> {
+    let mut outgoing = graph::outgoing_edges(definition, &node.id);
+    let edge = outgoing.next().filter(|e| e.kind == EdgeKind::Flow && e.condition.is_none())?;
+    if outgoing.next().is_some() {
+        return None;
+    }
+    let next = graph::find_node(definition, &edge.target)?;
+    let incoming = definition.edges.iter().filter(|e| e.target == next.id).count();
+    (incoming == 1 && next.id != node.id).then_some(next)
+}
+
+/// Variables a Transform assigns, if assigning them is all it does
+fn assignments(node: &WorkflowNode) -> Option<BTreeMap<String, Value>> {
+    if !matches!(node.node_type, NodeType::Transform) || node.config.as_object()?.keys().any(|k| k != "assign") {
+        return None;
+    }
+    node.typed_config::<TransformConfig>().ok().map(|c| c.assign)
+}
+
+/// Whether an Activity only names an activity derived from its label, so the generated
+/// activity can run it
+fn plain_activity(node: &WorkflowNode) -> bool {
+    matches!(node.node_type, NodeType::Activity) && node.typed_config::<ActivityConfig>().is_ok_and(|c| c.activity_type.is_none())
+}
+
+/// Whether two activities would be scheduled with the same options
+fn same_options(a: &WorkflowNode, b: &WorkflowNode) -> bool {
+    let options = |n: &WorkflowNode| serde_json::to_value((&n.retries, &n.timeouts)).ok();
+    options(a) == options(b)
+}
+
+/// Whether a Transform's assignments read any of `assigned`
+fn reads_any(assign: &BTreeMap<String, Value>, assigned: &BTreeMap<String, Value>) -> bool {
+    let mut templates = vec![];
+    for (name, value) in assign {
+        config_templates(value, format!("assign.{}", name), &mut templates);
+    }
+    templates.iter().any(|(_, template)| referenced_names(template).iter().any(|n| assigned.contains_key(*n)))
+}
+
+/// Whether `next` can join a chain ending in `last`, whose Transforms assign `assigned`
+fn extends(definition: &WorkflowDefinition, last: &WorkflowNode, next: &WorkflowNode, scoped: &[String], assigned: &BTreeMap<String, Value>) -> bool {
+    if !standalone(definition, next, scoped) {
+        return false;
+    }
+    match assignments(next) {
+        Some(assign) => assignments(last).is_some() && !reads_any(&assign, assigned),
+        None => plain_activity(last) && plain_activity(next) && same_options(last, next),
+    }
+}
+
+/// Merge every chain of assignment-only Transforms and, when `activities` is set, of plain
+/// Activity nodes into the chain's first node
+pub fn fuse_sequential(definition: &WorkflowDefinition, activities: bool) -> Result<(WorkflowDefinition, Vec<FusedChain>), CompilerError> {
+    let scoped: Vec<String> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::CancellationScope))
+        .filter_map(|n| n.typed_config::<CancellationScopeConfig>().ok())
+        .flat_map(|c| c.members)
+        .collect();
+    let candidate = |n: &WorkflowNode| standalone(definition, n, &scoped) && (assignments(n).is_some() || (activities && plain_activity(n)));
+    let registered = activity_names(definition);
+
+    let mut fused = definition.clone();
+    let mut chains = vec![];
+    for head in definition.nodes.iter().filter(|n| candidate(n)) {
+        limits::checkpoint("sequential fusion")?;
+        // A node continuing another candidate's chain is merged from that chain's head
+        let predecessor = definition.edges.iter()
+            .filter(|e| e.target == head.id)
+            .find_map(|e| graph::find_node(definition, &e.source))
+            .filter(|p| candidate(p) && sole_successor(definition, p).is_some_and(|s| s.id == head.id));
+        if predecessor.is_some_and(|p| extends(definition, p, head, &scoped, &assignments(p).unwrap_or_default())) {
+            continue;
+        }
+
+        let mut chain = vec![head];
+        let mut assigned = assignments(head).unwrap_or_default();
+        let mut seen = HashSet::from([head.id.as_str()]);
+        while let Some(next) = chain.last()
+            .and_then(|last| sole_successor(definition, last).filter(|next| extends(definition, last, next, &scoped, &assigned)))
+        {
+            if !seen.insert(next.id.as_str()) {
+                break;
+            }
+            assigned.extend(assignments(next).unwrap_or_default());
+            chain.push(next);
+        }
+        if chain.len() < 2 {
+            continue;
+        }
+
+        let label = chain.iter().map(|n| n.label.as_str()).collect::<Vec<_>>().join(" Then ");
+        let node = fused.nodes.iter_mut().find(|n| n.id == head.id).expect("chain head is in the definition");
+        let original = node.label.clone();
+        node.label = label;
+        let (activity, members) = match assignments(head) {
+            Some(_) => {
+                node.config = serde_json::json!({ "assign": assigned });
+                (None, vec![])
+            }
+            None => {
+                let activity = activity_name(node);
+                let members: Vec<String> = chain.iter().map(|n| activity_name(n)).collect();
+                // The name may only be shared with a chain running the same activities
+                let taken = registered.contains(&activity)
+                    || chains.iter().any(|c: &FusedChain| c.activity.as_ref() == Some(&activity) && c.activities != members);
+                if taken {
+                    node.label = original;
+                    continue;
+                }
+                (Some(activity), members)
+            }
+        };
+
+        let merged: Vec<String> = chain[1..].iter().map(|n| n.id.clone()).collect();
+        let last = chain[chain.len() - 1].id.clone();
+        fused.nodes.retain(|n| !merged.contains(&n.id));
+        fused.edges.retain(|e| !merged.contains(&e.target));
+        for edge in fused.edges.iter_mut().filter(|e| e.source == last) {
+            edge.source = head.id.clone();
+        }
+        chains.push(FusedChain { node: head.id.clone(), merged, activity, activities: members });
+    }
+    Ok((fused, chains))
+}
+
+/// Generate the fused activities of the chains, with stubs for the activities they run that
+/// no remaining node registers
+pub fn generate_fused_activities(definition: &WorkflowDefinition, chains: &[FusedChain], workflow_name: &str) -> String {
+    let registered = activity_names(definition);
+    let mut code = String::new();
+    let mut stubbed: Vec<&str> = vec![];
+    let mut generated: Vec<&str> = vec![];
+    for chain in chains {
+        let Some(name) = &chain.activity else { continue };
+        if generated.contains(&name.as_str()) {
+            continue;
+        }
+        generated.push(name);
+        for member in chain.activities.iter().filter(|m| !registered.contains(m)) {
+            if !stubbed.contains(&member.as_str()) {
+                stubbed.push(member);
+                code.push_str(&activity_stub(member, workflow_name));
+            }
+        }
+        let steps: String = chain.activities.iter()
+            .map(|member| format!("    if err := a.{member}(ctx, input); err != nil {{\n        return err\n    }}\n"))
+            .collect();
+        code.push_str(&format!(r#"// {name} runs {} in one activity; a retry runs them all again
+func (a *Activities) {name}(ctx context.Context, input {workflow_name}Input) error {{
+{steps}    return nil
+}}
+
+"#, chain.activities.join(", ")));
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{CompileOptions, WorkflowCompiler, WorkflowDefinition};
+
+    fn node(id: &str, node_type: &str, label: &str) -> serde_json::Value {
+        json!({ "id": id, "node_type": node_type, "label": label, "config": {}, "position": { "x": 0, "y": 0 } })
+    }
+
+    /// Start, one activity and End, plus two activities nothing leads to
+    fn with_unreachable_nodes() -> WorkflowDefinition {
+        serde_json::from_value(json!({
+            "id": "3f8a9c1e-1111-4222-8333-444455556667",
+            "name": "order flow",
+            "version": "1.0.0",
+            "variables": [],
+            "triggers": [],
+            "nodes": [
+                node("start", "start", "Start"),
+                node("charge", "activity", "Charge"),
+                node("end", "end", "End"),
+                node("orphan", "activity", "Orphan"),
+                node("stray", "activity", "Stray"),
+            ],
+            "edges": [
+                { "id": "e1", "source": "start", "target": "charge" },
+                { "id": "e2", "source": "charge", "target": "end" },
+                { "id": "e3", "source": "orphan", "target": "stray" },
+                { "id": "e4", "source": "stray", "target": "end" },
+            ],
+        }))
+        .expect("definition deserializes")
+    }
+
+    #[test]
+    fn compile_reports_unreachable_nodes_it_removes() {
+        let compiled = WorkflowCompiler::new(vec![])
+            .compile(&with_unreachable_nodes(), &CompileOptions::default())
+            .expect("dead-node elimination removes the unreachable nodes");
+
+        assert_eq!(compiled.metadata.eliminated.nodes, ["orphan", "stray"]);
+        assert_eq!(compiled.metadata.eliminated.edges, ["e3", "e4"]);
+        assert!(compiled.metadata.optimization_passes.iter().any(|p| p == "dead-nodes"));
+        assert!(!compiled.workflow_code.contains("Orphan"));
+    }
+
+    #[test]
+    fn compile_rejects_unreachable_nodes_without_dead_node_elimination() {
+        let options = CompileOptions { opt_level: Some(0), ..Default::default() };
+        assert!(WorkflowCompiler::new(vec![]).compile(&with_unreachable_nodes(), &options).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Transactional outbox for DatabaseQuery nodes directly followed by a PublishEvent node
+//!
+//! Writing to the database and then publishing from a separate activity loses the event if
+//! the worker dies in between. With the `outbox` compile option each such pair is fused into
+//! one activity that performs the write and inserts an outbox row in the same transaction;
+//! a generated relay publishes pending rows and marks them sent.
+
+use crate::compiler::codegen::{activity_name, go_string_literal};
+use crate::dsl::graph;
+use crate::{CancellationScopeConfig, EdgeKind, NodeType, PublishEventConfig, WorkflowDefinition, WorkflowNode};
+
+/// A fused write and publish
+#[derive(Debug, Clone)]
+pub struct OutboxPair {
+    /// Node ID of the PublishEvent removed from the graph; the fused node keeps the write's ID
+    pub publish: String,
+    /// Registered name of the fused activity
+    pub activity: String,
+    pub write_label: String,
+    pub publish_label: String,
+    pub topic: String,
+}
+
+/// The PublishEvent node fused into `write`, if the pair qualifies
+fn fusable<'a>(definition: &'a WorkflowDefinition, write: &WorkflowNode, scoped: &[String]) -> Option<&'a WorkflowNode> {
+    let standalone = |n: &WorkflowNode| {
+        n.session.is_none()
+            && !scoped.contains(&n.id)
+            && !definition.edges.iter().any(|e| e.kind == EdgeKind::Cancel && e.source == n.id)
+    };
+    if !matches!(write.node_type, NodeType::DatabaseQuery) || !standalone(write) {
+        return None;
+    }
+
+    let mut outgoing = graph::outgoing_edges(definition, &write.id).filter(|e| e.kind == EdgeKind::Flow);
+    let edge = outgoing.next().filter(|e| e.condition.is_none())?;
+    if outgoing.next().is_some() {
+        return None;
+    }
+    let publish = graph::find_node(definition, &edge.target)?;
+    let incoming = definition.edges.iter().filter(|e| e.target == publish.id).count();
+    (matches!(publish.node_type, NodeType::PublishEvent) && incoming == 1 && standalone(publish)).then_some(publish)
+}
+
+/// Rewrite the definition so every qualifying pair is a single DatabaseQuery node running
+/// the fused activity; returns the definition unchanged when `enabled` is false
+pub fn fuse(definition: &WorkflowDefinition, enabled: bool) -> (WorkflowDefinition, Vec<OutboxPair>) {
+    let mut fused = definition.clone();
+    if !enabled {
+        return (fused, vec![]);
+    }
+
+    let scoped: Vec<String> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::CancellationScope))
+        .filter_map(|n| n.typed_config::<CancellationScopeConfig>().ok())
+        .flat_map(|c| c.members)
+        .collect();
+    let mut pairs = Vec::new();
+    for write in &definition.nodes {
+        let Some(publish) = fusable(definition, write, &scoped) else { continue };
+        let topic = publish.typed_config::<PublishEventConfig>().map(|c| c.topic).unwrap_or_default();
+
+        let node = fused.nodes.iter_mut().find(|n| n.id == write.id).expect("write node is in the definition");
+        node.label = format!("{} With Outbox", write.label);
+        pairs.push(OutboxPair {
+            publish: publish.id.clone(),
+            activity: activity_name(node),
+            write_label: write.label.clone(),
+            publish_label: publish.label.clone(),
+            topic,
+        });
+
+        fused.nodes.retain(|n| n.id != publish.id);
+        fused.edges.retain(|e| e.target != publish.id);
+        for edge in fused.edges.iter_mut().filter(|e| e.source == publish.id) {
+            edge.source = write.id.clone();
+        }
+    }
+
+    (fused, pairs)
+}
+
+/// Generate the outbox schema, one transactional activity per pair and the relay
+pub fn generate_outbox_code(pairs: &[OutboxPair], package_name: &str, workflow_name: &str) -> String {
+    let activities: String = pairs.iter()
+        .map(|pair| {
+            let name = &pair.activity;
+            let write = &pair.write_label;
+            let publish = &pair.publish_label;
+            let topic = go_string_literal(&pair.topic);
+            format!(r#"// {name} performs the {write} write and records the {publish} event in one transaction
+func (a *Activities) {name}(ctx context.Context, input {workflow_name}Input) error {{
+    if a.DB == nil {{
+        return temporal.NewNonRetryableApplicationError("Activities.DB is not configured", "OutboxNotConfigured", nil)
+    }}
+    tx, err := a.DB.BeginTx(ctx, nil)
+    if err != nil {{
+        return err
+    }}
+    defer tx.Rollback()
+
+    // TODO: implement the {write} write using tx
+
+    if err := insertOutboxEvent(ctx, tx, {topic}, input); err != nil {{
+        return fmt.Errorf("record {publish} event: %w", err)
+    }}
+    return tx.Commit()
+}}
+
+"#)
+        })
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+package {package_name}
+
+import (
+    "context"
+    "database/sql"
+    "encoding/json"
+    "fmt"
+    "log"
+    "time"
+    "go.temporal.io/sdk/activity"
+    "go.temporal.io/sdk/temporal"
+)
+
+// OutboxSchema creates the PostgreSQL table outbox activities record events in
+const OutboxSchema = `CREATE TABLE IF NOT EXISTS workflow_outbox (
+    id           TEXT PRIMARY KEY,
+    topic        TEXT NOT NULL,
+    payload      JSONB NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    published_at TIMESTAMPTZ
+)`
+
+// insertOutboxEvent records an event in the caller's transaction. The ID is derived from the
+// activity, so a retried attempt that already committed does not record the event twice.
+func insertOutboxEvent(ctx context.Context, tx *sql.Tx, topic string, payload any) error {{
+    info := activity.GetInfo(ctx)
+    id := fmt.Sprintf("%s/%s/%s", info.WorkflowExecution.ID, info.WorkflowExecution.RunID, info.ActivityID)
+    data, err := json.Marshal(payload)
+    if err != nil {{
+        return err
+    }}
+    _, err = tx.ExecContext(ctx,
+        `INSERT INTO workflow_outbox (id, topic, payload) VALUES ($1, $2, $3) ON CONFLICT (id) DO NOTHING`,
+        id, topic, data)
+    return err
+}}
+
+{activities}// OutboxPublisher delivers one relayed event to the message broker
+type OutboxPublisher func(ctx context.Context, topic string, payload []byte) error
+
+// RunOutboxRelay publishes pending outbox events in insertion order every interval until ctx
+// is cancelled. Run it beside the worker or as its own process; delivery is at least once.
+func RunOutboxRelay(ctx context.Context, db *sql.DB, publish OutboxPublisher, interval time.Duration) error {{
+    ticker := time.NewTicker(interval)
+    defer ticker.Stop()
+    for {{
+        if err := relayOutboxBatch(ctx, db, publish); err != nil {{
+            log.Println("Outbox relay batch failed", err)
+        }}
+        select {{
+        case <-ctx.Done():
+            return ctx.Err()
+        case <-ticker.C:
+        }}
+    }}
+}}
+
+// relayOutboxBatch locks up to 100 pending events, publishes them and marks them published;
+// SKIP LOCKED lets several relays share the table
+func relayOutboxBatch(ctx context.Context, db *sql.DB, publish OutboxPublisher) error {{
+    tx, err := db.BeginTx(ctx, nil)
+    if err != nil {{
+        return err
+    }}
+    defer tx.Rollback()
+
+    rows, err := tx.QueryContext(ctx,
+        `SELECT id, topic, payload FROM workflow_outbox WHERE published_at IS NULL ORDER BY created_at LIMIT 100 FOR UPDATE SKIP LOCKED`)
+    if err != nil {{
+        return err
+    }}
+    type pendingEvent struct {{
+        id, topic string
+        payload   []byte
+    }}
+    var pending []pendingEvent
+    for rows.Next() {{
+        var e pendingEvent
+        if err := rows.Scan(&e.id, &e.topic, &e.payload); err != nil {{
+            rows.Close()
+            return err
+        }}
+        pending = append(pending, e)
+    }}
+    rows.Close()
+    if err := rows.Err(); err != nil {{
+        return err
+    }}
+
+    var publishErr error
+    for _, e := range pending {{
+        if publishErr = publish(ctx, e.topic, e.payload); publishErr != nil {{
+            break
+        }}
+        if _, err := tx.ExecContext(ctx, `UPDATE workflow_outbox SET published_at = now() WHERE id = $1`, e.id); err != nil {{
+            return err
+        }}
+    }}
+    // Keep the events published before a failure marked as sent
+    if err := tx.Commit(); err != nil {{
+        return err
+    }}
+    return publishErr
+}}
+"#)
+}
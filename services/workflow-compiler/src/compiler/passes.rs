@@ -0,0 +1,163 @@
+//! Optimization pipeline run between validation and code generation
+//!
+//! Each pass implements `OptimizationPass` and runs in the fixed order of `PASSES`, since
+//! later passes clean up after earlier ones: folding constants leaves branches nothing
+//! reaches, and removing them leaves variables nothing reads. The `opt_level` compile
+//! option picks the passes: 0 runs none, for fast editor feedback alongside validation; 1
+//! folds constants and removes dead nodes; 2, the default, runs every pass. The `passes`
+//! option instead names the passes to run, still in pipeline order.
+
+use crate::compiler::optimizer::{self, Eliminated, FusedChain};
+use crate::compiler::{folding, limits};
+use crate::error::CompilerError;
+use crate::{CompileOptions, WorkflowDefinition};
+
+/// Name of the constant folding pass
+pub const FOLD_CONSTANTS: &str = "fold-constants";
+
+/// Name of the dead-node elimination pass
+pub const DEAD_NODES: &str = "dead-nodes";
+
+/// `opt_level` used when the options set none
+pub const DEFAULT_LEVEL: u8 = 2;
+
+/// A definition as it goes through the pipeline, with what the passes changed
+#[derive(Debug, Clone)]
+pub struct Optimization {
+    pub definition: WorkflowDefinition,
+    pub eliminated: Eliminated,
+    pub fused: Vec<FusedChain>,
+    /// Names of the passes that ran, in order
+    pub passes: Vec<String>,
+}
+
+pub trait OptimizationPass: Sync {
+    /// Name selecting the pass in the `passes` compile option
+    fn name(&self) -> &'static str;
+
+    /// Lowest `opt_level` running the pass
+    fn level(&self) -> u8;
+
+    /// Rewrite the validated definition, recording what changed; long-running passes stop
+    /// at a `limits::checkpoint` past the compilation's limits
+    fn run(&self, optimization: &mut Optimization, options: &CompileOptions) -> Result<(), CompilerError>;
+}
+
+/// Decides Decision conditions over constant variables and prunes the branches they rule out
+struct FoldConstants;
+
+impl OptimizationPass for FoldConstants {
+    fn name(&self) -> &'static str {
+        FOLD_CONSTANTS
+    }
+
+    fn level(&self) -> u8 {
+        1
+    }
+
+    fn run(&self, optimization: &mut Optimization, _: &CompileOptions) -> Result<(), CompilerError> {
+        optimization.definition = folding::fold(&optimization.definition)?.0;
+        Ok(())
+    }
+}
+
+/// Removes nodes unreachable from Start and their edges
+struct DeadNodes;
+
+impl OptimizationPass for DeadNodes {
+    fn name(&self) -> &'static str {
+        DEAD_NODES
+    }
+
+    fn level(&self) -> u8 {
+        1
+    }
+
+    fn run(&self, optimization: &mut Optimization, _: &CompileOptions) -> Result<(), CompilerError> {
+        let (definition, eliminated) = optimizer::eliminate_unreachable(&optimization.definition);
+        optimization.definition = definition;
+        optimization.eliminated.nodes.extend(eliminated.nodes);
+        optimization.eliminated.edges.extend(eliminated.edges);
+        Ok(())
+    }
+}
+
+/// Removes variables no node or edge reads
+struct DeadVariables;
+
+impl OptimizationPass for DeadVariables {
+    fn name(&self) -> &'static str {
+        "dead-variables"
+    }
+
+    fn level(&self) -> u8 {
+        2
+    }
+
+    fn run(&self, optimization: &mut Optimization, _: &CompileOptions) -> Result<(), CompilerError> {
+        let (definition, variables) = optimizer::eliminate_unused_variables(&optimization.definition)?;
+        optimization.definition = definition;
+        optimization.eliminated.variables.extend(variables);
+        Ok(())
+    }
+}
+
+/// Merges chains of Transforms, and of activities with `fuse_activities`, into one node
+struct FuseSequential;
+
+impl OptimizationPass for FuseSequential {
+    fn name(&self) -> &'static str {
+        "fuse-sequential"
+    }
+
+    fn level(&self) -> u8 {
+        2
+    }
+
+    fn run(&self, optimization: &mut Optimization, options: &CompileOptions) -> Result<(), CompilerError> {
+        let (definition, fused) = optimizer::fuse_sequential(&optimization.definition, options.fuse_activities)?;
+        optimization.definition = definition;
+        optimization.fused.extend(fused);
+        Ok(())
+    }
+}
+
+/// Every pass, in the order the pipeline runs them
+pub const PASSES: &[&dyn OptimizationPass] = &[&FoldConstants, &DeadNodes, &DeadVariables, &FuseSequential];
+
+/// Passes the options select, in pipeline order: those named in `passes`, else those the
+/// `opt_level` runs
+pub fn select(options: &CompileOptions) -> Result<Vec<&'static dyn OptimizationPass>, CompilerError> {
+    let names = || PASSES.iter().map(|p| p.name()).collect::<Vec<_>>().join(", ");
+    if let Some(requested) = &options.passes {
+        if let Some(unknown) = requested.iter().find(|r| !PASSES.iter().any(|p| p.name() == r.as_str())) {
+            return Err(CompilerError::ValidationError(format!(
+                "Unknown optimization pass '{}'; passes are {}",
+                unknown,
+                names()
+            ).into()));
+        }
+        return Ok(PASSES.iter().copied().filter(|p| requested.iter().any(|r| r == p.name())).collect());
+    }
+    let level = options.opt_level.unwrap_or(DEFAULT_LEVEL);
+    if level > DEFAULT_LEVEL {
+        return Err(CompilerError::ValidationError(format!("Compile option opt_level {} is not 0, 1 or 2", level).into()));
+    }
+    Ok(PASSES.iter().copied().filter(|p| p.level() <= level).collect())
+}
+
+/// Run the selected passes over a validated definition
+pub fn run(definition: &WorkflowDefinition, options: &CompileOptions) -> Result<Optimization, CompilerError> {
+    let mut optimization = Optimization {
+        definition: definition.clone(),
+        eliminated: Eliminated::default(),
+        fused: vec![],
+        passes: vec![],
+    };
+    for pass in select(options)? {
+        pass.run(&mut optimization, options)?;
+        optimization.passes.push(pass.name().to_string());
+        limits::checkpoint("optimization")?;
+    }
+    Ok(optimization)
+}
@@ -0,0 +1,261 @@
+//! Compiler plugins: node types defined outside the core crate
+//!
+//! A plugin names its node type, publishes the JSON Schema of its config, validates nodes of
+//! its type and lowers each one to a built-in node before anything else runs. The lowered node
+//! keeps the original ID, label, position and edges, so every validation rule, target and
+//! export handles it without knowing about the plugin. Plugins are `NodePlugin`
+//! implementations registered when the server starts, or manifests read from the configured
+//! `plugin_dir` whose nodes run a named activity.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::config::ExecutionProfiles;
+use crate::error::CompilerError;
+use crate::{NodeType, RetryPolicy, WorkflowDefinition, WorkflowNode};
+
+/// Config key naming the activity type an Activity node runs, overriding the label-derived name
+pub const ACTIVITY_TYPE_KEY: &str = "activity_type";
+
+/// Node type supplied from outside the core crate
+pub trait NodePlugin: Send + Sync {
+    /// Node type name used in definitions, in snake_case
+    fn node_type(&self) -> &str;
+
+    /// One-line summary shown by `GET /api/v1/plugins`
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// JSON Schema of the node config
+    fn config_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    /// Check a node of this type; the default checks its config against `config_schema`
+    fn validate(&self, node: &WorkflowNode) -> Result<(), CompilerError> {
+        check_config(&self.config_schema(), node)
+    }
+
+    /// Built-in node to compile a node of this type as; its ID, label and position are reset
+    /// to the original node's
+    fn lower(&self, node: &WorkflowNode) -> Result<WorkflowNode, CompilerError>;
+}
+
+/// Plugin declared in a JSON file, whose nodes run one activity with the node config as input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub node_type: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "object_schema")]
+    pub config_schema: Value,
+    /// Activity type the nodes run, e.g. `ProvisionSimActivity`
+    pub activity: String,
+    /// Retry policy for nodes that set none
+    pub retries: Option<RetryPolicy>,
+    /// Execution profile for nodes that name none
+    pub profile: Option<String>,
+}
+
+fn object_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+impl PluginManifest {
+    /// Check the activity type is an identifier every target can name and the profile exists
+    pub fn check(&self, profiles: &ExecutionProfiles) -> Result<(), String> {
+        let mut chars = self.activity.chars();
+        if !(chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')) {
+            return Err(format!("activity '{}' must be a letter followed by letters, digits and '_'", self.activity));
+        }
+        if let Some(profile) = self.profile.as_deref().filter(|p| !profiles.0.contains_key(*p)) {
+            return Err(format!("profile '{}' is not a configured execution profile", profile));
+        }
+        Ok(())
+    }
+}
+
+impl NodePlugin for PluginManifest {
+    fn node_type(&self) -> &str {
+        &self.node_type
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn config_schema(&self) -> Value {
+        self.config_schema.clone()
+    }
+
+    fn lower(&self, node: &WorkflowNode) -> Result<WorkflowNode, CompilerError> {
+        let mut config = match &node.config {
+            Value::Object(config) => config.clone(),
+            _ => serde_json::Map::new(),
+        };
+        config.insert(ACTIVITY_TYPE_KEY.to_string(), json!(self.activity));
+        Ok(WorkflowNode {
+            node_type: NodeType::Activity,
+            config: Value::Object(config),
+            retries: node.retries.clone().or_else(|| self.retries.clone()),
+            profile: node.profile.clone().or_else(|| self.profile.clone()),
+            ..node.clone()
+        })
+    }
+}
+
+/// Node type, summary and config schema of a registered plugin
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    pub node_type: String,
+    pub description: String,
+    pub config_schema: Value,
+}
+
+/// Whether a name is taken by a built-in node type
+fn is_built_in(name: &str) -> bool {
+    serde_json::from_value::<NodeType>(json!(name)).is_ok_and(|t| !matches!(t, NodeType::Plugin(_)))
+}
+
+/// Registered plugins by node type
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    plugins: BTreeMap<String, Arc<dyn NodePlugin>>,
+}
+
+impl PluginRegistry {
+    /// Registry of the in-process plugins and the plugins of the configured manifests
+    pub fn with_manifests(registered: &[Arc<dyn NodePlugin>], manifests: &[PluginManifest]) -> Result<Self, CompilerError> {
+        let mut registry = Self::default();
+        for plugin in registered {
+            registry.register(plugin.clone())?;
+        }
+        for manifest in manifests {
+            registry.register(Arc::new(manifest.clone()))?;
+        }
+        Ok(registry)
+    }
+
+    /// Register a plugin, rejecting names of built-in node types and of registered plugins
+    pub fn register(&mut self, plugin: Arc<dyn NodePlugin>) -> Result<(), CompilerError> {
+        let name = plugin.node_type().to_string();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err(CompilerError::ParseError(format!("Plugin node type '{}' must be snake_case", name)));
+        }
+        if is_built_in(&name) {
+            return Err(CompilerError::ParseError(format!("Plugin node type '{}' is a built-in node type", name)));
+        }
+        if self.plugins.contains_key(&name) {
+            return Err(CompilerError::ParseError(format!("Plugin node type '{}' is registered twice", name)));
+        }
+        self.plugins.insert(name, plugin);
+        Ok(())
+    }
+
+    /// Registered plugins, sorted by node type
+    pub fn describe(&self) -> Vec<PluginInfo> {
+        self.plugins.values()
+            .map(|p| PluginInfo { node_type: p.node_type().to_string(), description: p.description().to_string(), config_schema: p.config_schema() })
+            .collect()
+    }
+
+    /// Definition with every plugin node validated and lowered to a built-in node
+    pub fn lower(&self, definition: &WorkflowDefinition) -> Result<WorkflowDefinition, CompilerError> {
+        let mut lowered = definition.clone();
+        for node in lowered.nodes.iter_mut() {
+            let NodeType::Plugin(name) = &node.node_type else { continue };
+            let plugin = self.plugins.get(name).ok_or_else(|| {
+                let known: Vec<&str> = self.plugins.keys().map(String::as_str).collect();
+                CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Node '{}' has unknown node type '{}'; registered plugins: {}",
+                    node.id, name, if known.is_empty() { "none".to_string() } else { known.join(", ") }
+                )).node(&node.id))
+            })?;
+            plugin.validate(node)?;
+            let replacement = plugin.lower(node)?;
+            if matches!(replacement.node_type, NodeType::Plugin(_)) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Plugin '{}' lowered node '{}' to another plugin node type",
+                    name, node.id
+                )).node(&node.id)));
+            }
+            *node = WorkflowNode { id: node.id.clone(), label: node.label.clone(), position: node.position.clone(), ..replacement };
+        }
+        Ok(lowered)
+    }
+}
+
+/// Plugin manifests in the JSON files of a directory, sorted by file name
+pub fn load_manifests(dir: &str) -> Result<Vec<PluginManifest>, CompilerError> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths.iter()
+        .map(|path| {
+            let raw = std::fs::read_to_string(path)?;
+            serde_json::from_str(&raw)
+                .map_err(|e| CompilerError::ParseError(format!("Invalid plugin manifest '{}': {}", path.display(), e)))
+        })
+        .collect()
+}
+
+/// Name of the JSON Schema type a value has
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check a value against the `type`, `enum`, `required`, `properties` and
+/// `additionalProperties` keywords of a schema
+fn check_value(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let actual = json_type(value);
+        if actual != expected && !(expected == "number" && actual == "integer") {
+            return Err(format!("{} must be of type {}, not {}", path, expected, actual));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!("{} must be one of {}", path, Value::Array(allowed.clone())));
+        }
+    }
+    let Value::Object(fields) = value else { return Ok(()) };
+    for required in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+        if !fields.contains_key(required) {
+            return Err(format!("{} is missing required field '{}'", path, required));
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, field) in fields {
+        match properties.and_then(|p| p.get(key)) {
+            Some(property) => check_value(property, field, &format!("{}.{}", path, key))?,
+            None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                return Err(format!("{} has unknown field '{}'", path, key));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Check a node's config against a plugin's config schema
+pub fn check_config(schema: &Value, node: &WorkflowNode) -> Result<(), CompilerError> {
+    let config = if node.config.is_null() { json!({}) } else { node.config.clone() };
+    check_value(schema, &config, "config").map_err(|reason| {
+        CompilerError::ValidationError(Diagnostic::new(format!("Invalid config for node '{}': {}", node.id, reason)).node(&node.id)).with_code("invalid-node-config")
+    })
+}
@@ -0,0 +1,520 @@
+//! Prefect 2 generation: an async flow calling one `@task` per activity, with the graph's
+//! branches as Python control flow
+//!
+//! Emits `flows.py`, `tasks.py`, a `serve.py` that serves the flow on the definition's
+//! schedules, a pytest suite and `pyproject.toml`. Activity, DatabaseQuery and PublishEvent
+//! nodes await the task of their activity with the node's retries and timeout, HttpCall nodes
+//! a shared `http_call` task, decisions are `if`/`elif`/`else` over the flow parameters up to
+//! the node where their branches merge, and forks gather one coroutine per branch before
+//! their join. Timers sleep,
+//! signal waits pause the flow run under the signal name as its key, and subworkflows run the
+//! child flow's deployment.
+//!
+//! As for Conductor, graphs that loop or whose branches do not merge have no structured form
+//! and are rejected. Schedule and trigger features Prefect lacks are listed in the target
+//! metadata instead.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::json;
+
+use crate::compiler::airflow::{identifier, python_expression, python_literal};
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{self, activity_name, go_string_literal as string_literal, is_activity_node};
+use crate::compiler::conductor::check_definition;
+use crate::compiler::python::py_type;
+use crate::compiler::steps::{self, TargetMetadata, TargetSources, UnmappedFeature};
+use crate::compiler::{duration, limits};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    CodegenTarget, DynamicActivityConfig, EdgeKind, GeneratedFile, HttpCallConfig, NodeType, OverlapPolicy, SubWorkflowConfig, TriggerType, WaitSignalConfig, WaitTimerConfig, WorkflowDefinition, WorkflowNode,
+};
+
+/// Prefect release the generated project depends on
+pub const PREFECT_VERSION: &str = "2.19.9";
+
+/// Longest a paused flow run waits for its signal before failing
+const SIGNAL_TIMEOUT_SECONDS: u64 = 7 * 24 * 3600;
+
+/// Retry delays listed per retry; later retries reuse the last
+const MAX_RETRY_DELAYS: usize = 50;
+
+pub struct Prefect;
+
+/// Flow being assembled from the graph
+struct Builder<'a> {
+    definition: &'a WorkflowDefinition,
+    target: &'a str,
+    /// Nodes already placed in the flow body
+    placed: HashSet<&'a str>,
+    /// Names of the nested branch coroutines
+    branches: HashSet<String>,
+    /// Task functions by activity name
+    tasks: BTreeMap<String, String>,
+    uses_http: bool,
+    uses_asyncio: bool,
+    uses_pause: bool,
+    uses_deployments: bool,
+    signals: Vec<String>,
+}
+
+impl<'a> Builder<'a> {
+    /// Task function for an activity, registered for the tasks module
+    fn task_function(&mut self, activity: &str) -> String {
+        let function = identifier(activity);
+        self.tasks.insert(activity.to_string(), function.clone());
+        format!("tasks.{}", function)
+    }
+
+    /// Unused name for a branch coroutine of a fork
+    fn branch_name(&mut self, fork: &WorkflowNode, index: usize) -> String {
+        let name = format!("{}_branch_{}", identifier(&fork.label), index + 1);
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while !self.branches.insert(unique.clone()) {
+            unique = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        unique
+    }
+
+    /// Single successor of a node with sequential semantics
+    fn successor(&self, node: &WorkflowNode) -> Result<Option<&'a WorkflowNode>, CompilerError> {
+        let targets = graph::flow_targets(self.definition, node);
+        if targets.len() > 1 {
+            return Err(steps::unsupported(node, "more than one outgoing edge on a node that is not a decision or fork", self.target));
+        }
+        Ok(targets.first().copied())
+    }
+
+    /// `with_options(...)` applying a node's retry policy and timeout to a task, if it has either
+    fn task_options(&self, node: &WorkflowNode) -> Result<String, CompilerError> {
+        let mut options = vec![];
+        if let Some(retry) = steps::retry(node)? {
+            let retries = retry.max_attempts.saturating_sub(1) as usize;
+            let mut delay = retry.initial_interval.as_secs_f64();
+            let mut delays = vec![];
+            for _ in 0..retries.min(MAX_RETRY_DELAYS) {
+                delays.push(delay.to_string());
+                delay = (delay * retry.backoff_coefficient).min(retry.max_interval.as_secs_f64());
+            }
+            options.push(format!("retries={}", retries));
+            if !delays.is_empty() {
+                options.push(format!("retry_delay_seconds=[{}]", delays.join(", ")));
+            }
+        }
+        let timeouts = steps::timeouts(node)?;
+        if let Some(timeout) = timeouts.start_to_close.or(timeouts.schedule_to_close) {
+            options.push(format!("timeout_seconds={}", timeout.as_secs_f64()));
+        }
+        Ok(if options.is_empty() { String::new() } else { format!(".with_options({})", options.join(", ")) })
+    }
+
+    /// Flow statements from `start` up to, not including, `stop`; `branch` is set inside the
+    /// coroutine of a fork branch, where an End node cannot end the flow
+    fn sequence(&mut self, start: Option<&'a WorkflowNode>, stop: Option<&str>, pad: &str, branch: bool) -> Result<String, CompilerError> {
+        let mut code = String::new();
+        let mut next = start;
+        while let Some(node) = next {
+            limits::checkpoint("code generation")?;
+            if Some(node.id.as_str()) == stop {
+                break;
+            }
+            if !matches!(node.node_type, NodeType::End) && !self.placed.insert(node.id.as_str()) {
+                return Err(steps::unsupported(node, "a node reached again through a loop or through branches that do not merge", self.target));
+            }
+            next = match node.node_type {
+                NodeType::Start => self.successor(node)?,
+                NodeType::Transform => {
+                    code.push_str(&format!("{pad}# {}: transform\n\n", node.label));
+                    self.successor(node)?
+                }
+                NodeType::End if branch => return Err(steps::unsupported(node, "an End node inside a parallel branch", self.target)),
+                NodeType::End => {
+                    if !pad.is_empty() && stop.is_some() {
+                        code.push_str(&format!("{pad}return\n"));
+                    }
+                    None
+                }
+                NodeType::Decision => {
+                    let merge = graph::merge_point(self.definition, node);
+                    code.push_str(&self.decision(node, merge, pad, branch)?);
+                    merge
+                }
+                NodeType::ParallelGateway if graph::is_join(self.definition, node) => self.successor(node)?,
+                NodeType::ParallelGateway => {
+                    let (fork, after) = self.fork(node, pad)?;
+                    code.push_str(&fork);
+                    after
+                }
+                _ => {
+                    code.push_str(&self.call(node, pad)?);
+                    self.successor(node)?
+                }
+            };
+        }
+        Ok(code)
+    }
+
+    /// `if`/`elif`/`else` over the edge conditions, each case running up to the merge point
+    fn decision(&mut self, node: &'a WorkflowNode, merge: Option<&'a WorkflowNode>, pad: &str, branch: bool) -> Result<String, CompilerError> {
+        let stop = merge.map(|m| m.id.as_str());
+        let inner = format!("{pad}    ");
+        let mut cases = vec![];
+        let mut default = None;
+        for edge in graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow) {
+            let Some(target) = graph::find_node(self.definition, &edge.target) else { continue };
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => {
+                    let expression = python_expression(self.definition, condition, "params")?;
+                    cases.push((expression, self.sequence(Some(target), stop, &inner, branch)?));
+                }
+                None if default.is_none() => default = Some(self.sequence(Some(target), stop, &inner, branch)?),
+                None => return Err(steps::unsupported(node, "more than one edge without a condition", self.target)),
+            }
+        }
+        let body = |code: String| if code.trim().is_empty() { format!("{inner}pass\n") } else { code.trim_end().to_string() + "\n" };
+        let mut code = format!("{pad}# Decision: {}\n", node.label);
+        for (i, (expression, case)) in cases.into_iter().enumerate() {
+            code.push_str(&format!("{pad}{} {}:\n{}", if i == 0 { "if" } else { "elif" }, expression, body(case)));
+        }
+        match default {
+            Some(case) if code.lines().count() == 1 => code.push_str(&body(case).lines().map(|l| format!("{}\n", l.strip_prefix("    ").unwrap_or(l))).collect::<String>()),
+            Some(case) => code.push_str(&format!("{pad}else:\n{}", body(case))),
+            None => {}
+        }
+        code.push('\n');
+        Ok(code)
+    }
+
+    /// Branch coroutines of a fork, gathered, and the node after its join
+    fn fork(&mut self, node: &'a WorkflowNode, pad: &str) -> Result<(String, Option<&'a WorkflowNode>), CompilerError> {
+        let targets = graph::flow_targets(self.definition, node);
+        let join = targets.first().and_then(|first| graph::find_join(self.definition, first));
+        let stop = join.map(|j| j.id.as_str());
+        let inner = format!("{pad}    ");
+        let mut code = format!("{pad}# Parallel: {}\n", node.label);
+        let mut names = vec![];
+        for (i, target) in targets.into_iter().enumerate() {
+            let name = self.branch_name(node, i);
+            let body = self.sequence(Some(target), stop, &inner, true)?;
+            let body = if body.trim().is_empty() { format!("{inner}pass\n") } else { body.trim_end().to_string() + "\n" };
+            code.push_str(&format!("{pad}async def {name}() -> None:\n{body}\n"));
+            names.push(name);
+        }
+        self.uses_asyncio = true;
+        let calls = names.iter().map(|n| format!("{}()", n)).collect::<Vec<_>>().join(", ");
+        code.push_str(&format!("{pad}await asyncio.gather({calls})\n\n"));
+        let after = match join {
+            Some(join) => {
+                self.placed.insert(join.id.as_str());
+                self.successor(join)?
+            }
+            None => None,
+        };
+        Ok((code, after))
+    }
+
+    /// Statements for a node without branching semantics
+    fn call(&mut self, node: &WorkflowNode, pad: &str) -> Result<String, CompilerError> {
+        let options = self.task_options(node)?;
+        let code = match node.node_type {
+            NodeType::HttpCall => {
+                let config: HttpCallConfig = node.typed_config()?;
+                if config.url.is_empty() {
+                    return Err(CompilerError::CodeGenError(format!("HttpCall node '{}' has no url", node.id)));
+                }
+                let mut arguments = vec![string_literal(&config.method.to_ascii_uppercase()), string_literal(&config.url)];
+                if !config.headers.is_empty() {
+                    arguments.push(format!("headers={}", python_literal(&json!(config.headers))));
+                }
+                if !config.body.is_null() {
+                    arguments.push(format!("body={}", python_literal(&config.body)));
+                }
+                self.uses_http = true;
+                format!("{pad}await tasks.http_call{options}({})\n", arguments.join(", "))
+            }
+            _ if is_activity_node(node) => {
+                let function = self.task_function(&activity_name(node));
+                format!("{pad}await {function}{options}(params)\n")
+            }
+            NodeType::DynamicActivity => {
+                let config: DynamicActivityConfig = node.typed_config()?;
+                let allowed: Vec<String> = config.allowed.iter()
+                    .map(|activity| format!("{}: {}", string_literal(activity), self.task_function(activity)))
+                    .collect();
+                let not_allowed = string_literal(&format!("{}: activity not allowed: ", node.label));
+                format!(
+                    "{pad}allowed = {{{}}}\n{pad}activity_name = params.get({}) or \"\"\n{pad}if activity_name not in allowed:\n{pad}    raise ValueError({} + activity_name)\n{pad}await allowed[activity_name]{options}(params)\n",
+                    allowed.join(", "),
+                    string_literal(&config.selector),
+                    not_allowed,
+                )
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                let child = identifier(&config.workflow);
+                self.uses_deployments = true;
+                let timeout = if config.wait_for_completion { "None" } else { "0" };
+                format!("{pad}await run_deployment(name={}, parameters=params, timeout={timeout})\n", string_literal(&format!("{child}/{child}")))
+            }
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config()?;
+                let timer = duration::parse_field(&config.duration, "duration", Some(&node.id))?;
+                self.uses_asyncio = true;
+                format!("{pad}await asyncio.sleep({})\n", timer.as_secs_f64())
+            }
+            NodeType::WaitSignal => {
+                // Resumed through the API by whoever sends the signal
+                let config: WaitSignalConfig = node.typed_config()?;
+                self.signals.push(config.signal.clone());
+                self.uses_pause = true;
+                format!("{pad}await pause_flow_run(key={}, timeout=SIGNAL_TIMEOUT_SECONDS)\n", string_literal(&config.signal))
+            }
+            _ => return Err(steps::unsupported(node, &format!("a {:?} node", node.node_type), self.target)),
+        };
+        Ok(format!("{pad}# {}\n{code}\n", node.label))
+    }
+}
+
+/// Flow parameters and the `params` dict the flow passes to its tasks
+fn parameters(definition: &WorkflowDefinition) -> (String, String) {
+    let mut signature = vec![];
+    let mut values = vec![];
+    for variable in &definition.variables {
+        let name = identifier(&variable.name);
+        let annotation = py_type(&variable.var_type);
+        signature.push(match &variable.default_value {
+            Some(default) if !default.is_null() => format!("{}: {} = {}", name, annotation, python_literal(default)),
+            _ if annotation == "Any" => format!("{}: Any = None", name),
+            _ => format!("{}: Optional[{}] = None", name, annotation),
+        });
+        values.push(format!("{}: {}", string_literal(&variable.name), name));
+    }
+    (signature.join(", "), format!("{{{}}}", values.join(", ")))
+}
+
+/// Serve script running the flow on its schedules, with the schedule features it leaves out
+fn generate_serve(definition: &WorkflowDefinition, package_name: &str, unmapped: &mut Vec<UnmappedFeature>) -> Result<String, CompilerError> {
+    let mut unmap = |feature: String, fallback: &str| unmapped.push(UnmappedFeature { node_id: None, feature, fallback: fallback.to_string() });
+    let schedules = codegen::schedule_triggers(definition)?;
+    for schedule in &schedules {
+        if schedule.overlap != OverlapPolicy::AllowAll {
+            let policy = serde_json::to_value(schedule.overlap).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+            unmap(format!("the {} schedule overlap policy", policy), "runs of the schedule may overlap");
+        }
+        if schedule.catchup_window.is_some() {
+            unmap("a schedule catch-up window".to_string(), "missed runs are not caught up");
+        }
+    }
+    let paused = !schedules.is_empty() && schedules.iter().all(|s| s.paused);
+    if !paused && schedules.iter().any(|s| s.paused) {
+        unmap("a paused schedule next to active ones".to_string(), "every schedule is active");
+    }
+    for trigger in &definition.triggers {
+        match trigger.trigger_type {
+            TriggerType::Webhook => unmap("a webhook trigger".to_string(), "not generated; add a Prefect automation or call the API"),
+            TriggerType::Event => unmap("an event trigger".to_string(), "not generated; add a deployment trigger on the event"),
+            TriggerType::Manual | TriggerType::Schedule => {}
+        }
+    }
+    if definition.timeouts.as_ref().is_some_and(|t| t.task.is_some()) {
+        unmap("a workflow task timeout".to_string(), "ignored; Prefect flow runs have no workflow tasks");
+    }
+
+    let function = identifier(package_name);
+    let (import, arguments) = if schedules.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let crons: String = schedules.iter().map(|s| format!("            CronSchedule(cron={}),\n", string_literal(&s.cron))).collect();
+        (
+            "from prefect.client.schemas.schedules import CronSchedule\n\n".to_string(),
+            format!("        schedules=[\n{crons}        ],\n{}", if paused { "        paused=True,\n" } else { "" }),
+        )
+    };
+    Ok(format!(r#"# Generated by OmniRoute Workflow Compiler
+
+{import}from flows import {function}
+
+if __name__ == "__main__":
+    {function}.serve(
+        name={name},
+{arguments}    )
+"#, name = string_literal(package_name)))
+}
+
+/// Flow module, tasks module and signals for a definition
+fn generate_flow(definition: &WorkflowDefinition, package_name: &str, fingerprint: &str, target: &str) -> Result<(String, String, Vec<String>), CompilerError> {
+    check_definition(definition, target)?;
+    let start = definition.nodes.iter().find(|n| matches!(n.node_type, NodeType::Start))
+        .ok_or_else(|| CompilerError::CodeGenError("The workflow needs a Start node".to_string()))?;
+    let mut builder = Builder {
+        definition,
+        target,
+        placed: HashSet::new(),
+        branches: HashSet::new(),
+        tasks: BTreeMap::new(),
+        uses_http: false,
+        uses_asyncio: false,
+        uses_pause: false,
+        uses_deployments: false,
+        signals: vec![],
+    };
+    let body = builder.sequence(Some(start), None, "    ", false)?;
+
+    let mut arguments = vec![format!("name={}", string_literal(package_name)), "version=DEFINITION_VERSION".to_string()];
+    if let Some(description) = definition.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        arguments.push(format!("description={}", string_literal(description)));
+    }
+    let timeout = match definition.timeouts.as_ref().and_then(|t| t.execution.as_ref().map(|raw| (raw, "timeouts.execution")).or(t.run.as_ref().map(|raw| (raw, "timeouts.run")))) {
+        Some((raw, field)) => Some(duration::parse_field(raw, field, None)?),
+        None => None,
+    };
+    if let Some(timeout) = timeout {
+        arguments.push(format!("timeout_seconds={}", timeout.as_secs_f64()));
+    }
+
+    let (signature, params) = parameters(definition);
+    let mut imports = String::new();
+    if builder.uses_asyncio {
+        imports.push_str("import asyncio\n");
+    }
+    imports.push_str(if signature.contains("Optional[") { "from typing import Any, Optional\n\n" } else { "from typing import Any\n\n" });
+    imports.push_str("from prefect import flow");
+    if builder.uses_pause {
+        imports.push_str(", pause_flow_run");
+    }
+    imports.push('\n');
+    if builder.uses_deployments {
+        imports.push_str("from prefect.deployments import run_deployment\n");
+    }
+    let signal_timeout = if builder.uses_pause {
+        format!("# Longest a paused run waits for its signal\nSIGNAL_TIMEOUT_SECONDS = {}\n", SIGNAL_TIMEOUT_SECONDS)
+    } else {
+        String::new()
+    };
+    let workflow_code = format!(r#"# Generated by OmniRoute Workflow Compiler
+# DO NOT EDIT - This file is auto-generated
+
+{imports}
+import tasks
+
+# Version of the workflow definition this flow was compiled from
+DEFINITION_VERSION = {version}
+# Content hash of that definition
+DEFINITION_FINGERPRINT = {fingerprint}
+{signal_timeout}
+
+@flow({arguments})
+async def {function}({signature}) -> None:
+    params: dict[str, Any] = {params}
+
+{body}"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        arguments = arguments.join(", "),
+        function = identifier(package_name),
+        body = body.trim_end().to_string() + "\n",
+    );
+
+    let mut functions: String = builder.tasks.iter()
+        .map(|(name, function)| format!(r#"
+
+@task(name={quoted})
+async def {function}(params: dict[str, Any]) -> None:
+    """{name} implements the {name} activity"""
+    # TODO: implement {name} using params
+"#, quoted = string_literal(name)))
+        .collect();
+    if builder.uses_http {
+        functions.push_str(r#"
+
+@task(name="http_call")
+async def http_call(method: str, url: str, headers: Optional[dict[str, str]] = None, body: Any = None) -> Any:
+    """Send an HTTP request, failing on an error status"""
+    content = body if isinstance(body, str) else None
+    payload = None if isinstance(body, str) else body
+    async with httpx.AsyncClient() as client:
+        response = await client.request(method, url, headers=headers, content=content, json=payload)
+    response.raise_for_status()
+    return response.json() if response.headers.get("content-type", "").startswith("application/json") else response.text
+"#);
+    }
+    let imports = if builder.uses_http { "from typing import Any, Optional\n\nimport httpx\n" } else { "from typing import Any\n\n" };
+    let activity_code = format!("# Generated by OmniRoute Workflow Compiler\n\n{imports}from prefect import task\n{functions}");
+
+    let mut signals = builder.signals;
+    signals.sort();
+    signals.dedup();
+    Ok((workflow_code, activity_code, signals))
+}
+
+fn generate_test(definition: &WorkflowDefinition, package_name: &str) -> String {
+    let parameters: Vec<String> = definition.variables.iter().map(|v| string_literal(&identifier(&v.name))).collect();
+    let expected = if parameters.is_empty() { "set()".to_string() } else { format!("{{{}}}", parameters.join(", ")) };
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+
+from flows import {function}
+
+
+def test_{function}_loads() -> None:
+    assert {function}.name == {name}
+    assert set({function}.parameters.properties) == {expected}
+"#, function = identifier(package_name), name = string_literal(package_name))
+}
+
+fn generate_project_files(package_name: &str, version: &str) -> Vec<GeneratedFile> {
+    let pyproject = format!(r#"[project]
+name = {name}
+version = {version}
+requires-python = ">=3.9"
+dependencies = ["prefect=={PREFECT_VERSION}", "httpx>=0.23"]
+
+[project.optional-dependencies]
+test = ["pytest>=8.0"]
+"#, name = string_literal(&package_name.replace('_', "-")), version = string_literal(version));
+
+    vec![GeneratedFile { path: "pyproject.toml".to_string(), content: pyproject }]
+}
+
+impl Backend for Prefect {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Prefect
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        PREFECT_VERSION
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        format!(r#"# Generated by OmniRoute Workflow Compiler
+services:
+  prefect:
+    image: prefecthq/prefect:{PREFECT_VERSION}-python3.11
+    command: prefect server start --host 0.0.0.0
+    ports:
+      - "4200:4200"
+"#)
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let (workflow_code, activity_code, signals) = generate_flow(definition, package_name, fingerprint, self.target().name())?;
+        let mut unmapped = vec![];
+        let worker_code = generate_serve(definition, package_name, &mut unmapped)?;
+
+        Ok(TargetSources {
+            workflow_code,
+            activity_code,
+            worker_code,
+            test_code: generate_test(definition, package_name),
+            files: generate_project_files(package_name, &definition.version),
+            signals,
+            metadata: Some(TargetMetadata { unmapped }),
+        })
+    }
+}
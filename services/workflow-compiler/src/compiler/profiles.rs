@@ -0,0 +1,86 @@
+//! Execution profiles: named defaults from the service configuration for the retry policy,
+//! timeouts and heartbeat of activity nodes
+//!
+//! A node names its profile or gets the configured `default_profile`. The profile only fills
+//! what the node leaves unset, so settings on the node always win, and every node a profile
+//! applied to is recorded in the compilation metadata with the settings it supplied.
+
+use serde::{Deserialize, Serialize};
+
+use crate::compiler::codegen::is_activity_node;
+use crate::compiler::diagnostic::Diagnostic;
+use crate::config::CompilerConfig;
+use crate::error::CompilerError;
+use crate::{ActivityTimeouts, NodeType, WorkflowDefinition, WorkflowNode};
+
+/// Profile applied to one node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedProfile {
+    pub node_id: String,
+    pub profile: String,
+    /// Settings the profile supplied, such as `retries` or `timeouts.heartbeat`; empty when
+    /// the node set everything itself
+    pub supplied: Vec<String>,
+}
+
+/// Whether a node executes an activity whose options a profile sets
+fn takes_profile(node: &WorkflowNode) -> bool {
+    is_activity_node(node) || matches!(node.node_type, NodeType::DynamicActivity)
+}
+
+/// Check every profile a node names is configured and applies to it
+pub fn check(definition: &WorkflowDefinition, config: &CompilerConfig) -> Result<(), CompilerError> {
+    for node in &definition.nodes {
+        let Some(profile) = &node.profile else { continue };
+        if !takes_profile(node) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Node '{}' names execution profile '{}' but runs no activity",
+                node.id, profile
+            )).node(&node.id)));
+        }
+        if !config.profiles.0.contains_key(profile) {
+            let known: Vec<&str> = config.profiles.0.keys().map(String::as_str).collect();
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Node '{}' names unknown execution profile '{}'; configured profiles: {}",
+                node.id, profile, known.join(", ")
+            )).node(&node.id)));
+        }
+    }
+    Ok(())
+}
+
+/// Definition with each activity node's unset retries and timeouts filled from its profile
+pub fn apply(definition: &WorkflowDefinition, config: &CompilerConfig) -> Result<(WorkflowDefinition, Vec<AppliedProfile>), CompilerError> {
+    check(definition, config)?;
+    let mut profiled = definition.clone();
+    let mut applied = Vec::new();
+    for node in profiled.nodes.iter_mut().filter(|n| takes_profile(n)) {
+        let Some(name) = node.profile.clone().or_else(|| config.default_profile.clone()) else { continue };
+        // Configured default profiles are checked when the configuration loads
+        let Some(profile) = config.profiles.0.get(&name) else { continue };
+
+        let mut supplied = Vec::new();
+        if node.retries.is_none() && profile.retries.is_some() {
+            node.retries = profile.retries.clone();
+            supplied.push("retries".to_string());
+        }
+        let timeouts = node.timeouts.get_or_insert_with(ActivityTimeouts::default);
+        let fields = [
+            ("timeouts.start_to_close", &mut timeouts.start_to_close, &profile.timeouts.start_to_close),
+            ("timeouts.schedule_to_close", &mut timeouts.schedule_to_close, &profile.timeouts.schedule_to_close),
+            ("timeouts.heartbeat", &mut timeouts.heartbeat, &profile.timeouts.heartbeat),
+        ];
+        for (field, own, default) in fields {
+            if own.is_none() && default.is_some() {
+                own.clone_from(default);
+                supplied.push(field.to_string());
+            }
+        }
+        if node.timeouts.as_ref() == Some(&ActivityTimeouts::default()) {
+            node.timeouts = None;
+        }
+
+        applied.push(AppliedProfile { node_id: node.id.clone(), profile: name, supplied });
+    }
+    Ok((profiled, applied))
+}
@@ -0,0 +1,400 @@
+//! Python code generation on the `temporalio` SDK
+//!
+//! Emits `workflows.py`, `activities.py`, `worker.py` and a pytest suite, plus
+//! `pyproject.toml`. Node logic comes from the shared step traversal.
+
+use std::time::Duration;
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{go_string_literal as string_literal, is_go_identifier, VERSION_QUERY};
+use crate::compiler::steps::{self, ActivityCall, Retry, Step, TargetSources, Timeouts};
+use crate::compiler::types::base_type;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, ChildCancellationType, CodegenTarget, GeneratedFile, ParentClosePolicy, WorkflowDefinition};
+
+/// Temporal Python SDK release the generated project depends on
+pub const SDK_VERSION: &str = "1.8.0";
+
+/// Python reserved words, which generated identifiers avoid
+pub const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+    "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Map a DSL variable type onto a Python type hint
+pub fn py_type(var_type: &str) -> &'static str {
+    match base_type(var_type) {
+        "string" => "str",
+        "int" | "integer" => "int",
+        "float" | "number" => "float",
+        "bool" | "boolean" => "bool",
+        "object" => "dict[str, Any]",
+        "array" => "list[Any]",
+        _ => "Any",
+    }
+}
+
+/// Dataclass field for a variable; names that are not Python identifiers are rewritten
+fn field_name(name: &str) -> String {
+    let mut field: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    if !is_go_identifier(&field) {
+        field.insert(0, '_');
+    }
+    if KEYWORDS.contains(&field.as_str()) {
+        field.push('_');
+    }
+    field
+}
+
+/// Module-level function name for a registered activity name
+fn function_name(activity: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in activity.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    field_name(&name)
+}
+
+fn timedelta(duration: &Duration) -> String {
+    format!("timedelta(seconds={})", duration.as_secs_f64())
+}
+
+fn retry_policy(retry: &Retry) -> String {
+    format!(
+        "RetryPolicy(initial_interval={}, maximum_interval={}, backoff_coefficient={}, maximum_attempts={})",
+        timedelta(&retry.initial_interval), timedelta(&retry.max_interval), retry.backoff_coefficient, retry.max_attempts
+    )
+}
+
+/// `execute_activity` call for a registered name or a name expression
+fn execute_activity(name: &str, retry: &Option<Retry>, timeouts: &Timeouts, pad: &str) -> String {
+    let start_to_close = timeouts.start_to_close.as_ref().map_or("ACTIVITY_TIMEOUT".to_string(), timedelta);
+    let mut options = format!("{pad}    start_to_close_timeout={start_to_close},\n");
+    if let Some(timeout) = &timeouts.schedule_to_close {
+        options.push_str(&format!("{pad}    schedule_to_close_timeout={},\n", timedelta(timeout)));
+    }
+    if let Some(timeout) = &timeouts.heartbeat {
+        options.push_str(&format!("{pad}    heartbeat_timeout={},\n", timedelta(timeout)));
+    }
+    if let Some(retry) = retry {
+        options.push_str(&format!("{pad}    retry_policy={},\n", retry_policy(retry)));
+    }
+    format!("{pad}await workflow.execute_activity(\n{pad}    {name},\n{pad}    input,\n{options}{pad})\n")
+}
+
+fn activity_call(call: &ActivityCall, pad: &str) -> String {
+    format!("{pad}# {}\n{}", call.label, execute_activity(&string_literal(&call.activity), &call.retry, &call.timeouts, pad))
+}
+
+fn render_step(step: &Step) -> String {
+    match step {
+        Step::Activity(call) => format!("{}\n", activity_call(call, "        ")),
+        Step::Timer { label, raw, duration } => {
+            format!("        # {label}: wait {raw}\n        await asyncio.sleep({})\n\n", duration.as_secs_f64())
+        }
+        Step::Signals { label, signals, required, correlation_key, timeout } => {
+            let names = signals.iter().map(|s| string_literal(s)).collect::<Vec<_>>().join(", ");
+            let key = correlation_key.as_deref().map(|k| format!("input.{}", field_name(k))).unwrap_or_else(|| "None".to_string());
+            let wait = match timeout {
+                Some(timeout) => format!(
+                    "        try:\n            await workflow.wait_condition(arrived, timeout={})\n        except asyncio.TimeoutError:\n            raise ApplicationError({}, type=\"SignalTimeout\", non_retryable=True)\n",
+                    timedelta(timeout),
+                    string_literal(&format!("{label} timed out waiting for signals"))
+                ),
+                None => "        await workflow.wait_condition(arrived)\n".to_string(),
+            };
+            format!(r#"        # Wait for signals: {label} ({required} of {count})
+        pending = {{{names}}}
+
+        def arrived() -> bool:
+            for name in list(pending):
+                if self._take_signal(name, {key}):
+                    pending.discard(name)
+            return {count} - len(pending) >= {required}
+
+{wait}
+"#, count = signals.len())
+        }
+        Step::DynamicActivity { label, selector, allowed, retry, timeouts } => {
+            let allowed = allowed.iter().map(|a| string_literal(a)).collect::<Vec<_>>().join(", ");
+            let not_allowed = string_literal(&format!("{label}: activity not allowed: "));
+            format!(r#"        # Dynamic activity: {label}
+        activity_name = input.{selector} or ""
+        if activity_name not in {{{allowed}}}:
+            raise ApplicationError({not_allowed} + activity_name, type="ActivityNotAllowed", non_retryable=True)
+{call}
+"#, selector = field_name(selector), call = execute_activity("activity_name", retry, timeouts, "        "))
+        }
+        Step::Child { label, workflow, task_queue, parent_close_policy, cancellation_type, wait_for_completion } => {
+            let close = match parent_close_policy {
+                ParentClosePolicy::Terminate => "TERMINATE",
+                ParentClosePolicy::RequestCancel => "REQUEST_CANCEL",
+                ParentClosePolicy::Abandon => "ABANDON",
+            };
+            let cancellation = match cancellation_type {
+                ChildCancellationType::TryCancel => "TRY_CANCEL",
+                ChildCancellationType::WaitCancellationCompleted => "WAIT_CANCELLATION_COMPLETED",
+                ChildCancellationType::Abandon => "ABANDON",
+            };
+            let task_queue = task_queue.as_deref()
+                .map(|q| format!("            task_queue={},\n", string_literal(q)))
+                .unwrap_or_default();
+            let start = if *wait_for_completion { "execute_child_workflow" } else { "start_child_workflow" };
+            format!(r#"        # Child workflow: {label}
+        await workflow.{start}(
+            {workflow},
+{task_queue}            parent_close_policy=workflow.ParentClosePolicy.{close},
+            cancellation_type=workflow.ChildWorkflowCancellationType.{cancellation},
+        )
+
+"#, workflow = string_literal(workflow))
+        }
+        Step::WeightedSplit { label, branches } => {
+            let weights = branches.iter()
+                .scan(0, |lower, (upper, _)| {
+                    let weight = upper - *lower;
+                    *lower = *upper;
+                    Some(weight.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let mut cases = String::new();
+            for (i, (upper, call)) in branches.iter().enumerate() {
+                let condition = if i + 1 == branches.len() {
+                    "        else:\n".to_string()
+                } else if i == 0 {
+                    format!("        if bucket < {upper}:\n")
+                } else {
+                    format!("        elif bucket < {upper}:\n")
+                };
+                cases.push_str(&condition);
+                cases.push_str(&activity_call(call, "            "));
+            }
+            format!(r#"        # Weighted split: {label} ({weights})
+        # workflow.random() is seeded per execution, so replays pick the same bucket
+        bucket = workflow.random().randrange(100)
+{cases}
+"#)
+        }
+    }
+}
+
+fn generate_workflow(definition: &WorkflowDefinition, steps: &[Step], workflow_name: &str, fingerprint: &str) -> String {
+    let fields: String = definition.variables.iter()
+        .map(|v| format!("    {}: Optional[{}] = None\n", field_name(&v.name), py_type(&v.var_type)))
+        .collect();
+    let fields = if fields.is_empty() { "    pass\n".to_string() } else { fields };
+
+    let signals = steps::signal_names(steps);
+    let (signal_types, signal_handlers) = if signals.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let handlers: String = signals.iter().enumerate()
+            .map(|(i, name)| format!(r#"
+    @workflow.signal(name={name})
+    def _signal_{i}(self, payload: Optional[SignalPayload] = None) -> None:
+        self._received.setdefault({name}, []).append(payload or SignalPayload())
+"#, name = string_literal(name)))
+            .collect();
+        (
+            r#"
+
+@dataclass
+class SignalPayload:
+    """Envelope expected on every correlated signal"""
+
+    correlation_key: Optional[str] = None
+    data: Any = None
+"#.to_string(),
+            format!(r#"
+    def __init__(self) -> None:
+        # Buffer signals from the start of the execution so none are missed before their wait
+        self._received: dict[str, list[SignalPayload]] = {{}}
+{handlers}
+    def _take_signal(self, name: str, key: Optional[str]) -> bool:
+        queue = self._received.get(name, [])
+        for index, payload in enumerate(queue):
+            if key is None or payload.correlation_key == key:
+                del queue[index]
+                return True
+        return False
+"#),
+        )
+    };
+
+    let body: String = steps.iter().map(render_step).collect();
+
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+# DO NOT EDIT - This file is auto-generated
+
+import asyncio
+from dataclasses import dataclass
+from datetime import timedelta
+from typing import Any, Optional
+
+from temporalio import workflow
+from temporalio.common import RetryPolicy
+from temporalio.exceptions import ApplicationError
+
+# Version of the workflow definition this code was compiled from
+DEFINITION_VERSION = {version}
+# Content hash of that definition
+DEFINITION_FINGERPRINT = {fingerprint}
+
+ACTIVITY_TIMEOUT = timedelta(minutes=10)
+
+
+@dataclass
+class {workflow_name}Input:
+{fields}
+
+@dataclass
+class {workflow_name}Output:
+    success: bool
+    message: str
+{signal_types}
+
+@workflow.defn(name="{workflow_name}")
+class {workflow_name}:{signal_handlers}
+    @workflow.query(name={query})
+    def version(self) -> dict[str, str]:
+        """Reports the definition version a running execution was compiled from"""
+        return {{"version": DEFINITION_VERSION, "fingerprint": DEFINITION_FINGERPRINT}}
+
+    @workflow.run
+    async def run(self, input: {workflow_name}Input) -> {workflow_name}Output:
+{body}        return {workflow_name}Output(success=True, message="Workflow completed successfully")
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        query = string_literal(VERSION_QUERY),
+    )
+}
+
+fn generate_activities(steps: &[Step], workflow_name: &str) -> String {
+    let names = steps::activity_names(steps);
+    let functions: String = names.iter()
+        .map(|name| format!(r#"
+
+@activity.defn(name="{name}")
+async def {function}(input: {workflow_name}Input) -> None:
+    """{name} implements the {name} activity"""
+    # TODO: implement {name}
+"#, function = function_name(name)))
+        .collect();
+    let registered = names.iter().map(|name| function_name(name)).collect::<Vec<_>>().join(", ");
+
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+
+from temporalio import activity
+
+from workflows import {workflow_name}Input
+{functions}
+
+# Activities registered by the worker
+ACTIVITIES = [{registered}]
+"#)
+}
+
+fn generate_worker(workflow_name: &str, package_name: &str) -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+
+import asyncio
+import os
+
+from temporalio.client import Client
+from temporalio.worker import Worker
+
+from activities import ACTIVITIES
+from workflows import {workflow_name}
+
+TASK_QUEUE = "{package_name}-task-queue"
+
+
+async def main() -> None:
+    client = await Client.connect(
+        os.environ.get("TEMPORAL_ADDRESS", "localhost:7233"),
+        namespace=os.environ.get("TEMPORAL_NAMESPACE", "default"),
+    )
+    worker = Worker(client, task_queue=TASK_QUEUE, workflows=[{workflow_name}], activities=ACTIVITIES)
+    await worker.run()
+
+
+if __name__ == "__main__":
+    asyncio.run(main())
+"#)
+}
+
+fn generate_test(workflow_name: &str, package_name: &str) -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+
+from temporalio.testing import WorkflowEnvironment
+from temporalio.worker import Worker
+
+from activities import ACTIVITIES
+from workflows import {workflow_name}, {workflow_name}Input
+
+
+async def test_{package_name}_completes_successfully() -> None:
+    task_queue = "test-{package_name}"
+    async with await WorkflowEnvironment.start_time_skipping() as env:
+        async with Worker(env.client, task_queue=task_queue, workflows=[{workflow_name}], activities=ACTIVITIES):
+            result = await env.client.execute_workflow(
+                {workflow_name}.run,
+                {workflow_name}Input(),
+                id="test-{package_name}",
+                task_queue=task_queue,
+            )
+
+    assert result.success
+"#, package_name = field_name(package_name))
+}
+
+fn generate_project_files(package_name: &str, version: &str) -> Vec<GeneratedFile> {
+    let pyproject = format!(r#"[project]
+name = {name}
+version = {version}
+requires-python = ">=3.9"
+dependencies = ["temporalio>={SDK_VERSION}"]
+
+[project.optional-dependencies]
+test = ["pytest>=8.0", "pytest-asyncio>=0.23"]
+
+[tool.pytest.ini_options]
+asyncio_mode = "auto"
+"#, name = string_literal(&package_name.replace('_', "-")), version = string_literal(version));
+
+    vec![GeneratedFile { path: "pyproject.toml".to_string(), content: pyproject }]
+}
+
+/// Python backend
+pub struct Python;
+
+impl Backend for Python {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Python
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let steps = steps::workflow_steps(definition, self.target().name())?;
+        let workflow_name = to_pascal_case(&definition.name);
+
+        Ok(TargetSources {
+            workflow_code: generate_workflow(definition, &steps, &workflow_name, fingerprint),
+            activity_code: generate_activities(&steps, &workflow_name),
+            worker_code: generate_worker(&workflow_name, package_name),
+            test_code: generate_test(&workflow_name, package_name),
+            files: generate_project_files(package_name, &definition.version),
+            signals: steps::signal_names(&steps),
+            metadata: None,
+        })
+    }
+}
@@ -0,0 +1,190 @@
+//! Execution reports: a workflow-local trace of the nodes a run executed, rendered and sent
+//! through the notification service when the run ends
+//!
+//! Enabled by the `execution_report` compile option. The workflow function is wrapped so the
+//! report is sent whether the run completes or fails; delivery problems are logged and never
+//! change the workflow result.
+
+use crate::compiler::codegen::go_string_literal;
+use crate::error::CompilerError;
+use crate::ExecutionReportOptions;
+
+/// Activity delivering the report
+pub const ACTIVITY: &str = "SendExecutionReport";
+
+/// Channels accepted by the notification service
+const CHANNELS: &[&str] = &["email", "sms", "whatsapp", "push", "voice", "ussd"];
+
+/// Check the channel and recipient
+pub fn validate(options: Option<&ExecutionReportOptions>) -> Result<(), CompilerError> {
+    let Some(options) = options else { return Ok(()) };
+    if !CHANNELS.contains(&options.channel.as_str()) {
+        return Err(CompilerError::ValidationError(format!(
+            "Execution report channel '{}' must be one of {}",
+            options.channel,
+            CHANNELS.join(", ")
+        ).into()));
+    }
+    if options.recipient.trim().is_empty() {
+        return Err(CompilerError::ValidationError("Execution report recipient must not be empty".into()));
+    }
+    Ok(())
+}
+
+/// Statement opening a traced step for a node
+pub fn generate_step(node_id: &str, label: &str) -> String {
+    format!("    report.Step(ctx, {}, {})\n", go_string_literal(node_id), go_string_literal(label))
+}
+
+/// Generate the trace types and the function finishing and sending a report
+pub fn generate_trace(options: &ExecutionReportOptions) -> String {
+    let skip_success = if options.only_on_failure { "    if err == nil {\n        return\n    }\n" } else { "" };
+    format!(r#"// ExecutionReportStep is one node executed by a run
+type ExecutionReportStep struct {{
+    NodeID    string        `json:"node_id"`
+    Label     string        `json:"label"`
+    StartedAt time.Time     `json:"started_at"`
+    Duration  time.Duration `json:"duration"`
+    // Outcome is running, completed or failed
+    Outcome string `json:"outcome"`
+}}
+
+// ExecutionReport is the workflow-local trace of a run, sent by {ACTIVITY} when the run ends
+type ExecutionReport struct {{
+    Workflow   string                `json:"workflow"`
+    WorkflowID string                `json:"workflow_id"`
+    RunID      string                `json:"run_id"`
+    StartedAt  time.Time             `json:"started_at"`
+    Duration   time.Duration         `json:"duration"`
+    Outcome    string                `json:"outcome"`
+    Error      string                `json:"error,omitempty"`
+    Steps      []ExecutionReportStep `json:"steps"`
+}}
+
+func newExecutionReport(ctx workflow.Context, workflowName string) *ExecutionReport {{
+    info := workflow.GetInfo(ctx)
+    return &ExecutionReport{{
+        Workflow:   workflowName,
+        WorkflowID: info.WorkflowExecution.ID,
+        RunID:      info.WorkflowExecution.RunID,
+        StartedAt:  workflow.Now(ctx),
+    }}
+}}
+
+// Step completes the running step and starts tracing the next node
+func (r *ExecutionReport) Step(ctx workflow.Context, nodeID, label string) {{
+    r.finishStep(ctx, "completed")
+    r.Steps = append(r.Steps, ExecutionReportStep{{NodeID: nodeID, Label: label, StartedAt: workflow.Now(ctx), Outcome: "running"}})
+}}
+
+func (r *ExecutionReport) finishStep(ctx workflow.Context, outcome string) {{
+    if n := len(r.Steps); n > 0 && r.Steps[n-1].Outcome == "running" {{
+        r.Steps[n-1].Duration = workflow.Now(ctx).Sub(r.Steps[n-1].StartedAt)
+        r.Steps[n-1].Outcome = outcome
+    }}
+}}
+
+// sendExecutionReport finishes the trace with the run's result and delivers it;
+// delivery failures are logged and never change the result
+func sendExecutionReport(ctx workflow.Context, report *ExecutionReport, err error) {{
+    report.Outcome = "completed"
+    if err != nil {{
+        report.Outcome = "failed"
+        report.Error = err.Error()
+    }}
+    report.finishStep(ctx, report.Outcome)
+    report.Duration = workflow.Now(ctx).Sub(report.StartedAt)
+{skip_success}
+    // Report cancelled runs too
+    ctx, _ = workflow.NewDisconnectedContext(ctx)
+    ctx = workflow.WithActivityOptions(ctx, workflow.ActivityOptions{{
+        StartToCloseTimeout: time.Minute,
+        RetryPolicy:         &temporal.RetryPolicy{{MaximumAttempts: 3}},
+    }})
+    if err := workflow.ExecuteActivity(ctx, "{ACTIVITY}", *report).Get(ctx, nil); err != nil {{
+        workflow.GetLogger(ctx).Warn("Execution report not delivered", "error", err)
+    }}
+}}
+
+"#)
+}
+
+/// Generate the wrapper registered as the workflow, running `run{workflow_name}` under a trace
+pub fn generate_wrapper(workflow_name: &str) -> String {
+    format!(r#"// {workflow_name} is the main workflow function; it runs the workflow under an execution trace
+// and sends the execution report when the run ends
+func {workflow_name}(ctx workflow.Context, input {workflow_name}Input) (*{workflow_name}Output, error) {{
+    report := newExecutionReport(ctx, "{workflow_name}")
+    output, err := run{workflow_name}(ctx, input, report)
+    sendExecutionReport(ctx, report, err)
+    return output, err
+}}
+
+"#)
+}
+
+/// Imports the report activity adds to the activities file
+pub const ACTIVITY_IMPORTS: &[&str] = &["\"bytes\"", "\"encoding/json\"", "\"fmt\"", "\"net/http\"", "\"os\"", "\"strings\"", "\"text/template\"", "\"go.temporal.io/sdk/temporal\""];
+
+/// Default report layout, a Go text/template over ExecutionReport
+const TEMPLATE: &str = r#"{{.Workflow}} {{.Outcome}} after {{.Duration}}
+Workflow ID: {{.WorkflowID}}
+Run ID: {{.RunID}}
+{{if .Error}}Error: {{.Error}}
+{{end}}
+Steps:
+{{range .Steps}}- {{.Label}} ({{.NodeID}}): {{.Outcome}} in {{.Duration}}
+{{end}}"#;
+
+/// Generate the report template and the activity posting it to the notification service
+pub fn generate_activity(options: &ExecutionReportOptions) -> String {
+    let channel = go_string_literal(&options.channel);
+    let recipient = go_string_literal(&options.recipient);
+    format!(r#"// ExecutionReportTemplate lays out the execution report; edit it to change what ops teams receive
+const ExecutionReportTemplate = `{TEMPLATE}`
+
+// {ACTIVITY} renders a run's report and sends it through the notification service at
+// NOTIFICATION_SERVICE_URL
+func (a *Activities) {ACTIVITY}(ctx context.Context, report ExecutionReport) error {{
+    tmpl, err := template.New("execution-report").Parse(ExecutionReportTemplate)
+    if err != nil {{
+        return temporal.NewNonRetryableApplicationError("invalid execution report template", "InvalidReportTemplate", err)
+    }}
+    var body strings.Builder
+    if err := tmpl.Execute(&body, report); err != nil {{
+        return err
+    }}
+
+    payload, err := json.Marshal(map[string]any{{
+        "channel":           {channel},
+        "recipient_address": {recipient},
+        "subject":           fmt.Sprintf("%s %s", report.Workflow, report.Outcome),
+        "body":              body.String(),
+        "correlation_id":    report.WorkflowID + "/" + report.RunID,
+        "data":              report,
+    }})
+    if err != nil {{
+        return err
+    }}
+    base := os.Getenv("NOTIFICATION_SERVICE_URL")
+    if base == "" {{
+        base = "http://notification-service:8083"
+    }}
+    req, err := http.NewRequestWithContext(ctx, http.MethodPost, base+"/api/v1/notifications", bytes.NewReader(payload))
+    if err != nil {{
+        return err
+    }}
+    req.Header.Set("Content-Type", "application/json")
+    resp, err := http.DefaultClient.Do(req)
+    if err != nil {{
+        return err
+    }}
+    defer resp.Body.Close()
+    if resp.StatusCode >= 300 {{
+        return fmt.Errorf("notification service returned %s", resp.Status)
+    }}
+    return nil
+}}
+
+"#)
+}
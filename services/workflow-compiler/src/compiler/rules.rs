@@ -0,0 +1,184 @@
+//! Catalog of the validation and lint rules the compiler applies
+//!
+//! Served by `GET /api/v1/rules` so the editor and docs portal can explain diagnostics, with
+//! descriptions translated by `messages` for the request's `Accept-Language`.
+//! Keep entries in the order the checks run in `WorkflowCompiler::validate`, which collects
+//! every failed check into a `ValidationReport`, followed by the lints of `lint::LINTS` in
+//! their order there.
+
+use serde::Serialize;
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::error::CompilerError;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Compilation fails
+    Error,
+    /// Compilation succeeds and the diagnostic is reported alongside the output
+    Warning,
+    /// A change made or suggested by the compiler, e.g. on lenient ingest
+    Info,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Structure,
+    ControlFlow,
+    Signals,
+    Activities,
+    ChildWorkflows,
+    Timing,
+    Retries,
+    Compatibility,
+    Types,
+    Deprecation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Rule {
+    pub code: &'static str,
+    pub description: &'static str,
+    pub severity: Severity,
+    pub category: Category,
+    /// Whether the compiler or lenient ingest can correct the input itself
+    pub auto_fixable: bool,
+}
+
+/// Warning raised by a rule
+#[derive(Debug, Clone)]
+pub struct RuleWarning {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl RuleWarning {
+    pub fn diagnostic(&self) -> Diagnostic {
+        let severity = find(self.code).map_or(Severity::Warning, |r| r.severity);
+        Diagnostic { code: Some(self.code), severity, ..Diagnostic::new(self.message.clone()) }
+    }
+}
+
+/// Attribute the warnings a check returned to the rule with `code`
+pub fn warnings(code: &'static str, messages: Vec<String>) -> impl Iterator<Item = RuleWarning> {
+    messages.into_iter().map(move |message| RuleWarning { code, message })
+}
+
+/// Every problem validation found, so the editor can show them all at once
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// Failed checks, each attributed to its rule, in the order the checks ran
+    pub errors: Vec<CompilerError>,
+    pub warnings: Vec<RuleWarning>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Record a check's failure under the rule with `code`, returning its value on success
+    pub fn check<T>(&mut self, code: &'static str, result: Result<T, CompilerError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.errors.push(e.with_code(code));
+                None
+            }
+        }
+    }
+
+    /// Record the warnings a check returned under the rule with `code`
+    pub fn warn(&mut self, code: &'static str, messages: Vec<String>) {
+        self.warnings.extend(warnings(code, messages));
+    }
+
+    /// The warnings when nothing failed, otherwise the first error, for callers that stop at one
+    pub fn into_result(self) -> Result<Vec<RuleWarning>, CompilerError> {
+        match self.errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(self.warnings),
+        }
+    }
+}
+
+/// Rule with `code`
+pub fn find(code: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|r| r.code == code)
+}
+
+const fn rule(code: &'static str, severity: Severity, category: Category, auto_fixable: bool, description: &'static str) -> Rule {
+    Rule { code, description, severity, category, auto_fixable }
+}
+
+use Category::*;
+use Severity::*;
+
+pub const RULES: &[Rule] = &[
+    rule("plugin-node", Error, Structure, false, "Nodes of plugin types name a registered plugin and pass its validation"),
+    rule("missing-start-node", Error, Structure, false, "The workflow needs a Start node"),
+    rule("missing-end-node", Error, Structure, false, "The workflow needs an End node"),
+    rule("duplicate-node-id", Error, Structure, false, "Every node has an ID no other node uses"),
+    rule("edge-reference", Error, Structure, false, "Edges connect existing nodes, never enter Start or leave End, and have unique IDs"),
+    rule("complexity-budget", Error, Structure, false, "Node and edge counts, parallel fan-out and nesting, and estimated history events stay within the configured budgets"),
+    rule("history-size", Warning, Structure, false, "A run is estimated to record more history events than Temporal's warning threshold"),
+    rule("invalid-node-config", Error, Structure, false, "A node's config does not match the schema of its node type"),
+    rule("legacy-payload", Info, Structure, true, "Legacy field names, missing IDs or positions and numeric strings were upgraded on lenient ingest"),
+    rule("untyped-variable", Warning, Types, true, "A variable without a type was inferred from defaults, output schemas and usage, or generated as any"),
+    rule("condition-syntax", Error, ControlFlow, false, "Edge conditions follow the condition grammar of comparisons, boolean operators, variables and literals"),
+    rule("variable-reference", Error, Types, false, "Variable names are unique, and conditions and config templates only reference declared variables"),
+    rule("variable-type", Error, Types, false, "Defaults, Transform assignments and outputs, and Decision conditions match the declared variable types"),
+    rule("constant-variable", Error, Types, false, "Constant variables have a default value and no Transform assigns them"),
+    rule("variable-schema", Error, Types, false, "Variable schemas use constraints that fit the variable type, valid RE2 patterns and a default that satisfies them"),
+    rule("unused-variable", Warning, Types, false, "A variable no node or edge reads is left out of the generated input"),
+    rule("synthetic-input-pattern", Warning, Types, false, "A pattern rejects the generated test input, so the variable needs a matching default_value"),
+    rule("workflow-cycle", Error, ControlFlow, false, "Every cycle of flow edges passes through a Decision marked as a loop"),
+    rule("unconnected-node", Error, Structure, false, "Every node is reachable from Start and has a path to End"),
+    rule("unreachable-node-removed", Warning, Structure, false, "Nodes unreachable from Start are removed by dead-node elimination instead of rejected"),
+    rule("decision-branches", Error, ControlFlow, false, "Decisions have at least one conditional edge and at most one default edge"),
+    rule("decision-not-exhaustive", Warning, ControlFlow, false, "A Decision without a default edge may match none of its conditions"),
+    rule("constant-branch-pruned", Warning, ControlFlow, false, "Conditions reading only constant variables and literals are decided at compile time and the branches they rule out are removed"),
+    rule("parallel-gateway-pairing", Error, ControlFlow, false, "Every fork gateway closes at a join every branch reaches, without branches leaving the region between them"),
+    rule("parallel-join-policy", Error, ControlFlow, false, "An n_of_m join must wait for between 1 and the number of forked branches"),
+    rule("decision-table-shape", Error, ControlFlow, false, "Decision tables declare workflow-variable inputs, at least one rule and one valid unary test per input"),
+    rule("decision-table-overlap", Error, ControlFlow, false, "Overlapping rules are rejected under the unique hit policy, and under any when their outcomes differ"),
+    rule("decision-table-gap", Error, ControlFlow, false, "A decision table without default_output has a rule matching every combination of input values"),
+    rule("decision-table-routing", Error, ControlFlow, false, "Each edge leaving a decision table names one of its outcomes, and every outcome has an edge or an unlabelled else edge"),
+    rule("signal-wait", Error, Signals, false, "Multi-signal waits need distinct signal names, a string correlation variable and a valid timeout"),
+    rule("dynamic-activity-allowlist", Error, Activities, false, "Dynamic activities select via a string variable from a non-empty allowlist of Go identifiers"),
+    rule("publish-event-topic", Error, Activities, false, "PublishEvent nodes name the topic they publish to"),
+    rule("weighted-split", Error, ControlFlow, false, "Weighted split weights sum to 100 and cover each outgoing edge to an activity exactly once"),
+    rule("feature-flag", Error, ControlFlow, false, "Feature flag keys are well formed and known, contexts are string variables, and edges are labelled on or off"),
+    rule("identifier-collision", Error, Compatibility, false, "Activity names and decision table functions generated from node labels are distinct for distinct nodes"),
+    rule("identifier-sanitized", Warning, Compatibility, true, "A workflow name, node label or variable name is not a valid Go identifier as written and was renamed in the generated code"),
+    rule("session-group", Error, Activities, false, "Worker session groups contain only activities and form one contiguous run"),
+    rule("child-workflow-options", Error, ChildWorkflows, false, "Child workflows name a target and avoid contradictory close and cancellation policies"),
+    rule("child-workflow-reference", Error, ChildWorkflows, false, "Child workflows name a known workflow and map the inputs it needs"),
+    rule("namespace-policy", Error, ChildWorkflows, false, "Cross-namespace targets follow Temporal naming rules and the configured namespace policy"),
+    rule("cancellation-scope", Error, ControlFlow, false, "Scope members are activities and cancel edges connect a valid trigger to a scope"),
+    rule("nexus-target", Error, Activities, false, "Nexus operations name a valid endpoint, service and operation"),
+    rule("nondeterministic-construct", Error, Compatibility, false, "Transforms read no wall-clock time, random values or network responses, and HttpCall nodes run as activities, so workflow code replays deterministically"),
+    rule("nondeterministic-activity-input", Warning, Activities, false, "An activity input template reads the wall-clock time, random values or a network response in workflow code"),
+    rule("workflow-timeouts", Error, Timing, false, "Workflow timeouts are positive, run fits in execution, and task is at most 2m and fits in run"),
+    rule("schedule-trigger", Error, Timing, false, "Schedule triggers carry a valid cron spec and a usable catchup window"),
+    rule("trigger-config", Error, Structure, false, "Webhook triggers set a valid method and path, and event triggers name a topic or subject"),
+    rule("execution-profile", Error, Retries, false, "Nodes name an execution profile configured in the service and run an activity"),
+    rule("duration-format", Error, Timing, false, "Timer durations, retry intervals and activity timeouts are Go-style, humantime or ISO-8601 durations"),
+    rule("retry-policy", Error, Retries, false, "Retry policies have 1 to the maximum attempts, a backoff of at least 1.0 and ordered intervals"),
+    rule("non-idempotent-retry", Warning, Retries, false, "Retrying a non-idempotent operation without an idempotency_key may repeat side effects"),
+    rule("failover-regions", Error, Compatibility, false, "Failover regions have unique names, host:port addresses, allowed namespaces and complete mTLS settings"),
+    rule("outbox-unused", Warning, Compatibility, false, "The outbox option is set but no DatabaseQuery is directly followed by a PublishEvent"),
+    rule("validation-profile", Error, Compatibility, false, "Compile options name a validation profile configured in the service"),
+    rule("execution-report", Error, Compatibility, false, "Execution reports go to a notification service channel and a non-empty recipient"),
+    rule("target-support", Error, Compatibility, false, "Non-Go targets support a subset of node types and compile options; unsupported ones are rejected"),
+    rule("optimization-passes", Error, Compatibility, false, "The opt_level compile option is 0, 1 or 2 and passes names only known optimization passes"),
+    rule("sdk-feature", Error, Compatibility, false, "Features used by the workflow are supported by the targeted Temporal SDK release"),
+    rule("unpinned-dependency", Warning, Compatibility, false, "A Go module has no pinned checksum, so builds need module proxy access"),
+    rule("deprecated-construct", Warning, Deprecation, false, "A node type or config field listed in the configured deprecations is used"),
+    rule("removed-construct", Error, Deprecation, false, "A deprecated node type or config field is used whose removal version the configured dsl_version has reached"),
+    rule("activity-missing-retry", Warning, Retries, false, "An activity has no retry policy of its own or from an execution profile"),
+    rule("http-call-without-timeout", Warning, Timing, false, "An HttpCall sets no start-to-close or schedule-to-close timeout of its own or from an execution profile"),
+    rule("unlabeled-decision-branch", Info, ControlFlow, false, "An edge leaving a Decision has no label to show in the editor and generated docs"),
+];
@@ -0,0 +1,92 @@
+//! SARIF 2.1.0 rendering of diagnostics for code-review tooling
+//!
+//! `POST /api/v1/validate` and `POST /api/v1/lint` answer with a SARIF log when called with
+//! `format=sarif`, so a CI job checking definitions kept in Git can upload the findings to
+//! code scanning. Every diagnostic becomes a result on the definition file the `path`
+//! parameter names, with the node or edge at fault as its logical location and the editor
+//! position in its properties; the compiler never sees the file's text, so results carry no
+//! line region. The rules results cite are described in the request's locale.
+
+use serde_json::{json, Value};
+
+use crate::compiler::messages::{self, LocalizedDiagnostic};
+use crate::compiler::rules::{self, Rule, Severity};
+
+/// Media type of SARIF logs
+pub const MEDIA_TYPE: &str = "application/sarif+json";
+
+/// Artifact results are reported on when the request names none
+pub const DEFAULT_PATH: &str = "workflow.json";
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+fn result(diagnostic: &LocalizedDiagnostic, path: &str, cited: &mut Vec<&'static Rule>) -> Value {
+    let mut result = json!({
+        "level": level(diagnostic.severity),
+        "message": { "text": diagnostic.detail },
+    });
+    if let Some(rule) = diagnostic.code.and_then(rules::find) {
+        let index = cited.iter().position(|r| r.code == rule.code).unwrap_or_else(|| {
+            cited.push(rule);
+            cited.len() - 1
+        });
+        result["ruleId"] = json!(rule.code);
+        result["ruleIndex"] = json!(index);
+    }
+
+    let mut location = json!({ "physicalLocation": { "artifactLocation": { "uri": path } } });
+    let element = match (&diagnostic.node, &diagnostic.edge) {
+        (Some(node), _) => Some(("nodes", node)),
+        (None, Some(edge)) => Some(("edges", edge)),
+        (None, None) => None,
+    };
+    if let Some((collection, id)) = element {
+        location["logicalLocations"] = json!([{
+            "name": id,
+            "fullyQualifiedName": format!("{}/{}", collection, id),
+            "kind": "object",
+        }]);
+    }
+    result["locations"] = json!([location]);
+    if let Some(position) = &diagnostic.position {
+        result["properties"] = json!({ "position": position });
+    }
+    result
+}
+
+/// SARIF log of one run reporting `diagnostics` on the definition at `path`; `successful` is
+/// false when the run stopped before checking everything, e.g. at a compilation limit
+pub fn render(diagnostics: &[LocalizedDiagnostic], path: &str, locale: &str, successful: bool) -> Value {
+    let mut cited = vec![];
+    let results: Vec<Value> = diagnostics.iter().map(|d| result(d, path, &mut cited)).collect();
+    let rules: Vec<Value> = cited.iter()
+        .map(|rule| json!({
+            "id": rule.code,
+            "shortDescription": { "text": messages::summary(rule.code, locale).unwrap_or(rule.description) },
+            "defaultConfiguration": { "level": level(rule.severity) },
+            "properties": { "category": rule.category },
+        }))
+        .collect();
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": {
+                "name": "workflow-compiler",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            } },
+            "invocations": [{ "executionSuccessful": successful }],
+            "language": locale,
+            "results": results,
+        }],
+    })
+}
@@ -0,0 +1,136 @@
+//! Project scaffold emitted alongside the generated Go sources
+//!
+//! Layout: workflow, activity, starter and test code at the module root and the
+//! worker entrypoint under `cmd/worker`.
+
+use crate::{CiProvider, GeneratedFile};
+
+/// Path of the worker entrypoint package within the generated module
+pub const WORKER_PACKAGE: &str = "./cmd/worker";
+
+/// Host port of the local Temporal dev server frontend
+const TEMPORAL_PORT: u16 = 7233;
+
+/// Generate a Makefile with build, test and run targets plus the local dev harness
+pub fn generate_makefile(package_name: &str, workflow_name: &str) -> String {
+    format!(
+        "# Generated by OmniRoute Workflow Compiler\n\
+         .PHONY: build test bench vet run start dev-up dev-down\n\
+         \n\
+         BIN := bin/{package_name}-worker\n\
+         TEMPORAL_ADDRESS ?= localhost:{TEMPORAL_PORT}\n\
+         \n\
+         build:\n\
+         \tgo build -o $(BIN) {WORKER_PACKAGE}\n\
+         \n\
+         test:\n\
+         \tgo test ./...\n\
+         \n\
+         bench:\n\
+         \tgo test -run '^$' -bench . -benchmem ./...\n\
+         \n\
+         vet:\n\
+         \tgo vet ./...\n\
+         \n\
+         run: build\n\
+         \tTEMPORAL_ADDRESS=$(TEMPORAL_ADDRESS) ./$(BIN)\n\
+         \n\
+         start:\n\
+         \tdocker compose exec temporal temporal workflow start \\\n\
+         \t\t--task-queue {package_name}-task-queue --type {workflow_name} --input '{{}}'\n\
+         \n\
+         dev-up:\n\
+         \tdocker compose up -d --wait\n\
+         \n\
+         dev-down:\n\
+         \tdocker compose down\n"
+    )
+}
+
+/// Generate a docker-compose file running a Temporal dev server with the web UI
+pub fn generate_docker_compose() -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+services:
+  temporal:
+    image: temporalio/temporal:latest
+    command: ["server", "start-dev", "--ip", "0.0.0.0", "--namespace", "default"]
+    ports:
+      - "{TEMPORAL_PORT}:7233"
+      - "8233:8233"
+    healthcheck:
+      test: ["CMD", "temporal", "operator", "cluster", "health", "--address", "localhost:7233"]
+      interval: 5s
+      timeout: 5s
+      retries: 12
+"#)
+}
+
+/// Generate a multi-stage Dockerfile building the worker into a static image
+pub fn generate_dockerfile(go_version: &str) -> String {
+    format!(r#"# Generated by OmniRoute Workflow Compiler
+FROM golang:{go_version}-alpine AS build
+WORKDIR /src
+COPY go.mod go.sum ./
+RUN go mod download
+COPY . .
+RUN CGO_ENABLED=0 go build -o /out/worker {WORKER_PACKAGE}
+
+FROM gcr.io/distroless/static-debian12
+COPY --from=build /out/worker /worker
+ENTRYPOINT ["/worker"]
+"#)
+}
+
+/// Generate the CI pipeline for the selected provider: build, vet, test and image build
+pub fn generate_ci_pipeline(provider: CiProvider, package_name: &str, go_version: &str) -> GeneratedFile {
+    match provider {
+        CiProvider::GithubActions => GeneratedFile {
+            path: ".github/workflows/ci.yml".to_string(),
+            content: format!(r#"# Generated by OmniRoute Workflow Compiler
+name: CI
+
+on:
+  push:
+    branches: [main]
+  pull_request:
+
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: actions/setup-go@v5
+        with:
+          go-version: "{go_version}"
+      - run: go build ./...
+      - run: go vet ./...
+      - run: go test ./...
+      - run: docker build -t {package_name}-worker:${{{{ github.sha }}}} .
+"#),
+        },
+        CiProvider::Gitlab => GeneratedFile {
+            path: ".gitlab-ci.yml".to_string(),
+            content: format!(r#"# Generated by OmniRoute Workflow Compiler
+stages:
+  - test
+  - build
+
+test:
+  stage: test
+  image: golang:{go_version}
+  script:
+    - go build ./...
+    - go vet ./...
+    - go test ./...
+
+docker:
+  stage: build
+  image: docker:24
+  services:
+    - docker:24-dind
+  script:
+    - docker build -t $CI_REGISTRY_IMAGE/{package_name}-worker:$CI_COMMIT_SHA .
+"#),
+        },
+    }
+}
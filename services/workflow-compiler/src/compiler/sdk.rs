@@ -0,0 +1,170 @@
+//! Temporal Go SDK version targeting
+//!
+//! Fleets run mixed SDK versions, so codegen resolves a requested version range
+//! against a known release matrix and adapts API usage to the selected release.
+
+use std::fmt;
+
+use crate::error::CompilerError;
+use crate::{CompileOptions, NodeType, TriggerType, WorkflowDefinition};
+
+/// Semantic version triple
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u32, pub u32, pub u32);
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// A Temporal Go SDK release the compiler knows how to target
+#[derive(Debug, Clone, Copy)]
+pub struct SdkRelease {
+    pub sdk: Version,
+    /// Minimum go.temporal.io/api version required by the release
+    pub api: Version,
+    /// Minimum Go toolchain for the `go` directive
+    pub go: &'static str,
+}
+
+/// Supported releases, oldest first
+pub const SDK_MATRIX: &[SdkRelease] = &[
+    SdkRelease { sdk: Version(1, 14, 0), api: Version(1, 7, 1), go: "1.18" },
+    SdkRelease { sdk: Version(1, 17, 0), api: Version(1, 12, 0), go: "1.18" },
+    SdkRelease { sdk: Version(1, 20, 0), api: Version(1, 16, 0), go: "1.19" },
+    SdkRelease { sdk: Version(1, 23, 1), api: Version(1, 21, 0), go: "1.20" },
+    SdkRelease { sdk: Version(1, 25, 1), api: Version(1, 24, 0), go: "1.21" },
+    SdkRelease { sdk: Version(1, 26, 1), api: Version(1, 26, 1), go: "1.21" },
+    SdkRelease { sdk: Version(1, 28, 1), api: Version(1, 36, 0), go: "1.21" },
+    SdkRelease { sdk: Version(1, 29, 1), api: Version(1, 38, 0), go: "1.22" },
+    SdkRelease { sdk: Version(1, 31, 0), api: Version(1, 43, 0), go: "1.22" },
+];
+
+impl SdkRelease {
+    /// `client.Dial` replaced the deprecated `client.NewClient` in 1.15
+    pub fn supports_dial(&self) -> bool {
+        self.sdk >= Version(1, 15, 0)
+    }
+
+    /// The schedule client API shipped in 1.20
+    pub fn supports_schedules(&self) -> bool {
+        self.sdk >= Version(1, 20, 0)
+    }
+
+    /// Build ID based worker versioning shipped in 1.23
+    pub fn supports_build_ids(&self) -> bool {
+        self.sdk >= Version(1, 23, 0)
+    }
+
+    /// Nexus operations from workflows shipped in 1.28
+    pub fn supports_nexus(&self) -> bool {
+        self.sdk >= Version(1, 28, 0)
+    }
+
+    /// Client constructor for the generated worker and starter
+    pub fn client_constructor(&self) -> &'static str {
+        if self.supports_dial() {
+            "client.Dial"
+        } else {
+            "client.NewClient"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// Bare or `~` versions: same major.minor, any patch
+    Tilde,
+}
+
+fn parse_version(raw: &str) -> Option<(Version, usize)> {
+    let parts: Vec<&str> = raw.trim().trim_start_matches('v').split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let mut numbers = [0u32; 3];
+    for (i, part) in parts.iter().enumerate() {
+        numbers[i] = part.parse().ok()?;
+    }
+    Some((Version(numbers[0], numbers[1], numbers[2]), parts.len()))
+}
+
+fn parse_comparator(raw: &str) -> Option<(Op, Version, usize)> {
+    let raw = raw.trim();
+    let (op, rest) = [(">=", Op::Ge), ("<=", Op::Le), (">", Op::Gt), ("<", Op::Lt), ("=", Op::Eq), ("~", Op::Tilde)]
+        .iter()
+        .find_map(|(prefix, op)| raw.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Tilde, raw));
+    let (version, precision) = parse_version(rest)?;
+    Some((op, version, precision))
+}
+
+fn matches(op: Op, bound: Version, precision: usize, v: Version) -> bool {
+    match op {
+        Op::Eq if precision < 3 => v.0 == bound.0 && (precision < 2 || v.1 == bound.1),
+        Op::Eq => v == bound,
+        Op::Gt => v > bound,
+        Op::Ge => v >= bound,
+        Op::Lt => v < bound,
+        Op::Le => v <= bound,
+        Op::Tilde => v.0 == bound.0 && (precision < 2 || v.1 == bound.1) && v >= bound,
+    }
+}
+
+/// Resolve a version range like `">=1.20, <1.26"` to the newest matching release.
+/// Without a range the newest supported release is targeted.
+pub fn resolve(range: Option<&str>) -> Result<&'static SdkRelease, CompilerError> {
+    let latest = SDK_MATRIX.last().expect("SDK matrix is not empty");
+    let Some(range) = range.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(latest);
+    };
+
+    let comparators = range.split(',')
+        .map(|c| parse_comparator(c).ok_or_else(|| {
+            CompilerError::ValidationError(format!("Invalid Temporal SDK version range '{}'", range).into())
+        }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    SDK_MATRIX.iter()
+        .rev()
+        .find(|release| comparators.iter().all(|(op, bound, precision)| matches(*op, *bound, *precision, release.sdk)))
+        .ok_or_else(|| CompilerError::ValidationError(format!(
+            "No supported Temporal SDK release matches '{}'; supported releases are {}",
+            range,
+            SDK_MATRIX.iter().map(|r| r.sdk.to_string()).collect::<Vec<_>>().join(", ")
+        ).into()))
+}
+
+/// Reject definitions that use features the targeted release lacks
+pub fn check_features(definition: &WorkflowDefinition, options: &CompileOptions, release: &SdkRelease) -> Result<(), CompilerError> {
+    let has_schedules = definition.triggers.iter().any(|t| matches!(t.trigger_type, TriggerType::Schedule));
+    if has_schedules && !release.supports_schedules() {
+        return Err(CompilerError::ValidationError(format!(
+            "Schedule triggers require Temporal SDK 1.20 or newer, but {} is targeted",
+            release.sdk
+        ).into()));
+    }
+
+    let has_nexus = definition.nodes.iter().any(|n| matches!(n.node_type, NodeType::NexusOperation));
+    if has_nexus && !release.supports_nexus() {
+        return Err(CompilerError::ValidationError(format!(
+            "Nexus operation nodes require Temporal SDK 1.28 or newer, but {} is targeted",
+            release.sdk
+        ).into()));
+    }
+
+    if options.worker_versioning && !release.supports_build_ids() {
+        return Err(CompilerError::ValidationError(format!(
+            "Worker versioning requires Temporal SDK 1.23 or newer, but {} is targeted",
+            release.sdk
+        ).into()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,730 @@
+//! CNCF Serverless Workflow export: a specification 0.8 workflow document, as YAML in the
+//! workflow code and as JSON in `<package>.sw.json`, so definitions run on any conforming
+//! runtime
+//!
+//! Serverless Workflow is itself a state machine, so nodes map onto states one to one and
+//! loops stay transitions. Activity and DatabaseQuery nodes become operation states calling a
+//! `custom` function named after the activity, HttpCall nodes `rest:<method>:<url>` functions,
+//! PublishEvent nodes produced events and subworkflows `subFlowRef` actions. Decisions are
+//! switch states with jq data conditions, forks parallel states whose branches hold the
+//! actions up to the join, timers sleep states and signal waits event states. Every state
+//! carries its node's ID, type and config in its metadata so the document imports back into
+//! the same graph.
+//!
+//! Features the specification cannot express do not fail the compilation: they are dropped or
+//! approximated, and each is listed in the target metadata as a round-trip fidelity warning.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use serde_json::{json, Map, Value};
+
+use crate::compiler::airflow::param_type;
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{self, activity_name, is_activity_node};
+use crate::compiler::steps::{self, TargetMetadata, TargetSources, UnmappedFeature};
+use crate::compiler::{duration, limits};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::export::yaml;
+use crate::{
+    to_pascal_case, ChildCancellationType, CodegenTarget, EdgeKind, GeneratedFile, JoinPolicy, NodeType, OverlapPolicy,
+    ParallelGatewayConfig, ParentClosePolicy, PublishEventConfig, SignalWaitMode, SubWorkflowConfig, TriggerType,
+    WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig, WorkflowDefinition, WorkflowNode,
+};
+
+/// Specification version of the exported documents
+pub const SPEC_VERSION: &str = "0.8";
+
+/// Prefix of the `custom` function operation invoking a registered activity
+pub const ACTIVITY_OPERATION: &str = "activity:";
+
+pub struct ServerlessWorkflow;
+
+/// Edge condition being rewritten as a jq expression over the state data
+struct Condition<'a> {
+    definition: &'a WorkflowDefinition,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Condition<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    /// Tokens up to the end, or up to the `)` closing a group when `nested`
+    fn sequence(&mut self, nested: bool) -> Result<String, String> {
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            if c == ')' && nested {
+                return Ok(out);
+            }
+            out.push_str(&self.token()?);
+        }
+        if nested {
+            return Err("unbalanced parentheses".to_string());
+        }
+        Ok(out)
+    }
+
+    fn token(&mut self) -> Result<String, String> {
+        let Some(c) = self.next() else { return Err("unexpected end of condition".to_string()) };
+        Ok(match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match self.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(self.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                Value::String(text).to_string()
+            }
+            '&' | '|' => {
+                if self.next() != Some(c) {
+                    return Err(format!("unsupported operator '{}'", c));
+                }
+                if c == '&' { " and " } else { " or " }.to_string()
+            }
+            // jq negates with the `not` filter, so `!x` becomes `(x | not)`
+            '!' if self.peek() != Some('=') => {
+                while self.peek().is_some_and(char::is_whitespace) {
+                    self.pos += 1;
+                }
+                match self.peek() {
+                    Some(ch) if ch == '(' || ch == '!' || ch == '"' || ch == '\'' || ch.is_ascii_alphabetic() || ch == '_' => {
+                        format!(" ({} | not) ", self.token()?.trim())
+                    }
+                    _ => return Err("'!' has no operand".to_string()),
+                }
+            }
+            '(' => {
+                let inner = self.sequence(true)?;
+                self.pos += 1;
+                format!("({})", inner.trim())
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(ch) = self.peek().filter(|ch| ch.is_ascii_alphanumeric() || *ch == '_' || *ch == '.') {
+                    word.push(ch);
+                    self.pos += 1;
+                }
+                match word.as_str() {
+                    "true" | "false" | "null" => word,
+                    _ => {
+                        let name = word.strip_prefix("input.").unwrap_or(&word);
+                        let mut path = name.split('.');
+                        let head = path.next().unwrap_or_default();
+                        let variable = self.definition.variables.iter()
+                            .find(|v| v.name == head || to_pascal_case(&v.name) == head)
+                            .ok_or_else(|| format!("'{}' is not a workflow variable", head))?;
+                        let mut field = format!(".{}", variable.name);
+                        for part in path {
+                            field.push('.');
+                            field.push_str(part);
+                        }
+                        field
+                    }
+                }
+            }
+            c => c.to_string(),
+        })
+    }
+}
+
+/// jq expression for an edge condition: workflow variables become fields of the state data
+/// and `&&`, `||` and `!` become `and`, `or` and `not`
+fn jq_expression(definition: &WorkflowDefinition, condition: &str) -> Result<String, String> {
+    let mut parser = Condition { definition, chars: condition.chars().collect(), pos: 0 };
+    let expression = parser.sequence(false)?;
+    Ok(expression.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Node types exported as inject states passing the data through unchanged
+fn placeholder(node_type: &NodeType) -> Option<&'static str> {
+    Some(match node_type {
+        NodeType::Transform => "a transform",
+        NodeType::Notification => "a notification",
+        NodeType::DecisionTable => "a decision table",
+        NodeType::DynamicActivity => "dynamic activity dispatch",
+        NodeType::CancellationScope => "a cancellation scope",
+        NodeType::NexusOperation => "a Nexus operation",
+        NodeType::FeatureFlag => "a feature flag",
+        NodeType::WeightedSplit => "a weighted split",
+        _ => return None,
+    })
+}
+
+/// Snake-case name a definition enum serializes to
+fn serde_name(value: impl serde::Serialize) -> String {
+    serde_json::to_value(value).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+/// Workflow document being assembled: states in the order they are reached from the start
+struct Document<'a> {
+    definition: &'a WorkflowDefinition,
+    package_name: &'a str,
+    /// Nodes whose state is yet to be emitted
+    pending: VecDeque<&'a WorkflowNode>,
+    queued: HashSet<&'a str>,
+    states: Vec<Value>,
+    functions: BTreeMap<String, Value>,
+    events: BTreeMap<String, Value>,
+    retries: BTreeMap<String, Value>,
+    unmapped: Vec<UnmappedFeature>,
+}
+
+impl<'a> Document<'a> {
+    fn new(definition: &'a WorkflowDefinition, package_name: &'a str) -> Self {
+        Document {
+            definition,
+            package_name,
+            pending: VecDeque::new(),
+            queued: HashSet::new(),
+            states: vec![],
+            functions: BTreeMap::new(),
+            events: BTreeMap::new(),
+            retries: BTreeMap::new(),
+            unmapped: vec![],
+        }
+    }
+
+    fn unmap(&mut self, node_id: Option<&str>, feature: impl Into<String>, fallback: &str) {
+        self.unmapped.push(UnmappedFeature { node_id: node_id.map(str::to_string), feature: feature.into(), fallback: fallback.to_string() });
+    }
+
+    /// State name for a node: its label, with the node ID when the label is not unique
+    fn state_name(&self, node: &WorkflowNode) -> String {
+        let label = node.label.trim();
+        let duplicate = self.definition.nodes.iter().filter(|n| n.label.trim() == label).count() > 1;
+        if label.is_empty() || duplicate {
+            format!("{} {}", label, node.id).trim().to_string()
+        } else {
+            label.to_string()
+        }
+    }
+
+    fn flow_targets(&self, node: &WorkflowNode) -> Vec<&'a WorkflowNode> {
+        graph::outgoing_edges(self.definition, &node.id)
+            .filter(|e| e.kind == EdgeKind::Flow)
+            .filter_map(|e| graph::find_node(self.definition, &e.target))
+            .collect()
+    }
+
+    /// Whether a node is a parallel gateway closing a fork
+    fn is_join(&self, node: &WorkflowNode) -> bool {
+        matches!(node.node_type, NodeType::ParallelGateway) && self.flow_targets(node).len() <= 1
+    }
+
+    /// Single successor of a node with sequential semantics
+    fn successor(&mut self, node: &WorkflowNode) -> Option<&'a WorkflowNode> {
+        let targets = self.flow_targets(node);
+        if targets.len() > 1 {
+            self.unmap(
+                Some(&node.id),
+                format!("{} outgoing edges", targets.len()),
+                "only the first edge is a transition; the other targets are reached only through other paths",
+            );
+        }
+        targets.first().copied()
+    }
+
+    /// Node whose state `next` leads to, skipping the Start node and joins; `None` when the
+    /// path ends the workflow
+    fn resolve(&self, mut next: Option<&'a WorkflowNode>) -> Option<&'a WorkflowNode> {
+        let mut skipped = HashSet::new();
+        while let Some(node) = next {
+            let passthrough = matches!(node.node_type, NodeType::Start) || self.is_join(node);
+            if matches!(node.node_type, NodeType::End) || (passthrough && !skipped.insert(node.id.as_str())) {
+                return None;
+            }
+            if !passthrough {
+                return Some(node);
+            }
+            next = self.flow_targets(node).first().copied();
+        }
+        None
+    }
+
+    /// `transition` to the state of `next`, queueing it, or `end` when there is none
+    fn continue_to(&mut self, next: Option<&'a WorkflowNode>) -> Map<String, Value> {
+        let mut fields = Map::new();
+        match self.resolve(next) {
+            Some(node) => {
+                fields.insert("transition".into(), json!(self.state_name(node)));
+                if self.queued.insert(node.id.as_str()) {
+                    self.pending.push_back(node);
+                }
+            }
+            None => {
+                fields.insert("end".into(), json!(true));
+            }
+        }
+        fields
+    }
+
+    /// State metadata recording the node, so an import restores it
+    fn node_metadata(&self, node: &WorkflowNode) -> Value {
+        let mut metadata = Map::new();
+        metadata.insert("nodeId".into(), json!(node.id));
+        metadata.insert("nodeType".into(), json!(serde_name(&node.node_type)));
+        let empty = node.config.is_null() || node.config.as_object().is_some_and(Map::is_empty);
+        if !empty {
+            metadata.insert("config".into(), json!(node.config.to_string()));
+        }
+        metadata.insert("position".into(), json!(format!("{},{}", node.position.x, node.position.y)));
+        if let Some(session) = &node.session {
+            metadata.insert("session".into(), json!(session));
+        }
+        for (key, value) in &node.annotations {
+            metadata.insert(format!("annotation.{}", key), json!(value));
+        }
+        Value::Object(metadata)
+    }
+
+    /// Retry definition for a node's retry policy, returning its name
+    fn retry_ref(&mut self, node: &WorkflowNode) -> Result<Option<String>, CompilerError> {
+        let Some(retry) = steps::retry(node)? else { return Ok(None) };
+        let name = format!("{} retry", self.state_name(node));
+        self.retries.insert(name.clone(), json!({
+            "name": name,
+            "delay": duration::to_iso8601(retry.initial_interval),
+            "maxDelay": duration::to_iso8601(retry.max_interval),
+            "multiplier": retry.backoff_coefficient,
+            "maxAttempts": retry.max_attempts,
+        }));
+        Ok(Some(name))
+    }
+
+    fn function(&mut self, name: &str, operation: String) {
+        self.functions.insert(name.to_string(), json!({ "name": name, "type": "custom", "operation": operation }));
+    }
+
+    /// Action performing a node, or `None` for nodes that are not actions
+    fn action(&mut self, node: &WorkflowNode) -> Result<Option<Value>, CompilerError> {
+        let mut action = match node.node_type {
+            NodeType::HttpCall => {
+                let config = |key: &str| node.config.get(key).filter(|v| !v.is_null());
+                let method = config("method").and_then(Value::as_str).unwrap_or("GET").to_ascii_lowercase();
+                let url = config("url").and_then(Value::as_str).unwrap_or_default();
+                let function = activity_name(node);
+                self.function(&function, format!("rest:{}:{}", method, url));
+                let mut reference = json!({ "refName": function });
+                let arguments: Map<String, Value> = ["headers", "body"].iter()
+                    .filter_map(|key| config(key).map(|v| (key.to_string(), v.clone())))
+                    .collect();
+                if !arguments.is_empty() {
+                    reference["arguments"] = Value::Object(arguments);
+                }
+                json!({ "name": node.label, "functionRef": reference })
+            }
+            NodeType::PublishEvent => {
+                let config: PublishEventConfig = node.typed_config()?;
+                self.events.insert(config.topic.clone(), json!({
+                    "name": config.topic,
+                    "kind": "produced",
+                    "type": config.topic,
+                    "source": self.package_name,
+                }));
+                json!({ "name": node.label, "eventRef": { "produceEventRef": config.topic, "data": "${ . }" } })
+            }
+            _ if is_activity_node(node) => {
+                let function = activity_name(node);
+                self.function(&function, format!("{}{}", ACTIVITY_OPERATION, function));
+                json!({ "name": node.label, "functionRef": { "refName": function } })
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                if config.namespace.is_some() || config.task_queue.is_some() {
+                    self.unmap(Some(&node.id), "a child workflow namespace or task queue", "dropped; the runtime resolves the child by workflow ID");
+                }
+                let on_parent_complete = match config.parent_close_policy {
+                    ParentClosePolicy::Abandon => "continue",
+                    ParentClosePolicy::Terminate => "terminate",
+                    ParentClosePolicy::RequestCancel => {
+                        self.unmap(Some(&node.id), "the request_cancel parent close policy", "onParentComplete terminate");
+                        "terminate"
+                    }
+                };
+                if config.cancellation_type != ChildCancellationType::default() {
+                    let cancellation = serde_name(config.cancellation_type);
+                    self.unmap(Some(&node.id), format!("the {} child cancellation type", cancellation), "dropped; cancellation follows the runtime");
+                }
+                json!({
+                    "name": node.label,
+                    "subFlowRef": {
+                        "workflowId": config.workflow,
+                        "invoke": if config.wait_for_completion { "sync" } else { "async" },
+                        "onParentComplete": on_parent_complete,
+                    },
+                })
+            }
+            _ => return Ok(None),
+        };
+        if let Some(session) = &node.session {
+            self.unmap(Some(&node.id), format!("the worker session group '{}'", session), "dropped; the runtime schedules actions independently");
+        }
+        let retryable = action.get("functionRef").is_some() || action.get("subFlowRef").is_some();
+        match self.retry_ref(node)? {
+            Some(retry) if retryable => action["retryRef"] = json!(retry),
+            Some(_) => self.unmap(Some(&node.id), "a retry policy on an event", "dropped; producing events is not retried"),
+            None => {}
+        }
+        Ok(Some(action))
+    }
+
+    /// Consumed event for a signal, created on first use
+    fn signal_event(&mut self, signal: &str, correlation_key: Option<&str>) {
+        let event = self.events.entry(signal.to_string()).or_insert_with(|| json!({ "name": signal, "kind": "consumed", "type": signal }));
+        if let Some(key) = correlation_key {
+            event["correlation"] = json!([{ "contextAttributeName": key }]);
+        }
+    }
+
+    fn state(&mut self, node: &'a WorkflowNode) -> Result<Value, CompilerError> {
+        let mut state = match node.node_type {
+            NodeType::Decision => self.switch(node),
+            NodeType::ParallelGateway => self.parallel(node)?,
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config()?;
+                let timer = duration::parse_field(&config.duration, "duration", Some(&node.id))?;
+                let mut state = json!({ "type": "sleep", "duration": duration::to_iso8601(timer) });
+                let next = self.successor(node);
+                state.as_object_mut().expect("state is an object").extend(self.continue_to(next));
+                state
+            }
+            NodeType::WaitSignal => {
+                let config: WaitSignalConfig = node.typed_config()?;
+                self.signal_event(&config.signal, None);
+                let mut state = json!({ "type": "event", "onEvents": [{ "eventRefs": [config.signal] }] });
+                let next = self.successor(node);
+                state.as_object_mut().expect("state is an object").extend(self.continue_to(next));
+                state
+            }
+            NodeType::WaitSignals => {
+                let config: WaitSignalsConfig = node.typed_config()?;
+                for signal in &config.signals {
+                    self.signal_event(signal, config.correlation_key.as_deref());
+                }
+                let mut state = match config.mode {
+                    SignalWaitMode::All => json!({ "type": "event", "exclusive": false, "onEvents": [{ "eventRefs": config.signals }] }),
+                    SignalWaitMode::Any => {
+                        let on_events: Vec<Value> = config.signals.iter().map(|s| json!({ "eventRefs": [s] })).collect();
+                        json!({ "type": "event", "exclusive": true, "onEvents": on_events })
+                    }
+                };
+                if let Some(timeout) = &config.timeout {
+                    let timeout = duration::parse_field(timeout, "timeout", Some(&node.id))?;
+                    state["timeouts"] = json!({ "eventTimeout": duration::to_iso8601(timeout) });
+                }
+                let next = self.successor(node);
+                state.as_object_mut().expect("state is an object").extend(self.continue_to(next));
+                state
+            }
+            _ => {
+                let mut state = match self.action(node)? {
+                    Some(action) => json!({ "type": "operation", "actions": [action] }),
+                    None => {
+                        let feature = placeholder(&node.node_type).unwrap_or("a node without a state equivalent");
+                        self.unmap(Some(&node.id), feature, "inject state passing the data through unchanged");
+                        json!({ "type": "inject", "data": {} })
+                    }
+                };
+                let next = self.successor(node);
+                state.as_object_mut().expect("state is an object").extend(self.continue_to(next));
+                state
+            }
+        };
+        state["name"] = json!(self.state_name(node));
+        state["metadata"] = self.node_metadata(node);
+        Ok(state)
+    }
+
+    fn switch(&mut self, node: &'a WorkflowNode) -> Value {
+        let mut conditions = vec![];
+        let mut default = None;
+        let edges: Vec<_> = graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow).collect();
+        for edge in edges {
+            let Some(target) = graph::find_node(self.definition, &edge.target) else { continue };
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => {
+                    let expression = match jq_expression(self.definition, condition) {
+                        Ok(expression) => expression,
+                        Err(e) => {
+                            self.unmap(
+                                Some(&node.id),
+                                format!("condition '{}' on edge '{}', which has no jq equivalent: {}", condition, edge.id, e),
+                                "kept verbatim as the data condition",
+                            );
+                            condition.to_string()
+                        }
+                    };
+                    let mut data_condition = json!({ "condition": format!("${{ {} }}", expression) });
+                    if let Some(label) = &edge.label {
+                        data_condition["name"] = json!(label);
+                    }
+                    data_condition.as_object_mut().expect("condition is an object").extend(self.continue_to(Some(target)));
+                    conditions.push(data_condition);
+                }
+                None if default.is_none() => default = Some(self.continue_to(Some(target))),
+                None => self.unmap(Some(&node.id), format!("an additional edge '{}' without a condition", edge.id), "dropped; a switch has one default"),
+            }
+        }
+        let default = default.unwrap_or_else(|| self.continue_to(None));
+        json!({ "type": "switch", "dataConditions": conditions, "defaultCondition": default })
+    }
+
+    /// Parallel state for a fork: one branch per outgoing edge holding the actions up to the
+    /// join, continuing after the join
+    fn parallel(&mut self, node: &'a WorkflowNode) -> Result<Value, CompilerError> {
+        let config: ParallelGatewayConfig = node.typed_config()?;
+        let mut state = json!({ "type": "parallel" });
+        match config.join {
+            JoinPolicy::All => state["completionType"] = json!("allOf"),
+            JoinPolicy::Any | JoinPolicy::NOfM(_) => {
+                let completed = match config.join {
+                    JoinPolicy::NOfM(n) => n,
+                    _ => 1,
+                };
+                state["completionType"] = json!("atLeast");
+                state["numCompleted"] = json!(completed);
+                self.unmap(Some(&node.id), "cancelling the branches still running once the join is met", "left to the runtime");
+            }
+        }
+
+        let targets = self.flow_targets(node);
+        let join = targets.first().and_then(|first| self.find_join(first));
+        let mut branches = vec![];
+        for target in &targets {
+            let mut actions = vec![];
+            let mut seen = HashSet::new();
+            let mut next = Some(*target);
+            while let Some(member) = next {
+                if join.is_some_and(|j| j.id == member.id) || matches!(member.node_type, NodeType::End) || !seen.insert(member.id.as_str()) {
+                    break;
+                }
+                match self.action(member)? {
+                    Some(action) => actions.push(action),
+                    None => self.unmap(
+                        Some(&member.id),
+                        format!("a {} node in a parallel branch", serde_name(&member.node_type)),
+                        "dropped from the branch; branches hold only actions",
+                    ),
+                }
+                next = self.successor(member);
+            }
+            branches.push(json!({ "name": self.state_name(target), "actions": actions }));
+        }
+        state["branches"] = json!(branches);
+
+        let after = match join {
+            Some(join) => self.flow_targets(join).first().copied(),
+            None => {
+                self.unmap(Some(&node.id), "parallel branches that never join", "the workflow ends once the branches complete");
+                None
+            }
+        };
+        state.as_object_mut().expect("state is an object").extend(self.continue_to(after));
+        Ok(state)
+    }
+
+    /// First join gateway on the path from a branch's first node
+    fn find_join(&self, start: &'a WorkflowNode) -> Option<&'a WorkflowNode> {
+        let mut seen = HashSet::new();
+        let mut node = start;
+        while seen.insert(node.id.as_str()) {
+            if self.is_join(node) {
+                return Some(node);
+            }
+            node = self.flow_targets(node).first().copied()?;
+        }
+        None
+    }
+
+    /// `start`, naming the first state and carrying the first schedule trigger
+    fn start(&mut self, state: &str) -> Result<Value, CompilerError> {
+        let schedules = codegen::schedule_triggers(self.definition)?;
+        let Some((schedule, rest)) = schedules.split_first() else { return Ok(json!(state)) };
+        if schedule.overlap != OverlapPolicy::AllowAll {
+            self.unmap(None, format!("the {} schedule overlap policy", serde_name(schedule.overlap)), "every scheduled instance starts, whether or not earlier ones still run");
+        }
+        if schedule.catchup_window.is_some() {
+            self.unmap(None, "a schedule catch-up window", "dropped; missed instances are not caught up");
+        }
+        if schedule.paused {
+            self.unmap(None, "a paused schedule", "the schedule is active once the workflow is deployed");
+        }
+        for other in rest {
+            self.unmap(None, format!("an additional schedule '{}'", other.cron), "not scheduled; only the first schedule trigger starts instances");
+        }
+        Ok(json!({ "stateName": state, "schedule": { "cron": schedule.cron } }))
+    }
+
+    /// `dataInputSchema` validating the workflow variables
+    fn data_input_schema(&self) -> Option<Value> {
+        if self.definition.variables.is_empty() {
+            return None;
+        }
+        let mut properties = Map::new();
+        let mut required = vec![];
+        for variable in &self.definition.variables {
+            let mut property = Map::new();
+            let var_type = param_type(&variable.var_type);
+            if let Some(t) = var_type {
+                property.insert("type".into(), json!(t));
+            }
+            if let Some(default) = variable.default_value.as_ref().filter(|d| !d.is_null()) {
+                property.insert("default".into(), default.clone());
+            }
+            if let Some(schema) = &variable.schema {
+                if schema.required {
+                    required.push(variable.name.clone());
+                }
+                let (min_key, max_key) = if var_type == Some("array") { ("minItems", "maxItems") } else { ("minLength", "maxLength") };
+                property.extend(schema.minimum.map(|m| ("minimum".to_string(), json!(m))));
+                property.extend(schema.maximum.map(|m| ("maximum".to_string(), json!(m))));
+                property.extend(schema.min_length.map(|m| (min_key.to_string(), json!(m))));
+                property.extend(schema.max_length.map(|m| (max_key.to_string(), json!(m))));
+                property.extend(schema.pattern.as_ref().map(|p| ("pattern".to_string(), json!(p))));
+            }
+            properties.insert(variable.name.clone(), Value::Object(property));
+        }
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            schema["required"] = json!(required);
+        }
+        Some(json!({ "schema": schema, "failOnValidationErrors": true }))
+    }
+
+    /// `timeouts` for the workflow-level timeouts the specification can express
+    fn timeouts(&mut self) -> Result<Option<Value>, CompilerError> {
+        let Some(timeouts) = &self.definition.timeouts else { return Ok(None) };
+        let execution = match (&timeouts.execution, &timeouts.run) {
+            (Some(raw), run) => {
+                if run.is_some() {
+                    self.unmap(None, "a workflow run timeout", "dropped; only the execution timeout is expressible");
+                }
+                Some(duration::parse_field(raw, "timeouts.execution", None)?)
+            }
+            (None, Some(raw)) => {
+                self.unmap(None, "a workflow run timeout", "used as the execution timeout");
+                Some(duration::parse_field(raw, "timeouts.run", None)?)
+            }
+            (None, None) => None,
+        };
+        if timeouts.task.is_some() {
+            self.unmap(None, "a workflow task timeout", "dropped; the runtime has no workflow tasks");
+        }
+        Ok(execution.map(|timeout| json!({ "workflowExecTimeout": { "duration": duration::to_iso8601(timeout) } })))
+    }
+}
+
+/// Serverless Workflow document for a definition
+fn document(doc: &mut Document, fingerprint: &str) -> Result<Value, CompilerError> {
+    let definition = doc.definition;
+    let start = definition.nodes.iter()
+        .find(|n| matches!(n.node_type, NodeType::Start))
+        .ok_or_else(|| CompilerError::CodeGenError("The workflow needs a Start node".to_string()))?;
+
+    let reachable = graph::reachable_nodes(definition);
+    for node in definition.nodes.iter().filter(|n| !reachable.contains(n.id.as_str())) {
+        doc.unmap(Some(&node.id), "a node unreachable from the Start node", "not exported");
+    }
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Cancel && reachable.contains(e.source.as_str())) {
+        doc.unmap(Some(&edge.source), format!("a cancel edge to '{}'", edge.target), "dropped");
+    }
+    for trigger in &definition.triggers {
+        match trigger.trigger_type {
+            TriggerType::Webhook => doc.unmap(None, "a webhook trigger", "dropped; start instances through the runtime's API"),
+            TriggerType::Event => doc.unmap(None, "an event trigger", "dropped; start instances through the runtime's API"),
+            TriggerType::Manual | TriggerType::Schedule => {}
+        }
+    }
+
+    let first = doc.continue_to(Some(start));
+    let first = match first.get("transition").and_then(Value::as_str) {
+        Some(name) => name.to_string(),
+        None => {
+            // Start leads straight to an End: a single state ending the workflow
+            let name = doc.state_name(start);
+            doc.states.push(json!({ "name": name, "type": "inject", "data": {}, "end": true, "metadata": doc.node_metadata(start) }));
+            name
+        }
+    };
+    while let Some(node) = doc.pending.pop_front() {
+        limits::checkpoint("code generation")?;
+        let state = doc.state(node)?;
+        doc.states.push(state);
+    }
+
+    let mut workflow = json!({
+        "id": doc.package_name,
+        "name": definition.name,
+        "version": definition.version,
+        "specVersion": SPEC_VERSION,
+        "start": doc.start(&first)?,
+        "metadata": { "definitionId": definition.id.to_string(), "fingerprint": fingerprint },
+    });
+    if let Some(description) = definition.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        workflow["description"] = json!(description);
+    }
+    if let Some(timeouts) = doc.timeouts()? {
+        workflow["timeouts"] = timeouts;
+    }
+    if let Some(schema) = doc.data_input_schema() {
+        workflow["dataInputSchema"] = schema;
+    }
+    for (key, definitions) in [("functions", &doc.functions), ("events", &doc.events), ("retries", &doc.retries)] {
+        if !definitions.is_empty() {
+            workflow[key] = json!(definitions.values().collect::<Vec<_>>());
+        }
+    }
+    workflow["states"] = json!(doc.states);
+    Ok(workflow)
+}
+
+impl Backend for ServerlessWorkflow {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::ServerlessWorkflow
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SPEC_VERSION
+    }
+
+    fn queries(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn docker_compose(&self) -> String {
+        String::new()
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let mut doc = Document::new(definition, package_name);
+        let workflow = document(&mut doc, fingerprint)?;
+        let json = serde_json::to_string_pretty(&workflow).map_err(|e| CompilerError::CodeGenError(e.to_string()))?;
+        let signals = doc.events.values()
+            .filter(|e| e["kind"] == "consumed")
+            .filter_map(|e| e["name"].as_str().map(str::to_string))
+            .collect();
+
+        Ok(TargetSources {
+            workflow_code: format!("# Generated by OmniRoute Workflow Compiler\n{}", yaml::render(&workflow)),
+            activity_code: String::new(),
+            worker_code: String::new(),
+            test_code: String::new(),
+            files: vec![GeneratedFile { path: format!("{}.sw.json", package_name), content: json + "\n" }],
+            signals,
+            metadata: Some(TargetMetadata { unmapped: doc.unmapped }),
+        })
+    }
+}
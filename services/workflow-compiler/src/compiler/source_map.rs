@@ -0,0 +1,71 @@
+//! Source map from generated workflow code back to the definition's nodes
+//!
+//! Entries keep each node's label, annotations and the labels of the edges leading into it,
+//! so business-readable names survive compilation. Lines are 1-based positions in the
+//! built-in Go workflow code where a node's statements begin; nodes emitted inside a fork,
+//! scope, split or flag block, nodes that emit no code and nodes of non-Go targets have no line.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dsl::graph;
+use crate::{EdgeKind, WorkflowDefinition, WorkflowEdge, WorkflowNode};
+
+/// Labelled flow edge leading into a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncomingLabel {
+    /// Source node ID
+    pub source: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceMapping {
+    pub node_id: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub incoming: Vec<IncomingLabel>,
+    pub line: Option<usize>,
+}
+
+/// Flow edges into `node_id` with a non-empty label
+pub fn labelled_incoming<'a>(definition: &'a WorkflowDefinition, node_id: &'a str) -> impl Iterator<Item = (&'a WorkflowEdge, &'a str)> {
+    definition.edges.iter()
+        .filter(move |e| e.target == node_id && e.kind == EdgeKind::Flow)
+        .filter_map(|e| e.label.as_deref().map(str::trim).filter(|l| !l.is_empty()).map(|l| (e, l)))
+}
+
+/// One-line notes describing a node for comments and exports: its annotations as
+/// `key: value`, then `via "label" from Source` for each labelled edge leading into it
+pub fn notes(definition: &WorkflowDefinition, node: &WorkflowNode) -> Vec<String> {
+    let single_line = |s: &str| s.replace(['\n', '\r'], " ");
+    let mut notes: Vec<String> = node.annotations.iter()
+        .map(|(key, value)| single_line(&format!("{}: {}", key, value)))
+        .collect();
+    for (edge, label) in labelled_incoming(definition, &node.id) {
+        let source = graph::find_node(definition, &edge.source).map_or(edge.source.as_str(), |n| n.label.as_str());
+        notes.push(single_line(&format!("via \"{}\" from {}", label, source)));
+    }
+    notes
+}
+
+/// Source map for `code`, given the byte offset at which each mapped node's statements begin
+pub fn build(definition: &WorkflowDefinition, code: &str, node_offsets: &[(String, usize)]) -> Vec<SourceMapping> {
+    definition.nodes.iter()
+        .map(|node| SourceMapping {
+            node_id: node.id.clone(),
+            label: node.label.clone(),
+            annotations: node.annotations.clone(),
+            incoming: labelled_incoming(definition, &node.id)
+                .map(|(edge, label)| IncomingLabel { source: edge.source.clone(), label: label.to_string() })
+                .collect(),
+            line: node_offsets.iter()
+                .find(|(id, _)| *id == node.id)
+                .and_then(|(_, offset)| code.get(..*offset))
+                .map(|before| before.matches('\n').count() + 1),
+        })
+        .collect()
+}
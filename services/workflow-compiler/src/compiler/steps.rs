@@ -0,0 +1,298 @@
+//! Language-neutral workflow steps for the non-Go code generation targets
+//!
+//! The graph is walked in definition order exactly like the Go body generator, with
+//! durations parsed and configs typed up front, so each target only renders syntax.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compiler::codegen::{activity_name, is_activity_node};
+use crate::compiler::{duration, limits};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    ChildCancellationType, CodegenTarget, CompileOptions, DynamicActivityConfig, GeneratedFile, NodeType, ParentClosePolicy,
+    SignalWaitMode, SubWorkflowConfig, WaitSignalsConfig, WaitTimerConfig, WeightedSplitConfig, WorkflowDefinition,
+    WorkflowNode,
+};
+
+/// Sources emitted by a non-Go target, mapped onto the `CompiledWorkflow` code fields
+#[derive(Debug, Clone)]
+pub struct TargetSources {
+    pub workflow_code: String,
+    pub activity_code: String,
+    pub worker_code: String,
+    pub test_code: String,
+    /// Project manifests such as `package.json`
+    pub files: Vec<GeneratedFile>,
+    pub signals: Vec<String>,
+    /// Details only this target reports, carried into the compilation metadata
+    pub metadata: Option<TargetMetadata>,
+}
+
+/// Target-specific block of the compilation metadata
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TargetMetadata {
+    /// Definition features the target has no equivalent for, compiled to a fallback instead
+    pub unmapped: Vec<UnmappedFeature>,
+}
+
+/// Definition feature compiled to a fallback rather than its own semantics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmappedFeature {
+    /// Node the feature belongs to; `None` for workflow-level features such as triggers
+    pub node_id: Option<String>,
+    pub feature: String,
+    /// What the generated code does instead
+    pub fallback: String,
+}
+
+/// Reject compile options only the Go target implements; the cadence target shares those
+/// its generated code does not get from the Temporal SDK or server
+pub fn check_target_options(options: &CompileOptions) -> Result<(), CompilerError> {
+    if options.target == CodegenTarget::Go {
+        return Ok(());
+    }
+    let cadence = options.target == CodegenTarget::Cadence;
+    let go_only = [
+        ("temporal_sdk", options.temporal_sdk.is_some()),
+        ("ci", options.ci.is_some() && !cadence),
+        ("worker_versioning", options.worker_versioning || options.build_id.is_some()),
+        ("regions", !options.regions.is_empty()),
+        ("outbox", options.outbox && !cadence),
+        ("fuse_activities", options.fuse_activities && !cadence),
+        ("execution_report", options.execution_report.is_some() && !cadence),
+    ];
+    match go_only.iter().find(|(_, set)| *set) {
+        Some((option, _)) => Err(CompilerError::ValidationError(format!(
+            "Compile option '{}' is only supported by the go target, not {}",
+            option,
+            options.target.name()
+        ).into())),
+        None => Ok(()),
+    }
+}
+
+/// Retry policy with parsed intervals
+#[derive(Debug, Clone)]
+pub struct Retry {
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    pub backoff_coefficient: f64,
+    pub max_attempts: u32,
+}
+
+/// Activity timeouts with parsed durations; unset ones use the target's default options
+#[derive(Debug, Clone, Default)]
+pub struct Timeouts {
+    pub start_to_close: Option<Duration>,
+    pub schedule_to_close: Option<Duration>,
+    pub heartbeat: Option<Duration>,
+}
+
+impl Timeouts {
+    pub fn is_empty(&self) -> bool {
+        self.start_to_close.is_none() && self.schedule_to_close.is_none() && self.heartbeat.is_none()
+    }
+}
+
+/// Activity invocation shared by plain, branch and split steps
+#[derive(Debug, Clone)]
+pub struct ActivityCall {
+    pub label: String,
+    pub activity: String,
+    pub retry: Option<Retry>,
+    pub timeouts: Timeouts,
+}
+
+#[derive(Debug, Clone)]
+pub enum Step {
+    Activity(ActivityCall),
+    Timer { label: String, raw: String, duration: Duration },
+    Signals {
+        label: String,
+        signals: Vec<String>,
+        /// Signals that must arrive: all of them, or one for `any` waits
+        required: usize,
+        /// Variable each signal's correlation key must match
+        correlation_key: Option<String>,
+        timeout: Option<Duration>,
+    },
+    DynamicActivity { label: String, selector: String, allowed: Vec<String>, retry: Option<Retry>, timeouts: Timeouts },
+    Child {
+        label: String,
+        workflow: String,
+        task_queue: Option<String>,
+        parent_close_policy: ParentClosePolicy,
+        cancellation_type: ChildCancellationType,
+        wait_for_completion: bool,
+    },
+    /// Branches with the exclusive upper bound of their bucket range in [0, 100)
+    WeightedSplit { label: String, branches: Vec<(u32, ActivityCall)> },
+}
+
+/// Retry policy of a node with its intervals parsed
+pub fn retry(node: &WorkflowNode) -> Result<Option<Retry>, CompilerError> {
+    let Some(retries) = &node.retries else { return Ok(None) };
+    Ok(Some(Retry {
+        initial_interval: duration::parse_field(&retries.initial_interval, "retries.initial_interval", Some(&node.id))?,
+        max_interval: duration::parse_field(&retries.max_interval, "retries.max_interval", Some(&node.id))?,
+        backoff_coefficient: retries.backoff_coefficient,
+        max_attempts: retries.max_attempts,
+    }))
+}
+
+/// Timeouts of a node with their durations parsed
+pub fn timeouts(node: &WorkflowNode) -> Result<Timeouts, CompilerError> {
+    let Some(timeouts) = &node.timeouts else { return Ok(Timeouts::default()) };
+    let parse = |raw: &Option<String>, field: &str| raw.as_deref().map(|raw| duration::parse_field(raw, field, Some(&node.id))).transpose();
+    Ok(Timeouts {
+        start_to_close: parse(&timeouts.start_to_close, "timeouts.start_to_close")?,
+        schedule_to_close: parse(&timeouts.schedule_to_close, "timeouts.schedule_to_close")?,
+        heartbeat: parse(&timeouts.heartbeat, "timeouts.heartbeat")?,
+    })
+}
+
+fn activity_call(node: &WorkflowNode) -> Result<ActivityCall, CompilerError> {
+    Ok(ActivityCall { label: node.label.clone(), activity: activity_name(node), retry: retry(node)?, timeouts: timeouts(node)? })
+}
+
+/// Error for a node construct `target` cannot express
+pub fn unsupported(node: &WorkflowNode, what: &str, target: &str) -> CompilerError {
+    CompilerError::CodeGenError(format!("Node '{}': {} is not supported by the {} target", node.id, what, target))
+}
+
+/// Steps of the main workflow function, or an error naming the first construct `target` cannot express
+pub fn workflow_steps(definition: &WorkflowDefinition, target: &str) -> Result<Vec<Step>, CompilerError> {
+    if let Some(variable) = definition.variables.iter().find(|v| v.schema.is_some()) {
+        return Err(CompilerError::CodeGenError(format!(
+            "Variable '{}': input schemas are not supported by the {} target",
+            variable.name, target
+        )));
+    }
+
+    // Split targets run inside the split step
+    let mut branch_nodes: Vec<&str> = Vec::new();
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::WeightedSplit)) {
+        let config: WeightedSplitConfig = node.typed_config()?;
+        branch_nodes.extend(config.branches.iter().filter_map(|b| graph::find_node(definition, &b.target)).map(|n| n.id.as_str()));
+    }
+
+    let mut steps = Vec::new();
+    for node in definition.nodes.iter().filter(|n| !branch_nodes.contains(&n.id.as_str())) {
+        limits::checkpoint("code generation")?;
+        if node.session.is_some() {
+            return Err(unsupported(node, "a worker session", target));
+        }
+        let step = match node.node_type {
+            _ if is_activity_node(node) => Step::Activity(activity_call(node)?),
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config()?;
+                let duration = duration::parse_field(&config.duration, "duration", Some(&node.id))?;
+                Step::Timer { label: node.label.clone(), raw: config.duration, duration }
+            }
+            NodeType::WaitSignals => {
+                let config: WaitSignalsConfig = node.typed_config()?;
+                let timeout = match &config.timeout {
+                    Some(raw) => Some(duration::parse_field(raw, "timeout", Some(&node.id))?),
+                    None => None,
+                };
+                Step::Signals {
+                    label: node.label.clone(),
+                    required: match config.mode {
+                        SignalWaitMode::All => config.signals.len(),
+                        SignalWaitMode::Any => 1,
+                    },
+                    signals: config.signals,
+                    correlation_key: config.correlation_key,
+                    timeout,
+                }
+            }
+            NodeType::DynamicActivity => {
+                let config: DynamicActivityConfig = node.typed_config()?;
+                Step::DynamicActivity {
+                    label: node.label.clone(),
+                    selector: config.selector,
+                    allowed: config.allowed,
+                    retry: retry(node)?,
+                    timeouts: timeouts(node)?,
+                }
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                if config.namespace.is_some() {
+                    return Err(unsupported(node, "a cross-namespace child workflow", target));
+                }
+                if !config.input.is_empty() {
+                    return Err(unsupported(node, "a child workflow input mapping", target));
+                }
+                Step::Child {
+                    label: node.label.clone(),
+                    workflow: config.workflow,
+                    task_queue: config.task_queue,
+                    parent_close_policy: config.parent_close_policy,
+                    cancellation_type: config.cancellation_type,
+                    wait_for_completion: config.wait_for_completion,
+                }
+            }
+            NodeType::WeightedSplit => {
+                let config: WeightedSplitConfig = node.typed_config()?;
+                let mut upper = 0;
+                let mut branches = Vec::new();
+                for branch in &config.branches {
+                    let target_node = graph::find_node(definition, &branch.target).ok_or_else(|| {
+                        CompilerError::CodeGenError(format!("WeightedSplit node '{}' target '{}' not found", node.id, branch.target))
+                    })?;
+                    upper += branch.weight;
+                    branches.push((upper, activity_call(target_node)?));
+                }
+                Step::WeightedSplit { label: node.label.clone(), branches }
+            }
+            NodeType::ParallelGateway if graph::outgoing_edges(definition, &node.id).count() > 1 => {
+                return Err(unsupported(node, "a parallel fork", target));
+            }
+            NodeType::DecisionTable => return Err(unsupported(node, "a decision table", target)),
+            NodeType::CancellationScope => return Err(unsupported(node, "a cancellation scope", target)),
+            NodeType::NexusOperation => return Err(unsupported(node, "a Nexus operation", target)),
+            NodeType::FeatureFlag => return Err(unsupported(node, "a feature flag", target)),
+            _ => continue,
+        };
+        steps.push(step);
+    }
+
+    Ok(steps)
+}
+
+/// Names of every signal the steps wait for, sorted and deduplicated
+pub fn signal_names(steps: &[Step]) -> Vec<String> {
+    let mut names: Vec<String> = steps.iter()
+        .filter_map(|s| match s {
+            Step::Signals { signals, .. } => Some(signals.clone()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Activities the steps invoke, in first-use order, including dynamic dispatch targets
+pub fn activity_names(steps: &[Step]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut add = |name: &String| {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    };
+    for step in steps {
+        match step {
+            Step::Activity(call) => add(&call.activity),
+            Step::DynamicActivity { allowed, .. } => allowed.iter().for_each(&mut add),
+            Step::WeightedSplit { branches, .. } => branches.iter().for_each(|(_, call)| add(&call.activity)),
+            _ => {}
+        }
+    }
+    names
+}
@@ -0,0 +1,30 @@
+//! Validation profiles: how strictly a definition is checked beyond the rules every
+//! definition passes
+//!
+//! Compile options name a profile from the service config's `validation_profiles`, else the
+//! configured `default_validation_profile` applies. A profile can run lint rules as part of
+//! validation, drop the findings of advisory warning rules, and fail validation on any
+//! warning that remains. Lints run only on definitions that passed every other check, after
+//! plugins are lowered and execution profiles applied, as they do for `POST /api/v1/lint`.
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::rules::{Severity, ValidationReport};
+use crate::config::ValidationProfile;
+use crate::error::CompilerError;
+
+/// Add the profile's lint findings to a report, drop the warnings it ignores and, for a
+/// profile with `warnings_as_errors`, turn the remaining warnings into errors
+pub fn apply(report: &mut ValidationReport, profile: &ValidationProfile, findings: Vec<Diagnostic>) {
+    for finding in findings {
+        let Some(code) = finding.code else { continue };
+        match finding.severity {
+            Severity::Error => report.errors.push(CompilerError::ValidationError(finding).with_code(code)),
+            Severity::Warning | Severity::Info => report.warn(code, vec![finding.message]),
+        }
+    }
+    report.warnings.retain(|w| !profile.ignore.iter().any(|code| code == w.code));
+    if profile.warnings_as_errors {
+        let warnings = std::mem::take(&mut report.warnings);
+        report.errors.extend(warnings.into_iter().map(|w| CompilerError::ValidationError(w.message.into()).with_code(w.code)));
+    }
+}
@@ -0,0 +1,238 @@
+//! Generated Go tests and benchmarks for compiled workflows
+
+use serde_json::Value;
+
+use crate::compiler::codegen::{activity_name, activity_names, failure_is_tolerated, go_string_literal, go_type, is_activity_node, is_decision_branch};
+use crate::{to_pascal_case, Variable, WorkflowDefinition};
+
+/// Mock expectations for activities that cannot run against the test environment, such as
+/// outbox activities that need a database; `except` is left unmocked
+pub fn generate_activity_mocks(mocked: &[String], except: &str) -> String {
+    mocked.iter()
+        .filter(|name| *name != except)
+        .map(|name| format!("\n    env.OnActivity(\"{name}\", mock.Anything, mock.Anything).Return(nil)"))
+        .collect()
+}
+
+/// Name of the generated helper building a synthetic workflow input
+pub fn synthetic_input_function(workflow_name: &str) -> String {
+    format!("synthetic{workflow_name}Input")
+}
+
+/// Render a JSON value as an untyped Go literal for `any`-typed positions
+pub fn go_any_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "nil".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => go_string_literal(s),
+        Value::Array(items) => format!(
+            "[]any{{{}}}",
+            items.iter().map(go_any_literal).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(fields) => format!(
+            "map[string]any{{{}}}",
+            fields.iter()
+                .map(|(k, v)| format!("{}: {}", go_string_literal(k), go_any_literal(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Go literal for a variable: its default when it fits the declared type, otherwise a synthetic value
+fn synthetic_value(variable: &Variable) -> String {
+    let go_type = go_type(&variable.var_type);
+    let fits = |value: &Value| match go_type {
+        "string" => value.is_string(),
+        "int64" => value.is_i64(),
+        "float64" => value.is_number(),
+        "bool" => value.is_boolean(),
+        "map[string]any" => value.is_object(),
+        "[]any" => value.is_array(),
+        _ => true,
+    };
+    if let Some(default) = variable.default_value.as_ref().filter(|v| fits(v)) {
+        return go_any_literal(default);
+    }
+
+    let schema = variable.schema.clone().unwrap_or_default();
+    // A bounded number takes its lower bound, or its upper one when only that is set
+    let bound = schema.minimum.or(schema.maximum);
+    match go_type {
+        "string" => go_string_literal(&synthetic_string(variable)),
+        "int64" => bound.map_or_else(|| "42".to_string(), |b| (b as i64).to_string()),
+        "float64" => bound.map_or_else(|| "3.14".to_string(), |b| b.to_string()),
+        "bool" => "true".to_string(),
+        "map[string]any" => r#"map[string]any{"key": "value"}"#.to_string(),
+        "[]any" => {
+            let items = 1.max(schema.min_length.unwrap_or(0)).min(schema.max_length.unwrap_or(usize::MAX));
+            format!("[]any{{{}}}", vec!["\"item\""; items].join(", "))
+        }
+        _ => go_string_literal("synthetic"),
+    }
+}
+
+/// Placeholder string for a variable without a usable default, fitted to its length bounds
+pub fn synthetic_string(variable: &Variable) -> String {
+    let schema = variable.schema.clone().unwrap_or_default();
+    let mut value = format!("synthetic-{}", variable.name);
+    let min = schema.min_length.unwrap_or(0);
+    while value.chars().count() < min {
+        value.push('x');
+    }
+    match schema.max_length {
+        Some(max) => value.chars().take(max).collect(),
+        None => value,
+    }
+}
+
+/// Generate the helper building a representative input from the workflow variable schema
+pub fn generate_synthetic_input(definition: &WorkflowDefinition, workflow_name: &str) -> String {
+    let function = synthetic_input_function(workflow_name);
+    let fields: String = definition.variables.iter()
+        .map(|v| format!("        {}: {},\n", to_pascal_case(&v.name), synthetic_value(v)))
+        .collect();
+
+    format!(r#"// {function} builds a representative input from the workflow variable schema
+func {function}() {workflow_name}Input {{
+    return {workflow_name}Input{{
+{fields}    }}
+}}
+"#)
+}
+
+/// Generate one Go benchmark per activity, each driven by the synthetic input
+/// Activities in `skip` need external systems and are not benchmarked
+pub fn generate_benchmarks(definition: &WorkflowDefinition, workflow_name: &str, package_name: &str, skip: &[String]) -> String {
+    let synthetic_input = generate_synthetic_input(definition, workflow_name);
+    let mut activities = activity_names(definition);
+    activities.retain(|a| !skip.contains(a));
+    if activities.is_empty() {
+        return format!("// Generated by OmniRoute Workflow Compiler\npackage {package_name}\n\n{synthetic_input}");
+    }
+
+    let function = synthetic_input_function(workflow_name);
+    let benchmarks: String = activities.iter()
+        .map(|activity| format!(r#"
+func Benchmark{activity}(b *testing.B) {{
+    testSuite := &testsuite.WorkflowTestSuite{{}}
+    env := testSuite.NewTestActivityEnvironment()
+    activities := NewActivities()
+    env.RegisterActivity(activities)
+    input := {function}()
+
+    b.ResetTimer()
+    for i := 0; i < b.N; i++ {{
+        if _, err := env.ExecuteActivity(activities.{activity}, input); err != nil {{
+            b.Fatal(err)
+        }}
+    }}
+}}
+"#))
+        .collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+package {package_name}
+
+import (
+    "testing"
+    "go.temporal.io/sdk/testsuite"
+)
+
+{synthetic_input}{benchmarks}"#)
+}
+
+/// Fault injected into one activity by a failure-injection test
+struct Fault {
+    case: &'static str,
+    description: &'static str,
+    /// Mock expectations; the activity name and argument matchers are prepended
+    returns: &'static str,
+    /// Whether the fault is cleared by a retry
+    transient: bool,
+}
+
+const FAULTS: &[Fault] = &[
+    Fault {
+        case: "Failure",
+        description: "fails permanently",
+        returns: r#".Return(temporal.NewNonRetryableApplicationError("injected failure", "InjectedFailure", nil))"#,
+        transient: false,
+    },
+    Fault {
+        case: "Timeout",
+        description: "times out once",
+        returns: ".Return(temporal.NewTimeoutError(enumspb.TIMEOUT_TYPE_START_TO_CLOSE, nil)).Once()",
+        transient: true,
+    },
+    Fault {
+        case: "WorkerRestart",
+        description: "loses its worker mid-attempt once",
+        returns: r#".Return(temporal.NewTimeoutError(enumspb.TIMEOUT_TYPE_HEARTBEAT, errors.New("worker restarted"))).Once()"#,
+        transient: true,
+    },
+];
+
+/// Generate chaos-style tests injecting failures, timeouts and worker loss into each activity
+/// node, asserting the workflow outcome its retry policy and join or scope semantics model;
+/// activities in `mocked` succeed unless they are the one under test
+pub fn generate_failure_tests(definition: &WorkflowDefinition, workflow_name: &str, package_name: &str, mocked: &[String]) -> String {
+    let function = synthetic_input_function(workflow_name);
+    let mut tests = String::new();
+    // Whether a Decision branch runs depends on the input, so the outcome cannot be asserted
+    for node in definition.nodes.iter().filter(|n| is_activity_node(n) && !is_decision_branch(definition, n)) {
+        let activity = activity_name(node);
+        let tolerated = failure_is_tolerated(definition, node);
+        let retryable = node.retries.as_ref().is_none_or(|r| r.max_attempts > 1);
+
+        for fault in FAULTS {
+            let survives = tolerated || (fault.transient && retryable);
+            let (assertion, outcome) = if survives {
+                ("require.NoError", "completes")
+            } else {
+                ("require.Error", "fails")
+            };
+            let mocks = generate_activity_mocks(mocked, &activity);
+            let recovery = if fault.transient {
+                format!("\n    env.OnActivity(\"{activity}\", mock.Anything, mock.Anything).Return(nil)")
+            } else {
+                String::new()
+            };
+
+            tests.push_str(&format!(r#"
+// {workflow_name} {outcome} when {label} {description}
+func Test{workflow_name}_{activity}_{case}(t *testing.T) {{
+    testSuite := &testsuite.WorkflowTestSuite{{}}
+    env := testSuite.NewTestWorkflowEnvironment()
+    env.RegisterWorkflow({workflow_name})
+    env.RegisterActivity(NewActivities())
+    env.OnActivity("{activity}", mock.Anything, mock.Anything){returns}{recovery}{mocks}
+
+    env.ExecuteWorkflow({workflow_name}, {function}())
+
+    require.True(t, env.IsWorkflowCompleted())
+    {assertion}(t, env.GetWorkflowError())
+}}
+"#, label = node.label, description = fault.description, case = fault.case, returns = fault.returns));
+        }
+    }
+
+    if tests.is_empty() {
+        return format!("// Generated by OmniRoute Workflow Compiler\npackage {package_name}\n");
+    }
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+package {package_name}
+
+import (
+    "errors"
+    "testing"
+    "github.com/stretchr/testify/mock"
+    "github.com/stretchr/testify/require"
+    enumspb "go.temporal.io/api/enums/v1"
+    "go.temporal.io/sdk/temporal"
+    "go.temporal.io/sdk/testsuite"
+)
+{tests}"#)
+}
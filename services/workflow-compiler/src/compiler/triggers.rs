@@ -0,0 +1,224 @@
+//! Trigger configs checked against the shape their trigger type expects
+//!
+//! Schedule cron expressions are parsed the way Temporal schedules read them: five fields
+//! (minute, hour, day of month, month, day of week), six with a trailing year, or seven
+//! with a leading second; an optional `CRON_TZ=` prefix; or one of the `@daily`-style
+//! descriptors and `@every <duration>`. Webhook triggers need a path when not imported,
+//! and the method and path they set must be well formed. Event triggers name the topic,
+//! subject or event they listen to, and topics and subjects contain no whitespace.
+//! Imported triggers recording the platform they came from in `source` may leave the path
+//! or name out until they are wired up by hand.
+
+use serde_json::Value;
+
+use crate::compiler::duration;
+use crate::error::CompilerError;
+use crate::{TriggerType, WorkflowDefinition};
+
+const DESCRIPTORS: &[&str] = &["@yearly", "@annually", "@monthly", "@weekly", "@daily", "@midnight", "@hourly"];
+
+const MONTHS: &[&str] = &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+const WEEKDAYS: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+const METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// Keys an event trigger may name what it listens to with; `message` and `signal` come from BPMN
+const EVENT_KEYS: &[&str] = &["topic", "subject", "event", "message", "signal"];
+
+/// Keys naming a broker topic or subject, which cannot contain whitespace
+const CHANNEL_KEYS: &[&str] = &["topic", "subject"];
+
+/// One cron field: its name, value range and the names it accepts for values from `min` up
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const SECOND: Field = Field { name: "second", min: 0, max: 59, names: &[] };
+const MINUTE: Field = Field { name: "minute", min: 0, max: 59, names: &[] };
+const HOUR: Field = Field { name: "hour", min: 0, max: 23, names: &[] };
+const DAY_OF_MONTH: Field = Field { name: "day of month", min: 1, max: 31, names: &[] };
+const MONTH: Field = Field { name: "month", min: 1, max: 12, names: MONTHS };
+const DAY_OF_WEEK: Field = Field { name: "day of week", min: 0, max: 7, names: WEEKDAYS };
+const YEAR: Field = Field { name: "year", min: 1970, max: 2999, names: &[] };
+
+impl Field {
+    fn value(&self, raw: &str) -> Result<u32, String> {
+        let value = match self.names.iter().position(|n| n.eq_ignore_ascii_case(raw)) {
+            Some(index) => self.min + index as u32,
+            None => raw.parse().map_err(|_| format!("'{}' is not a valid {}", raw, self.name))?,
+        };
+        if value < self.min || value > self.max {
+            return Err(format!("{} {} is outside {}-{}", self.name, value, self.min, self.max));
+        }
+        Ok(value)
+    }
+
+    /// Check a comma-separated list of `*`, values and ranges, each with an optional `/step`
+    fn check(&self, field: &str) -> Result<(), String> {
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            if let Some(step) = step {
+                if !step.parse::<u32>().is_ok_and(|s| s > 0) {
+                    return Err(format!("step '{}' in {} field is not a positive number", step, self.name));
+                }
+            }
+            if range == "*" || (range == "?" && matches!(self.name, "day of month" | "day of week")) {
+                continue;
+            }
+            match range.split_once('-') {
+                Some((low, high)) => {
+                    let (low, high) = (self.value(low)?, self.value(high)?);
+                    if low > high {
+                        return Err(format!("{} range '{}' runs backwards", self.name, range));
+                    }
+                }
+                None => {
+                    self.value(range)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check a cron expression parses, describing the first problem found
+pub fn check_cron(expression: &str) -> Result<(), String> {
+    let mut spec = expression.trim();
+    if let Some(rest) = spec.strip_prefix("CRON_TZ=").or_else(|| spec.strip_prefix("TZ=")) {
+        let (zone, rest) = rest.split_once(char::is_whitespace).ok_or("time zone prefix is not followed by a schedule")?;
+        if zone.is_empty() {
+            return Err("time zone prefix names no time zone".to_string());
+        }
+        spec = rest.trim_start();
+    }
+    if let Some(interval) = spec.strip_prefix("@every") {
+        return duration::parse_duration(interval.trim()).map(|_| ()).map_err(|e| format!("@every interval is invalid: {}", e));
+    }
+    if spec.starts_with('@') {
+        return match DESCRIPTORS.contains(&spec) {
+            true => Ok(()),
+            false => Err(format!("unknown descriptor '{}'; descriptors are {} and @every", spec, DESCRIPTORS.join(", "))),
+        };
+    }
+    let fields: Vec<&str> = spec.split_whitespace().collect();
+    let layout: &[&Field] = match fields.len() {
+        5 => &[&MINUTE, &HOUR, &DAY_OF_MONTH, &MONTH, &DAY_OF_WEEK],
+        6 => &[&MINUTE, &HOUR, &DAY_OF_MONTH, &MONTH, &DAY_OF_WEEK, &YEAR],
+        7 => &[&SECOND, &MINUTE, &HOUR, &DAY_OF_MONTH, &MONTH, &DAY_OF_WEEK, &YEAR],
+        n => return Err(format!("expected 5, 6 or 7 fields but found {}", n)),
+    };
+    fields.iter().zip(layout).try_for_each(|(value, field)| field.check(value))
+}
+
+/// Check a webhook path: non-empty, without whitespace, query string, fragment or empty segments
+fn check_path(path: &str) -> Result<(), String> {
+    let trimmed = path.strip_prefix('/').unwrap_or(path);
+    if trimmed.is_empty() {
+        Err("path is empty".to_string())
+    } else if let Some(c) = path.chars().find(|c| c.is_whitespace() || matches!(c, '?' | '#')) {
+        Err(format!("path '{}' contains '{}'", path, c.escape_default()))
+    } else if trimmed.split('/').any(str::is_empty) {
+        Err(format!("path '{}' contains an empty segment", path))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_webhook(config: &Value) -> Result<(), String> {
+    match config.get("method") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(method)) if METHODS.iter().any(|m| m.eq_ignore_ascii_case(method)) => {}
+        Some(method) => return Err(format!("method {} is not one of {}", method, METHODS.join(", "))),
+    }
+    match config.get("path") {
+        None | Some(Value::Null) if config.get("source").is_some() => Ok(()),
+        None | Some(Value::Null) => Err("sets no path".to_string()),
+        Some(Value::String(path)) => check_path(path),
+        Some(path) => Err(format!("path {} is not a string", path)),
+    }
+}
+
+fn check_event(config: &Value) -> Result<(), String> {
+    let named: Vec<(&str, &Value)> = EVENT_KEYS.iter().filter_map(|k| config.get(*k).map(|v| (*k, v))).collect();
+    if named.is_empty() {
+        return match config.get("source") {
+            Some(_) => Ok(()),
+            None => Err(format!("names no {} to listen to", EVENT_KEYS.join(" or "))),
+        };
+    }
+    for (key, value) in named {
+        match value.as_str() {
+            Some(name) if name.contains(char::is_whitespace) && CHANNEL_KEYS.contains(&key) => {
+                return Err(format!("{} '{}' contains whitespace", key, name));
+            }
+            Some(name) if !name.trim().is_empty() => {}
+            _ => return Err(format!("{} is not a non-empty string", key)),
+        }
+    }
+    Ok(())
+}
+
+/// Check the configs of webhook and event triggers
+pub fn validate(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for (index, trigger) in definition.triggers.iter().enumerate() {
+        let (kind, result) = match trigger.trigger_type {
+            TriggerType::Webhook => ("Webhook", check_webhook(&trigger.config)),
+            TriggerType::Event => ("Event", check_event(&trigger.config)),
+            TriggerType::Manual | TriggerType::Schedule => continue,
+        };
+        if let Err(problem) = result {
+            return Err(CompilerError::ValidationError(format!("{} trigger {} is invalid: {}", kind, index, problem).into()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_temporal_cron_spellings() {
+        let valid = [
+            "*/15 * * * *",
+            "0 9 * * MON-FRI",
+            "30 2 1,15 jan-jun ?",
+            "0 0 * * 7 2030",
+            "0 30 6 * * 1-5 *",
+            "CRON_TZ=Europe/Paris 0 8 * * *",
+            "TZ=UTC @daily",
+            "@every 1h30m",
+            "@annually",
+        ];
+        for expression in valid {
+            assert_eq!(check_cron(expression), Ok(()), "{expression}");
+        }
+    }
+
+    #[test]
+    fn describes_the_first_problem() {
+        let cases = [
+            ("* * * *", "expected 5, 6 or 7 fields but found 4"),
+            ("60 * * * *", "minute 60 is outside 0-59"),
+            ("0 0 0 * *", "day of month 0 is outside 1-31"),
+            ("0 0 * foo *", "'foo' is not a valid month"),
+            ("0 17-9 * * *", "hour range '17-9' runs backwards"),
+            ("*/0 * * * *", "step '0' in minute field is not a positive number"),
+            ("? * * * *", "'?' is not a valid minute"),
+            ("0 0 * * * 1969", "year 1969 is outside 1970-2999"),
+            ("CRON_TZ=Europe/Paris", "time zone prefix is not followed by a schedule"),
+            ("@fortnightly", "unknown descriptor '@fortnightly'; descriptors are @yearly, @annually, @monthly, @weekly, @daily, @midnight, @hourly and @every"),
+            ("@every soon", "@every interval is invalid: expected a number at 'soon'"),
+        ];
+        for (expression, error) in cases {
+            assert_eq!(check_cron(expression).unwrap_err(), error, "{expression}");
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Static types of workflow variables
+//!
+//! A `var_type` names a scalar (`string`, `int`, `float`, `bool`), an untyped `object` or
+//! `array`, a typed array such as `array<int>` or a typed object such as
+//! `{id: string, lines: array<{sku: string, qty: int}>}`. Empty, `auto` and `any` leave a
+//! variable unchecked. Default values, Transform assignments and output schemas, and the
+//! conditions on Decision edges are checked against the declared types.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::dsl::condition::{self, Expr, Literal};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, NodeType, TransformConfig, Variable, WorkflowDefinition};
+
+/// Type of a variable, a field or an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarType {
+    String,
+    Int,
+    Float,
+    Bool,
+    /// Object, with its field types when declared as a typed object
+    Object(Option<BTreeMap<String, VarType>>),
+    /// Array, with its item type when declared as a typed array
+    Array(Option<Box<VarType>>),
+    /// Undeclared, inferred later or not statically known
+    Any,
+}
+
+impl VarType {
+    /// Parse a declared `var_type`
+    pub fn parse(var_type: &str) -> Result<Self, String> {
+        let mut parser = TypeParser { text: var_type, pos: 0 };
+        let parsed = parser.parse()?;
+        parser.skip_whitespace();
+        match parser.pos < var_type.len() {
+            true => Err(format!("unexpected '{}' at offset {}", &var_type[parser.pos..], parser.pos)),
+            false => Ok(parsed),
+        }
+    }
+
+    /// Type of a JSON value; null is compatible with every type
+    pub fn of_value(value: &Value) -> Self {
+        match value {
+            Value::Null => VarType::Any,
+            Value::Bool(_) => VarType::Bool,
+            Value::Number(n) if n.is_i64() || n.is_u64() => VarType::Int,
+            Value::Number(_) => VarType::Float,
+            Value::String(_) => VarType::String,
+            Value::Array(items) => {
+                let mut item = items.iter().map(VarType::of_value).filter(|t| *t != VarType::Any);
+                let first = item.next();
+                match first {
+                    Some(first) if item.all(|t| t == first) => VarType::Array(Some(Box::new(first))),
+                    _ => VarType::Array(None),
+                }
+            }
+            Value::Object(fields) => VarType::Object(Some(fields.iter().map(|(k, v)| (k.clone(), VarType::of_value(v))).collect())),
+        }
+    }
+
+    /// Type described by a JSON Schema, `Any` when it has no usable `type`
+    pub fn of_schema(schema: &Value) -> Self {
+        match schema.get("type").and_then(Value::as_str) {
+            Some("string") => VarType::String,
+            Some("integer") => VarType::Int,
+            Some("number") => VarType::Float,
+            Some("boolean") => VarType::Bool,
+            Some("array") => VarType::Array(schema.get("items").map(|i| Box::new(VarType::of_schema(i)))),
+            Some("object") => VarType::Object(schema.get("properties").and_then(Value::as_object).map(|properties| {
+                properties.iter().map(|(k, v)| (k.clone(), VarType::of_schema(v))).collect()
+            })),
+            _ => VarType::Any,
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, VarType::Int | VarType::Float)
+    }
+
+    /// Whether a value of type `value` can be stored in a variable of this type; integers
+    /// widen to floats, and untyped objects and arrays match any fields or items
+    pub fn accepts(&self, value: &VarType) -> bool {
+        match (self, value) {
+            (VarType::Any, _) | (_, VarType::Any) | (VarType::Float, VarType::Int) => true,
+            (VarType::Object(Some(fields)), VarType::Object(Some(values))) => {
+                values.iter().all(|(name, value)| fields.get(name).is_some_and(|field| field.accepts(value)))
+            }
+            (VarType::Object(_), VarType::Object(_)) => true,
+            (VarType::Array(Some(item)), VarType::Array(Some(value))) => item.accepts(value),
+            (VarType::Array(_), VarType::Array(_)) => true,
+            (a, b) => a == b,
+        }
+    }
+
+    /// Whether values of the two types can be compared with `==` and `!=`
+    fn comparable(&self, other: &VarType) -> bool {
+        self.accepts(other) || other.accepts(self)
+    }
+
+    /// Whether values of the two types can be ordered with `<`, `<=`, `>` and `>=`
+    fn orderable(&self, other: &VarType) -> bool {
+        match (self, other) {
+            (VarType::Any, t) | (t, VarType::Any) => t.is_numeric() || *t == VarType::String || *t == VarType::Any,
+            (a, b) => (a.is_numeric() && b.is_numeric()) || (*a == VarType::String && *b == VarType::String),
+        }
+    }
+}
+
+impl fmt::Display for VarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarType::String => write!(f, "string"),
+            VarType::Int => write!(f, "int"),
+            VarType::Float => write!(f, "float"),
+            VarType::Bool => write!(f, "bool"),
+            VarType::Object(None) => write!(f, "object"),
+            VarType::Object(Some(fields)) => {
+                let fields: Vec<String> = fields.iter().map(|(name, t)| format!("{}: {}", name, t)).collect();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            VarType::Array(None) => write!(f, "array"),
+            VarType::Array(Some(item)) => write!(f, "array<{}>", item),
+            VarType::Any => write!(f, "any"),
+        }
+    }
+}
+
+/// The plain type name of a declared type, mapping typed arrays to `array` and typed objects
+/// to `object`, for generators that only distinguish the base types
+pub fn base_type(var_type: &str) -> &str {
+    let var_type = var_type.trim();
+    if var_type.starts_with("array<") {
+        "array"
+    } else if var_type.starts_with('{') {
+        "object"
+    } else {
+        var_type
+    }
+}
+
+/// Recursive-descent parser over a `var_type`
+struct TypeParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> TypeParser<'a> {
+    fn skip_whitespace(&mut self) {
+        self.pos = self.text.len() - self.text[self.pos..].trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        let matched = self.text[self.pos..].starts_with(c);
+        if matched {
+            self.pos += c.len_utf8();
+        }
+        matched
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.eat(c) {
+            true => Ok(()),
+            false => Err(format!("expected '{}' at offset {}", c, self.pos)),
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.text[self.pos..];
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn parse(&mut self) -> Result<VarType, String> {
+        if self.eat('{') {
+            let mut fields = BTreeMap::new();
+            if !self.eat('}') {
+                loop {
+                    let name = self.word().to_string();
+                    if name.is_empty() {
+                        return Err(format!("expected a field name at offset {}", self.pos));
+                    }
+                    self.expect(':')?;
+                    let field = self.parse()?;
+                    if fields.insert(name.clone(), field).is_some() {
+                        return Err(format!("field '{}' is declared twice", name));
+                    }
+                    if self.eat('}') {
+                        break;
+                    }
+                    self.expect(',')?;
+                }
+            }
+            return Ok(VarType::Object(Some(fields)));
+        }
+        Ok(match self.word() {
+            "" | "auto" | "any" => VarType::Any,
+            "string" => VarType::String,
+            "int" | "integer" => VarType::Int,
+            "float" | "number" => VarType::Float,
+            "bool" | "boolean" => VarType::Bool,
+            "object" => VarType::Object(None),
+            "array" if self.eat('<') => {
+                let item = self.parse()?;
+                self.expect('>')?;
+                VarType::Array(Some(Box::new(item)))
+            }
+            "array" => VarType::Array(None),
+            other => return Err(format!("unknown type '{}'", other)),
+        })
+    }
+}
+
+/// Type of a variable reference, descending into the fields of typed objects; `None` when
+/// the variable is not declared
+pub fn path_type(variables: &[(&Variable, VarType)], name: &str, fields: &[String]) -> Option<Result<VarType, String>> {
+    let (_, mut current) = variables.iter().find(|(v, _)| v.name == name || to_pascal_case(&v.name) == name)?.clone();
+    for (i, field) in fields.iter().enumerate() {
+        current = match current {
+            VarType::Object(Some(declared)) => match declared.get(field) {
+                Some(t) => t.clone(),
+                None => {
+                    let path = std::iter::once(name).chain(fields[..i].iter().map(String::as_str)).collect::<Vec<_>>().join(".");
+                    return Some(Err(format!("'{}' has no field '{}'", path, field)));
+                }
+            },
+            _ => VarType::Any,
+        };
+    }
+    Some(Ok(current))
+}
+
+/// Type of a literal in a condition
+pub fn literal_type(literal: &Literal) -> VarType {
+    match literal {
+        Literal::String(_) => VarType::String,
+        Literal::Number(n) if n.contains(['.', 'e', 'E']) => VarType::Float,
+        Literal::Number(_) => VarType::Int,
+        Literal::Bool(_) => VarType::Bool,
+        Literal::Null => VarType::Any,
+    }
+}
+
+/// Operand as quoted in a mismatch, with its type
+fn describe(expr: &Expr, t: &VarType) -> String {
+    match expr {
+        Expr::Literal(_) => format!("a literal of type {}", t),
+        _ => format!("'{}' ({})", expr, t),
+    }
+}
+
+/// Type of an expression, recording mismatches inside it; `None` for undeclared variables
+/// and references that already failed
+fn expr_type(variables: &[(&Variable, VarType)], expr: &Expr, problems: &mut Vec<String>) -> Option<VarType> {
+    match expr {
+        Expr::Variable { name, fields } => match path_type(variables, name, fields)? {
+            Ok(t) => Some(t),
+            Err(problem) => {
+                problems.push(problem);
+                None
+            }
+        },
+        Expr::Literal(literal) => Some(literal_type(literal)),
+        Expr::Not(inner) => {
+            require_bool(variables, inner, problems);
+            Some(VarType::Bool)
+        }
+        Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+            require_bool(variables, lhs, problems);
+            require_bool(variables, rhs, problems);
+            Some(VarType::Bool)
+        }
+        Expr::Compare(lhs, op, rhs) => {
+            let (a, b) = (expr_type(variables, lhs, problems), expr_type(variables, rhs, problems));
+            if let (Some(a), Some(b)) = (a, b) {
+                let fits = if op.is_ordering() { a.orderable(&b) } else { a.comparable(&b) };
+                if !fits {
+                    problems.push(format!("{} {} {} compares incompatible types", describe(lhs, &a), op.as_str(), describe(rhs, &b)));
+                }
+            }
+            Some(VarType::Bool)
+        }
+    }
+}
+
+/// Record a mismatch when an expression in a boolean position is not a bool
+fn require_bool(variables: &[(&Variable, VarType)], expr: &Expr, problems: &mut Vec<String>) {
+    if let Some(t) = expr_type(variables, expr, problems).filter(|t| !VarType::Bool.accepts(t)) {
+        problems.push(format!("{} is used as a condition but is not a bool", describe(expr, &t)));
+    }
+}
+
+/// Type mismatches in a condition; conditions that do not parse are reported by validation
+fn check_condition(variables: &[(&Variable, VarType)], condition: &str) -> Vec<String> {
+    let mut problems = vec![];
+    if let Ok(expr) = condition::parse(condition) {
+        require_bool(variables, &expr, &mut problems);
+    }
+    problems
+}
+
+/// Type of a value a Transform assigns: a lone `{{expression}}` has the type of the
+/// expression, any other string is a string
+fn assigned_type(variables: &[(&Variable, VarType)], value: &Value) -> VarType {
+    let template = value.as_str()
+        .and_then(|s| s.trim().strip_prefix("{{")?.strip_suffix("}}"))
+        .filter(|t| !t.contains("{{") && !t.contains("}}"));
+    let Some(template) = template else { return VarType::of_value(value) };
+    match condition::parse(template) {
+        Ok(expr) => expr_type(variables, &expr, &mut vec![]).unwrap_or(VarType::Any),
+        Err(_) => VarType::Any,
+    }
+}
+
+/// Check declared types, default values, Transform assignments and output schemas, and
+/// Decision edge conditions, listing every mismatch with the variable or node it is in
+pub fn check(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut problems = vec![];
+    let mut variables = vec![];
+    for variable in &definition.variables {
+        match VarType::parse(&variable.var_type) {
+            Ok(declared) => {
+                if let Some(default) = &variable.default_value {
+                    let value = VarType::of_value(default);
+                    if !declared.accepts(&value) {
+                        problems.push(format!("variable '{}' has a default value of type {} but is declared {}", variable.name, value, declared));
+                    }
+                }
+                variables.push((variable, declared));
+            }
+            Err(e) => {
+                problems.push(format!("variable '{}' has an invalid type '{}': {}", variable.name, variable.var_type, e));
+                variables.push((variable, VarType::Any));
+            }
+        }
+    }
+    let declared = |name: &str| variables.iter().find(|(v, _)| v.name == name).map(|(_, t)| t);
+
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::Transform)) {
+        let config: TransformConfig = node.typed_config()?;
+        for (name, value) in &config.assign {
+            let Some(target) = declared(name) else {
+                problems.push(format!("node '{}' assigns undeclared variable '{}'", node.id, name));
+                continue;
+            };
+            let value = assigned_type(&variables, value);
+            if !target.accepts(&value) {
+                problems.push(format!("node '{}' assigns a value of type {} to '{}' ({})", node.id, value, name, target));
+            }
+        }
+        let outputs = config.output_schema.as_ref().and_then(|s| s.get("properties")).and_then(Value::as_object);
+        for (name, schema) in outputs.into_iter().flatten() {
+            let Some(target) = declared(name) else { continue };
+            let output = VarType::of_schema(schema);
+            if !target.accepts(&output) {
+                problems.push(format!("node '{}' outputs a value of type {} as '{}' ({})", node.id, output, name, target));
+            }
+        }
+    }
+
+    for edge in &definition.edges {
+        let Some(condition) = &edge.condition else { continue };
+        let Some(decision) = graph::find_node(definition, &edge.source).filter(|n| matches!(n.node_type, NodeType::Decision)) else { continue };
+        for problem in check_condition(&variables, condition) {
+            problems.push(format!("node '{}' edge '{}': {}", decision.id, edge.id, problem));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(CompilerError::ValidationError(format!("Type mismatches: {}", problems.join("; ")).into()))
+}
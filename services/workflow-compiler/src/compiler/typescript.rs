@@ -0,0 +1,413 @@
+//! TypeScript code generation on `@temporalio/workflow` and `@temporalio/worker`
+//!
+//! Emits `src/workflows.ts`, `src/activities.ts`, `src/worker.ts` and a vitest suite, plus
+//! `package.json` and `tsconfig.json`. Node logic comes from the shared step traversal.
+
+use serde_json::json;
+
+use crate::compiler::backend::Backend;
+use crate::compiler::codegen::{go_string_literal as string_literal, is_go_identifier, VERSION_QUERY};
+use crate::compiler::steps::{self, ActivityCall, Retry, Step, TargetSources, Timeouts};
+use crate::compiler::types::base_type;
+use crate::error::CompilerError;
+use crate::{to_pascal_case, ChildCancellationType, CodegenTarget, GeneratedFile, ParentClosePolicy, WorkflowDefinition};
+
+/// Temporal TypeScript SDK release the generated project depends on
+pub const SDK_VERSION: &str = "1.11.0";
+
+/// Map a DSL variable type onto a TypeScript type
+fn ts_type(var_type: &str) -> &'static str {
+    match base_type(var_type) {
+        "string" => "string",
+        "int" | "integer" | "float" | "number" => "number",
+        "bool" | "boolean" => "boolean",
+        "object" => "Record<string, unknown>",
+        "array" => "unknown[]",
+        _ => "unknown",
+    }
+}
+
+/// Property access on the workflow input
+fn input_field(name: &str) -> String {
+    if is_go_identifier(name) {
+        format!("input.{name}")
+    } else {
+        format!("input[{}]", string_literal(name))
+    }
+}
+
+fn retry_options(retry: &Retry) -> String {
+    format!(
+        "{{ initialInterval: {}, maximumInterval: {}, backoffCoefficient: {}, maximumAttempts: {} }}",
+        retry.initial_interval.as_millis(), retry.max_interval.as_millis(), retry.backoff_coefficient, retry.max_attempts
+    )
+}
+
+/// Activity proxy expression, with a dedicated proxy when the node has a retry policy or
+/// timeouts of its own
+fn proxy(retry: &Option<Retry>, timeouts: &Timeouts, typed: &str) -> String {
+    let mut overrides = vec![];
+    let durations = [
+        ("startToCloseTimeout", timeouts.start_to_close),
+        ("scheduleToCloseTimeout", timeouts.schedule_to_close),
+        ("heartbeatTimeout", timeouts.heartbeat),
+    ];
+    for (option, duration) in durations {
+        overrides.extend(duration.map(|d| format!("{}: {}", option, d.as_millis())));
+    }
+    overrides.extend(retry.as_ref().map(|r| format!("retry: {}", retry_options(r))));
+    match overrides.is_empty() {
+        false => format!("wf.proxyActivities<{typed}>({{ ...activityOptions, {} }})", overrides.join(", ")),
+        true if typed == "typeof activities" => "acts".to_string(),
+        true => format!("wf.proxyActivities<{typed}>(activityOptions)"),
+    }
+}
+
+fn activity_call(call: &ActivityCall, pad: &str) -> String {
+    format!("{pad}// {}\n{pad}await {}.{}(input);\n", call.label, proxy(&call.retry, &call.timeouts, "typeof activities"), call.activity)
+}
+
+fn render_step(step: &Step, workflow_name: &str) -> String {
+    match step {
+        Step::Activity(call) => format!("{}\n", activity_call(call, "  ")),
+        Step::Timer { label, raw, duration } => {
+            format!("  // {label}: wait {raw}\n  await wf.sleep({});\n\n", duration.as_millis())
+        }
+        Step::Signals { label, signals, required, correlation_key, timeout } => {
+            let names = signals.iter().map(|s| string_literal(s)).collect::<Vec<_>>().join(", ");
+            let key = correlation_key.as_deref().map(input_field).unwrap_or_else(|| "undefined".to_string());
+            let wait = match timeout {
+                Some(timeout) => format!(
+                    "    if (!(await wf.condition(arrived, {}))) {{\n      throw wf.ApplicationFailure.nonRetryable({}, \"SignalTimeout\");\n    }}\n",
+                    timeout.as_millis(),
+                    string_literal(&format!("{label} timed out waiting for signals"))
+                ),
+                None => "    await wf.condition(arrived);\n".to_string(),
+            };
+            format!(r#"  // Wait for signals: {label} ({required} of {count})
+  {{
+    const pending = new Set<string>([{names}]);
+    const arrived = (): boolean => {{
+      for (const name of [...pending]) {{
+        if (takeSignal(name, {key})) pending.delete(name);
+      }}
+      return {count} - pending.size >= {required};
+    }};
+{wait}  }}
+
+"#, count = signals.len())
+        }
+        Step::DynamicActivity { label, selector, allowed, retry, timeouts } => {
+            let allowed = allowed.iter().map(|a| string_literal(a)).collect::<Vec<_>>().join(", ");
+            let dispatch = proxy(retry, timeouts, &format!("Record<string, (input: {workflow_name}Input) => Promise<void>>"));
+            let not_allowed = string_literal(&format!("{label}: activity not allowed: "));
+            format!(r#"  // Dynamic activity: {label}
+  {{
+    const allowed = new Set<string>([{allowed}]);
+    const activityName = {selector} ?? "";
+    if (!allowed.has(activityName)) {{
+      throw wf.ApplicationFailure.nonRetryable({not_allowed} + activityName, "ActivityNotAllowed");
+    }}
+    await {dispatch}[activityName](input);
+  }}
+
+"#, selector = input_field(selector))
+        }
+        Step::Child { label, workflow, task_queue, parent_close_policy, cancellation_type, wait_for_completion } => {
+            let close = match parent_close_policy {
+                ParentClosePolicy::Terminate => "TERMINATE",
+                ParentClosePolicy::RequestCancel => "REQUEST_CANCEL",
+                ParentClosePolicy::Abandon => "ABANDON",
+            };
+            let cancellation = match cancellation_type {
+                ChildCancellationType::TryCancel => "TRY_CANCEL",
+                ChildCancellationType::WaitCancellationCompleted => "WAIT_CANCELLATION_COMPLETED",
+                ChildCancellationType::Abandon => "ABANDON",
+            };
+            let task_queue = task_queue.as_deref()
+                .map(|q| format!("\n    taskQueue: {},", string_literal(q)))
+                .unwrap_or_default();
+            let start = if *wait_for_completion { "executeChild" } else { "startChild" };
+            format!(r#"  // Child workflow: {label}
+  await wf.{start}({workflow}, {{{task_queue}
+    parentClosePolicy: wf.ParentClosePolicy.{close},
+    cancellationType: wf.ChildWorkflowCancellationType.{cancellation},
+  }});
+
+"#, workflow = string_literal(workflow))
+        }
+        Step::WeightedSplit { label, branches } => {
+            let weights = branches.iter()
+                .scan(0, |lower, (upper, _)| {
+                    let weight = upper - *lower;
+                    *lower = *upper;
+                    Some(weight.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let mut cases = String::new();
+            for (i, (upper, call)) in branches.iter().enumerate() {
+                let condition = if i + 1 == branches.len() {
+                    " else {\n".to_string()
+                } else if i == 0 {
+                    format!("    if (bucket < {upper}) {{\n")
+                } else {
+                    format!(" else if (bucket < {upper}) {{\n")
+                };
+                cases.push_str(&condition);
+                cases.push_str(&activity_call(call, "      "));
+                cases.push_str("    }");
+            }
+            format!(r#"  // Weighted split: {label} ({weights})
+  {{
+    // Math.random is seeded per execution in the workflow sandbox, so replays pick the same bucket
+    const bucket = Math.floor(Math.random() * 100);
+{cases}
+  }}
+
+"#)
+        }
+    }
+}
+
+fn generate_workflow(definition: &WorkflowDefinition, steps: &[Step], workflow_name: &str, fingerprint: &str) -> String {
+    let fields: String = definition.variables.iter()
+        .map(|v| {
+            let key = if is_go_identifier(&v.name) { v.name.clone() } else { string_literal(&v.name) };
+            format!("  {key}?: {};\n", ts_type(&v.var_type))
+        })
+        .collect();
+
+    let signals = steps::signal_names(steps);
+    let (signal_types, signal_handlers) = if signals.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let names = signals.iter().map(|s| string_literal(s)).collect::<Vec<_>>().join(", ");
+        (
+            r#"
+/** Envelope expected on every correlated signal */
+export interface SignalPayload {
+  correlation_key?: string;
+  data?: unknown;
+}
+"#.to_string(),
+            format!(r#"
+  // Buffer signals from the start of the execution so none are missed before their wait
+  const received = new Map<string, SignalPayload[]>();
+  for (const name of [{names}]) {{
+    wf.setHandler(wf.defineSignal<[SignalPayload?]>(name), (payload) => {{
+      received.set(name, [...(received.get(name) ?? []), payload ?? {{}}]);
+    }});
+  }}
+  const takeSignal = (name: string, key?: string): boolean => {{
+    const queue = received.get(name) ?? [];
+    const index = queue.findIndex((p) => key === undefined || p.correlation_key === key);
+    if (index < 0) return false;
+    queue.splice(index, 1);
+    return true;
+  }};
+"#),
+        )
+    };
+
+    let body: String = steps.iter().map(|s| render_step(s, workflow_name)).collect();
+
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+// DO NOT EDIT - This file is auto-generated
+
+import * as wf from '@temporalio/workflow';
+import type * as activities from './activities';
+
+/** Version of the workflow definition this code was compiled from */
+export const definitionVersion = {version};
+/** Content hash of that definition */
+export const definitionFingerprint = {fingerprint};
+
+export interface {workflow_name}Input {{
+{fields}}}
+
+export interface {workflow_name}Output {{
+  success: boolean;
+  message: string;
+}}
+{signal_types}
+/** Reports the definition version a running execution was compiled from */
+export const versionQuery = wf.defineQuery<{{ version: string; fingerprint: string }}>({query});
+
+const activityOptions: wf.ActivityOptions = {{
+  startToCloseTimeout: '10 minutes',
+}};
+const acts = wf.proxyActivities<typeof activities>(activityOptions);
+
+export async function {workflow_name}(input: {workflow_name}Input): Promise<{workflow_name}Output> {{
+  wf.setHandler(versionQuery, () => ({{ version: definitionVersion, fingerprint: definitionFingerprint }}));
+{signal_handlers}
+{body}  return {{
+    success: true,
+    message: 'Workflow completed successfully',
+  }};
+}}
+"#,
+        version = string_literal(&definition.version),
+        fingerprint = string_literal(fingerprint),
+        query = string_literal(VERSION_QUERY),
+    )
+}
+
+fn generate_activities(steps: &[Step], workflow_name: &str) -> String {
+    let methods: String = steps::activity_names(steps).iter()
+        .map(|name| format!(r#"
+/** {name} implements the {name} activity */
+export async function {name}(input: {workflow_name}Input): Promise<void> {{
+  // TODO: implement {name}
+}}
+"#))
+        .collect();
+
+    format!("// Generated by OmniRoute Workflow Compiler\n\nimport type {{ {workflow_name}Input }} from './workflows';\n{methods}")
+}
+
+fn generate_worker(package_name: &str) -> String {
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+import {{ NativeConnection, Worker }} from '@temporalio/worker';
+import * as activities from './activities';
+
+export const taskQueue = '{package_name}-task-queue';
+
+async function run(): Promise<void> {{
+  const connection = await NativeConnection.connect({{
+    address: process.env.TEMPORAL_ADDRESS ?? 'localhost:7233',
+  }});
+  try {{
+    const worker = await Worker.create({{
+      connection,
+      namespace: process.env.TEMPORAL_NAMESPACE ?? 'default',
+      taskQueue,
+      workflowsPath: require.resolve('./workflows'),
+      activities,
+    }});
+    await worker.run();
+  }} finally {{
+    await connection.close();
+  }}
+}}
+
+run().catch((err) => {{
+  console.error(err);
+  process.exit(1);
+}});
+"#)
+}
+
+fn generate_test(workflow_name: &str, package_name: &str) -> String {
+    format!(r#"// Generated by OmniRoute Workflow Compiler
+
+import path from 'path';
+import {{ TestWorkflowEnvironment }} from '@temporalio/testing';
+import {{ Worker }} from '@temporalio/worker';
+import {{ afterAll, beforeAll, describe, expect, it }} from 'vitest';
+import * as activities from './activities';
+import {{ {workflow_name} }} from './workflows';
+
+describe('{workflow_name}', () => {{
+  let env: TestWorkflowEnvironment;
+
+  beforeAll(async () => {{
+    env = await TestWorkflowEnvironment.createTimeSkipping();
+  }}, 60_000);
+
+  afterAll(async () => {{
+    await env?.teardown();
+  }});
+
+  it('completes successfully', async () => {{
+    const taskQueue = 'test-{package_name}';
+    const worker = await Worker.create({{
+      connection: env.nativeConnection,
+      taskQueue,
+      workflowsPath: path.join(__dirname, 'workflows.ts'),
+      activities,
+    }});
+
+    const result = await worker.runUntil(
+      env.client.workflow.execute({workflow_name}, {{ workflowId: 'test-{package_name}', taskQueue, args: [{{}}] }}),
+    );
+
+    expect(result.success).toBe(true);
+  }}, 60_000);
+}});
+"#)
+}
+
+fn generate_project_files(package_name: &str, version: &str) -> Vec<GeneratedFile> {
+    let sdk = format!("^{SDK_VERSION}");
+    let package = json!({
+        "name": package_name.replace('_', "-"),
+        "version": version,
+        "private": true,
+        "scripts": {
+            "build": "tsc",
+            "start": "node lib/worker.js",
+            "test": "vitest run"
+        },
+        "dependencies": {
+            "@temporalio/activity": sdk,
+            "@temporalio/client": sdk,
+            "@temporalio/worker": sdk,
+            "@temporalio/workflow": sdk
+        },
+        "devDependencies": {
+            "@temporalio/testing": sdk,
+            "@types/node": "^20.0.0",
+            "typescript": "^5.4.0",
+            "vitest": "^1.6.0"
+        }
+    });
+    let tsconfig = json!({
+        "compilerOptions": {
+            "target": "ES2021",
+            "module": "commonjs",
+            "strict": true,
+            "esModuleInterop": true,
+            "skipLibCheck": true,
+            "rootDir": "src",
+            "outDir": "lib"
+        },
+        "include": ["src/**/*.ts"],
+        "exclude": ["src/**/*.test.ts"]
+    });
+
+    vec![
+        GeneratedFile { path: "package.json".to_string(), content: format!("{:#}\n", package) },
+        GeneratedFile { path: "tsconfig.json".to_string(), content: format!("{:#}\n", tsconfig) },
+    ]
+}
+
+/// TypeScript backend
+pub struct TypeScript;
+
+impl Backend for TypeScript {
+    fn target(&self) -> CodegenTarget {
+        CodegenTarget::Typescript
+    }
+
+    fn sdk_version(&self) -> &'static str {
+        SDK_VERSION
+    }
+
+    fn generate(&self, definition: &WorkflowDefinition, package_name: &str, fingerprint: &str) -> Result<TargetSources, CompilerError> {
+        let steps = steps::workflow_steps(definition, self.target().name())?;
+        let workflow_name = to_pascal_case(&definition.name);
+
+        Ok(TargetSources {
+            workflow_code: generate_workflow(definition, &steps, &workflow_name, fingerprint),
+            activity_code: generate_activities(&steps, &workflow_name),
+            worker_code: generate_worker(package_name),
+            test_code: generate_test(&workflow_name, package_name),
+            files: generate_project_files(package_name, &definition.version),
+            signals: steps::signal_names(&steps),
+            metadata: None,
+        })
+    }
+}
@@ -0,0 +1,1182 @@
+//! Structural and semantic validation of workflow definitions
+
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::{decision_table, limits, triggers};
+use crate::config::NamespacePolicy;
+use crate::dsl::{condition, graph, schema};
+use crate::error::CompilerError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use crate::compiler::codegen::{child_input_expr, is_activity_node, is_go_identifier, label_identifier, schedule_triggers};
+use crate::compiler::duration;
+use crate::{
+    to_pascal_case, ActivityConfig, CancellationScopeConfig, ChildCancellationType, DatabaseQueryConfig, DecisionConfig, DecisionTableConfig,
+    DynamicActivityConfig, EdgeKind, FeatureFlagConfig, HttpCallConfig, JoinPolicy, NexusOperationConfig,
+    NodeType, ParallelGatewayConfig, ParentClosePolicy, SubWorkflowConfig, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig,
+    PublishEventConfig, RetryPolicy, TransformConfig, WeightedSplitConfig, WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+/// Whether a node is a Decision marked with `"loop": true`, whose cycles are intended
+pub fn is_loop_head(node: &WorkflowNode) -> bool {
+    matches!(node.node_type, NodeType::Decision) && node.typed_config::<DecisionConfig>().is_ok_and(|c| c.is_loop)
+}
+
+/// Check every node's config against the schema of its node type and deserialize the typed
+/// config, naming the node and each offending field
+pub fn validate_node_configs(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    fn typed<T: DeserializeOwned + Default>(node: &WorkflowNode) -> Result<(), CompilerError> {
+        node.typed_config::<T>().map(|_| ())
+    }
+
+    for node in &definition.nodes {
+        let Ok(Value::String(node_type)) = serde_json::to_value(&node.node_type) else { continue };
+        if let Some(config_schema) = schema::node_config_schema(&node_type) {
+            let config = if node.config.is_null() { json!({}) } else { node.config.clone() };
+            let fields: Vec<String> = schema::violations(&config_schema, &config).into_iter()
+                .map(|v| format!("{} {}", config_field(&v.pointer), v.message))
+                .collect();
+            if !fields.is_empty() {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Invalid config for node '{}': {}",
+                    node.id,
+                    fields.join("; ")
+                )).node(&node.id)));
+            }
+        }
+        match node.node_type {
+            NodeType::Activity => typed::<ActivityConfig>(node),
+            NodeType::Decision => typed::<DecisionConfig>(node),
+            NodeType::ParallelGateway => typed::<ParallelGatewayConfig>(node),
+            NodeType::WaitTimer => typed::<WaitTimerConfig>(node),
+            NodeType::WaitSignal => typed::<WaitSignalConfig>(node),
+            NodeType::SubWorkflow => typed::<SubWorkflowConfig>(node),
+            NodeType::HttpCall => typed::<HttpCallConfig>(node),
+            NodeType::DatabaseQuery => typed::<DatabaseQueryConfig>(node),
+            NodeType::DecisionTable => typed::<DecisionTableConfig>(node),
+            NodeType::WaitSignals => typed::<WaitSignalsConfig>(node),
+            NodeType::DynamicActivity => typed::<DynamicActivityConfig>(node),
+            NodeType::CancellationScope => typed::<CancellationScopeConfig>(node),
+            NodeType::NexusOperation => typed::<NexusOperationConfig>(node),
+            NodeType::FeatureFlag => typed::<FeatureFlagConfig>(node),
+            NodeType::WeightedSplit => typed::<WeightedSplitConfig>(node),
+            NodeType::PublishEvent => typed::<PublishEventConfig>(node),
+            NodeType::Transform => typed::<TransformConfig>(node),
+            // Free-form configs, and plugin types already checked against their manifests
+            NodeType::Start | NodeType::End | NodeType::Notification | NodeType::Plugin(_) => Ok(()),
+        }?;
+    }
+    Ok(())
+}
+
+/// Dotted path of the config field a JSON pointer into the config refers to
+fn config_field(pointer: &str) -> String {
+    let tokens = pointer.split('/').skip(1).map(|t| t.replace("~1", "/").replace("~0", "~"));
+    std::iter::once("config".to_string()).chain(tokens).collect::<Vec<_>>().join(".")
+}
+
+/// Edges whose condition is an expression, with the condition; FeatureFlag edges select a
+/// branch with `on` or `off` instead, and decision table edges with an outcome
+pub fn condition_edges(definition: &WorkflowDefinition) -> impl Iterator<Item = (&WorkflowEdge, &str)> {
+    definition.edges.iter()
+        .filter(|e| !graph::find_node(definition, &e.source).is_some_and(|n| matches!(n.node_type, NodeType::FeatureFlag | NodeType::DecisionTable)))
+        .filter_map(|e| Some((e, e.condition.as_deref()?)))
+}
+
+/// Check that every edge condition parses, naming the edge and offset of each syntax error;
+/// the diagnostic points at the first invalid edge
+pub fn validate_conditions(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let errors: Vec<(&str, String)> = condition_edges(definition)
+        .filter_map(|(edge, c)| condition::parse(c).err().map(|e| (edge.id.as_str(), format!("edge '{}' condition '{}': {}", edge.id, c, e))))
+        .collect();
+    let Some((first, _)) = errors.first() else {
+        return Ok(());
+    };
+    let messages: Vec<&str> = errors.iter().map(|(_, m)| m.as_str()).collect();
+    Err(CompilerError::ValidationError(Diagnostic::new(format!("Invalid conditions: {}", messages.join("; "))).edge(first)))
+}
+
+/// Check that variable names are unique and that edge conditions and `{{...}}` templates in
+/// node configs only reference declared variables, listing each unknown reference; the
+/// diagnostic points at the edge or node of the first
+pub fn validate_variable_references(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut duplicates: Vec<&str> = vec![];
+    for (i, variable) in definition.variables.iter().enumerate() {
+        if definition.variables[..i].iter().any(|v| v.name == variable.name) && !duplicates.contains(&variable.name.as_str()) {
+            duplicates.push(&variable.name);
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(CompilerError::ValidationError(format!(
+            "Variables declared more than once: '{}'",
+            duplicates.join("', '")
+        ).into()));
+    }
+
+    let declared = |name: &str| definition.variables.iter().any(|v| v.name == name || to_pascal_case(&v.name) == name);
+    let mut unknown = vec![];
+    let (mut first_edge, mut first_node) = (None, None);
+    for (edge, condition) in condition_edges(definition) {
+        let Ok(expr) = condition::parse(condition) else { continue };
+        let mut names: Vec<&str> = vec![];
+        for (name, _) in expr.variables().into_iter().filter(|(n, _)| !declared(n)) {
+            if !names.contains(&name) {
+                names.push(name);
+                unknown.push(format!("'{}' in the condition on edge '{}'", name, edge.id));
+                first_edge.get_or_insert(edge.id.as_str());
+            }
+        }
+    }
+    for node in &definition.nodes {
+        let mut templates = vec![];
+        config_templates(&node.config, "config".to_string(), &mut templates);
+        for (field, template) in templates {
+            for name in referenced_names(template).into_iter().filter(|n| !declared(n)) {
+                unknown.push(format!("'{}' in {} of node '{}'", name, field, node.id));
+                first_node.get_or_insert(node.id.as_str());
+            }
+        }
+    }
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let diagnostic = Diagnostic::new(format!("Undeclared variables referenced: {}", unknown.join(", ")));
+    Err(CompilerError::ValidationError(match (first_edge, first_node) {
+        (Some(edge), _) => diagnostic.edge(edge),
+        (None, Some(node)) => diagnostic.node(node),
+        (None, None) => diagnostic,
+    }))
+}
+
+/// Contents of the `{{...}}` templates in a config's strings, with the dotted field path of each
+pub fn config_templates<'a>(value: &'a Value, field: String, templates: &mut Vec<(String, &'a str)>) {
+    match value {
+        Value::String(text) => {
+            let mut rest = text.as_str();
+            while let Some((_, after)) = rest.split_once("{{") {
+                let Some((template, tail)) = after.split_once("}}") else { break };
+                templates.push((field.clone(), template));
+                rest = tail;
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                config_templates(item, format!("{}.{}", field, i), templates);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, item) in fields {
+                config_templates(item, format!("{}.{}", field, key), templates);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Variable names an expression reads: the head of each identifier path outside string
+/// literals, without an `input.` prefix, skipping literals, `$`-prefixed names and calls
+pub fn referenced_names(expression: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = vec![];
+    let mut chars = expression.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                while let Some((_, ch)) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            chars.next();
+                        }
+                        ch if ch == c => break,
+                        _ => {}
+                    }
+                }
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '$' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, ch)) = chars.peek().filter(|(_, ch)| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.' | '$')) {
+                    end = i + ch.len_utf8();
+                    chars.next();
+                }
+                let word = &expression[start..end];
+                let call = expression[end..].trim_start().starts_with('(');
+                if c.is_ascii_digit() || c == '$' || call || matches!(word, "true" | "false" | "null") {
+                    continue;
+                }
+                let head = word.strip_prefix("input.").unwrap_or(word).split('.').next().unwrap_or_default();
+                if !head.is_empty() && !names.contains(&head) {
+                    names.push(head);
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Check that node IDs are unique, listing each ID declared more than once
+pub fn validate_node_ids(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut seen = HashSet::new();
+    let mut duplicates: Vec<&str> = vec![];
+    for node in &definition.nodes {
+        if !seen.insert(node.id.as_str()) && !duplicates.contains(&node.id.as_str()) {
+            duplicates.push(&node.id);
+        }
+    }
+    match duplicates.first() {
+        None => Ok(()),
+        Some(first) => Err(CompilerError::ValidationError(Diagnostic::new(format!(
+            "Node IDs declared more than once: '{}'",
+            duplicates.join("', '")
+        )).node(first))),
+    }
+}
+
+/// Check that edges connect existing nodes, never enter Start or leave End, and have unique
+/// IDs, listing the offending edge IDs of each kind
+pub fn validate_edges(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut dangling = vec![];
+    let mut into_start = vec![];
+    let mut out_of_end = vec![];
+    let mut duplicates: Vec<&str> = vec![];
+    let mut seen = HashSet::new();
+    for edge in &definition.edges {
+        if !seen.insert(edge.id.as_str()) && !duplicates.contains(&edge.id.as_str()) {
+            duplicates.push(&edge.id);
+        }
+        let source = graph::find_node(definition, &edge.source);
+        let target = graph::find_node(definition, &edge.target);
+        for (end, id, node) in [("source", &edge.source, source), ("target", &edge.target, target)] {
+            if node.is_none() {
+                dangling.push(format!("'{}' ({} '{}')", edge.id, end, id));
+            }
+        }
+        if target.is_some_and(|n| matches!(n.node_type, NodeType::Start)) {
+            into_start.push(format!("'{}'", edge.id));
+        }
+        if source.is_some_and(|n| matches!(n.node_type, NodeType::End)) {
+            out_of_end.push(format!("'{}'", edge.id));
+        }
+    }
+    let duplicates: Vec<String> = duplicates.iter().map(|id| format!("'{}'", id)).collect();
+    let problems: Vec<String> = [
+        ("reference unknown nodes", dangling),
+        ("target a Start node", into_start),
+        ("leave an End node", out_of_end),
+        ("share their ID with an earlier edge", duplicates),
+    ]
+    .into_iter()
+    .filter(|(_, edges)| !edges.is_empty())
+    .map(|(problem, edges)| format!("edges {}: {}", problem, edges.join(", ")))
+    .collect();
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(CompilerError::ValidationError(format!("Invalid edges; {}", problems.join("; ")).into()))
+}
+
+/// Check that every cycle of flow edges passes through a loop-marked Decision, reporting the
+/// first other cycle found depth-first in definition order as its node path
+pub fn validate_cycles(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let loop_heads: HashSet<&str> = definition.nodes.iter().filter(|n| is_loop_head(n)).map(|n| n.id.as_str()).collect();
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Flow) {
+        if !loop_heads.contains(edge.source.as_str()) && !loop_heads.contains(edge.target.as_str()) {
+            successors.entry(edge.source.as_str()).or_default().push(edge.target.as_str());
+        }
+    }
+
+    fn visit<'a>(
+        node: &'a str,
+        successors: &HashMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        path.push(node);
+        for &next in successors.get(node).into_iter().flatten() {
+            if let Some(start) = path.iter().position(|&n| n == next) {
+                return Some(path[start..].iter().chain([&next]).map(|n| n.to_string()).collect());
+            }
+            if !done.contains(next) {
+                if let Some(cycle) = visit(next, successors, path, done) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        done.insert(node);
+        None
+    }
+
+    let mut done = HashSet::new();
+    for node in &definition.nodes {
+        limits::checkpoint("validation")?;
+        if done.contains(node.id.as_str()) {
+            continue;
+        }
+        if let Some(path) = visit(&node.id, &successors, &mut vec![], &mut done) {
+            return Err(CompilerError::CycleDetected { path });
+        }
+    }
+
+    Ok(())
+}
+
+/// Nodes a run never executes or cannot finish from, in definition order
+#[derive(Debug, Clone, Serialize)]
+pub struct UnconnectedNodes {
+    /// Not reachable from any Start node
+    pub unreachable: Vec<String>,
+    /// Reachable, but with no path to an End node
+    pub no_path_to_end: Vec<String>,
+}
+
+impl std::fmt::Display for UnconnectedNodes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        if !self.unreachable.is_empty() {
+            parts.push(format!("unreachable from Start: {}", self.unreachable.join(", ")));
+        }
+        if !self.no_path_to_end.is_empty() {
+            parts.push(format!("no path to End: {}", self.no_path_to_end.join(", ")));
+        }
+        write!(f, "Workflow has disconnected nodes; {}", parts.join("; "))
+    }
+}
+
+/// Check that every node is reachable from Start and has a path to End. When optimization
+/// removes unreachable nodes, they are returned as warnings instead
+pub fn validate_connectivity(definition: &WorkflowDefinition, removes_unreachable: bool) -> Result<Vec<String>, CompilerError> {
+    let reachable = graph::reachable_nodes(definition);
+    let reaching_end = graph::nodes_reaching_end(definition);
+    let mut unreachable: Vec<String> = definition.nodes.iter()
+        .filter(|n| !reachable.contains(n.id.as_str()))
+        .map(|n| n.id.clone())
+        .collect();
+    let mut warnings = vec![];
+    if removes_unreachable && !unreachable.is_empty() {
+        warnings.push(format!(
+            "Nodes unreachable from Start are removed from the generated code: {}",
+            unreachable.join(", ")
+        ));
+        unreachable.clear();
+    }
+    let no_path_to_end: Vec<String> = definition.nodes.iter()
+        .filter(|n| reachable.contains(n.id.as_str()) && !reaching_end.contains(n.id.as_str()))
+        .map(|n| n.id.clone())
+        .collect();
+    if unreachable.is_empty() && no_path_to_end.is_empty() {
+        return Ok(warnings);
+    }
+    Err(CompilerError::UnconnectedNodes(UnconnectedNodes { unreachable, no_path_to_end }))
+}
+
+/// Check that each Decision branches on at least one condition and has at most one default
+/// edge, warning when a Decision without a default may match none of its conditions
+pub fn validate_decision_branches(definition: &WorkflowDefinition) -> Result<Vec<String>, CompilerError> {
+    let mut warnings = Vec::new();
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::Decision)) {
+        let (conditional, defaults): (Vec<&WorkflowEdge>, Vec<&WorkflowEdge>) = graph::outgoing_edges(definition, &node.id)
+            .filter(|e| e.kind == EdgeKind::Flow)
+            .partition(|e| e.condition.as_deref().is_some_and(|c| !c.trim().is_empty()));
+        if conditional.is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Decision node '{}' has no edge with a condition",
+                node.id
+            )).node(&node.id)));
+        }
+        if defaults.len() > 1 {
+            let ids: Vec<&str> = defaults.iter().map(|e| e.id.as_str()).collect();
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Decision node '{}' has more than one default edge: {}; give all but one a condition",
+                node.id,
+                ids.join(", ")
+            )).node(&node.id)));
+        }
+        let conditions: Vec<condition::Expr> = conditional.iter()
+            .filter_map(|e| e.condition.as_deref())
+            .filter_map(|c| condition::parse(c).ok())
+            .map(|c| negation_normal(&c, false))
+            .collect();
+        if defaults.is_empty() && !is_exhaustive(&conditions) {
+            warnings.push(format!(
+                "Decision node '{}' has no default edge and its conditions may all be false, leaving the workflow without a next step",
+                node.id
+            ));
+        }
+    }
+    Ok(warnings)
+}
+
+/// `expr`, or its negation when `negated` is set, with negations pushed down to comparisons
+/// and operands, so complementary conditions compare equal
+fn negation_normal(expr: &condition::Expr, negated: bool) -> condition::Expr {
+    use condition::{Expr, Literal};
+    match expr {
+        Expr::Not(inner) => negation_normal(inner, !negated),
+        Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+            let (lhs, rhs) = (Box::new(negation_normal(lhs, negated)), Box::new(negation_normal(rhs, negated)));
+            match (expr, negated) {
+                (Expr::And(..), false) | (Expr::Or(..), true) => Expr::And(lhs, rhs),
+                _ => Expr::Or(lhs, rhs),
+            }
+        }
+        Expr::Compare(lhs, op, rhs) if negated => Expr::Compare(lhs.clone(), op.negated(), rhs.clone()),
+        Expr::Literal(Literal::Bool(b)) => Expr::Literal(Literal::Bool(*b != negated)),
+        _ if negated => Expr::Not(Box::new(expr.clone())),
+        _ => expr.clone(),
+    }
+}
+
+/// Operands of the `||` chains in `expr`
+fn disjuncts<'a>(expr: &'a condition::Expr, into: &mut Vec<&'a condition::Expr>) {
+    match expr {
+        condition::Expr::Or(lhs, rhs) => {
+            disjuncts(lhs, into);
+            disjuncts(rhs, into);
+        }
+        _ => into.push(expr),
+    }
+}
+
+/// Whether some condition is sure to hold, going by their form: one is `true`, one is the
+/// complement of another, or their `||` operands include both a condition and its complement
+fn is_exhaustive(conditions: &[condition::Expr]) -> bool {
+    let complements: Vec<condition::Expr> = conditions.iter().map(|c| negation_normal(c, true)).collect();
+    if complements.iter().any(|c| conditions.contains(c)) {
+        return true;
+    }
+    let mut operands = vec![];
+    for condition in conditions {
+        disjuncts(condition, &mut operands);
+    }
+    operands.iter().any(|o| matches!(o, condition::Expr::Literal(condition::Literal::Bool(true))) || operands.contains(&&negation_normal(o, true)))
+}
+
+/// Check that every fork gateway closes at a join gateway every branch reaches, that branches
+/// stay inside the region between the two, and that every join closes a fork
+pub fn validate_gateway_pairs(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut problems = Vec::new();
+    let mut matched = HashSet::new();
+    let gateways = definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::ParallelGateway));
+    for fork in gateways.clone().filter(|n| !graph::is_join(definition, n)) {
+        let branches = graph::flow_targets(definition, fork);
+        let closing: Vec<Vec<&WorkflowNode>> = branches.iter().map(|b| closing_joins(definition, b)).collect();
+        let mut joins: Vec<&WorkflowNode> = vec![];
+        for join in closing.iter().flatten() {
+            if !joins.iter().any(|j| j.id == join.id) {
+                joins.push(join);
+            }
+        }
+        matched.extend(joins.iter().map(|j| j.id.as_str()));
+        for (branch, _) in branches.iter().zip(&closing).filter(|(_, c)| c.is_empty()) {
+            problems.push(format!("branch '{}' of fork '{}' never reaches a join gateway", branch.id, fork.id));
+        }
+        let join = match joins.as_slice() {
+            [] => continue,
+            [join] => *join,
+            _ => {
+                let targets: Vec<String> = branches.iter().zip(&closing).filter(|(_, c)| !c.is_empty())
+                    .map(|(b, c)| format!("'{}' (branch '{}')", c.iter().map(|j| j.id.as_str()).collect::<Vec<_>>().join("' or '"), b.id))
+                    .collect();
+                problems.push(format!("branches of fork '{}' close at different joins: {}", fork.id, targets.join(", ")));
+                continue;
+            }
+        };
+        if closing.iter().any(Vec::is_empty) {
+            continue;
+        }
+
+        // Nodes of each branch up to the join, keyed to the first node of their branch
+        let mut region: HashMap<&str, &str> = HashMap::new();
+        for branch in branches {
+            if branch.id == join.id {
+                continue;
+            }
+            let mut seen = HashSet::from([branch.id.as_str()]);
+            let mut pending = vec![branch];
+            while let Some(node) = pending.pop() {
+                if matches!(node.node_type, NodeType::End) {
+                    problems.push(format!("branch '{}' of fork '{}' reaches End node '{}' before join '{}'", branch.id, fork.id, node.id, join.id));
+                }
+                match region.insert(node.id.as_str(), branch.id.as_str()) {
+                    Some(other) if other != branch.id => {
+                        problems.push(format!("branches '{}' and '{}' of fork '{}' share node '{}'", other, branch.id, fork.id, node.id));
+                    }
+                    _ => {}
+                }
+                for next in graph::flow_targets(definition, node) {
+                    if next.id != join.id && seen.insert(next.id.as_str()) {
+                        pending.push(next);
+                    }
+                }
+            }
+        }
+
+        for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Flow && e.source != fork.id) {
+            let inside = region.contains_key(edge.source.as_str());
+            if !inside && (edge.target == join.id || region.contains_key(edge.target.as_str())) {
+                problems.push(format!("node '{}' enters the region of fork '{}' and join '{}' from outside it", edge.source, fork.id, join.id));
+            }
+        }
+    }
+    for join in gateways.filter(|n| graph::is_join(definition, n) && !matched.contains(n.id.as_str())) {
+        problems.push(format!("join '{}' has no matching fork gateway", join.id));
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(CompilerError::ValidationError(format!("Unbalanced parallel gateways; {}", problems.join("; ")).into()))
+}
+
+/// Joins closing the fork a branch starts in, stepping over the forks nested inside it
+fn closing_joins<'a>(definition: &'a WorkflowDefinition, branch: &'a WorkflowNode) -> Vec<&'a WorkflowNode> {
+    let mut closing: Vec<&WorkflowNode> = vec![];
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([(branch, 0usize)]);
+    while let Some((node, depth)) = queue.pop_front() {
+        if !seen.insert((node.id.as_str(), depth)) {
+            continue;
+        }
+        let depth = match node.node_type {
+            NodeType::ParallelGateway if graph::is_join(definition, node) => match depth.checked_sub(1) {
+                Some(depth) => depth,
+                None => {
+                    if !closing.iter().any(|j| j.id == node.id) {
+                        closing.push(node);
+                    }
+                    continue;
+                }
+            },
+            NodeType::ParallelGateway => depth + 1,
+            _ => depth,
+        };
+        // A cycle through an unclosed fork would nest forever
+        if depth > definition.nodes.len() {
+            continue;
+        }
+        queue.extend(graph::flow_targets(definition, node).into_iter().map(|next| (next, depth)));
+    }
+    closing
+}
+
+/// Check that every parallel gateway's join policy is satisfiable by its branches
+pub fn validate_parallel_gateways(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::ParallelGateway)) {
+        let config: ParallelGatewayConfig = node.typed_config()?;
+        // Join gateways inherit their semantics from the matching fork
+        if graph::is_join(definition, node) {
+            continue;
+        }
+        let branches = graph::flow_targets(definition, node).len() as u32;
+
+        if let JoinPolicy::NOfM(n) = config.join {
+            if n == 0 || n > branches {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Parallel gateway '{}' requires {} of {} branches",
+                    node.id, n, branches
+                )).node(&node.id)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check decision table shape, completeness, rule overlap and routing
+pub fn validate_decision_tables(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::DecisionTable)) {
+        decision_table::validate(definition, node)?;
+    }
+
+    Ok(())
+}
+
+/// Check multi-signal waits for duplicate signals, correlation variables and timeouts
+pub fn validate_signal_waits(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::WaitSignals)) {
+        let config: WaitSignalsConfig = node.typed_config()?;
+
+        if config.signals.is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "WaitSignals node '{}' must wait for at least one signal",
+                node.id
+            )).node(&node.id)));
+        }
+        for (i, signal) in config.signals.iter().enumerate() {
+            if config.signals[..i].contains(signal) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "WaitSignals node '{}' lists signal '{}' more than once",
+                    node.id, signal
+                )).node(&node.id)));
+            }
+        }
+
+        if let Some(key) = &config.correlation_key {
+            match definition.variables.iter().find(|v| &v.name == key) {
+                Some(v) if v.var_type == "string" => {}
+                Some(_) => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "WaitSignals node '{}' correlation key '{}' must be a string variable",
+                    node.id, key
+                )).node(&node.id))),
+                None => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "WaitSignals node '{}' correlation key '{}' is not a workflow variable",
+                    node.id, key
+                )).node(&node.id))),
+            }
+        }
+
+        if let Some(timeout) = &config.timeout {
+            duration::parse_field(timeout, "timeout", Some(&node.id))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that dynamic dispatch selects from a non-empty allowlist via a string variable
+pub fn validate_dynamic_activities(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::DynamicActivity)) {
+        let config: DynamicActivityConfig = node.typed_config()?;
+
+        match definition.variables.iter().find(|v| v.name == config.selector) {
+            Some(v) if v.var_type == "string" => {}
+            Some(_) => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "DynamicActivity node '{}' selector '{}' must be a string variable",
+                node.id, config.selector
+            )).node(&node.id))),
+            None => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "DynamicActivity node '{}' selector '{}' is not a workflow variable",
+                node.id, config.selector
+            )).node(&node.id))),
+        }
+
+        if config.allowed.is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "DynamicActivity node '{}' must allow at least one activity",
+                node.id
+            )).node(&node.id)));
+        }
+        if let Some(name) = config.allowed.iter().find(|a| !is_go_identifier(a)) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "DynamicActivity node '{}' allows invalid activity name '{}'",
+                node.id, name
+            )).node(&node.id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that split weights sum to 100 and cover each outgoing edge exactly once
+pub fn validate_weighted_splits(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::WeightedSplit)) {
+        let config: WeightedSplitConfig = node.typed_config()?;
+
+        if config.branches.len() < 2 {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "WeightedSplit node '{}' needs at least two branches",
+                node.id
+            )).node(&node.id)));
+        }
+        let total: u32 = config.branches.iter().map(|b| b.weight).sum();
+        if total != 100 {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "WeightedSplit node '{}' weights sum to {}, expected 100",
+                node.id, total
+            )).node(&node.id)));
+        }
+
+        let targets: Vec<&str> = graph::outgoing_edges(definition, &node.id)
+            .filter(|e| e.kind == EdgeKind::Flow)
+            .map(|e| e.target.as_str())
+            .collect();
+        for (i, branch) in config.branches.iter().enumerate() {
+            if config.branches[..i].iter().any(|b| b.target == branch.target) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "WeightedSplit node '{}' lists target '{}' more than once",
+                    node.id, branch.target
+                )).node(&node.id)));
+            }
+            if !targets.contains(&branch.target.as_str()) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "WeightedSplit node '{}' target '{}' is not connected by an outgoing edge",
+                    node.id, branch.target
+                )).node(&node.id)));
+            }
+            match graph::find_node(definition, &branch.target) {
+                Some(target) if is_activity_node(target) => {}
+                _ => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "WeightedSplit node '{}' target '{}' must be an activity node",
+                    node.id, branch.target
+                )).node(&node.id))),
+            }
+        }
+        if let Some(unweighted) = targets.iter().find(|t| !config.branches.iter().any(|b| b.target == **t)) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "WeightedSplit node '{}' has no weight for target '{}'",
+                node.id, unweighted
+            )).node(&node.id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that every PublishEvent node names its topic
+pub fn validate_publish_events(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::PublishEvent)) {
+        let config: PublishEventConfig = node.typed_config()?;
+        if config.topic.trim().is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "PublishEvent node '{}' must name a topic",
+                node.id
+            )).node(&node.id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that Go identifiers generated from node labels do not collide: activities named after
+/// labels that normalize alike but differ, or after the same label on nodes of different types,
+/// and decision tables sharing an evaluation function. Activities with an explicit
+/// `activity_type` are meant to share it and are not compared.
+pub fn validate_identifiers(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut generated: Vec<(String, Vec<&WorkflowNode>)> = vec![];
+    for node in &definition.nodes {
+        let Some(identifier) = label_identifier(node) else { continue };
+        match generated.iter_mut().find(|(name, _)| *name == identifier) {
+            Some((_, nodes)) => nodes.push(node),
+            None => generated.push((identifier, vec![node])),
+        }
+    }
+
+    let collisions: Vec<(&str, &[&WorkflowNode])> = generated.iter()
+        .filter(|(_, nodes)| {
+            nodes[1..].iter().any(|n| {
+                matches!(n.node_type, NodeType::DecisionTable) || n.label != nodes[0].label
+                    || std::mem::discriminant(&n.node_type) != std::mem::discriminant(&nodes[0].node_type)
+            })
+        })
+        .map(|(name, nodes)| (name.as_str(), nodes.as_slice()))
+        .collect();
+    let Some((_, first)) = collisions.first() else {
+        return Ok(());
+    };
+    let described: Vec<String> = collisions.iter()
+        .map(|(name, nodes)| {
+            let sources: Vec<String> = nodes.iter().map(|n| format!("'{}' (label \"{}\")", n.id, n.label)).collect();
+            format!("{} from nodes {}", name, sources.join(", "))
+        })
+        .collect();
+    Err(CompilerError::ValidationError(Diagnostic::new(format!(
+        "Generated Go identifiers collide: {}; rename the nodes or give each activity an explicit activity_type",
+        described.join("; ")
+    )).node(&first[1].id)))
+}
+
+/// Check that session groups only contain activities and form one contiguous run
+pub fn validate_sessions(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut closed: Vec<&str> = Vec::new();
+    let mut current: Option<&str> = None;
+
+    for node in &definition.nodes {
+        let session = node.session.as_deref();
+        if let Some(name) = session {
+            if name.trim().is_empty() {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Node '{}' has an empty session name",
+                    node.id
+                )).node(&node.id)));
+            }
+            if !is_activity_node(node) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Node '{}' cannot join session '{}': only activity nodes run in worker sessions",
+                    node.id, name
+                )).node(&node.id)));
+            }
+            if closed.contains(&name) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Session '{}' is interrupted before node '{}'; session nodes must be consecutive",
+                    name, node.id
+                )).node(&node.id)));
+            }
+        }
+        if session != current {
+            if let Some(previous) = current {
+                closed.push(previous);
+            }
+            current = session;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check child workflow targets and input values, and reject contradictory close/cancellation
+/// combinations
+pub fn validate_sub_workflows(definition: &WorkflowDefinition, namespace_policy: &NamespacePolicy) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::SubWorkflow)) {
+        let config: SubWorkflowConfig = node.typed_config()?;
+
+        if config.workflow.trim().is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "SubWorkflow node '{}' must name the child workflow",
+                node.id
+            )).node(&node.id)));
+        }
+
+        if let Some(name) = config.input.iter().find(|(_, v)| child_input_expr(definition, v).is_none()).map(|(name, _)| name) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "SubWorkflow node '{}' input '{}' must be a string, number or boolean literal or a single {{{{variable}}}} reference",
+                node.id, name
+            )).node(&node.id)));
+        }
+
+        if let Some(namespace) = &config.namespace {
+            namespace_policy.check(namespace).map_err(|reason| CompilerError::ValidationError(Diagnostic::new(format!(
+                "SubWorkflow node '{}' namespace '{}' {}",
+                node.id, namespace, reason
+            )).node(&node.id)).with_code("namespace-policy"))?;
+            // The parent's task queue name means nothing in another namespace
+            if config.task_queue.is_none() {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "SubWorkflow node '{}' targets namespace '{}' and must set task_queue",
+                    node.id, namespace
+                )).node(&node.id)).with_code("namespace-policy"));
+            }
+        }
+
+        if !config.wait_for_completion {
+            // A fire-and-forget child would be killed as soon as the parent completes
+            if config.parent_close_policy == ParentClosePolicy::Terminate {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "SubWorkflow node '{}' does not wait for completion, so its parent_close_policy cannot be terminate",
+                    node.id
+                )).node(&node.id)));
+            }
+            if config.cancellation_type == ChildCancellationType::WaitCancellationCompleted {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "SubWorkflow node '{}' cannot wait for cancellation without waiting for completion",
+                    node.id
+                )).node(&node.id)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that SubWorkflow nodes start one of the `known` workflows, by name or generated
+/// workflow type, and map every input the child needs and none it does not declare. A
+/// child needs the variables it marks required or gives no default value. Nothing is
+/// checked when no workflows are known, nor for children started in another namespace.
+pub fn validate_sub_workflow_references(definition: &WorkflowDefinition, known: &[WorkflowDefinition]) -> Result<(), CompilerError> {
+    if known.is_empty() {
+        return Ok(());
+    }
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::SubWorkflow)) {
+        let config: SubWorkflowConfig = node.typed_config()?;
+        if config.namespace.is_some() {
+            continue;
+        }
+        let Some(child) = known.iter().find(|w| w.name == config.workflow || to_pascal_case(&w.name) == config.workflow) else {
+            let mut names: Vec<&str> = known.iter().map(|w| w.name.as_str()).collect();
+            names.sort_unstable();
+            names.dedup();
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "SubWorkflow node '{}' starts workflow '{}', which is not among the known workflows: {}",
+                node.id, config.workflow, names.join(", ")
+            )).node(&node.id)));
+        };
+        let missing: Vec<&str> = child.variables.iter()
+            .filter(|v| v.default_value.is_none() || v.schema.as_ref().is_some_and(|s| s.required))
+            .filter(|v| !config.input.contains_key(&v.name))
+            .map(|v| v.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "SubWorkflow node '{}' does not map the inputs workflow '{}' needs: '{}'",
+                node.id, child.name, missing.join("', '")
+            )).node(&node.id)));
+        }
+        let unknown: Vec<&str> = config.input.keys()
+            .filter(|name| !child.variables.iter().any(|v| &v.name == *name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "SubWorkflow node '{}' maps inputs workflow '{}' does not declare: '{}'",
+                node.id, child.name, unknown.join("', '")
+            )).node(&node.id)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a name is a legal Nexus endpoint name: a letter, then letters, digits, `-` or `_`
+fn is_nexus_endpoint_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    name.len() <= 200
+        && matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Check that every Nexus operation names a complete endpoint/service/operation target
+pub fn validate_nexus_operations(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::NexusOperation)) {
+        let config: NexusOperationConfig = node.typed_config()?;
+
+        for (field, value) in [("endpoint", &config.endpoint), ("service", &config.service), ("operation", &config.operation)] {
+            if value.trim().is_empty() {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Nexus operation node '{}' must set {}",
+                    node.id, field
+                )).node(&node.id)));
+            }
+        }
+        if !is_nexus_endpoint_name(&config.endpoint) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Nexus operation node '{}' endpoint '{}' is not a valid endpoint name",
+                node.id, config.endpoint
+            )).node(&node.id)));
+        }
+        if let Some(raw) = &config.schedule_to_close_timeout {
+            if duration::parse_field(raw, "schedule_to_close_timeout", Some(&node.id))?.is_zero() {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Nexus operation node '{}' schedule_to_close_timeout must be positive",
+                    node.id
+                )).node(&node.id)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check scope membership and that cancel edges connect a valid trigger to a scope
+pub fn validate_cancellation_scopes(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let mut claimed: Vec<&str> = Vec::new();
+    for node in definition.nodes.iter().filter(|n| matches!(n.node_type, NodeType::CancellationScope)) {
+        let config: CancellationScopeConfig = node.typed_config()?;
+        if config.members.is_empty() {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Cancellation scope '{}' has no members",
+                node.id
+            )).node(&node.id)));
+        }
+        for id in &config.members {
+            let member = graph::find_node(definition, id).ok_or_else(|| CompilerError::ValidationError(Diagnostic::new(format!(
+                "Cancellation scope '{}' references unknown node '{}'",
+                node.id, id
+            )).node(&node.id)))?;
+            if !is_activity_node(member) || member.session.is_some() {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Cancellation scope '{}' member '{}' must be an activity outside any worker session",
+                    node.id, id
+                )).node(&node.id)));
+            }
+            if claimed.contains(&member.id.as_str()) {
+                return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                    "Node '{}' belongs to more than one cancellation scope",
+                    id
+                )).node(id)));
+            }
+            claimed.push(&member.id);
+        }
+    }
+
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Cancel) {
+        let target = graph::find_node(definition, &edge.target);
+        if !matches!(target.map(|n| &n.node_type), Some(NodeType::CancellationScope)) {
+            return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Cancel edge '{}' must target a cancellation scope",
+                edge.id
+            )).edge(&edge.id)));
+        }
+        match graph::find_node(definition, &edge.source) {
+            Some(source) if matches!(source.node_type, NodeType::WaitSignal) => {
+                let config: WaitSignalConfig = source.typed_config()?;
+                if config.signal.trim().is_empty() {
+                    return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                        "WaitSignal node '{}' must name the signal it waits for",
+                        source.id
+                    )).node(&source.id)));
+                }
+            }
+            Some(source) if is_activity_node(source) => {}
+            _ => return Err(CompilerError::ValidationError(Diagnostic::new(format!(
+                "Cancel edge '{}' must start at a WaitSignal or activity node",
+                edge.id
+            )).edge(&edge.id))),
+        }
+    }
+
+    Ok(())
+}
+
+/// Temporal rejects workflow task timeouts above two minutes
+const MAX_WORKFLOW_TASK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Check workflow-level timeouts parse and are mutually consistent
+pub fn validate_workflow_timeouts(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    let Some(timeouts) = &definition.timeouts else {
+        return Ok(());
+    };
+
+    let parse = |name: &str, value: &Option<String>| -> Result<Option<Duration>, CompilerError> {
+        let Some(raw) = value.as_deref() else {
+            return Ok(None);
+        };
+        let timeout = duration::parse_field(raw, &format!("workflow {} timeout", name), None)?;
+        if timeout.is_zero() {
+            return Err(CompilerError::ValidationError(format!("Workflow {} timeout must be positive", name).into()));
+        }
+        Ok(Some(timeout))
+    };
+    let execution = parse("execution", &timeouts.execution)?;
+    let run = parse("run", &timeouts.run)?;
+    let task = parse("task", &timeouts.task)?;
+
+    if let (Some(execution), Some(run)) = (execution, run) {
+        if run > execution {
+            return Err(CompilerError::ValidationError(
+                "Workflow run timeout cannot exceed the execution timeout".into(),
+            ));
+        }
+    }
+    if let Some(task) = task {
+        if task > MAX_WORKFLOW_TASK_TIMEOUT {
+            return Err(CompilerError::ValidationError(
+                "Workflow task timeout cannot exceed 2m".into(),
+            ));
+        }
+        if run.or(execution).is_some_and(|limit| task > limit) {
+            return Err(CompilerError::ValidationError(
+                "Workflow task timeout cannot exceed the run timeout".into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Temporal rejects schedule catchup windows shorter than ten seconds
+const MIN_CATCHUP_WINDOW: Duration = Duration::from_secs(10);
+
+/// Check schedule triggers carry a valid cron spec and a usable catchup window
+pub fn validate_schedule_triggers(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for schedule in schedule_triggers(definition)? {
+        if schedule.cron.trim().is_empty() {
+            return Err(CompilerError::ValidationError(
+                "Schedule trigger must define a cron expression".into(),
+            ));
+        }
+        if let Err(problem) = triggers::check_cron(&schedule.cron) {
+            return Err(CompilerError::ValidationError(format!(
+                "Schedule cron expression '{}' is invalid: {}",
+                schedule.cron, problem
+            ).into()));
+        }
+        if let Some(raw) = &schedule.catchup_window {
+            if duration::parse_field(raw, "schedule catchup_window", None)? < MIN_CATCHUP_WINDOW {
+                return Err(CompilerError::ValidationError(format!(
+                    "Schedule catchup_window '{}' must be at least 10s",
+                    raw
+                ).into()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check every free-form duration on nodes: retry intervals and timer durations
+pub fn validate_node_durations(definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    for node in &definition.nodes {
+        if let Some(retries) = &node.retries {
+            duration::parse_field(&retries.initial_interval, "retries.initial_interval", Some(&node.id))?;
+            duration::parse_field(&retries.max_interval, "retries.max_interval", Some(&node.id))?;
+        }
+        if let Some(timeouts) = &node.timeouts {
+            let fields = [
+                ("timeouts.start_to_close", &timeouts.start_to_close),
+                ("timeouts.schedule_to_close", &timeouts.schedule_to_close),
+                ("timeouts.heartbeat", &timeouts.heartbeat),
+            ];
+            for (field, raw) in fields {
+                if let Some(raw) = raw {
+                    duration::parse_field(raw, field, Some(&node.id))?;
+                }
+            }
+        }
+        if matches!(node.node_type, NodeType::WaitTimer) {
+            let config: WaitTimerConfig = node.typed_config()?;
+            duration::parse_field(&config.duration, "duration", Some(&node.id))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Upper bound on retry attempts; beyond this a retry policy is almost certainly a mistake
+const MAX_RETRY_ATTEMPTS: u32 = 100;
+
+/// Check a retry policy's attempts, backoff coefficient and intervals, naming the field at fault
+pub fn check_retry_policy(retries: &RetryPolicy) -> Result<(), String> {
+    if retries.max_attempts == 0 || retries.max_attempts > MAX_RETRY_ATTEMPTS {
+        return Err(format!("retries.max_attempts must be between 1 and {}", MAX_RETRY_ATTEMPTS));
+    }
+    if retries.backoff_coefficient.is_nan() || retries.backoff_coefficient < 1.0 {
+        return Err("retries.backoff_coefficient must be at least 1.0".to_string());
+    }
+    let interval = |field: &str, raw: &str| duration::parse_duration(raw).map_err(|e| format!("{} '{}': {}", field, raw, e));
+    let initial = interval("retries.initial_interval", &retries.initial_interval)?;
+    let max = interval("retries.max_interval", &retries.max_interval)?;
+    if initial.is_zero() {
+        return Err("retries.initial_interval must be positive".to_string());
+    }
+    if max < initial {
+        return Err("retries.max_interval must not be shorter than retries.initial_interval".to_string());
+    }
+    Ok(())
+}
+
+/// Check retry policies are internally consistent, warning when retries could repeat side effects
+pub fn validate_retry_policies(definition: &WorkflowDefinition) -> Result<Vec<String>, CompilerError> {
+    let mut warnings = Vec::new();
+
+    for node in &definition.nodes {
+        let Some(retries) = &node.retries else { continue };
+
+        check_retry_policy(retries).map_err(|e| CompilerError::ValidationError(Diagnostic::new(format!("Node '{}' {}", node.id, e)).node(&node.id)))?;
+
+        if retries.max_attempts > 1 && !is_idempotent(node) {
+            warnings.push(format!(
+                "Node '{}' retries a non-idempotent operation without an idempotency_key; retries may repeat side effects",
+                node.id
+            ));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Whether re-running the node is safe: either inherently idempotent or guarded by a key
+fn is_idempotent(node: &WorkflowNode) -> bool {
+    if node.config.get("idempotency_key").is_some_and(|k| !k.is_null()) {
+        return true;
+    }
+    match node.node_type {
+        NodeType::Notification | NodeType::PublishEvent => false,
+        NodeType::HttpCall => {
+            let method = node.typed_config::<HttpCallConfig>().map(|c| c.method).unwrap_or_default();
+            !matches!(method.to_ascii_uppercase().as_str(), "POST" | "PATCH")
+        }
+        _ => true,
+    }
+}
@@ -0,0 +1,359 @@
+//! Service configuration loaded from the JSON file named by `COMPILER_CONFIG`, at startup
+//! and again on SIGHUP or `POST /api/v1/admin/reload`
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::EnvFilter;
+
+use crate::compiler::deprecations::{parse_version, DeprecationRule};
+use crate::compiler::duration::parse_duration;
+use crate::compiler::lint::{self, LintLevel, LintLevels};
+use crate::compiler::rules::{self, Severity};
+use crate::compiler::plugins::{load_manifests, PluginManifest, PluginRegistry};
+use crate::compiler::validator::check_retry_policy;
+use crate::error::CompilerError;
+use crate::{ActivityTimeouts, NodeType, RetryPolicy};
+
+/// Compiler service configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompilerConfig {
+    pub namespace_policy: NamespacePolicy,
+    /// Tracing filter directive, e.g. `info` or `workflow_compiler_server=debug`
+    pub log_level: Option<String>,
+    /// Deprecated node types and config fields to report
+    pub deprecations: Vec<DeprecationRule>,
+    /// DSL version the service enforces; deprecated constructs removed in it or earlier stop compiling
+    pub dsl_version: Option<String>,
+    /// Flag keys FeatureFlag nodes may reference; empty allows any well-formed key
+    pub known_feature_flags: Vec<String>,
+    /// Size and complexity limits enforced when validating a definition
+    pub budgets: Budgets,
+    /// Time and memory each compilation may use
+    pub limits: CompilationLimits,
+    /// Compilations each priority lane runs at once
+    pub lanes: LaneConcurrency,
+    /// Named defaults for the retries and timeouts of activity nodes
+    pub profiles: ExecutionProfiles,
+    /// Profile applied to activity nodes that name none; unset applies no profile
+    pub default_profile: Option<String>,
+    /// Directory of plugin manifest JSON files defining extra node types
+    pub plugin_dir: Option<String>,
+    /// Manifests read from `plugin_dir`
+    #[serde(skip)]
+    pub plugins: Vec<PluginManifest>,
+    /// Levels of lint rules by code, overriding their default severity
+    pub lints: LintLevels,
+    /// Named validation strictness settings compile options may select
+    pub validation_profiles: ValidationProfiles,
+    /// Validation profile used when compile options name none; unset validates without one,
+    /// as the built-in `standard` profile does
+    pub default_validation_profile: Option<String>,
+}
+
+/// Definition size and complexity limits; a `null` limit is not enforced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Budgets {
+    pub max_nodes: Option<usize>,
+    pub max_edges: Option<usize>,
+    /// Most branches a single ParallelGateway may fork
+    pub max_fan_out: Option<usize>,
+    /// Most ParallelGateway forks open at once on any path
+    pub max_nesting_depth: Option<usize>,
+    /// Most history events a run is estimated to record
+    pub max_history_events: Option<u64>,
+    /// Iterations assumed for each loop when estimating history events
+    pub loop_iterations: u64,
+}
+
+impl Default for Budgets {
+    fn default() -> Self {
+        Self {
+            max_nodes: Some(2_000),
+            max_edges: Some(10_000),
+            max_fan_out: Some(256),
+            max_nesting_depth: Some(32),
+            max_history_events: None,
+            loop_iterations: 10,
+        }
+    }
+}
+
+/// Wall-clock and memory limits for a single compilation or validation; a `null` limit is
+/// not enforced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompilationLimits {
+    pub timeout_ms: Option<u64>,
+    /// Heap the compilation may allocate beyond what its thread held when it started
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl Default for CompilationLimits {
+    fn default() -> Self {
+        Self { timeout_ms: Some(10_000), max_memory_bytes: Some(512 * 1024 * 1024) }
+    }
+}
+
+/// Jobs the interactive and batch lanes each run at once
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaneConcurrency {
+    /// Editor requests: single compiles and validations
+    pub interactive: usize,
+    /// Bulk work such as revalidations and recompiles marked `X-Compile-Lane: batch`
+    pub batch: usize,
+}
+
+impl Default for LaneConcurrency {
+    fn default() -> Self {
+        Self { interactive: 8, batch: 2 }
+    }
+}
+
+/// Execution profiles by name; a configured map replaces the built-in `critical`,
+/// `best-effort` and `bulk` profiles entirely
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExecutionProfiles(pub BTreeMap<String, ExecutionProfile>);
+
+/// Retry policy and timeouts an execution profile supplies to nodes that leave them unset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionProfile {
+    pub retries: Option<RetryPolicy>,
+    pub timeouts: ActivityTimeouts,
+}
+
+fn profile(retries: (u32, &str, &str, f64), start_to_close: &str, schedule_to_close: Option<&str>, heartbeat: Option<&str>) -> ExecutionProfile {
+    let (max_attempts, initial_interval, max_interval, backoff_coefficient) = retries;
+    ExecutionProfile {
+        retries: Some(RetryPolicy {
+            max_attempts,
+            initial_interval: initial_interval.to_string(),
+            max_interval: max_interval.to_string(),
+            backoff_coefficient,
+        }),
+        timeouts: ActivityTimeouts {
+            start_to_close: Some(start_to_close.to_string()),
+            schedule_to_close: schedule_to_close.map(str::to_string),
+            heartbeat: heartbeat.map(str::to_string),
+        },
+    }
+}
+
+impl Default for ExecutionProfiles {
+    fn default() -> Self {
+        Self(BTreeMap::from([
+            // Retried patiently, with heartbeats catching stuck attempts early
+            ("critical".to_string(), profile((10, "1s", "1m", 2.0), "5m", Some("1h"), Some("30s"))),
+            // A single short attempt
+            ("best-effort".to_string(), profile((1, "1s", "1s", 1.0), "30s", None, None)),
+            // Long-running batch work, retried slowly
+            ("bulk".to_string(), profile((5, "10s", "10m", 2.0), "1h", Some("24h"), Some("5m"))),
+        ]))
+    }
+}
+
+impl ExecutionProfile {
+    /// Check the profile's retry policy is consistent and its timeouts parse
+    fn check(&self) -> Result<(), String> {
+        if let Some(retries) = &self.retries {
+            check_retry_policy(retries)?;
+        }
+        let timeouts = [
+            ("timeouts.start_to_close", &self.timeouts.start_to_close),
+            ("timeouts.schedule_to_close", &self.timeouts.schedule_to_close),
+            ("timeouts.heartbeat", &self.timeouts.heartbeat),
+        ];
+        let timeouts = timeouts.into_iter().filter_map(|(field, raw)| raw.as_ref().map(|raw| (field, raw)));
+        for (field, raw) in timeouts {
+            parse_duration(raw).map_err(|e| format!("{} '{}': {}", field, raw, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Validation profiles by name; a configured map replaces the built-in `strict`, `standard`
+/// and `lenient` profiles entirely
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidationProfiles(pub BTreeMap<String, ValidationProfile>);
+
+/// How strictly a definition is validated beyond the checks every definition passes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationProfile {
+    /// Fail validation on warnings as well as errors
+    pub warnings_as_errors: bool,
+    /// Lint rules also checked during validation, at these levels; an `error` fails it
+    pub lints: LintLevels,
+    /// Warning and info rules whose findings are dropped
+    pub ignore: Vec<String>,
+}
+
+impl Default for ValidationProfiles {
+    fn default() -> Self {
+        let lints = |retry| LintLevels::from([
+            ("activity-missing-retry".to_string(), retry),
+            ("http-call-without-timeout".to_string(), LintLevel::Warning),
+            ("unlabeled-decision-branch".to_string(), LintLevel::Warning),
+        ]);
+        let ignore = ["decision-not-exhaustive", "identifier-sanitized", "non-idempotent-retry"];
+        Self(BTreeMap::from([
+            // Every warning and lint finding blocks compilation
+            ("strict".to_string(), ValidationProfile { warnings_as_errors: true, lints: lints(LintLevel::Error), ignore: vec![] }),
+            ("standard".to_string(), ValidationProfile::default()),
+            // Advisory warnings are left out
+            ("lenient".to_string(), ValidationProfile { ignore: ignore.map(str::to_string).to_vec(), ..Default::default() }),
+        ]))
+    }
+}
+
+impl ValidationProfile {
+    /// Check the profile's lints are lint rules and its ignored codes warning or info rules
+    fn check(&self) -> Result<(), String> {
+        lint::check_levels(&self.lints)?;
+        for code in &self.ignore {
+            match rules::find(code) {
+                Some(rule) if !matches!(rule.severity, Severity::Error) => {}
+                Some(_) => return Err(format!("ignore lists error rule '{}', which cannot be ignored", code)),
+                None => return Err(format!("ignore lists unknown rule '{}'", code)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Naming policy for Temporal namespaces targeted by cross-namespace nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NamespacePolicy {
+    /// Regular expression every namespace must fully match, e.g. `^[a-z]+-(prod|staging)$`
+    pub pattern: Option<String>,
+    /// Explicit allowlist; an empty list allows any namespace matching the pattern
+    pub allowed: Vec<String>,
+}
+
+impl CompilerConfig {
+    /// Load the configuration named by `COMPILER_CONFIG`, or defaults when unset
+    pub fn load() -> Result<Self, CompilerError> {
+        match std::env::var("COMPILER_CONFIG") {
+            Ok(path) => Self::from_file(&path),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, CompilerError> {
+        let raw = std::fs::read_to_string(path)?;
+        let mut config: Self = serde_json::from_str(&raw)
+            .map_err(|e| CompilerError::ParseError(format!("Invalid compiler config '{}': {}", path, e)))?;
+
+        if let Some(pattern) = &config.namespace_policy.pattern {
+            Regex::new(pattern).map_err(|e| {
+                CompilerError::ParseError(format!("Invalid namespace_policy.pattern in '{}': {}", path, e))
+            })?;
+        }
+
+        if let Some(level) = &config.log_level {
+            EnvFilter::try_new(level).map_err(|e| {
+                CompilerError::ParseError(format!("Invalid log_level in '{}': {}", path, e))
+            })?;
+        }
+
+        if let Some(dir) = &config.plugin_dir {
+            config.plugins = load_manifests(dir)?;
+            for manifest in &config.plugins {
+                manifest.check(&config.profiles).map_err(|e| {
+                    CompilerError::ParseError(format!("Invalid plugin '{}' in '{}': {}", manifest.node_type, dir, e))
+                })?;
+            }
+            PluginRegistry::with_manifests(&[], &config.plugins)?;
+        }
+
+        for rule in &config.deprecations {
+            let node_type = serde_json::from_value::<NodeType>(serde_json::Value::String(rule.node_type.clone()));
+            let known = match node_type {
+                Ok(NodeType::Plugin(name)) => config.plugins.iter().any(|p| p.node_type == name),
+                Ok(_) => true,
+                Err(_) => false,
+            };
+            if !known {
+                return Err(CompilerError::ParseError(format!("Unknown node type '{}' in deprecations in '{}'", rule.node_type, path)));
+            }
+            if parse_version(&rule.removal_version).is_none() {
+                return Err(CompilerError::ParseError(format!(
+                    "Invalid removal_version '{}' in deprecations in '{}'",
+                    rule.removal_version, path
+                )));
+            }
+        }
+
+        if config.lanes.interactive == 0 || config.lanes.batch == 0 {
+            return Err(CompilerError::ParseError(format!("lanes in '{}' must allow at least one job in each lane", path)));
+        }
+
+        if let Some(version) = config.dsl_version.as_deref().filter(|v| parse_version(v).is_none()) {
+            return Err(CompilerError::ParseError(format!("Invalid dsl_version '{}' in '{}'", version, path)));
+        }
+
+        for (name, profile) in &config.profiles.0 {
+            profile.check().map_err(|e| {
+                CompilerError::ParseError(format!("Invalid execution profile '{}' in '{}': {}", name, path, e))
+            })?;
+        }
+        if let Some(name) = config.default_profile.as_deref().filter(|name| !config.profiles.0.contains_key(*name)) {
+            return Err(CompilerError::ParseError(format!("default_profile '{}' in '{}' is not a configured profile", name, path)));
+        }
+
+        lint::check_levels(&config.lints).map_err(|e| CompilerError::ParseError(format!("Invalid lints in '{}': {}", path, e)))?;
+
+        for (name, profile) in &config.validation_profiles.0 {
+            profile.check().map_err(|e| {
+                CompilerError::ParseError(format!("Invalid validation profile '{}' in '{}': {}", name, path, e))
+            })?;
+        }
+        let default_validation_profile = config.default_validation_profile.as_deref();
+        if let Some(name) = default_validation_profile.filter(|name| !config.validation_profiles.0.contains_key(*name)) {
+            return Err(CompilerError::ParseError(format!(
+                "default_validation_profile '{}' in '{}' is not a configured validation profile",
+                name, path
+            )));
+        }
+
+        Ok(config)
+    }
+
+    /// Tracing filter for the configured log level, defaulting to `info`
+    pub fn log_filter(&self) -> EnvFilter {
+        EnvFilter::try_new(self.log_level.as_deref().unwrap_or("info")).unwrap_or_else(|_| EnvFilter::new("info"))
+    }
+}
+
+impl NamespacePolicy {
+    /// Check a namespace against Temporal's naming rules and the configured policy
+    pub fn check(&self, namespace: &str) -> Result<(), String> {
+        let mut chars = namespace.chars();
+        let well_formed = namespace.len() <= 255
+            && matches!(chars.next(), Some(c) if c.is_ascii_alphanumeric())
+            && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+        if !well_formed {
+            return Err("must start with a letter or digit and contain only letters, digits, '.', '-' and '_'".into());
+        }
+
+        if let Some(pattern) = &self.pattern {
+            let regex = Regex::new(pattern).map_err(|e| format!("namespace policy pattern is invalid: {}", e))?;
+            if !regex.find(namespace).is_some_and(|m| m.start() == 0 && m.end() == namespace.len()) {
+                return Err(format!("does not match the namespace policy pattern '{}'", pattern));
+            }
+        }
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|a| a == namespace) {
+            return Err("is not in the namespace policy allowlist".into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+//! Direct deployment of compiled workflows to a Temporal cluster
+//!
+//! Talks to the frontend through the Temporal HTTP API, the JSON mapping of the
+//! gRPC WorkflowService and OperatorService.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::compiler::{codegen, duration};
+use crate::error::CompilerError;
+use crate::{to_pascal_case, CompiledWorkflow, OverlapPolicy, WorkflowDefinition};
+
+/// Temporal frontend HTTP API connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemporalConnection {
+    /// Base URL of the frontend HTTP API, e.g. `http://localhost:7243`
+    pub address: String,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    /// Bearer token for Temporal Cloud API keys
+    pub api_key: Option<String>,
+}
+
+fn default_namespace() -> String {
+    "default".to_string()
+}
+
+/// Indexed type of a custom search attribute
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchAttributeType {
+    Keyword,
+    Text,
+    Int,
+    Double,
+    Bool,
+    Datetime,
+    KeywordList,
+}
+
+impl SearchAttributeType {
+    fn proto_name(self) -> &'static str {
+        match self {
+            SearchAttributeType::Keyword => "INDEXED_VALUE_TYPE_KEYWORD",
+            SearchAttributeType::Text => "INDEXED_VALUE_TYPE_TEXT",
+            SearchAttributeType::Int => "INDEXED_VALUE_TYPE_INT",
+            SearchAttributeType::Double => "INDEXED_VALUE_TYPE_DOUBLE",
+            SearchAttributeType::Bool => "INDEXED_VALUE_TYPE_BOOL",
+            SearchAttributeType::Datetime => "INDEXED_VALUE_TYPE_DATETIME",
+            SearchAttributeType::KeywordList => "INDEXED_VALUE_TYPE_KEYWORD_LIST",
+        }
+    }
+}
+
+/// Smoke execution started after deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmokeRun {
+    pub workflow_id: String,
+    pub run_id: String,
+}
+
+/// Outcome of a deployment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeployReport {
+    pub search_attributes: Vec<String>,
+    pub schedules: Vec<String>,
+    /// Build ID promoted to the task queue default
+    pub build_id: Option<String>,
+    pub smoke_run: Option<SmokeRun>,
+}
+
+/// Deploys one compiled workflow to one namespace
+pub struct Deployer<'a> {
+    http: &'a reqwest::Client,
+    connection: &'a TemporalConnection,
+}
+
+/// Encode a value as a `json/plain` Temporal payload
+fn json_payload(value: &Value) -> Value {
+    json!({
+        "metadata": { "encoding": STANDARD.encode("json/plain") },
+        "data": STANDARD.encode(value.to_string()),
+    })
+}
+
+/// Render a duration in protobuf JSON form, e.g. `90s` or `1.5s`
+fn proto_duration(d: Duration) -> String {
+    if d.subsec_nanos() == 0 {
+        format!("{}s", d.as_secs())
+    } else {
+        let fraction = format!("{:09}", d.subsec_nanos());
+        format!("{}.{}s", d.as_secs(), fraction.trim_end_matches('0'))
+    }
+}
+
+impl<'a> Deployer<'a> {
+    pub fn new(http: &'a reqwest::Client, connection: &'a TemporalConnection) -> Self {
+        Self { http, connection }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/api/v1/namespaces/{}{}",
+            self.connection.address.trim_end_matches('/'),
+            self.connection.namespace,
+            path
+        )
+    }
+
+    /// POST a request, returning the response body; `tolerate` lists statuses treated as success
+    async fn post(&self, path: &str, body: &Value, tolerate: &[reqwest::StatusCode]) -> Result<(reqwest::StatusCode, Value), CompilerError> {
+        let mut request = self.http.post(self.url(path)).json(body);
+        if let Some(key) = &self.connection.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await
+            .map_err(|e| CompilerError::DeploymentError(format!("Temporal request to {} failed: {}", path, e)))?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() && !tolerate.contains(&status) {
+            let message = body.get("message").and_then(Value::as_str).unwrap_or("no error message");
+            return Err(CompilerError::DeploymentError(format!(
+                "Temporal rejected {} with {}: {}",
+                path, status, message
+            )));
+        }
+
+        Ok((status, body))
+    }
+
+    /// Add custom search attributes; attributes that already exist are left as they are
+    pub async fn upsert_search_attributes(&self, attributes: &BTreeMap<String, SearchAttributeType>) -> Result<Vec<String>, CompilerError> {
+        if attributes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let types: BTreeMap<&str, &str> = attributes.iter().map(|(name, t)| (name.as_str(), t.proto_name())).collect();
+        self.post("/search-attributes", &json!({ "searchAttributes": types }), &[reqwest::StatusCode::CONFLICT]).await?;
+
+        Ok(attributes.keys().cloned().collect())
+    }
+
+    /// Create or update one schedule per Schedule trigger, mirroring the generated starter code
+    pub async fn register_schedules(&self, definition: &WorkflowDefinition, compiled: &CompiledWorkflow) -> Result<Vec<String>, CompilerError> {
+        let package_name = &compiled.metadata.package_name;
+        let mut action = json!({
+            "workflowId": format!("{package_name}-scheduled"),
+            "workflowType": { "name": to_pascal_case(&definition.name) },
+            "taskQueue": { "name": format!("{package_name}-task-queue") },
+        });
+        if let Some(timeouts) = &definition.timeouts {
+            for (field, value) in [
+                ("workflowExecutionTimeout", &timeouts.execution),
+                ("workflowRunTimeout", &timeouts.run),
+                ("workflowTaskTimeout", &timeouts.task),
+            ] {
+                if let Some(raw) = value {
+                    action[field] = json!(proto_duration(duration::parse_field(raw, field, None)?));
+                }
+            }
+        }
+
+        let mut ids = Vec::new();
+        for (i, schedule) in codegen::schedule_triggers(definition)?.iter().enumerate() {
+            let overlap = match schedule.overlap {
+                OverlapPolicy::Skip => "SCHEDULE_OVERLAP_POLICY_SKIP",
+                OverlapPolicy::BufferOne => "SCHEDULE_OVERLAP_POLICY_BUFFER_ONE",
+                OverlapPolicy::BufferAll => "SCHEDULE_OVERLAP_POLICY_BUFFER_ALL",
+                OverlapPolicy::CancelOther => "SCHEDULE_OVERLAP_POLICY_CANCEL_OTHER",
+                OverlapPolicy::TerminateOther => "SCHEDULE_OVERLAP_POLICY_TERMINATE_OTHER",
+                OverlapPolicy::AllowAll => "SCHEDULE_OVERLAP_POLICY_ALLOW_ALL",
+            };
+            let mut policies = json!({ "overlapPolicy": overlap });
+            if let Some(raw) = &schedule.catchup_window {
+                policies["catchupWindow"] = json!(proto_duration(duration::parse_field(raw, "schedule catchup_window", None)?));
+            }
+
+            let id = format!("{package_name}-schedule-{i}");
+            let body = json!({
+                "schedule": {
+                    "spec": { "cronString": [schedule.cron] },
+                    "action": { "startWorkflow": action },
+                    "policies": policies,
+                    "state": { "paused": schedule.paused, "notes": schedule.note.clone().unwrap_or_default() },
+                },
+                "requestId": Uuid::new_v4().to_string(),
+            });
+
+            let (status, _) = self.post(&format!("/schedules/{id}"), &body, &[reqwest::StatusCode::CONFLICT]).await?;
+            if status == reqwest::StatusCode::CONFLICT {
+                self.post(&format!("/schedules/{id}/update"), &body, &[]).await?;
+            }
+            ids.push(id);
+        }
+
+        Ok(ids)
+    }
+
+    /// Add the compiled Build ID as the new default set on the workflow's task queue,
+    /// so new executions move to it while running ones stay on their original workers
+    pub async fn promote_build_id(&self, compiled: &CompiledWorkflow) -> Result<String, CompilerError> {
+        let Some(build_id) = &compiled.metadata.build_id else {
+            return Err(CompilerError::DeploymentError(
+                "Build ID promotion requires compiling with worker_versioning enabled".into(),
+            ));
+        };
+
+        let task_queue = format!("{}-task-queue", compiled.metadata.package_name);
+        let body = json!({ "taskQueue": task_queue, "addNewBuildIdInNewDefaultSet": build_id });
+        self.post(&format!("/task-queues/{task_queue}/update-build-id-compatibility"), &body, &[]).await?;
+
+        Ok(build_id.clone())
+    }
+
+    /// Start a single execution with the given input to smoke-test the deployed worker
+    pub async fn start_smoke_run(&self, definition: &WorkflowDefinition, compiled: &CompiledWorkflow, input: &Value) -> Result<SmokeRun, CompilerError> {
+        let package_name = &compiled.metadata.package_name;
+        let workflow_id = format!("{package_name}-smoke-{}", Uuid::new_v4());
+        let body = json!({
+            "workflowId": workflow_id,
+            "workflowType": { "name": to_pascal_case(&definition.name) },
+            "taskQueue": { "name": format!("{package_name}-task-queue") },
+            "input": { "payloads": [json_payload(input)] },
+            "requestId": Uuid::new_v4().to_string(),
+        });
+
+        let (_, response) = self.post(&format!("/workflows/{workflow_id}"), &body, &[]).await?;
+        let run_id = response.get("runId").and_then(Value::as_str).unwrap_or_default().to_string();
+
+        Ok(SmokeRun { workflow_id, run_id })
+    }
+}
@@ -0,0 +1,591 @@
+//! BPMN 2.0 import: each process of a BPMN XML document as a workflow definition
+//!
+//! Flow elements map onto nodes under their BPMN IDs and sequence flows onto edges, placed
+//! where the diagram section draws them. Service, send and script tasks become Activity nodes
+//! running their Zeebe job type, or HttpCall nodes for Camunda's REST connector; call
+//! activities become subworkflows, exclusive gateways decisions with their FEEL or JUEL
+//! conditions rewritten in the DSL's expression syntax, parallel gateways forks and joins,
+//! timer catch events timers, and message and signal catch events signal waits. Timer,
+//! message and signal start events become triggers. Gateways that only merge branches are
+//! dropped, their incoming flows continuing to what follows them.
+//!
+//! Elements without an equivalent are reported as unmapped. Those on the flow are kept as
+//! Transform placeholders so the graph stays connected; boundary events and everything else
+//! off the main flow are left out.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::compiler::duration::parse_duration;
+use crate::compiler::plugins::ACTIVITY_TYPE_KEY;
+use crate::dsl::xml::{self, Element};
+use crate::error::CompilerError;
+use crate::import::{http_call_config, ImportedWorkflow, UnmappedStep, NODE_SPACING};
+use crate::{
+    to_pascal_case, NodeType, Position, RetryPolicy, ScheduleTriggerConfig, Trigger, TriggerType, Variable,
+    WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+/// Job type prefix of Camunda's outbound REST connector
+const HTTP_CONNECTOR: &str = "io.camunda:http-json";
+
+/// Merges through gateways followed before giving up on a cycle of them
+const MAX_BYPASS_DEPTH: usize = 64;
+
+struct SequenceFlow {
+    id: String,
+    source: String,
+    target: String,
+    name: Option<String>,
+    condition: Option<String>,
+}
+
+/// Process being translated
+struct Builder<'a> {
+    process: &'a Element,
+    /// Message and signal names by ID
+    events: &'a HashMap<String, String>,
+    positions: &'a HashMap<String, Position>,
+    flows: Vec<SequenceFlow>,
+    nodes: Vec<WorkflowNode>,
+    /// Merging elements left out, with the element their single outgoing flow leads to
+    bypassed: HashMap<String, String>,
+    triggers: Vec<Trigger>,
+    variables: BTreeSet<String>,
+    unmapped: Vec<UnmappedStep>,
+}
+
+/// Text of an expression element, without the `=` marking FEEL or the `${}` around JUEL
+fn expression_text(element: &Element) -> String {
+    let text = element.text.trim();
+    let text = text.strip_prefix('=').unwrap_or(text);
+    match text.strip_prefix("${").or_else(|| text.strip_prefix("#{")).and_then(|t| t.strip_suffix('}')) {
+        Some(juel) => juel.trim().to_string(),
+        None => text.trim().to_string(),
+    }
+}
+
+/// Edge condition in the DSL's syntax for a FEEL or JUEL condition, with the variables it reads
+pub(crate) fn dsl_condition(expression: &str) -> Result<(String, Vec<String>), String> {
+    let mut out = String::new();
+    let mut variables = vec![];
+    let mut chars = expression.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                out.push_str(&Value::String(text).to_string());
+            }
+            '=' => {
+                // FEEL compares with a single `=`
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                }
+                out.push_str(" == ");
+            }
+            '!' | '<' | '>' if chars.peek() == Some(&'=') => {
+                chars.next();
+                out.push_str(&format!(" {}= ", c));
+            }
+            '!' => out.push('!'),
+            '<' | '>' => out.push_str(&format!(" {} ", c)),
+            '&' | '|' => {
+                if chars.next() != Some(c) {
+                    return Err(format!("unsupported operator '{}'", c));
+                }
+                out.push_str(if c == '&' { " && " } else { " || " });
+            }
+            c if c.is_ascii_digit() => {
+                out.push(c);
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_digit() || **ch == '.') {
+                    out.push(ch);
+                    chars.next();
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_alphanumeric() || **ch == '_' || **ch == '.') {
+                    word.push(ch);
+                    chars.next();
+                }
+                let operator = match word.as_str() {
+                    "and" => " && ",
+                    "or" => " || ",
+                    "not" => "!",
+                    "eq" => " == ",
+                    "ne" => " != ",
+                    "lt" => " < ",
+                    "gt" => " > ",
+                    "le" => " <= ",
+                    "ge" => " >= ",
+                    "true" | "false" | "null" => {
+                        out.push_str(&word);
+                        continue;
+                    }
+                    _ => {
+                        variables.extend(word.split('.').next().map(str::to_string));
+                        out.push_str(&word);
+                        continue;
+                    }
+                };
+                out.push_str(operator);
+            }
+            c if c.is_whitespace() => out.push(' '),
+            '(' | ')' | '+' | '-' | '*' | '/' | '%' => out.push(c),
+            other => return Err(format!("unsupported character '{}'", other)),
+        }
+    }
+    Ok((out.split_whitespace().collect::<Vec<_>>().join(" "), variables))
+}
+
+/// Five-field cron expression for a timer cycle: a cron expression, in Zeebe's seconds-first
+/// form or not, or an unbounded ISO-8601 repetition of a period that divides a day or week
+fn schedule_cron(cycle: &str) -> Option<String> {
+    let cycle = cycle.trim();
+    let fields: Vec<&str> = cycle.split_whitespace().collect();
+    match fields.len() {
+        5 => return Some(fields.join(" ")),
+        6 if fields[0] == "0" => return Some(fields[1..].join(" ")),
+        _ => {}
+    }
+    let seconds = parse_duration(cycle.strip_prefix("R/")?).ok()?.as_secs();
+    match seconds {
+        0 => None,
+        604_800 => Some("0 0 * * 0".to_string()),
+        86_400 => Some("0 0 * * *".to_string()),
+        3_600 => Some("0 * * * *".to_string()),
+        60 => Some("* * * * *".to_string()),
+        s if s % 3_600 == 0 && 24 % (s / 3_600) == 0 => Some(format!("0 */{} * * *", s / 3_600)),
+        s if s % 60 == 0 && s < 3_600 && 60 % (s / 60) == 0 => Some(format!("*/{} * * * *", s / 60)),
+        _ => None,
+    }
+}
+
+/// Value of an HTTP connector input: a FEEL literal, or the raw text when it is not one
+fn connector_input(source: &str) -> Value {
+    let literal = source.strip_prefix('=').unwrap_or(source);
+    serde_json::from_str(literal).unwrap_or_else(|_| Value::String(literal.to_string()))
+}
+
+impl<'a> Builder<'a> {
+    fn unmap(&mut self, element: &Element, reason: impl Into<String>) {
+        let step = element.attribute("id").unwrap_or_default().to_string();
+        self.unmapped.push(UnmappedStep { step, app: element.name.clone(), reason: reason.into() });
+    }
+
+    fn incoming(&self, id: &str) -> usize {
+        self.flows.iter().filter(|f| f.target == id).count()
+    }
+
+    fn outgoing(&self, id: &str) -> Vec<String> {
+        self.flows.iter().filter(|f| f.source == id).map(|f| f.target.clone()).collect()
+    }
+
+    fn add(&mut self, element: &Element, node_type: NodeType, config: Value) -> &mut WorkflowNode {
+        let id = element.attribute("id").unwrap_or_default().to_string();
+        let fallback = match node_type {
+            NodeType::Start => "Start",
+            NodeType::End => "End",
+            _ => &id,
+        };
+        let label = element.attribute("name").map(str::trim).filter(|n| !n.is_empty()).unwrap_or(fallback).to_string();
+        let position = self.positions.get(&id).cloned()
+            .unwrap_or(Position { x: self.nodes.len() as f64 * NODE_SPACING, y: 0.0 });
+        let annotations = element.child("extensionElements")
+            .and_then(|e| e.child("properties"))
+            .map(|properties| {
+                properties.children_named("property")
+                    .filter_map(|p| Some((p.attribute("name")?.to_string(), p.attribute("value").unwrap_or_default().to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.nodes.push(WorkflowNode {
+            id,
+            node_type,
+            label,
+            config,
+            position,
+            retries: None,
+            session: None,
+            annotations,
+            timeouts: None,
+            profile: None,
+        });
+        self.nodes.last_mut().expect("node was just added")
+    }
+
+    /// Transform node standing in for an element with no equivalent
+    fn placeholder(&mut self, element: &Element, reason: impl Into<String>) {
+        self.unmap(element, reason);
+        self.add(element, NodeType::Transform, json!({ "source": "bpmn", "element": element.name }));
+    }
+
+    /// Name of the message or signal an event definition refers to
+    fn event_name(&self, definition: &Element, element: &Element) -> String {
+        let reference = definition.attribute("messageRef").or_else(|| definition.attribute("signalRef"));
+        reference.and_then(|id| self.events.get(id).cloned())
+            .or_else(|| element.attribute("name").map(str::to_string))
+            .unwrap_or_else(|| element.attribute("id").unwrap_or_default().to_string())
+    }
+
+    /// First event definition of an event element
+    fn event_definition(element: &Element) -> Option<&Element> {
+        element.children.iter().find(|child| child.name.ends_with("EventDefinition"))
+    }
+
+    /// Retry policy from a Zeebe job definition and the retry headers the BPMN export writes
+    fn retries(element: &Element) -> Option<RetryPolicy> {
+        let extensions = element.child("extensionElements")?;
+        let attempts: u32 = extensions.child("taskDefinition")?.attribute("retries")?.trim().parse().ok()?;
+        let header = |key: &str| {
+            extensions.child("taskHeaders")?.children_named("header")
+                .find(|h| h.attribute("key") == Some(key))
+                .and_then(|h| h.attribute("value").map(str::to_string))
+        };
+        Some(RetryPolicy {
+            max_attempts: attempts.max(1),
+            initial_interval: header("retry.initial_interval").unwrap_or_else(|| "1s".to_string()),
+            max_interval: header("retry.max_interval").unwrap_or_else(|| "100s".to_string()),
+            backoff_coefficient: header("retry.backoff_coefficient").and_then(|c| c.parse().ok()).unwrap_or(2.0),
+        })
+    }
+
+    fn task(&mut self, element: &Element) {
+        let extensions = element.child("extensionElements");
+        let job_type = extensions.and_then(|e| e.child("taskDefinition")).and_then(|d| d.attribute("type"))
+            .or_else(|| element.attribute("topic"))
+            .or_else(|| element.attribute("type"))
+            .map(str::trim);
+        let retries = Self::retries(element);
+        let node = match job_type {
+            Some(job_type) if job_type.starts_with(HTTP_CONNECTOR) => {
+                let inputs: HashMap<&str, Value> = extensions.and_then(|e| e.child("ioMapping"))
+                    .map(|mapping| {
+                        mapping.children_named("input")
+                            .filter_map(|i| Some((i.attribute("target")?, connector_input(i.attribute("source")?))))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let method = inputs.get("method").and_then(Value::as_str).unwrap_or("GET").to_string();
+                let input = |key: &str| inputs.get(key).cloned().unwrap_or(Value::Null);
+                let config = http_call_config(&method, &input("url"), &input("headers"), &input("body"));
+                self.add(element, NodeType::HttpCall, config)
+            }
+            Some(selector) if selector.starts_with('=') => {
+                self.unmap(element, "a job type computed from a variable becomes a dynamic activity; list the activities it may run");
+                let config = json!({ "selector": selector.trim_start_matches('=').trim(), "allowed": [] });
+                self.add(element, NodeType::DynamicActivity, config)
+            }
+            _ => {
+                let node = self.add(element, NodeType::Activity, Value::Null);
+                if let Some(job_type) = job_type.filter(|t| !t.is_empty() && *t != format!("{}Activity", to_pascal_case(&node.label))) {
+                    node.config = json!({ ACTIVITY_TYPE_KEY: job_type });
+                }
+                node
+            }
+        };
+        node.retries = retries;
+    }
+
+    /// Start event as the Start node or a trigger; `main_targets` are where the Start node leads
+    fn start_event(&mut self, element: &Element, is_main: bool, main_targets: &[String]) {
+        let name = element.attribute("name").map(str::to_string);
+        match Self::event_definition(element) {
+            None => {}
+            Some(definition) if definition.name == "timerEventDefinition" => {
+                let cycle = definition.child("timeCycle").map(expression_text);
+                match cycle.as_deref().and_then(schedule_cron) {
+                    Some(cron) => {
+                        let config = ScheduleTriggerConfig { cron, note: name, ..Default::default() };
+                        self.triggers.push(Trigger { trigger_type: TriggerType::Schedule, config: serde_json::to_value(config).unwrap_or_default() });
+                    }
+                    None => self.unmap(element, "only timer cycles given as cron expressions or repeating periods become schedules"),
+                }
+            }
+            Some(definition) if matches!(definition.name.as_str(), "messageEventDefinition" | "signalEventDefinition") => {
+                let kind = definition.name.trim_end_matches("EventDefinition").to_string();
+                let config = json!({ "source": "bpmn", kind: self.event_name(definition, element) });
+                self.triggers.push(Trigger { trigger_type: TriggerType::Event, config });
+            }
+            Some(definition) => {
+                let reason = format!("{} start events are not imported", definition.name.trim_end_matches("EventDefinition"));
+                self.unmap(element, reason);
+            }
+        }
+        if is_main {
+            self.add(element, NodeType::Start, Value::Null);
+        } else if !self.outgoing(element.attribute("id").unwrap_or_default()).iter().all(|target| main_targets.contains(target)) {
+            self.unmap(element, "only one start event becomes the Start node; the flows from this one are left out");
+        }
+    }
+
+    fn catch_event(&mut self, element: &Element) {
+        let Some(definition) = Self::event_definition(element) else {
+            return self.placeholder(element, "catch events without an event definition have no equivalent");
+        };
+        match definition.name.as_str() {
+            "timerEventDefinition" => match definition.child("timeDuration").map(expression_text) {
+                Some(duration) if parse_duration(&duration).is_ok() => {
+                    self.add(element, NodeType::WaitTimer, json!({ "duration": duration }));
+                }
+                _ => self.placeholder(element, "only timers waiting a fixed duration become WaitTimer nodes"),
+            },
+            "messageEventDefinition" | "signalEventDefinition" => {
+                let signal = self.event_name(definition, element);
+                self.add(element, NodeType::WaitSignal, json!({ "signal": signal }));
+            }
+            other => self.placeholder(element, format!("{} catch events have no equivalent", other.trim_end_matches("EventDefinition"))),
+        }
+    }
+
+    fn throw_event(&mut self, element: &Element) {
+        match Self::event_definition(element) {
+            Some(definition) if definition.name == "messageEventDefinition" => {
+                let topic = element.child("extensionElements")
+                    .and_then(|e| e.child("taskHeaders"))
+                    .and_then(|h| h.children_named("header").find(|h| h.attribute("key") == Some("topic")))
+                    .and_then(|h| h.attribute("value").map(str::to_string))
+                    .unwrap_or_else(|| self.event_name(definition, element));
+                let retries = Self::retries(element);
+                self.add(element, NodeType::PublishEvent, json!({ "topic": topic })).retries = retries;
+            }
+            Some(definition) => self.placeholder(element, format!("{} throw events have no equivalent", definition.name.trim_end_matches("EventDefinition"))),
+            None => self.placeholder(element, "none throw events only mark a milestone"),
+        }
+    }
+
+    /// Map one child of the process onto a node, a trigger or nothing
+    fn element(&mut self, element: &Element, main_start: Option<&str>) {
+        let id = element.attribute("id").unwrap_or_default();
+        match element.name.as_str() {
+            "startEvent" => {
+                let main_targets = main_start.map(|start| self.outgoing(start)).unwrap_or_default();
+                self.start_event(element, main_start == Some(id), &main_targets);
+            }
+            "endEvent" => {
+                if let Some(definition) = Self::event_definition(element).filter(|d| d.name != "terminateEventDefinition") {
+                    let reason = format!("{} end events end the workflow normally", definition.name.trim_end_matches("EventDefinition"));
+                    self.unmap(element, reason);
+                }
+                self.add(element, NodeType::End, Value::Null);
+            }
+            "serviceTask" | "sendTask" | "scriptTask" | "businessRuleTask" | "task" | "manualTask" => self.task(element),
+            "userTask" => {
+                self.unmap(element, "user tasks become a wait for a signal named after the task; send it when the task is done");
+                self.add(element, NodeType::WaitSignal, json!({ "signal": id }));
+            }
+            "receiveTask" => {
+                let message = element.attribute("messageRef").and_then(|m| self.events.get(m).cloned()).unwrap_or_else(|| id.to_string());
+                self.add(element, NodeType::WaitSignal, json!({ "signal": message }));
+            }
+            "callActivity" => {
+                let called = element.child("extensionElements")
+                    .and_then(|e| e.child("calledElement"))
+                    .and_then(|c| c.attribute("processId"))
+                    .or_else(|| element.attribute("calledElement"))
+                    .unwrap_or_default();
+                self.add(element, NodeType::SubWorkflow, json!({ "workflow": called }));
+            }
+            "exclusiveGateway" | "inclusiveGateway" => {
+                if let [next] = &self.outgoing(id)[..] {
+                    self.bypassed.insert(id.to_string(), next.clone());
+                    return;
+                }
+                if element.name == "inclusiveGateway" {
+                    self.unmap(element, "inclusive gateways become decisions taking only the first matching branch");
+                }
+                self.add(element, NodeType::Decision, Value::Null);
+            }
+            "parallelGateway" => {
+                self.add(element, NodeType::ParallelGateway, json!({}));
+            }
+            "eventBasedGateway" | "complexGateway" => {
+                self.unmap(element, "this gateway becomes a decision; give its branches conditions");
+                self.add(element, NodeType::Decision, Value::Null);
+            }
+            "intermediateCatchEvent" => self.catch_event(element),
+            "intermediateThrowEvent" => self.throw_event(element),
+            "boundaryEvent" => self.unmap(element, "boundary events and the flows leaving them are not imported"),
+            "subProcess" | "transaction" | "adHocSubProcess" => {
+                self.placeholder(element, "embedded subprocesses are not expanded; move their steps into a subworkflow");
+            }
+            _ if self.flows.iter().any(|f| f.source == id || f.target == id) => {
+                self.placeholder(element, format!("{} elements have no equivalent", element.name));
+            }
+            // Lanes, data objects, annotations and the like carry no control flow
+            _ => {}
+        }
+    }
+
+    /// Node a flow into `target` arrives at, past any merging gateways left out
+    fn resolve<'f>(&'f self, mut target: &'f str) -> &'f str {
+        for _ in 0..MAX_BYPASS_DEPTH {
+            match self.bypassed.get(target) {
+                Some(next) => target = next,
+                None => break,
+            }
+        }
+        target
+    }
+
+    fn edges(&mut self) -> Vec<WorkflowEdge> {
+        let defaults: HashMap<&str, &str> = self.process.children.iter()
+            .filter_map(|e| Some((e.attribute("id")?, e.attribute("default")?)))
+            .collect();
+        let mut edges = vec![];
+        let mut unmapped = vec![];
+        let mut variables = vec![];
+        for flow in &self.flows {
+            if self.bypassed.contains_key(&flow.source) {
+                continue;
+            }
+            let target = self.resolve(&flow.target);
+            let find = |id: &str| self.nodes.iter().find(|n| n.id == id);
+            let (Some(source), Some(_)) = (find(&flow.source), find(target)) else { continue };
+            let branches = matches!(source.node_type, NodeType::Decision);
+            let condition = match &flow.condition {
+                Some(_) if defaults.get(flow.source.as_str()) == Some(&flow.id.as_str()) => None,
+                Some(expression) if branches => match dsl_condition(expression) {
+                    Ok((condition, read)) => {
+                        variables.extend(read);
+                        Some(condition)
+                    }
+                    Err(e) => {
+                        unmapped.push((flow.id.clone(), format!("condition '{}' is kept as written: {}", expression, e)));
+                        Some(expression.clone())
+                    }
+                },
+                Some(_) => {
+                    unmapped.push((flow.id.clone(), "conditions on flows not leaving a gateway are dropped".to_string()));
+                    None
+                }
+                None => None,
+            };
+            edges.push(WorkflowEdge {
+                id: flow.id.clone(),
+                source: flow.source.clone(),
+                target: target.to_string(),
+                condition,
+                label: flow.name.clone(),
+                kind: Default::default(),
+            });
+        }
+        self.variables.extend(variables);
+        for (step, reason) in unmapped {
+            self.unmapped.push(UnmappedStep { step, app: "sequenceFlow".to_string(), reason });
+        }
+        edges
+    }
+}
+
+/// Diagram positions of the shapes in the document's BPMNDI section, by element ID
+fn diagram_positions(root: &Element) -> HashMap<String, Position> {
+    root.descendants().into_iter()
+        .filter(|e| e.name == "BPMNShape")
+        .filter_map(|shape| {
+            let bounds = shape.child("Bounds")?;
+            let coordinate = |name: &str| bounds.attribute(name).and_then(|v| v.trim().parse::<f64>().ok());
+            Some((shape.attribute("bpmnElement")?.to_string(), Position { x: coordinate("x")?, y: coordinate("y")? }))
+        })
+        .collect()
+}
+
+fn import_process(process: &Element, events: &HashMap<String, String>, positions: &HashMap<String, Position>) -> ImportedWorkflow {
+    let process_id = process.attribute("id").unwrap_or("process");
+    let flows = process.children_named("sequenceFlow")
+        .filter_map(|flow| {
+            Some(SequenceFlow {
+                id: flow.attribute("id")?.to_string(),
+                source: flow.attribute("sourceRef")?.to_string(),
+                target: flow.attribute("targetRef")?.to_string(),
+                name: flow.attribute("name").filter(|n| !n.trim().is_empty()).map(str::to_string),
+                condition: flow.child("conditionExpression").map(expression_text).filter(|c| !c.is_empty()),
+            })
+        })
+        .collect();
+    let mut builder = Builder {
+        process,
+        events,
+        positions,
+        flows,
+        nodes: vec![],
+        bypassed: HashMap::new(),
+        triggers: vec![],
+        variables: BTreeSet::new(),
+        unmapped: vec![],
+    };
+
+    // A start event without a trigger is the entry point; timed and message starts only
+    // become the Start node when there is no such event
+    let starts: Vec<&Element> = process.children_named("startEvent").collect();
+    let main_start = starts.iter().find(|s| Builder::event_definition(s).is_none()).or(starts.first())
+        .and_then(|s| s.attribute("id"));
+    for element in &process.children {
+        builder.element(element, main_start);
+    }
+    if main_start.is_none() {
+        // Enter at the first element no flow leads to
+        let first = builder.nodes.iter()
+            .find(|n| builder.incoming(&n.id) == 0)
+            .map(|n| n.id.clone());
+        let start = Element { name: "startEvent".to_string(), attributes: vec![("id".to_string(), "start".to_string()), ("name".to_string(), "Start".to_string())], ..Default::default() };
+        builder.unmap(&start, "the process has no start event; a Start node was added");
+        builder.add(&start, NodeType::Start, Value::Null);
+        if let Some(first) = first {
+            builder.flows.push(SequenceFlow { id: "start-flow".to_string(), source: "start".to_string(), target: first, name: None, condition: None });
+        }
+    }
+    let edges = builder.edges();
+
+    let name = process.attribute("name").map(str::trim).filter(|n| !n.is_empty()).unwrap_or(process_id).to_string();
+    let description = process.child("documentation").map(|d| d.text.trim().to_string()).filter(|d| !d.is_empty())
+        .unwrap_or_else(|| format!("Imported from BPMN process {}", process_id));
+    let variables = builder.variables.iter()
+        .map(|name| Variable { name: name.clone(), var_type: String::new(), default_value: None, constant: false, schema: None })
+        .collect();
+    ImportedWorkflow {
+        workflow: WorkflowDefinition {
+            id: Uuid::new_v4(),
+            name,
+            version: "1.0.0".to_string(),
+            description: Some(description),
+            nodes: builder.nodes,
+            edges,
+            variables,
+            triggers: builder.triggers,
+            timeouts: None,
+        },
+        unmapped: builder.unmapped,
+    }
+}
+
+/// Import every process with flow elements in a BPMN 2.0 document
+pub fn from_bpmn(source: &str) -> Result<Vec<ImportedWorkflow>, CompilerError> {
+    let root = xml::parse(source)?;
+    if root.name != "definitions" {
+        return Err(CompilerError::ParseError(format!("Expected a BPMN definitions element, found '{}'", root.name)));
+    }
+    let events: HashMap<String, String> = root.children.iter()
+        .filter(|e| e.name == "message" || e.name == "signal")
+        .filter_map(|e| Some((e.attribute("id")?.to_string(), e.attribute("name").or(e.attribute("id"))?.to_string())))
+        .collect();
+    let positions = diagram_positions(&root);
+    let imported: Vec<ImportedWorkflow> = root.children_named("process")
+        .filter(|process| process.children.iter().any(|e| e.name != "sequenceFlow" && e.attribute("id").is_some()))
+        .map(|process| import_process(process, &events, &positions))
+        .collect();
+    if imported.is_empty() {
+        return Err(CompilerError::ParseError("The BPMN document has no process with flow elements".to_string()));
+    }
+    Ok(imported)
+}
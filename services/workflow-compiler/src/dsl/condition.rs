@@ -0,0 +1,387 @@
+//! Grammar of edge conditions
+//!
+//! ```text
+//! or      := and ("||" and)*
+//! and     := unary ("&&" unary)*
+//! unary   := "!" unary | compare
+//! compare := primary (("==" | "!=" | "<" | "<=" | ">" | ">=") primary)?
+//! primary := literal | variable | "(" or ")"
+//! literal := string | number | "true" | "false" | "null"
+//! variable:= ["input."] name ("." field)*
+//! ```
+//!
+//! Strings are single- or double-quoted with backslash escapes; numbers may carry a sign,
+//! a fraction and an exponent. Conditions are parsed during validation, so syntax errors
+//! surface at compile time with their offset, and code generators render the tree. Nesting
+//! is limited to `MAX_DEPTH` levels.
+
+use std::fmt;
+
+/// Deepest nesting of parentheses and negations a condition may use; deeper conditions are
+/// rejected rather than parsed on an ever-growing stack
+pub const MAX_DEPTH: usize = 128;
+
+/// Parsed edge condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Variable reference: the name as written without `input.`, then any field names
+    Variable { name: String, fields: Vec<String> },
+    Literal(Literal),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    String(String),
+    /// Number as written, so integers and floats keep their form in generated code
+    Number(String),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+
+    /// Whether the operator orders its operands rather than testing equality
+    pub fn is_ordering(self) -> bool {
+        !matches!(self, CompareOp::Eq | CompareOp::Ne)
+    }
+
+    /// Operator holding exactly when this one does not
+    pub fn negated(self) -> Self {
+        match self {
+            CompareOp::Eq => CompareOp::Ne,
+            CompareOp::Ne => CompareOp::Eq,
+            CompareOp::Lt => CompareOp::Ge,
+            CompareOp::Ge => CompareOp::Lt,
+            CompareOp::Gt => CompareOp::Le,
+            CompareOp::Le => CompareOp::Gt,
+        }
+    }
+}
+
+impl Expr {
+    /// Variable references in the order they appear, as `(name, fields)`
+    pub fn variables(&self) -> Vec<(&str, &[String])> {
+        let mut variables = vec![];
+        self.visit_variables(&mut variables);
+        variables
+    }
+
+    fn visit_variables<'a>(&'a self, variables: &mut Vec<(&'a str, &'a [String])>) {
+        match self {
+            Expr::Variable { name, fields } => variables.push((name, fields)),
+            Expr::Literal(_) => {}
+            Expr::Not(inner) => inner.visit_variables(variables),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) | Expr::Compare(lhs, _, rhs) => {
+                lhs.visit_variables(variables);
+                rhs.visit_variables(variables);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Variable { name, fields } => {
+                write!(f, "{}", name)?;
+                fields.iter().try_for_each(|field| write!(f, ".{}", field))
+            }
+            Expr::Literal(Literal::String(s)) => write!(f, "{}", serde_json::Value::String(s.clone())),
+            Expr::Literal(Literal::Number(n)) => write!(f, "{}", n),
+            Expr::Literal(Literal::Bool(b)) => write!(f, "{}", b),
+            Expr::Literal(Literal::Null) => write!(f, "null"),
+            Expr::Not(inner) => write!(f, "!({})", inner),
+            Expr::And(lhs, rhs) => write!(f, "({} && {})", lhs, rhs),
+            Expr::Or(lhs, rhs) => write!(f, "({} || {})", lhs, rhs),
+            Expr::Compare(lhs, op, rhs) => write!(f, "{} {} {}", lhs, op.as_str(), rhs),
+        }
+    }
+}
+
+/// Parse a condition, reporting the first syntax error with its byte offset
+pub fn parse(condition: &str) -> Result<Expr, String> {
+    let tokens = tokenize(condition)?;
+    let mut parser = Parser { tokens: &tokens, pos: 0, end: condition.len(), depth: 0 };
+    let expr = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+        Some((offset, token)) => Err(format!("unexpected {} at offset {}", token, offset)),
+        None => Ok(expr),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Literal(Literal),
+    Compare(CompareOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "'{}'", name),
+            Token::Literal(literal) => write!(f, "'{}'", Expr::Literal(literal.clone())),
+            Token::Compare(op) => write!(f, "'{}'", op.as_str()),
+            Token::And => write!(f, "'&&'"),
+            Token::Or => write!(f, "'||'"),
+            Token::Not => write!(f, "'!'"),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+        }
+    }
+}
+
+/// Tokens with the byte offset each starts at
+fn tokenize(condition: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = vec![];
+    let mut chars = condition.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, ch)| ch);
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, ch)) if ch == c => break,
+                        Some((_, '\\')) => text.extend(chars.next().map(|(_, ch)| ch)),
+                        Some((_, ch)) => text.push(ch),
+                        None => return Err(format!("unterminated string literal at offset {}", start)),
+                    }
+                }
+                Token::Literal(Literal::String(text))
+            }
+            '=' | '!' | '<' | '>' if next == Some('=') => {
+                chars.next();
+                Token::Compare(match c {
+                    '=' => CompareOp::Eq,
+                    '!' => CompareOp::Ne,
+                    '<' => CompareOp::Le,
+                    _ => CompareOp::Ge,
+                })
+            }
+            '<' => Token::Compare(CompareOp::Lt),
+            '>' => Token::Compare(CompareOp::Gt),
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' | '|' if next == Some(c) => {
+                chars.next();
+                if c == '&' { Token::And } else { Token::Or }
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|ch| ch.is_ascii_digit())) => {
+                let mut number = c.to_string();
+                while let Some(&(_, ch)) = chars.peek() {
+                    let exponent_sign = matches!(ch, '+' | '-') && number.ends_with(['e', 'E']);
+                    if !(ch.is_ascii_alphanumeric() || ch == '.' || exponent_sign) {
+                        break;
+                    }
+                    number.push(ch);
+                    chars.next();
+                }
+                if number.parse::<f64>().is_err() {
+                    return Err(format!("invalid number '{}' at offset {}", number, start));
+                }
+                Token::Literal(Literal::Number(number))
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&(_, ch)) = chars.peek().filter(|(_, ch)| ch.is_alphanumeric() || matches!(ch, '_' | '.')) {
+                    word.push(ch);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "true" => Token::Literal(Literal::Bool(true)),
+                    "false" => Token::Literal(Literal::Bool(false)),
+                    "null" => Token::Literal(Literal::Null),
+                    _ => Token::Name(word),
+                }
+            }
+            other => return Err(format!("unexpected character '{}' at offset {}", other, start)),
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    pos: usize,
+    /// Offset reported for errors at the end of the condition
+    end: usize,
+    /// Parentheses and negations enclosing the current position
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn eat(&mut self, token: &Token) -> bool {
+        let matched = self.tokens.get(self.pos).is_some_and(|(_, t)| t == token);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// Parse with `parse` one level deeper, failing past `MAX_DEPTH`
+    fn nested(&mut self, offset: usize, parse: fn(&mut Self) -> Result<Expr, String>) -> Result<Expr, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("condition nests deeper than {} levels at offset {}", MAX_DEPTH, offset));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if let Some((offset, Token::Not)) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.nested(*offset, Self::unary)?)));
+        }
+        let lhs = self.primary()?;
+        match self.tokens.get(self.pos) {
+            Some((_, Token::Compare(op))) => {
+                let op = *op;
+                self.pos += 1;
+                Ok(Expr::Compare(Box::new(lhs), op, Box::new(self.primary()?)))
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let Some((offset, token)) = self.tokens.get(self.pos) else {
+            return Err(format!("expected an operand at offset {}", self.end));
+        };
+        self.pos += 1;
+        match token {
+            Token::Literal(literal) => Ok(Expr::Literal(literal.clone())),
+            Token::Name(word) => {
+                let path = word.strip_prefix("input.").unwrap_or(word);
+                let mut segments = path.split('.');
+                let name = segments.next().unwrap_or_default().to_string();
+                let fields: Vec<String> = segments.map(str::to_string).collect();
+                if name.is_empty() || fields.iter().any(String::is_empty) {
+                    return Err(format!("invalid variable reference '{}' at offset {}", word, offset));
+                }
+                Ok(Expr::Variable { name, fields })
+            }
+            Token::Open => {
+                let expr = self.nested(*offset, Self::or)?;
+                if !self.eat(&Token::Close) {
+                    let offset = self.tokens.get(self.pos).map_or(self.end, |(o, _)| *o);
+                    return Err(format!("expected ')' at offset {}", offset));
+                }
+                Ok(expr)
+            }
+            other => Err(format!("expected an operand but found {} at offset {}", other, offset)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(condition: &str) -> String {
+        parse(condition).unwrap().to_string()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or_and_not_tightest() {
+        assert_eq!(parsed("a || b && c"), "(a || (b && c))");
+        assert_eq!(parsed("(a || b) && c"), "((a || b) && c)");
+        assert_eq!(parsed("!a && b"), "(!(a) && b)");
+        assert_eq!(parsed("a && b && c || d"), "(((a && b) && c) || d)");
+        assert_eq!(parsed("x > 1 && y == 'z'"), "(x > 1 && y == \"z\")");
+    }
+
+    #[test]
+    fn literals_and_variables() {
+        assert_eq!(parse("'it\\'s'").unwrap(), Expr::Literal(Literal::String("it's".to_string())));
+        assert_eq!(parse("\"a b\"").unwrap(), Expr::Literal(Literal::String("a b".to_string())));
+        for number in ["0", "-4", "2.50", "1e6", "-1.5E-3"] {
+            assert_eq!(parse(number).unwrap(), Expr::Literal(Literal::Number(number.to_string())));
+        }
+        assert_eq!(parse("true").unwrap(), Expr::Literal(Literal::Bool(true)));
+        assert_eq!(parse("null").unwrap(), Expr::Literal(Literal::Null));
+        assert_eq!(
+            parse("input.order.total").unwrap(),
+            Expr::Variable { name: "order".to_string(), fields: vec!["total".to_string()] }
+        );
+        assert_eq!(parse("a <= -2").unwrap().variables(), vec![("a", &[][..])]);
+    }
+
+    #[test]
+    fn errors_report_their_offset() {
+        let cases = [
+            ("a == 'open", "unterminated string literal at offset 5"),
+            ("a == 1.2.3", "invalid number '1.2.3' at offset 5"),
+            ("a # b", "unexpected character '#' at offset 2"),
+            ("a &&", "expected an operand at offset 4"),
+            ("(a || b", "expected ')' at offset 7"),
+            ("a b", "unexpected 'b' at offset 2"),
+            ("a == )", "expected an operand but found ')' at offset 5"),
+            ("order..total > 1", "invalid variable reference 'order..total' at offset 0"),
+        ];
+        for (condition, error) in cases {
+            assert_eq!(parse(condition).unwrap_err(), error, "{condition}");
+        }
+    }
+
+    #[test]
+    fn nesting_is_limited_to_max_depth() {
+        let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(
+            parse(&nested(MAX_DEPTH + 1)).unwrap_err(),
+            format!("condition nests deeper than {} levels at offset {}", MAX_DEPTH, MAX_DEPTH)
+        );
+        assert!(parse(&"!".repeat(100_000)).unwrap_err().starts_with("condition nests deeper"));
+    }
+}
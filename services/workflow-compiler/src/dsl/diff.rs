@@ -0,0 +1,86 @@
+//! Semantic differences between two versions of a workflow definition
+//!
+//! Nodes and edges are matched by ID, variables by name and triggers by position, so a change
+//! is reported against the element an editor touched rather than as shifted JSON lines.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::WorkflowDefinition;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One element that differs between two definitions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// `workflow`, `node`, `edge`, `variable` or `trigger`
+    pub element: &'static str,
+    /// Node or edge ID, variable name, trigger index, or workflow field for `workflow` changes
+    pub id: String,
+    pub kind: ChangeKind,
+    /// Fields that differ, for changed nodes, edges, variables and triggers
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+impl Change {
+    /// `element:id`, identifying the element across diffs
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.element, self.id)
+    }
+}
+
+/// Top-level fields of two serialized elements that differ, in name order
+fn changed_fields(old: &Value, new: &Value) -> Vec<String> {
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else { return vec![] };
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter().filter(|key| old.get(*key) != new.get(*key)).cloned().collect()
+}
+
+/// Changes between two lists of elements matched by key
+fn diff_elements(element: &'static str, old: Vec<(String, Value)>, new: Vec<(String, Value)>, changes: &mut Vec<Change>) {
+    for (id, before) in &old {
+        match new.iter().find(|(key, _)| key == id) {
+            None => changes.push(Change { element, id: id.clone(), kind: ChangeKind::Removed, fields: vec![] }),
+            Some((_, after)) if after != before => {
+                changes.push(Change { element, id: id.clone(), kind: ChangeKind::Changed, fields: changed_fields(before, after) });
+            }
+            Some(_) => {}
+        }
+    }
+    for (id, _) in new.iter().filter(|(id, _)| !old.iter().any(|(key, _)| key == id)) {
+        changes.push(Change { element, id: id.clone(), kind: ChangeKind::Added, fields: vec![] });
+    }
+}
+
+fn keyed<T: Serialize>(items: &[T], key: impl Fn(usize, &T) -> String) -> Vec<(String, Value)> {
+    items.iter().enumerate().map(|(i, item)| (key(i, item), serde_json::to_value(item).unwrap_or_default())).collect()
+}
+
+/// Changes turning `old` into `new`: workflow fields first, then nodes, edges, variables and
+/// triggers, each in definition order
+pub fn diff(old: &WorkflowDefinition, new: &WorkflowDefinition) -> Vec<Change> {
+    let mut changes = vec![];
+    let workflow_fields = [
+        ("name", old.name != new.name),
+        ("version", old.version != new.version),
+        ("description", old.description != new.description),
+        ("timeouts", serde_json::to_value(&old.timeouts).ok() != serde_json::to_value(&new.timeouts).ok()),
+    ];
+    for (field, _) in workflow_fields.iter().filter(|(_, differs)| *differs) {
+        changes.push(Change { element: "workflow", id: field.to_string(), kind: ChangeKind::Changed, fields: vec![] });
+    }
+    diff_elements("node", keyed(&old.nodes, |_, n| n.id.clone()), keyed(&new.nodes, |_, n| n.id.clone()), &mut changes);
+    diff_elements("edge", keyed(&old.edges, |_, e| e.id.clone()), keyed(&new.edges, |_, e| e.id.clone()), &mut changes);
+    diff_elements("variable", keyed(&old.variables, |_, v| v.name.clone()), keyed(&new.variables, |_, v| v.name.clone()), &mut changes);
+    diff_elements("trigger", keyed(&old.triggers, |i, _| i.to_string()), keyed(&new.triggers, |i, _| i.to_string()), &mut changes);
+    changes
+}
@@ -0,0 +1,674 @@
+//! Graphviz DOT importer
+//!
+//! Reads a `digraph` into a definition. The attributes `export::dot` writes restore an
+//! exported definition as it was: `node_type`, `config`, `retries`, `timeouts`,
+//! `annotations`, `session`, `profile` and `pos` on nodes, `condition`, `kind` and `id` on
+//! edges, and the definition's fields on the graph. Graphs drawn by hand fall back to the
+//! shapes the exporter draws: circles start or end the flow, diamonds are decisions,
+//! `Mdiamond`s parallel gateways, octagons waits, `box3d`s child workflows, cylinders
+//! database queries, components HTTP calls, hexagons transforms and `cds` shapes event
+//! publications; anything else is an activity named after its label. Attributes that are
+//! not Graphviz presentation attributes become config, read as JSON where they parse:
+//!
+//! ```text
+//! digraph "Refunds" {
+//!     hold [shape=octagon, duration="5m"];
+//!     check [shape=diamond, label="Large refund?"];
+//!     check -> review [label="amount > 100"];
+//!     check -> hold;
+//! }
+//! ```
+//!
+//! An edge label that reads as an expression becomes the condition of a decision branch.
+//! Subgraphs are flattened, ports are ignored, and undirected graphs are rejected.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::dsl::bpmn::dsl_condition;
+use crate::dsl::text::node_id;
+use crate::error::CompilerError;
+use crate::import::{add_terminals, layout, ImportedWorkflow, UnmappedStep};
+use crate::{to_pascal_case, EdgeKind, NodeType, Position, Variable, WorkflowDefinition, WorkflowEdge, WorkflowNode};
+
+/// Node attributes that only affect rendering, or that are read into fields of their own,
+/// and so never reach the config
+const RESERVED: &[&str] = &[
+    "annotations", "area", "class", "color", "colorscheme", "comment", "config", "distortion", "fillcolor",
+    "fixedsize", "fontcolor", "fontname", "fontsize", "gradientangle", "group", "height", "href", "id", "image",
+    "imagepos", "imagescale", "label", "labelloc", "layer", "margin", "node_type", "nojustify", "ordering",
+    "orientation", "penwidth", "peripheries", "pin", "pos", "profile", "rects", "regular", "retries", "root",
+    "samplepoints", "session", "shape", "shapefile", "showboxes", "sides", "skew", "sortv", "style", "target",
+    "timeouts", "tooltip", "url", "vertices", "width", "xlabel", "xlp", "z",
+];
+
+fn error(line: usize, message: impl std::fmt::Display) -> CompilerError {
+    CompilerError::ParseError(format!("Invalid DOT graph at line {}: {}", line, message))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// ID and whether it was bare; only bare IDs can be keywords
+    Id(String, bool),
+    /// `->`, or `--` when false
+    Edge(bool),
+    Punct(char),
+    End,
+}
+
+/// Text of an HTML label without its markup
+fn html_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&amp;", "&").trim().to_string()
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, CompilerError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut line_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '\n' {
+            line += 1;
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // `#` lines are C preprocessor output
+        if (c == '#' && line_start) || (c == '/' && next == Some('/')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        line_start = false;
+        let start = line;
+        match c {
+            '/' if next == Some('*') => {
+                i += 2;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(start, "comment is not closed by '*/'")),
+                        Some('*') if chars.get(i + 1) == Some(&'/') => {
+                            i += 2;
+                            break;
+                        }
+                        Some(ch) => {
+                            line += usize::from(*ch == '\n');
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(start, "string is not closed by '\"'")),
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('"') => text.push('"'),
+                                Some('\\') => text.push('\\'),
+                                Some('n' | 'l' | 'r') => text.push('\n'),
+                                // An escaped line break continues the string
+                                Some('\n') => line += 1,
+                                Some(other) => {
+                                    text.push('\\');
+                                    text.push(*other);
+                                }
+                                None => return Err(error(start, "string is not closed by '\"'")),
+                            }
+                            i += 1;
+                        }
+                        Some(ch) => {
+                            line += usize::from(*ch == '\n');
+                            text.push(*ch);
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push((Token::Id(text, false), start));
+            }
+            '<' => {
+                let mut depth = 0;
+                let begin = i;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(start, "HTML string is not closed by '>'")),
+                        Some('<') => depth += 1,
+                        Some('>') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some(ch) => line += usize::from(*ch == '\n'),
+                    }
+                    i += 1;
+                }
+                let html: String = chars[begin + 1..i].iter().collect();
+                i += 1;
+                tokens.push((Token::Id(html_text(&html), false), start));
+            }
+            '-' if next == Some('>') => {
+                tokens.push((Token::Edge(true), start));
+                i += 2;
+            }
+            '-' if next == Some('-') => {
+                tokens.push((Token::Edge(false), start));
+                i += 2;
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' | '+' => {
+                tokens.push((Token::Punct(c), start));
+                i += 1;
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let begin = i;
+                i += usize::from(c == '-');
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let numeral: String = chars[begin..i].iter().collect();
+                if numeral.parse::<f64>().is_err() {
+                    return Err(error(start, format!("'{}' is not a number", numeral)));
+                }
+                tokens.push((Token::Id(numeral, false), start));
+            }
+            c if c.is_alphabetic() || c == '_' || !c.is_ascii() => {
+                let begin = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || !chars[i].is_ascii()) {
+                    i += 1;
+                }
+                tokens.push((Token::Id(chars[begin..i].iter().collect(), true), start));
+            }
+            other => return Err(error(start, format!("unexpected character '{}'", other))),
+        }
+    }
+    tokens.push((Token::End, line));
+    Ok(tokens)
+}
+
+type Attributes = BTreeMap<String, String>;
+
+struct DotNode {
+    attributes: Attributes,
+    line: usize,
+}
+
+struct DotEdge {
+    source: String,
+    target: String,
+    attributes: Attributes,
+    line: usize,
+}
+
+#[derive(Default)]
+struct Graph {
+    id: Option<String>,
+    /// Graph attributes with the line each was set on
+    attributes: BTreeMap<String, (String, usize)>,
+    order: Vec<String>,
+    nodes: HashMap<String, DotNode>,
+    edges: Vec<DotEdge>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    strict: bool,
+    graph: Graph,
+    /// Node and edge defaults of each enclosing graph or subgraph
+    scopes: Vec<(Attributes, Attributes)>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.position].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].0.clone();
+        if self.position + 1 < self.tokens.len() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Token::Id(text, true) if text.eq_ignore_ascii_case(word))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), CompilerError> {
+        if *self.peek() == Token::Punct(c) {
+            self.advance();
+            Ok(())
+        } else {
+            Err(error(self.line(), format!("expected '{}'", c)))
+        }
+    }
+
+    /// ID, joining quoted strings concatenated with `+`
+    fn id(&mut self) -> Result<String, CompilerError> {
+        let Token::Id(mut text, _) = self.peek().clone() else {
+            return Err(error(self.line(), "expected an ID"));
+        };
+        self.advance();
+        while *self.peek() == Token::Punct('+') {
+            self.advance();
+            text.push_str(&self.id()?);
+        }
+        Ok(text)
+    }
+
+    fn document(&mut self) -> Result<(), CompilerError> {
+        if self.keyword("strict") {
+            self.advance();
+            self.strict = true;
+        }
+        if self.keyword("graph") {
+            return Err(error(self.line(), "undirected graphs have no flow to import; declare a digraph"));
+        }
+        if !self.keyword("digraph") {
+            return Err(error(self.line(), "expected 'digraph'"));
+        }
+        self.advance();
+        if matches!(self.peek(), Token::Id(..)) {
+            self.graph.id = Some(self.id()?);
+        }
+        self.expect('{')?;
+        self.statements(true, &mut vec![])?;
+        self.expect('}')?;
+        if *self.peek() != Token::End {
+            return Err(error(self.line(), "expected the end of the document after the graph"));
+        }
+        Ok(())
+    }
+
+    /// Statements up to the closing brace, collecting the nodes they mention into `members`
+    fn statements(&mut self, top: bool, members: &mut Vec<String>) -> Result<(), CompilerError> {
+        while !matches!(self.peek(), Token::Punct('}') | Token::End) {
+            self.statement(top, members)?;
+            if *self.peek() == Token::Punct(';') {
+                self.advance();
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, top: bool, members: &mut Vec<String>) -> Result<(), CompilerError> {
+        let line = self.line();
+        if self.keyword("graph") {
+            self.advance();
+            let attributes = self.attributes()?;
+            if top {
+                self.graph.attributes.extend(attributes.into_iter().map(|(k, v)| (k, (v, line))));
+            }
+            return Ok(());
+        }
+        if self.keyword("node") || self.keyword("edge") {
+            let nodes = self.keyword("node");
+            self.advance();
+            let attributes = self.attributes()?;
+            let scope = self.scopes.last_mut().expect("the graph scope is never popped");
+            if nodes {
+                scope.0.extend(attributes);
+            } else {
+                scope.1.extend(attributes);
+            }
+            return Ok(());
+        }
+        if self.keyword("subgraph") || *self.peek() == Token::Punct('{') {
+            let group = self.subgraph(members)?;
+            if matches!(self.peek(), Token::Edge(_)) {
+                self.edges(group, members)?;
+            }
+            return Ok(());
+        }
+        if self.tokens.get(self.position + 1).map(|(t, _)| t) == Some(&Token::Punct('=')) {
+            let key = self.id()?;
+            self.expect('=')?;
+            let value = self.id()?;
+            if top {
+                self.graph.attributes.insert(key, (value, line));
+            }
+            return Ok(());
+        }
+        let id = self.node_id()?;
+        if matches!(self.peek(), Token::Edge(_)) {
+            self.declare(&id, line, Attributes::new(), members);
+            self.edges(vec![id], members)
+        } else {
+            let attributes = self.attributes()?;
+            self.declare(&id, line, attributes, members);
+            Ok(())
+        }
+    }
+
+    /// Node ID without its port
+    fn node_id(&mut self) -> Result<String, CompilerError> {
+        let id = self.id()?;
+        for _ in 0..2 {
+            if *self.peek() == Token::Punct(':') {
+                self.advance();
+                self.id()?;
+            }
+        }
+        Ok(id)
+    }
+
+    fn declare(&mut self, id: &str, line: usize, attributes: Attributes, members: &mut Vec<String>) {
+        match self.graph.nodes.get_mut(id) {
+            Some(node) => node.attributes.extend(attributes),
+            None => {
+                let mut defaults = self.scopes.last().map(|scope| scope.0.clone()).unwrap_or_default();
+                defaults.extend(attributes);
+                self.graph.nodes.insert(id.to_string(), DotNode { attributes: defaults, line });
+                self.graph.order.push(id.to_string());
+            }
+        }
+        members.push(id.to_string());
+    }
+
+    /// Subgraph, returning the nodes it mentions
+    fn subgraph(&mut self, members: &mut Vec<String>) -> Result<Vec<String>, CompilerError> {
+        if self.keyword("subgraph") {
+            self.advance();
+            if matches!(self.peek(), Token::Id(..)) {
+                self.id()?;
+            }
+        }
+        self.expect('{')?;
+        let scope = self.scopes.last().cloned().unwrap_or_default();
+        self.scopes.push(scope);
+        let mut group = vec![];
+        self.statements(false, &mut group)?;
+        self.scopes.pop();
+        self.expect('}')?;
+        members.extend(group.iter().cloned());
+        Ok(group)
+    }
+
+    /// The rest of an edge statement after its first endpoint
+    fn edges(&mut self, first: Vec<String>, members: &mut Vec<String>) -> Result<(), CompilerError> {
+        let line = self.line();
+        let mut groups = vec![first];
+        while let Token::Edge(directed) = *self.peek() {
+            if !directed {
+                return Err(error(self.line(), "'--' edges belong to undirected graphs; use '->'"));
+            }
+            self.advance();
+            let group = if self.keyword("subgraph") || *self.peek() == Token::Punct('{') {
+                self.subgraph(members)?
+            } else {
+                let endpoint_line = self.line();
+                let id = self.node_id()?;
+                self.declare(&id, endpoint_line, Attributes::new(), members);
+                vec![id]
+            };
+            groups.push(group);
+        }
+        let mut attributes = self.scopes.last().map(|scope| scope.1.clone()).unwrap_or_default();
+        attributes.extend(self.attributes()?);
+        for pair in groups.windows(2) {
+            for source in &pair[0] {
+                for target in &pair[1] {
+                    // A strict graph merges repeated edges into one
+                    let existing = self.graph.edges.iter_mut().find(|e| e.source == *source && e.target == *target);
+                    match existing {
+                        Some(edge) if self.strict => edge.attributes.extend(attributes.clone()),
+                        _ => self.graph.edges.push(DotEdge {
+                            source: source.clone(),
+                            target: target.clone(),
+                            attributes: attributes.clone(),
+                            line,
+                        }),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Zero or more bracketed attribute lists
+    fn attributes(&mut self) -> Result<Attributes, CompilerError> {
+        let mut attributes = Attributes::new();
+        while *self.peek() == Token::Punct('[') {
+            self.advance();
+            while *self.peek() != Token::Punct(']') {
+                let key = self.id()?;
+                let value = if *self.peek() == Token::Punct('=') {
+                    self.advance();
+                    self.id()?
+                } else {
+                    "true".to_string()
+                };
+                attributes.insert(key, value);
+                if matches!(self.peek(), Token::Punct(',' | ';')) {
+                    self.advance();
+                }
+            }
+            self.advance();
+        }
+        Ok(attributes)
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(text: &str, line: usize, what: &str) -> Result<T, CompilerError> {
+    serde_json::from_str(text).map_err(|e| error(line, format!("{} is not valid JSON: {}", what, e)))
+}
+
+/// Node type and config inferred from a node's shape and label
+fn infer(attributes: &Attributes, label: &str, incoming: usize, outgoing: usize) -> (NodeType, Value) {
+    let name = to_pascal_case(&label.replace(|c: char| !c.is_alphanumeric(), " "));
+    let activity = || (NodeType::Activity, json!({ "activity": name }));
+    match attributes.get("shape").map(|shape| shape.to_ascii_lowercase()).as_deref() {
+        Some("circle" | "point" | "mcircle") => {
+            if incoming == 0 {
+                (NodeType::Start, json!({}))
+            } else if outgoing == 0 {
+                (NodeType::End, json!({}))
+            } else {
+                activity()
+            }
+        }
+        Some("doublecircle" | "msquare") => (NodeType::End, json!({})),
+        Some("diamond") => (NodeType::Decision, json!({})),
+        Some("mdiamond") => (NodeType::ParallelGateway, json!({})),
+        Some("octagon" | "doubleoctagon" | "tripleoctagon") if attributes.contains_key("duration") => (NodeType::WaitTimer, json!({})),
+        Some("octagon" | "doubleoctagon" | "tripleoctagon") => (NodeType::WaitSignal, json!({ "signal": node_id(label) })),
+        Some("box3d") => (NodeType::SubWorkflow, json!({ "workflow": name })),
+        Some("cylinder") => (NodeType::DatabaseQuery, json!({})),
+        Some("component") => (NodeType::HttpCall, json!({})),
+        Some("hexagon") => (NodeType::Transform, json!({})),
+        Some("cds") => (NodeType::PublishEvent, json!({})),
+        _ => activity(),
+    }
+}
+
+/// Editor position from a `pos` attribute, whose y axis points up
+fn position(pos: &str) -> Option<Position> {
+    let (x, y) = pos.trim_end_matches('!').split_once(',')?;
+    Some(Position { x: x.trim().parse().ok()?, y: 0.0 - y.trim().parse::<f64>().ok()? })
+}
+
+fn node(id: &str, dot: &DotNode, incoming: usize, outgoing: usize) -> Result<WorkflowNode, CompilerError> {
+    let attributes = &dot.attributes;
+    let label = attributes.get("label").filter(|l| !l.is_empty() && l.as_str() != "\\N").cloned().unwrap_or_else(|| id.to_string());
+    let (inferred_type, inferred_config) = infer(attributes, &label, incoming, outgoing);
+    let (node_type, mut config) = match attributes.get("node_type") {
+        Some(name) => {
+            let node_type: NodeType = serde_json::from_value(Value::String(name.clone()))
+                .map_err(|_| error(dot.line, format!("'{}' is not a node type", name)))?;
+            // A type named explicitly gets only the config given with it
+            if std::mem::discriminant(&node_type) == std::mem::discriminant(&inferred_type) {
+                (node_type, inferred_config)
+            } else {
+                (node_type, json!({}))
+            }
+        }
+        None => (inferred_type, inferred_config),
+    };
+    if let Some(text) = attributes.get("config") {
+        let Value::Object(given) = parse_json(text, dot.line, &format!("the config of node '{}'", id))? else {
+            return Err(error(dot.line, format!("the config of node '{}' is not a JSON object", id)));
+        };
+        for (key, value) in given {
+            config[key] = value;
+        }
+    }
+    for (key, value) in attributes.iter().filter(|(key, _)| !RESERVED.contains(&key.to_ascii_lowercase().as_str())) {
+        config[key] = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone()));
+    }
+    let field = |key: &str| attributes.get(key).map(|text| (text, format!("the {} of node '{}'", key, id)));
+    Ok(WorkflowNode {
+        id: id.to_string(),
+        node_type,
+        label,
+        config,
+        position: Position { x: 0.0, y: 0.0 },
+        retries: field("retries").map(|(text, what)| parse_json(text, dot.line, &what)).transpose()?,
+        session: attributes.get("session").cloned(),
+        annotations: field("annotations").map(|(text, what)| parse_json(text, dot.line, &what)).transpose()?.unwrap_or_default(),
+        timeouts: field("timeouts").map(|(text, what)| parse_json(text, dot.line, &what)).transpose()?,
+        profile: attributes.get("profile").cloned(),
+    })
+}
+
+/// Parse a DOT digraph into a definition
+pub fn from_dot(source: &str) -> Result<ImportedWorkflow, CompilerError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+        strict: false,
+        graph: Graph::default(),
+        scopes: vec![Default::default()],
+    };
+    parser.document()?;
+    let graph = parser.graph;
+    if graph.order.is_empty() {
+        return Err(error(1, "the graph has no nodes"));
+    }
+
+    let mut nodes = vec![];
+    let mut positioned = true;
+    for id in &graph.order {
+        let dot = &graph.nodes[id];
+        let incoming = graph.edges.iter().filter(|e| e.target == *id).count();
+        let outgoing = graph.edges.iter().filter(|e| e.source == *id).count();
+        let mut node = node(id, dot, incoming, outgoing)?;
+        match dot.attributes.get("pos").and_then(|pos| position(pos)) {
+            Some(position) => node.position = position,
+            None => positioned = false,
+        }
+        nodes.push(node);
+    }
+
+    let mut unmapped = vec![];
+    let mut read = vec![];
+    let mut edges = vec![];
+    let mut ids = BTreeSet::new();
+    for dot in &graph.edges {
+        let attributes = &dot.attributes;
+        let mut label = attributes.get("label").cloned();
+        let mut condition = attributes.get("condition").cloned();
+        let decision = nodes.iter().any(|n| n.id == dot.source && matches!(n.node_type, NodeType::Decision));
+        if let Some(text) = label.clone().filter(|text| decision && condition.is_none() && text.contains(['=', '<', '>', '!', '&', '|'])) {
+            match dsl_condition(&text) {
+                Ok((expression, variables)) => {
+                    read.extend(variables);
+                    condition = Some(expression);
+                    label = None;
+                }
+                Err(_) => unmapped.push(UnmappedStep {
+                    step: dot.source.clone(),
+                    app: "decision".to_string(),
+                    reason: format!("branch '{}' is not a condition the DSL can read; it became the default branch", text),
+                }),
+            }
+        }
+        // The exporter labels a branch with its condition when it has no label of its own
+        if condition.is_some() && label == condition {
+            label = None;
+        }
+        let kind = match attributes.get("kind").map(String::as_str) {
+            None | Some("flow") => EdgeKind::Flow,
+            Some("cancel") => EdgeKind::Cancel,
+            Some(other) => return Err(error(dot.line, format!("edge kind '{}' is neither flow nor cancel", other))),
+        };
+        let mut id = attributes.get("id").cloned().unwrap_or_else(|| format!("{}-{}", dot.source, dot.target));
+        if !ids.insert(id.clone()) {
+            id = format!("{}-{}", id, edges.len() + 1);
+            ids.insert(id.clone());
+        }
+        edges.push(WorkflowEdge { id, source: dot.source.clone(), target: dot.target.clone(), condition, label, kind });
+    }
+    for decision in nodes.iter().filter(|n| matches!(n.node_type, NodeType::Decision)) {
+        let defaults = edges.iter().filter(|e| e.source == decision.id && e.condition.is_none()).count();
+        if defaults > 1 {
+            unmapped.push(UnmappedStep {
+                step: decision.id.clone(),
+                app: "decision".to_string(),
+                reason: format!("{} branches have no condition; give all but one a condition", defaults),
+            });
+        }
+    }
+
+    let count = nodes.len();
+    add_terminals(&mut nodes, &mut edges);
+    if !positioned || nodes.len() != count {
+        layout(&mut nodes, &edges);
+    }
+
+    let attribute = |key: &str| graph.attributes.get(key);
+    let json_attribute = |key: &str| attribute(key).map(|(text, line)| (text.as_str(), *line, format!("the graph's {}", key)));
+    let mut variables: Vec<Variable> = json_attribute("variables").map(|(text, line, what)| parse_json(text, line, &what)).transpose()?.unwrap_or_default();
+    for name in read {
+        if !variables.iter().any(|v| v.name == name) {
+            variables.push(Variable { name, var_type: String::new(), default_value: None, constant: false, schema: None });
+        }
+    }
+    let id = match attribute("workflow_id") {
+        Some((text, line)) => Uuid::parse_str(text).map_err(|_| error(*line, format!("workflow_id '{}' is not a UUID", text)))?,
+        None => Uuid::new_v4(),
+    };
+    Ok(ImportedWorkflow {
+        workflow: WorkflowDefinition {
+            id,
+            name: attribute("label").map(|(name, _)| name.clone()).or(graph.id).unwrap_or_else(|| "DOT graph".to_string()),
+            version: attribute("version").map(|(version, _)| version.clone()).unwrap_or_else(|| "1.0.0".to_string()),
+            description: Some(attribute("description").map(|(text, _)| text.clone()).unwrap_or_else(|| "Imported from a Graphviz DOT graph".to_string())),
+            nodes,
+            edges,
+            variables,
+            triggers: json_attribute("triggers").map(|(text, line, what)| parse_json(text, line, &what)).transpose()?.unwrap_or_default(),
+            timeouts: json_attribute("timeouts").map(|(text, line, what)| parse_json(text, line, &what)).transpose()?,
+        },
+        unmapped,
+    })
+}
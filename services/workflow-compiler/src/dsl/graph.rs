@@ -0,0 +1,150 @@
+//! Graph helpers over workflow nodes and edges
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{CancellationScopeConfig, EdgeKind, NodeType, WorkflowDefinition, WorkflowEdge, WorkflowNode};
+
+/// Look up a node by ID
+pub fn find_node<'a>(definition: &'a WorkflowDefinition, id: &str) -> Option<&'a WorkflowNode> {
+    definition.nodes.iter().find(|n| n.id == id)
+}
+
+/// Edges leaving the given node, in definition order
+pub fn outgoing_edges<'a>(definition: &'a WorkflowDefinition, node_id: &'a str) -> impl Iterator<Item = &'a WorkflowEdge> + 'a {
+    definition.edges.iter().filter(move |e| e.source == node_id)
+}
+
+/// IDs of nodes reachable from a start node over edges, including members of reachable
+/// cancellation scopes
+pub fn reachable_nodes(definition: &WorkflowDefinition) -> HashSet<&str> {
+    let mut reachable = HashSet::new();
+    let mut pending: Vec<&str> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::Start))
+        .map(|n| n.id.as_str())
+        .collect();
+    while let Some(id) = pending.pop() {
+        let Some(node) = find_node(definition, id) else { continue };
+        if !reachable.insert(node.id.as_str()) {
+            continue;
+        }
+        pending.extend(outgoing_edges(definition, &node.id).map(|e| e.target.as_str()));
+        if matches!(node.node_type, NodeType::CancellationScope) {
+            if let Ok(config) = node.typed_config::<CancellationScopeConfig>() {
+                pending.extend(config.members.iter().filter_map(|m| find_node(definition, m)).map(|n| n.id.as_str()));
+            }
+        }
+    }
+    reachable
+}
+
+/// IDs of nodes with a path over edges to an End node, including members of such
+/// cancellation scopes
+pub fn nodes_reaching_end(definition: &WorkflowDefinition) -> HashSet<&str> {
+    let mut reaching = HashSet::new();
+    let mut pending: Vec<&str> = definition.nodes.iter()
+        .filter(|n| matches!(n.node_type, NodeType::End))
+        .map(|n| n.id.as_str())
+        .collect();
+    while let Some(id) = pending.pop() {
+        let Some(node) = find_node(definition, id) else { continue };
+        if !reaching.insert(node.id.as_str()) {
+            continue;
+        }
+        pending.extend(definition.edges.iter().filter(|e| e.target == node.id).map(|e| e.source.as_str()));
+        if matches!(node.node_type, NodeType::CancellationScope) {
+            if let Ok(config) = node.typed_config::<CancellationScopeConfig>() {
+                pending.extend(config.members.iter().filter_map(|m| find_node(definition, m)).map(|n| n.id.as_str()));
+            }
+        }
+    }
+    reaching
+}
+
+/// Targets of the flow edges leaving a node, in definition order
+pub fn flow_targets<'a>(definition: &'a WorkflowDefinition, node: &WorkflowNode) -> Vec<&'a WorkflowNode> {
+    definition.edges.iter()
+        .filter(|e| e.source == node.id && e.kind == EdgeKind::Flow)
+        .filter_map(|e| find_node(definition, &e.target))
+        .collect()
+}
+
+/// Whether a node is a parallel gateway closing a fork
+pub fn is_join(definition: &WorkflowDefinition, node: &WorkflowNode) -> bool {
+    matches!(node.node_type, NodeType::ParallelGateway) && flow_targets(definition, node).len() <= 1
+}
+
+/// Nodes reachable from `start` over flow edges, itself included, in breadth-first order
+pub fn reachable_from<'a>(definition: &'a WorkflowDefinition, start: &'a WorkflowNode) -> Vec<&'a WorkflowNode> {
+    let mut seen = HashSet::from([start.id.as_str()]);
+    let mut queue = VecDeque::from([start]);
+    let mut order = vec![];
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for next in flow_targets(definition, node) {
+            if seen.insert(next.id.as_str()) {
+                queue.push_back(next);
+            }
+        }
+    }
+    order
+}
+
+/// Node where the branches out of `node` merge: the nearest one every branch reaches, or
+/// else the nearest one several branches reach
+pub fn merge_point<'a>(definition: &'a WorkflowDefinition, node: &'a WorkflowNode) -> Option<&'a WorkflowNode> {
+    let branches: Vec<HashSet<&str>> = flow_targets(definition, node).into_iter()
+        .map(|target| reachable_from(definition, target).into_iter().map(|n| n.id.as_str()).collect())
+        .collect();
+    let candidates: Vec<&'a WorkflowNode> = reachable_from(definition, node).into_iter()
+        .skip(1)
+        .filter(|n| !matches!(n.node_type, NodeType::End) && n.id != node.id)
+        .collect();
+    let reached_by = |n: &WorkflowNode| branches.iter().filter(|b| b.contains(n.id.as_str())).count();
+    candidates.iter().find(|n| reached_by(n) == branches.len())
+        .or_else(|| candidates.iter().find(|n| reached_by(n) > 1))
+        .copied()
+}
+
+/// First join gateway on the path from a fork branch's first node
+pub fn find_join<'a>(definition: &'a WorkflowDefinition, start: &'a WorkflowNode) -> Option<&'a WorkflowNode> {
+    let mut seen = HashSet::new();
+    let mut node = start;
+    while seen.insert(node.id.as_str()) {
+        if is_join(definition, node) {
+            return Some(node);
+        }
+        node = flow_targets(definition, node).first().copied()?;
+    }
+    None
+}
+
+/// Nodes reachable from a Start node over flow edges in topological order, skipping the edges
+/// that close a loop, then the other nodes in definition order. Where the order is free, the
+/// branch of an earlier edge comes first.
+pub fn topological_order(definition: &WorkflowDefinition) -> Vec<&WorkflowNode> {
+    let mut visited = HashSet::new();
+    let mut finished = vec![];
+    for start in definition.nodes.iter().rev().filter(|n| matches!(n.node_type, NodeType::Start)) {
+        if !visited.insert(start.id.as_str()) {
+            continue;
+        }
+        // Depth-first, each entry holding the targets still to visit, the last edge's first
+        let mut stack = vec![(start, flow_targets(definition, start))];
+        while let Some((node, targets)) = stack.last_mut() {
+            match targets.pop() {
+                Some(next) if visited.insert(next.id.as_str()) => {
+                    let targets = flow_targets(definition, next);
+                    stack.push((next, targets));
+                }
+                Some(_) => {}
+                None => {
+                    finished.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+    finished.reverse();
+    finished.extend(definition.nodes.iter().filter(|n| !visited.contains(n.id.as_str())));
+    finished
+}
@@ -0,0 +1,198 @@
+//! Lenient ingest for payloads from older editor versions
+//!
+//! Legacy editors used camelCase or differently named fields, PascalCase node types and
+//! numeric strings, and omitted positions, versions and IDs. `upgrade` rewrites such a
+//! workflow in place into the current shape and lists every coercion it made.
+
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// Legacy field names and the current name they map to
+const WORKFLOW_ALIASES: &[(&str, &str)] = &[("title", "name"), ("desc", "description"), ("vars", "variables")];
+const NODE_ALIASES: &[(&str, &str)] = &[
+    ("type", "node_type"),
+    ("nodeType", "node_type"),
+    ("data", "config"),
+    ("title", "label"),
+    ("name", "label"),
+    ("retry", "retries"),
+    ("retryPolicy", "retries"),
+];
+const EDGE_ALIASES: &[(&str, &str)] = &[("from", "source"), ("to", "target"), ("sourceId", "source"), ("targetId", "target")];
+const VARIABLE_ALIASES: &[(&str, &str)] = &[
+    ("type", "var_type"),
+    ("varType", "var_type"),
+    ("default", "default_value"),
+    ("defaultValue", "default_value"),
+];
+const TRIGGER_ALIASES: &[(&str, &str)] = &[("type", "trigger_type"), ("triggerType", "trigger_type")];
+const RETRY_ALIASES: &[(&str, &str)] = &[
+    ("maxAttempts", "max_attempts"),
+    ("initialInterval", "initial_interval"),
+    ("maxInterval", "max_interval"),
+    ("backoffCoefficient", "backoff_coefficient"),
+];
+
+/// Records coercions under a path such as `nodes[2]`
+struct Upgrade {
+    coercions: Vec<String>,
+}
+
+impl Upgrade {
+    fn note(&mut self, path: &str, message: String) {
+        self.coercions.push(format!("{}: {}", path, message));
+    }
+
+    /// Rename legacy keys unless the current key is already present
+    fn rename(&mut self, path: &str, object: &mut Map<String, Value>, aliases: &[(&str, &str)]) {
+        for (legacy, current) in aliases {
+            if object.contains_key(*current) {
+                continue;
+            }
+            if let Some(value) = object.remove(*legacy) {
+                object.insert(current.to_string(), value);
+                self.note(path, format!("renamed '{}' to '{}'", legacy, current));
+            }
+        }
+    }
+
+    fn default(&mut self, path: &str, object: &mut Map<String, Value>, key: &str, value: Value) {
+        if object.get(key).is_none_or(Value::is_null) {
+            self.note(path, format!("defaulted missing '{}' to {}", key, value));
+            object.insert(key.to_string(), value);
+        }
+    }
+
+    /// Convert numeric strings such as `"120"` into numbers
+    fn number(&mut self, path: &str, object: &mut Map<String, Value>, key: &str, integer: bool) {
+        let Some(Value::String(raw)) = object.get(key) else { return };
+        let parsed = if integer {
+            raw.trim().parse::<u64>().ok().map(Value::from)
+        } else {
+            raw.trim().parse::<f64>().ok().map(Value::from)
+        };
+        if let Some(number) = parsed {
+            self.note(path, format!("converted '{}' from string \"{}\" to a number", key, raw));
+            object.insert(key.to_string(), number);
+        }
+    }
+
+    /// Normalise an enum tag such as `HttpCall`, `httpCall` or `http-call` to snake_case
+    fn snake_case(&mut self, path: &str, object: &mut Map<String, Value>, key: &str) {
+        let Some(Value::String(raw)) = object.get(key) else { return };
+        let mut snake = String::with_capacity(raw.len() + 4);
+        let chars: Vec<char> = raw.trim().chars().collect();
+        for (i, c) in chars.iter().enumerate() {
+            if *c == '-' || *c == ' ' {
+                snake.push('_');
+            } else if c.is_ascii_uppercase() {
+                // Break before an uppercase letter that follows a lowercase one or starts a new word
+                let after_lower = i > 0 && chars[i - 1].is_ascii_lowercase();
+                let starts_word = i > 0 && chars[i - 1].is_ascii_uppercase() && chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+                if after_lower || starts_word {
+                    snake.push('_');
+                }
+                snake.push(c.to_ascii_lowercase());
+            } else {
+                snake.push(*c);
+            }
+        }
+        if snake != *raw {
+            self.note(path, format!("normalised '{}' from \"{}\" to \"{}\"", key, raw, snake));
+            object.insert(key.to_string(), Value::String(snake));
+        }
+    }
+
+    fn node(&mut self, path: &str, node: &mut Map<String, Value>, index: usize) {
+        self.rename(path, node, NODE_ALIASES);
+        self.snake_case(path, node, "node_type");
+        let id = match node.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            Some(Value::Number(n)) => {
+                self.note(path, format!("converted 'id' {} to a string", n));
+                n.to_string()
+            }
+            _ => {
+                let id = format!("node_{}", index);
+                self.note(path, format!("defaulted missing 'id' to \"{}\"", id));
+                id
+            }
+        };
+        node.insert("id".to_string(), Value::String(id.clone()));
+        self.default(path, node, "label", Value::String(id));
+        if !node.contains_key("config") {
+            node.insert("config".to_string(), Value::Null);
+        }
+
+        match node.get_mut("position").and_then(Value::as_object_mut) {
+            Some(position) => {
+                let path = format!("{}.position", path);
+                self.number(&path, position, "x", false);
+                self.number(&path, position, "y", false);
+                self.default(&path, position, "x", json!(0.0));
+                self.default(&path, position, "y", json!(0.0));
+            }
+            None => self.default(path, node, "position", json!({ "x": 0.0, "y": 0.0 })),
+        }
+
+        if let Some(retries) = node.get_mut("retries").and_then(Value::as_object_mut) {
+            let path = format!("{}.retries", path);
+            self.rename(&path, retries, RETRY_ALIASES);
+            self.number(&path, retries, "max_attempts", true);
+            self.number(&path, retries, "backoff_coefficient", false);
+        }
+    }
+
+    fn edge(&mut self, path: &str, edge: &mut Map<String, Value>) {
+        self.rename(path, edge, EDGE_ALIASES);
+        self.snake_case(path, edge, "kind");
+        if edge.get("id").is_none_or(Value::is_null) {
+            let source = edge.get("source").and_then(Value::as_str).unwrap_or("?");
+            let target = edge.get("target").and_then(Value::as_str).unwrap_or("?");
+            let id = format!("{}-{}", source, target);
+            self.default(path, edge, "id", Value::String(id));
+        }
+    }
+}
+
+/// Apply `f` to every object in the array at `key`, defaulting a missing array to empty
+fn each_object(upgrade: &mut Upgrade, workflow: &mut Map<String, Value>, key: &str, mut f: impl FnMut(&mut Upgrade, &str, &mut Map<String, Value>, usize)) {
+    upgrade.default("workflow", workflow, key, json!([]));
+    if let Some(items) = workflow.get_mut(key).and_then(Value::as_array_mut) {
+        for (i, item) in items.iter_mut().enumerate() {
+            if let Some(object) = item.as_object_mut() {
+                f(upgrade, &format!("{}[{}]", key, i), object, i);
+            }
+        }
+    }
+}
+
+/// Upgrade a legacy workflow payload in place, returning the coercions applied
+pub fn upgrade(workflow: &mut Value) -> Vec<String> {
+    let mut upgrade = Upgrade { coercions: vec![] };
+    let Some(object) = workflow.as_object_mut() else {
+        return upgrade.coercions;
+    };
+
+    upgrade.rename("workflow", object, WORKFLOW_ALIASES);
+    upgrade.default("workflow", object, "id", Value::String(Uuid::new_v4().to_string()));
+    upgrade.default("workflow", object, "version", json!("1.0.0"));
+    if let Some(Value::Number(n)) = object.get("version") {
+        let version = n.to_string();
+        upgrade.note("workflow", format!("converted 'version' {} to a string", version));
+        object.insert("version".to_string(), Value::String(version));
+    }
+
+    each_object(&mut upgrade, object, "nodes", |u, path, node, i| u.node(path, node, i));
+    each_object(&mut upgrade, object, "edges", |u, path, edge, _| u.edge(path, edge));
+    each_object(&mut upgrade, object, "variables", |u, path, variable, _| u.rename(path, variable, VARIABLE_ALIASES));
+    each_object(&mut upgrade, object, "triggers", |u, path, trigger, _| {
+        u.rename(path, trigger, TRIGGER_ALIASES);
+        u.snake_case(path, trigger, "trigger_type");
+        if !trigger.contains_key("config") {
+            trigger.insert("config".to_string(), Value::Null);
+        }
+    });
+
+    upgrade.coercions
+}
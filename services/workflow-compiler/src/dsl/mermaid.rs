@@ -0,0 +1,576 @@
+//! Mermaid flowchart importer
+//!
+//! Converts a `flowchart` or `graph` diagram into a definition. Node types follow the
+//! shapes sketches already use: rhombi are decisions, circles and stadiums start or end the
+//! flow, subroutines are child workflows, cylinders database queries and flags signal
+//! waits; rectangles are activities unless their label reads as a timer (`Wait 5m`), a
+//! signal wait (`Wait for approval`) or an HTTP call (`POST https://...`). The edges of a
+//! decision become its branches: `yes` reads a boolean variable named after the decision,
+//! `no` or an unlabeled edge is the default branch, an expression label such as
+//! `amount > 100` is the condition, and any other label compares a string variable named
+//! after the decision with it.
+//!
+//! Inference is overridden with a class named after a node type, as in `A[Hold]:::wait_timer`
+//! or `class A,B wait_timer`, or with a comment directive giving the type and config:
+//!
+//! ```text
+//! %% @A wait_timer duration=5m
+//! %% @B http_call url="https://hooks.test/done" method=POST
+//! ```
+//!
+//! Subgraphs are flattened, and styling, click and link style statements are ignored.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::compiler::duration::parse_duration;
+use crate::dsl::bpmn::dsl_condition;
+use crate::dsl::text::node_id;
+use crate::error::CompilerError;
+use crate::import::{add_terminals, http_call_config, layout, ImportedWorkflow, UnmappedStep};
+use crate::{to_pascal_case, NodeType, Position, Variable, WorkflowDefinition, WorkflowEdge, WorkflowNode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Rectangle,
+    Rounded,
+    Stadium,
+    Subroutine,
+    Cylinder,
+    Circle,
+    DoubleCircle,
+    Flag,
+    Rhombus,
+    Hexagon,
+    Parallelogram,
+}
+
+/// Shape openers with their closers, longest opener first
+const SHAPES: &[(&str, &[&str], Shape)] = &[
+    ("(((", &[")))"], Shape::DoubleCircle),
+    ("((", &["))"], Shape::Circle),
+    ("([", &["])"], Shape::Stadium),
+    ("[[", &["]]"], Shape::Subroutine),
+    ("[(", &[")]"], Shape::Cylinder),
+    ("{{", &["}}"], Shape::Hexagon),
+    ("[/", &["/]", "\\]"], Shape::Parallelogram),
+    ("[\\", &["\\]", "/]"], Shape::Parallelogram),
+    ("(", &[")"], Shape::Rounded),
+    ("[", &["]"], Shape::Rectangle),
+    ("{", &["}"], Shape::Rhombus),
+    (">", &["]"], Shape::Flag),
+];
+
+#[derive(Debug, Default)]
+struct Node {
+    label: Option<String>,
+    shape: Option<Shape>,
+    /// Node type named by a class or directive
+    node_type: Option<NodeType>,
+    /// Config given by a directive
+    config: Map<String, Value>,
+}
+
+#[derive(Debug)]
+struct Link {
+    source: String,
+    target: String,
+    label: Option<String>,
+}
+
+#[derive(Default)]
+struct Diagram {
+    /// Node IDs in order of first appearance
+    order: Vec<String>,
+    nodes: HashMap<String, Node>,
+    links: Vec<Link>,
+}
+
+fn error(line: usize, message: impl std::fmt::Display) -> CompilerError {
+    CompilerError::ParseError(format!("Invalid Mermaid flowchart at line {}: {}", line, message))
+}
+
+/// Built-in node type with the given snake_case name
+fn builtin_type(name: &str) -> Option<NodeType> {
+    match serde_json::from_value(Value::String(name.to_string())) {
+        Ok(NodeType::Plugin(_)) | Err(_) => None,
+        Ok(node_type) => Some(node_type),
+    }
+}
+
+/// Label text without quotes, markdown backticks and line breaks
+fn label_text(raw: &str) -> String {
+    let text = raw.trim();
+    let text = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(text);
+    let text = text.strip_prefix('`').and_then(|t| t.strip_suffix('`')).unwrap_or(text);
+    let text = text.replace("<br>", " ").replace("<br/>", " ").replace("<br />", " ");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cursor over one statement
+struct Statement<'a> {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    diagram: &'a mut Diagram,
+}
+
+impl Statement<'_> {
+    fn rest(&self) -> String {
+        self.chars[self.position..].iter().collect()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars().enumerate().all(|(i, c)| self.chars.get(self.position + i) == Some(&c))
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.position).is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.chars.len()
+    }
+
+    /// Position of `closer` from the cursor, outside double quotes
+    fn find(&self, closer: &str) -> Option<usize> {
+        let closer: Vec<char> = closer.chars().collect();
+        let mut quoted = false;
+        let mut i = self.position;
+        while i < self.chars.len() {
+            if self.chars[i] == '"' {
+                quoted = !quoted;
+            } else if !quoted && self.chars[i..].starts_with(&closer) {
+                return Some(i);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// `id`, `id[label]` or another shape, with an optional `:::class`
+    fn node(&mut self) -> Result<String, CompilerError> {
+        let start = self.position;
+        while self.chars.get(self.position).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+            self.position += 1;
+        }
+        if self.position == start {
+            return Err(error(self.line, format!("expected a node ID at '{}'", self.rest())));
+        }
+        let id: String = self.chars[start..self.position].iter().collect();
+        if !self.diagram.nodes.contains_key(&id) {
+            self.diagram.order.push(id.clone());
+        }
+
+        let shape = SHAPES.iter().find(|(opener, ..)| self.starts_with(opener));
+        if let Some((opener, closers, shape)) = shape {
+            self.position += opener.chars().count();
+            let (end, closer) = closers.iter()
+                .filter_map(|closer| Some((self.find(closer)?, closer)))
+                .min_by_key(|(end, _)| *end)
+                .ok_or_else(|| error(self.line, format!("node '{}' has an unclosed shape", id)))?;
+            let label: String = self.chars[self.position..end].iter().collect();
+            self.position = end + closer.chars().count();
+            let node = self.diagram.nodes.entry(id.clone()).or_default();
+            node.label = Some(label_text(&label));
+            node.shape = Some(*shape);
+        } else {
+            self.diagram.nodes.entry(id.clone()).or_default();
+        }
+
+        if self.starts_with(":::") {
+            self.position += 3;
+            let start = self.position;
+            while self.chars.get(self.position).is_some_and(|c| c.is_alphanumeric() || *c == '_' || *c == '-') {
+                self.position += 1;
+            }
+            let class: String = self.chars[start..self.position].iter().collect();
+            if let Some(node_type) = builtin_type(&class) {
+                self.diagram.nodes.entry(id.clone()).or_default().node_type = Some(node_type);
+            }
+        }
+        Ok(id)
+    }
+
+    /// Nodes joined with `&`
+    fn group(&mut self) -> Result<Vec<String>, CompilerError> {
+        let mut ids = vec![self.node()?];
+        loop {
+            self.skip_spaces();
+            if !self.starts_with("&") {
+                return Ok(ids);
+            }
+            self.position += 1;
+            self.skip_spaces();
+            ids.push(self.node()?);
+        }
+    }
+
+    /// Move past the stroke and head ending a link
+    fn link_tail(&mut self) {
+        while self.chars.get(self.position).is_some_and(|c| matches!(c, '-' | '=' | '.')) {
+            self.position += 1;
+        }
+        match self.chars.get(self.position) {
+            Some('>') => self.position += 1,
+            Some('o' | 'x') if self.chars.get(self.position + 1).is_none_or(|c| c.is_whitespace()) => self.position += 1,
+            _ => {}
+        }
+    }
+
+    /// A link such as `-->`, `-.->`, `==>`, `-->|label|` or `-- label -->`, returning its label
+    fn link(&mut self) -> Result<Option<String>, CompilerError> {
+        if self.starts_with("<") {
+            self.position += 1;
+        }
+        // Text between the two halves of the stroke
+        for (opener, closers) in [("--", ["-->", "---"]), ("==", ["==>", "==="]), ("-.", [".->", ".-"])] {
+            if self.starts_with(opener) && self.chars.get(self.position + 2).is_some_and(|c| c.is_whitespace()) {
+                let saved = self.position;
+                self.position += 2;
+                let end = closers.iter().filter_map(|closer| self.find(closer)).min();
+                if let Some(end) = end {
+                    let label: String = self.chars[self.position..end].iter().collect();
+                    self.position = end;
+                    self.link_tail();
+                    return Ok(Some(label_text(&label)).filter(|l| !l.is_empty()));
+                }
+                self.position = saved;
+            }
+        }
+
+        let start = self.position;
+        self.link_tail();
+        let stroke: String = self.chars[start..self.position].iter().collect();
+        if !(stroke.contains("--") || stroke.contains("==") || stroke.contains("-.")) {
+            return Err(error(self.line, format!("expected a link at '{}'", self.rest())));
+        }
+        self.skip_spaces();
+        if self.starts_with("|") {
+            self.position += 1;
+            let end = self.find("|").ok_or_else(|| error(self.line, "unclosed link label"))?;
+            let label: String = self.chars[self.position..end].iter().collect();
+            self.position = end + 1;
+            return Ok(Some(label_text(&label)).filter(|l| !l.is_empty()));
+        }
+        Ok(None)
+    }
+
+    /// `A --> B --> C`, each side possibly a group
+    fn chain(&mut self) -> Result<(), CompilerError> {
+        let mut sources = self.group()?;
+        loop {
+            self.skip_spaces();
+            if self.at_end() || self.starts_with(";") {
+                return Ok(());
+            }
+            let label = self.link()?;
+            self.skip_spaces();
+            let targets = self.group()?;
+            for source in &sources {
+                for target in &targets {
+                    self.diagram.links.push(Link { source: source.clone(), target: target.clone(), label: label.clone() });
+                }
+            }
+            sources = targets;
+        }
+    }
+}
+
+/// Split a directive into words, keeping double-quoted values together
+fn directive_words(text: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `%% @id [node_type] [key=value ...]`
+fn directive(diagram: &mut Diagram, text: &str, line: usize) -> Result<(), CompilerError> {
+    let mut words = directive_words(text).into_iter();
+    let id = words.next().unwrap_or_default();
+    if id.is_empty() {
+        return Err(error(line, "directive without a node ID"));
+    }
+    if !diagram.nodes.contains_key(&id) {
+        diagram.order.push(id.clone());
+    }
+    let node = diagram.nodes.entry(id).or_default();
+    for word in words {
+        match word.split_once('=') {
+            Some((key, value)) => {
+                let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+                node.config.insert(key.to_string(), value);
+            }
+            None => {
+                let node_type = serde_json::from_value(Value::String(word.clone()))
+                    .map_err(|_| error(line, format!("'{}' is not a node type", word)))?;
+                node.node_type = Some(node_type);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Title from the diagram's front matter, returning the source after it
+fn front_matter(source: &str) -> Result<(Option<String>, &str, usize), CompilerError> {
+    let trimmed = source.trim_start();
+    let skipped = source[..source.len() - trimmed.len()].matches('\n').count();
+    let Some(rest) = trimmed.strip_prefix("---") else {
+        return Ok((None, source, 0));
+    };
+    let end = rest.find("\n---").ok_or_else(|| error(1, "front matter is not closed by '---'"))?;
+    let config = crate::dsl::yaml::to_value(&rest[..end])?;
+    let title = config.get("title").and_then(Value::as_str).map(str::to_string);
+    let body = &rest[end + 4..];
+    Ok((title, body, skipped + rest[..end + 4].matches('\n').count()))
+}
+
+fn parse(source: &str) -> Result<(Diagram, Option<String>), CompilerError> {
+    let (mut title, body, offset) = front_matter(source)?;
+    let mut diagram = Diagram::default();
+    let mut header = false;
+    for (index, raw) in body.lines().enumerate() {
+        let line = index + offset + 1;
+        let text = raw.trim();
+        if let Some(comment) = text.strip_prefix("%%") {
+            if let Some(directive_text) = comment.trim().strip_prefix('@') {
+                directive(&mut diagram, directive_text, line)?;
+            }
+            continue;
+        }
+        if text.is_empty() {
+            continue;
+        }
+        if !header {
+            let mut words = text.split_whitespace();
+            match words.next() {
+                Some("flowchart" | "graph") => {
+                    header = true;
+                    continue;
+                }
+                _ => return Err(error(line, "expected a 'flowchart' or 'graph' header")),
+            }
+        }
+        for statement in text.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let keyword = statement.split_whitespace().next().unwrap_or_default();
+            match keyword {
+                "subgraph" | "end" | "direction" | "classDef" | "style" | "linkStyle" | "click" => continue,
+                "accTitle:" | "title" => {
+                    title = Some(statement[keyword.len()..].trim().to_string());
+                    continue;
+                }
+                "accDescr:" => continue,
+                "class" => {
+                    let mut words = statement.split_whitespace().skip(1);
+                    let (Some(ids), Some(class)) = (words.next(), words.next()) else {
+                        return Err(error(line, "a class statement needs node IDs and a class"));
+                    };
+                    if let Some(node_type) = builtin_type(class) {
+                        for id in ids.split(',') {
+                            if !diagram.nodes.contains_key(id) {
+                                diagram.order.push(id.to_string());
+                            }
+                            diagram.nodes.entry(id.to_string()).or_default().node_type = Some(node_type.clone());
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let mut cursor = Statement { chars: statement.chars().collect(), position: 0, line, diagram: &mut diagram };
+            cursor.chain()?;
+        }
+    }
+    if !header {
+        return Err(error(1, "expected a 'flowchart' or 'graph' header"));
+    }
+    Ok((diagram, title))
+}
+
+/// Node type and config inferred from a node's shape and label
+fn infer(node: &Node, label: &str, incoming: usize, outgoing: usize) -> (NodeType, Value) {
+    let name = to_pascal_case(&label.replace(|c: char| !c.is_alphanumeric(), " "));
+    let activity = || (NodeType::Activity, json!({ "activity": name }));
+    match node.shape.unwrap_or(Shape::Rectangle) {
+        Shape::Rhombus => (NodeType::Decision, json!({})),
+        Shape::Circle | Shape::Stadium | Shape::DoubleCircle => {
+            let word = label.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+            if matches!(word.as_str(), "start" | "begin") || (incoming == 0 && node.shape != Some(Shape::DoubleCircle)) {
+                (NodeType::Start, json!({}))
+            } else if matches!(word.as_str(), "end" | "done" | "finish" | "stop" | "complete") || outgoing == 0 {
+                (NodeType::End, json!({}))
+            } else {
+                activity()
+            }
+        }
+        Shape::Subroutine => (NodeType::SubWorkflow, json!({ "workflow": name })),
+        Shape::Cylinder => (NodeType::DatabaseQuery, json!({})),
+        Shape::Hexagon => (NodeType::Transform, json!({})),
+        Shape::Flag => (NodeType::WaitSignal, json!({ "signal": node_id(label) })),
+        Shape::Rectangle | Shape::Rounded | Shape::Parallelogram => {
+            let lower = label.to_ascii_lowercase();
+            let mut words = label.split_whitespace();
+            let first = words.next().unwrap_or_default();
+            if let Some(rest) = lower.strip_prefix("wait for ") {
+                return (NodeType::WaitSignal, json!({ "signal": node_id(rest) }));
+            }
+            let delay = ["wait ", "delay ", "sleep "].iter().find_map(|prefix| lower.strip_prefix(prefix));
+            if let Some(duration) = delay.map(str::trim).filter(|d| parse_duration(d).is_ok()) {
+                return (NodeType::WaitTimer, json!({ "duration": duration }));
+            }
+            let method = first.to_ascii_uppercase();
+            if matches!(method.as_str(), "GET" | "POST" | "PUT" | "PATCH" | "DELETE") {
+                if let Some(url) = words.next().filter(|url| url.starts_with("http://") || url.starts_with("https://") || url.starts_with('/')) {
+                    return (NodeType::HttpCall, http_call_config(&method, &json!(url), &Value::Null, &Value::Null));
+                }
+            }
+            activity()
+        }
+    }
+}
+
+/// Parse a Mermaid flowchart into a definition
+pub fn from_mermaid(source: &str) -> Result<ImportedWorkflow, CompilerError> {
+    let (diagram, title) = parse(source)?;
+    if diagram.order.is_empty() {
+        return Err(error(1, "the flowchart has no nodes"));
+    }
+    let mut unmapped = vec![];
+    let count = |f: &dyn Fn(&Link) -> bool| diagram.links.iter().filter(|l| f(l)).count();
+
+    let mut nodes: Vec<WorkflowNode> = diagram.order.iter().map(|id| {
+        let node = &diagram.nodes[id];
+        let label = node.label.clone().filter(|l| !l.is_empty()).unwrap_or_else(|| id.clone());
+        let incoming = count(&|l| l.target == *id);
+        let outgoing = count(&|l| l.source == *id);
+        let (inferred_type, inferred_config) = infer(node, &label, incoming, outgoing);
+        let (node_type, mut config) = match &node.node_type {
+            // A type named explicitly gets only the config given with it
+            Some(node_type) if std::mem::discriminant(node_type) != std::mem::discriminant(&inferred_type) => (node_type.clone(), json!({})),
+            _ => (inferred_type, inferred_config),
+        };
+        for (key, value) in &node.config {
+            config[key] = value.clone();
+        }
+        WorkflowNode {
+            id: id.clone(),
+            node_type,
+            label,
+            config,
+            position: Position { x: 0.0, y: 0.0 },
+            retries: None,
+            session: None,
+            annotations: Default::default(),
+            timeouts: None,
+            profile: None,
+        }
+    }).collect();
+
+    let mut variables: Vec<(String, &str)> = vec![];
+    let mut edges = vec![];
+    let mut ids: BTreeSet<String> = BTreeSet::new();
+    for link in &diagram.links {
+        let source = nodes.iter().find(|n| n.id == link.source).expect("linked nodes are declared");
+        let mut condition = None;
+        let mut label = link.label.clone();
+        if matches!(source.node_type, NodeType::Decision) {
+            let variable = node_id(&source.label);
+            match link.label.as_deref().map(str::to_ascii_lowercase).as_deref() {
+                None | Some("no" | "false" | "else" | "otherwise" | "default") => {}
+                Some("yes" | "true" | "y") => {
+                    variables.push((variable.clone(), "bool"));
+                    condition = Some(variable);
+                }
+                Some(_) => {
+                    let text = link.label.clone().unwrap_or_default();
+                    let expression = text.contains(['=', '<', '>', '!', '&', '|']);
+                    match dsl_condition(&text) {
+                        Ok((expression_condition, read)) if expression => {
+                            variables.extend(read.into_iter().map(|name| (name, "")));
+                            condition = Some(expression_condition);
+                            label = None;
+                        }
+                        _ if expression => unmapped.push(UnmappedStep {
+                            step: source.id.clone(),
+                            app: "decision".to_string(),
+                            reason: format!("branch '{}' is not a condition the DSL can read; it became the default branch", text),
+                        }),
+                        _ => {
+                            variables.push((variable.clone(), "string"));
+                            condition = Some(format!("{} == {}", variable, Value::String(text)));
+                        }
+                    }
+                }
+            }
+        }
+        let mut id = format!("{}-{}", link.source, link.target);
+        if !ids.insert(id.clone()) {
+            id = format!("{}-{}", id, edges.len() + 1);
+            ids.insert(id.clone());
+        }
+        edges.push(WorkflowEdge {
+            id,
+            source: link.source.clone(),
+            target: link.target.clone(),
+            condition,
+            label,
+            kind: Default::default(),
+        });
+    }
+    for decision in nodes.iter().filter(|n| matches!(n.node_type, NodeType::Decision)) {
+        let defaults = edges.iter().filter(|e| e.source == decision.id && e.condition.is_none()).count();
+        if defaults > 1 {
+            unmapped.push(UnmappedStep {
+                step: decision.id.clone(),
+                app: "decision".to_string(),
+                reason: format!("{} branches have no condition; label all but one with yes, a value or an expression", defaults),
+            });
+        }
+    }
+
+    // Sketches often leave out the terminals
+    add_terminals(&mut nodes, &mut edges);
+    layout(&mut nodes, &edges);
+
+    let mut declared = BTreeSet::new();
+    let variables = variables.into_iter()
+        .filter(|(name, _)| declared.insert(name.clone()))
+        .map(|(name, var_type)| Variable { name, var_type: var_type.to_string(), default_value: None, constant: false, schema: None })
+        .collect();
+    Ok(ImportedWorkflow {
+        workflow: WorkflowDefinition {
+            id: Uuid::new_v4(),
+            name: title.unwrap_or_else(|| "Mermaid flowchart".to_string()),
+            version: "1.0.0".to_string(),
+            description: Some("Imported from a Mermaid flowchart".to_string()),
+            nodes,
+            edges,
+            variables,
+            triggers: vec![],
+            timeouts: None,
+        },
+        unmapped,
+    })
+}
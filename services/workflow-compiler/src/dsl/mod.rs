@@ -1,3 +1,21 @@
 //! DSL module for workflow definitions
+pub mod bpmn;
+pub mod condition;
+pub mod diff;
+pub mod dot;
 pub mod graph;
+pub mod lenient;
+pub mod mermaid;
+pub mod schema;
+pub mod serverless_workflow;
+pub mod text;
 pub mod types;
+pub mod xml;
+pub mod yaml;
+
+pub use bpmn::from_bpmn;
+pub use dot::from_dot;
+pub use mermaid::from_mermaid;
+pub use serverless_workflow::from_serverless_workflow;
+pub use text::parse_text;
+pub use yaml::parse_yaml;
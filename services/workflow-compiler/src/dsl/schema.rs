@@ -0,0 +1,315 @@
+//! JSON Schema of workflow definitions and a checker reporting every violation
+//!
+//! `workflow_schema` is derived with schemars from the serde models `WorkflowDefinition`
+//! deserializes into, so it follows them as fields are added: required fields, nullable
+//! ones, enums and doc comments come from the types themselves. On top of the derived schema
+//! it lists the registered plugins' node types and ties each node type to its typed config,
+//! the built-in ones derived the same way and the plugins' from their manifests, and schedule
+//! triggers to their config. Editors load it for autocomplete and client-side checks. Unknown
+//! fields are allowed, as deserialization ignores them.
+//!
+//! `violations` checks a document against a schema and reports each violation with the JSON
+//! pointer of the offending value. It understands the keywords the schema uses (`$ref` into
+//! `$defs`, `type`, `enum`, `const`, `required`, `properties`, `additionalProperties`,
+//! `items`, `anyOf`, `oneOf`, `allOf`, `if`/`then`/`else`, the numeric and length bounds,
+//! `pattern` and the `uuid` format) and ignores any other keyword a plugin's schema uses.
+
+use schemars::generate::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::compiler::plugins::PluginInfo;
+use crate::{
+    ActivityConfig, CancellationScopeConfig, DatabaseQueryConfig, DecisionConfig, DecisionTableConfig, DynamicActivityConfig, FeatureFlagConfig,
+    HttpCallConfig, NexusOperationConfig, NodeType, ParallelGatewayConfig, PublishEventConfig, ScheduleTriggerConfig, SubWorkflowConfig,
+    TransformConfig, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig, WeightedSplitConfig, WorkflowDefinition,
+};
+
+/// Schema violation at one location of the checked document
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaViolation {
+    /// JSON pointer of the offending value, empty for the document itself
+    pub pointer: String,
+    /// Keyword that failed, e.g. `required`
+    pub keyword: &'static str,
+    pub message: String,
+}
+
+fn generator() -> SchemaGenerator {
+    SchemaSettings::draft2020_12().for_deserialize().into_generator()
+}
+
+fn subschema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Value {
+    generator.subschema_for::<T>().to_value()
+}
+
+/// Schema of a built-in node type's typed config, with its definitions added to `generator`
+fn typed_config(node_type: &str, generator: &mut SchemaGenerator) -> Option<Value> {
+    Some(match node_type {
+        "start" | "end" | "notification" => json!({ "type": "object" }),
+        "activity" => subschema::<ActivityConfig>(generator),
+        "http_call" => subschema::<HttpCallConfig>(generator),
+        "database_query" => subschema::<DatabaseQueryConfig>(generator),
+        "transform" => subschema::<TransformConfig>(generator),
+        "parallel_gateway" => subschema::<ParallelGatewayConfig>(generator),
+        "decision" => subschema::<DecisionConfig>(generator),
+        "decision_table" => subschema::<DecisionTableConfig>(generator),
+        "wait_signals" => subschema::<WaitSignalsConfig>(generator),
+        "dynamic_activity" => subschema::<DynamicActivityConfig>(generator),
+        "sub_workflow" => subschema::<SubWorkflowConfig>(generator),
+        "wait_timer" => subschema::<WaitTimerConfig>(generator),
+        "wait_signal" => subschema::<WaitSignalConfig>(generator),
+        "cancellation_scope" => subschema::<CancellationScopeConfig>(generator),
+        "nexus_operation" => subschema::<NexusOperationConfig>(generator),
+        "feature_flag" => subschema::<FeatureFlagConfig>(generator),
+        "publish_event" => subschema::<PublishEventConfig>(generator),
+        "weighted_split" => subschema::<WeightedSplitConfig>(generator),
+        _ => return None,
+    })
+}
+
+/// Strings a schema's `enum` and `const` keywords allow, at any depth
+fn allowed_strings(schema: &Value, strings: &mut Vec<String>) {
+    match schema {
+        Value::Object(map) => {
+            for (keyword, value) in map {
+                match (keyword.as_str(), value) {
+                    ("const", Value::String(s)) => strings.push(s.clone()),
+                    ("enum", Value::Array(values)) => strings.extend(values.iter().filter_map(Value::as_str).map(str::to_string)),
+                    _ => allowed_strings(value, strings),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| allowed_strings(item, strings)),
+        _ => {}
+    }
+}
+
+/// Built-in node types in their serialized form
+fn node_types() -> Vec<String> {
+    let mut types = vec![];
+    allowed_strings(&schemars::schema_for!(NodeType).to_value(), &mut types);
+    types
+}
+
+/// Schema of a built-in node type's config, by serialized node type
+pub fn node_config_schema(node_type: &str) -> Option<Value> {
+    let mut generator = generator();
+    let mut schema = typed_config(node_type, &mut generator)?;
+    let definitions = generator.take_definitions(true);
+    if let (Some(schema), false) = (schema.as_object_mut(), definitions.is_empty()) {
+        schema.insert("$defs".to_string(), Value::Object(definitions));
+    }
+    Some(schema)
+}
+
+/// `if node_type is X then config matches S` for every typed config; a null config reads as
+/// the default one
+fn config_conditions(generator: &mut SchemaGenerator, plugins: &[PluginInfo]) -> Vec<Value> {
+    let built_in: Vec<(String, Value)> = node_types().into_iter()
+        .filter_map(|node_type| typed_config(&node_type, generator).map(|config| (node_type, config)))
+        .collect();
+    let plugin = plugins.iter().map(|p| (p.node_type.clone(), p.config_schema.clone()));
+    built_in.into_iter().chain(plugin)
+        .map(|(node_type, config)| json!({
+            "if": { "required": ["node_type"], "properties": { "node_type": { "const": node_type } } },
+            "then": { "properties": { "config": { "anyOf": [config, { "type": "null" }] } } },
+        }))
+        .collect()
+}
+
+/// JSON Schema of a workflow definition accepting the built-in and the given plugin node types
+pub fn workflow_schema(plugins: &[PluginInfo]) -> Value {
+    let mut generator = generator();
+    let conditions = config_conditions(&mut generator, plugins);
+    let schedule = subschema::<ScheduleTriggerConfig>(&mut generator);
+    let mut root = generator.into_root_schema_for::<WorkflowDefinition>().to_value();
+
+    let definitions = root.get_mut("$defs").and_then(Value::as_object_mut).expect("workflow schema has definitions");
+    // Plugin node types are any string to serde; the schema names the registered ones
+    let node_types: Vec<String> = node_types().into_iter().chain(plugins.iter().map(|p| p.node_type.clone())).collect();
+    definitions.insert("NodeType".to_string(), json!({ "type": "string", "enum": node_types }));
+    if let Some(node) = definitions.get_mut("WorkflowNode").and_then(Value::as_object_mut) {
+        node.insert("allOf".to_string(), Value::Array(conditions));
+    }
+    if let Some(trigger) = definitions.get_mut("Trigger").and_then(Value::as_object_mut) {
+        trigger.insert("allOf".to_string(), json!([{
+            "if": { "required": ["trigger_type"], "properties": { "trigger_type": { "const": "schedule" } } },
+            "then": { "properties": { "config": schedule } },
+        }]));
+    }
+    root
+}
+
+/// Name of the JSON Schema type a value has
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    let actual = json_type(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// JSON pointer of a child, escaping `~` and `/` in the token
+fn child(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, token.replace('~', "~0").replace('/', "~1"))
+}
+
+struct Checker<'a> {
+    root: &'a Value,
+    violations: Vec<SchemaViolation>,
+}
+
+impl Checker<'_> {
+    fn violation(&mut self, pointer: &str, keyword: &'static str, message: String) {
+        self.violations.push(SchemaViolation { pointer: pointer.to_string(), keyword, message });
+    }
+
+    /// Violations found by checking against a subschema on its own, for the combinators
+    fn probe(&self, schema: &Value, value: &Value, pointer: &str) -> Vec<SchemaViolation> {
+        let mut checker = Checker { root: self.root, violations: vec![] };
+        checker.check(schema, value, pointer);
+        checker.violations
+    }
+
+    fn check(&mut self, schema: &Value, value: &Value, pointer: &str) {
+        let Some(schema) = schema.as_object() else { return };
+        if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+            match target.strip_prefix('#').and_then(|path| self.root.pointer(path)) {
+                Some(resolved) => self.check(resolved, value, pointer),
+                None => self.violation(pointer, "$ref", format!("refers to unknown schema '{}'", target)),
+            }
+        }
+
+        let expected: Vec<&str> = match schema.get("type") {
+            Some(Value::String(expected)) => vec![expected],
+            Some(Value::Array(expected)) => expected.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if !expected.is_empty() && !expected.iter().any(|t| has_type(value, t)) {
+            self.violation(pointer, "type", format!("must be of type {}, not {}", expected.join(" or "), json_type(value)));
+            return;
+        }
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(value) {
+                self.violation(pointer, "enum", format!("must be one of {}", Value::Array(allowed.clone())));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                self.violation(pointer, "const", format!("must be {}", constant));
+            }
+        }
+
+        match value {
+            Value::Object(fields) => self.check_object(schema, fields, pointer),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &child(pointer, &index.to_string()));
+                    }
+                }
+            }
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64).filter(|m| number < *m) {
+                    self.violation(pointer, "minimum", format!("must be at least {}", minimum));
+                }
+                if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64).filter(|m| number > *m) {
+                    self.violation(pointer, "maximum", format!("must be at most {}", maximum));
+                }
+            }
+            Value::String(text) => self.check_string(schema, text, pointer),
+            _ => {}
+        }
+
+        for subschema in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+            self.check(subschema, value, pointer);
+        }
+        // Variants serde tells apart never overlap, so one matching is as good as exactly one
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(options) = schema.get(keyword).and_then(Value::as_array) {
+                self.check_any_of(options, value, pointer);
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.probe(condition, value, pointer).is_empty() { "then" } else { "else" };
+            if let Some(subschema) = schema.get(branch) {
+                self.check(subschema, value, pointer);
+            }
+        }
+    }
+
+    fn check_object(&mut self, schema: &Map<String, Value>, fields: &Map<String, Value>, pointer: &str) {
+        for required in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !fields.contains_key(required) {
+                self.violation(pointer, "required", format!("is missing required field '{}'", required));
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, field) in fields {
+            match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+                (Some(property), _) => self.check(property, field, &child(pointer, key)),
+                (None, Some(Value::Bool(false))) => self.violation(&child(pointer, key), "additionalProperties", format!("unknown field '{}'", key)),
+                (None, Some(additional)) => self.check(additional, field, &child(pointer, key)),
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn check_string(&mut self, schema: &Map<String, Value>, text: &str, pointer: &str) {
+        let length = text.chars().count() as u64;
+        if let Some(minimum) = schema.get("minLength").and_then(Value::as_u64).filter(|m| length < *m) {
+            self.violation(pointer, "minLength", format!("must be at least {} characters long", minimum));
+        }
+        if let Some(maximum) = schema.get("maxLength").and_then(Value::as_u64).filter(|m| length > *m) {
+            self.violation(pointer, "maxLength", format!("must be at most {} characters long", maximum));
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if regex::Regex::new(pattern).is_ok_and(|re| !re.is_match(text)) {
+                self.violation(pointer, "pattern", format!("must match the pattern '{}'", pattern));
+            }
+        }
+        if schema.get("format").and_then(Value::as_str) == Some("uuid") && uuid::Uuid::parse_str(text).is_err() {
+            self.violation(pointer, "format", "must be a UUID".to_string());
+        }
+    }
+
+    /// Passes when one option matches; otherwise reports the violations of the closest
+    /// option whose type matched, so a nullable field says why its value is wrong rather
+    /// than that it is not null
+    fn check_any_of(&mut self, options: &[Value], value: &Value, pointer: &str) {
+        let mut closest: Option<Vec<SchemaViolation>> = None;
+        for option in options {
+            let violations = self.probe(option, value, pointer);
+            if violations.is_empty() {
+                return;
+            }
+            let type_matched = !violations.iter().any(|v| v.pointer == pointer && v.keyword == "type");
+            if type_matched && closest.as_ref().is_none_or(|c| violations.len() < c.len()) {
+                closest = Some(violations);
+            }
+        }
+        match closest {
+            Some(violations) => self.violations.extend(violations),
+            None => self.violation(pointer, "anyOf", format!("does not match any of the allowed schemas, not {}", json_type(value))),
+        }
+    }
+}
+
+/// Every violation of a document against a schema
+pub fn violations(schema: &Value, document: &Value) -> Vec<SchemaViolation> {
+    let mut checker = Checker { root: schema, violations: vec![] };
+    checker.check(schema, document, "");
+    checker.violations
+}
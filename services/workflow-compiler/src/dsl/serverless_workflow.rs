@@ -0,0 +1,733 @@
+//! CNCF Serverless Workflow import: a specification 0.8 workflow document, in JSON or YAML,
+//! as a workflow definition
+//!
+//! States map onto nodes and transitions onto edges, reversing the Serverless Workflow
+//! target. Operation states become a node per action: `custom` functions with an
+//! `activity:` operation Activity nodes, `rest:<method>:<url>` ones HttpCall nodes and
+//! `expression` functions transforms, while produced events become PublishEvent nodes and
+//! `subFlowRef` actions subworkflows. Switch states become decisions with their jq data
+//! conditions rewritten in the DSL's expression syntax, parallel states forks whose branches
+//! meet at a join, sleep states timers and event states signal waits. The start schedule
+//! becomes a schedule trigger and the data input schema the workflow variables. The metadata
+//! the export writes on each state restores its node's ID, type, config, position, session and
+//! annotations.
+//!
+//! Features without an equivalent are reported as unmapped. States the graph cannot express
+//! are kept as Transform placeholders so their transitions stay connected.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::compiler::plugins::ACTIVITY_TYPE_KEY;
+use crate::compiler::serverless_workflow::ACTIVITY_OPERATION;
+use crate::dsl::text::node_id;
+use crate::error::CompilerError;
+use crate::import::{http_call_config, layout, ImportedWorkflow, UnmappedStep, NODE_SPACING};
+use crate::{
+    NodeType, Position, RetryPolicy, Trigger, TriggerType, Variable, VariableSchema, WorkflowDefinition, WorkflowEdge,
+    WorkflowNode, WorkflowTimeouts,
+};
+
+fn error(message: impl std::fmt::Display) -> CompilerError {
+    CompilerError::ParseError(format!("Invalid Serverless Workflow document: {}", message))
+}
+
+/// Text of a field given either as a string or as an object holding it under `key`
+fn name_or<'a>(value: Option<&'a Value>, key: &str) -> Option<&'a str> {
+    value.and_then(|v| v.as_str().or_else(|| v.get(key).and_then(Value::as_str)))
+}
+
+/// jq data condition being rewritten in the DSL's expression syntax
+struct Jq {
+    chars: Vec<char>,
+    position: usize,
+    variables: Vec<String>,
+}
+
+impl Jq {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn word(&mut self, path: bool) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || *c == '_' || (path && *c == '.')) {
+            word.push(c);
+            self.position += 1;
+        }
+        word
+    }
+
+    /// Tokens up to the end, or up to the `)` closing a group when `nested`, and whether they
+    /// end in `| not`
+    fn sequence(&mut self, nested: bool) -> Result<(String, bool), String> {
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return if nested { Err("unbalanced parentheses".to_string()) } else { Ok((out, false)) };
+            };
+            self.position += 1;
+            match c {
+                ')' if nested => return Ok((out, false)),
+                // jq negates with the `not` filter, so `(x | not)` becomes `!(x)`
+                '|' => {
+                    self.skip_whitespace();
+                    if self.word(false) != "not" {
+                        return Err("pipes other than '| not' have no DSL equivalent".to_string());
+                    }
+                    self.skip_whitespace();
+                    match self.peek() {
+                        None if !nested => return Ok((out, true)),
+                        Some(')') if nested => {
+                            self.position += 1;
+                            return Ok((out, true));
+                        }
+                        _ => return Err("'| not' must end a group".to_string()),
+                    }
+                }
+                '(' => {
+                    let (inner, negated) = self.sequence(true)?;
+                    let inner = inner.trim();
+                    out.push_str(&if negated { format!(" {} ", negate(inner)) } else { format!("({})", inner) });
+                }
+                '"' => {
+                    out.push('"');
+                    loop {
+                        let Some(ch) = self.peek() else { return Err("unterminated string literal".to_string()) };
+                        self.position += 1;
+                        out.push(ch);
+                        match ch {
+                            '"' => break,
+                            '\\' => {
+                                out.extend(self.peek());
+                                self.position += 1;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                '.' if self.peek().is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_') => {
+                    let path = self.word(true);
+                    let head = path.split('.').next().unwrap_or_default().to_string();
+                    if !self.variables.contains(&head) {
+                        self.variables.push(head);
+                    }
+                    out.push_str(&format!(" {} ", path));
+                }
+                '.' if !self.peek().is_some_and(|ch| ch.is_ascii_digit()) => {
+                    return Err("only field paths such as '.amount' have a DSL equivalent".to_string());
+                }
+                '$' => return Err("jq variables have no DSL equivalent".to_string()),
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    self.position -= 1;
+                    let word = self.word(false);
+                    match word.as_str() {
+                        "and" => out.push_str(" && "),
+                        "or" => out.push_str(" || "),
+                        "true" | "false" | "null" => out.push_str(&format!(" {} ", word)),
+                        other => return Err(format!("'{}' has no DSL equivalent", other)),
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+}
+
+/// DSL condition for a jq data condition, with the variables it reads: `.field` paths become
+/// variable references and `and`, `or` and `not` become `&&`, `||` and `!`
+fn dsl_condition(expression: &str) -> Result<(String, Vec<String>), String> {
+    let text = expression.trim();
+    let text = text.strip_prefix("${").and_then(|t| t.strip_suffix('}')).unwrap_or(text);
+    let mut jq = Jq { chars: text.chars().collect(), position: 0, variables: vec![] };
+    let (out, negated) = jq.sequence(false)?;
+    let out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    let out = out.replace("( ", "(").replace(" )", ")");
+    Ok((if negated { negate(&out) } else { out }, jq.variables))
+}
+
+/// `!` applied to an expression, parenthesized unless it already is as a whole
+fn negate(expression: &str) -> String {
+    let mut depth = 0;
+    let wrapped = expression.starts_with('(') && expression.char_indices().all(|(i, c)| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        depth > 0 || i == expression.len() - 1
+    });
+    if wrapped { format!("!{}", expression) } else { format!("!({})", expression) }
+}
+
+/// Transition leaving a state's last node
+struct Link {
+    source: String,
+    /// State the transition leads to; `None` ends the workflow
+    target: Option<String>,
+    condition: Option<String>,
+    label: Option<String>,
+}
+
+/// Document being translated
+struct Builder<'a> {
+    functions: HashMap<&'a str, &'a Value>,
+    events: HashMap<&'a str, &'a Value>,
+    retries: HashMap<&'a str, &'a Value>,
+    /// First node of each state, by state name
+    entries: HashMap<&'a str, String>,
+    nodes: Vec<WorkflowNode>,
+    /// Positions recorded in state metadata
+    positions: HashMap<String, Position>,
+    edges: Vec<WorkflowEdge>,
+    links: Vec<Link>,
+    /// Variables read by switch conditions and event correlations, with their type when known
+    variables: Vec<(String, &'static str)>,
+    unmapped: Vec<UnmappedStep>,
+}
+
+impl<'a> Builder<'a> {
+    fn unmap(&mut self, step: &str, app: &str, reason: impl Into<String>) {
+        self.unmapped.push(UnmappedStep { step: step.to_string(), app: app.to_string(), reason: reason.into() });
+    }
+
+    /// Definitions under `key`, by name; definitions kept in a separate file are not fetched
+    fn definitions(&mut self, document: &'a Value, key: &str) -> HashMap<&'a str, &'a Value> {
+        match document.get(key) {
+            Some(Value::Array(items)) => items.iter().filter_map(|d| Some((d.get("name")?.as_str()?, d))).collect(),
+            Some(Value::String(uri)) => {
+                self.unmap(key, key, format!("{} defined in '{}' are not fetched; references to them are imported by name", key, uri));
+                HashMap::new()
+            }
+            _ => HashMap::new(),
+        }
+    }
+
+    fn unique(&self, base: &str) -> String {
+        let base = if base.is_empty() { "state" } else { base };
+        let mut id = base.to_string();
+        let mut n = 1;
+        while self.nodes.iter().any(|node| node.id == id) {
+            n += 1;
+            id = format!("{}_{}", base, n);
+        }
+        id
+    }
+
+    fn add(&mut self, id: String, node_type: NodeType, label: &str, config: Value) -> String {
+        self.nodes.push(WorkflowNode {
+            id: id.clone(),
+            node_type,
+            label: label.to_string(),
+            config,
+            position: Position { x: 0.0, y: 0.0 },
+            retries: None,
+            session: None,
+            annotations: Default::default(),
+            timeouts: None,
+            profile: None,
+        });
+        id
+    }
+
+    fn edge(&mut self, source: &str, target: &str, condition: Option<String>, label: Option<String>) {
+        let mut id = format!("{}-{}", source, target);
+        if self.edges.iter().any(|e| e.id == id) {
+            id = format!("{}-{}", id, self.edges.len() + 1);
+        }
+        self.edges.push(WorkflowEdge { id, source: source.to_string(), target: target.to_string(), condition, label, kind: Default::default() });
+    }
+
+    fn node_mut(&mut self, id: &str) -> &mut WorkflowNode {
+        self.nodes.iter_mut().find(|n| n.id == id).expect("node was added")
+    }
+
+    /// Retry policy from a retry definition
+    fn retry(&mut self, step: &str, name: &str) -> Option<RetryPolicy> {
+        let Some(definition) = self.retries.get(name).copied() else {
+            self.unmap(step, "retryRef", format!("retry definition '{}' is not defined; the action is not retried", name));
+            return None;
+        };
+        let text = |key: &str, default: &str| definition.get(key).and_then(Value::as_str).unwrap_or(default).to_string();
+        let number = |key: &str| definition.get(key).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+        Some(RetryPolicy {
+            max_attempts: number("maxAttempts").map(|n| n.max(1.0) as u32).unwrap_or(3),
+            initial_interval: text("delay", "1s"),
+            max_interval: text("maxDelay", "100s"),
+            backoff_coefficient: number("multiplier").unwrap_or(2.0),
+        })
+    }
+
+    /// Node type and config for calling a function
+    fn function(&mut self, step: &str, reference: &'a Value) -> Result<(NodeType, Value), CompilerError> {
+        let name = name_or(Some(reference), "refName").ok_or_else(|| error(format!("an action of state '{}' has a functionRef without a refName", step)))?;
+        let arguments = reference.get("arguments");
+        let argument = |key: &str| arguments.and_then(|a| a.get(key)).cloned().unwrap_or(Value::Null);
+        let function = self.functions.get(name).copied();
+        let field = |key: &str| function.and_then(|f| f.get(key)).and_then(Value::as_str);
+        // `rest`, an OpenAPI operation, is the specification's default function type
+        let (kind, operation) = (field("type").unwrap_or("rest"), field("operation").unwrap_or_default());
+        if let Some(activity) = operation.strip_prefix(ACTIVITY_OPERATION).filter(|_| kind == "custom") {
+            return Ok((NodeType::Activity, json!({ ACTIVITY_TYPE_KEY: activity })));
+        }
+        if let Some(rest) = operation.strip_prefix("rest:").filter(|_| kind == "custom") {
+            if let Some((method, url)) = rest.split_once(':') {
+                return Ok((NodeType::HttpCall, http_call_config(method, &json!(url), &argument("headers"), &argument("body"))));
+            }
+        }
+        if kind == "expression" {
+            return Ok((NodeType::Transform, json!({ "expression": operation })));
+        }
+        let reason = match function {
+            Some(_) => format!("the {} function '{}' ({}) became an activity named after it", kind, name, operation),
+            None => format!("function '{}' is not defined; it became an activity named after it", name),
+        };
+        self.unmap(step, "functionRef", reason);
+        Ok((NodeType::Activity, json!({ ACTIVITY_TYPE_KEY: name })))
+    }
+
+    /// Node performing an action
+    fn action(&mut self, id: String, label: &str, action: &'a Value) -> Result<String, CompilerError> {
+        let (node_type, config) = if let Some(reference) = action.get("functionRef") {
+            self.function(&id, reference)?
+        } else if let Some(reference) = action.get("eventRef") {
+            let event = reference.get("produceEventRef").or_else(|| reference.get("triggerEventRef")).and_then(Value::as_str)
+                .ok_or_else(|| error(format!("an action of state '{}' has an eventRef without a produceEventRef", label)))?;
+            if let Some(result) = reference.get("resultEventRef").and_then(Value::as_str) {
+                self.unmap(&id, "eventRef", format!("waiting for the result event '{}' is dropped", result));
+            }
+            let topic = self.events.get(event).and_then(|e| e.get("type")).and_then(Value::as_str).unwrap_or(event);
+            (NodeType::PublishEvent, json!({ "topic": topic }))
+        } else if let Some(reference) = action.get("subFlowRef") {
+            let workflow = name_or(Some(reference), "workflowId")
+                .ok_or_else(|| error(format!("an action of state '{}' has a subFlowRef without a workflowId", label)))?;
+            let field = |key: &str| reference.get(key).and_then(Value::as_str);
+            (NodeType::SubWorkflow, json!({
+                "workflow": workflow,
+                "wait_for_completion": field("invoke") != Some("async"),
+                "parent_close_policy": if field("onParentComplete") == Some("continue") { "abandon" } else { "terminate" },
+            }))
+        } else {
+            self.unmap(&id, "action", "an action without a functionRef, eventRef or subFlowRef became a placeholder");
+            (NodeType::Transform, json!({ "source": "serverless_workflow", "element": "action" }))
+        };
+        let retries = match action.get("retryRef").and_then(Value::as_str) {
+            Some(name) => self.retry(&id, name),
+            None => None,
+        };
+        let label = action.get("name").and_then(Value::as_str).unwrap_or(label);
+        let id = self.add(id, node_type, label, config);
+        self.node_mut(&id).retries = retries;
+        Ok(id)
+    }
+
+    /// Nodes for a state's actions, run one after the other or in parallel, returning the
+    /// first and last node
+    fn actions(&mut self, state: &str, actions: &'a [Value], parallel: bool) -> Result<Option<(String, String)>, CompilerError> {
+        let mut ids = vec![];
+        for (index, action) in actions.iter().enumerate() {
+            let label = action.get("name").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| format!("{} {}", state, index + 1));
+            let id = self.unique(&node_id(&label));
+            ids.push(self.action(id, &label, action)?);
+        }
+        let (Some(first), Some(last)) = (ids.first().cloned(), ids.last().cloned()) else { return Ok(None) };
+        if ids.len() > 1 && parallel {
+            let fork = self.unique(&format!("{}_fork", node_id(state)));
+            let fork = self.add(fork, NodeType::ParallelGateway, state, json!({}));
+            let join = self.unique(&format!("{}_join", node_id(state)));
+            let join = self.add(join, NodeType::ParallelGateway, "Join", json!({}));
+            for id in &ids {
+                self.edge(&fork, id, None, None);
+                self.edge(id, &join, None, None);
+            }
+            return Ok(Some((fork, join)));
+        }
+        for pair in ids.windows(2) {
+            self.edge(&pair[0], &pair[1], None, None);
+        }
+        Ok(Some((first, last)))
+    }
+
+    /// Restore the node a state stands for from the metadata the export writes
+    fn restore(&mut self, id: &str, metadata: &Value) -> Result<(), CompilerError> {
+        let text = |key: &str| metadata.get(key).and_then(Value::as_str);
+        let node_type = match text("nodeType") {
+            Some(name) => Some(serde_json::from_value::<NodeType>(json!(name)).map_err(|e| error(format!("state metadata names node type '{}': {}", name, e)))?),
+            None => None,
+        };
+        let config = match text("config") {
+            Some(config) => Some(serde_json::from_str::<Value>(config).map_err(|e| error(format!("the config in the metadata of node '{}' is not JSON: {}", id, e)))?),
+            None => None,
+        };
+        let position = text("position").and_then(|p| p.split_once(',')).and_then(|(x, y)| Some(Position { x: x.trim().parse().ok()?, y: y.trim().parse().ok()? }));
+        if let Some(position) = position {
+            self.positions.insert(id.to_string(), position);
+        }
+        let node = self.node_mut(id);
+        if let Some(node_type) = node_type {
+            node.node_type = node_type;
+            node.config = config.unwrap_or_else(|| json!({}));
+        }
+        if let Some(session) = text("session") {
+            node.session = Some(session.to_string());
+        }
+        if let Some(metadata) = metadata.as_object() {
+            for (key, value) in metadata {
+                if let (Some(key), Some(value)) = (key.strip_prefix("annotation."), value.as_str()) {
+                    node.annotations.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Nodes for a state, with links for its transitions
+    fn state(&mut self, state: &'a Value) -> Result<(), CompilerError> {
+        let name = state.get("name").and_then(Value::as_str).ok_or_else(|| error("a state has no name"))?;
+        let state_type = state.get("type").and_then(Value::as_str).unwrap_or_default();
+        let metadata = state.get("metadata");
+        let recorded = name_or(metadata, "nodeId");
+        let id = self.unique(&recorded.map(str::to_string).unwrap_or_else(|| node_id(name)));
+        // The export names states after their node's label, adding the ID when labels repeat
+        let label = recorded.and_then(|r| name.strip_suffix(r)).and_then(|l| l.strip_suffix(' ')).unwrap_or(name).to_string();
+        let field = |key: &str| state.get(key).and_then(Value::as_str);
+        let list = |key: &str| state.get(key).and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+
+        let (entry, exits): (String, Vec<String>) = match state_type {
+            "operation" => {
+                let actions = list("actions");
+                if actions.len() == 1 {
+                    let node = self.action(id, &label, &actions[0])?;
+                    self.node_mut(&node).label = label.clone();
+                    (node.clone(), vec![node])
+                } else {
+                    match self.actions(name, actions, field("actionMode") == Some("parallel"))? {
+                        Some((first, last)) => (first, vec![last]),
+                        None => {
+                            let node = self.add(id, NodeType::Transform, &label, json!({}));
+                            (node.clone(), vec![node])
+                        }
+                    }
+                }
+            }
+            "switch" => {
+                let node = self.add(id, NodeType::Decision, &label, json!({}));
+                for branch in list("dataConditions") {
+                    let raw = branch.get("condition").and_then(Value::as_str).unwrap_or_default();
+                    let condition = match dsl_condition(raw) {
+                        Ok((condition, read)) => {
+                            self.variables.extend(read.into_iter().map(|name| (name, "")));
+                            condition
+                        }
+                        Err(e) => {
+                            self.unmap(&node, "dataCondition", format!("condition '{}' is kept as written: {}", raw, e));
+                            raw.to_string()
+                        }
+                    };
+                    let label = branch.get("name").and_then(Value::as_str).map(str::to_string);
+                    self.links.push(Link { source: node.clone(), target: transition(branch), condition: Some(condition), label });
+                }
+                if !list("eventConditions").is_empty() {
+                    self.unmap(&node, "eventConditions", "switching on events is dropped; the switch follows its default");
+                }
+                let default = state.get("defaultCondition").unwrap_or(&Value::Null);
+                self.links.push(Link { source: node.clone(), target: transition(default), condition: None, label: None });
+                if let Some(metadata) = metadata {
+                    self.restore(&node, metadata)?;
+                }
+                self.finish(state, &node);
+                self.entries.insert(name, node);
+                return Ok(());
+            }
+            "parallel" => {
+                let join = match (field("completionType"), state.get("numCompleted")) {
+                    (Some("atLeast"), Some(n)) => match n.as_u64().or_else(|| n.as_str()?.parse().ok()) {
+                        Some(1) => json!("any"),
+                        Some(n) => json!({ "n_of_m": n }),
+                        None => json!("all"),
+                    },
+                    _ => json!("all"),
+                };
+                let fork = self.add(id.clone(), NodeType::ParallelGateway, &label, json!({ "join": join }));
+                let join = self.unique(&format!("{}_join", id));
+                let join = self.add(join, NodeType::ParallelGateway, "Join", json!({}));
+                for branch in list("branches") {
+                    let branch_name = branch.get("name").and_then(Value::as_str).unwrap_or(name);
+                    let actions = branch.get("actions").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+                    match self.actions(branch_name, actions, false)? {
+                        Some((first, last)) => {
+                            self.edge(&fork, &first, None, None);
+                            self.edge(&last, &join, None, None);
+                        }
+                        None => self.edge(&fork, &join, None, None),
+                    }
+                }
+                (fork, vec![join])
+            }
+            "sleep" | "delay" => {
+                let duration = field("duration").or_else(|| field("timeDelay")).ok_or_else(|| error(format!("sleep state '{}' has no duration", name)))?;
+                let node = self.add(id, NodeType::WaitTimer, &label, json!({ "duration": duration }));
+                (node.clone(), vec![node])
+            }
+            "event" => {
+                let on_events = list("onEvents");
+                let signals: Vec<&str> = on_events.iter()
+                    .flat_map(|on| on.get("eventRefs").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default())
+                    .filter_map(Value::as_str)
+                    .collect();
+                let exclusive = state.get("exclusive").and_then(Value::as_bool).unwrap_or(true);
+                let timeout = name_or(state.get("timeouts").and_then(|t| t.get("eventTimeout")), "duration");
+                let correlation_key = signals.iter()
+                    .filter_map(|s| self.events.get(s))
+                    .find_map(|e| e.get("correlation")?.get(0)?.get("contextAttributeName")?.as_str());
+                let (node_type, config) = match signals.as_slice() {
+                    [signal] if timeout.is_none() && correlation_key.is_none() => (NodeType::WaitSignal, json!({ "signal": signal })),
+                    _ => {
+                        let mut config = json!({ "signals": signals, "mode": if exclusive { "any" } else { "all" } });
+                        if let Some(key) = correlation_key {
+                            config["correlation_key"] = json!(key);
+                            self.variables.push((key.to_string(), "string"));
+                        }
+                        if let Some(timeout) = timeout {
+                            config["timeout"] = json!(timeout);
+                        }
+                        (NodeType::WaitSignals, config)
+                    }
+                };
+                let wait = self.add(id, node_type, &label, config);
+                let with_actions = on_events.iter().filter(|on| on.get("actions").and_then(Value::as_array).is_some_and(|a| !a.is_empty())).count();
+                if exclusive && with_actions > 1 {
+                    self.unmap(&wait, "onEvents", "actions run for particular events all run after whichever event arrives");
+                }
+                let actions: Vec<&'a Value> = on_events.iter().flat_map(|on| on.get("actions").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()).collect();
+                let mut last = wait.clone();
+                for (index, action) in actions.into_iter().enumerate() {
+                    let action_label = action.get("name").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| format!("{} {}", name, index + 1));
+                    let action_id = self.unique(&node_id(&action_label));
+                    let node = self.action(action_id, &action_label, action)?;
+                    self.edge(&last, &node, None, None);
+                    last = node;
+                }
+                (wait, vec![last])
+            }
+            "callback" => {
+                let action = state.get("action").ok_or_else(|| error(format!("callback state '{}' has no action", name)))?;
+                let node = self.action(id, &label, action)?;
+                let event = field("eventRef").unwrap_or(name);
+                let wait = self.unique(&format!("{}_callback", node));
+                let wait = self.add(wait, NodeType::WaitSignal, &format!("Wait for {}", event), json!({ "signal": event }));
+                self.edge(&node, &wait, None, None);
+                (node, vec![wait])
+            }
+            "inject" => {
+                let data = state.get("data").cloned().unwrap_or_else(|| json!({}));
+                let config = if data.as_object().is_some_and(|d| d.is_empty()) { json!({}) } else { json!({ "data": data }) };
+                let node = self.add(id, NodeType::Transform, &label, config);
+                (node.clone(), vec![node])
+            }
+            other => {
+                let node = self.add(id, NodeType::Transform, &label, json!({ "source": "serverless_workflow", "state": other }));
+                self.unmap(&node, other, format!("{} states have no equivalent; the state became a placeholder", other));
+                (node.clone(), vec![node])
+            }
+        };
+        if let Some(metadata) = metadata {
+            self.restore(&entry, metadata)?;
+        }
+        for exit in exits {
+            self.links.push(Link { source: exit, target: transition(state), condition: None, label: None });
+        }
+        self.finish(state, &entry);
+        self.entries.insert(name, entry);
+        Ok(())
+    }
+
+    /// Report the parts of a state that are dropped
+    fn finish(&mut self, state: &Value, node: &str) {
+        if state.get("onErrors").and_then(Value::as_array).is_some_and(|e| !e.is_empty()) {
+            self.unmap(node, "onErrors", "error transitions are dropped; failures fail the workflow");
+        }
+        if state.get("compensatedBy").is_some() {
+            self.unmap(node, "compensatedBy", "compensation is dropped");
+        }
+    }
+}
+
+/// State a transition leads to, or `None` when it ends the workflow
+fn transition(value: &Value) -> Option<String> {
+    name_or(value.get("transition"), "nextState").map(str::to_string)
+}
+
+/// Workflow variables from the data input schema's properties
+fn variables(document: &Value, unmapped: &mut Vec<UnmappedStep>) -> Vec<Variable> {
+    let Some(input) = document.get("dataInputSchema") else { return vec![] };
+    let schema = input.get("schema").unwrap_or(input);
+    if let Some(uri) = schema.as_str() {
+        unmapped.push(UnmappedStep {
+            step: "dataInputSchema".to_string(),
+            app: "dataInputSchema".to_string(),
+            reason: format!("the schema at '{}' is not fetched; declare the workflow variables by hand", uri),
+        });
+        return vec![];
+    }
+    let required: Vec<&str> = schema.get("required").and_then(Value::as_array).map(|r| r.iter().filter_map(Value::as_str).collect()).unwrap_or_default();
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else { return vec![] };
+    properties.iter().map(|(name, property)| {
+        let var_type = match property.get("type").and_then(Value::as_str) {
+            Some("integer") => "int",
+            Some("number") => "float",
+            Some("boolean") => "bool",
+            Some(t @ ("string" | "object" | "array")) => t,
+            _ => "",
+        };
+        let number = |key: &str| property.get(key).and_then(Value::as_f64);
+        let length = |keys: [&str; 2]| keys.iter().find_map(|key| property.get(*key).and_then(Value::as_u64)).map(|n| n as usize);
+        let constraints = VariableSchema {
+            required: required.contains(&name.as_str()),
+            minimum: number("minimum"),
+            maximum: number("maximum"),
+            min_length: length(["minLength", "minItems"]),
+            max_length: length(["maxLength", "maxItems"]),
+            pattern: property.get("pattern").and_then(Value::as_str).map(str::to_string),
+        };
+        let constrained = constraints.required || constraints.minimum.is_some() || constraints.maximum.is_some()
+            || constraints.min_length.is_some() || constraints.max_length.is_some() || constraints.pattern.is_some();
+        Variable {
+            name: name.clone(),
+            var_type: var_type.to_string(),
+            default_value: property.get("default").cloned(),
+            constant: false,
+            schema: constrained.then_some(constraints),
+        }
+    }).collect()
+}
+
+/// Place nodes the metadata gave no position next to a positioned neighbour, or lay the
+/// whole graph out when the metadata positions none
+fn place(nodes: &mut [WorkflowNode], edges: &[WorkflowEdge], positions: &HashMap<String, Position>) {
+    if positions.is_empty() {
+        layout(nodes, edges);
+        return;
+    }
+    let mut placed = positions.clone();
+    loop {
+        let next = nodes.iter().filter(|n| !placed.contains_key(&n.id)).find_map(|node| {
+            let before = edges.iter().filter(|e| e.target == node.id).find_map(|e| placed.get(&e.source));
+            let after = edges.iter().filter(|e| e.source == node.id).find_map(|e| placed.get(&e.target));
+            match (before, after) {
+                (Some(p), _) => Some((node.id.clone(), Position { x: p.x + NODE_SPACING, y: p.y })),
+                (None, Some(p)) => Some((node.id.clone(), Position { x: p.x - NODE_SPACING, y: p.y })),
+                (None, None) => None,
+            }
+        });
+        let Some((id, position)) = next else { break };
+        placed.insert(id, position);
+    }
+    for node in nodes.iter_mut() {
+        node.position = placed.remove(&node.id).unwrap_or(Position { x: 0.0, y: 0.0 });
+    }
+}
+
+/// Translate a Serverless Workflow document into a definition
+pub fn from_serverless_workflow(document: &Value) -> Result<ImportedWorkflow, CompilerError> {
+    if document.get("document").is_some() && document.get("do").is_some() {
+        return Err(error("documents in the 1.0 DSL are not supported; export the workflow as specification 0.8"));
+    }
+    let states = document.get("states").and_then(Value::as_array).filter(|s| !s.is_empty())
+        .ok_or_else(|| error("the document has no states"))?;
+    let mut builder = Builder {
+        functions: HashMap::new(),
+        events: HashMap::new(),
+        retries: HashMap::new(),
+        entries: HashMap::new(),
+        nodes: vec![],
+        positions: HashMap::new(),
+        edges: vec![],
+        links: vec![],
+        variables: vec![],
+        unmapped: vec![],
+    };
+    builder.functions = builder.definitions(document, "functions");
+    builder.events = builder.definitions(document, "events");
+    builder.retries = builder.definitions(document, "retries");
+    for state in states {
+        if builder.entries.contains_key(state.get("name").and_then(Value::as_str).unwrap_or_default()) {
+            return Err(error(format!("state '{}' is declared twice", state["name"].as_str().unwrap_or_default())));
+        }
+        builder.state(state)?;
+    }
+
+    let start = document.get("start");
+    let first = name_or(start, "stateName").or_else(|| states[0].get("name").and_then(Value::as_str)).unwrap_or_default();
+    let entry = builder.entries.get(first).cloned().ok_or_else(|| error(format!("the start state '{}' is not declared", first)))?;
+    if !matches!(builder.nodes.iter().find(|n| n.id == entry).map(|n| &n.node_type), Some(NodeType::Start)) {
+        let id = builder.unique("start");
+        builder.add(id.clone(), NodeType::Start, "Start", json!({}));
+        builder.edge(&id, &entry, None, None);
+    }
+    let mut end: Option<String> = None;
+    for link in std::mem::take(&mut builder.links) {
+        let target = match &link.target {
+            Some(state) => builder.entries.get(state.as_str()).cloned()
+                .ok_or_else(|| error(format!("'{}' transitions to state '{}', which is not declared", link.source, state)))?,
+            None => match &end {
+                Some(end) => end.clone(),
+                None => {
+                    let id = builder.unique("end");
+                    end = Some(builder.add(id.clone(), NodeType::End, "End", json!({})));
+                    id
+                }
+            },
+        };
+        builder.edge(&link.source, &target, link.condition, link.label);
+    }
+    // Start first, as in the editor's palette order
+    if let Some(index) = builder.nodes.iter().position(|n| matches!(n.node_type, NodeType::Start)) {
+        let start_node = builder.nodes.remove(index);
+        builder.nodes.insert(0, start_node);
+    }
+    place(&mut builder.nodes, &builder.edges, &builder.positions);
+
+    let mut triggers = vec![];
+    if let Some(schedule) = start.and_then(|s| s.get("schedule")) {
+        match name_or(schedule.get("cron"), "expression") {
+            Some(cron) => triggers.push(Trigger { trigger_type: TriggerType::Schedule, config: json!({ "cron": cron, "overlap": "allow_all" }) }),
+            None => builder.unmap("start", "schedule", "interval schedules have no equivalent; add a cron schedule trigger by hand"),
+        }
+    }
+    let mut variables = variables(document, &mut builder.unmapped);
+    for (name, var_type) in std::mem::take(&mut builder.variables) {
+        if !variables.iter().any(|v| v.name == name) {
+            variables.push(Variable { name, var_type: var_type.to_string(), default_value: None, constant: false, schema: None });
+        }
+    }
+    let timeouts = name_or(document.get("timeouts").and_then(|t| t.get("workflowExecTimeout")), "duration")
+        .map(|execution| WorkflowTimeouts { execution: Some(execution.to_string()), run: None, task: None });
+    let text = |key: &str| document.get(key).and_then(Value::as_str);
+    let id = document.get("metadata").and_then(|m| m.get("definitionId")).and_then(Value::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .unwrap_or_else(Uuid::new_v4);
+
+    Ok(ImportedWorkflow {
+        workflow: WorkflowDefinition {
+            id,
+            name: text("name").or_else(|| text("id")).unwrap_or("Serverless workflow").to_string(),
+            version: text("version").unwrap_or("1.0.0").to_string(),
+            description: text("description").map(str::to_string),
+            nodes: builder.nodes,
+            edges: builder.edges,
+            variables,
+            triggers,
+            timeouts,
+        },
+        unmapped: builder.unmapped,
+    })
+}
@@ -0,0 +1,819 @@
+//! Textual workflow DSL
+//!
+//! A compact, line-oriented notation for writing definitions in an editor and reviewing
+//! them as diffs:
+//!
+//! ```text
+//! workflow "Order flow" version "1.2.0"
+//! var amount: float = 0
+//! trigger schedule "0 * * * *"
+//!
+//! activity ChargeCard [retries = 5, timeout = 30s]
+//!   -> decision BigOrder (amount > 100) ? Review : Ship
+//! signal Review "approve" -> Ship
+//! Ship -> parallel Fan -> { activity Pack, http Notify "https://hooks.test/n" } -> end
+//! ```
+//!
+//! Each line is a header, a `var`, a `trigger` or a chain of steps joined by `->`, and a
+//! line starting with `->` continues the chain above. A step declares a node as
+//! `kind Name ["argument"] [key = value, ...]` or refers to one by name; names never
+//! declared become activities. `{ a, b }` fans out to and in from several steps, and
+//! `decision Name (condition) ? yes : no` ends a chain with two branches, the condition
+//! defaulting to a boolean variable named after the decision. Without an explicit `start`
+//! the first chain begins at Start, and without an `end` every step left without a
+//! successor leads to End. Comments start with `#` or `//`.
+
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::error::CompilerError;
+use crate::import::layout;
+use crate::{
+    to_pascal_case, ActivityTimeouts, NodeType, Position, RetryPolicy, ScheduleTriggerConfig, Trigger, TriggerType,
+    Variable, WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    /// Number, possibly with a duration unit such as `30s` or `1h30m`
+    Number(String),
+    /// Raw text between balanced parentheses
+    Condition(String),
+    Arrow,
+    Question,
+    Colon,
+    Comma,
+    Equals,
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+    Newline,
+    End,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Str(text) => write!(f, "string \"{}\"", text),
+            Token::Number(number) => write!(f, "'{}'", number),
+            Token::Condition(condition) => write!(f, "condition '({})'", condition),
+            Token::Arrow => f.write_str("'->'"),
+            Token::Question => f.write_str("'?'"),
+            Token::Colon => f.write_str("':'"),
+            Token::Comma => f.write_str("','"),
+            Token::Equals => f.write_str("'='"),
+            Token::LeftBracket => f.write_str("'['"),
+            Token::RightBracket => f.write_str("']'"),
+            Token::LeftBrace => f.write_str("'{'"),
+            Token::RightBrace => f.write_str("'}'"),
+            Token::Newline => f.write_str("end of line"),
+            Token::End => f.write_str("end of input"),
+        }
+    }
+}
+
+/// Token with the line and column, both from 1, it starts at
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+fn error_at(line: usize, column: usize, message: impl std::fmt::Display) -> CompilerError {
+    CompilerError::ParseError(format!("Invalid workflow text at line {}, column {}: {}", line, column, message))
+}
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>, CompilerError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut line_start) = (0, 1, 0);
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i - line_start + 1;
+        let start = i;
+        let token = match c {
+            '\n' => {
+                i += 1;
+                let token = Spanned { token: Token::Newline, line, column };
+                line += 1;
+                line_start = i;
+                tokens.push(token);
+                continue;
+            }
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '-' if chars.get(i + 1) == Some(&'>') => {
+                i += 2;
+                Token::Arrow
+            }
+            '"' => {
+                i += 1;
+                let mut text = String::new();
+                loop {
+                    match chars.get(i) {
+                        None | Some('\n') => return Err(error_at(line, column, "unterminated string")),
+                        Some('"') => break,
+                        Some('\\') => {
+                            text.push(match chars.get(i + 1) {
+                                Some('n') => '\n',
+                                Some('t') => '\t',
+                                Some(&escaped @ ('"' | '\\')) => escaped,
+                                _ => return Err(error_at(line, i - line_start + 1, "unknown escape in string")),
+                            });
+                            i += 1;
+                        }
+                        Some(&c) => text.push(c),
+                    }
+                    i += 1;
+                }
+                i += 1;
+                Token::Str(text)
+            }
+            '(' => {
+                let mut depth = 0;
+                let mut quote = None;
+                loop {
+                    match (chars.get(i), quote) {
+                        (None, _) | (Some('\n'), _) => return Err(error_at(line, column, "unclosed '('")),
+                        (Some('\\'), Some(_)) => i += 1,
+                        (Some(&c), Some(q)) if c == q => quote = None,
+                        (Some(_), Some(_)) => {}
+                        (Some(&c @ ('"' | '\'')), None) => quote = Some(c),
+                        (Some('('), None) => depth += 1,
+                        (Some(')'), None) => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i += 1;
+                Token::Condition(chars[start + 1..i - 1].iter().collect::<String>().trim().to_string())
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                Token::Number(chars[start..i].iter().collect())
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Word(chars[start..i].iter().collect())
+            }
+            _ => {
+                i += 1;
+                match c {
+                    '?' => Token::Question,
+                    ':' => Token::Colon,
+                    ',' => Token::Comma,
+                    '=' => Token::Equals,
+                    '[' => Token::LeftBracket,
+                    ']' => Token::RightBracket,
+                    '{' => Token::LeftBrace,
+                    '}' => Token::RightBrace,
+                    _ => return Err(error_at(line, column, format!("unexpected character '{}'", c))),
+                }
+            }
+        };
+        tokens.push(Spanned { token, line, column });
+    }
+    let column = chars.len() - line_start + 1;
+    tokens.push(Spanned { token: Token::End, line, column });
+    Ok(tokens)
+}
+
+/// Node kinds a step may declare
+const KINDS: &[(&str, NodeType)] = &[
+    ("activity", NodeType::Activity),
+    ("decision", NodeType::Decision),
+    ("parallel", NodeType::ParallelGateway),
+    ("timer", NodeType::WaitTimer),
+    ("signal", NodeType::WaitSignal),
+    ("subworkflow", NodeType::SubWorkflow),
+    ("http", NodeType::HttpCall),
+    ("query", NodeType::DatabaseQuery),
+    ("transform", NodeType::Transform),
+    ("notify", NodeType::Notification),
+    ("publish", NodeType::PublishEvent),
+    ("end", NodeType::End),
+];
+
+fn kind(word: &str) -> Option<NodeType> {
+    KINDS.iter().find(|(name, _)| *name == word).map(|(_, node_type)| node_type.clone())
+}
+
+/// Node ID for a step name: `ChargeCard` and `"Charge card"` both become `charge_card`
+pub(crate) fn node_id(name: &str) -> String {
+    let mut id = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && previous_lower {
+                id.push('_');
+            }
+            id.extend(c.to_lowercase());
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        } else {
+            if !id.is_empty() && !id.ends_with('_') {
+                id.push('_');
+            }
+            previous_lower = false;
+        }
+    }
+    id.trim_end_matches('_').to_string()
+}
+
+/// Steps a chain element is entered at and left from
+struct Segment {
+    entries: Vec<String>,
+    exits: Vec<String>,
+    /// Decisions branch on their own and cannot be followed by `->`
+    terminal: bool,
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    index: usize,
+    name: Option<String>,
+    version: Option<String>,
+    id: Option<Uuid>,
+    description: Option<String>,
+    variables: Vec<Variable>,
+    triggers: Vec<Trigger>,
+    nodes: Vec<WorkflowNode>,
+    edges: Vec<WorkflowEdge>,
+    /// Referenced step names by node ID, with where each was first used
+    references: Vec<(String, String, usize, usize)>,
+    /// Entries of the first chain, joined to an implicit Start
+    first_entries: Option<Vec<String>>,
+    /// Boolean variables read by decisions without a condition, unless declared with `var`
+    implied_variables: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].token
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> CompilerError {
+        let token = &self.tokens[self.index];
+        error_at(token.line, token.column, message)
+    }
+
+    fn unexpected(&self, expected: &str) -> CompilerError {
+        self.error(format!("expected {}, found {}", expected, self.peek()))
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.index].token.clone();
+        if token != Token::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == token {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), CompilerError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&token.to_string()))
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.eat(&Token::Newline) {}
+    }
+
+    /// Whether the next line starts with `->`, continuing the current chain
+    fn continues_chain(&self) -> bool {
+        let mut index = self.index;
+        while self.tokens[index].token == Token::Newline {
+            index += 1;
+        }
+        self.tokens[index].token == Token::Arrow
+    }
+
+    fn end_statement(&mut self) -> Result<(), CompilerError> {
+        match self.peek() {
+            Token::Newline | Token::End => {
+                self.advance();
+                Ok(())
+            }
+            _ => Err(self.unexpected("end of line")),
+        }
+    }
+
+    fn name(&mut self, what: &str) -> Result<String, CompilerError> {
+        match self.advance() {
+            Token::Word(word) | Token::Str(word) => Ok(word),
+            _ => {
+                self.index -= 1;
+                Err(self.unexpected(what))
+            }
+        }
+    }
+
+    fn text(&mut self, what: &str) -> Result<String, CompilerError> {
+        match self.advance() {
+            Token::Str(text) | Token::Number(text) | Token::Word(text) => Ok(text),
+            _ => {
+                self.index -= 1;
+                Err(self.unexpected(what))
+            }
+        }
+    }
+
+    /// Literal value: a string, a number, a duration or a bare word
+    fn value(&mut self) -> Result<Value, CompilerError> {
+        Ok(match self.advance() {
+            Token::Str(text) => Value::String(text),
+            Token::Number(number) => {
+                number.parse::<i64>().map(Value::from)
+                    .or_else(|_| number.parse::<f64>().map(Value::from))
+                    .unwrap_or(Value::String(number))
+            }
+            Token::Word(word) => match word.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => Value::String(word),
+            },
+            _ => {
+                self.index -= 1;
+                return Err(self.unexpected("a value"));
+            }
+        })
+    }
+
+    /// `[key = value, ...]`, each key with where it was written
+    fn attributes(&mut self) -> Result<Vec<(String, Value, usize, usize)>, CompilerError> {
+        let mut attributes = Vec::new();
+        if !self.eat(&Token::LeftBracket) {
+            return Ok(attributes);
+        }
+        loop {
+            self.skip_newlines();
+            if self.eat(&Token::RightBracket) {
+                return Ok(attributes);
+            }
+            let Spanned { line, column, .. } = self.tokens[self.index].clone();
+            let key = self.name("an attribute name")?;
+            if attributes.iter().any(|(k, ..)| *k == key) {
+                return Err(error_at(line, column, format!("attribute '{}' is set twice", key)));
+            }
+            self.expect(Token::Equals)?;
+            let value = self.value()?;
+            attributes.push((key, value, line, column));
+            self.skip_newlines();
+            if !self.eat(&Token::Comma) && self.peek() != &Token::RightBracket {
+                return Err(self.unexpected("',' or ']'"));
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), CompilerError> {
+        match self.peek().clone() {
+            Token::Word(word) if word == "workflow" => {
+                self.advance();
+                if self.name.is_some() {
+                    return Err(self.error("the workflow header is given twice"));
+                }
+                self.name = Some(self.name("a workflow name")?);
+                loop {
+                    match self.peek().clone() {
+                        Token::Word(word) if word == "version" => {
+                            self.advance();
+                            self.version = Some(self.text("a version")?);
+                        }
+                        Token::Word(word) if word == "id" => {
+                            self.advance();
+                            let Spanned { line, column, .. } = self.tokens[self.index].clone();
+                            let id = self.text("a workflow ID")?;
+                            self.id = Some(Uuid::parse_str(&id).map_err(|e| error_at(line, column, format!("invalid workflow ID: {}", e)))?);
+                        }
+                        _ => break,
+                    }
+                }
+                self.end_statement()
+            }
+            Token::Word(word) if word == "description" => {
+                self.advance();
+                self.description = Some(self.text("a description")?);
+                self.end_statement()
+            }
+            Token::Word(word) if word == "var" => {
+                self.advance();
+                let Spanned { line, column, .. } = self.tokens[self.index].clone();
+                let name = self.name("a variable name")?;
+                if self.variables.iter().any(|v| v.name == name) {
+                    return Err(error_at(line, column, format!("variable '{}' is declared twice", name)));
+                }
+                let var_type = if self.eat(&Token::Colon) { self.name("a variable type")? } else { String::new() };
+                let default_value = if self.eat(&Token::Equals) { Some(self.value()?) } else { None };
+                self.variables.push(Variable { name, var_type, default_value, constant: false, schema: None });
+                self.end_statement()
+            }
+            Token::Word(word) if word == "trigger" => {
+                self.advance();
+                self.trigger()?;
+                self.end_statement()
+            }
+            _ => {
+                self.chain()?;
+                self.end_statement()
+            }
+        }
+    }
+
+    fn trigger(&mut self) -> Result<(), CompilerError> {
+        let kind = self.name("a trigger type")?;
+        let (trigger_type, argument) = match kind.as_str() {
+            "manual" => (TriggerType::Manual, None),
+            "schedule" => (TriggerType::Schedule, Some("cron")),
+            "webhook" => (TriggerType::Webhook, Some("path")),
+            "event" => (TriggerType::Event, Some("event")),
+            _ => {
+                self.index -= 1;
+                return Err(self.error(format!("unknown trigger type '{}'; expected manual, schedule, webhook or event", kind)));
+            }
+        };
+        let mut config = Map::new();
+        if let (Some(key), Token::Str(text)) = (argument, self.peek().clone()) {
+            self.advance();
+            config.insert(key.to_string(), Value::String(text));
+        }
+        for (key, value, ..) in self.attributes()? {
+            config.insert(key, value);
+        }
+        let config = match trigger_type {
+            TriggerType::Manual if config.is_empty() => Value::Null,
+            TriggerType::Schedule => {
+                let schedule: ScheduleTriggerConfig = serde_json::from_value(Value::Object(config))
+                    .map_err(|e| self.error(format!("invalid schedule trigger: {}", e)))?;
+                serde_json::to_value(schedule).unwrap_or_default()
+            }
+            _ => Value::Object(config),
+        };
+        self.triggers.push(Trigger { trigger_type, config });
+        Ok(())
+    }
+
+    fn chain(&mut self) -> Result<(), CompilerError> {
+        let first = self.segment()?;
+        if self.first_entries.is_none() {
+            self.first_entries = Some(first.entries.clone());
+        }
+        let mut previous = first;
+        while self.continues_chain() {
+            self.skip_newlines();
+            if previous.terminal {
+                return Err(self.error("a decision's branches continue on their own lines"));
+            }
+            self.expect(Token::Arrow)?;
+            self.skip_newlines();
+            let next = self.segment()?;
+            for source in &previous.exits {
+                for target in &next.entries {
+                    self.connect(source, target, None);
+                }
+            }
+            previous = next;
+        }
+        Ok(())
+    }
+
+    /// A step or a `{ ... }` group of steps
+    fn segment(&mut self) -> Result<Segment, CompilerError> {
+        if !self.eat(&Token::LeftBrace) {
+            return self.step();
+        }
+        let mut ids = Vec::new();
+        loop {
+            self.skip_newlines();
+            if self.eat(&Token::RightBrace) {
+                break;
+            }
+            let step = self.step()?;
+            if step.terminal {
+                return Err(self.error("a decision cannot be part of a group"));
+            }
+            ids.extend(step.entries);
+            self.skip_newlines();
+            if !self.eat(&Token::Comma) && self.peek() != &Token::RightBrace {
+                return Err(self.unexpected("',' or '}'"));
+            }
+        }
+        if ids.is_empty() {
+            return Err(self.error("a group needs at least one step"));
+        }
+        Ok(Segment { entries: ids.clone(), exits: ids, terminal: false })
+    }
+
+    fn step(&mut self) -> Result<Segment, CompilerError> {
+        let Spanned { token, line, column } = self.tokens[self.index].clone();
+        let single = |id: String| Segment { entries: vec![id.clone()], exits: vec![id], terminal: false };
+        match token {
+            Token::Word(word) if word == "start" => {
+                self.advance();
+                if !self.nodes.iter().any(|n| n.id == "start") {
+                    self.add("start", NodeType::Start, "Start", Map::new(), line, column)?;
+                }
+                Ok(single("start".to_string()))
+            }
+            Token::Word(word) if word == "end" && !matches!(self.tokens[self.index + 1].token, Token::Word(_) | Token::Str(_)) => {
+                self.advance();
+                if !self.nodes.iter().any(|n| n.id == "end") {
+                    self.add("end", NodeType::End, "End", Map::new(), line, column)?;
+                }
+                Ok(single("end".to_string()))
+            }
+            Token::Word(word) if kind(&word).is_some() && matches!(self.tokens[self.index + 1].token, Token::Word(_) | Token::Str(_)) => {
+                self.advance();
+                let node_type = kind(&word).expect("kind was matched");
+                self.declaration(node_type, line, column)
+            }
+            Token::Word(name) | Token::Str(name) => {
+                self.advance();
+                let id = node_id(&name);
+                if id.is_empty() {
+                    return Err(error_at(line, column, format!("'{}' cannot name a step", name)));
+                }
+                if !self.references.iter().any(|(reference, ..)| *reference == id) {
+                    self.references.push((id.clone(), name, line, column));
+                }
+                Ok(single(id))
+            }
+            _ => Err(self.unexpected("a step")),
+        }
+    }
+
+    fn add(&mut self, id: &str, node_type: NodeType, label: &str, config: Map<String, Value>, line: usize, column: usize) -> Result<&mut WorkflowNode, CompilerError> {
+        if let Some(existing) = self.nodes.iter().find(|n| n.id == id) {
+            return Err(error_at(line, column, format!("step '{}' is declared twice; refer to '{}' by name instead", label, existing.label)));
+        }
+        self.nodes.push(WorkflowNode {
+            id: id.to_string(),
+            node_type,
+            label: label.to_string(),
+            config: if config.is_empty() { json!({}) } else { Value::Object(config) },
+            position: Position { x: 0.0, y: 0.0 },
+            retries: None,
+            session: None,
+            annotations: Default::default(),
+            timeouts: None,
+            profile: None,
+        });
+        Ok(self.nodes.last_mut().expect("node was just added"))
+    }
+
+    fn declaration(&mut self, node_type: NodeType, line: usize, column: usize) -> Result<Segment, CompilerError> {
+        let label = self.name("a step name")?;
+        let id = node_id(&label);
+        if id.is_empty() || (id == "start" || id == "end" && !matches!(node_type, NodeType::End)) {
+            return Err(error_at(line, column, format!("'{}' cannot name a step", label)));
+        }
+
+        // The argument after the name fills the kind's main setting
+        let mut config = Map::new();
+        let argument = match node_type {
+            NodeType::Activity => Some("activity"),
+            NodeType::WaitTimer => Some("duration"),
+            NodeType::WaitSignal => Some("signal"),
+            NodeType::SubWorkflow => Some("workflow"),
+            NodeType::HttpCall => Some("url"),
+            NodeType::PublishEvent => Some("topic"),
+            _ => None,
+        };
+        if let (Some(key), Token::Str(text) | Token::Number(text)) = (argument, self.peek().clone()) {
+            self.advance();
+            config.insert(key.to_string(), Value::String(text));
+        }
+        match node_type {
+            NodeType::Activity => {
+                config.entry("activity").or_insert_with(|| Value::String(to_pascal_case(&label)));
+            }
+            NodeType::WaitSignal => {
+                config.entry("signal").or_insert_with(|| Value::String(id.clone()));
+            }
+            NodeType::SubWorkflow => {
+                config.entry("workflow").or_insert_with(|| Value::String(to_pascal_case(&label)));
+            }
+            NodeType::HttpCall => {
+                config.insert("method".to_string(), Value::String("GET".to_string()));
+            }
+            _ => {}
+        }
+
+        let mut retries = None;
+        let mut timeouts = ActivityTimeouts::default();
+        let (mut session, mut profile) = (None, None);
+        for (key, value, line, column) in self.attributes()? {
+            let text = || match &value {
+                Value::String(text) => Ok(text.clone()),
+                other => Err(error_at(line, column, format!("'{}' takes a string or duration, not {}", key, other))),
+            };
+            match key.as_str() {
+                "retries" => {
+                    let attempts = value.as_u64().filter(|&n| n >= 1).ok_or_else(|| error_at(line, column, "'retries' takes a number of attempts"))?;
+                    retries = Some(RetryPolicy {
+                        max_attempts: attempts as u32,
+                        initial_interval: "1s".to_string(),
+                        max_interval: "100s".to_string(),
+                        backoff_coefficient: 2.0,
+                    });
+                }
+                "timeout" => timeouts.start_to_close = Some(text()?),
+                "schedule_to_close" => timeouts.schedule_to_close = Some(text()?),
+                "heartbeat" => timeouts.heartbeat = Some(text()?),
+                "session" => session = Some(text()?),
+                "profile" => profile = Some(text()?),
+                "join" if matches!(node_type, NodeType::ParallelGateway) => {
+                    let join = match &value {
+                        Value::Number(n) => n.as_u64().map(|n| json!({ "n_of_m": n })),
+                        Value::String(policy) if policy == "all" || policy == "any" => Some(value.clone()),
+                        _ => None,
+                    };
+                    let join = join.ok_or_else(|| error_at(line, column, "'join' takes all, any or a number of branches"))?;
+                    config.insert(key, join);
+                }
+                _ => {
+                    config.insert(key, value);
+                }
+            }
+        }
+
+        let decision = matches!(node_type, NodeType::Decision);
+        let node = self.add(&id, node_type, &label, config, line, column)?;
+        node.retries = retries;
+        node.timeouts = (timeouts != ActivityTimeouts::default()).then_some(timeouts);
+        node.session = session;
+        node.profile = profile;
+        if decision {
+            return self.branches(id, &label);
+        }
+        Ok(Segment { entries: vec![id.clone()], exits: vec![id], terminal: false })
+    }
+
+    /// `(condition) ? yes : no` after a decision's name
+    fn branches(&mut self, id: String, label: &str) -> Result<Segment, CompilerError> {
+        let condition = match self.peek().clone() {
+            Token::Condition(condition) => {
+                self.advance();
+                if condition.is_empty() {
+                    return Err(self.error("empty decision condition"));
+                }
+                condition
+            }
+            _ => {
+                // The decision reads a boolean variable of its own name
+                let variable = node_id(label);
+                self.implied_variables.push(variable.clone());
+                variable
+            }
+        };
+        self.expect(Token::Question)?;
+        self.skip_newlines();
+        let yes = self.step()?;
+        self.skip_newlines();
+        self.expect(Token::Colon)?;
+        self.skip_newlines();
+        let no = self.step()?;
+        if yes.terminal || no.terminal {
+            return Err(self.error("a decision branch cannot be another decision; declare it on its own line"));
+        }
+        if yes.entries == no.entries {
+            return Err(self.error("both branches of a decision lead to the same step"));
+        }
+        self.connect(&id, &yes.entries[0], Some(condition));
+        self.connect(&id, &no.entries[0], None);
+        Ok(Segment { entries: vec![id], exits: vec![], terminal: true })
+    }
+
+    fn connect(&mut self, source: &str, target: &str, condition: Option<String>) {
+        if self.edges.iter().any(|e| e.source == source && e.target == target && e.condition == condition) {
+            return;
+        }
+        let mut id = format!("{}-{}", source, target);
+        if self.edges.iter().any(|e| e.id == id) {
+            id = format!("{}-{}", id, self.edges.len() + 1);
+        }
+        self.edges.push(WorkflowEdge {
+            id,
+            source: source.to_string(),
+            target: target.to_string(),
+            condition,
+            label: None,
+            kind: Default::default(),
+        });
+    }
+
+    fn finish(mut self) -> Result<WorkflowDefinition, CompilerError> {
+        // Names used without a declaration are activities
+        for (id, name, line, column) in std::mem::take(&mut self.references) {
+            if !self.nodes.iter().any(|n| n.id == id) {
+                let config = Map::from_iter([("activity".to_string(), Value::String(to_pascal_case(&name)))]);
+                self.add(&id, NodeType::Activity, &name, config, line, column)?;
+            }
+        }
+        for name in std::mem::take(&mut self.implied_variables) {
+            if !self.variables.iter().any(|v| v.name == name) {
+                self.variables.push(Variable { name, var_type: "bool".to_string(), default_value: None, constant: false, schema: None });
+            }
+        }
+        if self.nodes.is_empty() {
+            return Err(self.error("the workflow has no steps"));
+        }
+        if !self.nodes.iter().any(|n| matches!(n.node_type, NodeType::Start)) {
+            self.add("start", NodeType::Start, "Start", Map::new(), 1, 1)?;
+            for entry in self.first_entries.take().unwrap_or_default() {
+                self.connect("start", &entry, None);
+            }
+            let start = self.nodes.pop().expect("start was just added");
+            self.nodes.insert(0, start);
+        }
+        if !self.nodes.iter().any(|n| matches!(n.node_type, NodeType::End)) {
+            let open: Vec<String> = self.nodes.iter()
+                .filter(|n| !self.edges.iter().any(|e| e.source == n.id))
+                .map(|n| n.id.clone())
+                .collect();
+            self.add("end", NodeType::End, "End", Map::new(), 1, 1)?;
+            for id in open {
+                self.connect(&id, "end", None);
+            }
+        }
+        layout(&mut self.nodes, &self.edges);
+
+        Ok(WorkflowDefinition {
+            id: self.id.unwrap_or_else(Uuid::new_v4),
+            name: self.name.unwrap_or_else(|| "workflow".to_string()),
+            version: self.version.unwrap_or_else(|| "1.0.0".to_string()),
+            description: self.description,
+            nodes: self.nodes,
+            edges: self.edges,
+            variables: self.variables,
+            triggers: self.triggers,
+            timeouts: None,
+        })
+    }
+}
+
+/// Parse a workflow written in the textual DSL
+pub fn parse_text(source: &str) -> Result<WorkflowDefinition, CompilerError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        index: 0,
+        name: None,
+        version: None,
+        id: None,
+        description: None,
+        variables: vec![],
+        triggers: vec![],
+        nodes: vec![],
+        edges: vec![],
+        references: vec![],
+        first_entries: None,
+        implied_variables: vec![],
+    };
+    loop {
+        parser.skip_newlines();
+        if parser.peek() == &Token::End {
+            break;
+        }
+        parser.statement()?;
+    }
+    parser.finish()
+}
@@ -0,0 +1,225 @@
+//! Minimal XML reader for imported documents
+//!
+//! Parses a document into an element tree, enough for the diagram formats the importers
+//! read: namespace prefixes are dropped from element and attribute names, comments and
+//! processing instructions are skipped, and entity and character references are decoded.
+//! Documents with a DTD are rejected rather than expanded, and so are documents nesting
+//! elements deeper than `MAX_DEPTH`.
+
+use crate::error::CompilerError;
+
+/// Deepest element nesting read; diagram formats stay far below it
+pub const MAX_DEPTH: usize = 64;
+
+/// Element with its attributes, children and text content
+#[derive(Debug, Clone, Default)]
+pub struct Element {
+    /// Local name, without its namespace prefix
+    pub name: String,
+    /// Attributes by local name, in document order
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Text and CDATA directly inside the element, concatenated
+    pub text: String,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Children with the given local name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// This element and every element below it, depth first
+    pub fn descendants(&self) -> Vec<&Element> {
+        let mut all = vec![self];
+        for child in &self.children {
+            all.extend(child.descendants());
+        }
+        all
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+struct Reader<'a> {
+    source: &'a str,
+    position: usize,
+    /// Elements open around the current position
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, message: impl std::fmt::Display) -> CompilerError {
+        let line = self.source[..self.position].matches('\n').count() + 1;
+        CompilerError::ParseError(format!("Invalid XML at line {}: {}", line, message))
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Move past the next `terminator`, returning the text before it
+    fn take_until(&mut self, terminator: &str) -> Result<&'a str, CompilerError> {
+        let rest = self.rest();
+        let end = rest.find(terminator).ok_or_else(|| self.error(format!("missing '{}'", terminator)))?;
+        self.position += end + terminator.len();
+        Ok(&rest[..end])
+    }
+
+    fn name(&mut self) -> Result<&'a str, CompilerError> {
+        let rest = self.rest();
+        let end = rest.find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=')).unwrap_or(rest.len());
+        if end == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.position += end;
+        Ok(&rest[..end])
+    }
+
+    /// Skip comments, processing instructions and whitespace between elements
+    fn skip_misc(&mut self) -> Result<(), CompilerError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.take_until("?>")?;
+            } else if self.rest().starts_with("<!DOCTYPE") {
+                return Err(self.error("document type declarations are not supported"));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self) -> Result<Element, CompilerError> {
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        self.position += 1;
+        let tag = self.name()?;
+        let mut element = Element { name: local_name(tag).to_string(), ..Default::default() };
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error(format!("attribute '{}' has no value", attribute)));
+            }
+            self.position += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error(format!("value of attribute '{}' is not quoted", attribute))),
+            };
+            self.position += 1;
+            let raw = self.take_until(&quote.to_string())?;
+            let value = decode(raw).map_err(|e| self.error(e))?;
+            // Namespace declarations only matter for prefixes, which are dropped
+            if attribute != "xmlns" && !attribute.starts_with("xmlns:") {
+                element.attributes.push((local_name(attribute).to_string(), value));
+            }
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error(format!("element '{}' is not closed", tag)));
+            }
+            if rest.starts_with("</") {
+                self.position += 2;
+                let closing = self.name()?;
+                if closing != tag {
+                    return Err(self.error(format!("'{}' closed by '{}'", tag, closing)));
+                }
+                self.skip_whitespace();
+                self.take_until(">")?;
+                return Ok(element);
+            }
+            if rest.starts_with("<![CDATA[") {
+                self.position += "<![CDATA[".len();
+                element.text.push_str(self.take_until("]]>")?);
+            } else if rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if rest.starts_with("<?") {
+                self.take_until("?>")?;
+            } else if rest.starts_with('<') {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error(format!("elements nest deeper than {} levels", MAX_DEPTH)));
+                }
+                self.depth += 1;
+                let child = self.element();
+                self.depth -= 1;
+                element.children.push(child?);
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.position += end;
+                element.text.push_str(&decode(&rest[..end]).map_err(|e| self.error(e))?);
+            }
+        }
+    }
+}
+
+/// Replace entity and character references
+fn decode(raw: &str) -> Result<String, String> {
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or("unterminated entity reference")? + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => entity.strip_prefix('#').and_then(|decimal| decimal.parse().ok()),
+                };
+                code.and_then(char::from_u32).ok_or_else(|| format!("unknown entity '&{};'", entity))?
+            }
+        };
+        decoded.push(c);
+        rest = &rest[end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+/// Parse a document into its root element
+pub fn parse(source: &str) -> Result<Element, CompilerError> {
+    let mut reader = Reader { source: source.trim_start_matches('\u{feff}'), position: 0, depth: 1 };
+    reader.skip_misc()?;
+    let root = reader.element()?;
+    reader.skip_misc()?;
+    if !reader.rest().is_empty() {
+        return Err(reader.error("content after the root element"));
+    }
+    Ok(root)
+}
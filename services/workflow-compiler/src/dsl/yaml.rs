@@ -0,0 +1,66 @@
+//! YAML front-end for workflow definitions
+//!
+//! Sources are read with serde_yaml under the YAML 1.2 core schema, so block and flow
+//! collections, every scalar style, comments, and anchors with aliases and `<<` merge keys
+//! so shared config is written once, all work. Tags are read and ignored; scalar keys other
+//! than strings are used as their text. A source holds a single document. serde_yaml bounds
+//! nesting depth and alias expansion, so hostile sources fail with a parse error rather than
+//! exhausting the stack or memory.
+
+use serde_json::{Map, Value};
+use serde_yaml::Value as Yaml;
+
+use crate::error::CompilerError;
+use crate::WorkflowDefinition;
+
+fn error(message: impl std::fmt::Display) -> CompilerError {
+    CompilerError::ParseError(format!("Invalid YAML: {}", message))
+}
+
+/// Text of a mapping key, which JSON objects need to be a string
+fn key(key: Yaml) -> Result<String, CompilerError> {
+    match key {
+        Yaml::String(s) => Ok(s),
+        Yaml::Bool(b) => Ok(b.to_string()),
+        Yaml::Number(n) => Ok(n.to_string()),
+        Yaml::Null => Ok("null".to_string()),
+        Yaml::Tagged(tagged) => self::key(tagged.value),
+        Yaml::Sequence(_) | Yaml::Mapping(_) => Err(error("mapping keys must be scalars")),
+    }
+}
+
+fn to_json(value: Yaml) -> Result<Value, CompilerError> {
+    Ok(match value {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(b),
+        Yaml::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(i), _, _) => Value::from(i),
+            (None, Some(u), _) => Value::from(u),
+            (None, None, Some(f)) => serde_json::Number::from_f64(f).map(Value::Number).ok_or_else(|| error(format!("number {} has no JSON form", n)))?,
+            (None, None, None) => return Err(error(format!("number {} has no JSON form", n))),
+        },
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(items) => Value::Array(items.into_iter().map(to_json).collect::<Result<_, _>>()?),
+        Yaml::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (k, v) in mapping {
+                map.insert(key(k)?, to_json(v)?);
+            }
+            Value::Object(map)
+        }
+        Yaml::Tagged(tagged) => to_json(tagged.value)?,
+    })
+}
+
+/// Parse a YAML document into the JSON value it denotes
+pub fn to_value(source: &str) -> Result<Value, CompilerError> {
+    let mut value: Yaml = serde_yaml::from_str(source.trim_start_matches('\u{feff}')).map_err(error)?;
+    value.apply_merge().map_err(error)?;
+    to_json(value)
+}
+
+/// Parse a workflow definition written in YAML
+pub fn parse_yaml(source: &str) -> Result<WorkflowDefinition, CompilerError> {
+    let value = to_value(source)?;
+    serde_json::from_value(value).map_err(|e| CompilerError::ParseError(format!("Invalid workflow definition: {}", e)))
+}
@@ -2,23 +2,118 @@
 
 use thiserror::Error;
 
+use crate::compiler::diagnostic::Diagnostic;
+use crate::compiler::rules::Severity;
+
 #[derive(Error, Debug)]
 pub enum CompilerError {
     #[error("Validation error: {0}")]
-    ValidationError(String),
+    ValidationError(Diagnostic),
     
     #[error("Parse error: {0}")]
     ParseError(String),
     
-    #[error("Cycle detected in workflow graph")]
-    CycleDetected,
+    /// A cycle of flow edges through no loop-marked Decision; `path` lists its node IDs from
+    /// the first node on it back to that node
+    #[error("Cycle detected in workflow graph: {}; mark a Decision on it with \"loop\": true if the loop is intended", path.join(" -> "))]
+    CycleDetected { path: Vec<String> },
     
+    /// Nodes unreachable from Start or without a path to End
+    #[error("{0}")]
+    UnconnectedNodes(crate::compiler::validator::UnconnectedNodes),
+
     #[error("Code generation error: {0}")]
     CodeGenError(String),
     
+    #[error("Deployment error: {0}")]
+    DeploymentError(String),
+    
     #[error("Template error: {0}")]
     TemplateError(#[from] handlebars::TemplateError),
     
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    /// A conditional write expected a stored workflow at `expected`, 0 for not stored yet
+    #[error("Workflow '{name}' is at version {latest}, not the expected version {expected}")]
+    VersionConflict { name: String, expected: u32, latest: u32 },
+
+    /// An error raised by a validation rule, displayed as the error itself
+    #[error("{error}")]
+    Rule { code: &'static str, error: Box<CompilerError> },
+
+    /// A compilation ran past its wall-clock limit and was stopped at `stage`
+    #[error("Compilation timed out during {stage}: {elapsed_ms}ms elapsed, over the limit of {limit_ms}ms")]
+    CompilationTimedOut { stage: &'static str, limit_ms: u64, elapsed_ms: u64 },
+
+    /// A compilation allocated past its memory ceiling and was stopped at `stage`
+    #[error("Compilation exceeded its memory ceiling during {stage}: {used_bytes} bytes allocated, over the limit of {limit_bytes}")]
+    CompilationMemoryExceeded { stage: &'static str, limit_bytes: u64, used_bytes: u64 },
+}
+
+impl CompilerError {
+    /// Attribute the error to the rule with `code`, unless a more specific rule already claimed
+    /// it; compilations stopped by their limits are not a rule's failure
+    pub fn with_code(self, code: &'static str) -> Self {
+        match self {
+            CompilerError::Rule { .. } | CompilerError::CompilationTimedOut { .. } | CompilerError::CompilationMemoryExceeded { .. } => self,
+            error => CompilerError::Rule { code, error: Box::new(error) },
+        }
+    }
+
+    /// Node path of the cycle behind the error
+    pub fn cycle(&self) -> Option<&[String]> {
+        match self {
+            CompilerError::CycleDetected { path } => Some(path),
+            CompilerError::Rule { error, .. } => error.cycle(),
+            _ => None,
+        }
+    }
+
+    /// Disconnected nodes behind the error
+    pub fn unconnected(&self) -> Option<&crate::compiler::validator::UnconnectedNodes> {
+        match self {
+            CompilerError::UnconnectedNodes(nodes) => Some(nodes),
+            CompilerError::Rule { error, .. } => error.unconnected(),
+            _ => None,
+        }
+    }
+
+    /// Code of the rule that raised the error, as listed in `compiler::rules::RULES`
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            CompilerError::Rule { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Structured form of the error, with its rule's code and the position of the
+    /// node or edge at fault in `definition`; cycles and disconnected nodes point at their first node
+    pub fn diagnostic(&self, definition: &crate::WorkflowDefinition) -> Diagnostic {
+        self.unlocated().locate(definition)
+    }
+
+    fn unlocated(&self) -> Diagnostic {
+        match self {
+            CompilerError::Rule { code, error } => Diagnostic {
+                code: Some(code),
+                // Warning rules a validation profile escalates fail validation too
+                severity: Severity::Error,
+                ..error.unlocated()
+            },
+            CompilerError::ValidationError(diagnostic) => diagnostic.clone(),
+            CompilerError::CycleDetected { path } => match path.first() {
+                Some(first) => Diagnostic::new(self.to_string()).node(first),
+                None => Diagnostic::new(self.to_string()),
+            },
+            CompilerError::UnconnectedNodes(nodes) => match nodes.unreachable.iter().chain(&nodes.no_path_to_end).next() {
+                Some(first) => Diagnostic::new(self.to_string()).node(first),
+                None => Diagnostic::new(self.to_string()),
+            },
+            _ => Diagnostic::new(self.to_string()),
+        }
+    }
 }
@@ -0,0 +1,652 @@
+//! Camunda 8 export: BPMN 2.0 XML with Zeebe extension elements, deployable to Zeebe and
+//! editable in the Camunda Modeler
+//!
+//! Nodes map one-to-one onto flow elements and edges onto sequence flows, so the export keeps
+//! the shape of the graph, and the diagram section places each element at its node's
+//! position. Activities become service tasks whose job type is the activity name, HttpCall
+//! nodes service tasks for the REST connector, decisions exclusive gateways with FEEL
+//! conditions, subworkflows call activities, timers timer catch events and signal waits
+//! message catch events. Retry policies set the job retries, with the intervals passed to the
+//! worker as task headers. Constructs without a clean equivalent are reported as diagnostics;
+//! the XML is only returned when none of them is an error.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::compiler::codegen::{activity_name, is_activity_node, schedule_triggers};
+use crate::compiler::duration::{parse_duration, to_iso8601};
+use crate::compiler::rules::Severity;
+use crate::dsl::graph;
+use crate::export::Diagnostic;
+use crate::{
+    to_pascal_case, DynamicActivityConfig, EdgeKind, JoinPolicy, NodeType, OverlapPolicy, ParallelGatewayConfig,
+    PublishEventConfig, SignalWaitMode, SubWorkflowConfig, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig,
+    WorkflowDefinition, WorkflowEdge, WorkflowNode,
+};
+
+/// Camunda 8 release the Modeler is told the diagram targets; version tags need 8.6
+const CAMUNDA_VERSION: &str = "8.6.0";
+
+/// Job type of Camunda's outbound REST connector
+const HTTP_CONNECTOR: &str = "io.camunda:http-json:1";
+
+/// Process variable messages are correlated on when a wait names no correlation key
+const DEFAULT_CORRELATION_VARIABLE: &str = "workflowId";
+
+/// BPMN shape sizes the Modeler uses by default
+const EVENT_SIZE: f64 = 36.0;
+const GATEWAY_SIZE: f64 = 50.0;
+const TASK_WIDTH: f64 = 100.0;
+const TASK_HEIGHT: f64 = 80.0;
+
+/// Exported BPMN, present only when no diagnostic is an error
+#[derive(Debug, Clone, Serialize)]
+pub struct BpmnExport {
+    pub xml: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Diagram bounds of a flow element
+struct Shape {
+    id: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+struct Flow {
+    id: String,
+    source: String,
+    target: String,
+    name: Option<String>,
+    /// FEEL condition, without the leading `=`
+    condition: Option<String>,
+}
+
+struct Builder<'a> {
+    definition: &'a WorkflowDefinition,
+    diagnostics: Vec<Diagnostic>,
+    /// Flow elements of the process as XML, in definition order
+    elements: Vec<String>,
+    flows: Vec<Flow>,
+    shapes: Vec<Shape>,
+    /// Message IDs keyed by message name and correlation key expression
+    messages: BTreeMap<(String, String), String>,
+    /// Whether any message is correlated on `DEFAULT_CORRELATION_VARIABLE`
+    uses_default_correlation: bool,
+}
+
+/// XML-escaped text, safe in attribute values and element content
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// `name` attribute for a label, or nothing for an empty one
+fn named(name: &str) -> String {
+    if name.is_empty() { String::new() } else { format!(" name=\"{}\"", escape(name)) }
+}
+
+/// BPMN element ID for a graph ID, which must be an XML name
+fn element_id(raw: &str) -> String {
+    let id: String = raw.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' { c } else { '_' }).collect();
+    match id.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => id,
+        _ => format!("Node_{}", id),
+    }
+}
+
+/// FEEL literal for a JSON value; FEEL contexts, lists, strings and numbers share JSON's syntax
+fn feel(value: &Value) -> String {
+    format!("={}", value)
+}
+
+/// Five-field cron expression in the six-field, seconds-first form Zeebe timers expect
+fn zeebe_cron(cron: &str) -> String {
+    match cron.split_whitespace().count() {
+        5 => format!("0 {}", cron.trim()),
+        _ => cron.trim().to_string(),
+    }
+}
+
+/// FEEL expression for an edge condition: workflow variables become process variables and
+/// `&&`, `||`, `!` and `==` become `and`, `or`, `not()` and `=`
+fn feel_condition(definition: &WorkflowDefinition, condition: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut negate = false;
+    let mut chars = condition.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                Value::String(text).to_string()
+            }
+            '&' | '|' => {
+                if chars.next() != Some(c) {
+                    return Err(format!("unsupported operator '{}'", c));
+                }
+                out.push_str(if c == '&' { " and " } else { " or " });
+                continue;
+            }
+            '=' if chars.peek() == Some(&'=') => {
+                chars.next();
+                out.push('=');
+                continue;
+            }
+            '!' if chars.peek() != Some(&'=') => {
+                // `not` applies to a parenthesised group as a function call, and to a single
+                // operand by wrapping it
+                if chars.peek() == Some(&'(') {
+                    out.push_str("not");
+                } else {
+                    negate = true;
+                }
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_alphanumeric() || **ch == '_' || **ch == '.') {
+                    word.push(ch);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "true" | "false" | "null" => word,
+                    _ => {
+                        let name = word.strip_prefix("input.").unwrap_or(&word);
+                        let (head, rest) = name.split_once('.').map_or((name, ""), |(h, r)| (h, r));
+                        let variable = definition.variables.iter()
+                            .find(|v| v.name == head || to_pascal_case(&v.name) == head)
+                            .ok_or_else(|| format!("'{}' is not a workflow variable", head))?;
+                        if rest.is_empty() { variable.name.clone() } else { format!("{}.{}", variable.name, rest) }
+                    }
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let mut number = c.to_string();
+                while let Some(&ch) = chars.peek().filter(|ch| ch.is_ascii_digit() || **ch == '.') {
+                    number.push(ch);
+                    chars.next();
+                }
+                number
+            }
+            c => {
+                if negate && !c.is_whitespace() {
+                    return Err(format!("'!' cannot apply to '{}'", c));
+                }
+                out.push(c);
+                continue;
+            }
+        };
+        if std::mem::take(&mut negate) {
+            out.push_str(&format!("not({})", token));
+        } else {
+            out.push_str(&token);
+        }
+    }
+    if negate {
+        return Err("'!' has no operand".to_string());
+    }
+    Ok(out.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+impl<'a> Builder<'a> {
+    /// Add a flow element and its diagram shape; `attributes` follow the ID, and `extensions`
+    /// and `body` are the element's Zeebe extension elements and child elements, one per line
+    fn element(&mut self, tag: &str, id: &str, attributes: &str, extensions: Vec<String>, body: Vec<String>, (x, y, width, height): (f64, f64, f64, f64)) {
+        let mut xml = format!("    <bpmn:{} id=\"{}\"{}", tag, id, attributes);
+        if extensions.is_empty() && body.is_empty() {
+            xml.push_str(" />");
+        } else {
+            xml.push_str(">\n");
+            if !extensions.is_empty() {
+                xml.push_str("      <bpmn:extensionElements>\n");
+                for line in extensions {
+                    xml.push_str(&format!("        {}\n", line));
+                }
+                xml.push_str("      </bpmn:extensionElements>\n");
+            }
+            for line in body {
+                xml.push_str(&format!("      {}\n", line));
+            }
+            xml.push_str(&format!("    </bpmn:{}>", tag));
+        }
+        self.elements.push(xml);
+        self.shapes.push(Shape { id: id.to_string(), x, y, width, height });
+    }
+
+    /// Message definition for a signal, returning its ID
+    fn message(&mut self, signal: &str, correlation_key: Option<&str>) -> String {
+        let key = match correlation_key {
+            Some(variable) => format!("={}", variable),
+            None => {
+                self.uses_default_correlation = true;
+                format!("={}", DEFAULT_CORRELATION_VARIABLE)
+            }
+        };
+        let taken: Vec<String> = self.messages.values().cloned().collect();
+        self.messages.entry((signal.to_string(), key)).or_insert_with(|| {
+            let base = element_id(&format!("Message_{}", signal));
+            let mut id = base.clone();
+            let mut suffix = 2;
+            while taken.contains(&id) {
+                id = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            id
+        }).clone()
+    }
+
+    /// Zeebe extension elements setting a node's annotations as element properties
+    fn properties(node: &WorkflowNode) -> Vec<String> {
+        if node.annotations.is_empty() {
+            return vec![];
+        }
+        let mut lines = vec!["<zeebe:properties>".to_string()];
+        for (name, value) in &node.annotations {
+            lines.push(format!("  <zeebe:property name=\"{}\" value=\"{}\" />", escape(name), escape(value)));
+        }
+        lines.push("</zeebe:properties>".to_string());
+        lines
+    }
+
+    /// Job definition of a task run by a job worker, with its retry policy
+    fn task_definition(&mut self, node: &WorkflowNode, job_type: &str, headers: &mut Vec<(String, String)>) -> String {
+        let mut definition = format!("<zeebe:taskDefinition type=\"{}\"", escape(job_type));
+        if let Some(retries) = &node.retries {
+            definition.push_str(&format!(" retries=\"{}\"", retries.max_attempts.max(1)));
+            for (field, raw) in [("initial_interval", &retries.initial_interval), ("max_interval", &retries.max_interval)] {
+                match parse_duration(raw) {
+                    Ok(interval) => headers.push((format!("retry.{}", field), to_iso8601(interval))),
+                    Err(e) => self.diagnostics.push(Diagnostic::error(Some(&node.id), format!("Invalid retry {}: {}", field, e))),
+                }
+            }
+            headers.push(("retry.backoff_coefficient".to_string(), retries.backoff_coefficient.to_string()));
+        }
+        definition + " />"
+    }
+
+    fn task_headers(headers: Vec<(String, String)>) -> Vec<String> {
+        if headers.is_empty() {
+            return vec![];
+        }
+        let mut lines = vec!["<zeebe:taskHeaders>".to_string()];
+        for (key, value) in headers {
+            lines.push(format!("  <zeebe:header key=\"{}\" value=\"{}\" />", escape(&key), escape(&value)));
+        }
+        lines.push("</zeebe:taskHeaders>".to_string());
+        lines
+    }
+
+    /// Emit the elements for a node, returning the IDs its incoming and outgoing flows attach to
+    fn node(&mut self, node: &'a WorkflowNode) -> (String, String) {
+        let id = element_id(&node.id);
+        let node_id = Some(node.id.as_str());
+        let (x, y) = (node.position.x, node.position.y);
+        let event = (x, y, EVENT_SIZE, EVENT_SIZE);
+        let gateway = (x, y, GATEWAY_SIZE, GATEWAY_SIZE);
+        let task = (x, y, TASK_WIDTH, TASK_HEIGHT);
+        if node.session.is_some() {
+            self.diagnostics.push(Diagnostic::warning(node_id, "Worker session groups have no Zeebe equivalent and are ignored"));
+        }
+        let mut headers = vec![];
+        let mut extensions = vec![];
+        match node.node_type {
+            NodeType::Start => self.element("startEvent", &id, &named(&node.label), Self::properties(node), vec![], event),
+            NodeType::End => self.element("endEvent", &id, &named(&node.label), Self::properties(node), vec![], event),
+            NodeType::Decision => {
+                let edges: Vec<_> = graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow).collect();
+                if !edges.iter().any(|e| e.condition.as_deref().is_some_and(|c| !c.trim().is_empty())) {
+                    self.diagnostics.push(Diagnostic::error(node_id, "Decision has no conditional edges"));
+                }
+                let defaults: Vec<_> = edges.iter().filter(|e| e.condition.as_deref().is_none_or(|c| c.trim().is_empty())).collect();
+                if defaults.len() > 1 {
+                    self.diagnostics.push(Diagnostic::error(node_id, "Decision has more than one edge without a condition"));
+                }
+                let attributes = defaults.first().map(|e| format!(" default=\"{}\"", flow_id(&e.id))).unwrap_or_default();
+                self.element("exclusiveGateway", &id, &(named(&node.label) + &attributes), Self::properties(node), vec![], gateway);
+            }
+            NodeType::ParallelGateway => {
+                let config: ParallelGatewayConfig = node.typed_config().unwrap_or_default();
+                if !matches!(config.join, JoinPolicy::All) {
+                    self.diagnostics.push(Diagnostic::error(node_id, "Zeebe parallel gateways join every branch; any and n_of_m joins have no equivalent"));
+                }
+                self.element("parallelGateway", &id, &named(&node.label), Self::properties(node), vec![], gateway);
+            }
+            NodeType::HttpCall => {
+                let config = |key: &str| node.config.get(key).filter(|v| !v.is_null());
+                let method = config("method").and_then(Value::as_str).unwrap_or("GET").to_ascii_uppercase();
+                let mut inputs = vec![
+                    ("authentication.type".to_string(), "noAuth".to_string()),
+                    ("method".to_string(), method),
+                ];
+                match config("url") {
+                    Some(url) => inputs.push(("url".to_string(), feel(url))),
+                    None => self.diagnostics.push(Diagnostic::error(node_id, "HttpCall node has no url")),
+                }
+                for key in ["headers", "body"] {
+                    if let Some(value) = config(key) {
+                        inputs.push((key.to_string(), feel(value)));
+                    }
+                }
+                extensions.push(self.task_definition(node, HTTP_CONNECTOR, &mut headers));
+                extensions.push("<zeebe:ioMapping>".to_string());
+                for (target, source) in inputs {
+                    extensions.push(format!("  <zeebe:input source=\"{}\" target=\"{}\" />", escape(&source), target));
+                }
+                extensions.push("</zeebe:ioMapping>".to_string());
+                extensions.extend(Self::task_headers(headers));
+                extensions.extend(Self::properties(node));
+                self.element("serviceTask", &id, &named(&node.label), extensions, vec![], task);
+            }
+            NodeType::PublishEvent => {
+                // Message throw events are completed by a job worker, like service tasks
+                let config: PublishEventConfig = node.typed_config().unwrap_or_default();
+                headers.push(("topic".to_string(), config.topic));
+                extensions.push(self.task_definition(node, &activity_name(node), &mut headers));
+                extensions.extend(Self::task_headers(headers));
+                extensions.extend(Self::properties(node));
+                let body = vec![format!("<bpmn:messageEventDefinition id=\"{}_message\" />", id)];
+                self.element("intermediateThrowEvent", &id, &named(&node.label), extensions, body, event);
+            }
+            _ if is_activity_node(node) => {
+                extensions.push(self.task_definition(node, &activity_name(node), &mut headers));
+                extensions.extend(Self::task_headers(headers));
+                extensions.extend(Self::properties(node));
+                self.element("serviceTask", &id, &named(&node.label), extensions, vec![], task);
+            }
+            NodeType::DynamicActivity => {
+                let config: DynamicActivityConfig = node.typed_config().unwrap_or_default();
+                self.diagnostics.push(Diagnostic::warning(node_id, "The dynamic activity allowlist is not enforced by Zeebe; the selector variable is the job type"));
+                extensions.push(self.task_definition(node, &format!("={}", config.selector), &mut headers));
+                extensions.extend(Self::task_headers(headers));
+                extensions.extend(Self::properties(node));
+                self.element("serviceTask", &id, &named(&node.label), extensions, vec![], task);
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config().unwrap_or_default();
+                if config.namespace.is_some() || config.task_queue.is_some() {
+                    self.diagnostics.push(Diagnostic::warning(node_id, "Child workflow namespace and task queue are ignored; the child runs as another process on the same cluster"));
+                }
+                if !config.wait_for_completion {
+                    self.diagnostics.push(Diagnostic::warning(node_id, "Zeebe call activities always wait for the called process to complete"));
+                }
+                extensions.push(format!("<zeebe:calledElement processId=\"{}\" propagateAllChildVariables=\"false\" />", escape(&config.workflow)));
+                extensions.extend(Self::properties(node));
+                self.element("callActivity", &id, &named(&node.label), extensions, vec![], task);
+            }
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config().unwrap_or_default();
+                let duration = match parse_duration(&config.duration) {
+                    Ok(duration) => to_iso8601(duration),
+                    Err(e) => {
+                        self.diagnostics.push(Diagnostic::error(node_id, format!("Invalid timer duration '{}': {}", config.duration, e)));
+                        String::new()
+                    }
+                };
+                let body = vec![
+                    format!("<bpmn:timerEventDefinition id=\"{}_timer\">", id),
+                    format!("  <bpmn:timeDuration xsi:type=\"bpmn:tFormalExpression\">{}</bpmn:timeDuration>", duration),
+                    "</bpmn:timerEventDefinition>".to_string(),
+                ];
+                self.element("intermediateCatchEvent", &id, &named(&node.label), Self::properties(node), body, event);
+            }
+            NodeType::WaitSignal => {
+                let config: WaitSignalConfig = node.typed_config().unwrap_or_default();
+                let message = self.message(&config.signal, None);
+                let body = vec![format!("<bpmn:messageEventDefinition id=\"{}_message\" messageRef=\"{}\" />", id, message)];
+                self.element("intermediateCatchEvent", &id, &named(&node.label), Self::properties(node), body, event);
+            }
+            NodeType::WaitSignals => return self.wait_signals(node, &id),
+            NodeType::Transform => self.element("task", &id, &named(&node.label), Self::properties(node), vec![], task),
+            NodeType::Notification => {
+                self.diagnostics.push(Diagnostic::warning(node_id, "Notification nodes are not compiled to an activity and are exported as undefined tasks"));
+                self.element("task", &id, &named(&node.label), Self::properties(node), vec![], task);
+            }
+            _ => self.diagnostics.push(Diagnostic::error(node_id, format!("{:?} nodes have no Zeebe equivalent", node.node_type))),
+        }
+        (id.clone(), id)
+    }
+
+    /// Message catch events for a WaitSignals node between a split gateway and a merge: a
+    /// parallel gateway pair when every signal is needed, and an event-based gateway closed
+    /// by an exclusive gateway when one is enough
+    fn wait_signals(&mut self, node: &WorkflowNode, id: &str) -> (String, String) {
+        let config: WaitSignalsConfig = node.typed_config().unwrap_or_default();
+        if config.timeout.is_some() {
+            self.diagnostics.push(Diagnostic::warning(Some(&node.id), "Signal wait timeouts are not exported; add a timer boundary event in the Modeler"));
+        }
+        let (x, y) = (node.position.x, node.position.y);
+        let correlation_key = config.correlation_key.as_deref();
+        if config.signals.len() <= 1 {
+            let signal = config.signals.first().cloned().unwrap_or_default();
+            let message = self.message(&signal, correlation_key);
+            let body = vec![format!("<bpmn:messageEventDefinition id=\"{}_message\" messageRef=\"{}\" />", id, message)];
+            self.element("intermediateCatchEvent", id, &named(&node.label), Self::properties(node), body, (x, y, EVENT_SIZE, EVENT_SIZE));
+            return (id.to_string(), id.to_string());
+        }
+
+        let (split, merge) = match config.mode {
+            SignalWaitMode::All => ("parallelGateway", "parallelGateway"),
+            SignalWaitMode::Any => ("eventBasedGateway", "exclusiveGateway"),
+        };
+        let split_id = format!("{}_split", id);
+        let merge_id = format!("{}_merge", id);
+        let row = TASK_HEIGHT;
+        self.element(split, &split_id, &named(&node.label), Self::properties(node), vec![], (x, y, GATEWAY_SIZE, GATEWAY_SIZE));
+        for (index, signal) in config.signals.iter().enumerate() {
+            let event_id = element_id(&format!("{}_{}", id, signal));
+            let message = self.message(signal, correlation_key);
+            let body = vec![format!("<bpmn:messageEventDefinition id=\"{}_message\" messageRef=\"{}\" />", event_id, message)];
+            let bounds = (x + 2.0 * GATEWAY_SIZE, y + row * index as f64, EVENT_SIZE, EVENT_SIZE);
+            self.element("intermediateCatchEvent", &event_id, &named(signal), vec![], body, bounds);
+            for (source, target) in [(split_id.clone(), event_id.clone()), (event_id.clone(), merge_id.clone())] {
+                self.flows.push(Flow { id: format!("Flow_{}_{}", source, target), source, target, name: None, condition: None });
+            }
+        }
+        self.element(merge, &merge_id, "", vec![], vec![], (x + 3.0 * GATEWAY_SIZE + EVENT_SIZE, y, GATEWAY_SIZE, GATEWAY_SIZE));
+        (split_id, merge_id)
+    }
+
+    /// Timer start events for the definition's schedules, each flowing to what the Start node
+    /// leads to
+    fn schedules(&mut self, start: &WorkflowNode, starts: &HashMap<&str, (String, String)>) {
+        let triggers = match schedule_triggers(self.definition) {
+            Ok(triggers) => triggers,
+            Err(e) => {
+                self.diagnostics.push(Diagnostic::error(None, e.to_string()));
+                return;
+            }
+        };
+        let targets: Vec<String> = graph::outgoing_edges(self.definition, &start.id)
+            .filter(|e| e.kind == EdgeKind::Flow)
+            .filter_map(|e| starts.get(e.target.as_str()).map(|(entry, _)| entry.clone()))
+            .collect();
+        for (index, trigger) in triggers.iter().enumerate() {
+            if trigger.paused {
+                self.diagnostics.push(Diagnostic::warning(None, format!("Paused schedule '{}' is not exported", trigger.cron)));
+                continue;
+            }
+            if trigger.overlap != OverlapPolicy::default() || trigger.catchup_window.is_some() {
+                self.diagnostics.push(Diagnostic::warning(None, format!("Overlap and catch-up settings of schedule '{}' have no Zeebe equivalent", trigger.cron)));
+            }
+            let id = format!("{}_schedule_{}", element_id(&start.id), index + 1);
+            let body = vec![
+                format!("<bpmn:timerEventDefinition id=\"{}_timer\">", id),
+                format!("  <bpmn:timeCycle xsi:type=\"bpmn:tFormalExpression\">{}</bpmn:timeCycle>", escape(&zeebe_cron(&trigger.cron))),
+                "</bpmn:timerEventDefinition>".to_string(),
+            ];
+            let bounds = (start.position.x, start.position.y + TASK_HEIGHT * (index + 1) as f64, EVENT_SIZE, EVENT_SIZE);
+            let name = trigger.note.clone().unwrap_or_else(|| trigger.cron.clone());
+            self.element("startEvent", &id, &named(&name), vec![], body, bounds);
+            for target in &targets {
+                self.flows.push(Flow { id: format!("Flow_{}_{}", id, target), source: id.clone(), target: target.clone(), name: None, condition: None });
+            }
+        }
+    }
+
+    /// Sequence flow for an edge between exported nodes
+    fn edge(&mut self, edge: &WorkflowEdge, ends: &HashMap<&str, (String, String)>) {
+        let (Some((_, source)), Some((target, _))) = (ends.get(edge.source.as_str()), ends.get(edge.target.as_str())) else { return };
+        let from_decision = graph::find_node(self.definition, &edge.source).is_some_and(|n| matches!(n.node_type, NodeType::Decision));
+        let condition = match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            Some(condition) if from_decision => match feel_condition(self.definition, condition) {
+                Ok(expression) => Some(expression),
+                Err(e) => {
+                    self.diagnostics.push(Diagnostic::error(
+                        Some(&edge.source),
+                        format!("Condition '{}' on edge '{}' cannot be expressed in FEEL: {}", condition, edge.id, e),
+                    ));
+                    None
+                }
+            },
+            Some(_) => {
+                self.diagnostics.push(Diagnostic::warning(Some(&edge.source), format!("Condition on edge '{}' is dropped; only decisions branch on conditions", edge.id)));
+                None
+            }
+            None => None,
+        };
+        self.flows.push(Flow { id: flow_id(&edge.id), source: source.clone(), target: target.clone(), name: edge.label.clone(), condition });
+    }
+
+    fn render(self, process_id: &str) -> String {
+        let definition = self.definition;
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<bpmn:definitions xmlns:bpmn=\"http://www.omg.org/spec/BPMN/20100524/MODEL\" xmlns:bpmndi=\"http://www.omg.org/spec/BPMN/20100524/DI\" \
+             xmlns:dc=\"http://www.omg.org/spec/DD/20100524/DC\" xmlns:di=\"http://www.omg.org/spec/DD/20100524/DI\" \
+             xmlns:zeebe=\"http://camunda.org/schema/zeebe/1.0\" xmlns:modeler=\"http://camunda.org/schema/modeler/1.0\" \
+             xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" id=\"Definitions_{}\" targetNamespace=\"http://bpmn.io/schema/bpmn\" \
+             exporter=\"OmniRoute Workflow Compiler\" exporterVersion=\"{}\" modeler:executionPlatform=\"Camunda Cloud\" modeler:executionPlatformVersion=\"{}\">\n",
+            process_id, env!("CARGO_PKG_VERSION"), CAMUNDA_VERSION
+        ));
+        for ((name, key), id) in &self.messages {
+            xml.push_str(&format!("  <bpmn:message id=\"{}\" name=\"{}\">\n", id, escape(name)));
+            xml.push_str(&format!(
+                "    <bpmn:extensionElements>\n      <zeebe:subscription correlationKey=\"{}\" />\n    </bpmn:extensionElements>\n",
+                escape(key)
+            ));
+            xml.push_str("  </bpmn:message>\n");
+        }
+        xml.push_str(&format!("  <bpmn:process id=\"{}\" name=\"{}\" isExecutable=\"true\">\n", process_id, escape(&definition.name)));
+        if let Some(description) = definition.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            xml.push_str(&format!("    <bpmn:documentation>{}</bpmn:documentation>\n", escape(description)));
+        }
+        xml.push_str(&format!(
+            "    <bpmn:extensionElements>\n      <zeebe:versionTag value=\"{}\" />\n    </bpmn:extensionElements>\n",
+            escape(&definition.version)
+        ));
+        for element in &self.elements {
+            xml.push_str(element);
+            xml.push('\n');
+        }
+        for flow in &self.flows {
+            xml.push_str(&format!("    <bpmn:sequenceFlow id=\"{}\"", flow.id));
+            if let Some(name) = flow.name.as_deref().filter(|n| !n.is_empty()) {
+                xml.push_str(&format!(" name=\"{}\"", escape(name)));
+            }
+            xml.push_str(&format!(" sourceRef=\"{}\" targetRef=\"{}\"", flow.source, flow.target));
+            match &flow.condition {
+                Some(condition) => xml.push_str(&format!(
+                    ">\n      <bpmn:conditionExpression xsi:type=\"bpmn:tFormalExpression\">={}</bpmn:conditionExpression>\n    </bpmn:sequenceFlow>\n",
+                    escape(condition)
+                )),
+                None => xml.push_str(" />\n"),
+            }
+        }
+        xml.push_str("  </bpmn:process>\n");
+
+        xml.push_str("  <bpmndi:BPMNDiagram id=\"Diagram_1\">\n");
+        xml.push_str(&format!("    <bpmndi:BPMNPlane id=\"Plane_1\" bpmnElement=\"{}\">\n", process_id));
+        for shape in &self.shapes {
+            xml.push_str(&format!(
+                "      <bpmndi:BPMNShape id=\"{0}_di\" bpmnElement=\"{0}\">\n        <dc:Bounds x=\"{1}\" y=\"{2}\" width=\"{3}\" height=\"{4}\" />\n      </bpmndi:BPMNShape>\n",
+                shape.id, shape.x, shape.y, shape.width, shape.height
+            ));
+        }
+        let bounds: HashMap<&str, &Shape> = self.shapes.iter().map(|s| (s.id.as_str(), s)).collect();
+        for flow in &self.flows {
+            let (Some(source), Some(target)) = (bounds.get(flow.source.as_str()), bounds.get(flow.target.as_str())) else { continue };
+            xml.push_str(&format!(
+                "      <bpmndi:BPMNEdge id=\"{}_di\" bpmnElement=\"{}\">\n        <di:waypoint x=\"{}\" y=\"{}\" />\n        <di:waypoint x=\"{}\" y=\"{}\" />\n      </bpmndi:BPMNEdge>\n",
+                flow.id, flow.id,
+                source.x + source.width, source.y + source.height / 2.0,
+                target.x, target.y + target.height / 2.0
+            ));
+        }
+        xml.push_str("    </bpmndi:BPMNPlane>\n  </bpmndi:BPMNDiagram>\n</bpmn:definitions>\n");
+        xml
+    }
+}
+
+/// Sequence flow ID for an edge
+fn flow_id(edge_id: &str) -> String {
+    element_id(&format!("Flow_{}", edge_id))
+}
+
+/// Process ID for a workflow name: its words in snake_case
+fn process_id(name: &str) -> String {
+    let id = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("_");
+    element_id(if id.is_empty() { "workflow" } else { &id })
+}
+
+/// Convert a definition into Zeebe BPMN XML with diagnostics
+pub fn export(definition: &WorkflowDefinition) -> BpmnExport {
+    let mut builder = Builder {
+        definition,
+        diagnostics: vec![],
+        elements: vec![],
+        flows: vec![],
+        shapes: vec![],
+        messages: BTreeMap::new(),
+        uses_default_correlation: false,
+    };
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Cancel) {
+        builder.diagnostics.push(Diagnostic::error(Some(&edge.source), format!("Cancel edge '{}' has no Zeebe equivalent", edge.id)));
+    }
+    if definition.timeouts.as_ref().is_some_and(|t| t.execution.is_some() || t.run.is_some() || t.task.is_some()) {
+        builder.diagnostics.push(Diagnostic::warning(None, "Workflow timeouts have no Zeebe equivalent and are not exported"));
+    }
+
+    let reachable = graph::reachable_nodes(definition);
+    let mut ends = HashMap::new();
+    for node in &definition.nodes {
+        if reachable.contains(node.id.as_str()) {
+            ends.insert(node.id.as_str(), builder.node(node));
+        } else {
+            builder.diagnostics.push(Diagnostic::warning(Some(&node.id), "Node is unreachable from the Start node and is not exported"));
+        }
+    }
+    match definition.nodes.iter().find(|n| matches!(n.node_type, NodeType::Start)) {
+        Some(start) => builder.schedules(start, &ends),
+        None => builder.diagnostics.push(Diagnostic::error(None, "The workflow needs a Start node")),
+    }
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Flow) {
+        builder.edge(edge, &ends);
+    }
+    if builder.uses_default_correlation {
+        builder.diagnostics.push(Diagnostic::warning(
+            None,
+            format!("Signals without a correlation key are correlated on the {} process variable, which starters must set", DEFAULT_CORRELATION_VARIABLE),
+        ));
+    }
+
+    let failed = builder.diagnostics.iter().any(|d| matches!(d.severity, Severity::Error));
+    let diagnostics = builder.diagnostics.clone();
+    let xml = (!failed).then(|| builder.render(&process_id(&definition.name)));
+    BpmnExport { xml, diagnostics }
+}
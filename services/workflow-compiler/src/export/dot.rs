@@ -0,0 +1,120 @@
+//! Graphviz DOT rendering of workflow definitions
+//!
+//! Shapes follow the node type so `dot` output reads at a glance, and every field the
+//! renderer does not draw is kept in an attribute of its own (`node_type`, `config`,
+//! `retries`, the graph's `variables`, ...), so `dsl::from_dot` reads the export back into
+//! the same definition.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{EdgeKind, NodeType, WorkflowDefinition, WorkflowNode};
+
+/// Quoted DOT ID
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn json_attribute(value: &impl Serialize) -> String {
+    quote(&serde_json::to_string(value).unwrap_or_default())
+}
+
+/// Shape and style drawn for a node type
+fn shape(node_type: &NodeType) -> (&'static str, Option<&'static str>) {
+    match node_type {
+        NodeType::Start => ("circle", None),
+        NodeType::End => ("doublecircle", None),
+        NodeType::Decision | NodeType::DecisionTable | NodeType::FeatureFlag | NodeType::WeightedSplit => ("diamond", None),
+        NodeType::ParallelGateway => ("Mdiamond", None),
+        NodeType::WaitTimer | NodeType::WaitSignal | NodeType::WaitSignals => ("octagon", None),
+        NodeType::SubWorkflow => ("box3d", None),
+        NodeType::DatabaseQuery => ("cylinder", None),
+        NodeType::HttpCall | NodeType::NexusOperation => ("component", None),
+        NodeType::PublishEvent | NodeType::Notification => ("cds", None),
+        NodeType::CancellationScope => ("box", Some("dashed")),
+        _ => ("box", Some("rounded")),
+    }
+}
+
+fn node_type_name(node_type: &NodeType) -> String {
+    match serde_json::to_value(node_type) {
+        Ok(Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn node_line(node: &WorkflowNode) -> String {
+    let (shape, style) = shape(&node.node_type);
+    let mut attributes = vec![
+        format!("label={}", quote(&node.label)),
+        format!("node_type={}", quote(&node_type_name(&node.node_type))),
+        format!("shape={}", shape),
+    ];
+    attributes.extend(style.map(|style| format!("style={}", style)));
+    // Graphviz's y axis points up, the editor's down
+    attributes.push(format!("pos=\"{},{}\"", node.position.x, 0.0 - node.position.y));
+    if !node.config.is_null() && node.config != Value::Object(Default::default()) {
+        attributes.push(format!("config={}", json_attribute(&node.config)));
+    }
+    if let Some(retries) = &node.retries {
+        attributes.push(format!("retries={}", json_attribute(retries)));
+    }
+    if let Some(timeouts) = &node.timeouts {
+        attributes.push(format!("timeouts={}", json_attribute(timeouts)));
+    }
+    if !node.annotations.is_empty() {
+        attributes.push(format!("annotations={}", json_attribute(&node.annotations)));
+    }
+    if let Some(session) = &node.session {
+        attributes.push(format!("session={}", quote(session)));
+    }
+    if let Some(profile) = &node.profile {
+        attributes.push(format!("profile={}", quote(profile)));
+    }
+    format!("    {} [{}];", quote(&node.id), attributes.join(", "))
+}
+
+/// Render the definition as a DOT digraph
+pub fn export(definition: &WorkflowDefinition) -> String {
+    let mut lines = vec![format!("digraph {} {{", quote(&definition.name))];
+    lines.push(format!("    label={};", quote(&definition.name)));
+    lines.push(format!("    workflow_id={};", quote(&definition.id.to_string())));
+    lines.push(format!("    version={};", quote(&definition.version)));
+    if let Some(description) = &definition.description {
+        lines.push(format!("    description={};", quote(description)));
+    }
+    if !definition.variables.is_empty() {
+        lines.push(format!("    variables={};", json_attribute(&definition.variables)));
+    }
+    if !definition.triggers.is_empty() {
+        lines.push(format!("    triggers={};", json_attribute(&definition.triggers)));
+    }
+    if let Some(timeouts) = &definition.timeouts {
+        lines.push(format!("    timeouts={};", json_attribute(timeouts)));
+    }
+    lines.push("    rankdir=LR;".to_string());
+    lines.push("    node [fontname=\"Helvetica\"];".to_string());
+    lines.push("    edge [fontname=\"Helvetica\"];".to_string());
+    lines.push(String::new());
+    lines.extend(definition.nodes.iter().map(node_line));
+    lines.push(String::new());
+    for edge in &definition.edges {
+        let mut attributes = vec![];
+        if let Some(label) = edge.label.as_ref().or(edge.condition.as_ref()) {
+            attributes.push(format!("label={}", quote(label)));
+        }
+        if let Some(condition) = &edge.condition {
+            attributes.push(format!("condition={}", quote(condition)));
+        }
+        if edge.kind == EdgeKind::Cancel {
+            attributes.push("kind=\"cancel\"".to_string());
+            attributes.push("style=dashed".to_string());
+        }
+        attributes.push(format!("id={}", quote(&edge.id)));
+        lines.push(format!("    {} -> {} [{}];", quote(&edge.source), quote(&edge.target), attributes.join(", ")));
+    }
+    lines.push("}".to_string());
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
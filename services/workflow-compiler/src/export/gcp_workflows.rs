@@ -0,0 +1,534 @@
+//! Google Cloud Workflows export: YAML workflow definitions for workflows that can run
+//! without a Temporal cluster
+//!
+//! HttpCall nodes become `http.*` calls, decisions `switch` steps, parallel gateways
+//! `parallel` steps and timers `sys.sleep` calls. Other activities are posted to the service
+//! named by the `ACTIVITY_BASE_URL` environment variable, and signals wait on HTTP callback
+//! endpoints. Constructs without a clean equivalent are reported as diagnostics; the YAML is
+//! only returned when none of them is an error.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::compiler::codegen::{activity_name, is_activity_node};
+use crate::compiler::duration::parse_duration;
+use crate::compiler::rules::Severity;
+use crate::dsl::graph;
+use crate::export::{yaml, Diagnostic};
+use crate::{
+    to_pascal_case, DynamicActivityConfig, EdgeKind, JoinPolicy, NodeType, ParallelGatewayConfig, PublishEventConfig, RetryPolicy,
+    SignalWaitMode, SubWorkflowConfig, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig, WorkflowDefinition, WorkflowNode,
+};
+
+/// Environment variable holding the base URL activities are posted to
+const ACTIVITY_BASE_URL: &str = "ACTIVITY_BASE_URL";
+
+/// Longest wait Cloud Workflows allows for `sys.sleep` and callbacks, one year
+const MAX_WAIT_SECONDS: u64 = 31_536_000;
+
+/// Jump target ending the workflow, or the branch of a parallel step
+const END: &str = "end";
+
+/// Exported workflow, present only when no diagnostic is an error
+#[derive(Debug, Clone, Serialize)]
+pub struct GcpWorkflowsExport {
+    pub yaml: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Steps of one scope, the main workflow or a parallel branch, in emission order
+type Steps = Vec<(String, Value)>;
+
+struct Builder<'a> {
+    definition: &'a WorkflowDefinition,
+    diagnostics: Vec<Diagnostic>,
+    /// Whether any step posts to `ACTIVITY_BASE_URL`
+    uses_activity_service: bool,
+}
+
+impl<'a> Builder<'a> {
+    /// Step name for a node: its label in snake_case, with the node ID when the label is not
+    /// unique
+    fn step_name(&self, node: &WorkflowNode) -> String {
+        let duplicate = self.definition.nodes.iter().filter(|n| n.label == node.label).count() > 1;
+        let base = if duplicate || node.label.trim().is_empty() {
+            format!("{} {}", node.label, node.id)
+        } else {
+            node.label.clone()
+        };
+        let name = base
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("_");
+        match name.as_str() {
+            "" => "step".to_string(),
+            _ if name.starts_with(|c: char| c.is_ascii_digit()) || name == END => format!("step_{}", name),
+            _ => name,
+        }
+    }
+
+    fn flow_targets(&self, node: &WorkflowNode) -> Vec<&'a WorkflowNode> {
+        graph::outgoing_edges(self.definition, &node.id)
+            .filter(|e| e.kind == EdgeKind::Flow)
+            .filter_map(|e| graph::find_node(self.definition, &e.target))
+            .collect()
+    }
+
+    /// Whether a node is a parallel gateway closing a fork
+    fn is_join(&self, node: &WorkflowNode) -> bool {
+        matches!(node.node_type, NodeType::ParallelGateway) && self.flow_targets(node).len() <= 1
+    }
+
+    /// Whether a node needs no step of its own and jumps straight to its successor
+    fn is_passthrough(&self, node: &WorkflowNode) -> bool {
+        matches!(node.node_type, NodeType::Start | NodeType::Transform) || self.is_join(node)
+    }
+
+    /// Single successor of a node with sequential semantics
+    fn successor(&mut self, node: &WorkflowNode) -> Option<&'a WorkflowNode> {
+        let targets = self.flow_targets(node);
+        if targets.len() > 1 {
+            self.diagnostics.push(Diagnostic::error(
+                Some(&node.id),
+                format!("{:?} node has {} outgoing edges; only decisions and parallel gateways may branch", node.node_type, targets.len()),
+            ));
+        }
+        targets.first().copied()
+    }
+
+    /// Jump target for continuing to `next`: its step, or `end` when there is none or it is
+    /// the join closing the enclosing parallel branch
+    fn jump(&mut self, next: Option<&'a WorkflowNode>, steps: &mut Steps, join: Option<&str>) -> String {
+        let mut skipped = HashSet::new();
+        let mut next = next;
+        while let Some(node) = next {
+            if Some(node.id.as_str()) == join {
+                break;
+            }
+            if !self.is_passthrough(node) {
+                return self.emit(node, steps, join);
+            }
+            if !skipped.insert(node.id.as_str()) {
+                self.diagnostics.push(Diagnostic::error(Some(&node.id), "Loop passes through no node that produces a step"));
+                break;
+            }
+            next = self.successor(node);
+        }
+        END.to_string()
+    }
+
+    /// Emit the step for a node and everything it leads to, returning its name
+    fn emit(&mut self, node: &'a WorkflowNode, steps: &mut Steps, join: Option<&str>) -> String {
+        let name = self.step_name(node);
+        if steps.iter().any(|(n, _)| *n == name) {
+            return name;
+        }
+        // Reserve the slot first so loops back to this node terminate
+        let slot = steps.len();
+        steps.push((name.clone(), Value::Null));
+
+        let (mut step, next) = match node.node_type {
+            NodeType::Decision => (self.switch(node, steps, join), None),
+            NodeType::ParallelGateway => {
+                let (step, after) = self.parallel(node);
+                (step, Some(after))
+            }
+            NodeType::End => (json!({ "return": "${input}" }), None),
+            _ => {
+                let step = self.call(node, &name);
+                (step, Some(self.successor(node)))
+            }
+        };
+        if let Some(next) = next {
+            let target = self.jump(next, steps, join);
+            step["next"] = json!(target);
+        }
+        steps[slot].1 = step;
+        name
+    }
+
+    /// Step for a node without branching semantics
+    fn call(&mut self, node: &WorkflowNode, name: &str) -> Value {
+        let id = Some(node.id.as_str());
+        if node.session.is_some() {
+            self.diagnostics.push(Diagnostic::warning(id, "Worker session groups have no Cloud Workflows equivalent and are ignored"));
+        }
+        let step = match node.node_type {
+            NodeType::HttpCall => self.http_call(node),
+            NodeType::PublishEvent => {
+                let config: PublishEventConfig = node.typed_config().unwrap_or_default();
+                json!({
+                    "call": "googleapis.pubsub.v1.projects.topics.publish",
+                    "args": {
+                        "topic": format!("${{\"projects/\" + sys.get_env(\"GOOGLE_CLOUD_PROJECT_ID\") + \"/topics/\" + {}}}", quote(&config.topic)),
+                        "body": { "messages": [{ "data": "${base64.encode(json.encode(input))}" }] },
+                    },
+                })
+            }
+            _ if is_activity_node(node) => self.activity_post(&quote(&format!("/{}", activity_name(node)))),
+            NodeType::DynamicActivity => {
+                let config: DynamicActivityConfig = node.typed_config().unwrap_or_default();
+                self.diagnostics.push(Diagnostic::warning(id, "The dynamic activity allowlist is not enforced by Cloud Workflows; the selector names the activity path directly"));
+                self.activity_post(&format!("\"/\" + input.{}", config.selector))
+            }
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config().unwrap_or_default();
+                match parse_duration(&config.duration) {
+                    Ok(duration) => json!({ "call": "sys.sleep", "args": { "seconds": duration.as_secs().clamp(1, MAX_WAIT_SECONDS) } }),
+                    Err(e) => {
+                        self.diagnostics.push(Diagnostic::error(id, format!("Invalid timer duration '{}': {}", config.duration, e)));
+                        Value::Null
+                    }
+                }
+            }
+            NodeType::WaitSignal => {
+                let config: WaitSignalConfig = node.typed_config().unwrap_or_default();
+                self.signal_callbacks(node, name, &[config.signal], None)
+            }
+            NodeType::WaitSignals => {
+                let config: WaitSignalsConfig = node.typed_config().unwrap_or_default();
+                if config.correlation_key.is_some() {
+                    self.diagnostics.push(Diagnostic::warning(id, "Signal correlation keys are not checked by Cloud Workflows; each callback URL already identifies the execution"));
+                }
+                let timeout = config.timeout.as_deref().and_then(|t| parse_duration(t).ok()).map(|d| d.as_secs());
+                if matches!(config.mode, SignalWaitMode::Any) && config.signals.len() > 1 {
+                    self.diagnostics.push(Diagnostic::error(id, "Waiting for any one of several signals has no Cloud Workflows equivalent"));
+                }
+                self.signal_callbacks(node, name, &config.signals, timeout)
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config().unwrap_or_default();
+                if config.namespace.is_some() || config.task_queue.is_some() {
+                    self.diagnostics.push(Diagnostic::warning(id, "Child workflow namespace and task queue are ignored; the child runs as another Cloud Workflows workflow"));
+                }
+                if config.wait_for_completion {
+                    json!({
+                        "call": "googleapis.workflowexecutions.v1.projects.locations.workflows.executions.run",
+                        "args": { "workflow_id": config.workflow, "argument": "${input}" },
+                    })
+                } else {
+                    let parent = format!(
+                        "${{\"projects/\" + sys.get_env(\"GOOGLE_CLOUD_PROJECT_ID\") + \"/locations/\" + sys.get_env(\"GOOGLE_CLOUD_LOCATION\") + \"/workflows/\" + {}}}",
+                        quote(&config.workflow)
+                    );
+                    json!({
+                        "call": "googleapis.workflowexecutions.v1.projects.locations.workflows.executions.create",
+                        "args": { "parent": parent, "body": { "argument": "${json.encode_to_string(input)}" } },
+                    })
+                }
+            }
+            NodeType::Notification => {
+                self.diagnostics.push(Diagnostic::warning(id, "Notification nodes are not compiled to an activity and are exported as log steps"));
+                json!({ "call": "sys.log", "args": { "text": format!("Notification: {}", node.label), "severity": "INFO" } })
+            }
+            _ => {
+                self.diagnostics.push(Diagnostic::error(id, format!("{:?} nodes have no Cloud Workflows equivalent", node.node_type)));
+                Value::Null
+            }
+        };
+        let mut step = match step {
+            Value::Null => json!({ "call": "sys.log", "args": { "text": node.label } }),
+            step => step,
+        };
+        let retryable = matches!(step.get("call").and_then(Value::as_str), Some(call) if !call.starts_with("sys."));
+        if let Some(retries) = node.retries.as_ref().filter(|_| retryable) {
+            match retry(retries) {
+                Ok(retry) => step = json!({ "try": step, "retry": retry }),
+                Err(e) => self.diagnostics.push(Diagnostic::error(id, e)),
+            }
+        }
+        step
+    }
+
+    /// `http.get`, `http.post` and friends for an HttpCall node's method, URL, headers and body
+    fn http_call(&mut self, node: &WorkflowNode) -> Value {
+        let config = |key: &str| node.config.get(key).filter(|v| !v.is_null());
+        let method = config("method").and_then(Value::as_str).unwrap_or("GET").to_ascii_uppercase();
+        let mut args = Map::new();
+        match config("url") {
+            Some(url) => {
+                args.insert("url".into(), url.clone());
+            }
+            None => self.diagnostics.push(Diagnostic::error(Some(&node.id), "HttpCall node has no url")),
+        }
+        let call = match method.as_str() {
+            "GET" | "POST" | "PUT" | "PATCH" | "DELETE" => format!("http.{}", method.to_ascii_lowercase()),
+            _ => {
+                args.insert("method".into(), json!(method));
+                "http.request".to_string()
+            }
+        };
+        if let Some(headers) = config("headers") {
+            args.insert("headers".into(), headers.clone());
+        }
+        match config("body") {
+            Some(_) if method == "GET" => self.diagnostics.push(Diagnostic::warning(Some(&node.id), "GET requests carry no body in Cloud Workflows; the body is dropped")),
+            Some(body) => {
+                args.insert("body".into(), body.clone());
+            }
+            None => {}
+        }
+        json!({ "call": call, "args": args })
+    }
+
+    /// POST of the workflow input to `ACTIVITY_BASE_URL` followed by the activity path
+    /// expression, such as `"/ChargeActivity"`
+    fn activity_post(&mut self, path: &str) -> Value {
+        self.uses_activity_service = true;
+        json!({
+            "call": "http.post",
+            "args": {
+                "url": format!("${{sys.get_env(\"{}\") + {}}}", ACTIVITY_BASE_URL, path),
+                "body": "${input}",
+                "auth": { "type": "OIDC" },
+            },
+        })
+    }
+
+    /// Nested steps creating a callback endpoint per signal, logging its URL for the sender,
+    /// then awaiting each callback in turn
+    fn signal_callbacks(&mut self, node: &WorkflowNode, name: &str, signals: &[String], timeout: Option<u64>) -> Value {
+        let timeout = timeout.unwrap_or(MAX_WAIT_SECONDS).clamp(1, MAX_WAIT_SECONDS);
+        let callback = |signal: &str| {
+            let slug: String = signal.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+            format!("{}_{}", name, slug)
+        };
+        let mut steps = Vec::new();
+        for signal in signals {
+            self.diagnostics.push(Diagnostic::warning(
+                Some(&node.id),
+                format!("Signal '{}' is exported as an HTTP callback; the sender must POST to the callback URL the workflow logs", signal),
+            ));
+            let var = callback(signal);
+            steps.push(json!({ format!("create_{}", var): {
+                "call": "events.create_callback_endpoint",
+                "args": { "http_callback_method": "POST" },
+                "result": var,
+            }}));
+            steps.push(json!({ format!("log_{}", var): {
+                "call": "sys.log",
+                "args": { "text": format!("${{\"Awaiting signal {} at \" + {}.url}}", signal, var), "severity": "INFO" },
+            }}));
+        }
+        for signal in signals {
+            let var = callback(signal);
+            steps.push(json!({ format!("await_{}", var): {
+                "call": "events.await_callback",
+                "args": { "callback": format!("${{{}}}", var), "timeout": timeout },
+            }}));
+        }
+        json!({ "steps": steps })
+    }
+
+    fn switch(&mut self, node: &'a WorkflowNode, steps: &mut Steps, join: Option<&str>) -> Value {
+        let mut conditions = Vec::new();
+        let mut default = None;
+        let edges: Vec<_> = graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow).collect();
+        if !edges.iter().any(|e| e.condition.as_deref().is_some_and(|c| !c.trim().is_empty())) {
+            self.diagnostics.push(Diagnostic::error(Some(&node.id), "Decision has no conditional edges"));
+        }
+        for edge in edges {
+            let Some(target) = graph::find_node(self.definition, &edge.target) else { continue };
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => match expression(self.definition, condition) {
+                    Ok(expression) => {
+                        let next = self.jump(Some(target), steps, join);
+                        conditions.push(json!({ "condition": format!("${{{}}}", expression), "next": next }));
+                    }
+                    Err(e) => self.diagnostics.push(Diagnostic::error(
+                        Some(&node.id),
+                        format!("Condition '{}' on edge '{}' cannot be expressed in Cloud Workflows: {}", condition, edge.id, e),
+                    )),
+                },
+                None if default.is_none() => default = Some(self.jump(Some(target), steps, join)),
+                None => self.diagnostics.push(Diagnostic::error(Some(&node.id), "Decision has more than one edge without a condition")),
+            }
+        }
+        let mut step = json!({ "switch": conditions });
+        match default {
+            Some(default) => step["next"] = json!(default),
+            None => {
+                self.diagnostics.push(Diagnostic::warning(
+                    Some(&node.id),
+                    "Decision has no edge without a condition, so executions matching no condition end the workflow",
+                ));
+                step["next"] = json!(END);
+            }
+        }
+        step
+    }
+
+    /// Parallel step for a fork and the node following its join
+    fn parallel(&mut self, node: &'a WorkflowNode) -> (Value, Option<&'a WorkflowNode>) {
+        let config: ParallelGatewayConfig = node.typed_config().unwrap_or_default();
+        if !matches!(config.join, JoinPolicy::All) {
+            self.diagnostics.push(Diagnostic::error(
+                Some(&node.id),
+                "Parallel steps wait for every branch; any and n_of_m joins have no Cloud Workflows equivalent",
+            ));
+        }
+        let targets = self.flow_targets(node);
+        let join = targets.first().and_then(|first| self.find_join(first));
+        if join.is_none() {
+            self.diagnostics.push(Diagnostic::warning(Some(&node.id), "Parallel gateway branches never meet at a join; each branch ends the workflow"));
+        }
+        let join_id = join.map(|j| j.id.as_str());
+
+        let branches: Vec<Value> = targets.iter()
+            .map(|target| {
+                let mut branch_steps = Steps::new();
+                self.jump(Some(target), &mut branch_steps, join_id);
+                let name = format!("{}_branch", self.step_name(target));
+                json!({ name: { "steps": scope(branch_steps) } })
+            })
+            .collect();
+        let after = join.and_then(|j| self.flow_targets(j).first().copied());
+        (json!({ "parallel": { "branches": branches } }), after)
+    }
+
+    /// First join gateway on the path from a branch's first node
+    fn find_join(&self, start: &'a WorkflowNode) -> Option<&'a WorkflowNode> {
+        let mut seen = HashSet::new();
+        let mut node = start;
+        while seen.insert(node.id.as_str()) {
+            if self.is_join(node) {
+                return Some(node);
+            }
+            node = self.flow_targets(node).first().copied()?;
+        }
+        None
+    }
+}
+
+/// Steps of a scope as the list of single-key objects Cloud Workflows expects
+fn scope(steps: Steps) -> Vec<Value> {
+    steps.into_iter().map(|(name, step)| json!({ name: step })).collect()
+}
+
+/// Double-quoted Cloud Workflows string literal
+fn quote(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+/// Retry block for a node retry policy; Cloud Workflows counts retries, not attempts
+fn retry(retries: &RetryPolicy) -> Result<Value, String> {
+    let initial = parse_duration(&retries.initial_interval).map_err(|e| format!("Invalid retry initial_interval: {}", e))?;
+    let max = parse_duration(&retries.max_interval).map_err(|e| format!("Invalid retry max_interval: {}", e))?;
+    Ok(json!({
+        "predicate": "${http.default_retry_predicate}",
+        "max_retries": retries.max_attempts.saturating_sub(1),
+        "backoff": {
+            "initial_delay": initial.as_secs().max(1),
+            "max_delay": max.as_secs().max(1),
+            "multiplier": retries.backoff_coefficient,
+        },
+    }))
+}
+
+/// Cloud Workflows expression for an edge condition: workflow variables become fields of the
+/// `input` parameter and `&&`, `||` and `!` become `and`, `or` and `not`
+fn expression(definition: &WorkflowDefinition, condition: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = condition.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(ch) if ch == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(ch) => text.push(ch),
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                out.push_str(&quote(&text));
+            }
+            '&' | '|' => {
+                if chars.next() != Some(c) {
+                    return Err(format!("unsupported operator '{}'", c));
+                }
+                out.push_str(if c == '&' { " and " } else { " or " });
+            }
+            '!' if chars.peek() != Some(&'=') => out.push_str("not "),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&ch) = chars.peek() {
+                    if !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.') {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "true" | "false" | "null" => out.push_str(&word),
+                    _ => {
+                        let name = word.strip_prefix("input.").unwrap_or(&word);
+                        let (head, rest) = name.split_once('.').map_or((name, ""), |(h, r)| (h, r));
+                        let variable = definition.variables.iter()
+                            .find(|v| v.name == head || to_pascal_case(&v.name) == head)
+                            .ok_or_else(|| format!("'{}' is not a workflow variable", head))?;
+                        out.push_str(&format!("input.{}", variable.name));
+                        if !rest.is_empty() {
+                            out.push('.');
+                            out.push_str(rest);
+                        }
+                    }
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Convert a definition into a Cloud Workflows YAML definition with diagnostics
+pub fn export(definition: &WorkflowDefinition) -> GcpWorkflowsExport {
+    let mut builder = Builder { definition, diagnostics: vec![], uses_activity_service: false };
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Cancel) {
+        builder.diagnostics.push(Diagnostic::error(
+            Some(&edge.source),
+            format!("Cancel edge '{}' has no Cloud Workflows equivalent", edge.id),
+        ));
+    }
+
+    let mut steps = Steps::new();
+    match definition.nodes.iter().find(|n| matches!(n.node_type, NodeType::Start)) {
+        Some(start) => {
+            builder.jump(Some(start), &mut steps, None);
+        }
+        None => builder.diagnostics.push(Diagnostic::error(None, "The workflow needs a Start node")),
+    }
+    if steps.is_empty() {
+        builder.diagnostics.push(Diagnostic::error(None, "The workflow has no node that produces a step"));
+    }
+    let reachable = graph::reachable_nodes(definition);
+    for node in definition.nodes.iter().filter(|n| !reachable.contains(n.id.as_str())) {
+        builder.diagnostics.push(Diagnostic::warning(Some(&node.id), "Node is unreachable from the Start node and is not exported"));
+    }
+    if builder.uses_activity_service {
+        builder.diagnostics.push(Diagnostic::warning(
+            None,
+            format!("Activities are posted to the service at the {} environment variable of the deployed workflow", ACTIVITY_BASE_URL),
+        ));
+    }
+
+    let failed = builder.diagnostics.iter().any(|d| matches!(d.severity, Severity::Error));
+    let yaml = (!failed).then(|| {
+        let workflow = json!({ "main": { "params": ["input"], "steps": scope(steps) } });
+        let mut header = format!("# {} {}\n", to_pascal_case(&definition.name), definition.version);
+        if let Some(description) = definition.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            for line in description.lines() {
+                header.push_str(format!("# {}", line).trim_end());
+                header.push('\n');
+            }
+        }
+        header + &yaml::render(&workflow)
+    });
+
+    GcpWorkflowsExport { yaml, diagnostics: builder.diagnostics }
+}
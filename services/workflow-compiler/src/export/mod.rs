@@ -0,0 +1,30 @@
+//! Exporters rendering workflow definitions into other formats for review and documentation
+pub mod bpmn;
+pub mod dot;
+pub mod gcp_workflows;
+pub mod sequence;
+pub mod step_functions;
+pub mod yaml;
+
+use serde::Serialize;
+
+use crate::compiler::rules::Severity;
+
+/// Problem or lossy mapping found while exporting to another engine
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Node the diagnostic is about; absent for workflow-level diagnostics
+    pub node_id: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(node_id: Option<&str>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, node_id: node_id.map(str::to_string), message: message.into() }
+    }
+
+    pub fn warning(node_id: Option<&str>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, node_id: node_id.map(str::to_string), message: message.into() }
+    }
+}
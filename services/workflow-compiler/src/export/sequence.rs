@@ -0,0 +1,206 @@
+//! Sequence diagrams of the interactions between a workflow, its activities, child
+//! workflows and the external systems its HttpCall, DatabaseQuery and PublishEvent nodes reach
+//!
+//! Node annotations and the labels of edges into a node are drawn as a note after its
+//! interactions; branching nodes list the labels of their outgoing edges.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compiler::codegen::{activity_name, is_activity_node};
+use crate::compiler::{feature_flag, source_map};
+use crate::dsl::graph;
+use crate::error::CompilerError;
+use crate::{
+    to_pascal_case, DatabaseQueryConfig, FeatureFlagConfig, HttpCallConfig, NexusOperationConfig, NodeType, SubWorkflowConfig, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig,
+    WorkflowDefinition, WorkflowNode,
+};
+
+/// Diagram language to render
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceFormat {
+    #[default]
+    Mermaid,
+    Plantuml,
+}
+
+/// Diagram lines in the target language
+struct Diagram {
+    format: SequenceFormat,
+    participants: Vec<(String, String)>,
+    lines: Vec<String>,
+}
+
+/// Keep labels on one line and clear of statement separators
+fn clean(label: &str) -> String {
+    label.replace(['\n', '\r'], " ").replace([';', '#'], ",")
+}
+
+impl Diagram {
+    fn new(format: SequenceFormat) -> Self {
+        Self { format, participants: vec![], lines: vec![] }
+    }
+
+    /// Alias for a participant, declaring it the first time its label is seen
+    fn participant(&mut self, prefix: &str, label: &str) -> String {
+        if let Some((alias, _)) = self.participants.iter().find(|(_, l)| l == label) {
+            return alias.clone();
+        }
+        let alias = format!("{}{}", prefix, self.participants.len());
+        self.participants.push((alias.clone(), label.to_string()));
+        alias
+    }
+
+    fn call(&mut self, from: &str, to: &str, message: &str) {
+        let arrow = match self.format {
+            SequenceFormat::Mermaid => "->>",
+            SequenceFormat::Plantuml => "->",
+        };
+        self.lines.push(format!("{}{}{}: {}", from, arrow, to, clean(message)));
+    }
+
+    fn reply(&mut self, from: &str, to: &str, message: &str) {
+        let arrow = match self.format {
+            SequenceFormat::Mermaid => "-->>",
+            SequenceFormat::Plantuml => "-->",
+        };
+        self.lines.push(format!("{}{}{}: {}", from, arrow, to, clean(message)));
+    }
+
+    fn note(&mut self, over: &str, text: &str) {
+        let keyword = match self.format {
+            SequenceFormat::Mermaid => "Note",
+            SequenceFormat::Plantuml => "note",
+        };
+        self.lines.push(format!("{} over {}: {}", keyword, over, clean(text)));
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        match self.format {
+            SequenceFormat::Mermaid => {
+                out.push_str("sequenceDiagram\n");
+                for (alias, label) in &self.participants {
+                    out.push_str(&format!("    participant {} as {}\n", alias, clean(label)));
+                }
+            }
+            SequenceFormat::Plantuml => {
+                out.push_str("@startuml\n");
+                for (alias, label) in &self.participants {
+                    out.push_str(&format!("    participant \"{}\" as {}\n", clean(label).replace('"', "'"), alias));
+                }
+            }
+        }
+        for line in &self.lines {
+            out.push_str("    ");
+            out.push_str(line);
+            out.push('\n');
+        }
+        if self.format == SequenceFormat::Plantuml {
+            out.push_str("@enduml\n");
+        }
+        out
+    }
+}
+
+/// External system an HttpCall, DatabaseQuery or PublishEvent node talks to
+fn external_system(node: &WorkflowNode) -> Option<(String, String)> {
+    let config_str = |key: &str| node.config.get(key).and_then(Value::as_str);
+    match node.node_type {
+        NodeType::HttpCall => {
+            let config: HttpCallConfig = node.typed_config().ok()?;
+            let rest = config.url.split_once("://").map(|(_, rest)| rest).unwrap_or(&config.url);
+            let (host, path) = rest.split_once('/').map(|(h, p)| (h, format!("/{}", p))).unwrap_or((rest, "/".to_string()));
+            Some((host.to_string(), format!("{} {}", config.method.to_ascii_uppercase(), path)))
+        }
+        NodeType::DatabaseQuery => {
+            let config: DatabaseQueryConfig = node.typed_config().unwrap_or_default();
+            let database = config.database.or(config.connection).unwrap_or_else(|| "Database".to_string());
+            let query = config.operation.or(config.table).unwrap_or_else(|| "query".to_string());
+            Some((database, query))
+        }
+        NodeType::PublishEvent => {
+            let topic = config_str("topic").unwrap_or("event");
+            Some(("Event bus".to_string(), format!("publish {}", topic)))
+        }
+        _ => None,
+    }
+}
+
+/// Render a sequence diagram of the workflow in definition order
+pub fn generate_sequence_diagram(definition: &WorkflowDefinition, format: SequenceFormat) -> Result<String, CompilerError> {
+    let mut diagram = Diagram::new(format);
+    let workflow_name = to_pascal_case(&definition.name);
+    let client = diagram.participant("C", "Client");
+    let workflow = diagram.participant("W", &workflow_name);
+
+    diagram.call(&client, &workflow, &format!("start {}", workflow_name));
+    for node in &definition.nodes {
+        match node.node_type {
+            _ if is_activity_node(node) => {
+                let activity = diagram.participant("A", &activity_name(node));
+                diagram.call(&workflow, &activity, &node.label);
+                if let Some((system, request)) = external_system(node) {
+                    let system = diagram.participant("X", &system);
+                    diagram.call(&activity, &system, &request);
+                    diagram.reply(&system, &activity, "response");
+                }
+                diagram.reply(&activity, &workflow, "result");
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config()?;
+                let child = diagram.participant("S", &config.workflow);
+                diagram.call(&workflow, &child, &format!("start child {}", node.label));
+                if config.wait_for_completion {
+                    diagram.reply(&child, &workflow, "result");
+                }
+            }
+            NodeType::NexusOperation => {
+                let config: NexusOperationConfig = node.typed_config()?;
+                let endpoint = diagram.participant("N", &format!("{} (Nexus)", config.endpoint));
+                diagram.call(&workflow, &endpoint, &format!("{}.{}", config.service, config.operation));
+                diagram.reply(&endpoint, &workflow, "result");
+            }
+            NodeType::WaitSignal => {
+                let config: WaitSignalConfig = node.typed_config()?;
+                diagram.call(&client, &workflow, &format!("signal {}", config.signal));
+            }
+            NodeType::WaitSignals => {
+                let config: WaitSignalsConfig = node.typed_config()?;
+                for signal in &config.signals {
+                    diagram.call(&client, &workflow, &format!("signal {}", signal));
+                }
+            }
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config()?;
+                diagram.note(&workflow, &format!("wait {}", config.duration));
+            }
+            NodeType::FeatureFlag => {
+                let config: FeatureFlagConfig = node.typed_config()?;
+                let evaluator = diagram.participant("A", feature_flag::ACTIVITY);
+                diagram.call(&workflow, &evaluator, &format!("evaluate {}", config.flag));
+                diagram.reply(&evaluator, &workflow, "on / off");
+            }
+            NodeType::Decision | NodeType::DecisionTable | NodeType::ParallelGateway | NodeType::DynamicActivity | NodeType::WeightedSplit => {
+                // Label the branches with their edge labels where they have them
+                let branches: Vec<&str> = graph::outgoing_edges(definition, &node.id)
+                    .filter_map(|e| e.label.as_deref().map(str::trim).filter(|l| !l.is_empty()))
+                    .collect();
+                if branches.is_empty() {
+                    diagram.note(&workflow, &node.label);
+                } else {
+                    diagram.note(&workflow, &format!("{} ({})", node.label, branches.join(" / ")));
+                }
+            }
+            _ => continue,
+        }
+        let notes = source_map::notes(definition, node);
+        if !notes.is_empty() {
+            diagram.note(&workflow, &notes.join(", "));
+        }
+    }
+    diagram.reply(&workflow, &client, "result");
+
+    Ok(diagram.render())
+}
@@ -0,0 +1,498 @@
+//! AWS Step Functions export: Amazon States Language state machines for workflows that can
+//! run without a Temporal cluster
+//!
+//! Activities become Lambda Task states, decisions Choice states, parallel gateways Parallel
+//! states and timers Wait states. Constructs without a clean equivalent are reported as
+//! diagnostics; the state machine is only returned when none of them is an error. Nothing in
+//! the DSL iterates over a collection, so no node produces a Map state. States carry their
+//! node's annotations and incoming edge labels as a `Comment`.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::compiler::codegen::{activity_name, is_activity_node};
+use crate::compiler::duration::parse_duration;
+use crate::compiler::rules::Severity;
+use crate::compiler::source_map;
+use crate::dsl::graph;
+use crate::export::Diagnostic;
+use crate::{
+    to_pascal_case, DynamicActivityConfig, EdgeKind, JoinPolicy, NodeType, ParallelGatewayConfig, PublishEventConfig, RetryPolicy,
+    SignalWaitMode, SubWorkflowConfig, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig, WorkflowDefinition, WorkflowNode,
+};
+
+/// Longest state name Step Functions accepts
+const MAX_STATE_NAME: usize = 80;
+
+/// Lambda invoked for callback tasks standing in for signal waits
+const SIGNAL_CALLBACK_FUNCTION: &str = "RegisterSignalCallback";
+
+/// Exported state machine, present only when no diagnostic is an error
+#[derive(Debug, Clone, Serialize)]
+pub struct StepFunctionsExport {
+    pub state_machine: Option<Value>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+struct Builder<'a> {
+    definition: &'a WorkflowDefinition,
+    workflow_name: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Builder<'a> {
+    /// State name for a node: its label, or label and ID when the label is not unique
+    fn state_name(&self, node: &WorkflowNode) -> String {
+        let duplicate = self.definition.nodes.iter().filter(|n| n.label == node.label).count() > 1;
+        let name = if duplicate || node.label.trim().is_empty() {
+            format!("{} ({})", node.label.trim(), node.id)
+        } else {
+            node.label.trim().to_string()
+        };
+        name.chars().take(MAX_STATE_NAME).collect()
+    }
+
+    fn flow_targets(&self, node: &WorkflowNode) -> Vec<&'a WorkflowNode> {
+        graph::outgoing_edges(self.definition, &node.id)
+            .filter(|e| e.kind == EdgeKind::Flow)
+            .filter_map(|e| graph::find_node(self.definition, &e.target))
+            .collect()
+    }
+
+    /// Whether a node is a parallel gateway closing a fork
+    fn is_join(&self, node: &WorkflowNode) -> bool {
+        matches!(node.node_type, NodeType::ParallelGateway) && self.flow_targets(node).len() <= 1
+    }
+
+    /// `Next` or `End` for a state continuing to `next`; branches of a Parallel state end at
+    /// their join
+    fn transition(&mut self, state: &mut Map<String, Value>, next: Option<&'a WorkflowNode>, states: &mut Map<String, Value>, join: Option<&str>) {
+        match next {
+            Some(next) if Some(next.id.as_str()) != join => {
+                let name = self.emit(next, states, join);
+                state.insert("Next".into(), Value::String(name));
+            }
+            _ => {
+                state.insert("End".into(), Value::Bool(true));
+            }
+        }
+    }
+
+    /// Single successor of a node with sequential semantics
+    fn successor(&mut self, node: &WorkflowNode) -> Option<&'a WorkflowNode> {
+        let targets = self.flow_targets(node);
+        if targets.len() > 1 {
+            self.diagnostics.push(Diagnostic::error(
+                Some(&node.id),
+                format!("{:?} node has {} outgoing edges; only decisions and parallel gateways may branch", node.node_type, targets.len()),
+            ));
+        }
+        targets.first().copied()
+    }
+
+    /// Emit the state for a node and everything it leads to, returning its name
+    fn emit(&mut self, node: &'a WorkflowNode, states: &mut Map<String, Value>, join: Option<&str>) -> String {
+        let name = self.state_name(node);
+        if states.contains_key(&name) {
+            return name;
+        }
+        // Reserve the name first so loops back to this node terminate
+        states.insert(name.clone(), Value::Null);
+
+        let mut state = match node.node_type {
+            NodeType::Decision => {
+                let state = self.choice(node, states, join);
+                self.insert(node, &name, state, states);
+                return name;
+            }
+            NodeType::ParallelGateway if !self.is_join(node) => {
+                let (mut state, after) = self.parallel(node);
+                self.transition(&mut state, after, states, join);
+                self.insert(node, &name, Value::Object(state), states);
+                return name;
+            }
+            NodeType::End => {
+                self.insert(node, &name, json!({ "Type": "Succeed" }), states);
+                return name;
+            }
+            _ => self.task(node),
+        };
+        let next = self.successor(node);
+        self.transition(&mut state, next, states, join);
+        self.insert(node, &name, Value::Object(state), states);
+        name
+    }
+
+    /// Store a node's state, commented with its annotations and incoming edge labels
+    fn insert(&self, node: &WorkflowNode, name: &str, mut state: Value, states: &mut Map<String, Value>) {
+        let notes = source_map::notes(self.definition, node);
+        if !notes.is_empty() {
+            if let Some(fields) = state.as_object_mut() {
+                fields.insert("Comment".into(), Value::String(notes.join("; ")));
+            }
+        }
+        states.insert(name.to_string(), state);
+    }
+
+    /// State for a node without branching semantics
+    fn task(&mut self, node: &WorkflowNode) -> Map<String, Value> {
+        let id = Some(node.id.as_str());
+        if node.session.is_some() {
+            self.diagnostics.push(Diagnostic::warning(id, "Worker session groups have no Step Functions equivalent and are ignored"));
+        }
+        let state = match node.node_type {
+            NodeType::PublishEvent => {
+                let config: PublishEventConfig = node.typed_config().unwrap_or_default();
+                json!({
+                    "Type": "Task",
+                    "Resource": "arn:aws:states:::events:putEvents",
+                    "Parameters": {
+                        "Entries": [{
+                            "Source": format!("omniroute.{}", self.workflow_name),
+                            "DetailType": config.topic,
+                            "Detail.$": "$",
+                        }],
+                    },
+                    "ResultPath": null,
+                })
+            }
+            _ if is_activity_node(node) => lambda_task(json!(activity_name(node))),
+            NodeType::DynamicActivity => {
+                let config: DynamicActivityConfig = node.typed_config().unwrap_or_default();
+                self.diagnostics.push(Diagnostic::warning(id, "The dynamic activity allowlist is not enforced by Step Functions; the selector names the Lambda function directly"));
+                let mut task = lambda_task(Value::Null);
+                task["Parameters"] = json!({ "FunctionName.$": format!("$.{}", config.selector), "Payload.$": "$" });
+                task
+            }
+            NodeType::WaitTimer => {
+                let config: WaitTimerConfig = node.typed_config().unwrap_or_default();
+                match parse_duration(&config.duration) {
+                    Ok(duration) => json!({ "Type": "Wait", "Seconds": duration.as_secs().max(1) }),
+                    Err(e) => {
+                        self.diagnostics.push(Diagnostic::error(id, format!("Invalid timer duration '{}': {}", config.duration, e)));
+                        json!({ "Type": "Pass" })
+                    }
+                }
+            }
+            NodeType::WaitSignal => {
+                let config: WaitSignalConfig = node.typed_config().unwrap_or_default();
+                self.signal_callback(node, &config.signal, None)
+            }
+            NodeType::WaitSignals => {
+                let config: WaitSignalsConfig = node.typed_config().unwrap_or_default();
+                if config.correlation_key.is_some() {
+                    self.diagnostics.push(Diagnostic::warning(id, "Signal correlation keys are passed to the callback function but not checked by Step Functions"));
+                }
+                let timeout = config.timeout.as_deref().and_then(|t| parse_duration(t).ok()).map(|d| d.as_secs().max(1));
+                match (config.mode, config.signals.as_slice()) {
+                    (_, [signal]) => self.signal_callback(node, signal, timeout),
+                    (SignalWaitMode::All, signals) => {
+                        let branches: Vec<Value> = signals.iter()
+                            .map(|signal| {
+                                let state = self.signal_callback(node, signal, timeout);
+                                let mut state = state.as_object().cloned().unwrap_or_default();
+                                state.insert("End".into(), Value::Bool(true));
+                                json!({ "StartAt": signal, "States": { signal: state } })
+                            })
+                            .collect();
+                        json!({ "Type": "Parallel", "Branches": branches, "ResultPath": null })
+                    }
+                    (SignalWaitMode::Any, _) => {
+                        self.diagnostics.push(Diagnostic::error(id, "Waiting for any one of several signals has no Step Functions equivalent"));
+                        json!({ "Type": "Pass" })
+                    }
+                }
+            }
+            NodeType::SubWorkflow => {
+                let config: SubWorkflowConfig = node.typed_config().unwrap_or_default();
+                if config.namespace.is_some() || config.task_queue.is_some() {
+                    self.diagnostics.push(Diagnostic::warning(id, "Child workflow namespace and task queue are ignored; the child runs as a nested state machine"));
+                }
+                let resource = if config.wait_for_completion {
+                    "arn:aws:states:::states:startExecution.sync:2"
+                } else {
+                    "arn:aws:states:::states:startExecution"
+                };
+                json!({
+                    "Type": "Task",
+                    "Resource": resource,
+                    "Parameters": {
+                        "StateMachineArn": format!("arn:aws:states:${{AWS::Region}}:${{AWS::AccountId}}:stateMachine:{}", config.workflow),
+                        "Input.$": "$",
+                    },
+                    "ResultPath": null,
+                })
+            }
+            NodeType::Transform | NodeType::Start => json!({ "Type": "Pass" }),
+            // A join reached outside its fork
+            NodeType::ParallelGateway => json!({ "Type": "Pass" }),
+            NodeType::Notification => {
+                self.diagnostics.push(Diagnostic::warning(id, "Notification nodes are not compiled to an activity and are exported as Pass states"));
+                json!({ "Type": "Pass" })
+            }
+            _ => {
+                self.diagnostics.push(Diagnostic::error(id, format!("{:?} nodes have no Step Functions equivalent", node.node_type)));
+                json!({ "Type": "Pass" })
+            }
+        };
+        let mut state = state.as_object().cloned().unwrap_or_default();
+        if let (Some(retries), "Task") = (&node.retries, state["Type"].as_str().unwrap_or_default()) {
+            match retrier(retries) {
+                Ok(retrier) => {
+                    state.insert("Retry".into(), json!([retrier]));
+                }
+                Err(e) => self.diagnostics.push(Diagnostic::error(id, e)),
+            }
+        }
+        state
+    }
+
+    /// Callback task standing in for a signal: execution pauses until the task token is
+    /// completed with `SendTaskSuccess`
+    fn signal_callback(&mut self, node: &WorkflowNode, signal: &str, timeout: Option<u64>) -> Value {
+        self.diagnostics.push(Diagnostic::warning(
+            Some(&node.id),
+            format!("Signal '{}' is exported as a callback task; the sender must call SendTaskSuccess with the registered task token", signal),
+        ));
+        let mut task = json!({
+            "Type": "Task",
+            "Resource": "arn:aws:states:::lambda:invoke.waitForTaskToken",
+            "Parameters": {
+                "FunctionName": SIGNAL_CALLBACK_FUNCTION,
+                "Payload": {
+                    "signal": signal,
+                    "execution.$": "$$.Execution.Id",
+                    "task_token.$": "$$.Task.Token",
+                    "input.$": "$",
+                },
+            },
+            "ResultPath": null,
+        });
+        if let Some(seconds) = timeout {
+            task["TimeoutSeconds"] = json!(seconds);
+        }
+        task
+    }
+
+    fn choice(&mut self, node: &'a WorkflowNode, states: &mut Map<String, Value>, join: Option<&str>) -> Value {
+        let mut choices = Vec::new();
+        let mut default = None;
+        let edges: Vec<_> = graph::outgoing_edges(self.definition, &node.id).filter(|e| e.kind == EdgeKind::Flow).collect();
+        if !edges.iter().any(|e| e.condition.as_deref().is_some_and(|c| !c.trim().is_empty())) {
+            self.diagnostics.push(Diagnostic::error(Some(&node.id), "Decision has no conditional edges"));
+        }
+        for edge in edges {
+            let Some(target) = graph::find_node(self.definition, &edge.target) else { continue };
+            match edge.condition.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(condition) => match choice_rule(self.definition, condition) {
+                    Ok(mut rule) => {
+                        let next = self.branch_target(target, states, join);
+                        rule["Next"] = json!(next);
+                        choices.push(rule);
+                    }
+                    Err(e) => self.diagnostics.push(Diagnostic::error(
+                        Some(&node.id),
+                        format!("Condition '{}' on edge '{}' cannot be expressed as a Choice rule: {}", condition, edge.id, e),
+                    )),
+                },
+                None if default.is_none() => default = Some(self.branch_target(target, states, join)),
+                None => self.diagnostics.push(Diagnostic::error(Some(&node.id), "Decision has more than one edge without a condition")),
+            }
+        }
+        let mut state = json!({ "Type": "Choice", "Choices": choices });
+        match default {
+            Some(default) => state["Default"] = json!(default),
+            None => self.diagnostics.push(Diagnostic::warning(
+                Some(&node.id),
+                "Decision has no edge without a condition, so executions matching no condition fail with States.NoChoiceMatched",
+            )),
+        }
+        state
+    }
+
+    /// State a Choice rule leads to; a branch reaching the join of an enclosing fork ends there
+    fn branch_target(&mut self, target: &'a WorkflowNode, states: &mut Map<String, Value>, join: Option<&str>) -> String {
+        if Some(target.id.as_str()) == join {
+            let name = format!("{} done", self.state_name(target)).chars().take(MAX_STATE_NAME).collect::<String>();
+            states.insert(name.clone(), json!({ "Type": "Pass", "End": true }));
+            return name;
+        }
+        self.emit(target, states, join)
+    }
+
+    /// Parallel state for a fork and the node following its join
+    fn parallel(&mut self, node: &'a WorkflowNode) -> (Map<String, Value>, Option<&'a WorkflowNode>) {
+        let config: ParallelGatewayConfig = node.typed_config().unwrap_or_default();
+        if !matches!(config.join, JoinPolicy::All) {
+            self.diagnostics.push(Diagnostic::error(
+                Some(&node.id),
+                "Parallel states wait for every branch; any and n_of_m joins have no Step Functions equivalent",
+            ));
+        }
+        let targets = self.flow_targets(node);
+        let join = targets.first().and_then(|first| self.find_join(first));
+        if join.is_none() {
+            self.diagnostics.push(Diagnostic::warning(Some(&node.id), "Parallel gateway branches never meet at a join; each branch ends the workflow"));
+        }
+        let join_id = join.map(|j| j.id.as_str());
+
+        let branches: Vec<Value> = targets.iter()
+            .map(|target| {
+                let mut branch_states = Map::new();
+                let start = self.emit(target, &mut branch_states, join_id);
+                json!({ "StartAt": start, "States": branch_states })
+            })
+            .collect();
+        let state = json!({ "Type": "Parallel", "Branches": branches, "ResultPath": null });
+        let after = join.and_then(|j| self.flow_targets(j).first().copied());
+        (state.as_object().cloned().unwrap_or_default(), after)
+    }
+
+    /// First join gateway on the path from a branch's first node
+    fn find_join(&self, start: &'a WorkflowNode) -> Option<&'a WorkflowNode> {
+        let mut seen = HashSet::new();
+        let mut node = start;
+        while seen.insert(node.id.as_str()) {
+            if self.is_join(node) {
+                return Some(node);
+            }
+            node = self.flow_targets(node).first().copied()?;
+        }
+        None
+    }
+}
+
+fn lambda_task(function: Value) -> Value {
+    json!({
+        "Type": "Task",
+        "Resource": "arn:aws:states:::lambda:invoke",
+        "Parameters": { "FunctionName": function, "Payload.$": "$" },
+        "ResultPath": null,
+    })
+}
+
+/// Task retrier for a node retry policy; Step Functions counts retries, not attempts
+fn retrier(retries: &RetryPolicy) -> Result<Value, String> {
+    let initial = parse_duration(&retries.initial_interval).map_err(|e| format!("Invalid retry initial_interval: {}", e))?;
+    let max = parse_duration(&retries.max_interval).map_err(|e| format!("Invalid retry max_interval: {}", e))?;
+    Ok(json!({
+        "ErrorEquals": ["States.ALL"],
+        "IntervalSeconds": initial.as_secs().max(1),
+        "MaxDelaySeconds": max.as_secs().max(1),
+        "BackoffRate": retries.backoff_coefficient,
+        "MaxAttempts": retries.max_attempts.saturating_sub(1),
+    }))
+}
+
+/// Choice rule for an edge condition: comparisons of a variable against a literal or another
+/// variable, bare or negated boolean variables, joined by `&&` and `||`
+fn choice_rule(definition: &WorkflowDefinition, condition: &str) -> Result<Value, String> {
+    let any: Vec<&str> = condition.split("||").collect();
+    if any.len() > 1 {
+        let rules = any.iter().map(|c| choice_rule(definition, c)).collect::<Result<Vec<_>, _>>()?;
+        return Ok(json!({ "Or": rules }));
+    }
+    let all: Vec<&str> = condition.split("&&").collect();
+    if all.len() > 1 {
+        let rules = all.iter().map(|c| comparison(definition, c.trim())).collect::<Result<Vec<_>, _>>()?;
+        return Ok(json!({ "And": rules }));
+    }
+    comparison(definition, condition.trim())
+}
+
+/// JSONPath of a condition operand naming a workflow variable
+fn variable_path(definition: &WorkflowDefinition, operand: &str) -> Option<(String, String)> {
+    let name = operand.strip_prefix("input.").unwrap_or(operand);
+    let variable = definition.variables.iter().find(|v| v.name == name || to_pascal_case(&v.name) == name)?;
+    Some((format!("$.{}", variable.name), variable.var_type.clone()))
+}
+
+fn comparison(definition: &WorkflowDefinition, expr: &str) -> Result<Value, String> {
+    const OPERATORS: &[(&str, &str)] = &[
+        ("==", "Equals"), ("!=", "Equals"), ("<=", "LessThanEquals"), (">=", "GreaterThanEquals"), ("<", "LessThan"), (">", "GreaterThan"),
+    ];
+    let Some((op, suffix, lhs, rhs)) = OPERATORS.iter()
+        .find_map(|(op, suffix)| expr.split_once(op).map(|(l, r)| (*op, *suffix, l.trim(), r.trim())))
+    else {
+        let (negated, operand) = match expr.strip_prefix('!') {
+            Some(rest) => (true, rest.trim()),
+            None => (false, expr),
+        };
+        let (path, var_type) = variable_path(definition, operand).ok_or_else(|| format!("'{}' is not a workflow variable", operand))?;
+        if !matches!(var_type.as_str(), "bool" | "boolean") {
+            return Err(format!("'{}' is not a boolean variable", operand));
+        }
+        return Ok(json!({ "Variable": path, "BooleanEquals": !negated }));
+    };
+
+    let (path, var_type) = variable_path(definition, lhs).ok_or_else(|| format!("'{}' is not a workflow variable", lhs))?;
+    let mut rule = Map::new();
+    rule.insert("Variable".into(), Value::String(path));
+    if let Some((other, _)) = variable_path(definition, rhs) {
+        let kind = match var_type.as_str() {
+            "string" => "String",
+            "int" | "integer" | "float" | "number" => "Numeric",
+            "bool" | "boolean" if suffix == "Equals" => "Boolean",
+            _ => return Err(format!("'{}' has type {} which cannot be compared", lhs, var_type)),
+        };
+        rule.insert(format!("{}{}Path", kind, suffix), Value::String(other));
+    } else if let Ok(number) = rhs.parse::<i64>() {
+        rule.insert(format!("Numeric{}", suffix), json!(number));
+    } else if let Ok(number) = rhs.parse::<f64>() {
+        rule.insert(format!("Numeric{}", suffix), json!(number));
+    } else if let Some(text) = rhs.strip_prefix('"').and_then(|r| r.strip_suffix('"'))
+        .or_else(|| rhs.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')))
+    {
+        rule.insert(format!("String{}", suffix), Value::String(text.to_string()));
+    } else if let (Ok(flag), "Equals") = (rhs.parse::<bool>(), suffix) {
+        rule.insert("BooleanEquals".into(), Value::Bool(flag));
+    } else {
+        return Err(format!("'{}' is not a literal or workflow variable", rhs));
+    }
+
+    let rule = Value::Object(rule);
+    Ok(if op == "!=" { json!({ "Not": rule }) } else { rule })
+}
+
+/// Convert a definition into an Amazon States Language state machine with diagnostics
+pub fn export(definition: &WorkflowDefinition) -> StepFunctionsExport {
+    let mut builder = Builder { definition, workflow_name: to_pascal_case(&definition.name), diagnostics: vec![] };
+    for edge in definition.edges.iter().filter(|e| e.kind == EdgeKind::Cancel) {
+        builder.diagnostics.push(Diagnostic::error(
+            Some(&edge.source),
+            format!("Cancel edge '{}' has no Step Functions equivalent", edge.id),
+        ));
+    }
+
+    let mut states = Map::new();
+    let start = definition.nodes.iter().find(|n| matches!(n.node_type, NodeType::Start));
+    let first = start.and_then(|s| builder.successor(s));
+    let start_at = match first {
+        Some(first) => Some(builder.emit(first, &mut states, None)),
+        None => {
+            builder.diagnostics.push(Diagnostic::error(None, "The workflow needs a Start node leading to another node"));
+            None
+        }
+    };
+    let reachable = graph::reachable_nodes(definition);
+    for node in definition.nodes.iter().filter(|n| !reachable.contains(n.id.as_str())) {
+        builder.diagnostics.push(Diagnostic::warning(Some(&node.id), "Node is unreachable from the Start node and is not exported"));
+    }
+
+    let failed = builder.diagnostics.iter().any(|d| matches!(d.severity, Severity::Error));
+    let state_machine = start_at.filter(|_| !failed).map(|start_at| {
+        let mut machine = json!({
+            "Comment": definition.description.clone().filter(|d| !d.trim().is_empty()).unwrap_or_else(|| format!("{} {}", builder.workflow_name, definition.version)),
+            "StartAt": start_at,
+            "States": states,
+        });
+        if let Some(timeout) = definition.timeouts.as_ref().and_then(|t| t.execution.as_deref()).and_then(|t| parse_duration(t).ok()) {
+            machine["TimeoutSeconds"] = json!(timeout.as_secs().max(1));
+        }
+        machine
+    });
+
+    StepFunctionsExport { state_machine, diagnostics: builder.diagnostics }
+}
@@ -0,0 +1,90 @@
+//! Block-style YAML rendering of JSON values for exporters whose target engine reads YAML
+//!
+//! Strings that could be mistaken for another scalar, or that contain YAML indicators, are
+//! written as double-quoted JSON strings, which YAML reads unchanged.
+
+use serde_json::Value;
+
+/// Render a value as a YAML document
+pub fn render(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_block(&mut out, value, 0),
+        Value::Array(items) if !items.is_empty() => write_block(&mut out, value, 0),
+        _ => {
+            out.push_str(&scalar(value));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Write a non-empty mapping or sequence, one entry per line at `indent`
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                out.push_str(&format!("{}{}:", pad, string(key)));
+                write_nested(out, value, indent + 2);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                out.push_str(&format!("{}-", pad));
+                match item {
+                    // The first key shares the dash's line; the rest align beneath it
+                    Value::Object(map) if !map.is_empty() => {
+                        let mut entry = String::new();
+                        write_block(&mut entry, item, indent + 2);
+                        out.push(' ');
+                        out.push_str(entry.trim_start());
+                    }
+                    _ => write_nested(out, item, indent + 2),
+                }
+            }
+        }
+        _ => unreachable!("write_block is only called with mappings and sequences"),
+    }
+}
+
+/// Write the value after a key or dash: inline when scalar or empty, else on following lines
+fn write_nested(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            write_block(out, value, indent);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_block(out, value, indent);
+        }
+        _ => {
+            out.push(' ');
+            out.push_str(&scalar(value));
+            out.push('\n');
+        }
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => string(s),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// A string plain when it can only be read back as that string, quoted otherwise
+fn string(s: &str) -> String {
+    const RESERVED: &[&str] = &["true", "false", "null", "yes", "no", "on", "off", "y", "n", "~"];
+    let plain = s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '/' | '-'))
+        && !RESERVED.contains(&s.to_ascii_lowercase().as_str());
+    if plain {
+        s.to_string()
+    } else {
+        Value::String(s.to_string()).to_string()
+    }
+}
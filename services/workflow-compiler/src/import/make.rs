@@ -0,0 +1,145 @@
+//! Make (formerly Integromat) scenario blueprint importer
+//!
+//! Blueprints list modules in `flow` order. Webhook gateways and `watch*` modules
+//! become triggers; HTTP modules become HttpCall nodes and JSON, text and variable
+//! utilities become Transform nodes.
+
+use serde_json::{json, Map, Value};
+
+use super::{http_call_config, ImportedWorkflow, LinearBuilder};
+use crate::error::CompilerError;
+use crate::{NodeType, Trigger, TriggerType};
+
+/// Module prefixes that only reshape data
+const TRANSFORM_PREFIXES: &[&str] = &["json:", "xml:", "csv:", "regexp:", "util:", "markdown:", "html:"];
+
+/// Module as stored in a blueprint `flow`
+struct Module<'a> {
+    id: String,
+    name: &'a str,
+    label: Option<&'a str>,
+    parameters: &'a Value,
+    mapper: &'a Value,
+    raw: &'a Value,
+}
+
+impl<'a> Module<'a> {
+    fn parse(raw: &'a Value) -> Self {
+        let id = match raw.get("id") {
+            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+        Self {
+            id,
+            name: raw.get("module").and_then(Value::as_str).unwrap_or_default(),
+            label: raw.pointer("/metadata/designer/name").and_then(Value::as_str).filter(|n| !n.is_empty()),
+            parameters: raw.get("parameters").unwrap_or(&Value::Null),
+            mapper: raw.get("mapper").unwrap_or(&Value::Null),
+            raw,
+        }
+    }
+
+    fn label(&self) -> String {
+        self.label.unwrap_or(self.name).to_string()
+    }
+
+    fn mapped(&self, key: &str) -> &'a Value {
+        self.mapper.get(key).unwrap_or(&Value::Null)
+    }
+
+    /// Action part of the module name, e.g. `watchRows` in `google-sheets:watchRows`
+    fn action(&self) -> &'a str {
+        self.name.split_once(':').map(|(_, action)| action).unwrap_or(self.name)
+    }
+}
+
+/// Make lists headers as `[{name, value}]`; HttpCall takes an object
+fn header_object(headers: &Value) -> Value {
+    match headers.as_array() {
+        Some(list) if !list.is_empty() => Value::Object(
+            list.iter()
+                .filter_map(|h| Some((h.get("name")?.as_str()?.to_string(), h.get("value").cloned().unwrap_or(Value::Null))))
+                .collect::<Map<_, _>>(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+/// Map the first module onto a trigger; returns false when it is an ordinary step
+/// of a scheduled or manually run scenario
+fn map_trigger(builder: &mut LinearBuilder, module: &Module) -> bool {
+    let trigger = if module.name.starts_with("gateway:") {
+        Trigger {
+            trigger_type: TriggerType::Webhook,
+            config: json!({ "source": "make", "module": module.name, "hook": module.parameters.get("hook") }),
+        }
+    } else if module.action().starts_with("watch") {
+        Trigger {
+            trigger_type: TriggerType::Event,
+            config: json!({ "source": "make", "module": module.name, "parameters": module.parameters }),
+        }
+    } else {
+        return false;
+    };
+    builder.trigger(trigger);
+    true
+}
+
+/// Map one module onto a node, or report it along with any nested routes
+fn map_module(builder: &mut LinearBuilder, module: &Module) {
+    if module.raw.get("filter").is_some_and(|f| !f.is_null()) {
+        builder.unmapped(&format!("{}.filter", module.id), module.name, "filters are not imported; add a Decision node");
+    }
+
+    if module.name.starts_with("http:") {
+        let method = module.mapped("method").as_str().unwrap_or("get");
+        let config = http_call_config(method, module.mapped("url"), &header_object(module.mapped("headers")), module.mapped("data"));
+        builder.node(&module.id, NodeType::HttpCall, &module.label(), config);
+    } else if TRANSFORM_PREFIXES.iter().any(|p| module.name.starts_with(p)) {
+        let config = json!({ "source": "make", "module": module.name, "parameters": module.parameters, "mapper": module.mapper });
+        builder.node(&module.id, NodeType::Transform, &module.label(), config);
+    } else if module.name == "builtin:BasicRouter" {
+        builder.unmapped(&module.id, module.name, "routers are not imported; add a Decision or ParallelGateway node");
+        report_routes(builder, module);
+    } else if module.name.starts_with("builtin:") {
+        builder.unmapped(&module.id, module.name, "iterators and aggregators have no native equivalent");
+    } else {
+        builder.unmapped(&module.id, module.name, "no native equivalent for this app; replace it with an Activity node");
+    }
+}
+
+/// Report every module inside a router's routes, descending into nested routers
+fn report_routes(builder: &mut LinearBuilder, router: &Module) {
+    for route in router.raw.get("routes").and_then(Value::as_array).into_iter().flatten() {
+        for nested in route.get("flow").and_then(Value::as_array).into_iter().flatten() {
+            let module = Module::parse(nested);
+            builder.unmapped(&module.id, module.name, "inside a router route");
+            report_routes(builder, &module);
+        }
+    }
+}
+
+/// Import a scenario blueprint, bare or wrapped in a `blueprint` field
+pub fn import(source: &Value) -> Result<Vec<ImportedWorkflow>, CompilerError> {
+    let blueprint = source.get("blueprint").unwrap_or(source);
+    let flow = blueprint.get("flow").and_then(Value::as_array)
+        .ok_or_else(|| CompilerError::ParseError("Make blueprint must contain a 'flow' array".into()))?;
+    let name = blueprint.get("name").and_then(Value::as_str).filter(|n| !n.is_empty()).unwrap_or("Make Scenario");
+
+    let mut builder = LinearBuilder::new(name, Some("Imported from a Make scenario blueprint".to_string()));
+    let modules: Vec<Module> = flow.iter().map(Module::parse).collect();
+    let mut rest = modules.as_slice();
+    if let Some(first) = modules.first() {
+        if map_trigger(&mut builder, first) {
+            rest = &modules[1..];
+        } else {
+            builder.trigger(Trigger { trigger_type: TriggerType::Manual, config: Value::Null });
+        }
+    }
+    for module in rest {
+        map_module(&mut builder, module);
+    }
+
+    Ok(vec![builder.finish()])
+}
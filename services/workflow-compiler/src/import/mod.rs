@@ -0,0 +1,243 @@
+//! Importers translating third-party automation exports into workflow definitions
+//!
+//! Each importer maps what it can onto native nodes and triggers and reports the
+//! remaining steps as unmapped, so migrations can be finished by hand in the editor.
+//! Zapier and Make exports become a linear chain; n8n exports keep their graph. BPMN
+//! documents are read by `dsl::from_bpmn`, which keeps each process's graph as well.
+//! Serverless Workflow documents, Mermaid flowcharts, Graphviz digraphs, YAML definitions
+//! and the textual DSL are read by `dsl::from_serverless_workflow`, `dsl::from_mermaid`,
+//! `dsl::from_dot`, `dsl::parse_yaml` and `dsl::parse_text`.
+pub mod make;
+pub mod n8n;
+pub mod zapier;
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::error::CompilerError;
+use crate::{NodeType, Position, Trigger, WorkflowDefinition, WorkflowEdge, WorkflowNode};
+
+/// Horizontal spacing between imported nodes in the editor
+pub(crate) const NODE_SPACING: f64 = 200.0;
+
+/// Source step that has no native equivalent
+#[derive(Debug, Clone, Serialize)]
+pub struct UnmappedStep {
+    /// Step or module ID in the source export
+    pub step: String,
+    /// App and action the step used, e.g. `SlackCLIAPI:channel_message`
+    pub app: String,
+    pub reason: String,
+}
+
+/// One imported workflow and the steps left out of it
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedWorkflow {
+    pub workflow: WorkflowDefinition,
+    pub unmapped: Vec<UnmappedStep>,
+}
+
+/// Import an export in the named format
+pub fn import(format: &str, source: &Value) -> Result<Vec<ImportedWorkflow>, CompilerError> {
+    match format {
+        "zapier" => zapier::import(source),
+        "make" => make::import(source),
+        "n8n" => n8n::import(source),
+        // BPMN is XML; a JSON body carries the document as a string
+        "bpmn" => match source.as_str().or_else(|| source.get("xml").and_then(Value::as_str)) {
+            Some(xml) => crate::dsl::from_bpmn(xml),
+            None => Err(CompilerError::ParseError("A BPMN import needs the XML document as a string or under 'xml'".to_string())),
+        },
+        // A definition authored in YAML, converted to the graph JSON the editor loads
+        "yaml" => match source.as_str().or_else(|| source.get("yaml").and_then(Value::as_str)) {
+            Some(yaml) => Ok(vec![ImportedWorkflow { workflow: crate::dsl::parse_yaml(yaml)?, unmapped: vec![] }]),
+            None => Err(CompilerError::ParseError("A YAML import needs the document as a string or under 'yaml'".to_string())),
+        },
+        // JSON bodies carry the document itself, YAML ones the text
+        "serverless-workflow" => match source.as_str() {
+            Some(text) => Ok(vec![crate::dsl::from_serverless_workflow(&crate::dsl::yaml::to_value(text)?)?]),
+            None => Ok(vec![crate::dsl::from_serverless_workflow(source)?]),
+        },
+        "mermaid" => match source.as_str().or_else(|| source.get("mermaid").and_then(Value::as_str)) {
+            Some(diagram) => Ok(vec![crate::dsl::from_mermaid(diagram)?]),
+            None => Err(CompilerError::ParseError("A Mermaid import needs the flowchart as a string or under 'mermaid'".to_string())),
+        },
+        "dot" => match source.as_str().or_else(|| source.get("dot").and_then(Value::as_str)) {
+            Some(graph) => Ok(vec![crate::dsl::from_dot(graph)?]),
+            None => Err(CompilerError::ParseError("A DOT import needs the digraph as a string or under 'dot'".to_string())),
+        },
+        "text" => match source.as_str().or_else(|| source.get("text").and_then(Value::as_str)) {
+            Some(text) => Ok(vec![ImportedWorkflow { workflow: crate::dsl::parse_text(text)?, unmapped: vec![] }]),
+            None => Err(CompilerError::ParseError("A text import needs the workflow text as a string or under 'text'".to_string())),
+        },
+        other => Err(CompilerError::ParseError(format!(
+            "Unknown import format '{}'; expected zapier, make, n8n, bpmn, serverless-workflow, mermaid, dot, yaml or text",
+            other
+        ))),
+    }
+}
+
+/// Builds a linear definition from Start through the mapped steps to End
+pub(crate) struct LinearBuilder {
+    name: String,
+    description: Option<String>,
+    nodes: Vec<WorkflowNode>,
+    edges: Vec<WorkflowEdge>,
+    triggers: Vec<Trigger>,
+    unmapped: Vec<UnmappedStep>,
+}
+
+impl LinearBuilder {
+    pub fn new(name: &str, description: Option<String>) -> Self {
+        let mut builder = Self {
+            name: name.to_string(),
+            description,
+            nodes: vec![],
+            edges: vec![],
+            triggers: vec![],
+            unmapped: vec![],
+        };
+        builder.push("start", NodeType::Start, "Start", Value::Null);
+        builder
+    }
+
+    fn push(&mut self, id: &str, node_type: NodeType, label: &str, config: Value) {
+        if let Some(previous) = self.nodes.last() {
+            self.edges.push(WorkflowEdge {
+                id: format!("{}-{}", previous.id, id),
+                source: previous.id.clone(),
+                target: id.to_string(),
+                condition: None,
+                label: None,
+                kind: Default::default(),
+            });
+        }
+        self.nodes.push(WorkflowNode {
+            id: id.to_string(),
+            node_type,
+            label: label.to_string(),
+            config,
+            position: Position { x: self.nodes.len() as f64 * NODE_SPACING, y: 0.0 },
+            retries: None,
+            session: None,
+            annotations: Default::default(),
+            timeouts: None,
+            profile: None,
+        });
+    }
+
+    /// Append a node after the previously mapped one
+    pub fn node(&mut self, step: &str, node_type: NodeType, label: &str, config: Value) {
+        self.push(&format!("step_{}", step), node_type, label, config);
+    }
+
+    pub fn trigger(&mut self, trigger: Trigger) {
+        self.triggers.push(trigger);
+    }
+
+    pub fn unmapped(&mut self, step: &str, app: &str, reason: impl Into<String>) {
+        self.unmapped.push(UnmappedStep { step: step.to_string(), app: app.to_string(), reason: reason.into() });
+    }
+
+    /// Close the chain with an End node
+    pub fn finish(mut self) -> ImportedWorkflow {
+        self.push("end", NodeType::End, "End", Value::Null);
+        ImportedWorkflow {
+            workflow: WorkflowDefinition {
+                id: Uuid::new_v4(),
+                name: self.name,
+                version: "1.0.0".to_string(),
+                description: self.description,
+                nodes: self.nodes,
+                edges: self.edges,
+                variables: vec![],
+                triggers: self.triggers,
+                timeouts: None,
+            },
+            unmapped: self.unmapped,
+        }
+    }
+}
+
+/// HttpCall config from a method, URL, headers and body
+pub(crate) fn http_call_config(method: &str, url: &Value, headers: &Value, body: &Value) -> Value {
+    let mut config = json!({ "method": method.to_ascii_uppercase(), "url": url });
+    if !headers.is_null() {
+        config["headers"] = headers.clone();
+    }
+    if !body.is_null() {
+        config["body"] = body.clone();
+    }
+    config
+}
+
+/// Place nodes in columns by their distance from Start, in declaration order within a column
+pub(crate) fn layout(nodes: &mut [WorkflowNode], edges: &[WorkflowEdge]) {
+    let mut depth: HashMap<&str, usize> = HashMap::new();
+    let mut queue: VecDeque<&str> = nodes.iter().filter(|n| matches!(n.node_type, NodeType::Start)).map(|n| n.id.as_str()).collect();
+    for id in &queue {
+        depth.insert(id, 0);
+    }
+    while let Some(id) = queue.pop_front() {
+        let next = depth[id] + 1;
+        for edge in edges.iter().filter(|e| e.source == id) {
+            if !depth.contains_key(edge.target.as_str()) {
+                depth.insert(&edge.target, next);
+                queue.push_back(&edge.target);
+            }
+        }
+    }
+    let depths: Vec<usize> = nodes.iter().map(|n| depth.get(n.id.as_str()).copied().unwrap_or(0)).collect();
+    let mut rows: HashMap<usize, usize> = HashMap::new();
+    for (node, column) in nodes.iter_mut().zip(depths) {
+        let row = rows.entry(column).or_default();
+        node.position = Position { x: column as f64 * NODE_SPACING, y: *row as f64 * NODE_SPACING };
+        *row += 1;
+    }
+}
+
+/// Add a Start leading to every node without a predecessor when the graph has no Start, and
+/// an End after every node without a successor when it has no End
+pub(crate) fn add_terminals(nodes: &mut Vec<WorkflowNode>, edges: &mut Vec<WorkflowEdge>) {
+    let free_id = |base: &str, nodes: &[WorkflowNode]| {
+        let mut id = base.to_string();
+        while nodes.iter().any(|n| n.id == id) {
+            id.push('_');
+        }
+        id
+    };
+    let terminal = |id: String, node_type: NodeType, label: &str| WorkflowNode {
+        id,
+        node_type,
+        label: label.to_string(),
+        config: json!({}),
+        position: Position { x: 0.0, y: 0.0 },
+        retries: None,
+        session: None,
+        annotations: Default::default(),
+        timeouts: None,
+        profile: None,
+    };
+    if !nodes.iter().any(|n| matches!(n.node_type, NodeType::Start)) {
+        let id = free_id("start", nodes);
+        let mut entries: Vec<String> = nodes.iter().filter(|n| !edges.iter().any(|e| e.target == n.id)).map(|n| n.id.clone()).collect();
+        if entries.is_empty() {
+            entries.push(nodes[0].id.clone());
+        }
+        for entry in entries {
+            edges.push(WorkflowEdge { id: format!("{}-{}", id, entry), source: id.clone(), target: entry, condition: None, label: None, kind: Default::default() });
+        }
+        nodes.insert(0, terminal(id, NodeType::Start, "Start"));
+    }
+    if !nodes.iter().any(|n| matches!(n.node_type, NodeType::End)) {
+        let id = free_id("end", nodes);
+        let exits: Vec<String> = nodes.iter().filter(|n| !edges.iter().any(|e| e.source == n.id)).map(|n| n.id.clone()).collect();
+        for exit in exits {
+            edges.push(WorkflowEdge { id: format!("{}-{}", exit, id), source: exit, target: id.clone(), condition: None, label: None, kind: Default::default() });
+        }
+        nodes.push(terminal(id, NodeType::End, "End"));
+    }
+}
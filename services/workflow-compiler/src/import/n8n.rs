@@ -0,0 +1,804 @@
+//! n8n workflow export importer
+//!
+//! n8n exports list `nodes` with their canvas positions and a `connections` map from each
+//! node's name to the nodes every output leads to, so unlike the Zapier and Make importers
+//! this keeps the graph as drawn. Trigger nodes collapse into the Start node and become
+//! triggers; HTTP Request nodes become HttpCall nodes, IF and Switch nodes decisions with
+//! their conditions rewritten in the DSL's syntax, Wait nodes timers or signal waits, Execute
+//! Workflow nodes subworkflows, database nodes queries and data nodes such as Code, Set and
+//! Item Lists transforms. An output wired to several nodes forks through a ParallelGateway,
+//! a Merge below such a fork joins it, and disabled nodes are bypassed as n8n does.
+//!
+//! Nodes of other apps become Activity nodes named after the node type, and they are
+//! reported as unmapped together with every setting that has no equivalent.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use super::{http_call_config, ImportedWorkflow, UnmappedStep, NODE_SPACING};
+use crate::compiler::plugins::ACTIVITY_TYPE_KEY;
+use crate::dsl::text::node_id;
+use crate::error::CompilerError;
+use crate::{
+    NodeType, Position, RetryPolicy, ScheduleTriggerConfig, Trigger, TriggerType, Variable, WorkflowDefinition, WorkflowEdge,
+    WorkflowNode, WorkflowTimeouts,
+};
+
+/// Package of the nodes n8n ships with
+const BASE_PACKAGE: &str = "n8n-nodes-base.";
+
+/// Node types that only reshape the items passing through
+const TRANSFORM_TYPES: &[&str] = &[
+    "code", "function", "functionItem", "set", "itemLists", "splitOut", "aggregate", "summarize", "removeDuplicates",
+    "sort", "limit", "renameKeys", "dateTime", "crypto", "xml", "html", "markdown", "convertToFile", "extractFromFile",
+    "compareDatasets", "noOp",
+];
+
+/// Database node types that become DatabaseQuery nodes
+const DATABASE_TYPES: &[&str] = &["postgres", "mySql", "microsoftSql", "oracleSql", "snowflake", "questDb", "timescaleDb", "crateDb", "mongoDb"];
+
+/// Messaging node types that become Notification nodes
+const NOTIFICATION_TYPES: &[&str] = &["emailSend", "gmail", "slack", "telegram", "discord", "microsoftTeams", "mattermost", "twilio"];
+
+/// Node as stored in an export's `nodes` array
+struct Node<'a> {
+    id: String,
+    name: &'a str,
+    node_type: &'a str,
+    parameters: &'a Value,
+    raw: &'a Value,
+}
+
+impl<'a> Node<'a> {
+    /// Type without the built-in package, e.g. `httpRequest`
+    fn kind(&self) -> &'a str {
+        self.node_type.strip_prefix(BASE_PACKAGE).unwrap_or(self.node_type)
+    }
+
+    fn parameter(&self, key: &str) -> &'a Value {
+        self.parameters.get(key).unwrap_or(&Value::Null)
+    }
+
+    fn text(&self, key: &str) -> Option<&'a str> {
+        self.parameters.get(key).and_then(Value::as_str)
+    }
+
+    fn version(&self) -> f64 {
+        self.raw.get("typeVersion").and_then(Value::as_f64).unwrap_or(1.0)
+    }
+
+    fn disabled(&self) -> bool {
+        self.raw.get("disabled").and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    fn is_trigger(&self) -> bool {
+        let kind = self.kind();
+        kind.ends_with("Trigger") || matches!(kind, "start" | "webhook" | "cron" | "interval")
+    }
+
+    fn position(&self) -> Option<Position> {
+        let position = self.raw.get("position")?.as_array()?;
+        Some(Position { x: position.first()?.as_f64()?, y: position.get(1)?.as_f64()? })
+    }
+}
+
+/// Variable an n8n expression reads when it is a single `{{ $json.field }}`
+fn json_field(expression: &str) -> Option<String> {
+    let inner = expression.trim().strip_prefix("{{")?.strip_suffix("}}")?.trim();
+    let path = ["$json", "$input.item.json"].iter().find_map(|prefix| inner.strip_prefix(prefix))?;
+    let field = match path.strip_prefix('.') {
+        Some(field) => field,
+        None => {
+            let quoted = path.strip_prefix('[')?.strip_suffix(']')?;
+            quoted.strip_prefix('"').and_then(|f| f.strip_suffix('"'))
+                .or_else(|| quoted.strip_prefix('\'').and_then(|f| f.strip_suffix('\'')))?
+        }
+    };
+    let mut chars = field.chars();
+    let valid = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_');
+    valid.then(|| field.to_string())
+}
+
+/// DSL operand for a condition value of the given n8n type
+fn operand(value: &Value, value_type: &str, reads: &mut Vec<String>) -> Option<String> {
+    match value {
+        Value::String(text) => match text.strip_prefix('=') {
+            Some(expression) => {
+                let field = json_field(expression)?;
+                if !reads.contains(&field) {
+                    reads.push(field.clone());
+                }
+                Some(field)
+            }
+            None => match value_type {
+                "number" => text.trim().parse::<f64>().ok().map(|n| n.to_string()),
+                "boolean" => text.trim().parse::<bool>().ok().map(|b| b.to_string()),
+                _ => Some(value.to_string()),
+            },
+        },
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        Value::Null => Some(match value_type {
+            "number" => "0".to_string(),
+            "boolean" => "false".to_string(),
+            _ => "\"\"".to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// DSL comparison for one n8n condition; None when the operation has no equivalent
+fn comparison(left: &Value, operation: &str, right: &Value, value_type: &str, reads: &mut Vec<String>) -> Option<String> {
+    if !matches!(value_type, "string" | "number" | "boolean") {
+        return None;
+    }
+    let left = operand(left, value_type, reads)?;
+    let operator = match operation {
+        "equal" | "equals" => "==",
+        "notEqual" | "notEquals" => "!=",
+        "larger" | "gt" => ">",
+        "largerEqual" | "gte" => ">=",
+        "smaller" | "lt" => "<",
+        "smallerEqual" | "lte" => "<=",
+        "true" => return Some(format!("{} == true", left)),
+        "false" => return Some(format!("{} == false", left)),
+        "isEmpty" | "empty" if value_type == "string" => return Some(format!("{} == \"\"", left)),
+        "isNotEmpty" | "notEmpty" if value_type == "string" => return Some(format!("{} != \"\"", left)),
+        _ => return None,
+    };
+    Some(format!("{} {} {}", left, operator, operand(right, value_type, reads)?))
+}
+
+fn combine(parts: Vec<String>, any: bool) -> Option<String> {
+    (!parts.is_empty()).then(|| parts.join(if any { " || " } else { " && " }))
+}
+
+/// Condition for the filter IF v2 and Switch v3 use: `{conditions: [...], combinator}`
+fn filter_condition(filter: &Value, reads: &mut Vec<String>) -> Option<String> {
+    let parts = filter.get("conditions")?.as_array()?.iter()
+        .map(|condition| {
+            let operator = condition.get("operator")?;
+            comparison(
+                condition.get("leftValue").unwrap_or(&Value::Null),
+                operator.get("operation")?.as_str()?,
+                condition.get("rightValue").unwrap_or(&Value::Null),
+                operator.get("type").and_then(Value::as_str).unwrap_or("string"),
+                reads,
+            )
+        })
+        .collect::<Option<Vec<_>>>()?;
+    combine(parts, filter.get("combinator").and_then(Value::as_str) == Some("or"))
+}
+
+/// Condition for the IF v1 rules, grouped by value type and combined by `combineOperation`
+fn legacy_condition(node: &Node, reads: &mut Vec<String>) -> Option<String> {
+    let mut parts = vec![];
+    for (value_type, rules) in node.parameter("conditions").as_object()? {
+        for rule in rules.as_array()? {
+            parts.push(comparison(
+                rule.get("value1").unwrap_or(&Value::Null),
+                rule.get("operation").and_then(Value::as_str).unwrap_or("equal"),
+                rule.get("value2").unwrap_or(&Value::Null),
+                value_type,
+                reads,
+            )?);
+        }
+    }
+    combine(parts, node.text("combineOperation") == Some("any"))
+}
+
+/// Five-field cron expression for one Schedule Trigger rule
+fn interval_cron(rule: &Value) -> Option<String> {
+    let number = |key: &str, default: u64| rule.get(key).and_then(Value::as_u64).unwrap_or(default);
+    let every = |n: u64| if n > 1 { format!("*/{}", n) } else { "*".to_string() };
+    let (minute, hour) = (number("triggerAtMinute", 0), number("triggerAtHour", 0));
+    match rule.get("field").and_then(Value::as_str).unwrap_or("days") {
+        "cronExpression" => rule.get("expression").and_then(Value::as_str).map(str::to_string),
+        "minutes" => Some(format!("{} * * * *", every(number("minutesInterval", 5)))),
+        "hours" => Some(format!("{} {} * * *", minute, every(number("hoursInterval", 1)))),
+        "days" => Some(format!("{} {} {} * *", minute, hour, every(number("daysInterval", 1)))),
+        "weeks" if number("weeksInterval", 1) == 1 => {
+            let days: Vec<String> = rule.get("triggerAtDay").and_then(Value::as_array).into_iter().flatten()
+                .filter_map(Value::as_u64).map(|d| d.to_string()).collect();
+            let days = if days.is_empty() { "0".to_string() } else { days.join(",") };
+            Some(format!("{} {} * * {}", minute, hour, days))
+        }
+        "months" => Some(format!("{} {} {} {} *", minute, hour, number("triggerAtDayOfMonth", 1), every(number("monthsInterval", 1)))),
+        _ => None,
+    }
+}
+
+/// Cron expression for one item of the legacy Cron node's `triggerTimes`
+fn trigger_time_cron(item: &Value) -> Option<String> {
+    let number = |key: &str| item.get(key).and_then(Value::as_u64).unwrap_or(0);
+    match item.get("mode").and_then(Value::as_str)? {
+        "everyMinute" => Some("* * * * *".to_string()),
+        "everyHour" => Some(format!("{} * * * *", number("minute"))),
+        "everyDay" => Some(format!("{} {} * * *", number("minute"), number("hour"))),
+        "everyWeek" => Some(format!("{} {} * * {}", number("minute"), number("hour"), number("weekday"))),
+        "everyMonth" => Some(format!("{} {} {} * *", number("minute"), number("hour"), item.get("dayOfMonth").and_then(Value::as_u64).unwrap_or(1))),
+        "custom" => item.get("cronExpression").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+/// n8n lists headers and body fields as `[{name, value}]`; HttpCall takes objects
+fn field_object(fields: &Value) -> Value {
+    match fields.as_array() {
+        Some(list) if !list.is_empty() => Value::Object(
+            list.iter()
+                .filter_map(|f| Some((f.get("name")?.as_str()?.to_string(), f.get("value").cloned().unwrap_or(Value::Null))))
+                .collect::<Map<_, _>>(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+/// JSON body text, parsed when it is literal JSON; n8n marks expressions with a leading `=`
+fn json_body(body: &Value) -> Value {
+    match body.as_str() {
+        Some(text) => serde_json::from_str(text.strip_prefix('=').unwrap_or(text)).unwrap_or_else(|_| body.clone()),
+        None => body.clone(),
+    }
+}
+
+/// Outgoing branches of a decision: condition and label of each output
+type Branches = Vec<(Option<String>, Option<String>)>;
+
+/// Graph under construction for one n8n workflow
+struct Importer {
+    nodes: Vec<WorkflowNode>,
+    edges: Vec<WorkflowEdge>,
+    triggers: Vec<Trigger>,
+    reads: Vec<String>,
+    unmapped: Vec<UnmappedStep>,
+    /// Branches of each IF and Switch node, by node ID
+    branches: HashMap<String, Branches>,
+    /// Merge nodes, turned into joins when they sit below a fork
+    merges: Vec<String>,
+    forks: HashSet<String>,
+}
+
+impl Importer {
+    fn unmap(&mut self, node: &Node, reason: impl Into<String>) {
+        self.unmapped.push(UnmappedStep { step: node.name.to_string(), app: node.node_type.to_string(), reason: reason.into() });
+    }
+
+    fn free_id(&self, base: &str) -> String {
+        let mut id = base.to_string();
+        while self.nodes.iter().any(|n| n.id == id) {
+            id.push('_');
+        }
+        id
+    }
+
+    fn add(&mut self, id: String, node_type: NodeType, label: &str, config: Value, position: Position) -> &mut WorkflowNode {
+        self.nodes.push(WorkflowNode {
+            id,
+            node_type,
+            label: label.to_string(),
+            config,
+            position,
+            retries: None,
+            session: None,
+            annotations: Default::default(),
+            timeouts: None,
+            profile: None,
+        });
+        self.nodes.last_mut().expect("node was just added")
+    }
+
+    fn edge(&mut self, source: &str, target: &str, condition: Option<String>, label: Option<String>) {
+        let mut id = format!("{}-{}", source, target);
+        while self.edges.iter().any(|e| e.id == id) {
+            id.push('_');
+        }
+        self.edges.push(WorkflowEdge { id, source: source.to_string(), target: target.to_string(), condition, label, kind: Default::default() });
+    }
+
+    /// Lead from a node's output to its targets, through a fork when there are several
+    fn connect(&mut self, source: &str, targets: &[String], condition: Option<String>, label: Option<String>) {
+        match targets {
+            [] => {}
+            [target] => self.edge(source, target, condition, label),
+            _ => {
+                let fork = self.free_id(&format!("{}_fork", source));
+                let position = self.nodes.iter().find(|n| n.id == source)
+                    .map(|n| Position { x: n.position.x + NODE_SPACING / 2.0, y: n.position.y })
+                    .unwrap_or(Position { x: 0.0, y: 0.0 });
+                self.add(fork.clone(), NodeType::ParallelGateway, "Fork", json!({}), position);
+                self.forks.insert(fork.clone());
+                self.edge(source, &fork, condition, label);
+                for target in targets {
+                    self.edge(&fork, target, None, None);
+                }
+            }
+        }
+    }
+
+    /// Read a condition, keeping the variables it reads only when all of it converts
+    fn condition(&mut self, read: impl FnOnce(&mut Vec<String>) -> Option<String>) -> Option<String> {
+        let mut reads = vec![];
+        let condition = read(&mut reads)?;
+        for variable in reads {
+            if !self.reads.contains(&variable) {
+                self.reads.push(variable);
+            }
+        }
+        Some(condition)
+    }
+
+    fn map_trigger(&mut self, node: &Node) {
+        match node.kind() {
+            "manualTrigger" | "start" | "executeWorkflowTrigger" => {
+                self.triggers.push(Trigger { trigger_type: TriggerType::Manual, config: Value::Null });
+            }
+            "webhook" => self.triggers.push(Trigger {
+                trigger_type: TriggerType::Webhook,
+                config: json!({ "source": "n8n", "path": node.parameter("path"), "method": node.text("httpMethod").unwrap_or("GET") }),
+            }),
+            "scheduleTrigger" | "cron" | "interval" => {
+                let crons: Vec<Option<String>> = match node.kind() {
+                    "scheduleTrigger" => match node.parameters.pointer("/rule/interval").and_then(Value::as_array) {
+                        Some(rules) => rules.iter().map(interval_cron).collect(),
+                        None => vec![interval_cron(&json!({}))],
+                    },
+                    "cron" => node.parameters.pointer("/triggerTimes/item").and_then(Value::as_array).into_iter().flatten()
+                        .map(trigger_time_cron).collect(),
+                    _ => {
+                        let interval = node.parameter("interval").as_u64().unwrap_or(1);
+                        let every = if interval > 1 { format!("*/{}", interval) } else { "*".to_string() };
+                        vec![match node.text("unit").unwrap_or("seconds") {
+                            "minutes" => Some(format!("{} * * * *", every)),
+                            "hours" => Some(format!("0 {} * * *", every)),
+                            _ => None,
+                        }]
+                    }
+                };
+                for cron in crons {
+                    let Some(cron) = cron else {
+                        self.unmap(node, "only intervals a cron expression can express become schedules");
+                        continue;
+                    };
+                    let config = ScheduleTriggerConfig { cron, note: Some(node.name.to_string()), ..Default::default() };
+                    self.triggers.push(Trigger { trigger_type: TriggerType::Schedule, config: serde_json::to_value(config).unwrap_or_default() });
+                }
+            }
+            _ => self.triggers.push(Trigger {
+                trigger_type: TriggerType::Event,
+                config: json!({ "source": "n8n", "node": node.node_type, "parameters": node.parameters }),
+            }),
+        }
+    }
+
+    /// Node type and config for a Wait node
+    fn map_wait(&mut self, node: &Node) -> (NodeType, Value) {
+        match node.text("resume").unwrap_or("timeInterval") {
+            "timeInterval" => {
+                let amount = node.parameter("amount").as_f64().unwrap_or(1.0);
+                // Version 1 counted hours by default, later versions seconds
+                let default_unit = if node.version() < 1.1 { "hours" } else { "seconds" };
+                let unit = match node.text("unit").unwrap_or(default_unit) {
+                    "minutes" => "m",
+                    "hours" => "h",
+                    "days" => "d",
+                    _ => "s",
+                };
+                (NodeType::WaitTimer, json!({ "duration": format!("{}{}", amount, unit) }))
+            }
+            "webhook" | "form" => {
+                if node.parameters.get("limitWaitTime").and_then(Value::as_bool) == Some(true) {
+                    self.unmap(node, "the wait limit is not imported; set a timeout on the signal wait");
+                }
+                (NodeType::WaitSignal, json!({ "signal": node.id }))
+            }
+            _ => {
+                self.unmap(node, "waiting until a specific time has no equivalent; the node became a placeholder");
+                (NodeType::Transform, json!({ "source": "n8n", "node": node.node_type, "parameters": node.parameters }))
+            }
+        }
+    }
+
+    /// Node type and config for an HTTP Request node, in its v1 or v3+ parameter layout
+    fn map_http(&mut self, node: &Node) -> (NodeType, Value) {
+        if node.text("authentication").is_some_and(|a| a != "none") {
+            self.unmap(node, "credentials are not imported; configure authentication on the HTTP call");
+        }
+        let config = if node.version() < 3.0 {
+            let body = match node.parameter("jsonParameters").as_bool() {
+                Some(true) => json_body(node.parameter("bodyParametersJson")),
+                _ => field_object(node.parameters.pointer("/bodyParametersUi/parameter").unwrap_or(&Value::Null)),
+            };
+            let headers = field_object(node.parameters.pointer("/headerParametersUi/parameter").unwrap_or(&Value::Null));
+            http_call_config(node.text("requestMethod").unwrap_or("GET"), node.parameter("url"), &headers, &body)
+        } else {
+            if node.parameter("sendQuery").as_bool() == Some(true) {
+                self.unmap(node, "query parameters are not imported; add them to the URL");
+            }
+            let headers = field_object(node.parameters.pointer("/headerParameters/parameters").unwrap_or(&Value::Null));
+            let body = match (node.parameter("sendBody").as_bool(), node.text("specifyBody")) {
+                (Some(true), Some("json")) => json_body(node.parameter("jsonBody")),
+                (Some(true), _) => field_object(node.parameters.pointer("/bodyParameters/parameters").unwrap_or(&Value::Null)),
+                _ => Value::Null,
+            };
+            http_call_config(node.text("method").unwrap_or("GET"), node.parameter("url"), &headers, &body)
+        };
+        (NodeType::HttpCall, config)
+    }
+
+    /// Branches of an IF node: the conditioned `true` output and the default `false` one
+    fn map_if(&mut self, node: &Node) -> Branches {
+        let condition = if node.parameters.pointer("/conditions/conditions").is_some() {
+            self.condition(|reads| filter_condition(node.parameter("conditions"), reads))
+        } else {
+            self.condition(|reads| legacy_condition(node, reads))
+        };
+        if condition.is_none() {
+            self.unmap(node, "the conditions have no DSL equivalent; set the condition on the true branch");
+        }
+        vec![(condition, Some("true".to_string())), (None, Some("false".to_string()))]
+    }
+
+    /// Branches of a Switch node: one per output, the fallback output unconditioned
+    fn map_switch(&mut self, node: &Node) -> Branches {
+        if node.text("mode").is_some_and(|m| m != "rules") {
+            self.unmap(node, "outputs chosen by an expression are not imported; set a condition on each branch");
+            return vec![(None, None); node.parameter("numberOutputs").as_u64().unwrap_or(4) as usize];
+        }
+        let mut branches: Branches = vec![];
+        let mut convertible = true;
+        if node.version() < 3.0 {
+            let value_type = node.text("dataType").unwrap_or("number");
+            let left = node.parameter("value1");
+            let mut conditions: Vec<Vec<String>> = vec![vec![]; 4];
+            for rule in node.parameters.pointer("/rules/rules").and_then(Value::as_array).into_iter().flatten() {
+                let output = rule.get("output").and_then(Value::as_u64).unwrap_or(0) as usize;
+                let operation = rule.get("operation").and_then(Value::as_str).unwrap_or("equal");
+                let right = rule.get("value2").unwrap_or(&Value::Null);
+                match self.condition(|reads| comparison(left, operation, right, value_type, reads)) {
+                    Some(condition) if output < conditions.len() => conditions[output].push(condition),
+                    _ => convertible = false,
+                }
+            }
+            branches.extend(conditions.into_iter().map(|parts| (combine(parts, true), None)));
+            if let Some(fallback) = node.parameter("fallbackOutput").as_u64().and_then(|f| branches.get_mut(f as usize)) {
+                *fallback = (None, Some("fallback".to_string()));
+            }
+        } else {
+            for rule in node.parameters.pointer("/rules/values").and_then(Value::as_array).into_iter().flatten() {
+                let condition = self.condition(|reads| filter_condition(rule.get("conditions").unwrap_or(&Value::Null), reads));
+                convertible &= condition.is_some();
+                let label = rule.get("outputKey").and_then(Value::as_str).filter(|_| rule.get("renameOutput").and_then(Value::as_bool) == Some(true));
+                branches.push((condition, label.map(str::to_string)));
+            }
+            match node.parameters.pointer("/options/fallbackOutput") {
+                Some(Value::String(extra)) if extra == "extra" => branches.push((None, Some("fallback".to_string()))),
+                Some(Value::Number(index)) => {
+                    if let Some(fallback) = index.as_u64().and_then(|i| branches.get_mut(i as usize)) {
+                        fallback.0 = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !convertible {
+            self.unmap(node, "some rules have no DSL equivalent; set the condition on their branches");
+        }
+        branches
+    }
+
+    /// Add the node for an n8n node that is not a trigger
+    fn map_node(&mut self, node: &Node) {
+        let kind = node.kind();
+        let is_base = node.node_type.starts_with(BASE_PACKAGE);
+        let source_config = || json!({ "source": "n8n", "node": node.node_type, "parameters": node.parameters });
+        let (node_type, config) = match kind {
+            "httpRequest" if is_base => self.map_http(node),
+            "if" if is_base => {
+                let branches = self.map_if(node);
+                self.branches.insert(node.id.clone(), branches);
+                (NodeType::Decision, json!({}))
+            }
+            "switch" if is_base => {
+                let branches = self.map_switch(node);
+                self.branches.insert(node.id.clone(), branches);
+                (NodeType::Decision, json!({}))
+            }
+            "wait" if is_base => self.map_wait(node),
+            "merge" if is_base => {
+                self.merges.push(node.id.clone());
+                (NodeType::Transform, source_config())
+            }
+            "executeWorkflow" if is_base => {
+                // Resource locators carry the ID and the name the editor showed
+                let locator = node.parameter("workflowId");
+                let workflow = match locator.get("cachedResultName").and_then(Value::as_str) {
+                    Some(name) => json!(crate::to_pascal_case(name)),
+                    None => match locator.get("value").unwrap_or(locator) {
+                        Value::Number(id) => json!(id.to_string()),
+                        other => other.clone(),
+                    },
+                };
+                if node.text("source").is_some_and(|s| s != "database") || workflow.is_null() {
+                    self.unmap(node, "only workflows referenced by ID become subworkflows; set the workflow to start");
+                }
+                let wait = node.parameters.pointer("/options/waitForSubWorkflow").and_then(Value::as_bool).unwrap_or(true);
+                (NodeType::SubWorkflow, json!({ "workflow": workflow, "wait_for_completion": wait }))
+            }
+            _ if is_base && TRANSFORM_TYPES.contains(&kind) => (NodeType::Transform, source_config()),
+            _ if is_base && DATABASE_TYPES.contains(&kind) => {
+                let mut config = json!({ "database": kind });
+                match node.text("operation") {
+                    Some("executeQuery") | None if node.parameters.get("query").is_some() => config["query"] = node.parameter("query").clone(),
+                    operation => {
+                        self.unmap(node, "only Execute Query operations carry a query; write the statement for this operation");
+                        config["operation"] = json!(operation);
+                        config["table"] = node.parameter("table").clone();
+                    }
+                }
+                (NodeType::DatabaseQuery, config)
+            }
+            _ if is_base && NOTIFICATION_TYPES.contains(&kind) => {
+                let message = ["text", "message", "body"].iter().find_map(|key| node.parameters.get(*key)).cloned().unwrap_or(Value::Null);
+                (NodeType::Notification, json!({ "channel": kind, "message": message, "parameters": node.parameters }))
+            }
+            "stopAndError" if is_base => {
+                self.unmap(node, "stopping with an error is not imported; the workflow ends here instead");
+                (NodeType::End, json!({}))
+            }
+            "respondToWebhook" | "splitInBatches" | "filter" if is_base => {
+                let reason = match kind {
+                    "respondToWebhook" => "responses to the webhook caller have no equivalent; the node became a placeholder",
+                    "filter" => "filters are not imported; add a Decision node",
+                    _ => "batch loops have no equivalent; the node became a placeholder",
+                };
+                self.unmap(node, reason);
+                (NodeType::Transform, source_config())
+            }
+            _ => {
+                self.unmap(node, "no native equivalent for this node; implement the activity it became");
+                let package = node.node_type.rsplit('.').next().unwrap_or(node.node_type);
+                (NodeType::Activity, json!({ ACTIVITY_TYPE_KEY: crate::to_pascal_case(package) }))
+            }
+        };
+
+        let retries = node.raw.get("retryOnFail").and_then(Value::as_bool) == Some(true);
+        let retryable = matches!(node_type, NodeType::HttpCall | NodeType::Activity | NodeType::DatabaseQuery | NodeType::Notification);
+        if retries && !retryable {
+            self.unmap(node, "retries only apply to activities and were dropped");
+        }
+        let continues = node.raw.get("continueOnFail").and_then(Value::as_bool) == Some(true)
+            || node.raw.get("onError").and_then(Value::as_str).is_some_and(|o| o != "stopWorkflow");
+        if continues {
+            self.unmap(node, "continuing after a failure is not imported; failures fail the workflow");
+        }
+
+        let position = node.position().unwrap_or(Position { x: self.nodes.len() as f64 * NODE_SPACING, y: 0.0 });
+        let added = self.add(node.id.clone(), node_type, node.name, config, position);
+        if retries && retryable {
+            let interval = format!("{}ms", node.raw.get("waitBetweenTries").and_then(Value::as_u64).unwrap_or(1000));
+            added.retries = Some(RetryPolicy {
+                max_attempts: node.raw.get("maxTries").and_then(Value::as_u64).unwrap_or(3) as u32,
+                initial_interval: interval.clone(),
+                max_interval: interval,
+                backoff_coefficient: 1.0,
+            });
+        }
+        if let Some(notes) = node.raw.get("notes").and_then(Value::as_str).filter(|n| !n.is_empty()) {
+            added.annotations.insert("notes".to_string(), notes.to_string());
+        }
+    }
+
+    /// Whether a fork lies upstream of a node
+    fn below_fork(&self, id: &str) -> bool {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(current) = queue.pop_front() {
+            for edge in self.edges.iter().filter(|e| e.target == current) {
+                if self.forks.contains(&edge.source) {
+                    return true;
+                }
+                if seen.insert(edge.source.as_str()) {
+                    queue.push_back(&edge.source);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Targets of one output in the `connections` map, by node name
+fn output_targets<'a>(connections: &'a Value, name: &str) -> Vec<Vec<&'a str>> {
+    connections.get(name).and_then(|c| c.get("main")).and_then(Value::as_array).into_iter().flatten()
+        .map(|output| {
+            output.as_array().into_iter().flatten()
+                .filter_map(|target| target.get("node").and_then(Value::as_str))
+                .collect()
+        })
+        .collect()
+}
+
+/// Import one workflow: an object with `nodes` and `connections`
+fn import_workflow(workflow: &Value) -> Result<ImportedWorkflow, CompilerError> {
+    let raw_nodes = workflow.get("nodes").and_then(Value::as_array)
+        .ok_or_else(|| CompilerError::ParseError("n8n workflow must contain a 'nodes' array".into()))?;
+    let connections = workflow.get("connections").unwrap_or(&Value::Null);
+    let name = workflow.get("name").and_then(Value::as_str).filter(|n| !n.is_empty()).unwrap_or("n8n Workflow");
+
+    let mut ids: HashSet<String> = ["start".to_string(), "end".to_string()].into();
+    let mut nodes: Vec<Node> = vec![];
+    for raw in raw_nodes {
+        let node_type = raw.get("type").and_then(Value::as_str).unwrap_or_default();
+        if node_type == "n8n-nodes-base.stickyNote" {
+            continue;
+        }
+        let name = raw.get("name").and_then(Value::as_str).unwrap_or_default();
+        let base = Some(node_id(name)).filter(|id| !id.is_empty()).unwrap_or_else(|| "node".to_string());
+        let mut id = base.clone();
+        let mut suffix = 1;
+        while ids.contains(&id) {
+            suffix += 1;
+            id = format!("{}_{}", base, suffix);
+        }
+        ids.insert(id.clone());
+        nodes.push(Node { id, name, node_type, parameters: raw.get("parameters").unwrap_or(&Value::Null), raw });
+    }
+    let by_name: HashMap<&str, &Node> = nodes.iter().map(|n| (n.name, n)).collect();
+
+    let mut importer = Importer {
+        nodes: vec![],
+        edges: vec![],
+        triggers: vec![],
+        reads: vec![],
+        unmapped: vec![],
+        branches: HashMap::new(),
+        merges: vec![],
+        forks: HashSet::new(),
+    };
+    let start_position = nodes.iter().find(|n| n.is_trigger()).and_then(Node::position)
+        .or_else(|| {
+            let first = nodes.iter().filter_map(Node::position).min_by(|a, b| a.x.total_cmp(&b.x))?;
+            Some(Position { x: first.x - NODE_SPACING, y: first.y })
+        })
+        .unwrap_or(Position { x: 0.0, y: 0.0 });
+    importer.add("start".to_string(), NodeType::Start, "Start", Value::Null, start_position.clone());
+    for node in nodes.iter().filter(|n| !n.disabled()) {
+        if node.is_trigger() {
+            importer.map_trigger(node);
+        } else {
+            importer.map_node(node);
+        }
+    }
+    if importer.triggers.is_empty() {
+        importer.triggers.push(Trigger { trigger_type: TriggerType::Manual, config: Value::Null });
+    }
+
+    // Disabled nodes pass their input through their first output
+    fn resolve(name: &str, by_name: &HashMap<&str, &Node>, connections: &Value, seen: &mut HashSet<String>, out: &mut Vec<String>) {
+        let Some(node) = by_name.get(name) else { return };
+        if !seen.insert(node.id.clone()) || node.is_trigger() {
+            return;
+        }
+        if node.disabled() {
+            for target in output_targets(connections, name).into_iter().next().unwrap_or_default() {
+                resolve(target, by_name, connections, seen, out);
+            }
+        } else {
+            out.push(node.id.clone());
+        }
+    }
+    let targets = |names: &[&str]| {
+        let (mut seen, mut out) = (HashSet::new(), vec![]);
+        for name in names {
+            resolve(name, &by_name, connections, &mut seen, &mut out);
+        }
+        out
+    };
+
+    let mut entries: Vec<&str> = vec![];
+    let mut entry_triggers = 0;
+    for node in nodes.iter().filter(|n| n.is_trigger() && !n.disabled()) {
+        let first = output_targets(connections, node.name).into_iter().next().unwrap_or_default();
+        entry_triggers += usize::from(!first.is_empty());
+        entries.extend(first);
+    }
+    let entries = targets(&entries);
+    if entry_triggers > 1 && entries.len() > 1 {
+        importer.unmapped.push(UnmappedStep {
+            step: "triggers".to_string(),
+            app: "n8n".to_string(),
+            reason: "the triggers start different nodes; the import runs their paths in parallel from Start".to_string(),
+        });
+    }
+    importer.connect("start", &entries, None, None);
+
+    let mut dangling: Vec<(String, Option<String>, Option<String>)> = vec![];
+    for node in nodes.iter().filter(|n| !n.disabled() && !n.is_trigger()) {
+        let outputs = output_targets(connections, node.name);
+        match importer.branches.get(&node.id).cloned() {
+            Some(branches) => {
+                for (index, (condition, label)) in branches.into_iter().enumerate() {
+                    let targets = targets(outputs.get(index).map(Vec::as_slice).unwrap_or_default());
+                    // Unwired outputs end the workflow, except a Switch's unused ones
+                    if targets.is_empty() {
+                        if condition.is_some() || label.is_some() {
+                            dangling.push((node.id.clone(), condition, label));
+                        }
+                    } else {
+                        importer.connect(&node.id, &targets, condition, label);
+                    }
+                }
+            }
+            None => {
+                if let Some(first) = outputs.first() {
+                    importer.connect(&node.id, &targets(first), None, None);
+                }
+                if outputs.iter().skip(1).any(|o| !o.is_empty()) {
+                    importer.unmap(node, "only the first output is imported; error and secondary outputs were dropped");
+                }
+            }
+        }
+    }
+
+    for merge in std::mem::take(&mut importer.merges) {
+        if importer.below_fork(&merge) {
+            if let Some(node) = importer.nodes.iter_mut().find(|n| n.id == merge) {
+                node.node_type = NodeType::ParallelGateway;
+                node.config = json!({});
+            }
+        }
+    }
+
+    let exits: Vec<String> = importer.nodes.iter()
+        .filter(|n| !matches!(n.node_type, NodeType::End) && !importer.edges.iter().any(|e| e.source == n.id))
+        .map(|n| n.id.clone())
+        .collect();
+    if !exits.is_empty() || !dangling.is_empty() {
+        let x = importer.nodes.iter().map(|n| n.position.x).fold(f64::MIN, f64::max) + NODE_SPACING;
+        let y = importer.nodes.iter().find(|n| exits.first() == Some(&n.id)).map(|n| n.position.y).unwrap_or(start_position.y);
+        importer.add("end".to_string(), NodeType::End, "End", Value::Null, Position { x, y });
+        for exit in exits {
+            importer.edge(&exit, "end", None, None);
+        }
+        for (source, condition, label) in dangling {
+            importer.edge(&source, "end", condition, label);
+        }
+    }
+
+    let timeouts = workflow.pointer("/settings/executionTimeout").and_then(Value::as_i64).filter(|t| *t > 0)
+        .map(|seconds| WorkflowTimeouts { execution: Some(format!("{}s", seconds)), ..Default::default() });
+    let description = match workflow.get("id") {
+        Some(Value::String(id)) => format!("Imported from n8n workflow {}", id),
+        Some(Value::Number(id)) => format!("Imported from n8n workflow {}", id),
+        _ => "Imported from an n8n workflow".to_string(),
+    };
+    Ok(ImportedWorkflow {
+        workflow: WorkflowDefinition {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: Some(description),
+            nodes: importer.nodes,
+            edges: importer.edges,
+            variables: importer.reads.into_iter()
+                .map(|name| Variable { name, var_type: String::new(), default_value: None, constant: false, schema: None })
+                .collect(),
+            triggers: importer.triggers,
+            timeouts,
+        },
+        unmapped: importer.unmapped,
+    })
+}
+
+/// Import an exported workflow, an array of them, or a `workflows` or `data` list as the
+/// n8n API returns
+pub fn import(source: &Value) -> Result<Vec<ImportedWorkflow>, CompilerError> {
+    let list = source.as_array()
+        .or_else(|| source.get("workflows").and_then(Value::as_array))
+        .or_else(|| source.get("data").and_then(Value::as_array));
+    match list {
+        Some(workflows) => workflows.iter().map(import_workflow).collect(),
+        None => Ok(vec![import_workflow(source)?]),
+    }
+}
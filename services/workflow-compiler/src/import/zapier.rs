@@ -0,0 +1,207 @@
+//! Zapier export (`zapfile.json`) importer
+//!
+//! Each zap stores its steps as nodes linked by `parent_id`. The root `read` step becomes
+//! a trigger; Webhooks by Zapier actions become HttpCall nodes and Formatter or Code
+//! steps become Transform nodes.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use super::{http_call_config, ImportedWorkflow, LinearBuilder};
+use crate::error::CompilerError;
+use crate::{NodeType, ScheduleTriggerConfig, Trigger, TriggerType};
+
+/// Step as stored in a zap's `nodes` map
+struct Step<'a> {
+    id: String,
+    parent: Option<String>,
+    type_of: &'a str,
+    app: &'a str,
+    action: &'a str,
+    title: Option<&'a str>,
+    params: &'a Value,
+}
+
+fn id_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+impl<'a> Step<'a> {
+    fn parse(raw: &'a Value) -> Option<Self> {
+        let selected_api = raw.get("selected_api").and_then(Value::as_str).unwrap_or_default();
+        Some(Self {
+            id: id_string(raw.get("id")?)?,
+            parent: raw.get("parent_id").and_then(id_string),
+            type_of: raw.get("type_of").and_then(Value::as_str).unwrap_or_default(),
+            // Strip the version, e.g. `WebHookCLIAPI@1.0.0`
+            app: selected_api.split('@').next().unwrap_or_default(),
+            action: raw.get("action").and_then(Value::as_str).unwrap_or_default(),
+            title: raw.get("title").and_then(Value::as_str).filter(|t| !t.is_empty()),
+            params: raw.get("params").unwrap_or(&Value::Null),
+        })
+    }
+
+    fn app_action(&self) -> String {
+        format!("{}:{}", self.app, self.action)
+    }
+
+    fn label(&self) -> String {
+        self.title.map(str::to_string).unwrap_or_else(|| self.app_action())
+    }
+
+    fn param(&self, key: &str) -> &'a Value {
+        self.params.get(key).unwrap_or(&Value::Null)
+    }
+}
+
+/// Leading number of a parameter such as `9`, `"9"` or `"9am"`
+fn numeric_param(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().map(|n| n as u32),
+        Value::String(s) => s.chars().take_while(char::is_ascii_digit).collect::<String>().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Cron expression for a Schedule by Zapier trigger
+fn schedule_cron(step: &Step) -> Option<String> {
+    let hour = numeric_param(step.param("time_of_day")).unwrap_or(0).min(23);
+    match step.action.trim_start_matches("every_").trim_start_matches("every").to_ascii_lowercase().as_str() {
+        "hour" => Some("0 * * * *".to_string()),
+        "day" => {
+            let weekends = step.param("trigger_on_weekends").as_bool().unwrap_or(true);
+            Some(format!("0 {} * * {}", hour, if weekends { "*" } else { "1-5" }))
+        }
+        "week" => {
+            let day = step.param("day_of_week");
+            let weekday = numeric_param(day).or_else(|| {
+                let name = day.as_str()?.to_ascii_lowercase();
+                ["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"]
+                    .iter()
+                    .position(|d| name.starts_with(&d[..3]))
+                    .map(|i| i as u32)
+            });
+            Some(format!("0 {} * * {}", hour, weekday.unwrap_or(1) % 7))
+        }
+        "month" => {
+            let day = numeric_param(step.param("day_of_month")).unwrap_or(1).clamp(1, 31);
+            Some(format!("0 {} {} * *", hour, day))
+        }
+        _ => None,
+    }
+}
+
+fn is_webhook_app(app: &str) -> bool {
+    app.starts_with("WebHook")
+}
+
+/// Map the zap's root step onto a trigger
+fn map_trigger(builder: &mut LinearBuilder, zap_id: &str, step: &Step) {
+    let trigger = if is_webhook_app(step.app) {
+        Trigger {
+            trigger_type: TriggerType::Webhook,
+            config: json!({ "source": "zapier", "zap": zap_id, "action": step.action }),
+        }
+    } else if step.app.starts_with("Schedule") {
+        let Some(cron) = schedule_cron(step) else {
+            builder.unmapped(&step.id, &step.app_action(), "unsupported Schedule by Zapier interval");
+            return;
+        };
+        let config = ScheduleTriggerConfig { cron, note: Some(step.label()), ..Default::default() };
+        Trigger {
+            trigger_type: TriggerType::Schedule,
+            config: serde_json::to_value(config).unwrap_or_default(),
+        }
+    } else {
+        Trigger {
+            trigger_type: TriggerType::Event,
+            config: json!({ "source": "zapier", "app": step.app, "event": step.action, "params": step.params }),
+        }
+    };
+    builder.trigger(trigger);
+}
+
+/// Map one action step onto a node, or report it
+fn map_step(builder: &mut LinearBuilder, step: &Step) {
+    match step.type_of {
+        "write" if is_webhook_app(step.app) => {
+            let method = match step.action {
+                "custom_request" => step.param("method").as_str().unwrap_or("GET"),
+                action => action,
+            };
+            let config = http_call_config(method, step.param("url"), step.param("headers"), step.param("data"));
+            builder.node(&step.id, NodeType::HttpCall, &step.label(), config);
+        }
+        "write" if step.app.starts_with("Formatter") || step.app.starts_with("Code") => {
+            let config = json!({ "source": "zapier", "app": step.app, "operation": step.action, "params": step.params });
+            builder.node(&step.id, NodeType::Transform, &step.label(), config);
+        }
+        "write" => builder.unmapped(&step.id, &step.app_action(), "no native equivalent for this app; replace it with an Activity node"),
+        "filter" => builder.unmapped(&step.id, &step.app_action(), "filters are not imported; add a Decision node"),
+        "branch" => builder.unmapped(&step.id, &step.app_action(), "Paths are not imported; add a Decision node"),
+        "search" => builder.unmapped(&step.id, &step.app_action(), "search steps have no native equivalent"),
+        other => builder.unmapped(&step.id, &step.app_action(), format!("unsupported step type '{}'", other)),
+    }
+}
+
+/// Report a step and everything below it as left out
+fn report_subtree(builder: &mut LinearBuilder, children: &BTreeMap<String, Vec<&Step>>, step: &Step, reason: &str) {
+    builder.unmapped(&step.id, &step.app_action(), reason);
+    for child in children.get(&step.id).into_iter().flatten() {
+        report_subtree(builder, children, child, reason);
+    }
+}
+
+fn import_zap(zap: &Value) -> Result<ImportedWorkflow, CompilerError> {
+    let zap_id = zap.get("id").and_then(id_string).unwrap_or_default();
+    let title = zap.get("title").and_then(Value::as_str).filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Zap {}", zap_id));
+    let raw_steps: Vec<&Value> = match zap.get("nodes") {
+        Some(Value::Object(nodes)) => nodes.values().collect(),
+        Some(Value::Array(nodes)) => nodes.iter().collect(),
+        _ => return Err(CompilerError::ParseError(format!("Zap '{}' has no nodes", title))),
+    };
+    let mut steps: Vec<Step> = raw_steps.into_iter().filter_map(Step::parse).collect();
+    steps.sort_by_key(|s| (s.id.len(), s.id.clone()));
+
+    let mut children: BTreeMap<String, Vec<&Step>> = BTreeMap::new();
+    let mut roots = Vec::new();
+    for step in &steps {
+        match &step.parent {
+            Some(parent) => children.entry(parent.clone()).or_default().push(step),
+            None => roots.push(step),
+        }
+    }
+    let Some(root) = roots.first() else {
+        return Err(CompilerError::ParseError(format!("Zap '{}' has no trigger step", title)));
+    };
+
+    let mut builder = LinearBuilder::new(&title, Some(format!("Imported from Zapier zap {}", zap_id)));
+    map_trigger(&mut builder, &zap_id, root);
+
+    // Follow the first child at each level; sibling paths are reported rather than merged
+    let mut level = children.get(&root.id);
+    while let Some((next, siblings)) = level.and_then(|l| l.split_first()) {
+        map_step(&mut builder, next);
+        for sibling in siblings {
+            report_subtree(&mut builder, &children, sibling, "on a secondary path; only the first path is imported");
+        }
+        level = children.get(&next.id);
+    }
+
+    Ok(builder.finish())
+}
+
+/// Import every zap in a Zapier export
+pub fn import(source: &Value) -> Result<Vec<ImportedWorkflow>, CompilerError> {
+    let zaps = source.get("zaps").and_then(Value::as_array)
+        .ok_or_else(|| CompilerError::ParseError("Zapier export must contain a 'zaps' array".into()))?;
+
+    zaps.iter().map(import_zap).collect()
+}
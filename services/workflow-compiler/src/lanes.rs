@@ -0,0 +1,205 @@
+//! Priority lanes for compilation work
+//!
+//! Editor requests run in the interactive lane and bulk work, such as revalidating every
+//! stored workflow or a nightly recompile sending `X-Compile-Lane: batch`, in the batch lane.
+//! Each lane runs at most its configured number of jobs at once, on blocking threads so
+//! queued work never holds a runtime worker. A batch job is not started while interactive
+//! jobs are waiting, so editor requests overtake queued batch work; batch jobs already
+//! running finish undisturbed.
+
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use crate::config::LaneConcurrency;
+
+/// Request header choosing the lane of a request
+pub const LANE_HEADER: &str = "X-Compile-Lane";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lane {
+    Interactive,
+    Batch,
+}
+
+impl Lane {
+    fn index(self) -> usize {
+        match self {
+            Lane::Interactive => 0,
+            Lane::Batch => 1,
+        }
+    }
+
+    /// Lane named by the request's `X-Compile-Lane` header, or `default` when it names none
+    pub fn requested(headers: &HeaderMap, default: Lane) -> Lane {
+        match headers.get(LANE_HEADER).and_then(|v| v.to_str().ok()).map(str::trim) {
+            Some(lane) if lane.eq_ignore_ascii_case("batch") => Lane::Batch,
+            Some(lane) if lane.eq_ignore_ascii_case("interactive") => Lane::Interactive,
+            _ => default,
+        }
+    }
+}
+
+/// Jobs and queue times of one lane
+#[derive(Debug, Default)]
+struct LaneState {
+    queued: usize,
+    running: usize,
+    started: u64,
+    total_queue_time: Duration,
+    max_queue_time: Duration,
+}
+
+/// Snapshot of a lane returned by `GET /api/v1/stats/lanes`
+#[derive(Debug, Serialize)]
+pub struct LaneReport {
+    pub lane: Lane,
+    pub concurrency: usize,
+    pub queued: usize,
+    pub running: usize,
+    /// Jobs started since the process began
+    pub started: u64,
+    pub average_queue_ms: f64,
+    pub max_queue_ms: u64,
+}
+
+#[derive(Debug)]
+struct State {
+    concurrency: LaneConcurrency,
+    lanes: [LaneState; 2],
+}
+
+/// Two-lane scheduler shared by every request
+#[derive(Debug)]
+pub struct Lanes {
+    state: Mutex<State>,
+    /// Woken whenever a job finishes or the concurrency changes
+    changed: Notify,
+}
+
+/// Slot of a running job, released on drop
+struct Slot {
+    lanes: Arc<Lanes>,
+    lane: Lane,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.lanes.state.lock().unwrap().lanes[self.lane.index()].running -= 1;
+        self.lanes.changed.notify_waiters();
+    }
+}
+
+/// Stops counting a job as queued if its request is dropped while it waits
+struct Waiting<'a> {
+    lanes: &'a Lanes,
+    lane: Lane,
+    admitted: bool,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        if !self.admitted {
+            self.lanes.state.lock().unwrap().lanes[self.lane.index()].queued -= 1;
+            // A batch job may have been held back only by this interactive one
+            self.lanes.changed.notify_waiters();
+        }
+    }
+}
+
+impl State {
+    fn limit(&self, lane: Lane) -> usize {
+        match lane {
+            Lane::Interactive => self.concurrency.interactive,
+            Lane::Batch => self.concurrency.batch,
+        }
+    }
+
+    fn can_start(&self, lane: Lane) -> bool {
+        let state = &self.lanes[lane.index()];
+        let yields = lane == Lane::Batch && self.lanes[Lane::Interactive.index()].queued > 0;
+        state.running < self.limit(lane) && !yields
+    }
+}
+
+impl Lanes {
+    pub fn new(concurrency: LaneConcurrency) -> Self {
+        Self { state: Mutex::new(State { concurrency, lanes: Default::default() }), changed: Notify::new() }
+    }
+
+    /// Apply reloaded concurrency limits; jobs already running keep their slots
+    pub fn configure(&self, concurrency: LaneConcurrency) {
+        self.state.lock().unwrap().concurrency = concurrency;
+        self.changed.notify_waiters();
+    }
+
+    /// Wait for a slot in `lane`
+    async fn acquire(self: &Arc<Self>, lane: Lane) -> Slot {
+        let queued_at = Instant::now();
+        self.state.lock().unwrap().lanes[lane.index()].queued += 1;
+        let mut waiting = Waiting { lanes: self, lane, admitted: false };
+        loop {
+            // Registered before checking, so a slot freed in between still wakes this job
+            let mut changed = pin!(self.changed.notified());
+            changed.as_mut().enable();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.can_start(lane) {
+                    let waited = queued_at.elapsed();
+                    let lane_state = &mut state.lanes[lane.index()];
+                    lane_state.queued -= 1;
+                    lane_state.running += 1;
+                    lane_state.started += 1;
+                    lane_state.total_queue_time += waited;
+                    lane_state.max_queue_time = lane_state.max_queue_time.max(waited);
+                    waiting.admitted = true;
+                    return Slot { lanes: self.clone(), lane };
+                }
+            }
+            changed.await;
+        }
+    }
+
+    /// Run `job` on a blocking thread once `lane` has a free slot; the slot is held until the
+    /// job returns, even if the request waiting on it goes away
+    pub async fn run<T: Send + 'static>(self: &Arc<Self>, lane: Lane, job: impl FnOnce() -> T + Send + 'static) -> T {
+        let slot = self.acquire(lane).await;
+        let job = move || {
+            let _slot = slot;
+            job()
+        };
+        match tokio::task::spawn_blocking(job).await {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    pub fn report(&self) -> Vec<LaneReport> {
+        let state = self.state.lock().unwrap();
+        [Lane::Interactive, Lane::Batch]
+            .into_iter()
+            .map(|lane| {
+                let lane_state = &state.lanes[lane.index()];
+                let average = if lane_state.started == 0 {
+                    0.0
+                } else {
+                    lane_state.total_queue_time.as_secs_f64() * 1000.0 / lane_state.started as f64
+                };
+                LaneReport {
+                    lane,
+                    concurrency: state.limit(lane),
+                    queued: lane_state.queued,
+                    running: lane_state.running,
+                    started: lane_state.started,
+                    average_queue_ms: average,
+                    max_queue_ms: lane_state.max_queue_time.as_millis() as u64,
+                }
+            })
+            .collect()
+    }
+}
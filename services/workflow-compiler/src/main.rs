@@ -2,30 +2,49 @@
 //! Follows DDD principles with clear domain separation
 
 use axum::{
-    extract::State,
-    http::StatusCode,
-    routing::{get, post},
+    extract::{FromRequest, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
     Json, Router,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
 use tokio::net::TcpListener;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::{error, info};
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
 use uuid::Uuid;
 
+mod audit;
+mod cache;
 mod compiler;
+mod config;
+mod deploy;
 mod dsl;
 mod error;
+mod export;
+mod import;
+mod lanes;
+mod registry;
+mod regress;
+mod stats;
+mod store;
+mod trace;
 
 pub use error::CompilerError;
 
+/// Counts each thread's heap so compilations can be held to their memory ceiling
+#[global_allocator]
+static ALLOCATOR: compiler::limits::CountingAllocator = compiler::limits::CountingAllocator;
+
 // =============================================================================
 // DOMAIN MODELS
 // =============================================================================
 
 /// Workflow definition from visual editor
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkflowDefinition {
     pub id: Uuid,
     pub name: String,
@@ -35,10 +54,35 @@ pub struct WorkflowDefinition {
     pub edges: Vec<WorkflowEdge>,
     pub variables: Vec<Variable>,
     pub triggers: Vec<Trigger>,
+    pub timeouts: Option<WorkflowTimeouts>,
+}
+
+/// Workflow-level Temporal timeouts as duration strings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowTimeouts {
+    /// WorkflowExecutionTimeout, spanning retries and continue-as-new
+    pub execution: Option<String>,
+    /// WorkflowRunTimeout for a single run
+    pub run: Option<String>,
+    /// WorkflowTaskTimeout for a single workflow task
+    pub task: Option<String>,
+}
+
+impl WorkflowDefinition {
+    /// Content hash of the definition, stable across serializations of the same graph
+    pub fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        // serde_json maps are ordered, so serializing the definition is canonical
+        let canonical = serde_json::to_vec(self).unwrap_or_default();
+        let digest = Sha256::digest(&canonical);
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("sha256:{}", hex)
+    }
 }
 
 /// Node in the workflow graph
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkflowNode {
     pub id: String,
     pub node_type: NodeType,
@@ -46,9 +90,22 @@ pub struct WorkflowNode {
     pub config: serde_json::Value,
     pub position: Position,
     pub retries: Option<RetryPolicy>,
+    /// Worker session group; activities sharing a group run on the same host
+    pub session: Option<String>,
+    /// Business metadata such as an owner or SLA, carried into generated comments, exports
+    /// and the source map
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    /// Activity timeouts; unset ones come from the execution profile or the generated defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<ActivityTimeouts>,
+    /// Execution profile in the service configuration that fills unset retries and timeouts;
+    /// defaults to the configured `default_profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum NodeType {
     Start,
@@ -63,15 +120,27 @@ pub enum NodeType {
     DatabaseQuery,
     Transform,
     Notification,
+    DecisionTable,
+    WaitSignals,
+    DynamicActivity,
+    CancellationScope,
+    NexusOperation,
+    FeatureFlag,
+    WeightedSplit,
+    PublishEvent,
+    /// Node type defined by a compiler plugin, which lowers it to a built-in node before
+    /// compilation
+    #[serde(untagged)]
+    Plugin(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Position {
     pub x: f64,
     pub y: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RetryPolicy {
     pub max_attempts: u32,
     pub initial_interval: String,
@@ -79,32 +148,386 @@ pub struct RetryPolicy {
     pub backoff_coefficient: f64,
 }
 
+/// Activity timeouts as duration strings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ActivityTimeouts {
+    /// StartToCloseTimeout, the longest a single attempt may run
+    pub start_to_close: Option<String>,
+    /// ScheduleToCloseTimeout, the longest the activity may take across all attempts
+    pub schedule_to_close: Option<String>,
+    /// HeartbeatTimeout, the longest an attempt may go without heartbeating
+    pub heartbeat: Option<String>,
+}
+
+/// Configuration for Activity nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ActivityConfig {
+    /// Registered activity name; defaults to one derived from the label
+    pub activity_type: Option<String>,
+}
+
+/// Configuration for Decision nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DecisionConfig {
+    /// Marks the decision as the head of an intended loop
+    #[serde(default, rename = "loop")]
+    pub is_loop: bool,
+}
+
+/// Configuration for HttpCall nodes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpCallConfig {
+    /// Request method, case-insensitive
+    #[serde(default = "default_http_method")]
+    #[schemars(extend("enum" = [
+        "GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS", "get", "head", "post", "put", "patch", "delete", "options",
+    ]))]
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Sent as is when a string, JSON-encoded otherwise
+    #[serde(default)]
+    pub body: serde_json::Value,
+    /// Asks for the request to run in workflow code rather than as an activity; rejected,
+    /// since network calls there break replay
+    #[serde(default)]
+    pub inline: bool,
+}
+
+impl Default for HttpCallConfig {
+    fn default() -> Self {
+        Self { method: default_http_method(), url: String::new(), headers: BTreeMap::new(), body: serde_json::Value::Null, inline: false }
+    }
+}
+
+fn default_http_method() -> String {
+    "GET".to_string()
+}
+
+/// Configuration for DatabaseQuery nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DatabaseQueryConfig {
+    /// SQL statement; without one the node runs as a plain activity
+    pub query: Option<String>,
+    /// Connection the statement runs on
+    pub connection: Option<String>,
+    pub database: Option<String>,
+    pub table: Option<String>,
+    /// Operation performed, e.g. `insert`, for diagrams
+    pub operation: Option<String>,
+}
+
+/// Configuration for Transform nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TransformConfig {
+    /// Values assigned to variables by name: a literal, or a `{{...}}` template
+    #[serde(default)]
+    pub assign: BTreeMap<String, serde_json::Value>,
+    /// JSON Schema of the output, whose properties set the variables they name
+    #[serde(default)]
+    pub output_schema: Option<serde_json::Value>,
+}
+
+/// Configuration for ParallelGateway nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ParallelGatewayConfig {
+    #[serde(default)]
+    pub join: JoinPolicy,
+}
+
+/// How a parallel gateway fork waits for its branches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinPolicy {
+    /// Wait for every branch to complete
+    #[default]
+    All,
+    /// First completed branch wins, remaining branches are cancelled
+    Any,
+    /// Wait for N branches to complete, then cancel the rest
+    NOfM(u32),
+}
+
+/// Configuration for DecisionTable nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DecisionTableConfig {
+    /// Workflow variables matched by each rule column
+    pub inputs: Vec<String>,
+    pub rules: Vec<DecisionRule>,
+    #[serde(default)]
+    pub hit_policy: HitPolicy,
+    /// Outcome when no rule matches
+    pub default_output: Option<String>,
+}
+
+/// Single decision table row: one unary test per input and the resulting outcome
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DecisionRule {
+    pub when: Vec<String>,
+    pub then: String,
+}
+
+/// DMN hit policy controlling how matching rules are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HitPolicy {
+    /// At most one rule may match
+    #[default]
+    Unique,
+    /// First matching rule in table order wins
+    First,
+    /// Several rules may match if they agree on the outcome
+    Any,
+}
+
+/// Configuration for WaitSignals nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WaitSignalsConfig {
+    pub signals: Vec<String>,
+    #[serde(default)]
+    pub mode: SignalWaitMode,
+    /// String variable that signal payloads must carry as their correlation key
+    pub correlation_key: Option<String>,
+    /// Duration after which waiting fails, e.g. "30m"
+    pub timeout: Option<String>,
+}
+
+/// Whether a WaitSignals node needs every signal or just one of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalWaitMode {
+    #[default]
+    All,
+    Any,
+}
+
+/// Configuration for DynamicActivity nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DynamicActivityConfig {
+    /// String variable holding the activity name to run
+    pub selector: String,
+    /// Activity names the selector may resolve to
+    pub allowed: Vec<String>,
+}
+
+/// Configuration for SubWorkflow nodes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubWorkflowConfig {
+    /// Workflow type name of the child
+    pub workflow: String,
+    pub task_queue: Option<String>,
+    /// Temporal namespace to start the child in; defaults to the parent's namespace
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub parent_close_policy: ParentClosePolicy,
+    #[serde(default)]
+    pub cancellation_type: ChildCancellationType,
+    /// Block until the child completes; otherwise only wait for it to start
+    #[serde(default = "default_true")]
+    pub wait_for_completion: bool,
+    /// Child input variables by name: a JSON scalar, or a `{{...}}` template naming one of
+    /// the parent's variables
+    #[serde(default)]
+    pub input: BTreeMap<String, serde_json::Value>,
+}
+
+impl Default for SubWorkflowConfig {
+    fn default() -> Self {
+        Self {
+            workflow: String::new(),
+            task_queue: None,
+            namespace: None,
+            parent_close_policy: ParentClosePolicy::default(),
+            cancellation_type: ChildCancellationType::default(),
+            wait_for_completion: true,
+            input: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// What happens to a child workflow when its parent closes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ParentClosePolicy {
+    #[default]
+    Terminate,
+    RequestCancel,
+    Abandon,
+}
+
+/// How cancelling the parent's scope propagates to a child workflow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildCancellationType {
+    /// Request cancellation and continue immediately
+    #[default]
+    TryCancel,
+    /// Request cancellation and wait for the child to finish cancelling
+    WaitCancellationCompleted,
+    /// Leave the child running
+    Abandon,
+}
+
+/// Configuration for WaitTimer nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WaitTimerConfig {
+    /// Go-style, humantime or ISO-8601 duration
+    pub duration: String,
+}
+
+/// Configuration for WaitSignal nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WaitSignalConfig {
+    pub signal: String,
+}
+
+/// Configuration for CancellationScope nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CancellationScopeConfig {
+    /// Activity node IDs executed inside the scope, in definition order
+    pub members: Vec<String>,
+}
+
+/// Configuration for NexusOperation nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NexusOperationConfig {
+    /// Nexus endpoint registered in the cluster's endpoint registry
+    pub endpoint: String,
+    pub service: String,
+    pub operation: String,
+    /// Total time allowed for the operation, including retries and async completion
+    pub schedule_to_close_timeout: Option<String>,
+}
+
+/// Configuration for FeatureFlag nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FeatureFlagConfig {
+    #[serde(default)]
+    pub provider: FlagProvider,
+    /// Flag key as registered with the provider
+    pub flag: String,
+    /// Value used when the flag is unknown or evaluation fails
+    #[serde(default)]
+    pub default: bool,
+    /// String variable used as the targeting key, e.g. a user or tenant ID
+    pub context: Option<String>,
+}
+
+/// Where a FeatureFlag node's flag is evaluated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagProvider {
+    /// JSON file of booleans named by `FEATURE_FLAGS_FILE`
+    #[default]
+    Static,
+    Launchdarkly,
+    Unleash,
+}
+
+/// Configuration for PublishEvent nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PublishEventConfig {
+    /// Broker topic or subject the event is published to
+    pub topic: String,
+}
+
+/// Configuration for WeightedSplit nodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WeightedSplitConfig {
+    /// One entry per outgoing edge; weights are percentages summing to 100
+    pub branches: Vec<SplitBranch>,
+}
+
+/// Share of executions routed to one WeightedSplit target
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SplitBranch {
+    /// Target node ID of an outgoing edge
+    pub target: String,
+    pub weight: u32,
+}
+
+impl WorkflowNode {
+    /// Deserialize the node's free-form config into a typed model
+    pub fn typed_config<T: serde::de::DeserializeOwned + Default>(&self) -> Result<T, CompilerError> {
+        if self.config.is_null() {
+            return Ok(T::default());
+        }
+        serde_json::from_value(self.config.clone()).map_err(|e| {
+            CompilerError::ValidationError(compiler::diagnostic::Diagnostic::new(format!("Invalid config for node '{}': {}", self.id, e)).node(&self.id)).with_code("invalid-node-config")
+        })
+    }
+}
+
 /// Edge connecting nodes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkflowEdge {
     pub id: String,
     pub source: String,
     pub target: String,
     pub condition: Option<String>,
     pub label: Option<String>,
+    #[serde(default)]
+    pub kind: EdgeKind,
+}
+
+/// What traversing an edge means at runtime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Ordinary control flow
+    #[default]
+    Flow,
+    /// Completion of the source cancels the target cancellation scope
+    Cancel,
 }
 
 /// Workflow variable
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Variable {
     pub name: String,
+    /// Declared type; empty or `auto` infers it from defaults and usage
+    #[serde(default)]
     pub var_type: String,
     pub default_value: Option<serde_json::Value>,
+    /// Keeps the default value for the whole run, so conditions reading the variable are
+    /// decided at compile time; Transforms may not assign it
+    #[serde(default)]
+    pub constant: bool,
+    /// Constraints checked at the top of the generated workflow
+    #[serde(default)]
+    pub schema: Option<VariableSchema>,
+}
+
+/// Input constraints on a workflow variable
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct VariableSchema {
+    /// Reject the empty string or a missing value; not available for numbers and booleans
+    pub required: bool,
+    /// Inclusive bounds for numeric variables
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    /// Inclusive length bounds in characters for strings, or items for arrays
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// RE2 pattern a non-empty string must match
+    pub pattern: Option<String>,
 }
 
 /// Workflow trigger
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Trigger {
     pub trigger_type: TriggerType,
     pub config: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TriggerType {
     Manual,
@@ -113,6 +536,32 @@ pub enum TriggerType {
     Event,
 }
 
+/// Configuration for Schedule triggers
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduleTriggerConfig {
+    pub cron: String,
+    #[serde(default)]
+    pub overlap: OverlapPolicy,
+    /// How far back missed actions are caught up after an outage, e.g. "1h"
+    pub catchup_window: Option<String>,
+    #[serde(default)]
+    pub paused: bool,
+    pub note: Option<String>,
+}
+
+/// What a schedule does when an action is due while the previous one still runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    #[default]
+    Skip,
+    BufferOne,
+    BufferAll,
+    CancelOther,
+    TerminateOther,
+    AllowAll,
+}
+
 // =============================================================================
 // COMPILATION OUTPUT
 // =============================================================================
@@ -123,7 +572,22 @@ pub struct CompiledWorkflow {
     pub workflow_code: String,
     pub activity_code: String,
     pub worker_code: String,
+    /// Multi-region connection helper; empty unless failover regions are configured
+    pub connection_code: String,
+    /// Outbox activities and relay; empty unless the outbox option fused a write and publish
+    pub outbox_code: String,
+    pub starter_code: String,
     pub test_code: String,
+    pub benchmark_code: String,
+    pub failure_test_code: String,
+    pub go_mod: String,
+    pub go_sum: String,
+    pub makefile: String,
+    pub docker_compose: String,
+    pub dockerfile: String,
+    pub ci_pipeline: Option<GeneratedFile>,
+    /// Project manifests of non-Go targets, e.g. `package.json`
+    pub files: Vec<GeneratedFile>,
     pub metadata: CompilationMetadata,
 }
 
@@ -131,10 +595,199 @@ pub struct CompiledWorkflow {
 pub struct CompilationMetadata {
     pub workflow_name: String,
     pub package_name: String,
+    pub definition_version: String,
+    pub definition_fingerprint: String,
+    pub target: CodegenTarget,
+    /// SDK release targeted, for the language of `target`; the Durable Task extension for
+    /// the Durable Functions targets, the Argo Workflows or Airflow release for `argo` and
+    /// `airflow`, the specification version for `serverless_workflow`, the Conductor OSS
+    /// release for `conductor`, the Cadence Go client release for `cadence`, and the Dagster
+    /// or Prefect release for `dagster` and `prefect`
+    pub temporal_sdk: String,
+    /// Build ID stamped into the worker when worker versioning is enabled
+    pub build_id: Option<String>,
+    /// Activities reachable from the start node after optimization
     pub activities: Vec<String>,
+    /// Node IDs each reachable activity originates from, in `activities` order
+    pub activity_origins: Vec<ActivityOrigin>,
     pub signals: Vec<String>,
     pub queries: Vec<String>,
     pub estimated_complexity: u32,
+    pub warnings: Vec<String>,
+    /// Tenant template overrides applied to the output, as `name@vN`
+    pub tenant_templates: Vec<String>,
+    /// Uses of deprecated node types and config fields
+    pub deprecations: Vec<compiler::deprecations::DeprecationWarning>,
+    /// Every node with its annotations, incoming edge labels and generated code line
+    #[serde(default)]
+    pub source_map: Vec<compiler::source_map::SourceMapping>,
+    /// Details specific to `target`, such as the features it could not map
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_metadata: Option<compiler::steps::TargetMetadata>,
+    /// Execution profiles applied to nodes, with the settings each supplied
+    #[serde(default)]
+    pub profiles: Vec<compiler::profiles::AppliedProfile>,
+    /// Nodes unreachable from Start with their edges, and variables nothing reads, removed by
+    /// optimization
+    #[serde(default)]
+    pub eliminated: compiler::optimizer::Eliminated,
+    /// Chains of sequential nodes merged into one node by optimization
+    #[serde(default)]
+    pub fused: Vec<compiler::optimizer::FusedChain>,
+    /// Optimization passes run, in order
+    #[serde(default)]
+    pub optimization_passes: Vec<String>,
+}
+
+/// Nodes that execute an activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityOrigin {
+    pub activity: String,
+    pub node_ids: Vec<String>,
+}
+
+/// Auxiliary project file emitted at a fixed path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// CI system targeted by the generated pipeline
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiProvider {
+    GithubActions,
+    Gitlab,
+}
+
+/// Language the workflow is compiled to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodegenTarget {
+    #[default]
+    Go,
+    /// `@temporalio/workflow` and `@temporalio/worker`
+    Typescript,
+    /// `temporalio` Python SDK
+    Python,
+    /// `io.temporal:temporal-sdk` in a Maven project
+    Java,
+    /// `Temporalio` .NET SDK in C#
+    Dotnet,
+    /// Azure Durable Functions in C# on the isolated worker
+    DurableCsharp,
+    /// Azure Durable Functions in JavaScript on the v4 Node.js programming model
+    DurableJavascript,
+    /// Argo Workflows `WorkflowTemplate` and `Workflow` manifests
+    Argo,
+    /// Apache Airflow DAG file
+    Airflow,
+    /// CNCF Serverless Workflow document in YAML and JSON
+    ServerlessWorkflow,
+    /// Netflix Conductor workflow and task definition JSON
+    Conductor,
+    /// Go on the Cadence client, sharing the go target's workflow and activity code
+    Cadence,
+    /// Dagster ops and a job definition in Python
+    Dagster,
+    /// Prefect 2 flow and tasks in Python
+    Prefect,
+}
+
+impl CodegenTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodegenTarget::Go => "go",
+            CodegenTarget::Typescript => "typescript",
+            CodegenTarget::Python => "python",
+            CodegenTarget::Java => "java",
+            CodegenTarget::Dotnet => "dotnet",
+            CodegenTarget::DurableCsharp => "durable_csharp",
+            CodegenTarget::DurableJavascript => "durable_javascript",
+            CodegenTarget::Argo => "argo",
+            CodegenTarget::Airflow => "airflow",
+            CodegenTarget::ServerlessWorkflow => "serverless_workflow",
+            CodegenTarget::Conductor => "conductor",
+            CodegenTarget::Cadence => "cadence",
+            CodegenTarget::Dagster => "dagster",
+            CodegenTarget::Prefect => "prefect",
+        }
+    }
+}
+
+/// Options controlling code generation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompileOptions {
+    /// Language to generate; the remaining options apply to the Go target only
+    pub target: CodegenTarget,
+    /// Temporal Go SDK version range to target, e.g. ">=1.20, <1.26"; defaults to the newest supported release
+    pub temporal_sdk: Option<String>,
+    /// Emit a CI pipeline for the given provider
+    pub ci: Option<CiProvider>,
+    /// Stamp the worker with a Build ID and opt it into worker versioning
+    pub worker_versioning: bool,
+    /// Build ID to stamp; defaults to the definition version plus a fingerprint prefix
+    pub build_id: Option<String>,
+    /// Temporal clusters the worker may connect to, in failover priority order
+    pub regions: Vec<FailoverRegion>,
+    /// Fuse each DatabaseQuery directly followed by a PublishEvent into a transactional-outbox activity
+    pub outbox: bool,
+    /// Fuse chains of plain Activity nodes with the same retries and timeouts into one
+    /// activity each, scheduled once; a retry of the fused activity repeats the whole chain
+    pub fuse_activities: bool,
+    /// Optimization level: 0 runs no passes, for fast feedback while editing; 1 folds constant
+    /// conditions and removes dead nodes; 2, the default, runs every pass
+    pub opt_level: Option<u8>,
+    /// Optimization passes to run, e.g. `["fold-constants", "dead-nodes"]`, overriding `opt_level`
+    pub passes: Option<Vec<String>>,
+    /// Trace each run and send an execution report through the notification service when it ends
+    pub execution_report: Option<ExecutionReportOptions>,
+    /// Definitions of the workflows SubWorkflow nodes may start; when any are known, each
+    /// reference must name one of them and map its inputs. Tenant requests add the latest
+    /// version of every workflow in the tenant's registry
+    pub workflows: Vec<WorkflowDefinition>,
+    /// Validation profile configured in the service, e.g. `strict` or `lenient`; defaults to
+    /// the configured default_validation_profile
+    pub validation_profile: Option<String>,
+}
+
+/// Where and when execution reports are sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionReportOptions {
+    /// Notification service channel, e.g. "email" or "sms"
+    #[serde(default = "default_report_channel")]
+    pub channel: String,
+    /// Address on that channel, e.g. an ops mailbox
+    pub recipient: String,
+    /// Send only when the run fails
+    #[serde(default)]
+    pub only_on_failure: bool,
+}
+
+fn default_report_channel() -> String {
+    "email".to_string()
+}
+
+/// One Temporal cluster in a multi-region deployment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FailoverRegion {
+    pub name: String,
+    /// Frontend gRPC endpoint as host:port
+    pub address: String,
+    /// Namespace in this cluster; defaults to "default"
+    pub namespace: Option<String>,
+    pub tls: Option<RegionTls>,
+}
+
+/// TLS settings for one region; a client certificate and key enable mTLS
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionTls {
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub root_ca: Option<String>,
+    pub server_name: Option<String>,
 }
 
 // =============================================================================
@@ -144,113 +797,623 @@ pub struct CompilationMetadata {
 #[derive(Clone)]
 struct AppState {
     compiler: Arc<WorkflowCompiler>,
+    http: reqwest::Client,
+    stats: Arc<Mutex<stats::UsageStats>>,
+    templates: registry::TemplateRegistry,
+    workflows: registry::WorkflowRegistry,
+    log_filter: LogFilterHandle,
+    cache: Arc<cache::ResponseCache>,
+    audit: audit::AuditLog,
+    lanes: Arc<lanes::Lanes>,
+}
+
+impl AppState {
+    /// State whose registries, response cache and audit log all live in `store`
+    fn new(compiler: WorkflowCompiler, store: Arc<dyn store::WorkflowStore>, log_filter: LogFilterHandle) -> Self {
+        let concurrency = compiler.config.read().unwrap().lanes;
+        Self {
+            compiler: Arc::new(compiler),
+            http: reqwest::Client::new(),
+            stats: Arc::new(Mutex::new(stats::UsageStats::default())),
+            templates: registry::TemplateRegistry::new(store.clone()),
+            workflows: registry::WorkflowRegistry::new(store.clone()),
+            log_filter,
+            cache: Arc::new(cache::ResponseCache::new(store.clone())),
+            audit: audit::AuditLog::new(store),
+            lanes: Arc::new(lanes::Lanes::new(concurrency)),
+        }
+    }
 }
 
+/// Handle for swapping the tracing filter when the log level is reloaded
+type LogFilterHandle = tracing_subscriber::reload::Handle<EnvFilter, tracing_subscriber::fmt::Formatter>;
+
 struct WorkflowCompiler {
     templates: handlebars::Handlebars<'static>,
+    dependencies: RwLock<compiler::dependencies::DependencyManifest>,
+    config: RwLock<config::CompilerConfig>,
+    /// Plugins registered at startup, kept across reloads
+    registered: Vec<Arc<dyn compiler::plugins::NodePlugin>>,
+    /// Registered plugins together with those of the configured plugin manifests
+    plugins: RwLock<compiler::plugins::PluginRegistry>,
 }
 
 impl WorkflowCompiler {
-    fn new() -> Self {
+    fn new(registered: Vec<Arc<dyn compiler::plugins::NodePlugin>>) -> Self {
         let mut templates = handlebars::Handlebars::new();
+        registry::register_helpers(&mut templates);
         
         // Register templates for Go code generation
         templates.register_template_string("workflow", include_str!("templates/workflow.hbs"))
             .expect("Failed to register workflow template");
         templates.register_template_string("activity", include_str!("templates/activity.hbs"))
             .expect("Failed to register activity template");
+
+        let dependencies = compiler::dependencies::DependencyManifest::load()
+            .expect("Failed to load dependency manifest");
+        let config = config::CompilerConfig::load()
+            .expect("Failed to load compiler config");
+        let plugins = compiler::plugins::PluginRegistry::with_manifests(&registered, &config.plugins)
+            .expect("Failed to register compiler plugins");
         
-        Self { templates }
+        Self {
+            templates,
+            dependencies: RwLock::new(dependencies),
+            config: RwLock::new(config),
+            registered,
+            plugins: RwLock::new(plugins),
+        }
+    }
+
+    /// Re-read the compiler config and dependency manifest, keeping the current ones if either fails
+    fn reload(&self) -> Result<config::CompilerConfig, CompilerError> {
+        let dependencies = compiler::dependencies::DependencyManifest::load()?;
+        let config = config::CompilerConfig::load()?;
+        let plugins = compiler::plugins::PluginRegistry::with_manifests(&self.registered, &config.plugins)?;
+
+        *self.dependencies.write().unwrap() = dependencies;
+        *self.config.write().unwrap() = config.clone();
+        *self.plugins.write().unwrap() = plugins;
+        Ok(config)
+    }
+
+    /// Definition with its plugin nodes lowered to built-in nodes
+    fn lower_plugins(&self, definition: &WorkflowDefinition) -> Result<WorkflowDefinition, CompilerError> {
+        self.plugins.read().unwrap().lower(definition).map_err(|e| e.with_code("plugin-node"))
     }
     
-    fn compile(&self, definition: &WorkflowDefinition) -> Result<CompiledWorkflow, CompilerError> {
+    /// Time and memory limits for the next compilation
+    fn limits(&self) -> config::CompilationLimits {
+        self.config.read().unwrap().limits.clone()
+    }
+
+    /// Compile a definition within the configured limits
+    fn compile(&self, definition: &WorkflowDefinition, options: &CompileOptions) -> Result<CompiledWorkflow, CompilerError> {
+        compiler::limits::enforce(&self.limits(), || self.compile_unlimited(definition, options))
+    }
+
+    fn compile_unlimited(&self, definition: &WorkflowDefinition, options: &CompileOptions) -> Result<CompiledWorkflow, CompilerError> {
+        // Check graph size before any pass walks it
+        let size = compiler::budget::check_size(definition, &self.config.read().unwrap().budgets);
+        size.map_err(|e| e.with_code("complexity-budget"))?;
+
+        // Lower plugin node types to built-in nodes
+        let lowered = self.lower_plugins(definition)?;
+
+        // Infer types of untyped variables
+        let (typed, mut warnings) = compiler::inference::infer_variable_types(&lowered);
+        compiler::limits::checkpoint("type inference")?;
+
+        // Fill unset activity retries and timeouts from execution profiles
+        let (typed, profiles) = compiler::profiles::apply(&typed, &self.config.read().unwrap()).map_err(|e| e.with_code("execution-profile"))?;
+
         // Validate workflow
-        self.validate(definition)?;
+        warnings.extend(self.validate(&typed, options)?.into_result()?.into_iter().map(|w| w.message));
         
         // Optimize graph
-        let optimized = self.optimize(definition)?;
+        let optimization = self.optimize(&typed, options)?;
+        warnings.extend(optimization.eliminated.variables.iter().map(|name| format!(
+            "Variable '{}' is never read by a node or edge condition and is left out of the generated input",
+            name
+        )));
+        let optimized = &optimization.definition;
         
         // Generate code
-        self.generate_code(&optimized)
+        let mut compiled = match compiler::backend::for_target(options.target) {
+            None => {
+                let sdk = compiler::sdk::resolve(options.temporal_sdk.as_deref())?;
+                self.generate_code(optimized, &definition.fingerprint(), sdk, options, &optimization.fused)?
+            }
+            Some(backend) => self.generate_target_code(optimized, &definition.fingerprint(), backend)?,
+        };
+        compiler::limits::checkpoint("code generation")?;
+        warnings.append(&mut compiled.metadata.warnings);
+        compiled.metadata.warnings = warnings;
+        compiled.metadata.deprecations = self.deprecations(definition);
+        compiled.metadata.profiles = profiles;
+        compiled.metadata.eliminated = optimization.eliminated;
+        compiled.metadata.fused = optimization.fused;
+        compiled.metadata.optimization_passes = optimization.passes;
+        Ok(compiled)
+    }
+
+    /// Uses of deprecated node types and config fields listed in the compiler config
+    fn deprecations(&self, definition: &WorkflowDefinition) -> Vec<compiler::deprecations::DeprecationWarning> {
+        compiler::deprecations::check(definition, &self.config.read().unwrap().deprecations)
+    }
+
+    /// Lint the definition within the configured limits, at the levels in `rules` over the
+    /// configured ones; findings are located in the definition as submitted
+    fn lint(&self, definition: &WorkflowDefinition, rules: &compiler::lint::LintLevels) -> Result<Vec<compiler::diagnostic::Diagnostic>, CompilerError> {
+        compiler::lint::check_levels(rules).map_err(CompilerError::ParseError)?;
+        compiler::limits::enforce(&self.limits(), || {
+            let size = compiler::budget::check_size(definition, &self.config.read().unwrap().budgets);
+            size.map_err(|e| e.with_code("complexity-budget"))?;
+            let lowered = self.lower_plugins(definition)?;
+            let config = self.config.read().unwrap();
+            let (profiled, _) = compiler::profiles::apply(&lowered, &config)?;
+            Ok(compiler::lint::lint(&profiled, &config.lints, rules).into_iter().map(|f| f.locate(definition)).collect())
+        })
     }
     
-    fn validate(&self, definition: &WorkflowDefinition) -> Result<(), CompilerError> {
+    /// Validate the definition within the configured limits, collecting every failed check and
+    /// non-fatal warning; only exceeding a limit ends validation with an error
+    fn validate(&self, definition: &WorkflowDefinition, options: &CompileOptions) -> Result<compiler::rules::ValidationReport, CompilerError> {
+        compiler::limits::enforce(&self.limits(), || {
+            let mut report = self.validate_unlimited(definition, options)?;
+            self.apply_validation_profile(definition, options, &mut report)?;
+            Ok(report)
+        })
+    }
+
+    /// Apply the validation profile the options name, else the configured default, to a report
+    fn apply_validation_profile(
+        &self,
+        definition: &WorkflowDefinition,
+        options: &CompileOptions,
+        report: &mut compiler::rules::ValidationReport,
+    ) -> Result<(), CompilerError> {
+        let config = self.config.read().unwrap();
+        let Some(name) = options.validation_profile.as_ref().or(config.default_validation_profile.as_ref()) else {
+            return Ok(());
+        };
+        let Some(profile) = config.validation_profiles.0.get(name) else {
+            let names: Vec<&str> = config.validation_profiles.0.keys().map(String::as_str).collect();
+            report.errors.push(CompilerError::ValidationError(format!(
+                "Unknown validation profile '{}'; configured profiles are {}",
+                name,
+                names.join(", ")
+            ).into()).with_code("validation-profile"));
+            return Ok(());
+        };
+        let mut findings = vec![];
+        if report.is_valid() && !profile.lints.is_empty() {
+            let lowered = self.lower_plugins(definition)?;
+            let (profiled, _) = compiler::profiles::apply(&lowered, &config)?;
+            findings = compiler::lint::lint_only(&profiled, &profile.lints);
+        }
+        compiler::strictness::apply(report, profile, findings);
+        Ok(())
+    }
+
+    fn validate_unlimited(&self, definition: &WorkflowDefinition, options: &CompileOptions) -> Result<compiler::rules::ValidationReport, CompilerError> {
+        let mut report = compiler::rules::ValidationReport::default();
+
+        // Check graph size before any pass walks it
+        let size = compiler::budget::check_size(definition, &self.config.read().unwrap().budgets);
+        if report.check("complexity-budget", size).is_none() {
+            return Ok(report);
+        }
+
+        // Check plugin nodes and lower them to built-in nodes
+        let lowered = match self.lower_plugins(definition) {
+            Ok(lowered) => lowered,
+            Err(e) => {
+                report.errors.push(e);
+                return Ok(report);
+            }
+        };
+
+        // Fill unset activity retries and timeouts from execution profiles as compilation does,
+        // so both check the same settings; unknown profile names are reported below
+        let profiled = compiler::profiles::apply(&lowered, &self.config.read().unwrap()).map_or(lowered, |(profiled, _)| profiled);
+        let definition = &profiled;
+
         // Check for start and end nodes
         let has_start = definition.nodes.iter().any(|n| matches!(n.node_type, NodeType::Start));
         let has_end = definition.nodes.iter().any(|n| matches!(n.node_type, NodeType::End));
         
         if !has_start {
-            return Err(CompilerError::ValidationError("Missing start node".into()));
+            report.errors.push(CompilerError::ValidationError("Missing start node".into()).with_code("missing-start-node"));
         }
         if !has_end {
-            return Err(CompilerError::ValidationError("Missing end node".into()));
+            report.errors.push(CompilerError::ValidationError("Missing end node".into()).with_code("missing-end-node"));
         }
         
-        // Check for cycles (simplified)
-        // Full implementation would use petgraph for cycle detection
-        
-        Ok(())
+        // Check node IDs
+        report.check("duplicate-node-id", compiler::validator::validate_node_ids(definition));
+
+        // Check edge endpoints and IDs
+        report.check("edge-reference", compiler::validator::validate_edges(definition));
+
+        // Check node configs against their node types
+        report.check("invalid-node-config", compiler::validator::validate_node_configs(definition));
+
+        // Check edge conditions against the condition grammar
+        report.check("condition-syntax", compiler::validator::validate_conditions(definition));
+
+        // Check variable names and the variables conditions and templates reference
+        report.check("variable-reference", compiler::validator::validate_variable_references(definition));
+
+        // Check declared variable types against defaults, assignments and conditions
+        report.check("variable-type", compiler::types::check(definition));
+
+        // Check constant variables have a default and are never assigned
+        report.check("constant-variable", compiler::folding::validate_constants(definition));
+
+        // Check for constructs removed in the enforced DSL version
+        report.check("removed-construct", compiler::deprecations::check_removed(definition, &self.config.read().unwrap()));
+
+        // Check size and complexity budgets
+        if let Some(budget) = report.check("complexity-budget", compiler::budget::check(definition, &self.config.read().unwrap().budgets)) {
+            report.warn("history-size", budget);
+        }
+
+        // Check variable schemas against their types and defaults
+        if let Some(schemas) = report.check("variable-schema", compiler::input_validation::validate(definition)) {
+            report.warn("synthetic-input-pattern", schemas);
+        }
+
+        // The remaining checks walk the graph and assume its nodes, edges and conditions are well-formed
+        if !report.is_valid() {
+            return Ok(report);
+        }
+
+        // Check for cycles outside loop-marked decisions
+        report.check("workflow-cycle", compiler::validator::validate_cycles(definition));
+
+        // Check every node runs from Start and leads to End, warning instead about unreachable
+        // nodes the selected optimization passes remove
+        let removes_unreachable = compiler::passes::select(options).is_ok_and(|p| p.iter().any(|p| p.name() == compiler::passes::DEAD_NODES));
+        if let Some(removed) = report.check("unconnected-node", compiler::validator::validate_connectivity(definition, removes_unreachable)) {
+            report.warn("unreachable-node-removed", removed);
+        }
+
+        // Check decision branch conditions and defaults
+        if let Some(decisions) = report.check("decision-branches", compiler::validator::validate_decision_branches(definition)) {
+            report.warn("decision-not-exhaustive", decisions);
+        }
+
+        // Check parallel gateway fork and join pairing
+        report.check("parallel-gateway-pairing", compiler::validator::validate_gateway_pairs(definition));
+
+        // Check parallel gateway join policies
+        report.check("parallel-join-policy", compiler::validator::validate_parallel_gateways(definition));
+
+        // Check decision table completeness and overlap
+        report.check("decision-table-shape", compiler::validator::validate_decision_tables(definition));
+
+        // Check multi-signal waits
+        report.check("signal-wait", compiler::validator::validate_signal_waits(definition));
+
+        // Check dynamic activity allowlists
+        report.check("dynamic-activity-allowlist", compiler::validator::validate_dynamic_activities(definition));
+
+        // Check Go identifiers generated from node labels
+        report.check("identifier-collision", compiler::validator::validate_identifiers(definition));
+
+        // Report names sanitized into Go identifiers
+        report.warn("identifier-sanitized", compiler::identifiers::check(definition));
+
+        // Check worker session groups
+        report.check("session-group", compiler::validator::validate_sessions(definition));
+
+        // Check child workflow options
+        report.check("child-workflow-options", compiler::validator::validate_sub_workflows(definition, &self.config.read().unwrap().namespace_policy));
+
+        // Check child workflow references against the known definitions
+        report.check("child-workflow-reference", compiler::validator::validate_sub_workflow_references(definition, &options.workflows));
+
+        // Check cancellation scope members and triggers
+        report.check("cancellation-scope", compiler::validator::validate_cancellation_scopes(definition));
+
+        // Check Nexus operation targets
+        report.check("nexus-target", compiler::validator::validate_nexus_operations(definition));
+
+        // Check event publish topics
+        report.check("publish-event-topic", compiler::validator::validate_publish_events(definition));
+
+        // Check weighted split branches and weights
+        report.check("weighted-split", compiler::validator::validate_weighted_splits(definition));
+
+        // Check feature flag keys and branches
+        report.check("feature-flag", compiler::feature_flag::validate(definition, &self.config.read().unwrap().known_feature_flags));
+
+        // Check for replay-unsafe calls and inline HTTP requests
+        if let Some(calls) = report.check("nondeterministic-construct", compiler::determinism::check(definition)) {
+            report.warn("nondeterministic-activity-input", calls);
+        }
+
+        // Check workflow-level timeouts
+        report.check("workflow-timeouts", compiler::validator::validate_workflow_timeouts(definition));
+
+        // Check schedule trigger policies
+        report.check("schedule-trigger", compiler::validator::validate_schedule_triggers(definition));
+
+        // Check webhook and event trigger configs
+        report.check("trigger-config", compiler::triggers::validate(definition));
+
+        // Check execution profile names
+        report.check("execution-profile", compiler::profiles::check(definition, &self.config.read().unwrap()));
+
+        // Check retry intervals, activity timeouts and timer durations
+        report.check("duration-format", compiler::validator::validate_node_durations(definition));
+
+        // Check retry policy semantics
+        if let Some(retries) = report.check("retry-policy", compiler::validator::validate_retry_policies(definition)) {
+            report.warn("non-idempotent-retry", retries);
+        }
+
+        // Check failover region settings
+        report.check("failover-regions", compiler::failover::validate_regions(&options.regions, &self.config.read().unwrap().namespace_policy));
+
+        // Check execution report settings
+        report.check("execution-report", compiler::report::validate(options.execution_report.as_ref()));
+
+        // Check options against the code generation target
+        report.check("target-support", compiler::steps::check_target_options(options));
+
+        // Check the optimization passes requested, and warn about the Decision branches
+        // constant folding would remove
+        if let Some(passes) = report.check("optimization-passes", compiler::passes::select(options)) {
+            if passes.iter().any(|p| p.name() == compiler::passes::FOLD_CONSTANTS) {
+                report.warn("constant-branch-pruned", compiler::folding::fold(definition)?.1);
+            }
+        }
+
+        // Check features against the targeted SDK release
+        if let Some(sdk) = report.check("sdk-feature", compiler::sdk::resolve(options.temporal_sdk.as_deref())) {
+            report.check("sdk-feature", compiler::sdk::check_features(definition, options, sdk));
+        }
+
+        compiler::limits::checkpoint("validation")?;
+        Ok(report)
     }
     
-    fn optimize(&self, definition: &WorkflowDefinition) -> Result<WorkflowDefinition, CompilerError> {
-        // Clone and optimize
-        let mut optimized = definition.clone();
-        
-        // Remove unreachable nodes
-        // Merge sequential activities
-        // Optimize parallel branches
-        
-        Ok(optimized)
+    fn optimize(
+        &self,
+        definition: &WorkflowDefinition,
+        options: &CompileOptions,
+    ) -> Result<compiler::passes::Optimization, CompilerError> {
+        // Fold constants, remove dead nodes and variables, and merge sequential nodes, as far
+        // as the options select
+        compiler::passes::run(definition, options)
     }
     
-    fn generate_code(&self, definition: &WorkflowDefinition) -> Result<CompiledWorkflow, CompilerError> {
-        let package_name = definition.name.to_lowercase().replace(" ", "_");
-        
-        // Extract activities from nodes
-        let activities: Vec<String> = definition.nodes.iter()
-            .filter(|n| matches!(n.node_type, NodeType::Activity | NodeType::HttpCall | NodeType::DatabaseQuery))
-            .map(|n| format!("{}Activity", to_pascal_case(&n.label)))
-            .collect();
+    fn generate_code(
+        &self,
+        definition: &WorkflowDefinition,
+        fingerprint: &str,
+        sdk: &compiler::sdk::SdkRelease,
+        options: &CompileOptions,
+        chains: &[compiler::optimizer::FusedChain],
+    ) -> Result<CompiledWorkflow, CompilerError> {
+        let package_name = compiler::identifiers::go_package_name(&definition.name);
+        let mut warnings = Vec::new();
+        // The cadence target ports this pipeline's sources and wires its own client and worker
+        let cadence = options.target == CodegenTarget::Cadence;
+        if cadence {
+            compiler::cadence::check(definition)?;
+        }
+
+        // Fuse database writes with the events they publish
+        let (fused, outbox) = compiler::outbox::fuse(definition, options.outbox);
+        let definition = &fused;
+        if options.outbox && outbox.is_empty() {
+            warnings.push("Outbox option is set but no DatabaseQuery node is directly followed by a PublishEvent node".to_string());
+        }
+        let outbox_activities: Vec<String> = outbox.iter().map(|p| p.activity.clone()).collect();
+        // Activities that reach external systems are mocked in generated tests
+        let mut mocked = outbox_activities.clone();
+        if options.execution_report.is_some() {
+            mocked.push(compiler::report::ACTIVITY.to_string());
+        }
+
+        // Extract activities from reachable nodes
+        let mut activity_origins = compiler::codegen::reachable_activities(definition);
+        for pair in &outbox {
+            if let Some(origin) = activity_origins.iter_mut().find(|o| o.activity == pair.activity) {
+                origin.node_ids.push(pair.publish.clone());
+            }
+        }
+        let activities = activity_origins.iter().map(|o| o.activity.clone()).collect();
         
         // Generate workflow code
-        let workflow_code = self.generate_workflow_code(definition, &package_name)?;
-        let activity_code = self.generate_activity_code(definition, &package_name)?;
-        let worker_code = self.generate_worker_code(definition, &package_name)?;
-        let test_code = self.generate_test_code(definition, &package_name)?;
+        let report = options.execution_report.as_ref();
+        let (mut workflow_code, mut node_offsets) = self.generate_workflow_code(definition, &package_name, fingerprint, report)?;
+        if cadence {
+            (workflow_code, node_offsets) = compiler::cadence::port_workflow(&workflow_code, &node_offsets);
+        }
+        let source_map = compiler::source_map::build(definition, &workflow_code, &node_offsets);
+        let mut activity_code = self.generate_activity_code(definition, &package_name, &outbox_activities, chains, report)?;
+        let build_id = options.worker_versioning.then(|| {
+            options.build_id.clone().unwrap_or_else(|| compiler::codegen::default_build_id(definition, fingerprint))
+        });
+        let worker_code = if cadence {
+            compiler::cadence::generate_worker_code(definition, &package_name)
+        } else {
+            self.generate_worker_code(definition, &package_name, sdk, build_id.as_deref(), !options.regions.is_empty())?
+        };
+        let connection_code = if options.regions.is_empty() {
+            String::new()
+        } else {
+            compiler::failover::generate_connection_code(&options.regions, &package_name, sdk)
+        };
+        let mut outbox_code = if outbox.is_empty() {
+            String::new()
+        } else {
+            compiler::outbox::generate_outbox_code(&outbox, &package_name, &to_pascal_case(&definition.name))
+        };
+        let starter_code = if cadence {
+            compiler::cadence::generate_starter_code(definition, &package_name)?
+        } else {
+            self.generate_starter_code(definition, &package_name)?
+        };
+        let mut test_code = self.generate_test_code(definition, &package_name, &mocked)?;
+        let mut benchmark_code = compiler::testgen::generate_benchmarks(definition, &to_pascal_case(&definition.name), &package_name, &outbox_activities);
+        let mut failure_test_code = compiler::testgen::generate_failure_tests(definition, &to_pascal_case(&definition.name), &package_name, &mocked);
+        if cadence {
+            for code in [&mut activity_code, &mut outbox_code, &mut test_code, &mut benchmark_code, &mut failure_test_code] {
+                *code = compiler::cadence::port(code);
+            }
+        }
+        let dependencies = self.dependencies.read().unwrap();
+        let requirements = if cadence { dependencies.cadence_requirements() } else { dependencies.requirements(sdk) };
+        let go_version = dependencies.go_version(sdk);
+        let go_mod = compiler::dependencies::generate_go_mod(&package_name, go_version, &requirements);
+        let (go_sum, mut dependency_warnings) = compiler::dependencies::generate_go_sum(&dependencies, &requirements);
+        warnings.append(&mut dependency_warnings);
+        let (makefile, docker_compose) = if cadence {
+            (compiler::cadence::generate_makefile(&package_name, &to_pascal_case(&definition.name)), compiler::cadence::generate_docker_compose())
+        } else {
+            (compiler::scaffold::generate_makefile(&package_name, &to_pascal_case(&definition.name)), compiler::scaffold::generate_docker_compose())
+        };
+        let dockerfile = compiler::scaffold::generate_dockerfile(go_version);
+        let ci_pipeline = options.ci
+            .map(|provider| compiler::scaffold::generate_ci_pipeline(provider, &package_name, go_version));
         
         Ok(CompiledWorkflow {
             workflow_code,
             activity_code,
             worker_code,
+            connection_code,
+            outbox_code,
+            starter_code,
             test_code,
+            benchmark_code,
+            failure_test_code,
+            go_mod,
+            go_sum,
+            makefile,
+            docker_compose,
+            dockerfile,
+            ci_pipeline,
+            files: vec![],
             metadata: CompilationMetadata {
                 workflow_name: definition.name.clone(),
                 package_name,
+                definition_version: definition.version.clone(),
+                definition_fingerprint: fingerprint.to_string(),
+                target: options.target,
+                temporal_sdk: if cadence { compiler::cadence::CADENCE_VERSION.to_string() } else { sdk.sdk.to_string() },
+                build_id,
                 activities,
-                signals: vec![],
-                queries: vec![],
+                activity_origins,
+                signals: compiler::codegen::signal_names(definition),
+                queries: vec![compiler::codegen::VERSION_QUERY.to_string()],
+                estimated_complexity: definition.nodes.len() as u32,
+                warnings,
+                tenant_templates: vec![],
+                deprecations: vec![],
+                source_map,
+                target_metadata: None,
+                profiles: vec![],
+                eliminated: Default::default(),
+                fused: vec![],
+                optimization_passes: vec![],
+            },
+        })
+    }
+    
+    /// Generate a non-Go project from the shared step traversal
+    fn generate_target_code(&self, definition: &WorkflowDefinition, fingerprint: &str, backend: &dyn compiler::backend::Backend) -> Result<CompiledWorkflow, CompilerError> {
+        let package_name = compiler::identifiers::go_package_name(&definition.name);
+        let sources = backend.generate(definition, &package_name, fingerprint)?;
+        let activity_origins = compiler::codegen::reachable_activities(definition);
+
+        Ok(CompiledWorkflow {
+            workflow_code: sources.workflow_code,
+            activity_code: sources.activity_code,
+            worker_code: sources.worker_code,
+            connection_code: String::new(),
+            outbox_code: String::new(),
+            starter_code: String::new(),
+            test_code: sources.test_code,
+            benchmark_code: String::new(),
+            failure_test_code: String::new(),
+            go_mod: String::new(),
+            go_sum: String::new(),
+            makefile: String::new(),
+            docker_compose: backend.docker_compose(),
+            dockerfile: String::new(),
+            ci_pipeline: None,
+            files: sources.files,
+            metadata: CompilationMetadata {
+                workflow_name: definition.name.clone(),
+                package_name,
+                definition_version: definition.version.clone(),
+                definition_fingerprint: fingerprint.to_string(),
+                target: backend.target(),
+                temporal_sdk: backend.sdk_version().to_string(),
+                build_id: None,
+                activities: activity_origins.iter().map(|o| o.activity.clone()).collect(),
+                activity_origins,
+                signals: sources.signals,
+                queries: backend.queries(),
                 estimated_complexity: definition.nodes.len() as u32,
+                warnings: vec![],
+                tenant_templates: vec![],
+                deprecations: vec![],
+                source_map: compiler::source_map::build(definition, "", &[]),
+                target_metadata: sources.metadata,
+                profiles: vec![],
+                eliminated: Default::default(),
+                fused: vec![],
+                optimization_passes: vec![],
             },
         })
     }
     
-    fn generate_workflow_code(&self, definition: &WorkflowDefinition, package_name: &str) -> Result<String, CompilerError> {
+    fn generate_workflow_code(
+        &self,
+        definition: &WorkflowDefinition,
+        package_name: &str,
+        fingerprint: &str,
+        report: Option<&ExecutionReportOptions>,
+    ) -> Result<(String, Vec<(String, usize)>), CompilerError> {
         let workflow_name = to_pascal_case(&definition.name);
-        
-        Ok(format!(r#"// Generated by OmniRoute Workflow Compiler
+        let version_info = compiler::codegen::generate_version_info(definition, &workflow_name, fingerprint);
+        let version_query = compiler::codegen::generate_version_query(&workflow_name);
+        let input_check = compiler::input_validation::generate_check(definition, &workflow_name);
+        let pins = compiler::folding::generate_pins(definition);
+        let compiler::codegen::WorkflowBody { code: body, node_offsets } = compiler::codegen::generate_workflow_body(definition, report.is_some())?;
+        let mut helpers = compiler::codegen::generate_workflow_helpers(definition, &workflow_name)?;
+        let input_fields = compiler::codegen::generate_input_fields(definition);
+        let imports = compiler::codegen::generate_workflow_imports(definition, report.is_some());
+        // With execution reports the workflow body runs inside a wrapper that owns the trace
+        let (wrapper, signature) = match report {
+            Some(options) => {
+                helpers.push_str(&compiler::report::generate_trace(options));
+                (
+                    compiler::report::generate_wrapper(&workflow_name),
+                    format!("// run{workflow_name} runs the workflow nodes, tracing each in report\nfunc run{workflow_name}(ctx workflow.Context, input {workflow_name}Input, report *ExecutionReport)"),
+                )
+            }
+            None => (String::new(), format!("// {workflow_name} is the main workflow function\nfunc {workflow_name}(ctx workflow.Context, input {workflow_name}Input)")),
+        };
+
+        let code = format!(r#"// Generated by OmniRoute Workflow Compiler
 // DO NOT EDIT - This file is auto-generated
 
 package {package_name}
 
 import (
-    "go.temporal.io/sdk/workflow"
-    "time"
-)
+{imports})
 
+{version_info}
 // {workflow_name}Input defines the workflow input
 type {workflow_name}Input struct {{
-    // Add input fields based on workflow variables
-}}
+{input_fields}}}
 
 // {workflow_name}Output defines the workflow output
 type {workflow_name}Output struct {{
@@ -258,70 +1421,126 @@ type {workflow_name}Output struct {{
     Message string
 }}
 
-// {workflow_name} is the main workflow function
-func {workflow_name}(ctx workflow.Context, input {workflow_name}Input) (*{workflow_name}Output, error) {{
+{wrapper}{signature} (*{workflow_name}Output, error) {{
     logger := workflow.GetLogger(ctx)
     logger.Info("{workflow_name} started")
-    
-    // Activity options
+
+{version_query}    
+{input_check}{pins}    // Activity options
     ao := workflow.ActivityOptions{{
         StartToCloseTimeout: 10 * time.Minute,
     }}
     ctx = workflow.WithActivityOptions(ctx, ao)
-    
-    // TODO: Generated workflow logic from nodes
-    
+
+{body}
     return &{workflow_name}Output{{
         Success: true,
         Message: "Workflow completed successfully",
     }}, nil
 }}
-"#))
+
+{helpers}"#);
+
+        // Node offsets are relative to the body; shift them to where it landed in the file
+        let body_start = code.find(&body).unwrap_or_default();
+        let node_offsets = node_offsets.into_iter().map(|(id, offset)| (id, body_start + offset)).collect();
+        Ok((code, node_offsets))
     }
     
-    fn generate_activity_code(&self, definition: &WorkflowDefinition, package_name: &str) -> Result<String, CompilerError> {
+    fn generate_activity_code(
+        &self,
+        definition: &WorkflowDefinition,
+        package_name: &str,
+        outbox_activities: &[String],
+        fused: &[compiler::optimizer::FusedChain],
+        report: Option<&ExecutionReportOptions>,
+    ) -> Result<String, CompilerError> {
+        let workflow_name = to_pascal_case(&definition.name);
+        // Fused activities run the stubs of the activities they replace
+        let mut skip = outbox_activities.to_vec();
+        skip.extend(fused.iter().filter_map(|c| c.activity.clone()));
+        let mut methods = compiler::codegen::generate_activity_methods(definition, &workflow_name, &skip)?;
+        methods.push_str(&compiler::optimizer::generate_fused_activities(definition, fused, &workflow_name));
+        let mut imports = vec!["\"context\""];
+        if compiler::feature_flag::uses_feature_flags(definition) {
+            methods.push_str(&compiler::feature_flag::generate_activity());
+            imports.extend(compiler::feature_flag::ACTIVITY_IMPORTS);
+        }
+        if let Some(options) = report {
+            methods.push_str(&compiler::report::generate_activity(options));
+            imports.extend(compiler::report::ACTIVITY_IMPORTS);
+        }
+        let dependencies = if outbox_activities.is_empty() {
+            "    // Add dependencies here\n"
+        } else {
+            imports.push("\"database/sql\"");
+            "    // DB is written by outbox activities and must hold the workflow_outbox table\n    DB *sql.DB\n    // Add dependencies here\n"
+        };
+        let imports = if methods.is_empty() && outbox_activities.is_empty() {
+            String::new()
+        } else {
+            imports.sort_unstable();
+            imports.dedup();
+            let imports: String = imports.iter().map(|i| format!("    {i}\n")).collect();
+            format!("import (\n{imports})\n\n")
+        };
+
         Ok(format!(r#"// Generated by OmniRoute Workflow Compiler
 package {package_name}
 
-import (
-    "context"
-)
-
-// Activities struct holds activity implementations
+{imports}// Activities struct holds activity implementations
 type Activities struct {{
-    // Add dependencies here
-}}
+{dependencies}}}
 
 // NewActivities creates a new Activities instance
 func NewActivities() *Activities {{
     return &Activities{{}}
 }}
 
-// TODO: Generate activity methods from workflow nodes
-"#))
+{methods}"#))
     }
     
-    fn generate_worker_code(&self, definition: &WorkflowDefinition, package_name: &str) -> Result<String, CompilerError> {
+    fn generate_worker_code(
+        &self,
+        definition: &WorkflowDefinition,
+        package_name: &str,
+        sdk: &compiler::sdk::SdkRelease,
+        build_id: Option<&str>,
+        failover: bool,
+    ) -> Result<String, CompilerError> {
         let workflow_name = to_pascal_case(&definition.name);
+        let new_client = sdk.client_constructor();
+        let worker_options = compiler::codegen::generate_worker_options(definition, build_id);
+        let (client_imports, connect, connected) = if failover {
+            (
+                "",
+                format!("    c, region, err := {package_name}.DialTemporal()\n"),
+                "    log.Println(\"Connected to Temporal region\", region.Name)\n",
+            )
+        } else {
+            (
+                "    \"os\"\n    \"go.temporal.io/sdk/client\"\n",
+                format!("    c, err := {new_client}(client.Options{{\n        HostPort: os.Getenv(\"TEMPORAL_ADDRESS\"),\n    }})\n"),
+                "",
+            )
+        };
         
         Ok(format!(r#"// Generated by OmniRoute Workflow Compiler
 package main
 
 import (
     "log"
-    "go.temporal.io/sdk/client"
-    "go.temporal.io/sdk/worker"
+{client_imports}    "go.temporal.io/sdk/worker"
     "{package_name}"
 )
 
 func main() {{
-    c, err := client.Dial(client.Options{{}})
-    if err != nil {{
+{connect}    if err != nil {{
         log.Fatalln("Unable to create client", err)
     }}
     defer c.Close()
-
-    w := worker.New(c, "{package_name}-task-queue", worker.Options{{}})
+{connected}
+    w := worker.New(c, "{package_name}-task-queue", {worker_options})
 
     w.RegisterWorkflow({package_name}.{workflow_name})
     
@@ -336,16 +1555,74 @@ func main() {{
 "#))
     }
     
-    fn generate_test_code(&self, definition: &WorkflowDefinition, package_name: &str) -> Result<String, CompilerError> {
+    fn generate_starter_code(&self, definition: &WorkflowDefinition, package_name: &str) -> Result<String, CompilerError> {
+        let workflow_name = to_pascal_case(&definition.name);
+        let timeout_options = compiler::codegen::generate_timeout_options(definition)?;
+        let schedules = compiler::codegen::generate_schedules(definition, &workflow_name, package_name)?;
+        let imports = compiler::codegen::generate_starter_imports(definition);
+
+        Ok(format!(r#"// Generated by OmniRoute Workflow Compiler
+package {package_name}
+
+import (
+{imports})
+
+// {workflow_name}StartOptions returns the start options for {workflow_name} executions
+func {workflow_name}StartOptions(workflowID string) client.StartWorkflowOptions {{
+    return client.StartWorkflowOptions{{
+        ID:        workflowID,
+        TaskQueue: "{package_name}-task-queue",
+{timeout_options}    }}
+}}
+
+// Start{workflow_name} starts a new {workflow_name} execution
+func Start{workflow_name}(ctx context.Context, c client.Client, workflowID string, input {workflow_name}Input) (client.WorkflowRun, error) {{
+    return c.ExecuteWorkflow(ctx, {workflow_name}StartOptions(workflowID), {workflow_name}, input)
+}}
+{schedules}"#))
+    }
+    
+    fn generate_test_code(&self, definition: &WorkflowDefinition, package_name: &str, mocked: &[String]) -> Result<String, CompilerError> {
         let workflow_name = to_pascal_case(&definition.name);
+        let mocks = compiler::testgen::generate_activity_mocks(mocked, "");
+        let mock_import = if mocks.is_empty() { "" } else { "    \"github.com/stretchr/testify/mock\"\n" };
+        // Validated inputs need the synthetic input from the benchmark file to pass
+        let function = compiler::testgen::synthetic_input_function(&workflow_name);
+        let input = if compiler::input_validation::uses_input_validation(definition) {
+            format!("{function}()")
+        } else {
+            format!("{workflow_name}Input{{}}")
+        };
+        let rejection_test = match compiler::input_validation::invalid_assignment(definition) {
+            Some((assignment, rule)) => format!(r#"
+func Test{workflow_name}RejectsInvalidInput(t *testing.T) {{
+    testSuite := &testsuite.WorkflowTestSuite{{}}
+    env := testSuite.NewTestWorkflowEnvironment()
+
+    env.RegisterWorkflow({workflow_name})
+    input := {function}()
+    // {rule}
+    {assignment}
+
+    env.ExecuteWorkflow({workflow_name}, input)
+
+    require.True(t, env.IsWorkflowCompleted())
+    var appErr *temporal.ApplicationError
+    require.ErrorAs(t, env.GetWorkflowError(), &appErr)
+    require.Equal(t, "{error_type}", appErr.Type())
+}}
+"#, error_type = compiler::input_validation::ERROR_TYPE),
+            None => String::new(),
+        };
+        let temporal_import = if rejection_test.is_empty() { "" } else { "    \"go.temporal.io/sdk/temporal\"\n" };
         
         Ok(format!(r#"// Generated by OmniRoute Workflow Compiler
 package {package_name}
 
 import (
     "testing"
-    "github.com/stretchr/testify/require"
-    "go.temporal.io/sdk/testsuite"
+{mock_import}    "github.com/stretchr/testify/require"
+{temporal_import}    "go.temporal.io/sdk/testsuite"
 )
 
 func Test{workflow_name}(t *testing.T) {{
@@ -354,19 +1631,22 @@ func Test{workflow_name}(t *testing.T) {{
 
     env.RegisterWorkflow({workflow_name})
     activities := NewActivities()
-    env.RegisterActivity(activities)
+    env.RegisterActivity(activities){mocks}
 
-    env.ExecuteWorkflow({workflow_name}, {workflow_name}Input{{}})
+    env.ExecuteWorkflow({workflow_name}, {input})
 
     require.True(t, env.IsWorkflowCompleted())
     require.NoError(t, env.GetWorkflowError())
 }}
-"#))
+{rejection_test}"#))
     }
 }
 
+/// Exported Go identifier for a name: words split at every character other than a letter or
+/// digit, each capitalized, with an `X` prefix when the result would not start with an
+/// upper-case letter, e.g. after a leading digit
 fn to_pascal_case(s: &str) -> String {
-    s.split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+    let pascal: String = s.split(|c: char| !c.is_alphanumeric())
         .map(|word| {
             let mut chars = word.chars();
             match chars.next() {
@@ -374,7 +1654,12 @@ fn to_pascal_case(s: &str) -> String {
                 Some(c) => c.to_uppercase().chain(chars).collect(),
             }
         })
-        .collect()
+        .collect();
+    if pascal.starts_with(char::is_uppercase) {
+        pascal
+    } else {
+        format!("X{}", pascal)
+    }
 }
 
 // API Handlers
@@ -382,6 +1667,22 @@ fn to_pascal_case(s: &str) -> String {
 #[derive(Deserialize)]
 struct CompileRequest {
     workflow: WorkflowDefinition,
+    #[serde(default)]
+    options: CompileOptions,
+    /// Shorthand for `options.target`, taking precedence when both are set
+    #[serde(default)]
+    target: Option<CodegenTarget>,
+}
+
+impl CompileRequest {
+    /// Options with the top-level target applied
+    fn options(&self) -> CompileOptions {
+        let mut options = self.options.clone();
+        if let Some(target) = self.target {
+            options.target = target;
+        }
+        options
+    }
 }
 
 #[derive(Serialize)]
@@ -389,67 +1690,995 @@ struct CompileResponse {
     success: bool,
     compiled: Option<CompiledWorkflow>,
     error: Option<String>,
+    /// Time or memory limit the compilation was stopped by
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_exceeded: Option<compiler::limits::LimitExceeded>,
+    /// Node path of the cycle that failed validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cycle: Option<Vec<String>>,
+    /// Nodes unreachable from Start or without a path to End that failed validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unconnected: Option<compiler::validator::UnconnectedNodes>,
+    /// Code, severity and location of the error, for editors to highlight the element at fault
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnostic: Option<compiler::diagnostic::Diagnostic>,
+    /// Legacy payload fields upgraded by lenient ingest
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    coercions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct DeployRequest {
+    workflow: WorkflowDefinition,
+    #[serde(default)]
+    options: CompileOptions,
+    connection: deploy::TemporalConnection,
+    #[serde(default)]
+    search_attributes: std::collections::BTreeMap<String, deploy::SearchAttributeType>,
+    /// Input for a smoke execution started once deployment succeeds
+    smoke_test_input: Option<serde_json::Value>,
+    /// Make the compiled Build ID the task queue default before the smoke run
+    #[serde(default)]
+    promote_build_id: bool,
+}
+
+#[derive(Serialize)]
+struct DeployResponse {
+    success: bool,
+    deployment: Option<deploy::DeployReport>,
+    error: Option<String>,
+    /// Legacy payload fields upgraded by lenient ingest
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    coercions: Vec<String>,
+}
+
+/// JSON or YAML body whose `workflow` is first upgraded from a legacy editor payload when
+/// the body sets `"lenient": true`; `coercions` lists every change made
+struct Ingest<T> {
+    request: T,
+    coercions: Vec<String>,
+}
+
+/// Media types read as YAML rather than JSON
+const YAML_CONTENT_TYPES: &[&str] = &["application/yaml", "application/x-yaml", "text/yaml", "text/x-yaml"];
+
+fn is_yaml(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|media_type| YAML_CONTENT_TYPES.iter().any(|yaml| media_type.trim().eq_ignore_ascii_case(yaml)))
+}
+
+#[axum::async_trait]
+impl<S: Send + Sync, T: serde::de::DeserializeOwned> FromRequest<S> for Ingest<T> {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let mut body = if is_yaml(req.headers()) {
+            let source = String::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+            dsl::yaml::to_value(&source).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()).into_response())?
+        } else {
+            let Json(body) = Json::<serde_json::Value>::from_request(req, state).await
+                .map_err(IntoResponse::into_response)?;
+            body
+        };
+        let coercions = if body.get("lenient").and_then(serde_json::Value::as_bool) == Some(true) {
+            body.get_mut("workflow").map(dsl::lenient::upgrade).unwrap_or_default()
+        } else {
+            vec![]
+        };
+        let request = serde_json::from_value(body).map_err(unprocessable)?;
+
+        Ok(Self { request, coercions })
+    }
+}
+
+/// Rejection of an ingested body that does not fit the request type
+fn unprocessable(e: serde_json::Error) -> Response {
+    (StatusCode::UNPROCESSABLE_ENTITY, format!("Failed to deserialize the request body: {}", e)).into_response()
 }
 
 async fn health() -> &'static str {
     "OK"
 }
 
+/// Add the latest version of each workflow in the calling tenant's registry to the workflows
+/// child references are checked against, unless the request supplies one of the same name
+fn with_tenant_workflows(state: &AppState, headers: &HeaderMap, mut options: CompileOptions) -> Result<CompileOptions, CompilerError> {
+    if let Some(tenant) = tenant_id(headers) {
+        for stored in state.workflows.latest(tenant)? {
+            if !options.workflows.iter().any(|w| w.name == stored.name) {
+                options.workflows.push(stored);
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// Compile a request as the calling tenant, whose templates override Go sources and whose
+/// stored workflows child references are checked against
+fn compile_for_tenant(state: &AppState, headers: &HeaderMap, request: &CompileRequest) -> Result<CompiledWorkflow, CompilerError> {
+    let options = with_tenant_workflows(state, headers, request.options())?;
+    let mut compiled = state.compiler.compile(&request.workflow, &options)?;
+    if let Some(tenant) = tenant_id(headers).filter(|_| options.target == CodegenTarget::Go) {
+        state.templates.apply(tenant, &request.workflow, &mut compiled)?;
+    }
+    Ok(compiled)
+}
+
 async fn compile_workflow(
     State(state): State<AppState>,
-    Json(request): Json<CompileRequest>,
+    headers: HeaderMap,
+    Ingest { request, coercions }: Ingest<CompileRequest>,
 ) -> Result<Json<CompileResponse>, StatusCode> {
-    match state.compiler.compile(&request.workflow) {
+    let lane = lanes::Lane::requested(&headers, lanes::Lane::Interactive);
+    let (request, result) = state.lanes.run(lane, {
+        let state = state.clone();
+        move || {
+            let result = compile_for_tenant(&state, &headers, &request);
+            state.stats.lock().unwrap().record(&request.workflow, result.as_ref().err());
+            (request, result)
+        }
+    }).await;
+
+    match result {
         Ok(compiled) => Ok(Json(CompileResponse {
             success: true,
             compiled: Some(compiled),
             error: None,
+            limit_exceeded: None,
+            cycle: None,
+            unconnected: None,
+            diagnostic: None,
+            coercions,
         })),
         Err(e) => Ok(Json(CompileResponse {
             success: false,
             compiled: None,
             error: Some(e.to_string()),
+            limit_exceeded: compiler::limits::LimitExceeded::from_error(&e),
+            cycle: e.cycle().map(<[String]>::to_vec),
+            unconnected: e.unconnected().cloned(),
+            diagnostic: Some(e.diagnostic(&request.workflow)),
+            coercions,
         })),
     }
 }
 
+#[derive(Deserialize)]
+struct RegressRequest {
+    #[serde(flatten)]
+    compile: CompileRequest,
+    /// Artifacts compiled earlier from the same definition
+    previous: serde_json::Value,
+}
+
+/// Report the file-level changes recompiling would make to previously compiled artifacts
+async fn regress_workflow(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Ingest { request, coercions }: Ingest<RegressRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let previous = regress::artifact_files(&request.previous);
+    if previous.is_empty() {
+        return Ok(Json(serde_json::json!({
+            "success": false,
+            "error": "previous must be compiled artifacts or an object of file contents",
+        })));
+    }
+
+    let lane = lanes::Lane::requested(&headers, lanes::Lane::Interactive);
+    let compile = request.compile;
+    let result = state.lanes.run(lane, {
+        let state = state.clone();
+        move || compile_for_tenant(&state, &headers, &compile)
+    }).await;
+    match result {
+        Ok(compiled) => {
+            let current = serde_json::to_value(&compiled).map(|v| regress::artifact_files(&v)).unwrap_or_default();
+            let report = regress::compare(&previous, &current);
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "compiler_version": env!("CARGO_PKG_VERSION"),
+                "previous_fingerprint": request.previous.pointer("/metadata/definition_fingerprint"),
+                "definition_fingerprint": compiled.metadata.definition_fingerprint,
+                "summary": report.summary,
+                "files": report.files,
+                "coercions": coercions,
+            })))
+        }
+        Err(e) => Ok(Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+            "coercions": coercions,
+        }))),
+    }
+}
+
+#[derive(Deserialize)]
+struct TraceRequest {
+    workflow: WorkflowDefinition,
+    /// Execution history of a run of the compiled workflow
+    history: serde_json::Value,
+}
+
+/// Map an execution history onto the definition: which nodes ran, how long each took and
+/// where the run failed
+async fn trace_execution(
+    State(state): State<AppState>,
+    Ingest { request, coercions }: Ingest<TraceRequest>,
+) -> Json<serde_json::Value> {
+    let result = state.compiler.lower_plugins(&request.workflow)
+        .and_then(|workflow| trace::map_history(&workflow, &request.history));
+    match result {
+        Ok(trace) => Json(serde_json::json!({
+            "success": true,
+            "trace": trace,
+            "coercions": coercions,
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+            "coercions": coercions,
+        })),
+    }
+}
+
+/// What `POST /api/v1/validate` checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ValidationMode {
+    /// Semantic validation of the deserialized definition
+    #[default]
+    Semantic,
+    /// Check the workflow against the published JSON Schema first and report every violation
+    /// with its JSON pointer; semantic validation only runs when there are none
+    Schema,
+}
+
+/// How `POST /api/v1/validate` and `POST /api/v1/lint` render their findings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DiagnosticFormat {
+    /// The endpoint's JSON response
+    #[default]
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning in review tooling
+    Sarif,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ValidateParams {
+    #[serde(default)]
+    mode: ValidationMode,
+    #[serde(default)]
+    format: DiagnosticFormat,
+    /// Path of the definition in its repository, which SARIF results are reported on
+    path: Option<String>,
+}
+
+/// JSON Schema of workflow definitions with the registered plugin node types
+fn current_schema(state: &AppState) -> serde_json::Value {
+    dsl::schema::workflow_schema(&state.compiler.plugins.read().unwrap().describe())
+}
+
+async fn workflow_schema(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    state.cache.respond(&headers, "schema", || Ok(current_schema(&state)))
+}
+
 async fn validate_workflow(
     State(state): State<AppState>,
-    Json(request): Json<CompileRequest>,
+    headers: HeaderMap,
+    Query(params): Query<ValidateParams>,
+    Ingest { request: body, coercions }: Ingest<serde_json::Value>,
+) -> Result<Response, StatusCode> {
+    let locale = accepted_locale(&headers);
+    let schema_mode = params.mode == ValidationMode::Schema;
+    if schema_mode {
+        // Pointers are relative to the workflow document, not the request body
+        let violations = dsl::schema::violations(&current_schema(&state), body.get("workflow").unwrap_or(&serde_json::Value::Null));
+        if !violations.is_empty() {
+            let errors: Vec<String> = violations.iter()
+                .map(|v| format!("{}: {}", if v.pointer.is_empty() { "(root)" } else { &v.pointer }, v.message))
+                .collect();
+            if params.format == DiagnosticFormat::Sarif {
+                let diagnostics: Vec<_> = errors.into_iter()
+                    .map(|error| compiler::messages::localize(compiler::diagnostic::Diagnostic::new(error), locale))
+                    .collect();
+                return Ok(sarif_response(locale, &diagnostics, params.path.as_deref(), true));
+            }
+            let body = serde_json::json!({
+                "valid": false,
+                "errors": errors,
+                "warnings": [],
+                "schema_violations": violations,
+                "locale": locale,
+                "coercions": coercions
+            });
+            return Ok(localized_response(locale, Json(body).into_response()));
+        }
+    }
+    let request: CompileRequest = match serde_json::from_value(body) {
+        Ok(request) => request,
+        Err(e) => return Ok(unprocessable(e)),
+    };
+    let lane = lanes::Lane::requested(&headers, lanes::Lane::Interactive);
+    let (request, result) = state.lanes.run(lane, {
+        let state = state.clone();
+        move || {
+            let (typed, diagnostics) = compiler::inference::infer_variable_types(&request.workflow);
+            let result = with_tenant_workflows(&state, &headers, request.options())
+                .and_then(|options| state.compiler.validate(&typed, &options))
+                .map(|mut report| {
+                    report.warnings.splice(0..0, compiler::rules::warnings("untyped-variable", diagnostics));
+                    report
+                });
+            let error = match &result {
+                Ok(report) => report.errors.first(),
+                Err(e) => Some(e),
+            };
+            state.stats.lock().unwrap().record(&request.workflow, error);
+            (request, result)
+        }
+    }).await;
+
+    let mut body = match result {
+        Ok(report) => {
+            let errors = report.errors.iter()
+                .map(|e| compiler::messages::localize(e.diagnostic(&request.workflow), locale));
+            let warnings = report.warnings.iter()
+                .map(|w| compiler::messages::localize(w.diagnostic(), locale));
+            let diagnostics: Vec<_> = errors.chain(warnings).collect();
+            if params.format == DiagnosticFormat::Sarif {
+                return Ok(sarif_response(locale, &diagnostics, params.path.as_deref(), true));
+            }
+            serde_json::json!({
+                "valid": report.is_valid(),
+                "errors": report.errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "warnings": report.warnings.iter().map(|w| w.message.clone()).collect::<Vec<_>>(),
+                "diagnostics": diagnostics,
+                "cycle": report.errors.iter().find_map(CompilerError::cycle),
+                "unconnected": report.errors.iter().find_map(CompilerError::unconnected),
+                "locale": locale,
+                "deprecations": state.compiler.deprecations(&request.workflow),
+                "coercions": coercions
+            })
+        }
+        Err(e) if params.format == DiagnosticFormat::Sarif => {
+            let diagnostics = [compiler::messages::localize(e.diagnostic(&request.workflow), locale)];
+            return Ok(sarif_response(locale, &diagnostics, params.path.as_deref(), false));
+        }
+        Err(e) => serde_json::json!({
+            "valid": false,
+            "errors": [e.to_string()],
+            "warnings": [],
+            "diagnostics": [compiler::messages::localize(e.diagnostic(&request.workflow), locale)],
+            "limit_exceeded": compiler::limits::LimitExceeded::from_error(&e),
+            "locale": locale,
+            "coercions": coercions
+        }),
+    };
+    if schema_mode {
+        body["schema_violations"] = serde_json::json!([]);
+    }
+    Ok(localized_response(locale, Json(body).into_response()))
+}
+
+#[derive(Deserialize)]
+struct LintRequest {
+    workflow: WorkflowDefinition,
+    /// Lint levels for this request by rule code, over the configured ones
+    #[serde(default)]
+    rules: compiler::lint::LintLevels,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LintParams {
+    #[serde(default)]
+    format: DiagnosticFormat,
+    /// Path of the definition in its repository, which SARIF results are reported on
+    path: Option<String>,
+}
+
+async fn lint_workflow(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<LintParams>,
+    Ingest { request, coercions }: Ingest<LintRequest>,
+) -> Result<Response, StatusCode> {
+    let locale = accepted_locale(&headers);
+    let lane = lanes::Lane::requested(&headers, lanes::Lane::Interactive);
+    let (request, result) = state.lanes.run(lane, {
+        let state = state.clone();
+        move || {
+            let result = state.compiler.lint(&request.workflow, &request.rules);
+            (request, result)
+        }
+    }).await;
+
+    if params.format == DiagnosticFormat::Sarif {
+        let (diagnostics, successful): (Vec<_>, _) = match result {
+            Ok(findings) => (findings.into_iter().map(|f| compiler::messages::localize(f, locale)).collect(), true),
+            Err(e) => (vec![compiler::messages::localize(e.diagnostic(&request.workflow), locale)], false),
+        };
+        return Ok(sarif_response(locale, &diagnostics, params.path.as_deref(), successful));
+    }
+    let body = match result {
+        Ok(findings) => serde_json::json!({
+            "success": true,
+            "findings": findings.into_iter().map(|f| compiler::messages::localize(f, locale)).collect::<Vec<_>>(),
+            "locale": locale,
+            "coercions": coercions
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+            "diagnostic": compiler::messages::localize(e.diagnostic(&request.workflow), locale),
+            "limit_exceeded": compiler::limits::LimitExceeded::from_error(&e),
+            "locale": locale,
+            "coercions": coercions
+        }),
+    };
+    Ok(localized_response(locale, Json(body).into_response()))
+}
+
+/// Locale for diagnostics, negotiated from the request's `Accept-Language`
+fn accepted_locale(headers: &HeaderMap) -> &'static str {
+    compiler::messages::negotiate(headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok()))
+}
+
+/// SARIF log of the diagnostics, reported on the definition at `path`, as a localized response
+fn sarif_response(locale: &'static str, diagnostics: &[compiler::messages::LocalizedDiagnostic], path: Option<&str>, successful: bool) -> Response {
+    let log = compiler::sarif::render(diagnostics, path.unwrap_or(compiler::sarif::DEFAULT_PATH), locale, successful);
+    let mut response = Json(log).into_response();
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(compiler::sarif::MEDIA_TYPE));
+    localized_response(locale, response)
+}
+
+/// Mark a response as localized so caches keep one copy per language
+fn localized_response(locale: &'static str, mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale));
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Language"));
+    response
+}
+
+async fn deploy_workflow(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Ingest { request, coercions }: Ingest<DeployRequest>,
+) -> Result<Json<DeployResponse>, StatusCode> {
+    let lane = lanes::Lane::requested(&headers, lanes::Lane::Interactive);
+    let (request, compiled) = state.lanes.run(lane, {
+        let state = state.clone();
+        move || {
+            // Deploy what /api/v1/compile returns for the caller, tenant templates and workflows included
+            let compile = CompileRequest { workflow: request.workflow.clone(), options: request.options.clone(), target: None };
+            let compiled = compile_for_tenant(&state, &headers, &compile);
+            (request, compiled)
+        }
+    }).await;
+
+    let result = async {
+        let compiled = compiled?;
+        let deployer = deploy::Deployer::new(&state.http, &request.connection);
+
+        let mut report = deploy::DeployReport {
+            search_attributes: deployer.upsert_search_attributes(&request.search_attributes).await?,
+            schedules: deployer.register_schedules(&request.workflow, &compiled).await?,
+            build_id: None,
+            smoke_run: None,
+        };
+        if request.promote_build_id {
+            report.build_id = Some(deployer.promote_build_id(&compiled).await?);
+        }
+        if let Some(input) = &request.smoke_test_input {
+            report.smoke_run = Some(deployer.start_smoke_run(&request.workflow, &compiled, input).await?);
+        }
+        Ok::<_, CompilerError>(report)
+    }.await;
+
+    match result {
+        Ok(report) => Ok(Json(DeployResponse {
+            success: true,
+            deployment: Some(report),
+            error: None,
+            coercions,
+        })),
+        Err(e) => Ok(Json(DeployResponse {
+            success: false,
+            deployment: None,
+            error: Some(e.to_string()),
+            coercions,
+        })),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SequenceExportRequest {
+    workflow: WorkflowDefinition,
+    #[serde(default)]
+    format: export::sequence::SequenceFormat,
+}
+
+async fn export_sequence(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<SequenceExportRequest>,
+) -> Response {
+    let key = format!("export/sequence/{:?}/{}", request.format, request.workflow.fingerprint());
+    state.cache.respond(&headers, &key, || {
+        let workflow = state.compiler.lower_plugins(&request.workflow)?;
+        let diagram = export::sequence::generate_sequence_diagram(&workflow, request.format)?;
+        Ok(serde_json::json!({
+            "success": true,
+            "format": request.format,
+            "diagram": diagram,
+        }))
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct StepFunctionsExportRequest {
+    workflow: WorkflowDefinition,
+}
+
+async fn export_step_functions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<StepFunctionsExportRequest>,
+) -> Response {
+    let key = format!("export/step-functions/{}", request.workflow.fingerprint());
+    state.cache.respond(&headers, &key, || {
+        let workflow = state.compiler.lower_plugins(&request.workflow)?;
+        let exported = export::step_functions::export(&workflow);
+        Ok(serde_json::json!({
+            "success": exported.state_machine.is_some(),
+            "state_machine": exported.state_machine,
+            "diagnostics": exported.diagnostics,
+        }))
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpWorkflowsExportRequest {
+    workflow: WorkflowDefinition,
+}
+
+async fn export_gcp_workflows(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<GcpWorkflowsExportRequest>,
+) -> Response {
+    let key = format!("export/gcp-workflows/{}", request.workflow.fingerprint());
+    state.cache.respond(&headers, &key, || {
+        let workflow = state.compiler.lower_plugins(&request.workflow)?;
+        let exported = export::gcp_workflows::export(&workflow);
+        Ok(serde_json::json!({
+            "success": exported.yaml.is_some(),
+            "yaml": exported.yaml,
+            "diagnostics": exported.diagnostics,
+        }))
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct BpmnExportRequest {
+    workflow: WorkflowDefinition,
+}
+
+async fn export_bpmn(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<BpmnExportRequest>,
+) -> Response {
+    let key = format!("export/bpmn/{}", request.workflow.fingerprint());
+    state.cache.respond(&headers, &key, || {
+        let workflow = state.compiler.lower_plugins(&request.workflow)?;
+        let exported = export::bpmn::export(&workflow);
+        Ok(serde_json::json!({
+            "success": exported.xml.is_some(),
+            "xml": exported.xml,
+            "diagnostics": exported.diagnostics,
+        }))
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct DotExportRequest {
+    workflow: WorkflowDefinition,
+}
+
+async fn export_dot(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<DotExportRequest>,
+) -> Response {
+    let key = format!("export/dot/{}", request.workflow.fingerprint());
+    state.cache.respond(&headers, &key, || {
+        let workflow = state.compiler.lower_plugins(&request.workflow)?;
+        Ok(serde_json::json!({
+            "success": true,
+            "dot": export::dot::export(&workflow),
+        }))
+    })
+}
+
+async fn list_rules(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let locale = accepted_locale(&headers);
+    let response = state.cache.respond(&headers, &format!("rules/{}", locale), || {
+        let rules: Vec<_> = compiler::rules::RULES.iter()
+            .map(|rule| compiler::rules::Rule { description: compiler::messages::summary(rule.code, locale).unwrap_or(rule.description), ..rule.clone() })
+            .collect();
+        Ok(serde_json::json!({
+            "success": true,
+            "locale": locale,
+            "rules": rules,
+        }))
+    });
+    localized_response(locale, response)
+}
+
+async fn import_workflows(
+    Path(format): Path<String>,
+    Json(source): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.compiler.validate(&request.workflow) {
-        Ok(_) => Ok(Json(serde_json::json!({
-            "valid": true,
-            "errors": []
+    match import::import(&format, &source) {
+        Ok(imported) => Ok(Json(serde_json::json!({
+            "success": true,
+            "imported": imported,
         }))),
         Err(e) => Ok(Json(serde_json::json!({
-            "valid": false,
-            "errors": [e.to_string()]
+            "success": false,
+            "error": e.to_string(),
+        }))),
+    }
+}
+
+/// Import a document posted as is, or as a JSON string or field like the other import formats
+fn import_document(
+    format: &str,
+    parse: impl FnOnce(&str) -> Result<Vec<import::ImportedWorkflow>, CompilerError>,
+    headers: &HeaderMap,
+    body: &str,
+) -> Json<serde_json::Value> {
+    let json = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).is_some_and(|v| v.starts_with("application/json"));
+    let result = if json {
+        serde_json::from_str(body)
+            .map_err(|e| CompilerError::ParseError(format!("Failed to parse the JSON body: {}", e)))
+            .and_then(|source| import::import(format, &source))
+    } else {
+        parse(body)
+    };
+    match result {
+        Ok(imported) => Json(serde_json::json!({
+            "success": true,
+            "imported": imported,
+        })),
+        Err(e) => Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+        })),
+    }
+}
+
+/// Import the processes of a BPMN 2.0 document posted as XML
+async fn import_bpmn(headers: HeaderMap, body: String) -> Json<serde_json::Value> {
+    import_document("bpmn", dsl::from_bpmn, &headers, &body)
+}
+
+/// Import a Serverless Workflow document posted as JSON or YAML
+async fn import_serverless_workflow(headers: HeaderMap, body: String) -> Json<serde_json::Value> {
+    import_document(
+        "serverless-workflow",
+        |text| Ok(vec![dsl::from_serverless_workflow(&dsl::yaml::to_value(text)?)?]),
+        &headers,
+        &body,
+    )
+}
+
+/// Import a Mermaid flowchart posted as text
+async fn import_mermaid(headers: HeaderMap, body: String) -> Json<serde_json::Value> {
+    import_document("mermaid", |diagram| dsl::from_mermaid(diagram).map(|imported| vec![imported]), &headers, &body)
+}
+
+/// Import a Graphviz digraph posted as text
+async fn import_dot(headers: HeaderMap, body: String) -> Json<serde_json::Value> {
+    import_document("dot", |graph| dsl::from_dot(graph).map(|imported| vec![imported]), &headers, &body)
+}
+
+/// Tenant of the authenticated caller, as forwarded by the gateway
+fn tenant_id(headers: &HeaderMap) -> Option<&str> {
+    headers.get("X-Tenant-ID").and_then(|v| v.to_str().ok()).filter(|t| !t.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+struct TemplateUpload {
+    content: String,
+}
+
+async fn upload_template(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(upload): Json<TemplateUpload>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let tenant = tenant_id(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let result = state.templates.upload(tenant, &name, upload.content);
+
+    match result {
+        Ok(version) => {
+            state.audit.record(audit::AuditAction::TemplateUploaded, Some(tenant), Some(&name), Some(version));
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "name": name,
+                "version": version,
+            })))
+        }
+        Err(e) => Ok(Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+        }))),
+    }
+}
+
+async fn list_templates(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let tenant = tenant_id(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+
+    match state.templates.list(tenant) {
+        Ok(templates) => Ok(Json(serde_json::json!({
+            "tenant": tenant,
+            "templates": templates,
+        }))),
+        Err(e) => Ok(Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+        }))),
+    }
+}
+
+/// Latest version a workflow write expects: the version of an `If-Match` ETag, or 0 for
+/// `If-None-Match: *`; none for an unconditional write
+fn expected_version(headers: &HeaderMap) -> Result<Option<u32>, StatusCode> {
+    let header_value = |name| headers.get(name).map(|v| v.to_str().map_err(|_| StatusCode::BAD_REQUEST)).transpose();
+    if let Some(tag) = header_value(header::IF_MATCH)? {
+        return registry::parse_workflow_etag(tag).map(Some).ok_or(StatusCode::BAD_REQUEST);
+    }
+    match header_value(header::IF_NONE_MATCH)? {
+        Some(tag) if tag.trim() == "*" => Ok(Some(0)),
+        Some(_) => Err(StatusCode::BAD_REQUEST),
+        None => Ok(None),
+    }
+}
+
+/// Validate a definition and store it as a new version in the tenant's workflow registry; a
+/// write with `If-Match` or `If-None-Match: *` that another editor got ahead of is rejected
+/// with 409 and the changes of both sides
+async fn store_workflow(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Ingest { request, coercions }: Ingest<CompileRequest>,
+) -> Result<Response, StatusCode> {
+    let tenant = tenant_id(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let expected = expected_version(&headers)?;
+    let options = request.options();
+    let (typed, _) = compiler::inference::infer_variable_types(&request.workflow);
+    let checked = with_tenant_workflows(&state, &headers, options.clone())
+        .and_then(|known| state.compiler.validate(&typed, &known))
+        .and_then(compiler::rules::ValidationReport::into_result);
+    if let Err(e) = checked {
+        return Ok(Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+            "coercions": coercions,
+        })).into_response());
+    }
+
+    let deprecations = state.compiler.deprecations(&request.workflow);
+    let submitted = expected.map(|_| request.workflow.clone());
+    let version = match state.workflows.store(tenant, &name, request.workflow, options, expected) {
+        Ok(version) => version,
+        Err(e @ CompilerError::VersionConflict { expected, .. }) => {
+            let submitted = submitted.expect("conditional writes keep the submitted definition");
+            let body = match state.workflows.conflict(tenant, &name, expected, &submitted) {
+                Ok(conflict) => serde_json::json!({ "success": false, "error": e.to_string(), "conflict": conflict }),
+                Err(diff_error) => serde_json::json!({ "success": false, "error": format!("{}; {}", e, diff_error) }),
+            };
+            return Ok((StatusCode::CONFLICT, Json(body)).into_response());
+        }
+        Err(e) => {
+            return Ok(Json(serde_json::json!({
+                "success": false,
+                "error": e.to_string(),
+                "coercions": coercions,
+            })).into_response());
+        }
+    };
+    state.audit.record(audit::AuditAction::WorkflowStored, Some(tenant), Some(&name), Some(version));
+    let etag = HeaderValue::from_str(&registry::workflow_etag(version)).expect("ETag is ASCII");
+    Ok(([(header::ETAG, etag)], Json(serde_json::json!({
+        "success": true,
+        "name": name,
+        "version": version,
+        "deprecations": deprecations,
+        "coercions": coercions,
+    }))).into_response())
+}
+
+async fn list_workflows(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let tenant = tenant_id(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+
+    match state.workflows.list(tenant) {
+        Ok(workflows) => Ok(Json(serde_json::json!({
+            "tenant": tenant,
+            "workflows": workflows,
+        }))),
+        Err(e) => Ok(Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+        }))),
+    }
+}
+
+/// Revalidate every stored workflow against the current rules and DSL version, e.g. after
+/// a config reload tightens validation or removes a deprecated construct; runs in the batch
+/// lane so editor requests are not held up behind it
+async fn revalidate(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let dsl_version = state.compiler.config.read().unwrap().dsl_version.clone();
+    let result = state.lanes.run(lanes::Lane::Batch, {
+        let state = state.clone();
+        move || state.workflows.revalidate(|definition, options| {
+            let (typed, _) = compiler::inference::infer_variable_types(definition);
+            let errors = match state.compiler.validate(&typed, options) {
+                Ok(report) => report.errors.iter().map(ToString::to_string).collect(),
+                Err(e) => vec![e.to_string()],
+            };
+            (errors, state.compiler.deprecations(definition))
+        })
+    }).await;
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => return Json(serde_json::json!({ "success": false, "error": e.to_string() })),
+    };
+    state.audit.record(audit::AuditAction::WorkflowsRevalidated, None, None, None);
+    if !report.newly_failing.is_empty() {
+        info!("Revalidation found {} newly failing workflows", report.newly_failing.len());
+    }
+
+    Json(serde_json::json!({
+        "success": true,
+        "dsl_version": dsl_version,
+        "report": report,
+    }))
+}
+
+/// Changes to stored state and configuration, oldest first
+async fn audit_events(State(state): State<AppState>) -> Json<serde_json::Value> {
+    match state.audit.events() {
+        Ok(events) => Json(serde_json::json!({ "success": true, "events": events })),
+        Err(e) => Json(serde_json::json!({ "success": false, "error": e.to_string() })),
+    }
+}
+
+async fn list_plugins(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "success": true,
+        "plugins": state.compiler.plugins.read().unwrap().describe(),
+    }))
+}
+
+async fn usage_stats(State(state): State<AppState>) -> Json<stats::StatsReport> {
+    Json(state.stats.lock().unwrap().report())
+}
+
+/// Concurrency, queue depth and queue times of the priority lanes
+async fn lane_stats(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "lanes": state.lanes.report() }))
+}
+
+/// Reload configuration in place, so in-flight requests finish on the settings they started with
+fn reload_config(state: &AppState) -> Result<config::CompilerConfig, CompilerError> {
+    let config = state.compiler.reload()?;
+    state.lanes.configure(config.lanes);
+    state.cache.clear();
+    state.log_filter.reload(config.log_filter())
+        .map_err(|e| CompilerError::ParseError(format!("Failed to apply log_level: {}", e)))?;
+    state.audit.record(audit::AuditAction::ConfigReloaded, None, None, None);
+
+    info!("Configuration reloaded");
+    Ok(config)
+}
+
+async fn reload(State(state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    match reload_config(&state) {
+        Ok(config) => Ok(Json(serde_json::json!({
+            "success": true,
+            "config": config,
+        }))),
+        Err(e) => Ok(Json(serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
         }))),
     }
 }
 
+/// Reload configuration whenever the process receives SIGHUP
+async fn reload_on_sighup(state: AppState) {
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("Failed to install SIGHUP handler");
+    while hangup.recv().await.is_some() {
+        if let Err(e) = reload_config(&state) {
+            error!("Configuration reload failed, keeping current settings: {}", e);
+        }
+    }
+}
+
+/// Node type plugins built into the server; platform teams register their `NodePlugin`
+/// implementations here, and manifest plugins come from the configured `plugin_dir`
+fn plugins() -> Vec<Arc<dyn compiler::plugins::NodePlugin>> {
+    vec![]
+}
+
 #[tokio::main]
 async fn main() {
+    let compiler = WorkflowCompiler::new(plugins());
+
     // Initialize tracing
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
-    
-    let state = AppState {
-        compiler: Arc::new(WorkflowCompiler::new()),
-    };
+    let builder = FmtSubscriber::builder()
+        .with_env_filter(compiler.config.read().unwrap().log_filter())
+        .with_filter_reloading();
+    let log_filter = builder.reload_handle();
+    tracing::subscriber::set_global_default(builder.finish()).expect("setting default subscriber failed");
     
-    let app = Router::new()
-        .route("/health", get(health))
-        .route("/api/v1/compile", post(compile_workflow))
-        .route("/api/v1/validate", post(validate_workflow))
-        .with_state(state);
+    let state = AppState::new(compiler, Arc::new(store::MemoryStore::default()), log_filter);
+    tokio::spawn(reload_on_sighup(state.clone()));
     
     let port = std::env::var("PORT").unwrap_or_else(|_| "8130".to_string());
     let listener = TcpListener::bind(format!("0.0.0.0:{}", port)).await.unwrap();
     
     info!("Workflow Compiler listening on port {}", port);
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, router(state)).await.unwrap();
+}
+
+/// Every API route over `state`
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/api/v1/compile", post(compile_workflow))
+        .route("/api/v1/regress", post(regress_workflow))
+        .route("/api/v1/trace", post(trace_execution))
+        .route("/api/v1/validate", post(validate_workflow))
+        .route("/api/v1/lint", post(lint_workflow))
+        .route("/api/v1/schema", get(workflow_schema))
+        .route("/api/v1/deploy", post(deploy_workflow))
+        .route("/api/v1/import/bpmn", post(import_bpmn))
+        .route("/api/v1/import/serverless-workflow", post(import_serverless_workflow))
+        .route("/api/v1/import/mermaid", post(import_mermaid))
+        .route("/api/v1/import/dot", post(import_dot))
+        .route("/api/v1/import/:format", post(import_workflows))
+        .route("/api/v1/export/sequence", post(export_sequence))
+        .route("/api/v1/export/step-functions", post(export_step_functions))
+        .route("/api/v1/export/gcp-workflows", post(export_gcp_workflows))
+        .route("/api/v1/export/bpmn", post(export_bpmn))
+        .route("/api/v1/export/dot", post(export_dot))
+        .route("/api/v1/rules", get(list_rules))
+        .route("/api/v1/plugins", get(list_plugins))
+        .route("/api/v1/stats", get(usage_stats))
+        .route("/api/v1/stats/lanes", get(lane_stats))
+        .route("/api/v1/templates", get(list_templates))
+        .route("/api/v1/templates/:name", put(upload_template))
+        .route("/api/v1/workflows", get(list_workflows))
+        .route("/api/v1/workflows/:name", put(store_workflow))
+        .route("/api/v1/admin/reload", post(reload))
+        .route("/api/v1/admin/revalidate", post(revalidate))
+        .route("/api/v1/admin/audit", get(audit_events))
+        .with_state(state)
 }
@@ -0,0 +1,392 @@
+//! Per-tenant registries of custom codegen templates and stored workflow definitions, kept in
+//! the service's `WorkflowStore`
+//!
+//! Tenants upload Handlebars templates that replace individual generated files. Every
+//! upload becomes a new version; compilation renders the latest version of each. Workflow
+//! definitions are versioned the same way and keep the result of their last validation, so
+//! revalidating after a rule or DSL version change can tell newly failing workflows apart.
+//! Each workflow version has an ETag; a write naming the version it started from is rejected
+//! with a semantic diff of both sides when another editor stored a version since.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use handlebars::{handlebars_helper, Handlebars};
+use serde::Serialize;
+
+use crate::compiler::codegen::go_type;
+use crate::compiler::deprecations::DeprecationWarning;
+use crate::dsl::diff::{self, Change};
+use crate::error::CompilerError;
+use crate::store::WorkflowStore;
+use crate::{to_pascal_case, CompileOptions, CompiledWorkflow, WorkflowDefinition};
+
+/// Generated files a tenant template may replace
+pub const TEMPLATE_NAMES: &[&str] = &["workflow", "activity", "worker", "starter", "test"];
+
+handlebars_helper!(pascal_case_helper: |s: str| to_pascal_case(s));
+handlebars_helper!(go_type_helper: |s: str| go_type(s));
+
+/// Register the helpers available to built-in and tenant templates
+pub fn register_helpers(handlebars: &mut Handlebars) {
+    handlebars.register_helper("pascal_case", Box::new(pascal_case_helper));
+    handlebars.register_helper("go_type", Box::new(go_type_helper));
+}
+
+/// One uploaded revision of a template
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateVersion {
+    pub version: u32,
+    /// Upload time in seconds since the Unix epoch
+    pub uploaded_at: u64,
+    #[serde(skip)]
+    pub content: String,
+}
+
+/// Version history of one template, as listed by `GET /api/v1/templates`
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateHistory {
+    pub name: String,
+    pub latest: u32,
+    pub versions: Vec<TemplateVersion>,
+}
+
+#[derive(Serialize)]
+struct TemplateVariable<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    var_type: &'a str,
+}
+
+#[derive(Serialize)]
+struct TemplateActivity<'a> {
+    name: &'a str,
+    /// Activities receive the workflow input, so their inputs are the workflow variables
+    inputs: &'a [TemplateVariable<'a>],
+}
+
+/// Data a tenant template is rendered with
+#[derive(Serialize)]
+struct TemplateContext<'a> {
+    package_name: &'a str,
+    workflow_name: String,
+    definition_version: &'a str,
+    definition_fingerprint: &'a str,
+    variables: &'a [TemplateVariable<'a>],
+    activities: Vec<TemplateActivity<'a>>,
+    /// The built-in output for the file, for templates that wrap rather than replace it
+    default: &'a str,
+}
+
+/// Render one template in strict mode, so references to unknown fields are errors
+fn render(name: &str, content: &str, context: &TemplateContext) -> Result<String, String> {
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    register_helpers(&mut handlebars);
+    handlebars.register_template_string(name, content)
+        .map_err(|e| format!("Template '{}' is invalid: {}", name, e.reason()))?;
+    handlebars.render(name, context)
+        .map_err(|e| format!("Template '{}' failed to render: {}", name, e))
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+/// The generated file a template name replaces
+fn output_mut<'a>(compiled: &'a mut CompiledWorkflow, name: &str) -> Option<&'a mut String> {
+    match name {
+        "workflow" => Some(&mut compiled.workflow_code),
+        "activity" => Some(&mut compiled.activity_code),
+        "worker" => Some(&mut compiled.worker_code),
+        "starter" => Some(&mut compiled.starter_code),
+        "test" => Some(&mut compiled.test_code),
+        _ => None,
+    }
+}
+
+/// Custom templates keyed by tenant, then template name
+#[derive(Clone)]
+pub struct TemplateRegistry {
+    store: Arc<dyn WorkflowStore>,
+}
+
+impl TemplateRegistry {
+    pub fn new(store: Arc<dyn WorkflowStore>) -> Self {
+        Self { store }
+    }
+
+    /// Validate and store a new version of a tenant template, returning its version number
+    ///
+    /// Templates must parse and render against a sample workflow before they are accepted.
+    pub fn upload(&self, tenant: &str, name: &str, content: String) -> Result<u32, CompilerError> {
+        if !TEMPLATE_NAMES.contains(&name) {
+            return Err(CompilerError::ValidationError(format!(
+                "Unknown template '{}'; expected one of {}",
+                name,
+                TEMPLATE_NAMES.join(", ")
+            ).into()));
+        }
+
+        let variables = [TemplateVariable { name: "order_id", var_type: "string" }];
+        let sample = TemplateContext {
+            package_name: "sample",
+            workflow_name: "Sample".to_string(),
+            definition_version: "1.0.0",
+            definition_fingerprint: "sha256:0",
+            variables: &variables,
+            activities: vec![TemplateActivity { name: "ProcessOrder", inputs: &variables }],
+            default: "",
+        };
+        render(name, &content, &sample).map_err(|e| CompilerError::ValidationError(e.into()))?;
+
+        self.store.append_template(tenant, name, TemplateVersion { version: 0, uploaded_at: now(), content })
+    }
+
+    /// Version history of every template the tenant has uploaded
+    pub fn list(&self, tenant: &str) -> Result<Vec<TemplateHistory>, CompilerError> {
+        Ok(self.store.templates(tenant)?
+            .into_iter()
+            .map(|(name, versions)| TemplateHistory { name, latest: versions.len() as u32, versions })
+            .collect())
+    }
+
+    /// Re-render compiled output with the latest version of each of the tenant's templates,
+    /// recording the applied `name@vN` pairs in the metadata
+    pub fn apply(&self, tenant: &str, definition: &WorkflowDefinition, compiled: &mut CompiledWorkflow) -> Result<(), CompilerError> {
+        let templates = self.store.templates(tenant)?;
+        if templates.is_empty() {
+            return Ok(());
+        }
+
+        let variables: Vec<TemplateVariable> = definition.variables.iter()
+            .map(|v| TemplateVariable { name: &v.name, var_type: &v.var_type })
+            .collect();
+        let metadata = compiled.metadata.clone();
+        let mut applied = Vec::new();
+        for (name, versions) in &templates {
+            let Some(latest) = versions.last() else { continue };
+            let Some(output) = output_mut(compiled, name) else { continue };
+            let context = TemplateContext {
+                package_name: &metadata.package_name,
+                workflow_name: to_pascal_case(&metadata.workflow_name),
+                definition_version: &metadata.definition_version,
+                definition_fingerprint: &metadata.definition_fingerprint,
+                variables: &variables,
+                activities: metadata.activities.iter()
+                    .map(|name| TemplateActivity { name, inputs: &variables })
+                    .collect(),
+                default: output,
+            };
+            *output = render(name, &latest.content, &context).map_err(CompilerError::CodeGenError)?;
+            applied.push(format!("{}@v{}", name, latest.version));
+        }
+        compiled.metadata.tenant_templates = applied;
+
+        Ok(())
+    }
+}
+
+/// Result of the last validation of a stored workflow
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationStatus {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    /// Validation time in seconds since the Unix epoch
+    pub validated_at: u64,
+}
+
+impl ValidationStatus {
+    pub fn new(errors: Vec<String>) -> Self {
+        Self { valid: errors.is_empty(), errors, validated_at: now() }
+    }
+}
+
+/// One stored revision of a workflow definition
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowVersion {
+    pub version: u32,
+    /// Store time in seconds since the Unix epoch
+    pub stored_at: u64,
+    pub definition_version: String,
+    pub status: ValidationStatus,
+    #[serde(skip)]
+    pub definition: WorkflowDefinition,
+    /// Options the definition was validated with when stored
+    #[serde(skip)]
+    pub options: CompileOptions,
+}
+
+/// Version history of one stored workflow, as listed by `GET /api/v1/workflows`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowHistory {
+    pub name: String,
+    pub latest: u32,
+    /// ETag of the latest version, for `If-Match` on the next write
+    pub etag: String,
+    pub versions: Vec<WorkflowVersion>,
+}
+
+/// ETag of a stored workflow version
+pub fn workflow_etag(version: u32) -> String {
+    format!("\"v{}\"", version)
+}
+
+/// Version an ETag from `workflow_etag` names, also accepting it weak or unquoted
+pub fn parse_workflow_etag(tag: &str) -> Option<u32> {
+    let tag = tag.trim();
+    tag.strip_prefix("W/").unwrap_or(tag).trim_matches('"').strip_prefix('v')?.parse().ok()
+}
+
+/// Write rejected because the workflow changed after the version the writer started from
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowConflict {
+    pub name: String,
+    /// Version the writer started from, 0 for a workflow it expected not to be stored yet
+    pub expected: u32,
+    pub latest: u32,
+    /// ETag of the latest version, to write against once the changes are merged
+    pub etag: String,
+    /// Changes stored after `expected`
+    pub theirs: Vec<Change>,
+    /// Changes the rejected write made to `expected`
+    pub yours: Vec<Change>,
+    /// Elements both sides changed, as `element:id`
+    pub overlapping: Vec<String>,
+}
+
+/// Outcome of revalidating the latest version of one stored workflow
+#[derive(Debug, Clone, Serialize)]
+pub struct Revalidation {
+    pub tenant: String,
+    pub name: String,
+    pub version: u32,
+    pub errors: Vec<String>,
+    pub deprecations: Vec<DeprecationWarning>,
+}
+
+/// Revalidation of every stored workflow, grouped by how its result changed
+#[derive(Debug, Default, Serialize)]
+pub struct RevalidationReport {
+    pub checked: usize,
+    /// Valid at their last validation, failing now
+    pub newly_failing: Vec<Revalidation>,
+    pub still_failing: Vec<Revalidation>,
+    /// Failing at their last validation, valid now
+    pub recovered: Vec<Revalidation>,
+    /// Valid workflows using deprecated constructs
+    pub deprecated: Vec<Revalidation>,
+}
+
+/// Stored workflow definitions keyed by tenant, then workflow name
+#[derive(Clone)]
+pub struct WorkflowRegistry {
+    store: Arc<dyn WorkflowStore>,
+}
+
+impl WorkflowRegistry {
+    pub fn new(store: Arc<dyn WorkflowStore>) -> Self {
+        Self { store }
+    }
+
+    /// Store a new version of a tenant workflow that passed validation, returning its version
+    /// number; with `expected`, only while that is the latest version, 0 for none
+    pub fn store(
+        &self,
+        tenant: &str,
+        name: &str,
+        definition: WorkflowDefinition,
+        options: CompileOptions,
+        expected: Option<u32>,
+    ) -> Result<u32, CompilerError> {
+        let version = WorkflowVersion {
+            version: 0,
+            stored_at: now(),
+            definition_version: definition.version.clone(),
+            status: ValidationStatus::new(Vec::new()),
+            definition,
+            options,
+        };
+        self.store.append_workflow(tenant, name, version, expected)
+    }
+
+    /// Conflict a write of `submitted` based on version `expected` ran into: what was stored
+    /// since, what the write changed, and the elements both changed
+    pub fn conflict(&self, tenant: &str, name: &str, expected: u32, submitted: &WorkflowDefinition) -> Result<WorkflowConflict, CompilerError> {
+        let versions = self.store.workflows(tenant)?.remove(name).unwrap_or_default();
+        let empty = WorkflowDefinition {
+            description: None,
+            nodes: vec![],
+            edges: vec![],
+            variables: vec![],
+            triggers: vec![],
+            timeouts: None,
+            ..submitted.clone()
+        };
+        let base = expected.checked_sub(1).and_then(|i| versions.get(i as usize)).map_or(&empty, |v| &v.definition);
+        let latest = versions.last().map_or(&empty, |v| &v.definition);
+        let theirs = diff::diff(base, latest);
+        let yours = diff::diff(base, submitted);
+        let overlapping = yours.iter()
+            .filter(|change| theirs.iter().any(|c| c.key() == change.key()))
+            .map(Change::key)
+            .collect();
+        Ok(WorkflowConflict {
+            name: name.to_string(),
+            expected,
+            latest: versions.len() as u32,
+            etag: workflow_etag(versions.len() as u32),
+            theirs,
+            yours,
+            overlapping,
+        })
+    }
+
+    /// Version history of every workflow the tenant has stored
+    pub fn list(&self, tenant: &str) -> Result<Vec<WorkflowHistory>, CompilerError> {
+        Ok(self.store.workflows(tenant)?
+            .into_iter()
+            .map(|(name, versions)| WorkflowHistory { name, latest: versions.len() as u32, etag: workflow_etag(versions.len() as u32), versions })
+            .collect())
+    }
+
+    /// Latest stored definition of every workflow the tenant has stored
+    pub fn latest(&self, tenant: &str) -> Result<Vec<WorkflowDefinition>, CompilerError> {
+        Ok(self.store.workflows(tenant)?
+            .into_values()
+            .filter_map(|mut versions| versions.pop())
+            .map(|v| v.definition)
+            .collect())
+    }
+
+    /// Revalidate the latest version of every tenant's workflows with `validate`, which returns
+    /// the validation errors and deprecation warnings, and record the new results
+    pub fn revalidate(
+        &self,
+        validate: impl Fn(&WorkflowDefinition, &CompileOptions) -> (Vec<String>, Vec<DeprecationWarning>),
+    ) -> Result<RevalidationReport, CompilerError> {
+        let mut report = RevalidationReport::default();
+        for tenant in self.store.workflow_tenants()? {
+            for (name, versions) in self.store.workflows(&tenant)? {
+                let Some(latest) = versions.last() else { continue };
+                let (errors, deprecations) = validate(&latest.definition, &latest.options);
+                let was_valid = latest.status.valid;
+                let status = ValidationStatus::new(errors.clone());
+                let is_valid = status.valid;
+                self.store.set_workflow_status(&tenant, &name, latest.version, status)?;
+                report.checked += 1;
+
+                let result = Revalidation { tenant: tenant.clone(), name, version: latest.version, errors, deprecations };
+                match (was_valid, is_valid) {
+                    (true, false) => report.newly_failing.push(result),
+                    (false, false) => report.still_failing.push(result),
+                    (false, true) => report.recovered.push(result),
+                    (true, true) if !result.deprecations.is_empty() => report.deprecated.push(result),
+                    (true, true) => {}
+                }
+            }
+        }
+        Ok(report)
+    }
+}
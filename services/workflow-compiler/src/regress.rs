@@ -0,0 +1,193 @@
+//! Compile regression reports: what recompiling a definition with this compiler would change
+//! in previously compiled artifacts, file by file
+//!
+//! Artifacts are the `compiled` object a compile response returned, or any JSON object of
+//! file name to content. Code fields are keyed by field name and generated files by path, so
+//! artifacts from older compiler versions compare without sharing this version's schema.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Largest line product diffed in full; bigger files are reported without hunks
+const MAX_DIFF_CELLS: usize = 16_000_000;
+
+/// Lines of unchanged context around each hunk
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub status: FileStatus,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unified diff; absent when the file is too large to diff
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RegressionSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegressionReport {
+    pub summary: RegressionSummary,
+    /// Files that differ, in path order
+    pub files: Vec<FileDiff>,
+}
+
+/// Non-empty files in compiled artifacts, keyed by field name or generated file path
+pub fn artifact_files(artifacts: &Value) -> BTreeMap<String, String> {
+    let mut files = BTreeMap::new();
+    let Some(fields) = artifacts.as_object() else { return files };
+    let mut add = |path: &str, content: Option<&str>| {
+        if let Some(content) = content.filter(|c| !c.is_empty()) {
+            files.insert(path.to_string(), content.to_string());
+        }
+    };
+    for (name, value) in fields {
+        match value {
+            Value::String(content) => add(name, Some(content)),
+            Value::Array(generated) if name == "files" => {
+                for file in generated {
+                    if let Some(path) = file.get("path").and_then(Value::as_str) {
+                        add(path, file.get("content").and_then(Value::as_str));
+                    }
+                }
+            }
+            Value::Object(file) if file.contains_key("path") => {
+                if let Some(path) = file.get("path").and_then(Value::as_str) {
+                    add(path, file.get("content").and_then(Value::as_str));
+                }
+            }
+            _ => {}
+        }
+    }
+    files
+}
+
+/// Compare previous artifacts with freshly compiled ones
+pub fn compare(previous: &BTreeMap<String, String>, current: &BTreeMap<String, String>) -> RegressionReport {
+    let mut summary = RegressionSummary::default();
+    let mut files = Vec::new();
+    let paths: BTreeSet<&String> = previous.keys().chain(current.keys()).collect();
+    for path in paths {
+        let (old, new) = (previous.get(path), current.get(path));
+        let status = match (old, new) {
+            (Some(old), Some(new)) if old == new => {
+                summary.unchanged += 1;
+                continue;
+            }
+            (Some(_), Some(_)) => {
+                summary.changed += 1;
+                FileStatus::Changed
+            }
+            (None, _) => {
+                summary.added += 1;
+                FileStatus::Added
+            }
+            (_, None) => {
+                summary.removed += 1;
+                FileStatus::Removed
+            }
+        };
+        files.push(diff_file(path, status, old.map_or("", String::as_str), new.map_or("", String::as_str)));
+    }
+    RegressionReport { summary, files }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Line {
+    Keep,
+    Delete,
+    Insert,
+}
+
+fn diff_file(path: &str, status: FileStatus, old: &str, new: &str) -> FileDiff {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return FileDiff { path: path.to_string(), status, additions: new.len(), deletions: old.len(), diff: None };
+    }
+    let script = edit_script(&old, &new);
+    let additions = script.iter().filter(|(op, _, _)| *op == Line::Insert).count();
+    let deletions = script.iter().filter(|(op, _, _)| *op == Line::Delete).count();
+    FileDiff { path: path.to_string(), status, additions, deletions, diff: Some(unified(path, &old, &new, &script)) }
+}
+
+/// Line edit script from a longest-common-subsequence table, with the old and new line index
+/// of each step
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<(Line, usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j] is the LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut script = Vec::with_capacity(n + m);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            script.push((Line::Keep, i, j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            script.push((Line::Delete, i, j));
+            i += 1;
+        } else {
+            script.push((Line::Insert, i, j));
+            j += 1;
+        }
+    }
+    script
+}
+
+/// Render an edit script as a unified diff with `CONTEXT` lines around each hunk
+fn unified(path: &str, old: &[&str], new: &[&str], script: &[(Line, usize, usize)]) -> String {
+    let mut out = format!("--- a/{path}\n+++ b/{path}\n");
+    let changes: Vec<usize> = script.iter().enumerate().filter(|(_, (op, _, _))| *op != Line::Keep).map(|(k, _)| k).collect();
+    let mut k = 0;
+    while k < changes.len() {
+        // Merge changes whose context overlaps into one hunk
+        let start = changes[k].saturating_sub(CONTEXT);
+        let mut end = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] <= end + 2 * CONTEXT + 1 {
+            k += 1;
+            end = changes[k];
+        }
+        let end = (end + CONTEXT + 1).min(script.len());
+        k += 1;
+
+        let hunk = &script[start..end];
+        let (_, old_start, new_start) = hunk[0];
+        let old_count = hunk.iter().filter(|(op, _, _)| *op != Line::Insert).count();
+        let new_count = hunk.iter().filter(|(op, _, _)| *op != Line::Delete).count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_count > 0), old_count, new_start + usize::from(new_count > 0), new_count
+        ));
+        for &(op, i, j) in hunk {
+            match op {
+                Line::Keep => out.push_str(&format!(" {}\n", old[i])),
+                Line::Delete => out.push_str(&format!("-{}\n", old[i])),
+                Line::Insert => out.push_str(&format!("+{}\n", new[j])),
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,107 @@
+//! Aggregate compiler usage statistics, kept in memory per process
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::error::CompilerError;
+use crate::WorkflowDefinition;
+
+/// Number of validation errors reported by the stats endpoint
+const TOP_ERRORS: usize = 10;
+
+/// Running counters across compile and validate requests
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    requests: u64,
+    failed: u64,
+    total_nodes: u64,
+    total_edges: u64,
+    node_types: HashMap<String, u64>,
+    errors: HashMap<String, u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: u64,
+}
+
+/// Snapshot returned by `GET /api/v1/stats`
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    pub requests: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub average_nodes: f64,
+    pub average_edges: f64,
+    /// Node type frequency across all submitted definitions, most used first
+    pub node_types: Vec<Count>,
+    /// Most common errors, with quoted identifiers masked so equal causes group together
+    pub top_errors: Vec<Count>,
+}
+
+/// Replace quoted values such as node IDs with `'…'`
+fn error_pattern(message: &str) -> String {
+    let mut pattern = String::with_capacity(message.len());
+    let mut parts = message.split('\'');
+    if let Some(first) = parts.next() {
+        pattern.push_str(first);
+    }
+    let rest: Vec<&str> = parts.collect();
+    // Quotes pair up as open/close; an odd trailing quote is kept verbatim
+    for (i, part) in rest.iter().enumerate() {
+        if i % 2 == 0 {
+            if i + 1 < rest.len() {
+                pattern.push_str("'…'");
+            } else {
+                pattern.push('\'');
+                pattern.push_str(part);
+            }
+        } else {
+            pattern.push_str(part);
+        }
+    }
+    pattern
+}
+
+fn ranked(counts: &HashMap<String, u64>, limit: usize) -> Vec<Count> {
+    let mut ranked: Vec<Count> = counts.iter().map(|(name, count)| Count { name: name.clone(), count: *count }).collect();
+    ranked.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    ranked.truncate(limit);
+    ranked
+}
+
+impl UsageStats {
+    /// Record one submitted definition and the error it failed with, if any
+    pub fn record(&mut self, definition: &WorkflowDefinition, error: Option<&CompilerError>) {
+        self.requests += 1;
+        self.total_nodes += definition.nodes.len() as u64;
+        self.total_edges += definition.edges.len() as u64;
+        for node in &definition.nodes {
+            let name = serde_json::to_value(&node.node_type)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            *self.node_types.entry(name).or_default() += 1;
+        }
+        if let Some(error) = error {
+            self.failed += 1;
+            *self.errors.entry(error_pattern(&error.to_string())).or_default() += 1;
+        }
+    }
+
+    pub fn report(&self) -> StatsReport {
+        let average = |total: u64| if self.requests == 0 { 0.0 } else { total as f64 / self.requests as f64 };
+
+        StatsReport {
+            requests: self.requests,
+            succeeded: self.requests - self.failed,
+            failed: self.failed,
+            average_nodes: average(self.total_nodes),
+            average_edges: average(self.total_edges),
+            node_types: ranked(&self.node_types, usize::MAX),
+            top_errors: ranked(&self.errors, TOP_ERRORS),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Storage behind the template and workflow registries, the response cache and the audit log
+//!
+//! The subsystems keep their own logic and reach their data only through `WorkflowStore`, so
+//! a database backend plugs in without touching the handlers. `MemoryStore` keeps everything
+//! in the process; it is the server's default and lets integration tests and library
+//! consumers run the whole API without external services.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::audit::AuditEvent;
+use crate::cache::CachedResponse;
+use crate::error::CompilerError;
+use crate::registry::{TemplateVersion, ValidationStatus, WorkflowVersion};
+
+/// Persistent state of the service, keyed by tenant and then by template or workflow name
+///
+/// Appending assigns the next version number of the history, overwriting the `version` of the
+/// record passed in, so concurrent writers never number two versions alike. A workflow append
+/// with an `expected` version only happens while that is still the latest, checked atomically
+/// with the append, so concurrent editors cannot overwrite each other's changes unseen.
+pub trait WorkflowStore: Send + Sync {
+    /// Version histories of the tenant's templates, oldest version first
+    fn templates(&self, tenant: &str) -> Result<BTreeMap<String, Vec<TemplateVersion>>, CompilerError>;
+
+    /// Append a template version, returning its number
+    fn append_template(&self, tenant: &str, name: &str, version: TemplateVersion) -> Result<u32, CompilerError>;
+
+    /// Version histories of the tenant's workflows, oldest version first
+    fn workflows(&self, tenant: &str) -> Result<BTreeMap<String, Vec<WorkflowVersion>>, CompilerError>;
+
+    /// Append a workflow version, returning its number; with `expected`, fail with
+    /// `CompilerError::VersionConflict` unless the latest version is `expected`, 0 for none
+    fn append_workflow(&self, tenant: &str, name: &str, version: WorkflowVersion, expected: Option<u32>) -> Result<u32, CompilerError>;
+
+    /// Tenants with stored workflows, sorted
+    fn workflow_tenants(&self) -> Result<Vec<String>, CompilerError>;
+
+    /// Record a new validation result for one workflow version
+    fn set_workflow_status(&self, tenant: &str, name: &str, version: u32, status: ValidationStatus) -> Result<(), CompilerError>;
+
+    fn cached_response(&self, key: &str) -> Result<Option<Arc<CachedResponse>>, CompilerError>;
+
+    fn cache_response(&self, key: &str, response: Arc<CachedResponse>) -> Result<(), CompilerError>;
+
+    /// Drop every cached response
+    fn clear_responses(&self) -> Result<(), CompilerError>;
+
+    fn append_audit(&self, event: AuditEvent) -> Result<(), CompilerError>;
+
+    /// Audit events, oldest first
+    fn audit_events(&self) -> Result<Vec<AuditEvent>, CompilerError>;
+}
+
+/// Cached responses kept before the cache is emptied and refilled
+const MAX_RESPONSES: usize = 1024;
+
+/// Audit events kept; older ones are dropped first
+const MAX_AUDIT_EVENTS: usize = 10_000;
+
+type Histories<T> = HashMap<String, BTreeMap<String, Vec<T>>>;
+
+/// Store kept in process memory and lost on restart
+#[derive(Default)]
+pub struct MemoryStore {
+    templates: Mutex<Histories<TemplateVersion>>,
+    workflows: Mutex<Histories<WorkflowVersion>>,
+    responses: Mutex<HashMap<String, Arc<CachedResponse>>>,
+    audit: Mutex<VecDeque<AuditEvent>>,
+}
+
+impl WorkflowStore for MemoryStore {
+    fn templates(&self, tenant: &str) -> Result<BTreeMap<String, Vec<TemplateVersion>>, CompilerError> {
+        Ok(self.templates.lock().unwrap().get(tenant).cloned().unwrap_or_default())
+    }
+
+    fn append_template(&self, tenant: &str, name: &str, mut version: TemplateVersion) -> Result<u32, CompilerError> {
+        let mut templates = self.templates.lock().unwrap();
+        let versions = templates.entry(tenant.to_string()).or_default().entry(name.to_string()).or_default();
+        version.version = versions.len() as u32 + 1;
+        versions.push(version);
+        Ok(versions.len() as u32)
+    }
+
+    fn workflows(&self, tenant: &str) -> Result<BTreeMap<String, Vec<WorkflowVersion>>, CompilerError> {
+        Ok(self.workflows.lock().unwrap().get(tenant).cloned().unwrap_or_default())
+    }
+
+    fn append_workflow(&self, tenant: &str, name: &str, mut version: WorkflowVersion, expected: Option<u32>) -> Result<u32, CompilerError> {
+        let mut workflows = self.workflows.lock().unwrap();
+        let latest = workflows.get(tenant).and_then(|w| w.get(name)).map_or(0, |versions| versions.len() as u32);
+        if let Some(expected) = expected.filter(|e| *e != latest) {
+            return Err(CompilerError::VersionConflict { name: name.to_string(), expected, latest });
+        }
+        let versions = workflows.entry(tenant.to_string()).or_default().entry(name.to_string()).or_default();
+        version.version = versions.len() as u32 + 1;
+        versions.push(version);
+        Ok(versions.len() as u32)
+    }
+
+    fn workflow_tenants(&self) -> Result<Vec<String>, CompilerError> {
+        let mut tenants: Vec<String> = self.workflows.lock().unwrap().keys().cloned().collect();
+        tenants.sort();
+        Ok(tenants)
+    }
+
+    fn set_workflow_status(&self, tenant: &str, name: &str, version: u32, status: ValidationStatus) -> Result<(), CompilerError> {
+        let mut workflows = self.workflows.lock().unwrap();
+        let stored = workflows.get_mut(tenant)
+            .and_then(|w| w.get_mut(name))
+            .and_then(|versions| versions.iter_mut().find(|v| v.version == version))
+            .ok_or_else(|| CompilerError::StorageError(format!("Workflow '{}' v{} of tenant '{}' is not stored", name, version, tenant)))?;
+        stored.status = status;
+        Ok(())
+    }
+
+    fn cached_response(&self, key: &str) -> Result<Option<Arc<CachedResponse>>, CompilerError> {
+        Ok(self.responses.lock().unwrap().get(key).cloned())
+    }
+
+    fn cache_response(&self, key: &str, response: Arc<CachedResponse>) -> Result<(), CompilerError> {
+        let mut responses = self.responses.lock().unwrap();
+        if responses.len() >= MAX_RESPONSES {
+            responses.clear();
+        }
+        responses.insert(key.to_string(), response);
+        Ok(())
+    }
+
+    fn clear_responses(&self) -> Result<(), CompilerError> {
+        self.responses.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn append_audit(&self, event: AuditEvent) -> Result<(), CompilerError> {
+        let mut audit = self.audit.lock().unwrap();
+        if audit.len() >= MAX_AUDIT_EVENTS {
+            audit.pop_front();
+        }
+        audit.push_back(event);
+        Ok(())
+    }
+
+    fn audit_events(&self) -> Result<Vec<AuditEvent>, CompilerError> {
+        Ok(self.audit.lock().unwrap().iter().cloned().collect())
+    }
+}
@@ -0,0 +1,375 @@
+//! Execution trace import: a workflow history mapped back onto the definition it was compiled
+//! from, for the editor's execution heatmap
+//!
+//! Histories are the JSON `temporal workflow show --output json` prints, or the bare event
+//! list; event types may be spelled `EVENT_TYPE_ACTIVITY_TASK_SCHEDULED` or
+//! `ActivityTaskScheduled`, and event times RFC 3339 strings or Unix nanoseconds as Cadence
+//! writes them. Activity events map to the nodes running the activity type, child workflow
+//! events to the node whose ID ends the child's workflow ID, timers to the wait timer nodes
+//! of their duration and signals to the nodes waiting for them. Nodes that record no events,
+//! such as decisions and transforms, are reported `not_observed`.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::compiler::{codegen, duration, report};
+use crate::error::CompilerError;
+use crate::{NodeType, WaitSignalConfig, WaitSignalsConfig, WaitTimerConfig, WorkflowDefinition};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    Completed,
+    Failed,
+    Running,
+    Canceled,
+    NotObserved,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Running,
+    Completed,
+    Failed,
+    TimedOut,
+    Canceled,
+    Terminated,
+    ContinuedAsNew,
+}
+
+/// What the history records of one node
+#[derive(Debug, Serialize)]
+pub struct NodeTrace {
+    pub node_id: String,
+    pub label: String,
+    /// Status of the node's last execution
+    pub status: NodeStatus,
+    /// Times the node ran, more than once inside loops
+    pub executions: u32,
+    /// Attempts over all executions, counting retries
+    pub attempts: u32,
+    /// Time from scheduling to close, summed over closed executions
+    pub duration_ms: u64,
+    /// Event time of the first execution
+    pub started_at: Option<String>,
+    /// Failure message of the last failed execution
+    pub failure: Option<String>,
+}
+
+/// A workflow history mapped onto its definition
+#[derive(Debug, Serialize)]
+pub struct ExecutionTrace {
+    pub workflow_type: Option<String>,
+    pub status: ExecutionStatus,
+    pub started_at: Option<String>,
+    pub closed_at: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub failure: Option<String>,
+    /// Last node to fail before the workflow failed or timed out
+    pub failed_node: Option<String>,
+    /// Every node of the definition, in definition order
+    pub nodes: Vec<NodeTrace>,
+    /// Activity types and child workflow IDs in the history that no node matches
+    pub unmatched: Vec<String>,
+}
+
+/// History event with its type normalized to lowercase without separators, e.g.
+/// `activitytaskscheduled`
+struct Event<'a> {
+    id: u64,
+    kind: String,
+    time: Option<String>,
+    attributes: &'a Value,
+}
+
+fn number(value: Option<&Value>) -> Option<u64> {
+    match value? {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Events of a history, in the order they appear
+fn events(history: &Value) -> Result<Vec<Event<'_>>, CompilerError> {
+    let list = history.get("events").unwrap_or(history).as_array()
+        .ok_or_else(|| CompilerError::ParseError("Execution history must be an object with an events array, or an array of events".to_string()))?;
+    list.iter().enumerate()
+        .map(|(i, event)| {
+            let kind = event.get("eventType").and_then(Value::as_str).ok_or_else(|| {
+                CompilerError::ParseError(format!("Execution history event {} has no eventType", i + 1))
+            })?;
+            let attributes = event.as_object()
+                .and_then(|fields| fields.iter().find(|(key, _)| key.ends_with("Attributes")).map(|(_, value)| value))
+                .unwrap_or(&Value::Null);
+            let time = match event.get("eventTime").or_else(|| event.get("timestamp")) {
+                Some(Value::String(time)) => Some(time.clone()),
+                Some(Value::Number(nanos)) => nanos.as_i64().map(|n| format_time(n / 1_000_000)),
+                _ => None,
+            };
+            Ok(Event {
+                id: number(event.get("eventId")).unwrap_or(i as u64 + 1),
+                kind: kind.trim_start_matches("EVENT_TYPE_").replace('_', "").to_ascii_lowercase(),
+                time,
+                attributes,
+            })
+        })
+        .collect()
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Unix milliseconds of an RFC 3339 timestamp such as `2024-05-01T10:00:00.123456Z`
+fn parse_time(time: &str) -> Option<i64> {
+    let field = |range: std::ops::Range<usize>| time.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    let rest = time.get(19..)?;
+    let zone_at = rest.find(['Z', 'z', '+', '-'])?;
+    let (fraction, zone) = rest.split_at(zone_at);
+    let millis = match fraction.strip_prefix('.') {
+        Some(digits) => format!("{:0<3}", &digits[..digits.len().min(3)]).parse::<i64>().ok()?,
+        None if fraction.is_empty() => 0,
+        None => return None,
+    };
+    let offset = match zone {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = if zone.starts_with('-') { -1 } else { 1 };
+            let hours: i64 = zone.get(1..3)?.parse().ok()?;
+            let minutes: i64 = zone.get(4..6)?.parse().ok()?;
+            sign * (hours * 60 + minutes)
+        }
+    };
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second - offset * 60;
+    Some(seconds * 1_000 + millis)
+}
+
+/// RFC 3339 timestamp in UTC of Unix milliseconds
+fn format_time(millis: i64) -> String {
+    let (days, millis_of_day) = (millis.div_euclid(86_400_000), millis.rem_euclid(86_400_000));
+    // Inverse of days_from_civil
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let seconds = millis_of_day / 1_000;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, seconds / 3_600, seconds / 60 % 60, seconds % 60, millis_of_day % 1_000
+    )
+}
+
+fn elapsed(from: Option<&str>, to: Option<&str>) -> Option<u64> {
+    let (from, to) = (parse_time(from?)?, parse_time(to?)?);
+    u64::try_from(to - from).ok()
+}
+
+fn failure_message(attributes: &Value) -> Option<String> {
+    attributes.pointer("/failure/message")
+        .or_else(|| attributes.get("reason"))
+        .or_else(|| attributes.get("cause"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Execution of one or more nodes opened by a scheduling event
+struct Open {
+    nodes: Vec<usize>,
+    time: Option<String>,
+    attempt: u32,
+}
+
+/// Map a history onto the definition it ran, which must have its plugin nodes lowered
+pub fn map_history(definition: &WorkflowDefinition, history: &Value) -> Result<ExecutionTrace, CompilerError> {
+    let events = events(history)?;
+    let index: HashMap<&str, usize> = definition.nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect();
+    let activities: HashMap<String, Vec<usize>> = codegen::reachable_activities(definition).into_iter()
+        .map(|origin| (origin.activity, origin.node_ids.iter().filter_map(|id| index.get(id.as_str()).copied()).collect()))
+        .collect();
+    let of_type = |f: fn(&NodeType) -> bool| -> Vec<usize> {
+        definition.nodes.iter().enumerate().filter(|(_, n)| f(&n.node_type)).map(|(i, _)| i).collect()
+    };
+
+    let mut nodes: Vec<NodeTrace> = definition.nodes.iter()
+        .map(|n| NodeTrace {
+            node_id: n.id.clone(),
+            label: n.label.clone(),
+            status: NodeStatus::NotObserved,
+            executions: 0,
+            attempts: 0,
+            duration_ms: 0,
+            started_at: None,
+            failure: None,
+        })
+        .collect();
+    let mut trace = ExecutionTrace {
+        workflow_type: None,
+        status: ExecutionStatus::Running,
+        started_at: None,
+        closed_at: None,
+        duration_ms: None,
+        failure: None,
+        failed_node: None,
+        nodes: vec![],
+        unmatched: vec![],
+    };
+    let mut open: HashMap<u64, Open> = HashMap::new();
+    let mut last_failed = None;
+
+    let start = |nodes: &mut Vec<NodeTrace>, open: &mut HashMap<u64, Open>, event: &Event, matched: Vec<usize>| {
+        for &i in &matched {
+            let node = &mut nodes[i];
+            node.status = NodeStatus::Running;
+            node.executions += 1;
+            node.started_at = node.started_at.take().or_else(|| event.time.clone());
+        }
+        open.insert(event.id, Open { nodes: matched, time: event.time.clone(), attempt: 1 });
+    };
+
+    for event in &events {
+        let attributes = event.attributes;
+        let opened_by = number(attributes.get("scheduledEventId"))
+            .or_else(|| number(attributes.get("initiatedEventId")))
+            .or_else(|| number(attributes.get("startedEventId")));
+        match event.kind.as_str() {
+            "workflowexecutionstarted" => {
+                trace.workflow_type = attributes.pointer("/workflowType/name").and_then(Value::as_str).map(str::to_string);
+                trace.started_at = event.time.clone();
+                for i in of_type(|t| matches!(t, NodeType::Start)) {
+                    let node = &mut nodes[i];
+                    node.status = NodeStatus::Completed;
+                    node.executions = 1;
+                    node.attempts = 1;
+                    node.started_at = event.time.clone();
+                }
+            }
+            "activitytaskscheduled" => {
+                let name = attributes.pointer("/activityType/name").and_then(Value::as_str).unwrap_or_default();
+                match activities.get(name) {
+                    Some(matched) => start(&mut nodes, &mut open, event, matched.clone()),
+                    None if name == report::ACTIVITY => {}
+                    None => trace.unmatched.push(format!("activity {}", name)),
+                }
+            }
+            "startchildworkflowexecutioninitiated" => {
+                let workflow_id = attributes.get("workflowId").and_then(Value::as_str).unwrap_or_default();
+                let matched: Vec<usize> = of_type(|t| matches!(t, NodeType::SubWorkflow)).into_iter()
+                    .filter(|&i| workflow_id.ends_with(&format!("-{}", definition.nodes[i].id)))
+                    .collect();
+                if matched.is_empty() {
+                    trace.unmatched.push(format!("child workflow {}", workflow_id));
+                } else {
+                    start(&mut nodes, &mut open, event, matched);
+                }
+            }
+            "timerstarted" => {
+                let fires = attributes.get("startToFireTimeout").and_then(Value::as_str).and_then(|d| duration::parse_duration(d).ok());
+                let matched: Vec<usize> = of_type(|t| matches!(t, NodeType::WaitTimer)).into_iter()
+                    .filter(|&i| {
+                        let config = definition.nodes[i].typed_config::<WaitTimerConfig>().ok();
+                        fires.is_some() && config.and_then(|c| duration::parse_duration(&c.duration).ok()) == fires
+                    })
+                    .collect();
+                // Timers of signal timeouts and helpers belong to no node
+                if !matched.is_empty() {
+                    start(&mut nodes, &mut open, event, matched);
+                }
+            }
+            "workflowexecutionsignaled" => {
+                let signal = attributes.get("signalName").and_then(Value::as_str).unwrap_or_default();
+                for (i, node) in definition.nodes.iter().enumerate() {
+                    let waits = match node.node_type {
+                        NodeType::WaitSignal => node.typed_config::<WaitSignalConfig>().is_ok_and(|c| c.signal == signal),
+                        NodeType::WaitSignals => node.typed_config::<WaitSignalsConfig>().is_ok_and(|c| c.signals.iter().any(|s| s == signal)),
+                        _ => false,
+                    };
+                    if waits && nodes[i].executions == 0 {
+                        let node = &mut nodes[i];
+                        node.status = NodeStatus::Completed;
+                        node.executions = 1;
+                        node.attempts = 1;
+                        node.started_at = event.time.clone();
+                    }
+                }
+            }
+            "activitytaskstarted" => {
+                if let Some(execution) = opened_by.and_then(|id| open.get_mut(&id)) {
+                    execution.attempt = number(attributes.get("attempt")).map_or(1, |a| a as u32);
+                }
+            }
+            kind @ ("activitytaskcompleted" | "activitytaskfailed" | "activitytasktimedout" | "activitytaskcanceled"
+                | "childworkflowexecutioncompleted" | "childworkflowexecutionfailed" | "childworkflowexecutiontimedout"
+                | "childworkflowexecutioncanceled" | "childworkflowexecutionterminated" | "startchildworkflowexecutionfailed"
+                | "timerfired" | "timercanceled") => {
+                let Some(execution) = opened_by.and_then(|id| open.remove(&id)) else { continue };
+                let status = if kind.ends_with("completed") || kind == "timerfired" {
+                    NodeStatus::Completed
+                } else if kind.ends_with("canceled") {
+                    NodeStatus::Canceled
+                } else {
+                    NodeStatus::Failed
+                };
+                let took = elapsed(execution.time.as_deref(), event.time.as_deref()).unwrap_or_default();
+                for i in execution.nodes {
+                    let node = &mut nodes[i];
+                    node.status = status;
+                    node.attempts += execution.attempt;
+                    node.duration_ms += took;
+                    if status == NodeStatus::Failed {
+                        node.failure = failure_message(attributes).or_else(|| Some(kind.to_string()));
+                        last_failed = Some(i);
+                    }
+                }
+            }
+            kind @ ("workflowexecutioncompleted" | "workflowexecutionfailed" | "workflowexecutiontimedout"
+                | "workflowexecutioncanceled" | "workflowexecutionterminated" | "workflowexecutioncontinuedasnew") => {
+                trace.status = match kind {
+                    "workflowexecutioncompleted" => ExecutionStatus::Completed,
+                    "workflowexecutionfailed" => ExecutionStatus::Failed,
+                    "workflowexecutiontimedout" => ExecutionStatus::TimedOut,
+                    "workflowexecutioncanceled" => ExecutionStatus::Canceled,
+                    "workflowexecutionterminated" => ExecutionStatus::Terminated,
+                    _ => ExecutionStatus::ContinuedAsNew,
+                };
+                trace.closed_at = event.time.clone();
+                trace.failure = failure_message(attributes);
+                let ends = of_type(|t| matches!(t, NodeType::End));
+                // Which End node a run reached is only known when there is one
+                if trace.status == ExecutionStatus::Completed && ends.len() == 1 {
+                    let node = &mut nodes[ends[0]];
+                    node.status = NodeStatus::Completed;
+                    node.executions = 1;
+                    node.attempts = 1;
+                    node.started_at = event.time.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    trace.duration_ms = elapsed(trace.started_at.as_deref(), trace.closed_at.as_deref());
+    if matches!(trace.status, ExecutionStatus::Failed | ExecutionStatus::TimedOut) {
+        trace.failed_node = last_failed.map(|i| nodes[i].node_id.clone());
+    }
+    trace.unmatched.sort();
+    trace.unmatched.dedup();
+    trace.nodes = nodes;
+    Ok(trace)
+}